@@ -154,6 +154,17 @@ impl<B: Backend, C: CheckpointStrategy> AutodiffBackend for Autodiff<B, C> {
         tensor
     }
 
+    fn tangent(tensor: &AutodiffTensor<B>) -> Option<AutodiffTensor<B>> {
+        tensor.tangent()
+    }
+
+    fn with_tangent(
+        tensor: AutodiffTensor<B>,
+        tangent: Option<AutodiffTensor<B>>,
+    ) -> AutodiffTensor<B> {
+        tensor.with_tangent(tangent)
+    }
+
     fn set_distributed_params(
         tensor: AutodiffTensor<B>,
         param_id: DistributedParamId,
@@ -1,4 +1,7 @@
-use core::marker::PhantomData;
+use core::{
+    f64::consts::{FRAC_1_SQRT_2, FRAC_2_SQRT_PI, SQRT_2},
+    marker::PhantomData,
+};
 
 use crate::{
     Autodiff,
@@ -8,10 +11,17 @@ use crate::{
     },
     grads::Gradients,
     graph::NodeId,
-    ops::{Backward, Ops, OpsKind, unary},
+    ops::{Backward, Ops, OpsKind, tangent, unary},
     retro_unary,
 };
-use burn_backend::{Backend, ops::ActivationOps, tensor::FloatTensor};
+use burn_backend::{
+    Backend, TensorMetadata, get_device_settings,
+    ops::{ActivationOps, FloatTensorOps},
+    tensor::FloatTensor,
+};
+
+/// 1 / sqrt(2 * pi), the normalization of the standard normal density.
+const FRAC_1_SQRT_2PI: f64 = FRAC_1_SQRT_2 * FRAC_2_SQRT_PI * 0.5;
 
 impl<B: Backend, C: CheckpointStrategy> ActivationOps<Autodiff<B, C>> for Autodiff<B, C> {
     fn gelu(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -37,7 +47,21 @@ impl<B: Backend, C: CheckpointStrategy> ActivationOps<Autodiff<B, C>> for Autodi
             }
         }
 
-        match Gelu
+        let tangent = tensor.tangent().map(|tangent| {
+            // gelu'(x) = cdf(x) + x * pdf(x), with the standard normal distribution.
+            let cdf = Self::float_erf(Self::float_div_scalar(tensor.primal(), SQRT_2.into()));
+            let cdf =
+                Self::float_mul_scalar(Self::float_add_scalar(cdf, 1f32.into()), 0.5f32.into());
+            let pdf = Self::float_exp(Self::float_mul_scalar(
+                Self::float_mul(tensor.primal(), tensor.primal()),
+                (-0.5f32).into(),
+            ));
+            let pdf = Self::float_mul_scalar(pdf, FRAC_1_SQRT_2PI.into());
+            let value = Self::float_add(cdf, Self::float_mul(tensor.primal(), pdf));
+            Self::float_mul(tangent, value)
+        });
+
+        let output = match Gelu
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroGelu::<B>::new(tensor.node.id))
@@ -49,7 +73,9 @@ impl<B: Backend, C: CheckpointStrategy> ActivationOps<Autodiff<B, C>> for Autodi
                 prep.finish(state, B::gelu(tensor.primitive.clone()))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::gelu(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn relu(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -74,7 +100,13 @@ impl<B: Backend, C: CheckpointStrategy> ActivationOps<Autodiff<B, C>> for Autodi
             }
         }
 
-        match Relu
+        let tangent = tensor.tangent().map(|tangent| {
+            let bool_dtype = get_device_settings::<B>(&tensor.device()).bool_dtype;
+            let mask = Self::float_lower_equal_elem(tensor.primal(), 0f32.into(), bool_dtype);
+            Self::float_mask_fill(tangent, mask, 0f32.into())
+        });
+
+        let output = match Relu
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroRelu::<B>::new(tensor.node.id))
@@ -86,7 +118,9 @@ impl<B: Backend, C: CheckpointStrategy> ActivationOps<Autodiff<B, C>> for Autodi
                 prep.finish(state, B::relu(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::relu(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn sigmoid(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -112,7 +146,16 @@ impl<B: Backend, C: CheckpointStrategy> ActivationOps<Autodiff<B, C>> for Autodi
             }
         }
 
-        match Sigmoid
+        let tangent = tensor.tangent().map(|tangent| {
+            let output = Self::sigmoid(tensor.primal());
+            let value = Self::float_mul(
+                output.clone(),
+                Self::float_add_scalar(Self::float_neg(output), 1f32.into()),
+            );
+            Self::float_mul(tangent, value)
+        });
+
+        let output = match Sigmoid
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroSigmoid::<B>::new(tensor.node.id))
@@ -124,7 +167,9 @@ impl<B: Backend, C: CheckpointStrategy> ActivationOps<Autodiff<B, C>> for Autodi
                 prep.finish(state, B::sigmoid(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::sigmoid(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn log_sigmoid(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -150,7 +195,11 @@ impl<B: Backend, C: CheckpointStrategy> ActivationOps<Autodiff<B, C>> for Autodi
             }
         }
 
-        match LogSigmoid
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_mul(tangent, Self::sigmoid(Self::float_neg(tensor.primal())))
+        });
+
+        let output = match LogSigmoid
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroLogSigmoid::<B>::new(tensor.node.id))
@@ -162,6 +211,8 @@ impl<B: Backend, C: CheckpointStrategy> ActivationOps<Autodiff<B, C>> for Autodi
                 prep.finish(state, B::log_sigmoid(tensor.primitive.clone()))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::log_sigmoid(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 }
@@ -13,7 +13,7 @@ use burn_backend::Backend;
 use crate::{
     Autodiff,
     checkpoint::strategy::CheckpointStrategy,
-    ops::{Backward, Ops, OpsKind, unary},
+    ops::{Backward, Ops, OpsKind, tangent, unary},
 };
 
 impl<B: Backend, C: CheckpointStrategy> DistributedOps<Self> for Autodiff<B, C> {
@@ -43,6 +43,8 @@ impl<B: Backend, C: CheckpointStrategy> DistributedOps<Self> for Autodiff<B, C>
         op: ReduceOperation,
         device_ids: Vec<DeviceId>,
     ) -> CollectiveTensor<Self> {
+        tangent::unsupported("all_reduce", [&tensor]);
+
         #[derive(Debug)]
         struct AllReduce;
 
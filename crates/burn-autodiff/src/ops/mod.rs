@@ -6,6 +6,7 @@ mod distributed;
 mod int_tensor;
 mod module;
 mod qtensor;
+mod tangent;
 mod tensor;
 mod transaction;
 
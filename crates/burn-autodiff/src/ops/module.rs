@@ -5,7 +5,7 @@ use crate::checkpoint::base::Checkpointer;
 use crate::checkpoint::strategy::CheckpointStrategy;
 use crate::grads::Gradients;
 use crate::graph::NodeId;
-use crate::ops::{Backward, Ops, unary};
+use crate::ops::{Backward, Ops, tangent, unary};
 use crate::tensor::AutodiffTensor;
 
 use burn_backend::TensorMetadata;
@@ -39,7 +39,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match Embedding
+        let tangent = weights
+            .tangent()
+            .map(|tangent| Self::embedding(tangent, indices.clone()));
+
+        let output = match Embedding
             .prepare::<C>([weights.node])
             .compute_bound()
             .stateful()
@@ -49,7 +53,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                 B::embedding(weights.primitive, indices),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::embedding(weights.primitive, indices)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn embedding_backward(
@@ -135,7 +141,15 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
         let x_tracked = x.is_tracked();
         let weight_tracked = weight.is_tracked();
 
-        match bias {
+        let tangent = tangent::bilinear::<B, C>(
+            &x,
+            &weight,
+            bias.as_ref(),
+            x.shape().num_dims() - 1,
+            |x, weight| Self::linear(x, weight, None),
+        );
+
+        let output = match bias {
             Some(bias) => match LinearWithBias
                 .prepare::<C>([x.node.clone(), weight.node.clone(), bias.node.clone()])
                 .compute_bound()
@@ -173,7 +187,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                     prep.finish(B::linear(x.primitive, weight.primitive, None))
                 }
             },
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn linear_x_backward(
@@ -275,7 +291,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                 }
             }
         }
-        match bias {
+        let tangent = tangent::bilinear::<B, C>(&x, &weight, bias.as_ref(), 1, |x, weight| {
+            Self::conv1d(x, weight, None, options.clone())
+        });
+
+        let output = match bias {
             Some(bias) => match Conv1DWithBias
                 .prepare::<C>([x.node.clone(), weight.node.clone(), bias.node.clone()])
                 .compute_bound()
@@ -314,7 +334,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                     prep.finish(B::conv1d(x.primitive, weight.primitive, None, options))
                 }
             },
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn conv_transpose1d(
@@ -402,7 +424,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match bias {
+        let tangent = tangent::bilinear::<B, C>(&x, &weight, bias.as_ref(), 1, |x, weight| {
+            Self::conv_transpose1d(x, weight, None, options.clone())
+        });
+
+        let output = match bias {
             Some(bias) => match ConvTranspose1DWithBias
                 .prepare::<C>([x.node.clone(), weight.node.clone(), bias.node.clone()])
                 .compute_bound()
@@ -449,7 +475,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                     options,
                 )),
             },
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn conv2d(
@@ -535,7 +563,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match bias {
+        let tangent = tangent::bilinear::<B, C>(&x, &weight, bias.as_ref(), 1, |x, weight| {
+            Self::conv2d(x, weight, None, options.clone())
+        });
+
+        let output = match bias {
             Some(bias) => match Conv2DWithBias
                 .prepare::<C>([x.node.clone(), weight.node.clone(), bias.node.clone()])
                 .compute_bound()
@@ -575,7 +607,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                     prep.finish(B::conv2d(x.primitive, weight.primitive, None, options))
                 }
             },
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn deform_conv2d(
@@ -586,6 +620,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
         bias: Option<AutodiffTensor<B>>,
         options: DeformConvOptions<2>,
     ) -> AutodiffTensor<B> {
+        let tensors = [&x, &offset, &weight].into_iter().chain(&mask).chain(&bias);
+        tangent::unsupported("deform_conv2d", tensors);
+
         #[derive(Debug)]
         struct DeformConv2DWithMaskWithBias;
         #[derive(Debug)]
@@ -1000,7 +1037,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match bias {
+        let tangent = tangent::bilinear::<B, C>(&x, &weight, bias.as_ref(), 1, |x, weight| {
+            Self::conv_transpose2d(x, weight, None, options.clone())
+        });
+
+        let output = match bias {
             Some(bias) => match ConvTranspose2DWithBias
                 .prepare::<C>([x.node.clone(), weight.node.clone(), bias.node.clone()])
                 .compute_bound()
@@ -1049,7 +1090,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                     options,
                 )),
             },
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn conv3d(
@@ -1135,7 +1178,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match bias {
+        let tangent = tangent::bilinear::<B, C>(&x, &weight, bias.as_ref(), 1, |x, weight| {
+            Self::conv3d(x, weight, None, options.clone())
+        });
+
+        let output = match bias {
             Some(bias) => match Conv3DWithBias
                 .prepare::<C>([x.node.clone(), weight.node.clone(), bias.node.clone()])
                 .compute_bound()
@@ -1175,7 +1222,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                     prep.finish(B::conv3d(x.primitive, weight.primitive, None, options))
                 }
             },
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn conv_transpose3d(
@@ -1263,7 +1312,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match bias {
+        let tangent = tangent::bilinear::<B, C>(&x, &weight, bias.as_ref(), 1, |x, weight| {
+            Self::conv_transpose3d(x, weight, None, options.clone())
+        });
+
+        let output = match bias {
             Some(bias) => match ConvTranspose3DWithBias
                 .prepare::<C>([x.node.clone(), weight.node.clone(), bias.node.clone()])
                 .compute_bound()
@@ -1312,7 +1365,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                     options,
                 )),
             },
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     // TODO: Support a custom unfold4d operation by overriding the default implementation.
@@ -1371,7 +1426,18 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match AvgPool1D
+        let tangent = x.tangent().map(|tangent| {
+            Self::avg_pool1d(
+                tangent,
+                kernel_size,
+                stride,
+                padding,
+                count_include_pad,
+                ceil_mode,
+            )
+        });
+
+        let output = match AvgPool1D
             .prepare::<C>([x.node.clone()])
             .compute_bound()
             .stateful()
@@ -1405,7 +1471,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                 count_include_pad,
                 ceil_mode,
             )),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn avg_pool2d(
@@ -1449,7 +1517,18 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match AvgPool2D
+        let tangent = x.tangent().map(|tangent| {
+            Self::avg_pool2d(
+                tangent,
+                kernel_size,
+                stride,
+                padding,
+                count_include_pad,
+                ceil_mode,
+            )
+        });
+
+        let output = match AvgPool2D
             .prepare::<C>([x.node.clone()])
             .compute_bound()
             .stateful()
@@ -1483,7 +1562,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
                 count_include_pad,
                 ceil_mode,
             )),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn avg_pool2d_backward(
@@ -1506,6 +1587,20 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
         dilation: usize,
         ceil_mode: bool,
     ) -> AutodiffTensor<B> {
+        if x.tangent.is_some() {
+            let settings = get_device_settings::<B>(&x.primitive.device());
+            return Self::max_pool1d_with_indices(
+                x,
+                kernel_size,
+                stride,
+                padding,
+                dilation,
+                ceil_mode,
+                settings.int_dtype,
+            )
+            .output;
+        }
+
         match MaxPool1D
             .prepare::<C>([x.node.clone()])
             .compute_bound()
@@ -1556,7 +1651,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
         ceil_mode: bool,
         int_dtype: IntDType,
     ) -> MaxPool1dWithIndices<Self> {
-        match MaxPool1D
+        let tangent = x.tangent();
+
+        let output = match MaxPool1D
             .prepare::<C>([x.node.clone()])
             .compute_bound()
             .stateful()
@@ -1602,7 +1699,14 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
 
                 MaxPool1dWithIndices::new(output_tensor, output.indices)
             }
-        }
+        };
+        let tangent =
+            tangent.map(|tangent| tangent::max_pool::<B, C>(tangent, output.indices.clone()));
+
+        MaxPool1dWithIndices::new(
+            tangent::attach::<B, C>(output.output, tangent),
+            output.indices,
+        )
     }
 
     fn max_pool1d_with_indices_backward(
@@ -1636,6 +1740,20 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
         dilation: [usize; 2],
         ceil_mode: bool,
     ) -> AutodiffTensor<B> {
+        if x.tangent.is_some() {
+            let settings = get_device_settings::<B>(&x.primitive.device());
+            return Self::max_pool2d_with_indices(
+                x,
+                kernel_size,
+                stride,
+                padding,
+                dilation,
+                ceil_mode,
+                settings.int_dtype,
+            )
+            .output;
+        }
+
         match MaxPool2D
             .prepare::<C>([x.node.clone()])
             .compute_bound()
@@ -1686,7 +1804,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
         ceil_mode: bool,
         int_dtype: IntDType,
     ) -> MaxPool2dWithIndices<Self> {
-        match MaxPool2D
+        let tangent = x.tangent();
+
+        let output = match MaxPool2D
             .prepare::<C>([x.node.clone()])
             .compute_bound()
             .stateful()
@@ -1733,7 +1853,14 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
 
                 MaxPool2dWithIndices::new(output_tensor, output.indices)
             }
-        }
+        };
+        let tangent =
+            tangent.map(|tangent| tangent::max_pool::<B, C>(tangent, output.indices.clone()));
+
+        MaxPool2dWithIndices::new(
+            tangent::attach::<B, C>(output.output, tangent),
+            output.indices,
+        )
     }
 
     fn max_pool2d_with_indices_backward(
@@ -1772,7 +1899,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match AdaptiveAvgPool1D
+        let tangent = x
+            .tangent()
+            .map(|tangent| Self::adaptive_avg_pool1d(tangent, output_size));
+
+        let output = match AdaptiveAvgPool1D
             .prepare::<C>([x.node.clone()])
             .compute_bound()
             .stateful()
@@ -1784,7 +1915,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::adaptive_avg_pool1d(x.primitive, output_size))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn adaptive_avg_pool2d(x: AutodiffTensor<B>, output_size: [usize; 2]) -> AutodiffTensor<B> {
@@ -1811,7 +1944,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match AdaptiveAvgPool2D
+        let tangent = x
+            .tangent()
+            .map(|tangent| Self::adaptive_avg_pool2d(tangent, output_size));
+
+        let output = match AdaptiveAvgPool2D
             .prepare::<C>([x.node.clone()])
             .compute_bound()
             .stateful()
@@ -1823,7 +1960,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::adaptive_avg_pool2d(x.primitive, output_size))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn adaptive_avg_pool2d_backward(
@@ -1857,7 +1996,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match AdaptiveAvgPool3D
+        let tangent = x
+            .tangent()
+            .map(|tangent| Self::adaptive_avg_pool3d(tangent, output_size));
+
+        let output = match AdaptiveAvgPool3D
             .prepare::<C>([x.node.clone()])
             .compute_bound()
             .stateful()
@@ -1869,7 +2012,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::adaptive_avg_pool3d(x.primitive, output_size))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn adaptive_avg_pool3d_backward(
@@ -1908,7 +2053,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
-        match Interpolate
+        let tangent = x
+            .tangent()
+            .map(|tangent| Self::interpolate(tangent, output_size, options.clone()));
+
+        let output = match Interpolate
             .prepare::<C>([x.node.clone()])
             .compute_bound()
             .stateful()
@@ -1921,7 +2070,9 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::interpolate(x.primitive, output_size, options))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn interpolate_backward(
@@ -1939,9 +2090,10 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
         value: FloatTensor<Autodiff<B, C>>,
         mask: Option<burn_backend::tensor::BoolTensor<Autodiff<B, C>>>,
         attn_bias: Option<FloatTensor<Autodiff<B, C>>>,
+        seq_offsets: Option<IntTensor<Autodiff<B, C>>>,
         options: AttentionModuleOptions,
     ) -> FloatTensor<Autodiff<B, C>> {
-        attention_fallback::<Self>(query, key, value, mask, attn_bias, options)
+        attention_fallback::<Self>(query, key, value, mask, attn_bias, seq_offsets, options)
     }
 
     fn ctc_loss(
@@ -1953,8 +2105,8 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
     ) -> FloatTensor<Autodiff<B, C>> {
         // Backends without a native ctc_loss_backward fall back to the default
         // implementation, which is built from differentiable tensor ops so the
-        // autodiff layer derives the gradient automatically.
-        if !B::has_ctc_loss_backward() {
+        // autodiff layer derives the gradient automatically. The same goes for tangents.
+        if !B::has_ctc_loss_backward() || log_probs.tangent.is_some() {
             return burn_backend::ops::ctc::ctc_loss_default::<Self>(
                 log_probs,
                 targets,
@@ -2032,6 +2184,112 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
         }
     }
 
+    fn selective_scan(
+        x: AutodiffTensor<B>,
+        delta: AutodiffTensor<B>,
+        a: AutodiffTensor<B>,
+        b: AutodiffTensor<B>,
+        c: AutodiffTensor<B>,
+    ) -> AutodiffTensor<B> {
+        // Backends without a native selective_scan_backward fall back to the default
+        // parallel scan, which is built from differentiable tensor ops. The same goes for tangents.
+        if !B::has_selective_scan_backward() || tangent::any(&[&x, &delta, &a, &b, &c]) {
+            return burn_backend::ops::selective_scan::selective_scan_default::<Self>(
+                x, delta, a, b, c,
+            );
+        }
+
+        #[derive(Debug)]
+        struct SelectiveScan;
+
+        impl<B: Backend> Backward<B, 5> for SelectiveScan {
+            type State = (NodeId, NodeId, NodeId, NodeId, NodeId);
+
+            fn backward(
+                self,
+                ops: Ops<Self::State, 5>,
+                grads: &mut Gradients,
+                checkpointer: &mut Checkpointer,
+            ) {
+                let [node_x, node_delta, node_a, node_b, node_c] = ops.parents;
+                let grad = grads.consume::<B>(&ops.node);
+
+                let (x_state, delta_state, a_state, b_state, c_state) = ops.state;
+                let x = checkpointer.retrieve_node_output(x_state);
+                let delta = checkpointer.retrieve_node_output(delta_state);
+                let a = checkpointer.retrieve_node_output(a_state);
+                let b = checkpointer.retrieve_node_output(b_state);
+                let c = checkpointer.retrieve_node_output(c_state);
+
+                let backward = B::selective_scan_backward(x, delta, a, b, c, grad);
+
+                if let Some(node) = node_x {
+                    grads.register::<B>(node.id, backward.x_grad)
+                }
+                if let Some(node) = node_delta {
+                    grads.register::<B>(node.id, backward.delta_grad)
+                }
+                if let Some(node) = node_a {
+                    grads.register::<B>(node.id, backward.a_grad)
+                }
+                if let Some(node) = node_b {
+                    grads.register::<B>(node.id, backward.b_grad)
+                }
+                if let Some(node) = node_c {
+                    grads.register::<B>(node.id, backward.c_grad)
+                }
+            }
+        }
+
+        match SelectiveScan
+            .prepare::<C>([
+                x.node.clone(),
+                delta.node.clone(),
+                a.node.clone(),
+                b.node.clone(),
+                c.node.clone(),
+            ])
+            .compute_bound()
+            .stateful()
+        {
+            OpsKind::Tracked(mut prep) => {
+                let x_state = prep.checkpoint(&x);
+                let delta_state = prep.checkpoint(&delta);
+                let a_state = prep.checkpoint(&a);
+                let b_state = prep.checkpoint(&b);
+                let c_state = prep.checkpoint(&c);
+                prep.finish(
+                    (x_state, delta_state, a_state, b_state, c_state),
+                    B::selective_scan(
+                        x.primitive,
+                        delta.primitive,
+                        a.primitive,
+                        b.primitive,
+                        c.primitive,
+                    ),
+                )
+            }
+            OpsKind::UnTracked(prep) => prep.finish(B::selective_scan(
+                x.primitive,
+                delta.primitive,
+                a.primitive,
+                b.primitive,
+                c.primitive,
+            )),
+        }
+    }
+
+    fn selective_scan_backward(
+        _x: AutodiffTensor<B>,
+        _delta: AutodiffTensor<B>,
+        _a: AutodiffTensor<B>,
+        _b: AutodiffTensor<B>,
+        _c: AutodiffTensor<B>,
+        _output_grad: AutodiffTensor<B>,
+    ) -> SelectiveScanBackward<Self> {
+        panic!("Can't differentiate selective scan backward.");
+    }
+
     fn rfft(
         signal: FloatTensor<Autodiff<B, C>>,
         dim: usize,
@@ -2066,6 +2324,11 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             }
         }
 
+        let tangent = signal.tangent().map(|tangent| {
+            let (re, im) = Self::rfft(tangent, dim, n);
+            Self::float_cat(vec![re, im], 0)
+        });
+
         let input_len = signal.shape()[dim];
         let n_fft = n.unwrap_or(input_len);
         let (re, im) = B::rfft(signal.primitive, dim, n);
@@ -2103,6 +2366,7 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
             OpsKind::Tracked(prep) => prep.finish(state, spectrum),
             OpsKind::UnTracked(prep) => prep.finish(spectrum),
         };
+        let spectrum = tangent::attach::<B, C>(spectrum, tangent);
 
         let re = Self::float_slice(spectrum.clone(), &slices_re);
         let im = Self::float_slice(spectrum.clone(), &slices_im);
@@ -2156,14 +2420,25 @@ impl<B: Backend, C: CheckpointStrategy> ModuleOps<Autodiff<B, C>> for Autodiff<B
         let n_fft = n.unwrap_or(signal.shape()[dim]);
         let state = (dim, n, input_len, n_fft);
 
-        match Irfft
+        let tangent = tangent::any(&[&spectrum_re, &spectrum_im]).then(|| {
+            Self::irfft(
+                tangent::or_zeros(&spectrum_re),
+                tangent::or_zeros(&spectrum_im),
+                dim,
+                n,
+            )
+        });
+
+        let output = match Irfft
             .prepare::<C>([spectrum_re.node.clone(), spectrum_im.node.clone()])
             .compute_bound()
             .stateful()
         {
             OpsKind::Tracked(prep) => prep.finish(state, signal),
             OpsKind::UnTracked(prep) => prep.finish(signal),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 }
 
@@ -0,0 +1,115 @@
+//! Forward-mode differentiation.
+//!
+//! Float operations compute the tangent of their output from the tangents of their inputs using
+//! autodiff operations on the primal values. The tangent is therefore part of the graph like any
+//! other tensor, and backward on a tangent gives second-order derivatives (reverse-over-forward).
+
+use crate::{Autodiff, checkpoint::strategy::CheckpointStrategy, tensor::AutodiffTensor};
+use alloc::vec;
+use burn_backend::{
+    Backend, TensorMetadata,
+    ops::{FloatTensorOps, IntTensorOps},
+    tensor::IntTensor,
+};
+use burn_std::Shape;
+
+/// Attach the tangent to the output of an operation.
+///
+/// Tangents coming from broadcasted inputs are expanded to the output shape.
+pub(crate) fn attach<B: Backend, C: CheckpointStrategy>(
+    output: AutodiffTensor<B>,
+    tangent: Option<AutodiffTensor<B>>,
+) -> AutodiffTensor<B> {
+    let tangent = tangent.map(|tangent| {
+        let shape = output.shape();
+
+        if tangent.shape() == shape {
+            tangent
+        } else {
+            Autodiff::<B, C>::float_expand(tangent, shape)
+        }
+    });
+
+    output.with_tangent(tangent)
+}
+
+/// Sum the tangent contributions of two inputs.
+pub(crate) fn sum<B: Backend, C: CheckpointStrategy>(
+    lhs: Option<AutodiffTensor<B>>,
+    rhs: Option<AutodiffTensor<B>>,
+) -> Option<AutodiffTensor<B>> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => Some(Autodiff::<B, C>::float_add(lhs, rhs)),
+        (lhs, rhs) => lhs.or(rhs),
+    }
+}
+
+/// Tangent of an operation linear in both `x` and `weight`, plus a bias broadcast along
+/// `bias_dim` of the output (linear layers and convolutions).
+pub(crate) fn bilinear<B: Backend, C: CheckpointStrategy>(
+    x: &AutodiffTensor<B>,
+    weight: &AutodiffTensor<B>,
+    bias: Option<&AutodiffTensor<B>>,
+    bias_dim: usize,
+    op: impl Fn(AutodiffTensor<B>, AutodiffTensor<B>) -> AutodiffTensor<B>,
+) -> Option<AutodiffTensor<B>> {
+    let tangent = sum::<B, C>(
+        x.tangent().map(|tangent| op(tangent, weight.primal())),
+        weight.tangent().map(|tangent| op(x.primal(), tangent)),
+    );
+    let bias = bias.and_then(|bias| bias.tangent()).map(|tangent| {
+        let mut shape = vec![1; x.shape().num_dims()];
+        shape[bias_dim] = tangent.shape().num_elements();
+        Autodiff::<B, C>::float_reshape(tangent, Shape::from(shape))
+    });
+
+    sum::<B, C>(tangent, bias)
+}
+
+/// Tangent of a max pooling: the tangent of the input at the position of each maximum.
+///
+/// The indices are flat over the spatial dimensions of each channel.
+pub(crate) fn max_pool<B: Backend, C: CheckpointStrategy>(
+    tangent: AutodiffTensor<B>,
+    indices: IntTensor<B>,
+) -> AutodiffTensor<B> {
+    let shape_in = tangent.shape();
+    let shape_out = indices.shape();
+    let [batch_size, channels] = [shape_in[0], shape_in[1]];
+    let flat = |shape: &Shape| {
+        let spatial = shape.iter().skip(2).product::<usize>();
+        Shape::new([batch_size, channels, spatial])
+    };
+
+    let tangent = Autodiff::<B, C>::float_reshape(tangent, flat(&shape_in));
+    let indices = B::int_reshape(indices, flat(&shape_out));
+    let output = Autodiff::<B, C>::float_gather(2, tangent, indices);
+
+    Autodiff::<B, C>::float_reshape(output, shape_out)
+}
+
+/// Whether any of the tensors carries a tangent.
+pub(crate) fn any<B: Backend>(tensors: &[&AutodiffTensor<B>]) -> bool {
+    tensors.iter().any(|tensor| tensor.tangent.is_some())
+}
+
+/// The tangent of the tensor, or zeros when it doesn't carry one.
+pub(crate) fn or_zeros<B: Backend>(tensor: &AutodiffTensor<B>) -> AutodiffTensor<B> {
+    tensor.tangent().unwrap_or_else(|| {
+        AutodiffTensor::new(B::float_zeros(
+            tensor.shape(),
+            &tensor.device(),
+            tensor.dtype().into(),
+        ))
+    })
+}
+
+/// Panic when a tangent reaches an operation without a forward-mode rule.
+pub(crate) fn unsupported<'a, B: Backend>(
+    op: &str,
+    tensors: impl IntoIterator<Item = &'a AutodiffTensor<B>>,
+) {
+    if tensors.into_iter().any(|tensor| tensor.tangent.is_some()) {
+        panic!("Forward-mode differentiation isn't supported for `{op}`");
+    }
+}
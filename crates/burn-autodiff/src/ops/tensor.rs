@@ -13,7 +13,7 @@ use crate::{
     },
     grads::Gradients,
     graph::{ComputingProperty, NodeId, NodeRef, Parent, Requirement, Step},
-    ops::{Backward, Ops, OpsKind, binary, broadcast_shape, unary},
+    ops::{Backward, Ops, OpsKind, binary, broadcast_shape, tangent, unary},
     retro_binary, retro_unary, retro_unary_scalar,
     tensor::AutodiffTensor,
     utils::duplicate,
@@ -47,6 +47,39 @@ fn unsqueeze_like<B: Backend>(
     B::float_reshape(tensor, Shape::from(dims))
 }
 
+/// Index of the input position that produced each output of a cumulative min or max along `dim`.
+fn cumulative_source_indices<B: Backend>(
+    input: B::FloatTensorPrimitive,
+    output: B::FloatTensorPrimitive,
+    dim: usize,
+) -> IntTensor<B> {
+    let shape = input.shape();
+    let device = input.device();
+    let settings = get_device_settings::<B>(&device);
+    let dim_size = shape[dim] as i64;
+
+    // Create indices [0, 1, 2, ...] along the dimension
+    let arange_1d = B::int_arange(0..dim_size, &device, settings.int_dtype);
+
+    // Reshape to broadcast along the specified dimension
+    let mut arange_shape = vec![1; shape.num_dims()];
+    arange_shape[dim] = dim_size as usize;
+    let arange = B::int_reshape(arange_1d, Shape::from(arange_shape));
+
+    // Expand to match input shape
+    let arange = B::int_expand(arange, shape);
+
+    // Find where output[i] == input[i] (these are source positions)
+    let is_source = B::float_equal(output, input, settings.bool_dtype);
+    let is_source_int = B::bool_into_int(is_source, settings.int_dtype);
+
+    // Mask: where is_source, use index; else 0
+    let masked_indices = B::int_mul(arange, is_source_int);
+
+    // Cummax propagates the last valid (non-zero) index forward
+    B::int_cummax(masked_indices, dim)
+}
+
 impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C> {
     #[cfg_attr(feature = "tracing", tracing::instrument(
         level="trace",
@@ -115,7 +148,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match ToDevice
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_to_device(tangent, device));
+
+        let output = match ToDevice
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -125,7 +162,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(device_old, B::float_to_device(tensor.primitive, device))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_to_device(tensor.primitive, device)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_empty(shape: Shape, device: &Device<Self>, dtype: FloatDType) -> FloatTensor<Self> {
@@ -159,7 +198,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Add
+        let tangent = tangent::sum::<B, C>(lhs.tangent(), rhs.tangent());
+
+        let output = match Add
             .prepare::<C>([lhs.node.clone(), rhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroAdd::<B>::new(lhs.node.id, rhs.node.id))
@@ -171,7 +212,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_add(lhs.primitive, rhs.primitive),
             ),
             OpsKind::UnTracked(preps) => preps.finish(B::float_add(lhs.primitive, rhs.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_add_scalar(lhs: FloatTensor<Self>, rhs: Scalar) -> FloatTensor<Self> {
@@ -193,12 +236,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        AddScalar
+        let tangent = lhs.tangent();
+
+        let output = AddScalar
             .prepare::<C>([lhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroAddScalar::<B>::new(lhs.node.id, rhs))
             .parents([&lhs])
-            .stateless(B::float_add_scalar(lhs.primitive, rhs))
+            .stateless(B::float_add_scalar(lhs.primitive, rhs));
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_sub(lhs: FloatTensor<Self>, rhs: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -228,7 +275,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Sub
+        let tangent = tangent::sum::<B, C>(lhs.tangent(), rhs.tangent().map(Self::float_neg));
+
+        let output = match Sub
             .prepare::<C>([lhs.node.clone(), rhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroSub::<B>::new(lhs.node.id, rhs.node.id))
@@ -240,7 +289,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_sub(lhs.primitive, rhs.primitive),
             ),
             OpsKind::UnTracked(preps) => preps.finish(B::float_sub(lhs.primitive, rhs.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_sub_scalar(lhs: FloatTensor<Self>, rhs: Scalar) -> FloatTensor<Self> {
@@ -262,12 +313,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        SubScalar
+        let tangent = lhs.tangent();
+
+        let output = SubScalar
             .prepare::<C>([lhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroSubScalar::<B>::new(lhs.node.id, rhs))
             .parents([&lhs])
-            .stateless(B::float_sub_scalar(lhs.primitive, rhs))
+            .stateless(B::float_sub_scalar(lhs.primitive, rhs));
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_mul(lhs: FloatTensor<Self>, rhs: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -309,7 +364,14 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         let rhs_tracked = rhs.is_tracked();
         let broadcast = BinaryOpsBroadcast::new::<B>(&lhs.primitive, &rhs.primitive);
 
-        match Mul
+        let tangent = tangent::sum::<B, C>(
+            lhs.tangent()
+                .map(|tangent| Self::float_mul(tangent, rhs.primal())),
+            rhs.tangent()
+                .map(|tangent| Self::float_mul(lhs.primal(), tangent)),
+        );
+
+        let output = match Mul
             .prepare::<C>([lhs.node.clone(), rhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroMul::<B>::new(lhs.node.id, rhs.node.id))
@@ -326,7 +388,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 )
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_mul(lhs.primitive, rhs.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_mul_scalar(lhs: FloatTensor<Self>, rhs: Scalar) -> FloatTensor<Self> {
@@ -350,7 +414,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match MulScalar
+        let tangent = lhs
+            .tangent()
+            .map(|tangent| Self::float_mul_scalar(tangent, rhs));
+
+        let output = match MulScalar
             .prepare::<C>([lhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroMulScalar::<B>::new(lhs.node.id, rhs))
@@ -359,7 +427,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         {
             OpsKind::Tracked(prep) => prep.finish(rhs, B::float_mul_scalar(lhs.primitive, rhs)),
             OpsKind::UnTracked(prep) => prep.finish(B::float_mul_scalar(lhs.primitive, rhs)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_div(lhs: FloatTensor<Self>, rhs: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -410,7 +480,17 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         let rhs_tracked = rhs.is_tracked();
         let broadcast = BinaryOpsBroadcast::new::<B>(&lhs.primitive, &rhs.primitive);
 
-        match Div
+        let tangent = tangent::sum::<B, C>(
+            lhs.tangent()
+                .map(|tangent| Self::float_div(tangent, rhs.primal())),
+            rhs.tangent().map(|tangent| {
+                let value =
+                    Self::float_div(lhs.primal(), Self::float_mul(rhs.primal(), rhs.primal()));
+                Self::float_neg(Self::float_mul(tangent, value))
+            }),
+        );
+
+        let output = match Div
             .prepare::<C>([lhs.node.clone(), rhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroDiv::<B>::new(lhs.node.id, rhs.node.id))
@@ -427,7 +507,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 )
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_div(lhs.primitive, rhs.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_div_scalar(lhs: FloatTensor<Self>, rhs: Scalar) -> FloatTensor<Self> {
@@ -452,7 +534,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match DivScalar
+        let tangent = lhs
+            .tangent()
+            .map(|tangent| Self::float_div_scalar(tangent, rhs));
+
+        let output = match DivScalar
             .prepare::<C>([lhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroDivScalar::<B>::new(lhs.node.id, rhs))
@@ -461,7 +547,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         {
             OpsKind::Tracked(prep) => prep.finish(rhs, B::float_div_scalar(lhs.primitive, rhs)),
             OpsKind::UnTracked(prep) => prep.finish(B::float_div_scalar(lhs.primitive, rhs)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_remainder(lhs: FloatTensor<Self>, rhs: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -508,7 +596,15 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         let rhs_tracked = rhs.is_tracked();
         let broadcast = BinaryOpsBroadcast::new::<B>(&lhs.primitive, &rhs.primitive);
 
-        match Rem
+        let tangent = tangent::sum::<B, C>(
+            lhs.tangent(),
+            rhs.tangent().map(|tangent| {
+                let value = Self::float_floor(Self::float_div(lhs.primal(), rhs.primal()));
+                Self::float_neg(Self::float_mul(tangent, value))
+            }),
+        );
+
+        let output = match Rem
             .prepare::<C>([lhs.node.clone(), rhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroRem::<B>::new(lhs.node.id, rhs.node.id))
@@ -527,7 +623,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::float_remainder(lhs.primitive, rhs.primitive))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_remainder_scalar(lhs: FloatTensor<Self>, rhs: Scalar) -> FloatTensor<Self> {
@@ -549,12 +647,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        RemainderScalar
+        let tangent = lhs.tangent();
+
+        let output = RemainderScalar
             .prepare::<C>([lhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroRemainderScalar::<B>::new(lhs.node.id, rhs))
             .parents([&lhs])
-            .stateless(B::float_remainder_scalar(lhs.primitive, rhs))
+            .stateless(B::float_remainder_scalar(lhs.primitive, rhs));
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_matmul(lhs: FloatTensor<Self>, rhs: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -598,7 +700,14 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         let rhs_tracked = rhs.is_tracked();
         let broadcast = BinaryOpsBroadcast::new::<B>(&lhs.primitive, &rhs.primitive);
 
-        match Matmul
+        let tangent = tangent::sum::<B, C>(
+            lhs.tangent()
+                .map(|tangent| Self::float_matmul(tangent, rhs.primal())),
+            rhs.tangent()
+                .map(|tangent| Self::float_matmul(lhs.primal(), tangent)),
+        );
+
+        let output = match Matmul
             .prepare::<C>([lhs.node.clone(), rhs.node.clone()])
             .compute_bound()
             .stateful()
@@ -612,7 +721,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 )
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_matmul(lhs.primitive, rhs.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_cross(
@@ -649,7 +760,14 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         let lhs_tracked = lhs.is_tracked();
         let rhs_tracked = rhs.is_tracked();
 
-        match Cross
+        let tangent = tangent::sum::<B, C>(
+            lhs.tangent()
+                .map(|tangent| Self::float_cross(tangent, rhs.primal(), dim)),
+            rhs.tangent()
+                .map(|tangent| Self::float_cross(lhs.primal(), tangent, dim)),
+        );
+
+        let output = match Cross
             .prepare::<C>([lhs.node.clone(), rhs.node.clone()])
             .compute_bound()
             .stateful()
@@ -665,7 +783,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::float_cross(lhs.primitive, rhs.primitive, dim))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_neg(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -687,11 +807,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        Neg.prepare::<C>([tensor.node.clone()])
+        let tangent = tensor.tangent().map(Self::float_neg);
+
+        let output = Neg
+            .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroNeg::<B>::new(tensor.node.id))
             .parents([&tensor])
-            .stateless(B::float_neg(tensor.primitive))
+            .stateless(B::float_neg(tensor.primitive));
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_recip(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -719,7 +844,14 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Recip
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_neg(Self::float_div(
+                tangent,
+                Self::float_mul(tensor.primal(), tensor.primal()),
+            ))
+        });
+
+        let output = match Recip
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroRecip::<B>::new(tensor.node.id))
@@ -731,7 +863,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_recip(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_recip(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_swap_dims(tensor: FloatTensor<Self>, dim1: usize, dim2: usize) -> FloatTensor<Self> {
@@ -771,7 +905,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match SwapDim
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_swap_dims(tangent, dim1, dim2));
+
+        let output = match SwapDim
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroSwapDims::<B>::new(tensor.node.id, dim1, dim2))
@@ -785,7 +923,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::float_swap_dims(tensor.primitive, dim1, dim2))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_permute(tensor: FloatTensor<Self>, axes: &[usize]) -> FloatTensor<Self> {
@@ -829,7 +969,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match PermuteDim
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_permute(tangent, axes));
+
+        let output = match PermuteDim
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroPermuteDims::<B>::new(tensor.node.id, axes.to_vec()))
@@ -840,7 +984,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(axes.to_vec(), B::float_permute(tensor.primitive, axes))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_permute(tensor.primitive, axes)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_flip(tensor: FloatTensor<Self>, axes: &[usize]) -> FloatTensor<Self> {
@@ -879,7 +1025,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match FlipDim
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_flip(tangent, axes));
+
+        let output = match FlipDim
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroFlipDims::<B>::new(tensor.node.id, axes.to_vec()))
@@ -890,7 +1040,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(axes.to_vec(), B::float_flip(tensor.primitive, axes))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_flip(tensor.primitive, axes)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_reshape(tensor: FloatTensor<Self>, shape: Shape) -> FloatTensor<Self> {
@@ -939,7 +1091,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match ReshapeDim
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_reshape(tangent, shape.clone()));
+
+        let output = match ReshapeDim
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroReshape::<B>::new(tensor.node.id, shape.clone()))
@@ -951,7 +1107,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_reshape(tensor.primitive, shape),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_reshape(tensor.primitive, shape)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_gather(
@@ -980,7 +1138,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Gather
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_gather(dim, tangent, indices.clone()));
+
+        let output = match Gather
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -997,7 +1159,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::float_gather(dim, tensor.primitive, indices))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_scatter_add(
@@ -1031,7 +1195,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Scatter
+        let tangent = tangent::any(&[&tensor, &value]).then(|| {
+            Self::float_scatter_add(
+                dim,
+                tangent::or_zeros(&tensor),
+                indices.clone(),
+                tangent::or_zeros(&value),
+            )
+        });
+
+        let output = match Scatter
             .prepare::<C>([tensor.node, value.node])
             .compute_bound()
             .stateful()
@@ -1046,7 +1219,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 indices,
                 value.primitive,
             )),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_scatter(
@@ -1094,7 +1269,17 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                     }
                 }
 
-                match ScatterAssign
+                let tangent = tangent::any(&[&tensor, &value]).then(|| {
+                    Self::float_scatter(
+                        dim,
+                        tangent::or_zeros(&tensor),
+                        indices.clone(),
+                        tangent::or_zeros(&value),
+                        IndexingUpdateOp::Assign,
+                    )
+                });
+
+                let output = match ScatterAssign
                     .prepare::<C>([tensor.node, value.node])
                     .compute_bound()
                     .stateful()
@@ -1120,7 +1305,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                         value.primitive,
                         IndexingUpdateOp::Assign,
                     )),
-                }
+                };
+
+                tangent::attach::<B, C>(output, tangent)
             }
             other => unimplemented!("float_scatter with {other:?} update is not implemented"),
         }
@@ -1161,7 +1348,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                     }
                 }
 
-                match ScatterNdAdd
+                let tangent = tangent::any(&[&data, &values]).then(|| {
+                    Self::float_scatter_nd(
+                        tangent::or_zeros(&data),
+                        indices.clone(),
+                        tangent::or_zeros(&values),
+                        IndexingUpdateOp::Add,
+                    )
+                });
+
+                let output = match ScatterNdAdd
                     .prepare::<C>([data.node, values.node])
                     .compute_bound()
                     .stateful()
@@ -1181,7 +1377,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                         values.primitive,
                         IndexingUpdateOp::Add,
                     )),
-                }
+                };
+
+                tangent::attach::<B, C>(output, tangent)
             }
             IndexingUpdateOp::Assign => {
                 #[derive(Debug)]
@@ -1219,7 +1417,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                     }
                 }
 
-                match ScatterNdAssign
+                let tangent = tangent::any(&[&data, &values]).then(|| {
+                    Self::float_scatter_nd(
+                        tangent::or_zeros(&data),
+                        indices.clone(),
+                        tangent::or_zeros(&values),
+                        IndexingUpdateOp::Assign,
+                    )
+                });
+
+                let output = match ScatterNdAssign
                     .prepare::<C>([data.node, values.node])
                     .compute_bound()
                     .stateful()
@@ -1243,7 +1450,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                         values.primitive,
                         IndexingUpdateOp::Assign,
                     )),
-                }
+                };
+
+                tangent::attach::<B, C>(output, tangent)
             }
             IndexingUpdateOp::Mul => {
                 // Forward: out[idx[i]] = data[idx[i]] * values[i] (unique indices assumed).
@@ -1295,7 +1504,29 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 let data_tracked = data.is_tracked();
                 let values_tracked = values.is_tracked();
 
-                match ScatterNdMul
+                let tangent = tangent::sum::<B, C>(
+                    data.tangent().map(|tangent| {
+                        Self::float_scatter_nd(
+                            tangent,
+                            indices.clone(),
+                            values.primal(),
+                            IndexingUpdateOp::Mul,
+                        )
+                    }),
+                    values.tangent().map(|tangent| {
+                        let data_at_idx = Self::float_gather_nd(data.primal(), indices.clone());
+                        let zeros =
+                            Self::float_zeros(data.shape(), &data.device(), data.dtype().into());
+                        Self::float_scatter_nd(
+                            zeros,
+                            indices.clone(),
+                            Self::float_mul(data_at_idx, tangent),
+                            IndexingUpdateOp::Assign,
+                        )
+                    }),
+                );
+
+                let output = match ScatterNdMul
                     .prepare::<C>([data.node, values.node])
                     .compute_bound()
                     .stateful()
@@ -1319,7 +1550,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                         values.primitive,
                         IndexingUpdateOp::Mul,
                     )),
-                }
+                };
+
+                tangent::attach::<B, C>(output, tangent)
             }
             IndexingUpdateOp::Min | IndexingUpdateOp::Max => {
                 // Unique indices are required for this backward formula; duplicate indices have
@@ -1403,7 +1636,32 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
 
                 let is_max = matches!(reduction, IndexingUpdateOp::Max);
 
-                match ScatterNdMinMax
+                let tangent = tangent::any(&[&data, &values]).then(|| {
+                    // The scattered positions follow the tangent of the winner, the data on ties.
+                    let bool_dtype = get_device_settings::<B>(&data.device()).bool_dtype;
+                    let data_at_idx = Self::float_gather_nd(data.primal(), indices.clone());
+                    let data_won = if is_max {
+                        Self::float_greater_equal(data_at_idx, values.primal(), bool_dtype)
+                    } else {
+                        Self::float_lower_equal(data_at_idx, values.primal(), bool_dtype)
+                    };
+                    let data_tangent = tangent::or_zeros(&data);
+                    let data_tangent_at_idx =
+                        Self::float_gather_nd(data_tangent.clone(), indices.clone());
+                    let value = Self::float_mask_where(
+                        tangent::or_zeros(&values),
+                        data_won,
+                        data_tangent_at_idx,
+                    );
+                    Self::float_scatter_nd(
+                        data_tangent,
+                        indices.clone(),
+                        value,
+                        IndexingUpdateOp::Assign,
+                    )
+                });
+
+                let output = match ScatterNdMinMax
                     .prepare::<C>([data.node, values.node])
                     .compute_bound()
                     .stateful()
@@ -1423,7 +1681,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                         values.primitive,
                         reduction,
                     )),
-                }
+                };
+
+                tangent::attach::<B, C>(output, tangent)
             }
         }
     }
@@ -1455,7 +1715,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match GatherNd
+        let tangent = data
+            .tangent()
+            .map(|tangent| Self::float_gather_nd(tangent, indices.clone()));
+
+        let output = match GatherNd
             .prepare::<C>([data.node])
             .compute_bound()
             .stateful()
@@ -1469,7 +1733,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_gather_nd(data.primitive, indices),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_gather_nd(data.primitive, indices)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_select(
@@ -1513,7 +1779,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Select
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_select(tangent, dim, indices.clone()));
+
+        let output = match Select
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroSelect::<B>::new(tensor.node.id, dim, indices.clone()))
@@ -1532,7 +1802,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::float_select(tensor.primitive, dim, indices))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_select_add(
@@ -1582,7 +1854,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match IndexSelectDimAssign
+        let tangent = tangent::any(&[&tensor, &value]).then(|| {
+            Self::float_select_add(
+                tangent::or_zeros(&tensor),
+                dim,
+                indices.clone(),
+                tangent::or_zeros(&value),
+            )
+        });
+
+        let output = match IndexSelectDimAssign
             .prepare::<C>([tensor.node.clone(), value.node.clone()])
             .memory_bound()
             .retro_forward(RetroSelectAssign::<B>::new(
@@ -1604,7 +1885,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 indices,
                 value.primitive,
             )),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_select_assign(
@@ -1675,7 +1958,17 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                     }
                 }
 
-                match IndexSelectDimAssignReplace
+                let tangent = tangent::any(&[&tensor, &value]).then(|| {
+                    Self::float_select_assign(
+                        tangent::or_zeros(&tensor),
+                        dim,
+                        indices.clone(),
+                        tangent::or_zeros(&value),
+                        IndexingUpdateOp::Assign,
+                    )
+                });
+
+                let output = match IndexSelectDimAssignReplace
                     .prepare::<C>([tensor.node.clone(), value.node.clone()])
                     .memory_bound()
                     .retro_forward(RetroSelectAssignReplace::<B>::new(
@@ -1708,7 +2001,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                         value.primitive,
                         IndexingUpdateOp::Assign,
                     )),
-                }
+                };
+
+                tangent::attach::<B, C>(output, tangent)
             }
             other => {
                 unimplemented!("float_select_assign with {other:?} update is not implemented")
@@ -1753,7 +2048,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Index
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_slice(tangent, slices));
+
+        let output = match Index
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroSlice::<B>::new(tensor.node.id, slices.to_vec()))
@@ -1769,7 +2068,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_slice(tensor.primitive, slices),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_slice(tensor.primitive, slices)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_slice_assign(
@@ -1822,7 +2123,15 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match SliceAssign
+        let tangent = tangent::any(&[&tensor, &value]).then(|| {
+            Self::float_slice_assign(
+                tangent::or_zeros(&tensor),
+                slices,
+                tangent::or_zeros(&value),
+            )
+        });
+
+        let output = match SliceAssign
             .prepare::<C>([tensor.node.clone(), value.node.clone()])
             .memory_bound()
             .retro_forward(RetroSliceAssign::<B>::new(
@@ -1846,7 +2155,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 slices,
                 value.primitive,
             )),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_mask_where(
@@ -1889,7 +2200,15 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match MaskWhere
+        let tangent = tangent::any(&[&tensor, &source]).then(|| {
+            Self::float_mask_where(
+                tangent::or_zeros(&tensor),
+                mask.clone(),
+                tangent::or_zeros(&source),
+            )
+        });
+
+        let output = match MaskWhere
             .prepare::<C>([tensor.node, source.node])
             .compute_bound()
             .stateful()
@@ -1908,7 +2227,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 mask,
                 source.primitive,
             )),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_mask_fill(
@@ -1934,7 +2255,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match MaskFill
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_mask_fill(tangent, mask.clone(), 0f32.into()));
+
+        let output = match MaskFill
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -1946,7 +2271,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::float_mask_fill(tensor.primitive, mask, value))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_equal(
@@ -2092,12 +2419,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Mean.prepare::<C>([tensor.node]).compute_bound().stateful() {
+        let tangent = tensor.tangent().map(Self::float_mean);
+
+        let output = match Mean.prepare::<C>([tensor.node]).compute_bound().stateful() {
             OpsKind::Tracked(prep) => {
                 prep.finish(tensor.primitive.shape(), B::float_mean(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_mean(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_sum(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2122,12 +2453,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Sum.prepare::<C>([tensor.node]).compute_bound().stateful() {
+        let tangent = tensor.tangent().map(Self::float_sum);
+
+        let output = match Sum.prepare::<C>([tensor.node]).compute_bound().stateful() {
             OpsKind::Tracked(prep) => {
                 prep.finish(tensor.primitive.shape(), B::float_sum(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_sum(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_mean_dim(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
@@ -2156,7 +2491,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match MeanDim
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_mean_dim(tangent, dim));
+
+        let output = match MeanDim
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -2166,7 +2505,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_mean_dim(tensor.primitive, dim),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_mean_dim(tensor.primitive, dim)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_sum_dim(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
@@ -2193,7 +2534,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match SumDim
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_sum_dim(tangent, dim));
+
+        let output = match SumDim
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -2203,7 +2548,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_sum_dim(tensor.primitive, dim),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_sum_dim(tensor.primitive, dim)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_prod(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2241,13 +2588,22 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Prod.prepare::<C>([tensor.node]).compute_bound().stateful() {
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_mul(
+                Self::float_prod(tensor.primal()),
+                Self::float_sum(Self::float_div(tangent, tensor.primal())),
+            )
+        });
+
+        let output = match Prod.prepare::<C>([tensor.node]).compute_bound().stateful() {
             OpsKind::Tracked(prep) => {
                 let output = B::float_prod(tensor.primitive.clone());
                 prep.finish((tensor.primitive, output.clone()), output)
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_prod(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_prod_dim(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
@@ -2282,7 +2638,14 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match ProdDim
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_mul(
+                Self::float_prod_dim(tensor.primal(), dim),
+                Self::float_sum_dim(Self::float_div(tangent, tensor.primal()), dim),
+            )
+        });
+
+        let output = match ProdDim
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -2292,7 +2655,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish((tensor.primitive, output.clone()), output)
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_prod_dim(tensor.primitive, dim)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_cumsum(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
@@ -2319,7 +2684,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match CumSum
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_cumsum(tangent, dim));
+
+        let output = match CumSum
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -2329,7 +2698,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_cumsum(tensor.primitive, dim),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_cumsum(tensor.primitive, dim)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_cumprod(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
@@ -2424,7 +2795,14 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match CumProd
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_mul(
+                Self::float_cumprod(tensor.primal(), dim),
+                Self::float_cumsum(Self::float_div(tangent, tensor.primal()), dim),
+            )
+        });
+
+        let output = match CumProd
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -2434,7 +2812,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_cumprod(tensor.primitive, dim),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_cumprod(tensor.primitive, dim)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_cummin(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
@@ -2456,33 +2836,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 unary::<B, _>(ops.parents, ops.node, grads, |grad| {
                     // Gradient flows to the input positions that produced each output
                     // Use scatter to accumulate gradients (scatter does sum reduction)
-
                     let shape = input.shape();
                     let device = input.device();
-                    let settings = get_device_settings::<B>(&device);
-                    let dim_size = shape[dim] as i64;
-
-                    // Create indices [0, 1, 2, ...] along the dimension
-                    let arange_1d = B::int_arange(0..dim_size, &device, settings.int_dtype);
-
-                    // Reshape to broadcast along the specified dimension
-                    let mut arange_shape = vec![1; shape.num_dims()];
-                    arange_shape[dim] = dim_size as usize;
-                    let arange = B::int_reshape(arange_1d, Shape::from(arange_shape));
-
-                    // Expand to match input shape
-                    let arange = B::int_expand(arange, shape.clone());
-
-                    // Find where cummin[i] == input[i] (these are source positions)
-                    let is_source =
-                        B::float_equal(output.clone(), input.clone(), settings.bool_dtype);
-                    let is_source_int = B::bool_into_int(is_source, settings.int_dtype);
-
-                    // Mask: where is_source, use index; else 0
-                    let masked_indices = B::int_mul(arange, is_source_int);
-
-                    // Cummax propagates the last valid (non-zero) index forward
-                    let source_indices = B::int_cummax(masked_indices, dim);
+                    let source_indices = cumulative_source_indices::<B>(input, output, dim);
 
                     // Scatter gradients to source positions (sum reduction)
                     let zeros = B::float_zeros(shape, &device, grad.dtype().into());
@@ -2491,7 +2847,13 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match CumMin
+        let tangent = tensor.tangent().map(|tangent| {
+            let output = B::float_cummin(tensor.primitive.clone(), dim);
+            let indices = cumulative_source_indices::<B>(tensor.primitive.clone(), output, dim);
+            Self::float_gather(dim, tangent, indices)
+        });
+
+        let output = match CumMin
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -2501,7 +2863,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_cummin(tensor.primitive, dim),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_cummin(tensor.primitive, dim)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_cummax(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
@@ -2523,33 +2887,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 unary::<B, _>(ops.parents, ops.node, grads, |grad| {
                     // Gradient flows to the input positions that produced each output
                     // Use scatter to accumulate gradients (scatter does sum reduction)
-
                     let shape = input.shape();
                     let device = input.device();
-                    let settings = get_device_settings::<B>(&device);
-                    let dim_size = shape[dim] as i64;
-
-                    // Create indices [0, 1, 2, ...] along the dimension
-                    let arange_1d = B::int_arange(0..dim_size, &device, settings.int_dtype);
-
-                    // Reshape to broadcast along the specified dimension
-                    let mut arange_shape = vec![1; shape.num_dims()];
-                    arange_shape[dim] = dim_size as usize;
-                    let arange = B::int_reshape(arange_1d, Shape::from(arange_shape));
-
-                    // Expand to match input shape
-                    let arange = B::int_expand(arange, shape.clone());
-
-                    // Find where cummax[i] == input[i] (these are source positions)
-                    let is_source =
-                        B::float_equal(output.clone(), input.clone(), settings.bool_dtype);
-                    let is_source_int = B::bool_into_int(is_source, settings.int_dtype);
-
-                    // Mask: where is_source, use index; else 0
-                    let masked_indices = B::int_mul(arange, is_source_int);
-
-                    // Cummax propagates the last valid (non-zero) index forward
-                    let source_indices = B::int_cummax(masked_indices, dim);
+                    let source_indices = cumulative_source_indices::<B>(input, output, dim);
 
                     // Scatter gradients to source positions (sum reduction)
                     let zeros = B::float_zeros(shape, &device, grad.dtype().into());
@@ -2558,7 +2898,13 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match CumMax
+        let tangent = tensor.tangent().map(|tangent| {
+            let output = B::float_cummax(tensor.primitive.clone(), dim);
+            let indices = cumulative_source_indices::<B>(tensor.primitive.clone(), output, dim);
+            Self::float_gather(dim, tangent, indices)
+        });
+
+        let output = match CumMax
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -2568,7 +2914,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_cummax(tensor.primitive, dim),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_cummax(tensor.primitive, dim)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_argmax(tensor: FloatTensor<Self>, dim: usize, out_dtype: IntDType) -> IntTensor<B> {
@@ -2615,7 +2963,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Exp
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_mul(tangent, Self::float_exp(tensor.primal())));
+
+        let output = match Exp
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroExp::<B>::new(tensor.node.id))
@@ -2627,7 +2979,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_exp(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_exp(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_log(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2653,7 +3007,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Log
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_div(tangent, tensor.primal()));
+
+        let output = match Log
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroLog::<B>::new(tensor.node.id))
@@ -2665,7 +3023,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_log(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_log(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_log1p(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2693,7 +3053,14 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Log1P
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_div(
+                tangent,
+                Self::float_add_scalar(tensor.primal(), 1f32.into()),
+            )
+        });
+
+        let output = match Log1P
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroLog1P::<B>::new(tensor.node.id))
@@ -2705,7 +3072,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_log1p(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_log1p(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_powf_scalar_impl(tensor: FloatTensor<Self>, value: Scalar) -> FloatTensor<Self> {
@@ -2748,7 +3117,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match PowfScalar
+        let tangent = tensor.tangent().map(|tangent| {
+            let exponent = value.elem::<f64>() - 1.0;
+            let value = Self::float_mul_scalar(
+                Self::float_powf_scalar(tensor.primal(), exponent.into()),
+                value,
+            );
+            Self::float_mul(tangent, value)
+        });
+
+        let output = match PowfScalar
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroPowfScalar::<B>::new(tensor.node.id, value.elem()))
@@ -2760,7 +3138,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_powf_scalar(tensor.primitive, value))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_powf_scalar(tensor.primitive, value)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_sqrt(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2790,7 +3170,14 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Sqrt
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_div(
+                tangent,
+                Self::float_mul_scalar(Self::float_sqrt(tensor.primal()), 2f32.into()),
+            )
+        });
+
+        let output = match Sqrt
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroSqrt::<B>::new(tensor.node.id))
@@ -2802,7 +3189,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_sqrt(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_sqrt(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_abs(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2828,7 +3217,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Abs
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_mul(tangent, Self::float_sign(tensor.primal())));
+
+        let output = match Abs
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroAbs::<B>::new(tensor.node.id))
@@ -2840,7 +3233,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_abs(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_abs(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_cos(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2867,7 +3262,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Cos
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_neg(Self::float_mul(tangent, Self::float_sin(tensor.primal())))
+        });
+
+        let output = match Cos
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroCos::<B>::new(tensor.node.id))
@@ -2879,7 +3278,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_cos(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_cos(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_sin(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2905,7 +3306,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Sin
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_mul(tangent, Self::float_cos(tensor.primal())));
+
+        let output = match Sin
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroSin::<B>::new(tensor.node.id))
@@ -2917,7 +3322,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_sin(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_sin(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_tanh(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2947,7 +3354,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Tanh
+        let tangent = tensor.tangent().map(|tangent| {
+            let tanh = Self::float_tanh(tensor.primal());
+            let value = Self::float_add_scalar(
+                Self::float_neg(Self::float_mul(tanh.clone(), tanh)),
+                1f32.into(),
+            );
+            Self::float_mul(tangent, value)
+        });
+
+        let output = match Tanh
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroTanh::<B>::new(tensor.node.id))
@@ -2959,7 +3375,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_tanh(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_tanh(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_cosh(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -2984,7 +3402,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Cosh
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_mul(tangent, Self::float_sinh(tensor.primal())));
+
+        let output = match Cosh
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroCosh::<B>::new(tensor.node.id))
@@ -2996,7 +3418,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_cosh(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_cosh(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_sinh(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3021,7 +3445,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Sinh
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_mul(tangent, Self::float_cosh(tensor.primal())));
+
+        let output = match Sinh
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroSinh::<B>::new(tensor.node.id))
@@ -3033,7 +3461,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_sinh(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_sinh(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_tan(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3061,7 +3491,13 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Tan
+        let tangent = tensor.tangent().map(|tangent| {
+            let tan = Self::float_tan(tensor.primal());
+            let value = Self::float_add_scalar(Self::float_mul(tan.clone(), tan), 1f32.into());
+            Self::float_mul(tangent, value)
+        });
+
+        let output = match Tan
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroTan::<B>::new(tensor.node.id))
@@ -3073,7 +3509,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_tan(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_tan(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_asin(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3101,7 +3539,15 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Asin
+        let tangent = tensor.tangent().map(|tangent| {
+            let value = Self::float_sqrt(Self::float_add_scalar(
+                Self::float_neg(Self::float_mul(tensor.primal(), tensor.primal())),
+                1f32.into(),
+            ));
+            Self::float_div(tangent, value)
+        });
+
+        let output = match Asin
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroAsin::<B>::new(tensor.node.id))
@@ -3113,7 +3559,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_asin(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_asin(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_acos(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3142,7 +3590,15 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Acos
+        let tangent = tensor.tangent().map(|tangent| {
+            let value = Self::float_sqrt(Self::float_add_scalar(
+                Self::float_neg(Self::float_mul(tensor.primal(), tensor.primal())),
+                1f32.into(),
+            ));
+            Self::float_neg(Self::float_div(tangent, value))
+        });
+
+        let output = match Acos
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroAcos::<B>::new(tensor.node.id))
@@ -3154,7 +3610,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_acos(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_acos(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_atan(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3182,7 +3640,17 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Atan
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_div(
+                tangent,
+                Self::float_add_scalar(
+                    Self::float_mul(tensor.primal(), tensor.primal()),
+                    1f32.into(),
+                ),
+            )
+        });
+
+        let output = match Atan
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroAtan::<B>::new(tensor.node.id))
@@ -3194,7 +3662,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_atan(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_atan(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_asinh(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3223,7 +3693,17 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Asinh
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_div(
+                tangent,
+                Self::float_sqrt(Self::float_add_scalar(
+                    Self::float_mul(tensor.primal(), tensor.primal()),
+                    1f32.into(),
+                )),
+            )
+        });
+
+        let output = match Asinh
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroAsinh::<B>::new(tensor.node.id))
@@ -3235,7 +3715,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_asinh(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_asinh(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_acosh(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3264,7 +3746,17 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Acosh
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_div(
+                tangent,
+                Self::float_sqrt(Self::float_sub_scalar(
+                    Self::float_mul(tensor.primal(), tensor.primal()),
+                    1f32.into(),
+                )),
+            )
+        });
+
+        let output = match Acosh
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroAcosh::<B>::new(tensor.node.id))
@@ -3276,7 +3768,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_acosh(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_acosh(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_atanh(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3305,7 +3799,17 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Atanh
+        let tangent = tensor.tangent().map(|tangent| {
+            Self::float_div(
+                tangent,
+                Self::float_add_scalar(
+                    Self::float_neg(Self::float_mul(tensor.primal(), tensor.primal())),
+                    1f32.into(),
+                ),
+            )
+        });
+
+        let output = match Atanh
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroAtanh::<B>::new(tensor.node.id))
@@ -3317,7 +3821,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_atanh(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_atanh(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_atan2(y: FloatTensor<Self>, x: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3377,7 +3883,27 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         let x_tracked = x.is_tracked();
         let broadcast = BinaryOpsBroadcast::new::<B>(&y.primitive, &x.primitive);
 
-        match Atan2
+        let tangent = {
+            let denominator = || {
+                Self::float_add(
+                    Self::float_mul(x.primal(), x.primal()),
+                    Self::float_mul(y.primal(), y.primal()),
+                )
+            };
+            tangent::sum::<B, C>(
+                y.tangent().map(|tangent| {
+                    Self::float_mul(tangent, Self::float_div(x.primal(), denominator()))
+                }),
+                x.tangent().map(|tangent| {
+                    Self::float_neg(Self::float_mul(
+                        tangent,
+                        Self::float_div(y.primal(), denominator()),
+                    ))
+                }),
+            )
+        };
+
+        let output = match Atan2
             .prepare::<C>([y.node.clone(), x.node.clone()])
             .memory_bound()
             .retro_forward(RetroAtan2::<B>::new(y.node.id, x.node.id))
@@ -3395,7 +3921,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 )
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_atan2(y.primitive, x.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_round(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3569,7 +4097,16 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Erf
+        let tangent = tensor.tangent().map(|tangent| {
+            let value = Self::float_exp(Self::float_neg(Self::float_mul(
+                tensor.primal(),
+                tensor.primal(),
+            )));
+            let value = Self::float_mul_scalar(value, (2.0 / core::f64::consts::PI.sqrt()).into());
+            Self::float_mul(tangent, value)
+        });
+
+        let output = match Erf
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroErf::<B>::new(tensor.node.id))
@@ -3581,7 +4118,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 prep.finish(state, B::float_erf(tensor.primitive))
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_erf(tensor.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_cat(tensors: Vec<FloatTensor<Self>>, dim: usize) -> FloatTensor<Self> {
@@ -3640,6 +4179,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
+        let tangent = tensors
+            .iter()
+            .any(|tensor| tensor.tangent.is_some())
+            .then(|| Self::float_cat(tensors.iter().map(tangent::or_zeros).collect(), dim));
+
         let mut nodes = Vec::with_capacity(tensors.len());
         let mut primitives = Vec::with_capacity(tensors.len());
         let mut dim_sizes = Vec::with_capacity(tensors.len());
@@ -3658,12 +4202,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
 
         let output = B::float_cat(primitives, dim);
         if requirement.is_none() {
-            return AutodiffTensor::from_parents(
-                output,
-                &nodes,
-                requirement,
-                cat_computing_property,
-            );
+            let output =
+                AutodiffTensor::from_parents(output, &nodes, requirement, cat_computing_property);
+            return tangent::attach::<B, C>(output, tangent);
         }
 
         let output =
@@ -3679,10 +4220,17 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             parents.push(Parent { id: node.id });
         }
         let ops = CatStep::<B>::new(nodes, dim_sizes, output.node.clone(), dim, parents);
-        output.register_step(ops, checkpointer_builder)
+        let output = output.register_step(ops, checkpointer_builder);
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_max_dim(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
+        if tensor.tangent.is_some() {
+            let settings = get_device_settings::<B>(&tensor.primitive.device());
+            return Self::float_max_dim_with_indices(tensor, dim, settings.int_dtype).0;
+        }
+
         match MaxMinDim
             .prepare::<C>([tensor.node])
             .compute_bound()
@@ -3703,7 +4251,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         dim: usize,
         indices_dtype: IntDType,
     ) -> (FloatTensor<Self>, IntTensor<B>) {
-        match MaxMinDim
+        let tangent = tensor.tangent();
+
+        let (output, indices) = match MaxMinDim
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -3723,10 +4273,18 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
 
                 (tensor, index)
             }
-        }
+        };
+        let tangent = tangent.map(|tangent| Self::float_gather(dim, tangent, indices.clone()));
+
+        (tangent::attach::<B, C>(output, tangent), indices)
     }
 
     fn float_min_dim(tensor: FloatTensor<Self>, dim: usize) -> FloatTensor<Self> {
+        if tensor.tangent.is_some() {
+            let settings = get_device_settings::<B>(&tensor.primitive.device());
+            return Self::float_min_dim_with_indices(tensor, dim, settings.int_dtype).0;
+        }
+
         match MaxMinDim
             .prepare::<C>([tensor.node])
             .compute_bound()
@@ -3747,7 +4305,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         dim: usize,
         indices_dtype: IntDType,
     ) -> (FloatTensor<Self>, IntTensor<B>) {
-        match MaxMinDim
+        let tangent = tensor.tangent();
+
+        let (output, indices) = match MaxMinDim
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -3767,7 +4327,10 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
 
                 (tensor, index)
             }
-        }
+        };
+        let tangent = tangent.map(|tangent| Self::float_gather(dim, tangent, indices.clone()));
+
+        (tangent::attach::<B, C>(output, tangent), indices)
     }
 
     fn float_into_int(tensor: FloatTensor<Self>, out_dtype: IntDType) -> IntTensor<B> {
@@ -3831,7 +4394,22 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
 
         let broadcast = BinaryOpsBroadcast::new::<B>(&lhs.primitive, &rhs.primitive);
 
-        match PowF
+        let tangent = tangent::sum::<B, C>(
+            lhs.tangent().map(|tangent| {
+                let exponent = Self::float_sub_scalar(rhs.primal(), 1.0.into());
+                let value = Self::float_mul(Self::float_powf(lhs.primal(), exponent), rhs.primal());
+                Self::float_mul(tangent, value)
+            }),
+            rhs.tangent().map(|tangent| {
+                let value = Self::float_mul(
+                    Self::float_powf(lhs.primal(), rhs.primal()),
+                    Self::float_log(lhs.primal()),
+                );
+                Self::float_mul(tangent, value)
+            }),
+        );
+
+        let output = match PowF
             .prepare::<C>([lhs.node.clone(), rhs.node.clone()])
             .memory_bound()
             .retro_forward(RetroPowf::<B>::new(lhs.node.id, rhs.node.id))
@@ -3847,7 +4425,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 )
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_powf(lhs.primitive, rhs.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_hypot(lhs: FloatTensor<Self>, rhs: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3890,7 +4470,20 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
         let broadcast = BinaryOpsBroadcast::new::<B>(&lhs.primitive, &rhs.primitive);
-        match Hypot
+        let tangent = tangent::sum::<B, C>(
+            lhs.tangent().map(|tangent| {
+                let value =
+                    Self::float_div(lhs.primal(), Self::float_hypot(lhs.primal(), rhs.primal()));
+                Self::float_mul(tangent, value)
+            }),
+            rhs.tangent().map(|tangent| {
+                let value =
+                    Self::float_div(rhs.primal(), Self::float_hypot(lhs.primal(), rhs.primal()));
+                Self::float_mul(tangent, value)
+            }),
+        );
+
+        let output = match Hypot
             .prepare::<C>([lhs.node.clone(), rhs.node.clone()])
             .compute_bound()
             .stateful()
@@ -3904,7 +4497,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 )
             }
             OpsKind::UnTracked(prep) => prep.finish(B::float_hypot(lhs.primitive, rhs.primitive)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_sign(tensor: FloatTensor<Self>) -> FloatTensor<Self> {
@@ -3993,7 +4588,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match ExpandDim
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_expand(tangent, shape.clone()));
+
+        let output = match ExpandDim
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroExpand::<B>::new(tensor.node.id, shape.clone()))
@@ -4005,10 +4604,17 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_expand(tensor.primitive, shape),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_expand(tensor.primitive, shape)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_sort(tensor: FloatTensor<Self>, dim: usize, descending: bool) -> FloatTensor<Self> {
+        if tensor.tangent.is_some() {
+            let settings = get_device_settings::<B>(&tensor.primitive.device());
+            return Self::float_sort_with_indices(tensor, dim, descending, settings.int_dtype).0;
+        }
+
         match super::sort::SortDim
             .prepare::<C>([tensor.node])
             .compute_bound()
@@ -4037,7 +4643,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
         descending: bool,
         indices_dtype: IntDType,
     ) -> (FloatTensor<Self>, IntTensor<B>) {
-        match super::sort::SortDim
+        let tangent = tensor.tangent();
+
+        let (output, indices) = match super::sort::SortDim
             .prepare::<C>([tensor.node])
             .compute_bound()
             .stateful()
@@ -4057,7 +4665,10 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
 
                 (tensor, indices)
             }
-        }
+        };
+        let tangent = tangent.map(|tangent| Self::float_gather(dim, tangent, indices.clone()));
+
+        (tangent::attach::<B, C>(output, tangent), indices)
     }
 
     fn float_argsort(
@@ -4117,7 +4728,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Repeat
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_repeat_dim(tangent, dim, times));
+
+        let output = match Repeat
             .prepare::<C>([tensor.node.clone()])
             .memory_bound()
             .retro_forward(RetroRepeat::<B>::new(tensor.node.id, dim, times))
@@ -4131,7 +4746,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::float_repeat_dim(tensor.primitive, dim, times))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_cast(tensor: FloatTensor<Self>, dtype: burn_std::FloatDType) -> FloatTensor<Self> {
@@ -4155,7 +4772,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Cast
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_cast(tangent, dtype));
+
+        let output = match Cast
             .prepare::<C>([tensor.node.clone()])
             .compute_bound()
             .stateful()
@@ -4165,7 +4786,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
                 B::float_cast(tensor.primitive, dtype),
             ),
             OpsKind::UnTracked(prep) => prep.finish(B::float_cast(tensor.primitive, dtype)),
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 
     fn float_unfold(
@@ -4235,7 +4858,11 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             }
         }
 
-        match Unfold
+        let tangent = tensor
+            .tangent()
+            .map(|tangent| Self::float_unfold(tangent, dim, size, step));
+
+        let output = match Unfold
             .prepare::<C>([tensor.node.clone()])
             .compute_bound()
             .stateful()
@@ -4247,7 +4874,9 @@ impl<B: Backend, C: CheckpointStrategy> FloatTensorOps<Self> for Autodiff<B, C>
             OpsKind::UnTracked(prep) => {
                 prep.finish(B::float_unfold(tensor.primitive, dim, size, step))
             }
-        }
+        };
+
+        tangent::attach::<B, C>(output, tangent)
     }
 }
 
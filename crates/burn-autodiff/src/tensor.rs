@@ -22,6 +22,8 @@ pub struct AutodiffTensor<B: BackendTypes> {
     pub primitive: B::FloatTensorPrimitive,
     pub node: NodeRef,
     pub rc: NodeRefCount,
+    /// Forward-mode tangent, propagated eagerly through every float operation.
+    pub tangent: Option<Box<AutodiffTensor<B>>>,
 }
 
 impl<B: BackendTypes> TensorMetadata for AutodiffTensor<B> {
@@ -96,6 +98,7 @@ impl<B: Backend> AutodiffTensor<B> {
             rc: Arc::new(node.id),
             primitive,
             node: node.clone(),
+            tangent: None,
         }
     }
 
@@ -170,9 +173,33 @@ impl<B: Backend> AutodiffTensor<B> {
             rc: Arc::new(node.id),
             primitive,
             node,
+            tangent: None,
         }
     }
 
+    /// The same tensor without its forward-mode tangent.
+    pub fn primal(&self) -> Self {
+        Self {
+            primitive: self.primitive.clone(),
+            node: self.node.clone(),
+            rc: self.rc.clone(),
+            tangent: None,
+        }
+    }
+
+    /// The forward-mode tangent carried by the tensor, if any.
+    pub fn tangent(&self) -> Option<Self> {
+        self.tangent.as_deref().cloned()
+    }
+
+    /// Replace the forward-mode tangent carried by the tensor.
+    ///
+    /// Tangents are first-order: the tangent of the given tangent is dropped.
+    pub fn with_tangent(mut self, tangent: Option<Self>) -> Self {
+        self.tangent = tangent.map(|tangent| Box::new(tangent.primal()));
+        self
+    }
+
     /// Register a step into a graph for that tensor.
     ///
     /// # Warning
@@ -0,0 +1,32 @@
+use super::*;
+use burn_tensor::Distribution;
+use burn_tensor::module::packed_attention;
+use burn_tensor::ops::AttentionModuleOptions;
+
+#[test]
+fn test_attention_packed_sequences_grad() {
+    let device = AutodiffDevice::new();
+    let shape = [1, 2, 6, 4];
+    let query = TestTensor::<4>::random(shape, Distribution::Uniform(-1., 1.), &device);
+    let key = TestTensor::<4>::random(shape, Distribution::Uniform(-1., 1.), &device);
+    let value =
+        TestTensor::<4>::random(shape, Distribution::Uniform(-1., 1.), &device).require_grad();
+
+    let options = AttentionModuleOptions {
+        sliding_window: Some(2),
+        alibi_max_bias: Some(8.0),
+        ..Default::default()
+    };
+    let seq_offsets = TestTensorInt::<1>::from_ints([0, 2, 6], &device);
+    let output = packed_attention(query, key, value.clone(), seq_offsets, None, None, options);
+
+    // Only the second document contributes to the loss, so no gradient flows to the values
+    // of the first one.
+    let grads = output.slice_dim(2, 2..6).sum().backward();
+    let grad = value.grad(&grads).unwrap();
+
+    let first = grad.clone().slice_dim(2, 0..2).abs().sum();
+    let second = grad.slice_dim(2, 2..6).abs().sum();
+    assert_eq!(first.into_scalar::<f32>(), 0.0);
+    assert!(second.into_scalar::<f32>() > 0.0);
+}
@@ -0,0 +1,156 @@
+use super::*;
+use burn_tensor::{TensorData, Tolerance};
+
+#[test]
+fn should_compute_vjp() {
+    let device = AutodiffDevice::new();
+    let primal = TestTensor::<2>::from_data([[1.0, -2.0], [3.0, 0.5]], &device);
+    let cotangent = TestTensor::<2>::from_data([[1.0, 2.0], [-1.0, 0.0]], &device);
+
+    let (output, grad) = TestTensor::vjp(|x| x.clone() * x * 3.0, primal, cotangent);
+
+    output.into_data().assert_approx_eq::<FloatElem>(
+        &TensorData::from([[3.0, 12.0], [27.0, 0.75]]),
+        Tolerance::default(),
+    );
+    grad.into_data().assert_approx_eq::<FloatElem>(
+        &TensorData::from([[6.0, -24.0], [-18.0, 0.0]]),
+        Tolerance::default(),
+    );
+}
+
+#[test]
+fn should_compute_jacobian_of_linear_map() {
+    let device = AutodiffDevice::new();
+    let weights = TestTensor::<2>::from_data([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]], &device);
+    let primal = TestTensor::<1>::from_data([0.5, -1.0, 2.0], &device);
+
+    let jacobian = TestTensor::jacobian(
+        |x| x.reshape([1, 3]).matmul(weights.clone()).reshape([2]),
+        primal,
+    );
+
+    jacobian.into_data().assert_approx_eq::<FloatElem>(
+        &TensorData::from([[1.0, 3.0, 5.0], [2.0, 4.0, 6.0]]),
+        Tolerance::default(),
+    );
+}
+
+#[test]
+fn should_compute_jacobian_of_elementwise_op() {
+    let device = AutodiffDevice::new();
+    let primal = TestTensor::<1>::from_data([0.0, 1.0, -1.0], &device);
+
+    let jacobian = TestTensor::jacobian(|x| x.exp(), primal);
+
+    let e = core::f64::consts::E;
+    jacobian.into_data().assert_approx_eq::<FloatElem>(
+        &TensorData::from([[1.0, 0.0, 0.0], [0.0, e, 0.0], [0.0, 0.0, 1.0 / e]]),
+        Tolerance::default(),
+    );
+}
+
+#[test]
+fn should_compute_jvp() {
+    let device = AutodiffDevice::new();
+    let primal = TestTensor::<2>::from_data([[1.0, -2.0], [3.0, 0.5]], &device);
+    let tangent = TestTensor::<2>::from_data([[1.0, 1.0], [0.0, -2.0]], &device);
+
+    // The directional derivative of `x * sum(x)` is `t * sum(x) + x * sum(t)`.
+    let (output, jvp) = TestTensor::jvp(|x| x.clone() * x.sum().unsqueeze(), primal, tangent);
+
+    output.into_data().assert_approx_eq::<FloatElem>(
+        &TensorData::from([[2.5, -5.0], [7.5, 1.25]]),
+        Tolerance::default(),
+    );
+    jvp.into_data().assert_approx_eq::<FloatElem>(
+        &TensorData::from([[2.5, 2.5], [0.0, -5.0]]),
+        Tolerance::default(),
+    );
+}
+
+#[test]
+fn should_compute_zero_jvp_of_constant_function() {
+    let device = AutodiffDevice::new();
+    let primal = TestTensor::<1>::from_data([1.0, -2.0], &device);
+    let tangent = TestTensor::<1>::from_data([1.0, 1.0], &device);
+
+    let (_output, jvp) = TestTensor::jvp(|x| x.detach().exp(), primal, tangent);
+
+    jvp.into_data()
+        .assert_approx_eq::<FloatElem>(&TensorData::from([0.0, 0.0]), Tolerance::default());
+}
+
+#[test]
+fn should_propagate_tangent() {
+    let device = AutodiffDevice::new();
+    let primal = TestTensor::<1>::from_data([0.5, -1.0], &device);
+    let tangent = TestTensor::<1>::from_data([1.0, 2.0], &device);
+
+    let output = primal.with_tangent(tangent).tanh();
+
+    // The derivative of `tanh(x)` is `1 - tanh(x)²`.
+    output
+        .tangent()
+        .unwrap()
+        .into_data()
+        .assert_approx_eq::<FloatElem>(
+            &TensorData::from([0.7864477, 0.8399487]),
+            Tolerance::default(),
+        );
+}
+
+#[test]
+fn should_compute_hvp_of_quadratic_form() {
+    let device = AutodiffDevice::new();
+    let matrix = TestTensor::<2>::from_data([[2.0, 1.0], [0.0, 3.0]], &device);
+    let primal = TestTensor::<1>::from_data([1.0, -1.0], &device);
+    let vector = TestTensor::<1>::from_data([1.0, 2.0], &device);
+
+    // The Hessian of `xᵀ A x` is `A + Aᵀ`.
+    let (output, hvp) = TestTensor::hvp(
+        |x| {
+            let column = x.clone().reshape([2, 1]);
+            x.reshape([1, 2])
+                .matmul(matrix.clone())
+                .matmul(column)
+                .reshape([1])
+        },
+        primal,
+        vector,
+    );
+
+    output
+        .into_data()
+        .assert_approx_eq::<FloatElem>(&TensorData::from([4.0]), Tolerance::default());
+    hvp.into_data()
+        .assert_approx_eq::<FloatElem>(&TensorData::from([6.0, 13.0]), Tolerance::default());
+}
+
+#[test]
+fn should_compute_hvp_of_cubic() {
+    let device = AutodiffDevice::new();
+    let primal = TestTensor::<1>::from_data([1.0, -0.5, 2.0], &device);
+    let vector = TestTensor::<1>::from_data([0.5, 1.0, -1.0], &device);
+
+    // The Hessian of `sum(x³)` is `diag(6 x)`.
+    let (_output, hvp) = TestTensor::hvp(|x| x.powi_scalar(3).sum(), primal, vector);
+
+    hvp.into_data()
+        .assert_approx_eq::<FloatElem>(&TensorData::from([3.0, -3.0, -12.0]), Tolerance::default());
+}
+
+#[test]
+fn should_compute_hvp_of_nonlinear_function() {
+    let device = AutodiffDevice::new();
+    let primal = TestTensor::<1>::from_data([0.5, -1.0], &device);
+    let vector = TestTensor::<1>::from_data([1.0, 2.0], &device);
+
+    // The Hessian of `sum(x sin(x))` is `diag(2 cos(x) - x sin(x))`.
+    let (_output, hvp) = TestTensor::hvp(|x| (x.clone() * x.sin()).sum(), primal, vector);
+
+    hvp.into_data().assert_approx_eq::<FloatElem>(
+        &TensorData::from([1.5154524, 0.4782673]),
+        Tolerance::default(),
+    );
+}
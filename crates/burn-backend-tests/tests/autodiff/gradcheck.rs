@@ -0,0 +1,308 @@
+use super::*;
+use burn_tensor::gradcheck::{
+    GradCheckError, GradInput, GradInputs, GradOutputs, gradcheck, gradgradcheck,
+};
+use burn_tensor::module::{avg_pool2d, conv2d, max_pool2d};
+use burn_tensor::ops::ConvOptions;
+use burn_tensor::{Tensor, TensorData, activation};
+
+/// A float input with distinct values in `[low, high]`.
+fn float<const D: usize>(dims: [usize; D], low: f64, high: f64) -> GradInput {
+    let num_elements = dims.iter().product::<usize>();
+    let values = (0..num_elements)
+        .map(|i| low + (high - low) * (0.5 + 0.5 * (1.7 * i as f64 + 0.3).sin()))
+        .collect::<Vec<_>>();
+
+    GradInput::Float(TensorData::new(values, dims))
+}
+
+/// Check the gradients of `func`, skipping devices without f64 support.
+fn check<O: Into<GradOutputs>>(inputs: &[GradInput], func: impl Fn(&GradInputs) -> O) {
+    match gradcheck(&AutodiffDevice::new(), inputs, func) {
+        Ok(()) | Err(GradCheckError::UnsupportedDevice) => {}
+        Err(err) => panic!("{err}"),
+    }
+}
+
+/// Check the first and second-order gradients of `func`, skipping devices without f64 support.
+fn check_twice<O: Into<GradOutputs>>(inputs: &[GradInput], func: impl Fn(&GradInputs) -> O) {
+    check(inputs, &func);
+    match gradgradcheck(&AutodiffDevice::new(), inputs, &func) {
+        Ok(()) | Err(GradCheckError::UnsupportedDevice) => {}
+        Err(err) => panic!("{err}"),
+    }
+}
+
+#[test]
+fn should_check_smooth_unary_ops() {
+    let ops: [fn(Tensor<2>) -> Tensor<2>; 10] = [
+        |x| x.exp(),
+        |x| x.sin(),
+        |x| x.cos(),
+        |x| x.tanh(),
+        |x| x.erf(),
+        activation::sigmoid,
+        activation::gelu,
+        activation::log_sigmoid,
+        |x| x.powf_scalar(3.0),
+        |x| x.clone() * x.clone() - x * 2.0,
+    ];
+
+    for op in ops {
+        check_twice(&[float([3, 4], -2.0, 2.0)], |inputs| op(inputs.float(0)));
+    }
+}
+
+#[test]
+fn should_check_unary_ops_on_positive_inputs() {
+    let ops: [fn(Tensor<2>) -> Tensor<2>; 5] = [
+        |x| x.log(),
+        |x| x.log1p(),
+        |x| x.sqrt(),
+        |x| x.recip(),
+        |x| x.powf_scalar(0.5),
+    ];
+
+    for op in ops {
+        check_twice(&[float([3, 4], 0.5, 2.0)], |inputs| op(inputs.float(0)));
+    }
+}
+
+#[test]
+fn should_check_piecewise_unary_ops() {
+    let ops: [fn(Tensor<2>) -> Tensor<2>; 3] =
+        [|x| x.abs(), activation::relu, |x| x.clamp(-0.5, 0.5)];
+
+    // None of the inputs is within `eps` of a kink.
+    for op in ops {
+        check_twice(&[float([3, 4], -1.0, 1.0)], |inputs| op(inputs.float(0)));
+    }
+}
+
+#[test]
+fn should_check_binary_ops_with_broadcasting() {
+    let ops: [fn(Tensor<2>, Tensor<2>) -> Tensor<2>; 5] = [
+        |lhs, rhs| lhs + rhs,
+        |lhs, rhs| lhs - rhs,
+        |lhs, rhs| lhs * rhs,
+        |lhs, rhs| lhs / rhs,
+        |lhs, rhs| lhs.powf(rhs),
+    ];
+
+    for op in ops {
+        check_twice(
+            &[float([3, 4], 0.5, 2.0), float([1, 4], 0.5, 2.0)],
+            |inputs| op(inputs.float(0), inputs.float(1)),
+        );
+    }
+}
+
+#[test]
+fn should_check_matmul() {
+    check_twice(
+        &[float([2, 3, 4], -1.0, 1.0), float([2, 4, 5], -1.0, 1.0)],
+        |inputs| inputs.float::<3>(0).matmul(inputs.float(1)),
+    );
+}
+
+#[test]
+fn should_check_reductions() {
+    let ops: [fn(Tensor<2>) -> Tensor<2>; 7] = [
+        |x| x.sum().unsqueeze(),
+        |x| x.mean().unsqueeze(),
+        |x| x.prod().unsqueeze(),
+        |x| x.sum_dim(1),
+        |x| x.mean_dim(0),
+        |x| x.max_dim(1),
+        |x| x.min_dim(0),
+    ];
+
+    for op in ops {
+        check_twice(&[float([3, 4], 0.5, 2.0)], |inputs| op(inputs.float(0)));
+    }
+}
+
+#[test]
+fn should_check_cumulative_ops() {
+    check_twice(&[float([3, 4], 0.5, 2.0)], |inputs| {
+        let x = inputs.float::<2>(0);
+        GradOutputs::new()
+            .with(x.clone().cumsum(1))
+            .with(x.cumprod(0))
+    });
+}
+
+#[test]
+fn should_check_shape_ops() {
+    let ops: [fn(Tensor<3>) -> Tensor<3>; 7] = [
+        |x| x.reshape([4, 3, 2]),
+        |x| x.permute([2, 0, 1]),
+        |x| x.swap_dims(0, 2),
+        |x| x.flip([0, 2]),
+        |x| x.slice([0..2, 1..3, 0..1]),
+        |x| x.repeat_dim(1, 2),
+        |x| Tensor::cat(vec![x.clone(), x.exp()], 2),
+    ];
+
+    for op in ops {
+        check_twice(&[float([2, 3, 4], -1.0, 1.0)], |inputs| op(inputs.float(0)));
+    }
+}
+
+#[test]
+fn should_check_expand() {
+    check_twice(&[float([3, 1], -1.0, 1.0)], |inputs| {
+        inputs.float::<2>(0).expand([2, 3, 4])
+    });
+}
+
+#[test]
+fn should_check_gather_and_select_with_int_indices() {
+    let inputs = [
+        float([3, 4], -1.0, 1.0),
+        GradInput::Int(TensorData::from([[0, 3, 3], [1, 0, 2], [2, 2, 2]])),
+        GradInput::Int(TensorData::from([2, 0, 2])),
+    ];
+
+    check_twice(&inputs, |inputs| {
+        let x = inputs.float::<2>(0);
+        GradOutputs::new()
+            .with(x.clone().gather(1, inputs.int(1)))
+            .with(x.select(0, inputs.int(2)))
+    });
+}
+
+#[test]
+fn should_check_masks_with_bool_inputs() {
+    let inputs = [
+        float([2, 3], -1.0, 1.0),
+        float([2, 3], 1.0, 2.0),
+        GradInput::Bool(TensorData::from([
+            [true, false, true],
+            [false, false, true],
+        ])),
+    ];
+
+    check_twice(&inputs, |inputs| {
+        let (lhs, rhs) = (inputs.float::<2>(0), inputs.float::<2>(1));
+        GradOutputs::new()
+            .with(lhs.clone().mask_where(inputs.bool(2), rhs))
+            .with(lhs.mask_fill(inputs.bool(2), 3.0))
+    });
+}
+
+#[test]
+fn should_check_multiple_outputs() {
+    check_twice(&[float([3, 4], -1.0, 1.0)], |inputs| {
+        let x = inputs.float::<2>(0);
+        let (sorted, _indices) = x.clone().sort_with_indices(1);
+        let (max, _indices) = x.clone().max_dim_with_indices(0);
+
+        x.split(2, 1).into_iter().fold(
+            GradOutputs::new().with(sorted).with(max),
+            |outputs, part| outputs.with(part),
+        )
+    });
+}
+
+#[test]
+fn should_check_softmax() {
+    check_twice(&[float([3, 4], -2.0, 2.0)], |inputs| {
+        let x = inputs.float::<2>(0);
+        GradOutputs::new()
+            .with(activation::softmax(x.clone(), 1))
+            .with(activation::log_softmax(x, 0))
+    });
+}
+
+#[test]
+fn should_check_conv2d() {
+    let inputs = [
+        float([2, 2, 4, 4], -1.0, 1.0),
+        float([3, 2, 3, 3], -1.0, 1.0),
+        float([3], -1.0, 1.0),
+    ];
+
+    check_twice(&inputs, |inputs| {
+        conv2d(
+            inputs.float::<4>(0),
+            inputs.float(1),
+            Some(inputs.float(2)),
+            ConvOptions::new([1, 2], [1, 1], [1, 1], 1),
+        )
+    });
+}
+
+#[test]
+fn should_check_pooling() {
+    check_twice(&[float([1, 2, 4, 4], -1.0, 1.0)], |inputs| {
+        let x = inputs.float::<4>(0);
+        GradOutputs::new()
+            .with(avg_pool2d(x.clone(), [2, 2], [1, 1], [1, 1], true, false))
+            .with(max_pool2d(x, [2, 2], [2, 2], [0, 0], [1, 1], false))
+    });
+}
+
+#[test]
+fn should_check_composition() {
+    let inputs = [
+        float([4, 3], -1.0, 1.0),
+        float([3, 5], -1.0, 1.0),
+        float([5], -0.5, 0.5),
+    ];
+
+    check_twice(&inputs, |inputs| {
+        let hidden =
+            inputs.float::<2>(0).matmul(inputs.float(1)) + inputs.float::<1>(2).unsqueeze();
+        activation::softmax(hidden.tanh(), 1)
+    });
+}
+
+#[test]
+fn should_report_the_worst_elements_of_a_wrong_gradient() {
+    // The detached factor hides half of the gradient of `x * x`.
+    let result = gradcheck(
+        &AutodiffDevice::new(),
+        &[float([2, 3], 1.0, 2.0)],
+        |inputs| {
+            let x = inputs.float::<2>(0);
+            x.clone() * x.detach()
+        },
+    );
+
+    let report = match result {
+        Err(GradCheckError::Mismatch(report)) => report,
+        Err(GradCheckError::UnsupportedDevice) => return,
+        other => panic!("Should detect the wrong gradient, got {other:?}"),
+    };
+
+    assert_eq!(report.num_checked, 6);
+    assert_eq!(report.num_mismatched, 6);
+    assert!(
+        report
+            .worst
+            .windows(2)
+            .all(|pair| pair[0].error() >= pair[1].error())
+    );
+    for mismatch in report.worst.iter() {
+        assert!((mismatch.numerical - 2.0 * mismatch.analytic).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn should_detect_a_wrong_second_order_derivative() {
+    // Detaching one factor of `x * sin(x)` at a time keeps its gradient exact, but the gradient
+    // no longer depends on the detached factors.
+    let func = |inputs: &GradInputs| {
+        let x = inputs.float::<2>(0);
+        x.clone() * x.clone().sin().detach() + x.clone().detach() * x.sin()
+    };
+    let inputs = [float([2, 3], -1.0, 1.0)];
+    check(&inputs, func);
+
+    match gradgradcheck(&AutodiffDevice::new(), &inputs, func) {
+        Err(GradCheckError::Mismatch(report)) => assert_eq!(report.num_mismatched, 6),
+        Err(GradCheckError::UnsupportedDevice) => {}
+        other => panic!("Should detect the wrong second-order derivative, got {other:?}"),
+    }
+}
@@ -9,6 +9,7 @@ mod add;
 mod aggregation;
 #[cfg(feature = "distributed")]
 mod all_reduce;
+mod attention;
 mod avgpool1d;
 mod avgpool2d;
 mod backward;
@@ -39,9 +40,11 @@ mod exp;
 mod expand;
 mod flip;
 mod floor;
+mod functional;
 mod gather_scatter;
 mod gather_scatter_nd;
 mod gelu;
+mod gradcheck;
 mod gradients;
 mod hypot;
 mod linear;
@@ -72,6 +75,7 @@ mod reshape;
 mod rfft;
 mod round;
 mod select;
+mod selective_scan;
 mod sigmoid;
 mod sign;
 mod slice;
@@ -0,0 +1,61 @@
+use super::*;
+use burn_tensor::{TensorData, Tolerance, module::selective_scan};
+
+#[test]
+fn test_selective_scan_grad() {
+    let device = AutodiffDevice::new();
+    let x = TestTensor::<3>::from_data([[[0.5, -1.0], [1.0, 0.25], [-0.5, 2.0]]], &device)
+        .require_grad();
+    let delta =
+        TestTensor::<3>::from_data([[[0.1, 0.2], [0.3, 0.4], [0.5, 0.6]]], &device).require_grad();
+    let a = TestTensor::<2>::from_data([[-1.0, -0.5], [-2.0, -0.25]], &device).require_grad();
+    let b = TestTensor::<3>::from_data([[[1.0, 0.5], [-0.5, 1.0], [0.25, -1.0]]], &device)
+        .require_grad();
+    let c = TestTensor::<3>::from_data([[[0.5, 1.0], [1.0, -0.5], [-1.0, 0.5]]], &device)
+        .require_grad();
+
+    let output = selective_scan(x.clone(), delta.clone(), a.clone(), b.clone(), c.clone());
+    let grads = output.sum().backward();
+
+    let tolerance = Tolerance::rel_abs(1e-3, 1e-3).set_half_precision_absolute(1e-2);
+    x.grad(&grads)
+        .unwrap()
+        .into_data()
+        .assert_approx_eq::<FloatElem>(
+            &TensorData::from([[[0.12439, 0.2565], [-0.0922, -0.16762], [-0.375, -0.45]]]),
+            tolerance,
+        );
+    delta
+        .grad(&grads)
+        .unwrap()
+        .into_data()
+        .assert_approx_eq::<FloatElem>(
+            &TensorData::from([[
+                [0.62195, -1.28248],
+                [-0.32072, 0.01926],
+                [0.24389, -1.58528],
+            ]]),
+            tolerance,
+        );
+    a.grad(&grads)
+        .unwrap()
+        .into_data()
+        .assert_approx_eq::<FloatElem>(
+            &TensorData::from([[0.03863, 0.06189], [0.00016, 0.00498]]),
+            tolerance,
+        );
+    b.grad(&grads)
+        .unwrap()
+        .into_data()
+        .assert_approx_eq::<FloatElem>(
+            &TensorData::from([[[-0.12322, -0.14216], [0.18792, -0.04014], [-0.95, 0.475]]]),
+            tolerance,
+        );
+    c.grad(&grads)
+        .unwrap()
+        .into_data()
+        .assert_approx_eq::<FloatElem>(
+            &TensorData::from([[[-0.15, -0.075], [-0.25282, 0.33103], [0.12686, -0.69141]]]),
+            tolerance,
+        );
+}
@@ -0,0 +1,208 @@
+//! Differential tests across backends.
+//!
+//! Random chains of operations from the [`OperationIr`](burn_ir::OperationIr) catalogue, over
+//! random shapes, dtypes and layouts (permuted, flipped and strided views), are executed on
+//! burn-flex, burn-ndarray and the CubeCL CPU runtime with and without fusion. Every result is
+//! compared against the first backend supporting the dtype, burn-flex when it does, and a failing
+//! case is shrunk to a minimal reproducer.
+//!
+//! The number of cases and the seed can be set with the `BURN_CONFORMANCE_CASES` and
+//! `BURN_CONFORMANCE_SEED` environment variables, e.g. to replay a reported failure.
+
+#![cfg(feature = "conformance")]
+
+mod case;
+mod generate;
+mod shrink;
+mod target;
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use burn_backend::{DType, Element, TensorData, Tolerance, bf16, f16};
+use burn_ir::{FloatOperationIr, OperationIr};
+use num_traits::Float;
+use serial_test::serial;
+
+use case::{Case, Step, UnaryOp, Value};
+use generate::Generator;
+use target::{Target, targets};
+
+/// How many times a failing case is shrunk at most.
+const MAX_SHRINKS: usize = 256;
+
+/// A target whose result doesn't agree with the reference.
+#[derive(Debug)]
+struct Mismatch {
+    target: &'static str,
+    message: String,
+}
+
+/// Execute `case` on every target supporting its dtype and compare the results to the
+/// reference, which is the first target.
+fn check(case: &Case, targets: &[Target]) -> Result<(), Mismatch> {
+    let lowered = case.lower();
+    let mut targets = targets.iter().filter(|target| target.supports(case.dtype));
+    let Some(reference) = targets.next() else {
+        return Ok(());
+    };
+
+    let execute = |target: &Target| {
+        quietly(|| target.execute(&lowered)).map_err(|message| Mismatch {
+            target: target.name,
+            message: format!("Execution failed: {message}"),
+        })
+    };
+
+    let expected = execute(reference)?;
+    for target in targets {
+        let actual = execute(target)?;
+        quietly(|| assert_agree(case.dtype, &actual, &expected)).map_err(|message| Mismatch {
+            target: target.name,
+            message,
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Compare float results within a tolerance that depends on the precision of the dtype.
+fn assert_agree(dtype: DType, actual: &TensorData, expected: &TensorData) {
+    match dtype {
+        DType::F64 => actual.assert_approx_eq::<f64>(expected, tolerance()),
+        DType::F32 | DType::Flex32 => actual.assert_approx_eq::<f32>(expected, tolerance()),
+        DType::F16 => actual.assert_approx_eq::<f16>(expected, tolerance()),
+        DType::BF16 => actual.assert_approx_eq::<bf16>(expected, tolerance()),
+        _ => actual.assert_eq(expected, true),
+    }
+}
+
+fn tolerance<F: Float + Element>() -> Tolerance<F> {
+    Tolerance::rel_abs(1e-3, 1e-4)
+        .set_half_precision_relative(5e-2)
+        .set_half_precision_absolute(5e-2)
+}
+
+/// Run `func`, returning the panic message instead of printing it if it panics.
+fn quietly<T>(func: impl FnOnce() -> T) -> Result<T, String> {
+    catch_unwind(AssertUnwindSafe(func)).map_err(|payload| {
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|msg| msg.to_string()))
+            .unwrap_or_else(|| "unknown panic".to_string())
+    })
+}
+
+/// Shrink a failing case, keeping the variants that still fail on the same target.
+fn shrink(mut case: Case, mut mismatch: Mismatch, targets: &[Target]) -> (Case, Mismatch) {
+    for _ in 0..MAX_SHRINKS {
+        let smaller = shrink::candidates(&case).into_iter().find_map(|candidate| {
+            match check(&candidate, targets) {
+                Err(found) if found.target == mismatch.target => Some((candidate, found)),
+                _ => None,
+            }
+        });
+
+        match smaller {
+            Some((smaller, found)) => (case, mismatch) = (smaller, found),
+            None => break,
+        }
+    }
+
+    (case, mismatch)
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Run `func` without printing panics: the expected ones are reported in the reproducer.
+fn silenced<T>(func: impl FnOnce() -> T) -> T {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let output = func();
+    std::panic::set_hook(hook);
+    output
+}
+
+/// Check random cases of the given dtypes, panicking with a shrunk reproducer on the first
+/// mismatch.
+fn run(dtypes: Vec<DType>, targets: &[Target]) {
+    let seed = env_or("BURN_CONFORMANCE_SEED", 0);
+    let num_cases = env_or("BURN_CONFORMANCE_CASES", 200);
+    let mut generator = Generator::new(seed, dtypes);
+
+    let failure = silenced(|| {
+        (0..num_cases).find_map(|index| {
+            let case = generator.case();
+            check(&case, targets)
+                .err()
+                .map(|mismatch| (index, shrink(case, mismatch, targets)))
+        })
+    });
+
+    if let Some((index, (case, mismatch))) = failure {
+        panic!(
+            "Case {index} (BURN_CONFORMANCE_SEED={seed}) disagrees on {}.\n\n\
+             Minimal reproducer:\n{case}\n{}",
+            mismatch.target, mismatch.message
+        );
+    }
+}
+
+#[test]
+#[serial]
+fn full_precision_chains_agree_across_backends() {
+    run(vec![DType::F32, DType::F64], &targets());
+}
+
+#[test]
+#[serial]
+fn half_precision_chains_agree_across_backends() {
+    run(vec![DType::F16, DType::BF16], &targets());
+}
+
+#[test]
+#[serial]
+fn mismatch_is_shrunk_to_the_faulty_op() {
+    // Compare burn-flex with a CubeCL CPU target that is off by one whenever `exp` runs.
+    let mut targets = targets();
+    let cpu = targets.remove(2);
+    targets.truncate(1);
+    targets.push(cpu.map("faulty-cpu", |lowered, data| {
+        let runs_exp = lowered
+            .operations
+            .iter()
+            .any(|operation| matches!(operation, OperationIr::Float(_, FloatOperationIr::Exp(_))));
+        if !runs_exp {
+            return data;
+        }
+        let values = data
+            .iter::<f32>()
+            .map(|value| value + 1.0)
+            .collect::<Vec<_>>();
+        TensorData::new(values, data.shape.clone())
+    }));
+
+    let is_exp = |step: &Step| matches!(step, Step::Unary(UnaryOp::Exp, _));
+    let mut generator = Generator::new(0, vec![DType::F32]);
+    let case = (0..1000)
+        .map(|_| generator.case())
+        .find(|case| case.steps.len() > 2 && case.steps.iter().any(is_exp))
+        .expect("Should generate a case running `exp` among other steps");
+
+    let (shrunk, mismatch) = silenced(|| {
+        let mismatch = check(&case, &targets).expect_err("Should detect the fault");
+        shrink(case, mismatch, &targets)
+    });
+
+    assert_eq!(mismatch.target, "faulty-cpu");
+    assert_eq!(
+        shrunk.steps,
+        vec![Step::Unary(UnaryOp::Exp, Value::Input(0))]
+    );
+    assert_eq!(shrunk.inputs.len(), 1);
+}
@@ -0,0 +1,441 @@
+use core::fmt;
+
+use burn_backend::{DType, Shape, Slice, TensorData, calculate_matmul_output};
+use burn_ir::{
+    ActivationOperationIr, BaseOperationIr, BinaryOpIr, DimOpIr, FlipOpIr, FloatOperationIr,
+    MatmulOpIr, NumericOperationIr, OperationIr, PermuteOpIr, ReduceDimOpIr, ScalarIr, ScalarOpIr,
+    ShapeOpIr, SliceOpIr, TensorId, TensorIr, TensorStatus, UnaryOpIr,
+};
+
+/// A value of a [case](Case): one of its inputs, or the output of one of its steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Value {
+    Input(usize),
+    Step(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Abs,
+    Exp,
+    Tanh,
+    Sin,
+    Cos,
+    Erf,
+    Sigmoid,
+    Relu,
+    Gelu,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScalarOp {
+    Add,
+    Mul,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReduceOp {
+    Sum,
+    Mean,
+    Max,
+}
+
+/// A single operation of a [case](Case), each lowered to one [operation](OperationIr).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    Unary(UnaryOp, Value),
+    Binary(BinaryOp, Value, Value),
+    Scalar(ScalarOp, Value, f64),
+    Matmul(Value, Value),
+    Permute(Value, Vec<usize>),
+    Flip(Value, Vec<usize>),
+    Slice(Value, Vec<Slice>),
+    Reshape(Value, Vec<usize>),
+    /// Reduce along an axis, keeping it with a size of one.
+    Reduce(ReduceOp, Value, usize),
+    Softmax(Value, usize),
+}
+
+impl Step {
+    /// The values read by the step.
+    pub fn operands(&self) -> Vec<Value> {
+        match self {
+            Step::Binary(_, lhs, rhs) | Step::Matmul(lhs, rhs) => vec![*lhs, *rhs],
+            Step::Unary(_, input)
+            | Step::Scalar(_, input, _)
+            | Step::Permute(input, _)
+            | Step::Flip(input, _)
+            | Step::Slice(input, _)
+            | Step::Reshape(input, _)
+            | Step::Reduce(_, input, _)
+            | Step::Softmax(input, _) => vec![*input],
+        }
+    }
+
+    /// Replace every operand with the value returned by `map`.
+    pub fn map_operands(&mut self, map: impl Fn(Value) -> Value) {
+        match self {
+            Step::Binary(_, lhs, rhs) | Step::Matmul(lhs, rhs) => {
+                *lhs = map(*lhs);
+                *rhs = map(*rhs);
+            }
+            Step::Unary(_, input)
+            | Step::Scalar(_, input, _)
+            | Step::Permute(input, _)
+            | Step::Flip(input, _)
+            | Step::Slice(input, _)
+            | Step::Reshape(input, _)
+            | Step::Reduce(_, input, _)
+            | Step::Softmax(input, _) => *input = map(*input),
+        }
+    }
+
+    /// The shape of the step's output, or `None` if the step doesn't apply to its operands.
+    pub fn shape(&self, shape_of: impl Fn(Value) -> Shape) -> Option<Shape> {
+        let shape = match self {
+            Step::Unary(_, input) | Step::Scalar(_, input, _) => shape_of(*input),
+            Step::Binary(_, lhs, rhs) => shape_of(*lhs).broadcast(&shape_of(*rhs)).ok()?,
+            Step::Matmul(lhs, rhs) => {
+                let (lhs, rhs) = (shape_of(*lhs), shape_of(*rhs));
+                if lhs.rank() < 2 || lhs.rank() != rhs.rank() {
+                    return None;
+                }
+                calculate_matmul_output(&lhs, &rhs).ok()?
+            }
+            Step::Permute(input, axes) => shape_of(*input).permuted(axes).ok()?,
+            Step::Flip(input, axes) => {
+                let shape = shape_of(*input);
+                if axes.iter().any(|axis| *axis >= shape.rank()) {
+                    return None;
+                }
+                shape
+            }
+            Step::Slice(input, ranges) => shape_of(*input).slice(ranges).ok()?,
+            Step::Reshape(input, dims) => {
+                shape_of(*input).reshape(Shape::from(dims.clone())).ok()?
+            }
+            Step::Reduce(_, input, axis) => {
+                let mut shape = shape_of(*input);
+                if *axis >= shape.rank() {
+                    return None;
+                }
+                shape[*axis] = 1;
+                shape
+            }
+            Step::Softmax(input, axis) => {
+                let shape = shape_of(*input);
+                if *axis >= shape.rank() {
+                    return None;
+                }
+                shape
+            }
+        };
+
+        // Empty tensors are out of scope: most ops are no-ops on them.
+        shape.as_slice().iter().all(|dim| *dim > 0).then_some(shape)
+    }
+}
+
+/// A program of [steps](Step) applied to random inputs, whose output is the last step.
+#[derive(Clone, Debug)]
+pub struct Case {
+    /// The float type of every value.
+    pub dtype: DType,
+    pub inputs: Vec<TensorData>,
+    pub steps: Vec<Step>,
+}
+
+/// A [case](Case) lowered to the operation IR, ready to be executed by an interpreter.
+pub struct Lowered {
+    pub inputs: Vec<(TensorId, TensorData)>,
+    pub operations: Vec<OperationIr>,
+    pub output: TensorIr,
+}
+
+impl Case {
+    /// The shape of every step output, or `None` if a step doesn't apply to its operands.
+    pub fn shapes(&self) -> Option<Vec<Shape>> {
+        let mut shapes: Vec<Shape> = Vec::with_capacity(self.steps.len());
+
+        for (index, step) in self.steps.iter().enumerate() {
+            if step
+                .operands()
+                .iter()
+                .any(|value| !self.precedes(*value, index))
+            {
+                return None;
+            }
+
+            let shape = step.shape(|value| match value {
+                Value::Input(input) => self.inputs[input].shape.clone(),
+                Value::Step(step) => shapes[step].clone(),
+            })?;
+            shapes.push(shape);
+        }
+
+        Some(shapes)
+    }
+
+    /// Whether every step applies to its operands.
+    pub fn is_valid(&self) -> bool {
+        !self.steps.is_empty() && self.shapes().is_some()
+    }
+
+    fn precedes(&self, value: Value, step: usize) -> bool {
+        match value {
+            Value::Input(input) => input < self.inputs.len(),
+            Value::Step(other) => other < step,
+        }
+    }
+
+    /// Remove the inputs and steps the output doesn't depend on.
+    pub fn prune(mut self) -> Self {
+        let mut used_inputs = vec![false; self.inputs.len()];
+        let mut used_steps = vec![false; self.steps.len()];
+        if let Some(last) = used_steps.last_mut() {
+            *last = true;
+        }
+
+        for index in (0..self.steps.len()).rev() {
+            if !used_steps[index] {
+                continue;
+            }
+            for value in self.steps[index].operands() {
+                match value {
+                    Value::Input(input) => used_inputs[input] = true,
+                    Value::Step(step) => used_steps[step] = true,
+                }
+            }
+        }
+
+        let input_map = remap(&used_inputs);
+        let step_map = remap(&used_steps);
+
+        self.inputs = keep(self.inputs, &used_inputs);
+        self.steps = keep(self.steps, &used_steps);
+        for step in self.steps.iter_mut() {
+            step.map_operands(|value| match value {
+                Value::Input(input) => Value::Input(input_map[input]),
+                Value::Step(step) => Value::Step(step_map[step]),
+            });
+        }
+
+        self
+    }
+
+    /// Lower the case to the operation IR.
+    ///
+    /// Each value is marked [read-write](TensorStatus::ReadWrite) on its last use, so backends
+    /// are free to reuse its buffer in-place, and read-only before.
+    pub fn lower(&self) -> Lowered {
+        let shapes = self.shapes().expect("Case should be valid");
+        let num_inputs = self.inputs.len();
+        let id_of = |value: Value| match value {
+            Value::Input(input) => TensorId::new(input as u64),
+            Value::Step(step) => TensorId::new((num_inputs + step) as u64),
+        };
+        let tensor_of = |value: Value, status: TensorStatus| TensorIr {
+            id: id_of(value),
+            shape: match value {
+                Value::Input(input) => self.inputs[input].shape.clone(),
+                Value::Step(step) => shapes[step].clone(),
+            },
+            status,
+            dtype: self.dtype,
+        };
+
+        let mut last_uses = Vec::new();
+        for (index, step) in self.steps.iter().enumerate() {
+            for value in step.operands() {
+                match last_uses.iter_mut().find(|(used, _)| *used == value) {
+                    Some((_, last)) => *last = index,
+                    None => last_uses.push((value, index)),
+                }
+            }
+        }
+
+        let operations = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                let operands = step.operands();
+                let read = |value: Value| {
+                    let last_use = last_uses
+                        .iter()
+                        .any(|(used, last)| *used == value && *last == index);
+                    let repeated = operands.iter().filter(|operand| **operand == value).count() > 1;
+                    let status = if last_use && !repeated {
+                        TensorStatus::ReadWrite
+                    } else {
+                        TensorStatus::ReadOnly
+                    };
+                    tensor_of(value, status)
+                };
+                let out = || id_of(Value::Step(index));
+
+                lower_step(step, self.dtype, read, out)
+            })
+            .collect();
+
+        let inputs = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(input, data)| (id_of(Value::Input(input)), data.clone()))
+            .collect();
+
+        Lowered {
+            inputs,
+            operations,
+            output: tensor_of(Value::Step(self.steps.len() - 1), TensorStatus::ReadWrite),
+        }
+    }
+}
+
+fn lower_step(
+    step: &Step,
+    dtype: DType,
+    read: impl Fn(Value) -> TensorIr,
+    out: impl Fn() -> TensorId,
+) -> OperationIr {
+    match step {
+        Step::Unary(op, input) => {
+            let desc = UnaryOpIr::create(read(*input), out);
+            match op {
+                UnaryOp::Abs => OperationIr::NumericFloat(dtype, NumericOperationIr::Abs(desc)),
+                UnaryOp::Exp => OperationIr::Float(dtype, FloatOperationIr::Exp(desc)),
+                UnaryOp::Tanh => OperationIr::Float(dtype, FloatOperationIr::Tanh(desc)),
+                UnaryOp::Sin => OperationIr::Float(dtype, FloatOperationIr::Sin(desc)),
+                UnaryOp::Cos => OperationIr::Float(dtype, FloatOperationIr::Cos(desc)),
+                UnaryOp::Erf => OperationIr::Float(dtype, FloatOperationIr::Erf(desc)),
+                UnaryOp::Sigmoid => OperationIr::Activation(ActivationOperationIr::Sigmoid(desc)),
+                UnaryOp::Relu => OperationIr::Activation(ActivationOperationIr::Relu(desc)),
+                UnaryOp::Gelu => OperationIr::Activation(ActivationOperationIr::Gelu(desc)),
+            }
+        }
+        Step::Binary(op, lhs, rhs) => {
+            let desc = BinaryOpIr::create(read(*lhs), read(*rhs), out);
+            let op = match op {
+                BinaryOp::Add => NumericOperationIr::Add(desc),
+                BinaryOp::Sub => NumericOperationIr::Sub(desc),
+                BinaryOp::Mul => NumericOperationIr::Mul(desc),
+            };
+            OperationIr::NumericFloat(dtype, op)
+        }
+        Step::Scalar(op, input, value) => {
+            let desc = ScalarOpIr::create(read(*input), ScalarIr::Float(*value), out);
+            let op = match op {
+                ScalarOp::Add => NumericOperationIr::AddScalar(desc),
+                ScalarOp::Mul => NumericOperationIr::MulScalar(desc),
+            };
+            OperationIr::NumericFloat(dtype, op)
+        }
+        Step::Matmul(lhs, rhs) => OperationIr::Float(
+            dtype,
+            FloatOperationIr::Matmul(MatmulOpIr::create(read(*lhs), read(*rhs), out)),
+        ),
+        Step::Permute(input, axes) => OperationIr::BaseFloat(BaseOperationIr::Permute(
+            PermuteOpIr::create(read(*input), axes.clone(), out),
+        )),
+        Step::Flip(input, axes) => OperationIr::BaseFloat(BaseOperationIr::Flip(FlipOpIr::create(
+            read(*input),
+            axes.clone(),
+            out,
+        ))),
+        Step::Slice(input, ranges) => OperationIr::BaseFloat(BaseOperationIr::Slice(
+            SliceOpIr::create(read(*input), ranges.clone(), out),
+        )),
+        Step::Reshape(input, dims) => OperationIr::BaseFloat(BaseOperationIr::Reshape(
+            ShapeOpIr::reshape(read(*input), Shape::from(dims.clone()), out),
+        )),
+        Step::Reduce(op, input, axis) => {
+            let desc = ReduceDimOpIr::create(read(*input), *axis, 1, out);
+            let op = match op {
+                ReduceOp::Sum => NumericOperationIr::SumDim(desc),
+                ReduceOp::Mean => NumericOperationIr::MeanDim(desc),
+                ReduceOp::Max => NumericOperationIr::MaxDim(desc),
+            };
+            OperationIr::NumericFloat(dtype, op)
+        }
+        Step::Softmax(input, axis) => OperationIr::Activation(ActivationOperationIr::Softmax(
+            DimOpIr::create(read(*input), *axis, out),
+        )),
+    }
+}
+
+fn remap(used: &[bool]) -> Vec<usize> {
+    let mut next = 0;
+    used.iter()
+        .map(|used| {
+            let index = next;
+            next += *used as usize;
+            index
+        })
+        .collect()
+}
+
+fn keep<T>(items: Vec<T>, used: &[bool]) -> Vec<T> {
+    items
+        .into_iter()
+        .zip(used)
+        .filter_map(|(item, used)| used.then_some(item))
+        .collect()
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Input(input) => write!(f, "%in{input}"),
+            Value::Step(step) => write!(f, "%{step}"),
+        }
+    }
+}
+
+/// Prints the case as a standalone program, e.g.:
+///
+/// ```text
+/// dtype = F16
+/// %in0: [2, 3] = [0.25, -0.5, ...]
+/// %0 = Exp(%in0)
+/// %1 = Permute(%0, [1, 0])
+/// ```
+impl fmt::Display for Case {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "dtype = {:?}", self.dtype)?;
+        for (index, data) in self.inputs.iter().enumerate() {
+            let values = data.iter::<f64>().collect::<Vec<_>>();
+            writeln!(
+                f,
+                "{}: {:?} = {values:?}",
+                Value::Input(index),
+                data.shape.as_slice()
+            )?;
+        }
+
+        for (index, step) in self.steps.iter().enumerate() {
+            write!(f, "{} = ", Value::Step(index))?;
+            match step {
+                Step::Unary(op, input) => writeln!(f, "{op:?}({input})")?,
+                Step::Binary(op, lhs, rhs) => writeln!(f, "{op:?}({lhs}, {rhs})")?,
+                Step::Scalar(op, input, value) => writeln!(f, "{op:?}Scalar({input}, {value})")?,
+                Step::Matmul(lhs, rhs) => writeln!(f, "Matmul({lhs}, {rhs})")?,
+                Step::Permute(input, axes) => writeln!(f, "Permute({input}, {axes:?})")?,
+                Step::Flip(input, axes) => writeln!(f, "Flip({input}, {axes:?})")?,
+                Step::Slice(input, ranges) => writeln!(f, "Slice({input}, {ranges:?})")?,
+                Step::Reshape(input, dims) => writeln!(f, "Reshape({input}, {dims:?})")?,
+                Step::Reduce(op, input, axis) => writeln!(f, "{op:?}Dim({input}, {axis})")?,
+                Step::Softmax(input, axis) => writeln!(f, "Softmax({input}, {axis})")?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use burn_backend::{DType, Distribution, Shape, Slice, TensorData};
+use rand::{RngExt, SeedableRng, rngs::StdRng};
+
+use super::case::{BinaryOp, Case, ReduceOp, ScalarOp, Step, UnaryOp, Value};
+
+const UNARY_OPS: [UnaryOp; 9] = [
+    UnaryOp::Abs,
+    UnaryOp::Exp,
+    UnaryOp::Tanh,
+    UnaryOp::Sin,
+    UnaryOp::Cos,
+    UnaryOp::Erf,
+    UnaryOp::Sigmoid,
+    UnaryOp::Relu,
+    UnaryOp::Gelu,
+];
+const BINARY_OPS: [BinaryOp; 3] = [BinaryOp::Add, BinaryOp::Sub, BinaryOp::Mul];
+const SCALAR_OPS: [ScalarOp; 2] = [ScalarOp::Add, ScalarOp::Mul];
+const REDUCE_OPS: [ReduceOp; 3] = [ReduceOp::Sum, ReduceOp::Mean, ReduceOp::Max];
+
+/// How many times a random step is drawn before falling back to a unary op.
+const MAX_ATTEMPTS: usize = 16;
+
+/// Generates random [cases](Case): a chain of steps, each reading the previous one, with
+/// random shapes and layouts.
+pub struct Generator {
+    rng: StdRng,
+    dtypes: Vec<DType>,
+    max_rank: usize,
+    max_dim: usize,
+    max_steps: usize,
+}
+
+impl Generator {
+    pub fn new(seed: u64, dtypes: Vec<DType>) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            dtypes,
+            max_rank: 4,
+            max_dim: 5,
+            max_steps: 6,
+        }
+    }
+
+    pub fn case(&mut self) -> Case {
+        let dtype = self.dtypes[self.rng.random_range(0..self.dtypes.len())];
+        let rank = self.rng.random_range(1..=self.max_rank);
+        let dims = (0..rank)
+            .map(|_| self.rng.random_range(1..=self.max_dim))
+            .collect::<Vec<_>>();
+
+        let mut case = Case {
+            dtype,
+            inputs: vec![self.input(dims, dtype)],
+            steps: Vec::new(),
+        };
+        let mut shapes = Vec::new();
+        let num_steps = self.rng.random_range(1..=self.max_steps);
+
+        for _ in 0..num_steps {
+            let current = match case.steps.len() {
+                0 => Value::Input(0),
+                len => Value::Step(len - 1),
+            };
+            let shape = shape_of(&case, &shapes, None)(current);
+
+            let (step, input) = (0..MAX_ATTEMPTS)
+                .map(|_| self.step(current, &shape, &case, &shapes))
+                .find(|(step, input)| {
+                    step.shape(shape_of(&case, &shapes, input.as_ref()))
+                        .is_some()
+                })
+                .unwrap_or_else(|| (Step::Unary(UnaryOp::Tanh, current), None));
+
+            let shape = step
+                .shape(shape_of(&case, &shapes, input.as_ref()))
+                .unwrap();
+            case.inputs.extend(input);
+            shapes.push(shape);
+            case.steps.push(step);
+        }
+
+        case
+    }
+
+    /// Draw a step reading `current`, along with the new input it reads, if any.
+    fn step(
+        &mut self,
+        current: Value,
+        shape: &Shape,
+        case: &Case,
+        shapes: &[Shape],
+    ) -> (Step, Option<TensorData>) {
+        let dims = shape.as_slice().to_vec();
+        let rank = dims.len();
+        let new_input = Value::Input(case.inputs.len());
+
+        let step = match self.rng.random_range(0..10) {
+            0 | 1 => Step::Unary(self.pick(&UNARY_OPS), current),
+            2 => {
+                let value = (self.rng.random_range(-2.0..2.0) * 100.0f64).round() / 100.0;
+                Step::Scalar(self.pick(&SCALAR_OPS), current, value)
+            }
+            3 => {
+                let op = self.pick(&BINARY_OPS);
+                // Reuse an earlier value of the same shape, or broadcast a new input.
+                let earlier = shapes
+                    .iter()
+                    .position(|other| other == shape)
+                    .map(Value::Step)
+                    .filter(|value| *value != current);
+
+                match earlier {
+                    Some(value) if self.rng.random_bool(0.5) => Step::Binary(op, current, value),
+                    _ => {
+                        let dims = dims
+                            .iter()
+                            .map(|dim| if self.rng.random_bool(0.3) { 1 } else { *dim })
+                            .collect::<Vec<_>>();
+                        let input = self.input(dims, case.dtype);
+                        let step = if self.rng.random_bool(0.5) {
+                            Step::Binary(op, current, new_input)
+                        } else {
+                            Step::Binary(op, new_input, current)
+                        };
+                        return (step, Some(input));
+                    }
+                }
+            }
+            4 => {
+                let mut dims = dims;
+                if rank >= 2 {
+                    dims[rank - 2] = dims[rank - 1];
+                    dims[rank - 1] = self.rng.random_range(1..=self.max_dim);
+                }
+                let input = self.input(dims, case.dtype);
+                return (Step::Matmul(current, new_input), Some(input));
+            }
+            5 => {
+                let mut axes = (0..rank).collect::<Vec<_>>();
+                for i in (1..rank).rev() {
+                    axes.swap(i, self.rng.random_range(0..=i));
+                }
+                Step::Permute(current, axes)
+            }
+            6 => {
+                let axes = (0..rank)
+                    .filter(|_| self.rng.random_bool(0.5))
+                    .collect::<Vec<_>>();
+                if axes.is_empty() {
+                    Step::Flip(current, vec![rank - 1])
+                } else {
+                    Step::Flip(current, axes)
+                }
+            }
+            7 => {
+                let ranges = dims
+                    .iter()
+                    .map(|dim| {
+                        let start = self.rng.random_range(0..*dim);
+                        let end = self.rng.random_range(start + 1..=*dim);
+                        let step = self.rng.random_range(1..=2);
+                        Slice::new(start as isize, Some(end as isize), step)
+                    })
+                    .collect();
+                Step::Slice(current, ranges)
+            }
+            8 => {
+                let axis = self.rng.random_range(0..rank);
+                let mut dims = dims;
+                match self.rng.random_range(0..3) {
+                    // Flatten.
+                    0 => dims = vec![dims.iter().product()],
+                    // Merge two adjacent axes.
+                    1 if axis + 1 < rank => {
+                        let merged = dims.remove(axis + 1);
+                        dims[axis] *= merged;
+                    }
+                    // Insert a unit axis.
+                    _ => dims.insert(axis, 1),
+                }
+                Step::Reshape(current, dims)
+            }
+            _ => {
+                let axis = self.rng.random_range(0..rank);
+                if self.rng.random_bool(0.75) {
+                    Step::Reduce(self.pick(&REDUCE_OPS), current, axis)
+                } else {
+                    Step::Softmax(current, axis)
+                }
+            }
+        };
+
+        (step, None)
+    }
+
+    fn input(&mut self, dims: Vec<usize>, dtype: DType) -> TensorData {
+        TensorData::random::<f64, _, _>(dims, Distribution::Uniform(-1.0, 1.0), &mut self.rng)
+            .convert_dtype(dtype)
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.rng.random_range(0..items.len())]
+    }
+}
+
+/// Look up the shape of a value of `case`, where `input` is a new input not pushed yet.
+fn shape_of<'a>(
+    case: &'a Case,
+    shapes: &'a [Shape],
+    input: Option<&'a TensorData>,
+) -> impl Fn(Value) -> Shape + 'a {
+    move |value| match value {
+        Value::Input(index) => case.inputs.get(index).or(input).unwrap().shape.clone(),
+        Value::Step(step) => shapes[step].clone(),
+    }
+}
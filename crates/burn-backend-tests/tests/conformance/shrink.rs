@@ -0,0 +1,85 @@
+use burn_backend::TensorData;
+
+use super::case::{Case, Value};
+
+/// The smaller variants of a failing case, from the most to the least aggressive reduction.
+///
+/// Every variant is strictly smaller than `case`: it has fewer steps, fewer inputs, fewer
+/// elements or simpler input values, so repeatedly shrinking always terminates.
+pub fn candidates(case: &Case) -> Vec<Case> {
+    let mut candidates = Vec::new();
+    let num_steps = case.steps.len();
+
+    // Make an earlier step the output.
+    for last in 0..num_steps.saturating_sub(1) {
+        let mut candidate = case.clone();
+        candidate.steps.truncate(last + 1);
+        candidates.push(candidate.prune());
+    }
+
+    // Skip a step, reading one of its operands instead.
+    for index in 0..num_steps.saturating_sub(1) {
+        for operand in case.steps[index].operands() {
+            let mut candidate = case.clone();
+            candidate.steps.remove(index);
+            for step in candidate.steps.iter_mut().skip(index) {
+                step.map_operands(|value| match value {
+                    Value::Step(step) if step == index => operand,
+                    Value::Step(step) if step > index => Value::Step(step - 1),
+                    value => value,
+                });
+            }
+            candidates.push(candidate.prune());
+        }
+    }
+
+    // Halve a dimension of an input.
+    for (input, data) in case.inputs.iter().enumerate() {
+        for (dim, size) in data.shape.as_slice().iter().enumerate() {
+            if *size > 1 {
+                let mut candidate = case.clone();
+                candidate.inputs[input] = narrow(data, dim, size / 2);
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    // Round the values of an input.
+    for (input, data) in case.inputs.iter().enumerate() {
+        let rounded = round(data);
+        if rounded != *data {
+            let mut candidate = case.clone();
+            candidate.inputs[input] = rounded;
+            candidates.push(candidate);
+        }
+    }
+
+    candidates.retain(Case::is_valid);
+    candidates
+}
+
+/// Keep the first `len` elements of `data` along `dim`.
+fn narrow(data: &TensorData, dim: usize, len: usize) -> TensorData {
+    let mut dims = data.shape.as_slice().to_vec();
+    let stride = dims[dim + 1..].iter().product::<usize>();
+    let size = dims[dim];
+    let values = data
+        .iter::<f64>()
+        .enumerate()
+        .filter(|(index, _)| (index / stride) % size < len)
+        .map(|(_, value)| value)
+        .collect::<Vec<_>>();
+    dims[dim] = len;
+
+    TensorData::new(values, dims).convert_dtype(data.dtype)
+}
+
+/// Round the values of `data` to a single decimal.
+fn round(data: &TensorData) -> TensorData {
+    let values = data
+        .iter::<f64>()
+        .map(|value| (value * 10.0).round() / 10.0)
+        .collect::<Vec<_>>();
+
+    TensorData::new(values, data.shape.clone()).convert_dtype(data.dtype)
+}
@@ -0,0 +1,79 @@
+use burn_backend::{DType, TensorData};
+use burn_cubecl::CubeBackend;
+use burn_cubecl::cubecl::cpu::{CpuDevice, CpuRuntime};
+use burn_flex::{Flex, FlexDevice};
+use burn_fusion::Fusion;
+use burn_ir::BackendIr;
+use burn_ndarray::{NdArray, NdArrayDevice};
+use burn_router::TensorInterpreter;
+use burn_std::future;
+
+use super::case::Lowered;
+
+/// A backend the conformance cases are executed on.
+pub struct Target {
+    pub name: &'static str,
+    supports: Box<dyn Fn(DType) -> bool>,
+    execute: Box<dyn Fn(&Lowered) -> TensorData>,
+}
+
+impl Target {
+    /// Execute the cases with a [tensor interpreter](TensorInterpreter) for the backend `B`.
+    pub fn new<B: BackendIr>(name: &'static str, device: B::Device) -> Self {
+        let supported = device.clone();
+
+        Self {
+            name,
+            supports: Box::new(move |dtype| B::supports_dtype(&supported, dtype)),
+            execute: Box::new(move |case| execute::<B>(case, device.clone())),
+        }
+    }
+
+    /// Post-process the results of the target, e.g. to inject a fault in the harness tests.
+    pub fn map(
+        self,
+        name: &'static str,
+        map: impl Fn(&Lowered, TensorData) -> TensorData + 'static,
+    ) -> Self {
+        let execute = self.execute;
+
+        Self {
+            name,
+            supports: self.supports,
+            execute: Box::new(move |case| map(case, execute(case))),
+        }
+    }
+
+    pub fn supports(&self, dtype: DType) -> bool {
+        (self.supports)(dtype)
+    }
+
+    pub fn execute(&self, case: &Lowered) -> TensorData {
+        (self.execute)(case)
+    }
+}
+
+fn execute<B: BackendIr>(case: &Lowered, device: B::Device) -> TensorData {
+    let mut interpreter = TensorInterpreter::<B>::new(device);
+
+    for (id, data) in case.inputs.iter() {
+        interpreter.register_tensor_data_id(*id, data.clone());
+    }
+    for operation in case.operations.iter() {
+        interpreter.register_op(operation.clone());
+    }
+
+    future::block_on(interpreter.read_tensor_async(case.output.clone()))
+        .expect("Should read the output")
+}
+
+/// The compared backends, starting with the reference every other result is checked against.
+#[allow(deprecated)] // NdArray stays compared while it is deprecated.
+pub fn targets() -> Vec<Target> {
+    vec![
+        Target::new::<Flex>("flex", FlexDevice),
+        Target::new::<NdArray>("ndarray", NdArrayDevice::Cpu),
+        Target::new::<CubeBackend<CpuRuntime>>("cpu", CpuDevice),
+        Target::new::<Fusion<CubeBackend<CpuRuntime>>>("cpu-fusion", CpuDevice),
+    ]
+}
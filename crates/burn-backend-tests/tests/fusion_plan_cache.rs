@@ -0,0 +1,81 @@
+//! Tests the on-disk fusion plan cache against the CubeCL CPU runtime.
+//!
+//! The cache directory is read once with the global Burn config, from the
+//! `BURN_FUSION_PLAN_CACHE` environment variable, so this test is isolated in its own binary
+//! where the variable can be set before the config is first loaded. Reloading the plans in a new
+//! store is checked by running this binary again in a fresh process.
+
+#![cfg(all(feature = "cpu", feature = "fusion"))]
+
+use burn_cubecl::cubecl::cpu::{CpuDevice, CpuRuntime};
+use burn_cubecl::fusion::FusionCubeRuntime;
+use burn_fusion::stream::PlanCache;
+use burn_tensor::{Device, Tensor};
+
+type Runtime = FusionCubeRuntime<CpuRuntime>;
+
+fn run_graph(device: &Device) {
+    let a = Tensor::<2>::ones([8, 8], device);
+    let b = Tensor::<2>::full([8, 8], 2.0, device);
+    let _ = ((a.clone() + b).exp().mul_scalar(2.0) - a).into_data();
+    device.sync().unwrap();
+}
+
+#[test]
+fn plans_explored_on_cpu_are_saved_and_restored() {
+    let directory =
+        std::env::temp_dir().join(format!("burn-fusion-plan-cache-{}", std::process::id()));
+    // SAFETY: no other thread reads the environment, and the config isn't loaded yet.
+    unsafe { std::env::set_var("BURN_FUSION_PLAN_CACHE", &directory) };
+
+    let device = Device::cpu();
+    run_graph(&device);
+
+    let cache = PlanCache::<Runtime>::new(&directory, &CpuDevice);
+    let num_plans = cache.len();
+    assert!(num_plans > 0, "the explored plans are saved");
+    assert_eq!(
+        cache.validate(&CpuDevice),
+        num_plans,
+        "every saved optimization is restored on the device"
+    );
+
+    // The same graph reuses the cached plans instead of exploring new ones.
+    run_graph(&device);
+    assert_eq!(cache.len(), num_plans);
+
+    // A fresh process builds its store from the file alone.
+    let status = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([
+            "cached_plans_are_hit_in_a_new_process",
+            "--exact",
+            "--ignored",
+        ])
+        .env("BURN_FUSION_PLAN_CACHE", &directory)
+        .status()
+        .unwrap();
+    assert!(
+        status.success(),
+        "the cached plans are hit in a new process"
+    );
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+#[ignore = "run in a fresh process by plans_explored_on_cpu_are_saved_and_restored"]
+fn cached_plans_are_hit_in_a_new_process() {
+    let directory = std::env::var_os("BURN_FUSION_PLAN_CACHE").expect("a cache directory is set");
+    let cache = PlanCache::<Runtime>::new(&directory, &CpuDevice);
+    let saved = std::fs::read(cache.path()).expect("the plans were saved");
+
+    let device = Device::cpu();
+    run_graph(&device);
+
+    // Exploring would have added plans to the store, and syncing would have saved them.
+    assert_eq!(
+        std::fs::read(cache.path()).unwrap(),
+        saved,
+        "the preloaded plans are executed without exploring"
+    );
+}
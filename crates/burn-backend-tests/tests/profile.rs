@@ -0,0 +1,56 @@
+//! Tests the operation profiler on the backend selected by the enabled features.
+//!
+//! Profiling sessions record the operations of every thread, so this test is isolated in its own
+//! binary where no other test runs operations concurrently.
+
+#![cfg(feature = "std")]
+
+use burn_tensor::{
+    Device, Tensor,
+    profile::{ProfileEventKind, Profiler, scope},
+};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn records_operations_with_output_sizes() {
+    let device = Device::default();
+    let a = Tensor::<2>::ones([8, 16], &device);
+    let b = Tensor::<2>::full([16, 4], 2.0, &device);
+
+    let (data, report) = Profiler::new(&device).run(|| {
+        scope("step", || {
+            let c = a.clone().matmul(b.clone()).exp();
+            c.into_data()
+        })
+    });
+    assert_eq!(data.num_elements(), 8 * 4);
+
+    let events = report.events();
+    let step = events
+        .iter()
+        .find(|event| event.kind == ProfileEventKind::Scope)
+        .expect("the scope is recorded");
+    assert_eq!(step.name, "step");
+
+    let matmul = events
+        .iter()
+        .find(|event| event.name == "float_matmul")
+        .expect("the operation is recorded");
+    assert_eq!(matmul.bytes, Some((8 * 4 * data.dtype.size()) as u64));
+    assert!(events.iter().any(|event| event.name == "float_exp"));
+    assert!(events.iter().any(|event| event.name == "float_into_data"));
+
+    assert!(report.chrome_trace().contains(r#""name":"float_matmul""#));
+    assert!(report.to_string().contains("float_matmul"));
+}
+
+#[test]
+#[serial]
+fn nothing_is_recorded_outside_the_profiler() {
+    let device = Device::default();
+    let (_, report) = Profiler::new(&device).run(|| ());
+    let _ = Tensor::<1>::ones([4], &device).exp().into_data();
+
+    assert!(report.events().is_empty());
+}
@@ -4,6 +4,7 @@ use burn_tensor::TensorData;
 use burn_tensor::Tolerance;
 use burn_tensor::module::attention;
 use burn_tensor::module::attention_fallback;
+use burn_tensor::module::packed_attention;
 use burn_tensor::ops::AttentionModuleOptions;
 use num_traits::cast::cast;
 
@@ -475,6 +476,7 @@ fn test_attention_all_options() {
         scale: Some(0.05),
         softcap: Some(30.0),
         is_causal: true,
+        ..Default::default()
     };
 
     let output = attention(
@@ -640,3 +642,184 @@ fn test_attention_bool_mask_broadcast_batch_and_heads() {
         Tolerance::rel_abs(1e-2, 1e-3).set_half_precision_relative(1e-1),
     );
 }
+
+fn random_qkv(shape: [usize; 4]) -> (TestTensor<4>, TestTensor<4>, TestTensor<4>) {
+    let random =
+        || TestTensor::<4>::random(shape, Distribution::Uniform(-1., 1.), &Default::default());
+    (random(), random(), random())
+}
+
+#[test]
+fn test_attention_sliding_window() {
+    let [num_batches, num_heads, seq_len, head_dim] = [2, 2, 16, 32];
+    let window = 4;
+    let (query, key, value) = random_qkv([num_batches, num_heads, seq_len, head_dim]);
+
+    let options = AttentionModuleOptions {
+        is_causal: true,
+        sliding_window: Some(window),
+        ..Default::default()
+    };
+    let output = attention(
+        query.clone(),
+        key.clone(),
+        value.clone(),
+        None,
+        None,
+        options,
+    );
+
+    // Query i attends to keys i - window + 1 ..= i.
+    let mask: Vec<bool> = (0..seq_len)
+        .flat_map(|i| (0..seq_len).map(move |j| i >= j + window))
+        .collect();
+    let mask = TestTensorBool::<4>::from_data(
+        TensorData::new(mask, [1, 1, seq_len, seq_len]),
+        &Default::default(),
+    );
+    let causal = AttentionModuleOptions {
+        is_causal: true,
+        ..Default::default()
+    };
+    let expected = attention_fallback(query, key, value, Some(mask), None, causal);
+
+    output.into_data().assert_approx_eq::<FloatElem>(
+        &expected.into_data(),
+        Tolerance::rel_abs(1e-2, 1e-3).set_half_precision_relative(1e-1),
+    );
+}
+
+#[test]
+fn test_attention_alibi() {
+    let [num_batches, num_heads, seq_len, head_dim] = [1, 4, 16, 32];
+    let (query, key, value) = random_qkv([num_batches, num_heads, seq_len, head_dim]);
+
+    let options = AttentionModuleOptions {
+        is_causal: true,
+        alibi_max_bias: Some(8.0),
+        ..Default::default()
+    };
+    let output = attention(
+        query.clone(),
+        key.clone(),
+        value.clone(),
+        None,
+        None,
+        options,
+    );
+
+    // Slopes of 4 heads with a maximum bias of 8: 2^-2, 2^-4, 2^-6, 2^-8.
+    let slopes = [0.25f32, 0.0625, 0.015625, 0.00390625];
+    let bias: Vec<f32> = slopes
+        .iter()
+        .flat_map(|slope| {
+            (0..seq_len)
+                .flat_map(move |i| (0..seq_len).map(move |j| -slope * (i as f32 - j as f32).abs()))
+        })
+        .collect();
+    let bias = TestTensor::<4>::from_data(
+        TensorData::new(bias, [1, num_heads, seq_len, seq_len]),
+        &Default::default(),
+    );
+    let causal = AttentionModuleOptions {
+        is_causal: true,
+        ..Default::default()
+    };
+    let expected = attention_fallback(query, key, value, None, Some(bias), causal);
+
+    output.into_data().assert_approx_eq::<FloatElem>(
+        &expected.into_data(),
+        Tolerance::rel_abs(1e-2, 1e-3).set_half_precision_relative(1e-1),
+    );
+}
+
+#[test]
+fn test_attention_packed_sequences() {
+    let [num_batches, num_heads, seq_len, head_dim] = [2, 2, 12, 32];
+    let offsets = [0, 5, 12];
+    let (query, key, value) = random_qkv([num_batches, num_heads, seq_len, head_dim]);
+
+    let options = AttentionModuleOptions {
+        is_causal: true,
+        ..Default::default()
+    };
+    let seq_offsets = TestTensorInt::<1>::from_ints(offsets, &query.device());
+    let output = packed_attention(
+        query.clone(),
+        key.clone(),
+        value.clone(),
+        seq_offsets,
+        None,
+        None,
+        options,
+    );
+
+    // Each document attends only to itself, as if it had its own batch row.
+    let documents = offsets
+        .windows(2)
+        .map(|range| {
+            let slice = |tensor: &TestTensor<4>| tensor.clone().slice_dim(2, range[0]..range[1]);
+            let causal = AttentionModuleOptions {
+                is_causal: true,
+                ..Default::default()
+            };
+            attention_fallback(
+                slice(&query),
+                slice(&key),
+                slice(&value),
+                None,
+                None,
+                causal,
+            )
+        })
+        .collect();
+    let expected = TestTensor::cat(documents, 2);
+
+    output.into_data().assert_approx_eq::<FloatElem>(
+        &expected.into_data(),
+        Tolerance::rel_abs(1e-2, 1e-3).set_half_precision_relative(1e-1),
+    );
+}
+
+/// Windows, ALiBi, masks and biases together, with more queries and keys than fit in one tile
+/// of a tiled kernel.
+#[test]
+fn test_attention_positional_options_across_key_tiles() {
+    let [num_batches, num_heads, seq_q, seq_k, head_dim] = [2, 3, 40, 100, 16];
+    let (query, _, _) = random_qkv([num_batches, num_heads, seq_q, head_dim]);
+    let (key, value, _) = random_qkv([num_batches, num_heads, seq_k, head_dim]);
+    let bias = TestTensor::<4>::random(
+        [1, num_heads, seq_q, seq_k],
+        Distribution::Uniform(-0.5, 0.5),
+        &Default::default(),
+    );
+    let mask = TestTensor::<4>::random(
+        [num_batches, 1, seq_q, seq_k],
+        Distribution::Uniform(0., 1.),
+        &Default::default(),
+    )
+    .greater_elem(0.8);
+
+    let options = AttentionModuleOptions {
+        softcap: Some(20.0),
+        is_causal: true,
+        sliding_window: Some(48),
+        alibi_max_bias: Some(8.0),
+        ..Default::default()
+    };
+    let output = attention(
+        query.clone(),
+        key.clone(),
+        value.clone(),
+        Some(mask.clone()),
+        Some(bias.clone()),
+        options,
+    );
+
+    let expected = attention_fallback(query, key, value, Some(mask), Some(bias), options);
+
+    output.into_data().assert_approx_eq::<FloatElem>(
+        &expected.into_data(),
+        Tolerance::rel_abs(1e-2, 1e-3).set_half_precision_relative(1e-1),
+    );
+}
@@ -24,4 +24,5 @@ mod linear;
 mod maxpool1d;
 mod maxpool2d;
 mod nearest_interpolate;
+mod selective_scan;
 mod unfold4d;
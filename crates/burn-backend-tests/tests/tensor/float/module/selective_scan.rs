@@ -0,0 +1,63 @@
+use super::*;
+use burn_tensor::Distribution;
+use burn_tensor::TensorData;
+use burn_tensor::Tolerance;
+use burn_tensor::module::{selective_scan, selective_scan_default};
+
+#[test]
+fn test_selective_scan() {
+    let device = Default::default();
+    let x = TestTensor::<3>::from_data([[[0.5, -1.0], [1.0, 0.25], [-0.5, 2.0]]], &device);
+    let delta = TestTensor::<3>::from_data([[[0.1, 0.2], [0.3, 0.4], [0.5, 0.6]]], &device);
+    let a = TestTensor::<2>::from_data([[-1.0, -0.5], [-2.0, -0.25]], &device);
+    let b = TestTensor::<3>::from_data([[[1.0, 0.5], [-0.5, 1.0], [0.25, -1.0]]], &device);
+    let c = TestTensor::<3>::from_data([[[0.5, 1.0], [1.0, -0.5], [-1.0, 0.5]]], &device);
+
+    let output = selective_scan(x, delta, a, b, c);
+
+    let expected =
+        TensorData::from([[[0.05, -0.2], [-0.273718, -0.144624], [0.381212, -0.853778]]]);
+    output
+        .into_data()
+        .assert_approx_eq::<FloatElem>(&expected, Tolerance::rel_abs(1e-3, 1e-3));
+}
+
+#[test]
+fn test_selective_scan_matches_default() {
+    // Backends with a native kernel must agree with the decomposed parallel scan.
+    let device = Default::default();
+    let [batch_size, seq_len, d_inner, d_state] = [2, 7, 3, 4];
+    let x = TestTensor::<3>::random(
+        [batch_size, seq_len, d_inner],
+        Distribution::Uniform(-1., 1.),
+        &device,
+    );
+    let delta = TestTensor::<3>::random(
+        [batch_size, seq_len, d_inner],
+        Distribution::Uniform(0.01, 0.5),
+        &device,
+    );
+    let a = TestTensor::<2>::random(
+        [d_inner, d_state],
+        Distribution::Uniform(-2., -0.1),
+        &device,
+    );
+    let b = TestTensor::<3>::random(
+        [batch_size, seq_len, d_state],
+        Distribution::Uniform(-1., 1.),
+        &device,
+    );
+    let c = TestTensor::<3>::random(
+        [batch_size, seq_len, d_state],
+        Distribution::Uniform(-1., 1.),
+        &device,
+    );
+
+    let output = selective_scan(x.clone(), delta.clone(), a.clone(), b.clone(), c.clone());
+    let reference = selective_scan_default(x, delta, a, b, c);
+
+    output.into_data().assert_approx_eq::<FloatElem>(
+        &reference.into_data(),
+        Tolerance::rel_abs(1e-3, 1e-3).set_half_precision_absolute(1e-2),
+    );
+}
@@ -404,6 +404,37 @@ pub trait AutodiffBackend: Backend {
     /// The autodiff backend tensor.
     fn q_from_inner(tensor: QuantizedTensor<Self::InnerBackend>) -> QuantizedTensor<Self>;
 
+    /// Returns the forward-mode tangent carried by a tensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The tensor to get the tangent of.
+    ///
+    /// # Returns
+    ///
+    /// An optional tensor containing the tangent, `None` when the tangent is zero.
+    fn tangent(tensor: &FloatTensor<Self>) -> Option<FloatTensor<Self>>;
+
+    /// Attach a forward-mode tangent to a tensor, replacing the one it carries.
+    ///
+    /// Every float operation applied to the returned tensor propagates the tangent, so the
+    /// tangent of a result is its Jacobian-vector product with the tangents of the inputs.
+    /// Tangents are tracked like any other tensor: the backward pass of a tangent computes
+    /// second-order derivatives.
+    ///
+    /// # Arguments
+    ///
+    /// * `tensor` - The tensor to attach the tangent to.
+    /// * `tangent` - The tangent, with the same shape as the tensor, or `None` to remove it.
+    ///
+    /// # Returns
+    ///
+    /// The tensor carrying the tangent.
+    fn with_tangent(
+        tensor: FloatTensor<Self>,
+        tangent: Option<FloatTensor<Self>>,
+    ) -> FloatTensor<Self>;
+
     /// Mark the tensor as distributed across multiple devices.
     /// The gradients will be aggregated during the backward pass.
     ///
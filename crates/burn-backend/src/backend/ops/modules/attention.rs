@@ -2,22 +2,27 @@ use core::f32;
 #[allow(unused_imports)]
 use num_traits::Float as _;
 
-use burn_std::Shape;
+use alloc::vec::Vec;
+use burn_std::{Shape, Slice};
 
 use crate::{
-    Backend, TensorMetadata, get_device_settings,
+    Backend, TensorData, TensorMetadata, get_device_settings,
     ops::AttentionModuleOptions,
-    tensor::{BoolTensor, FloatTensor},
+    tensor::{BoolTensor, Device, FloatTensor, IntTensor},
 };
 
 /// Computes softmax(QKᵗ * scale) · V using separate kernels.
 /// Serves as a fallback when FlashAttention is not used.
+///
+/// Every option is applied with differentiable tensor operations, so the autodiff backward
+/// pass of the fallback supports all of them.
 pub fn attention_fallback<B: Backend>(
     query: FloatTensor<B>,
     key: FloatTensor<B>,
     value: FloatTensor<B>,
     mask: Option<BoolTensor<B>>,
     attn_bias: Option<FloatTensor<B>>,
+    seq_offsets: Option<IntTensor<B>>,
     options: AttentionModuleOptions,
 ) -> FloatTensor<B> {
     if let Some(softcap) = options.softcap {
@@ -51,6 +56,11 @@ pub fn attention_fallback<B: Backend>(
         attention_scores
     };
 
+    let [batch_size, num_heads, seq_q, seq_k] = attention_scores.shape().dims::<4>();
+    let scores_shape = Shape::new([batch_size, num_heads, seq_q, seq_k]);
+    let device = attention_scores.device();
+    let settings = get_device_settings::<B>(&device);
+
     // Causal masking: mask positions where col > row (future positions)
     let attention_scores = if options.is_causal {
         let causal_mask = build_causal_mask::<B>(&attention_scores);
@@ -59,6 +69,56 @@ pub fn attention_fallback<B: Backend>(
         attention_scores
     };
 
+    // Sliding window: mask positions at least `window` positions away from the query
+    let attention_scores = if let Some(window) = options.sliding_window {
+        assert!(window > 0, "sliding_window must be non-zero");
+        let distances = B::int_abs(relative_positions::<B>(&device, seq_q, seq_k));
+        let window_mask =
+            B::int_greater_equal_elem(distances, (window as i64).into(), settings.bool_dtype);
+        let window_mask = expand_mask::<B>(window_mask, scores_shape.clone());
+        B::float_mask_fill(attention_scores, window_mask, f32::NEG_INFINITY.into())
+    } else {
+        attention_scores
+    };
+
+    // Packed sequences: mask positions of other sequences
+    let attention_scores = if let Some(seq_offsets) = seq_offsets {
+        assert_eq!(
+            seq_q, seq_k,
+            "Packed sequences need the same query and key sequence length"
+        );
+        let ids = packed_sequence_ids::<B>(seq_offsets, seq_k);
+        let rows = B::int_reshape(ids.clone(), Shape::new([seq_q, 1]));
+        let cols = B::int_reshape(ids, Shape::new([1, seq_k]));
+        let rows = B::int_expand(rows, Shape::new([seq_q, seq_k]));
+        let cols = B::int_expand(cols, Shape::new([seq_q, seq_k]));
+        let sequence_mask = B::int_not_equal(rows, cols, settings.bool_dtype);
+        let sequence_mask = expand_mask::<B>(sequence_mask, scores_shape.clone());
+        B::float_mask_fill(attention_scores, sequence_mask, f32::NEG_INFINITY.into())
+    } else {
+        attention_scores
+    };
+
+    // ALiBi: -slope * |i - j|, with one slope per head
+    let attention_scores = if let Some(slopes) = options.alibi_slopes(num_heads) {
+        let dtype = attention_scores.dtype();
+        let distances = B::int_abs(relative_positions::<B>(&device, seq_q, seq_k));
+        let distances = B::int_into_float(distances, dtype.into());
+        let distances = B::float_reshape(distances, Shape::new([1, 1, seq_q, seq_k]));
+        let slopes: Vec<f32> = slopes.into_iter().map(|slope| -slope as f32).collect();
+        let slopes = B::float_from_data(
+            TensorData::new(slopes, [1, num_heads, 1, 1]).convert_dtype(dtype),
+            &device,
+        );
+        let alibi = B::float_mul(
+            B::float_expand(distances, scores_shape.clone()),
+            B::float_expand(slopes, scores_shape),
+        );
+        B::float_add(attention_scores, alibi)
+    } else {
+        attention_scores
+    };
+
     // Additive bias (ALiBi, relative position biases, etc.)
     let attention_scores = if let Some(bias) = attn_bias {
         B::float_add(attention_scores, bias)
@@ -85,32 +145,83 @@ pub fn attention_fallback<B: Backend>(
     B::float_matmul(softmax, value)
 }
 
+/// The sequence of each of the `seq_len` positions of a row packing variable-length sequences,
+/// given the cumulative offsets `[0, len_0, len_0 + len_1, ..., seq_len]` of the sequences.
+///
+/// Each offset after the first one that is at or before a position starts a new sequence, so
+/// the ids are computed on the device without reading the offsets.
+///
+/// # Panics
+///
+/// If there are less than 2 offsets.
+pub fn packed_sequence_ids<B: Backend>(seq_offsets: IntTensor<B>, seq_len: usize) -> IntTensor<B> {
+    let [num_offsets] = seq_offsets.shape().dims::<1>();
+    assert!(
+        num_offsets >= 2,
+        "seq_offsets should go from 0 to the sequence length, got {num_offsets} offsets"
+    );
+    let device = seq_offsets.device();
+    let settings = get_device_settings::<B>(&device);
+    let shape = Shape::new([seq_len, num_offsets - 1]);
+
+    let seq_offsets = B::int_cast(seq_offsets, settings.int_dtype);
+    let starts = B::int_slice(seq_offsets, &[Slice::new(1, Some(num_offsets as isize), 1)]);
+    let starts = B::int_reshape(starts, Shape::new([1, num_offsets - 1]));
+    let positions = B::int_reshape(
+        B::int_arange(0..seq_len as i64, &device, settings.int_dtype),
+        Shape::new([seq_len, 1]),
+    );
+
+    let started = B::int_greater_equal(
+        B::int_expand(positions, shape.clone()),
+        B::int_expand(starts, shape),
+        settings.bool_dtype,
+    );
+    let ids = B::int_sum_dim(B::bool_into_int(started, settings.int_dtype), 1);
+    B::int_reshape(ids, Shape::new([seq_len]))
+}
+
 /// Builds a causal (upper-triangular) bool mask where `true` means "mask this position".
 /// Shape: [batch_size, num_heads, seq_q, seq_k], masking positions where col > row.
 fn build_causal_mask<B: Backend>(attention_scores: &FloatTensor<B>) -> BoolTensor<B> {
     let device = attention_scores.device();
-    let scores_shape = attention_scores.shape().dims::<4>();
-    let [batch_size, num_heads, seq_q, seq_k] = scores_shape;
+    let scores_shape = attention_scores.shape();
+    let [_, _, seq_q, seq_k] = scores_shape.dims::<4>();
     let settings = get_device_settings::<B>(&device);
 
+    // mask where col > row + offset (upper triangle)
+    let positions = relative_positions::<B>(&device, seq_q, seq_k);
+    let mask_2d = B::int_lower_elem(positions, 0i64.into(), settings.bool_dtype);
+
+    expand_mask::<B>(mask_2d, scores_shape)
+}
+
+/// The position of each query relative to each key, `row + offset - col`, of shape
+/// [seq_q, seq_k].
+///
+/// Column indices are offset so that the boundary aligns at the bottom-right corner,
+/// which handles cross-attention (seq_k > seq_q) correctly.
+fn relative_positions<B: Backend>(device: &Device<B>, seq_q: usize, seq_k: usize) -> IntTensor<B> {
+    let settings = get_device_settings::<B>(device);
+
     // row indices [seq_q, 1] and col indices [1, seq_k]
-    // Offset col indices so that the causal boundary aligns at the bottom-right corner,
-    // which handles cross-attention (seq_k > seq_q) correctly.
     let offset = seq_k as i64 - seq_q as i64;
     let rows = B::int_reshape(
-        B::int_arange(0..seq_q as i64, &device, settings.int_dtype),
+        B::int_arange(0..seq_q as i64, device, settings.int_dtype),
         Shape::new([seq_q, 1]),
     );
     let cols = B::int_reshape(
-        B::int_arange(0..seq_k as i64, &device, settings.int_dtype),
+        B::int_arange(0..seq_k as i64, device, settings.int_dtype),
         Shape::new([1, seq_k]),
     );
 
-    // mask where col > row + offset (upper triangle)
     let rows_shifted = B::int_add_scalar(rows, offset.into());
-    let mask_2d = B::int_lower(rows_shifted, cols, settings.bool_dtype);
+    B::int_sub(rows_shifted, cols)
+}
 
-    // Reshape to [1, 1, seq_q, seq_k] then expand to [batch_size, num_heads, seq_q, seq_k]
+/// Reshapes a [seq_q, seq_k] mask to [1, 1, seq_q, seq_k] then expands it to the scores shape.
+fn expand_mask<B: Backend>(mask_2d: BoolTensor<B>, scores_shape: Shape) -> BoolTensor<B> {
+    let [_, _, seq_q, seq_k] = scores_shape.dims::<4>();
     let mask_4d = B::bool_reshape(mask_2d, Shape::new([1, 1, seq_q, seq_k]));
-    B::bool_expand(mask_4d, Shape::new([batch_size, num_heads, seq_q, seq_k]))
+    B::bool_expand(mask_4d, scores_shape)
 }
@@ -1,4 +1,4 @@
-use super::{conv, ctc, linear, pool};
+use super::{conv, ctc, linear, pool, selective_scan};
 use crate::ops::unfold::{create_unfolding_weight, unfold4d_using_conv2d};
 use crate::tensor::{BoolTensor, FloatTensor, IntTensor};
 use crate::{Backend, Scalar, TensorMetadata};
@@ -54,6 +54,26 @@ pub struct Conv3dBackward<B: Backend> {
     pub bias_grad: Option<FloatTensor<B>>,
 }
 
+/// Gradient computed during the backward pass for each tensor used by
+/// [selective_scan](ModuleOps::selective_scan).
+#[derive(new)]
+pub struct SelectiveScanBackward<B: Backend> {
+    /// Input gradient.
+    pub x_grad: FloatTensor<B>,
+
+    /// Step size gradient.
+    pub delta_grad: FloatTensor<B>,
+
+    /// State matrix gradient.
+    pub a_grad: FloatTensor<B>,
+
+    /// Input projection gradient.
+    pub b_grad: FloatTensor<B>,
+
+    /// Output projection gradient.
+    pub c_grad: FloatTensor<B>,
+}
+
 /// Gradient computed during the backward pass for each tensor used by [max_pool1d](ModuleOps::max_pool1d).
 #[derive(new)]
 pub struct MaxPool1dBackward<B: Backend> {
@@ -810,7 +830,12 @@ pub trait ModuleOps<B: Backend> {
     ///   where `true` indicates positions to mask (i.e. set to -inf before softmax).
     /// - `attn_bias`: Optional float tensor of shape `[batch_size, num_heads, seq_len_q, seq_len_k]`
     ///   added to the attention scores before softmax (e.g. ALiBi, relative position biases).
-    /// - `options`: Additional attention options (custom scale, softcap, causal masking).
+    /// - `seq_offsets`: Optional int tensor of shape `[num_sequences + 1]` packing variable-length
+    ///   sequences in each batch row: the cumulative offsets `[0, len_0, len_0 + len_1, ...,
+    ///   seq_len]` of the sequences, shared by every row. Positions only attend to positions of
+    ///   the same sequence. Requires the same query and key sequence length.
+    /// - `options`: Additional attention options (custom scale, softcap, causal masking, sliding
+    ///   window, ALiBi).
     ///
     /// # Returns
     /// A tensor of shape `[batch_size, num_heads, seq_len_q, val_dim]`
@@ -825,6 +850,7 @@ pub trait ModuleOps<B: Backend> {
         value: FloatTensor<B>,
         mask: Option<BoolTensor<B>>,
         attn_bias: Option<FloatTensor<B>>,
+        seq_offsets: Option<IntTensor<B>>,
         options: AttentionModuleOptions,
     ) -> FloatTensor<B>;
 
@@ -948,6 +974,84 @@ pub trait ModuleOps<B: Backend> {
         )
     }
 
+    /// Computes a selective (input-dependent) state-space scan, as used by Mamba blocks.
+    ///
+    /// For every batch element, channel `d` and state `n`, runs the discretized recurrence
+    ///
+    /// ```text
+    /// h[t, d, n] = exp(delta[t, d] * a[d, n]) * h[t-1, d, n] + delta[t, d] * b[t, n] * x[t, d]
+    /// y[t, d]    = sum_n c[t, n] * h[t, d, n]
+    /// ```
+    ///
+    /// starting from a zero state.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input of shape `[batch, seq_len, d_inner]`
+    /// * `delta` - Positive step sizes of shape `[batch, seq_len, d_inner]`
+    /// * `a` - State matrix of shape `[d_inner, d_state]`
+    /// * `b` - Input projection of shape `[batch, seq_len, d_state]`
+    /// * `c` - Output projection of shape `[batch, seq_len, d_state]`
+    ///
+    /// # Returns
+    ///
+    /// Output of shape `[batch, seq_len, d_inner]`
+    fn selective_scan(
+        x: FloatTensor<B>,
+        delta: FloatTensor<B>,
+        a: FloatTensor<B>,
+        b: FloatTensor<B>,
+        c: FloatTensor<B>,
+    ) -> FloatTensor<B> {
+        selective_scan::selective_scan_default::<B>(x, delta, a, b, c)
+    }
+
+    /// Returns `true` if this backend implements
+    /// [selective_scan_backward](ModuleOps::selective_scan_backward) natively.
+    ///
+    /// Autodiff queries this flag to decide between two paths:
+    /// - `true`: use the backend's [selective_scan](ModuleOps::selective_scan) and
+    ///   [selective_scan_backward](ModuleOps::selective_scan_backward) directly.
+    /// - `false`: call [selective_scan::selective_scan_default] for the forward pass;
+    ///   autodiff then differentiates through the decomposed tensor ops.
+    ///
+    /// Backends that override `selective_scan_backward` must also override this to
+    /// return `true`.
+    fn has_selective_scan_backward() -> bool {
+        false
+    }
+
+    /// Backward pass for [selective_scan](ModuleOps::selective_scan).
+    ///
+    /// Only called when [has_selective_scan_backward](ModuleOps::has_selective_scan_backward)
+    /// returns `true`.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - Input of shape `[batch, seq_len, d_inner]`
+    /// * `delta` - Positive step sizes of shape `[batch, seq_len, d_inner]`
+    /// * `a` - State matrix of shape `[d_inner, d_state]`
+    /// * `b` - Input projection of shape `[batch, seq_len, d_state]`
+    /// * `c` - Output projection of shape `[batch, seq_len, d_state]`
+    /// * `output_grad` - Upstream gradient of shape `[batch, seq_len, d_inner]`
+    ///
+    /// # Returns
+    ///
+    /// The gradients w.r.t. every input, each with the shape of its input.
+    fn selective_scan_backward(
+        _x: FloatTensor<B>,
+        _delta: FloatTensor<B>,
+        _a: FloatTensor<B>,
+        _b: FloatTensor<B>,
+        _c: FloatTensor<B>,
+        _output_grad: FloatTensor<B>,
+    ) -> SelectiveScanBackward<B> {
+        unreachable!(
+            "selective_scan_backward called on a backend whose has_selective_scan_backward() \
+             returns false"
+        )
+    }
+
     /// Real-valued FFT with optional size parameter.
     ///
     /// When `n` is `None`, the signal must be a power of two along `dim`, and the output has
@@ -19,6 +19,9 @@ pub mod pool;
 /// Module for grid_sample operations
 pub mod grid_sample;
 
+/// Module with selective scan operations.
+pub mod selective_scan;
+
 mod base;
 
 pub use base::*;
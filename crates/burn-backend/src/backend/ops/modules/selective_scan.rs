@@ -0,0 +1,81 @@
+use alloc::vec;
+use burn_std::{Shape, Slice};
+
+use crate::{Backend, TensorMetadata, tensor::FloatTensor};
+
+/// Default selective scan implementation using a parallel (Hillis-Steele) prefix scan.
+///
+/// The recurrence `h_t = exp(delta_t * A) * h_{t-1} + delta_t * B_t * x_t` is a first-order
+/// linear recurrence, so each step can be written as the affine pair `(a_t, b_t)` and composed
+/// with the associative operator `(a_1, b_1) ∘ (a_2, b_2) = (a_1 * a_2, a_2 * b_1 + b_2)`.
+/// The scan runs in `ceil(log2(seq_len))` rounds of whole-tensor ops, so it is built only from
+/// differentiable primitives and autodiff derives its gradient automatically.
+///
+/// The full hidden state `[batch, seq_len, d_inner, d_state]` is materialized, so backends that
+/// care about memory should provide a native kernel instead.
+///
+/// # Arguments
+///
+/// * `x` - Input of shape `[batch, seq_len, d_inner]`
+/// * `delta` - Positive step sizes of shape `[batch, seq_len, d_inner]`
+/// * `a` - State matrix of shape `[d_inner, d_state]`
+/// * `b` - Input projection of shape `[batch, seq_len, d_state]`
+/// * `c` - Output projection of shape `[batch, seq_len, d_state]`
+///
+/// # Returns
+///
+/// Output of shape `[batch, seq_len, d_inner]`
+pub fn selective_scan_default<B: Backend>(
+    x: FloatTensor<B>,
+    delta: FloatTensor<B>,
+    a: FloatTensor<B>,
+    b: FloatTensor<B>,
+    c: FloatTensor<B>,
+) -> FloatTensor<B> {
+    let [batch_size, seq_len, d_inner] = x.shape().dims::<3>();
+    let d_state = a.shape().dims::<2>()[1];
+    let device = x.device();
+    let dtype = x.dtype().into();
+
+    let delta_4d = B::float_reshape(delta.clone(), Shape::new([batch_size, seq_len, d_inner, 1]));
+    let a_4d = B::float_reshape(a, Shape::new([1, 1, d_inner, d_state]));
+    let b_4d = B::float_reshape(b, Shape::new([batch_size, seq_len, 1, d_state]));
+    let c_4d = B::float_reshape(c, Shape::new([batch_size, seq_len, 1, d_state]));
+
+    // Discretized transition `exp(delta * A)` and input `delta * B * x` per step.
+    let mut decay = B::float_exp(B::float_mul(delta_4d, a_4d));
+    let delta_x = B::float_reshape(
+        B::float_mul(delta, x),
+        Shape::new([batch_size, seq_len, d_inner, 1]),
+    );
+    let mut state = B::float_mul(delta_x, b_4d);
+
+    let mut offset = 1;
+    while offset < seq_len {
+        let shifted = |tensor: FloatTensor<B>, identity: FloatTensor<B>| {
+            let head = B::float_slice(
+                tensor,
+                &[
+                    Slice::full(),
+                    Slice::new(0, Some((seq_len - offset) as isize), 1),
+                    Slice::full(),
+                    Slice::full(),
+                ],
+            );
+            B::float_cat(vec![identity, head], 1)
+        };
+        let pad_shape = Shape::new([batch_size, offset, d_inner, d_state]);
+        let decay_prev = shifted(
+            decay.clone(),
+            B::float_ones(pad_shape.clone(), &device, dtype),
+        );
+        let state_prev = shifted(state.clone(), B::float_zeros(pad_shape, &device, dtype));
+
+        state = B::float_add(B::float_mul(decay.clone(), state_prev), state);
+        decay = B::float_mul(decay, decay_prev);
+        offset *= 2;
+    }
+
+    let output = B::float_sum_dim(B::float_mul(state, c_4d), 3);
+    B::float_reshape(output, Shape::new([batch_size, seq_len, d_inner]))
+}
@@ -22,6 +22,10 @@ pub use burn_std::{
     TensorData, Tolerance, bf16, distribution, element, f16, stream::StreamId,
 };
 
+/// Profiling of tensor operations.
+#[cfg(feature = "std")]
+pub use burn_std::profile;
+
 /// Shape definition.
 pub mod shape {
     pub use burn_std::shape::*;
@@ -14,18 +14,23 @@ use tokio::sync::Mutex;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct TensorTransferId(u64);
+/// Identifies an exposed tensor, and is the only secret needed to download it.
+///
+/// The data service serves any peer that can connect, so ids must be unguessable: create them
+/// from 32 random bytes shared only with the peers allowed to download the tensor.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TensorTransferId([u8; 32]);
 
-impl From<u64> for TensorTransferId {
-    fn from(value: u64) -> Self {
+impl From<[u8; 32]> for TensorTransferId {
+    fn from(value: [u8; 32]) -> Self {
         Self(value)
     }
 }
 
-impl TensorTransferId {
-    pub fn next(&mut self) {
-        self.0 += 1;
+impl core::fmt::Debug for TensorTransferId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // The id is a download secret.
+        f.write_str("TensorTransferId(..)")
     }
 }
 
@@ -163,12 +168,17 @@ impl<B: Backend, P: Protocol> ExternalCommService<B, P> {
                 return None;
             };
 
-            let ExternalCommMessage::Tensor(data) = rmp_serde::from_slice(&msg.data)
-                .expect("Can deserialize messages from the websocket.")
-            else {
-                panic!("Message should have been TensorData")
+            return match rmp_serde::from_slice(&msg.data) {
+                Ok(ExternalCommMessage::Tensor(data)) => Some(data),
+                Ok(ExternalCommMessage::TensorRequest(_)) => {
+                    log::warn!("Received a request instead of the tensor data.");
+                    None
+                }
+                Err(err) => {
+                    log::warn!("Received invalid tensor data: {err}");
+                    None
+                }
             };
-            return Some(data);
         }
         log::warn!("Closed connection");
         None
@@ -227,6 +237,8 @@ impl<B: Backend, P: Protocol> ExternalCommService<B, P> {
     }
 
     /// Handle incoming connections for downloading tensors.
+    ///
+    /// A peer sending anything but a tensor request is disconnected.
     pub(crate) async fn handle_data_channel(
         &self,
         mut channel: <P::Server as ProtocolServer>::Channel,
@@ -234,26 +246,38 @@ impl<B: Backend, P: Protocol> ExternalCommService<B, P> {
         log::info!("[Data Handler] New connection for download.");
 
         while !self.cancel_token.is_cancelled() {
-            match channel.recv().await {
-                Ok(message) => {
-                    if let Some(msg) = message {
-                        let bytes = msg.data;
-                        let msg: ExternalCommMessage = rmp_serde::from_slice(&bytes)
-                            .expect("Can deserialize messages from the websocket.");
-                        let ExternalCommMessage::TensorRequest(transfer_id) = msg else {
-                            panic!("Received a message that wasn't a tensor request! {msg:?}");
-                        };
-
-                        let bytes = self.get_exposed_tensor_bytes(transfer_id).await.unwrap();
-
-                        channel.send(Message::new(bytes)).await.unwrap();
-                    } else {
-                        log::info!("Closed connection");
-                        return;
-                    }
+            let message = match channel.recv().await {
+                Ok(Some(message)) => message,
+                Ok(None) => {
+                    log::info!("Closed connection");
+                    return;
+                }
+                Err(err) => {
+                    log::warn!("[Data Handler] Failed to receive a message: {err:?}");
+                    return;
                 }
-                Err(err) => panic!("Failed to receive message from websocket: {err:?}"),
             };
+
+            let transfer_id = match rmp_serde::from_slice(&message.data) {
+                Ok(ExternalCommMessage::TensorRequest(transfer_id)) => transfer_id,
+                Ok(ExternalCommMessage::Tensor(_)) => {
+                    log::warn!("[Data Handler] Received a tensor instead of a request, closing.");
+                    return;
+                }
+                Err(err) => {
+                    log::warn!("[Data Handler] Received an invalid request, closing: {err}");
+                    return;
+                }
+            };
+
+            let Some(bytes) = self.get_exposed_tensor_bytes(transfer_id).await else {
+                return;
+            };
+
+            if let Err(err) = channel.send(Message::new(bytes)).await {
+                log::warn!("[Data Handler] Failed to send the tensor: {err:?}");
+                return;
+            }
         }
         log::info!("[Data Service] Closing connection for download.");
     }
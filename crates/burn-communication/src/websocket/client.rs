@@ -13,6 +13,9 @@ use tokio_tungstenite::{
     tungstenite::{self, protocol::WebSocketConfig},
 };
 
+#[cfg(feature = "tls")]
+use crate::websocket::TlsClientConfig;
+
 #[derive(Clone)]
 pub struct WsClient;
 
@@ -25,27 +28,58 @@ impl ProtocolClient for WsClient {
     }
 }
 
+impl WsClient {
+    /// Like [`connect`](ProtocolClient::connect), but a `wss` address is verified with the given
+    /// TLS configuration instead of the bundled web PKI roots.
+    #[cfg(feature = "tls")]
+    pub fn connect_tls(
+        address: Address,
+        route: &str,
+        tls: &TlsClientConfig,
+    ) -> DynFut<Result<WsClientChannel, WsClientError>> {
+        Box::pin(connect_wss(address, route.to_owned(), tls.clone()))
+    }
+}
+
 /// Open a new WebSocket connection to the address.
 async fn connect_ws(address: Address, route: String) -> Result<WsClientChannel, WsClientError> {
     let address = parse_ws_address(address).map_err(WsClientError::Address)?;
     let url = format!("{address}/{route}");
-    const MB: usize = 1024 * 1024;
-    let (stream, _) = connect_async_with_config(
+    let (stream, _) = connect_async_with_config(url, Some(ws_config()), true).await?;
+
+    Ok(WsClientChannel { inner: stream })
+}
+
+/// Open a new WebSocket connection to the address, using the TLS configuration for `wss`.
+#[cfg(feature = "tls")]
+async fn connect_wss(
+    address: Address,
+    route: String,
+    tls: TlsClientConfig,
+) -> Result<WsClientChannel, WsClientError> {
+    let address = parse_ws_address(address).map_err(WsClientError::Address)?;
+    let url = format!("{address}/{route}");
+    let (stream, _) = tokio_tungstenite::connect_async_tls_with_config(
         url,
-        Some(
-            WebSocketConfig::default()
-                .write_buffer_size(0)
-                .max_message_size(None)
-                .max_frame_size(Some(MB * 512))
-                .accept_unmasked_frames(true)
-                .read_buffer_size(64 * 1024), // 64 KiB (previous default)
-        ),
+        Some(ws_config()),
         true,
+        Some(tokio_tungstenite::Connector::Rustls(tls.config)),
     )
     .await?;
 
     Ok(WsClientChannel { inner: stream })
 }
+
+fn ws_config() -> WebSocketConfig {
+    const MB: usize = 1024 * 1024;
+    WebSocketConfig::default()
+        .write_buffer_size(0)
+        .max_message_size(None)
+        .max_frame_size(Some(MB * 512))
+        .accept_unmasked_frames(true)
+        .read_buffer_size(64 * 1024) // 64 KiB (previous default)
+}
+
 pub struct WsClientChannel {
     inner: WebSocketStream<MaybeTlsStream<TcpStream>>,
 }
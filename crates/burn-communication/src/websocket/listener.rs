@@ -0,0 +1,184 @@
+use std::net::SocketAddr;
+
+use axum::{extract::connect_info::Connected, serve::IncomingStream};
+use tokio::net::{TcpListener, TcpStream};
+
+#[cfg(feature = "tls")]
+use super::tls::TlsServerConfig;
+
+/// The remote end of a [`WsServerChannel`](super::WsServerChannel), as seen by the server.
+#[derive(Clone, Debug)]
+pub struct WsPeer {
+    address: SocketAddr,
+    certificate: Option<Vec<u8>>,
+}
+
+impl WsPeer {
+    /// The socket address of the peer.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// The DER-encoded certificate the peer presented during the TLS handshake, if any.
+    ///
+    /// Only set when the server requires client certificates (`TlsServerConfig::mutual`), in
+    /// which case the certificate has already been verified against the client authorities.
+    pub fn certificate(&self) -> Option<&[u8]> {
+        self.certificate.as_deref()
+    }
+}
+
+impl Connected<IncomingStream<'_, WsListener>> for WsPeer {
+    fn connect_info(stream: IncomingStream<'_, WsListener>) -> Self {
+        stream.remote_addr().clone()
+    }
+}
+
+#[cfg(feature = "tls")]
+type WsIo = tokio_util::either::Either<TcpStream, tokio_rustls::server::TlsStream<TcpStream>>;
+#[cfg(not(feature = "tls"))]
+type WsIo = TcpStream;
+
+/// Accepts the TCP connections of a [`WsServer`](super::WsServer), terminating TLS when it is
+/// configured.
+pub(crate) struct WsListener {
+    listener: TcpListener,
+    #[cfg(feature = "tls")]
+    tls: Option<TlsAcceptor>,
+}
+
+impl WsListener {
+    #[cfg(feature = "tls")]
+    pub(crate) fn new(listener: TcpListener, tls: Option<TlsServerConfig>) -> Self {
+        Self {
+            listener,
+            tls: tls.map(TlsAcceptor::new),
+        }
+    }
+
+    #[cfg(not(feature = "tls"))]
+    pub(crate) fn new(listener: TcpListener) -> Self {
+        Self { listener }
+    }
+}
+
+impl axum::serve::Listener for WsListener {
+    type Io = WsIo;
+    type Addr = WsPeer;
+
+    #[cfg(feature = "tls")]
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        use tokio_util::either::Either;
+
+        let Some(tls) = &mut self.tls else {
+            let (stream, address) = accept_tcp(&self.listener).await;
+            return (Either::Left(stream), plain_peer(address));
+        };
+
+        // Handshakes run concurrently, so a slow or silent client can't hold back the others.
+        loop {
+            tokio::select! {
+                (stream, address) = accept_tcp(&self.listener) => tls.handshake(stream, address),
+                Some(handshake) = tls.handshakes.join_next(), if !tls.handshakes.is_empty() => {
+                    if let Ok(Some((stream, peer))) = handshake {
+                        return (Either::Right(stream), peer);
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "tls"))]
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let (stream, address) = accept_tcp(&self.listener).await;
+        (stream, plain_peer(address))
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        self.listener.local_addr().map(plain_peer)
+    }
+}
+
+async fn accept_tcp(listener: &TcpListener) -> (TcpStream, SocketAddr) {
+    loop {
+        match listener.accept().await {
+            Ok(accepted) => return accepted,
+            Err(err) => {
+                // Same policy as axum's own listener: connection errors only concern that
+                // connection, anything else (e.g. too many open files) needs a short break.
+                if !is_connection_error(&err) {
+                    log::error!("Failed to accept a connection: {err}");
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+}
+
+fn plain_peer(address: SocketAddr) -> WsPeer {
+    WsPeer {
+        address,
+        certificate: None,
+    }
+}
+
+fn is_connection_error(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::ConnectionReset
+    )
+}
+
+#[cfg(feature = "tls")]
+struct TlsAcceptor {
+    acceptor: tokio_rustls::TlsAcceptor,
+    handshakes: tokio::task::JoinSet<Option<(tokio_rustls::server::TlsStream<TcpStream>, WsPeer)>>,
+}
+
+#[cfg(feature = "tls")]
+impl TlsAcceptor {
+    /// Clients that don't complete the handshake in time are dropped.
+    const HANDSHAKE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+    fn new(config: TlsServerConfig) -> Self {
+        Self {
+            acceptor: tokio_rustls::TlsAcceptor::from(config.config),
+            handshakes: tokio::task::JoinSet::new(),
+        }
+    }
+
+    fn handshake(&mut self, stream: TcpStream, address: SocketAddr) {
+        let accept = self.acceptor.accept(stream);
+
+        self.handshakes.spawn(async move {
+            let stream = match tokio::time::timeout(Self::HANDSHAKE_TIMEOUT, accept).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(err)) => {
+                    log::warn!("TLS handshake with {address} failed: {err}");
+                    return None;
+                }
+                Err(_) => {
+                    log::warn!("TLS handshake with {address} timed out");
+                    return None;
+                }
+            };
+
+            let certificate = stream
+                .get_ref()
+                .1
+                .peer_certificates()
+                .and_then(|chain| chain.first())
+                .map(|certificate| certificate.to_vec());
+
+            Some((
+                stream,
+                WsPeer {
+                    address,
+                    certificate,
+                },
+            ))
+        });
+    }
+}
@@ -1,7 +1,13 @@
 mod base;
 mod client;
+mod listener;
 mod server;
+#[cfg(feature = "tls")]
+mod tls;
 
 pub use base::*;
 pub use client::*;
+pub use listener::WsPeer;
 pub use server::*;
+#[cfg(feature = "tls")]
+pub use tls::*;
@@ -1,10 +1,11 @@
-use std::net::SocketAddr;
-
 use crate::base::{CommunicationChannel, CommunicationError, Message, ProtocolServer};
+#[cfg(feature = "tls")]
+use crate::websocket::TlsServerConfig;
+use crate::websocket::{WsPeer, listener::WsListener};
 use axum::{
     Router,
     extract::{
-        State, WebSocketUpgrade,
+        ConnectInfo, WebSocketUpgrade,
         ws::{self, WebSocket},
     },
     routing::get,
@@ -39,10 +40,13 @@ fn init_logging() {
 pub struct WsServer {
     port: u16,
     router: Router<()>,
+    #[cfg(feature = "tls")]
+    tls: Option<TlsServerConfig>,
 }
 
 pub struct WsServerChannel {
     inner: WebSocket,
+    peer: WsPeer,
 }
 
 impl WsServer {
@@ -50,9 +54,18 @@ impl WsServer {
         Self {
             port,
             router: Router::new(),
+            #[cfg(feature = "tls")]
+            tls: None,
         }
     }
 
+    /// Serve over TLS: clients must connect with a `wss` address.
+    #[cfg(feature = "tls")]
+    pub fn tls(mut self, config: TlsServerConfig) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
     /// Serve on an already-bound listener instead of binding `0.0.0.0:port` ourselves.
     ///
     /// This is what [`serve`](ProtocolServer::serve) delegates to; it is exposed so a caller
@@ -76,10 +89,14 @@ impl WsServer {
             Err(err) => log::info!("Server started (could not resolve bound address: {err})"),
         }
 
+        #[cfg(feature = "tls")]
+        let listener = WsListener::new(listener, self.tls);
+        #[cfg(not(feature = "tls"))]
+        let listener = WsListener::new(listener);
+
         axum::serve(
             listener,
-            self.router
-                .into_make_service_with_connect_info::<SocketAddr>(),
+            self.router.into_make_service_with_connect_info::<WsPeer>(),
         )
         .with_graceful_shutdown(shutdown)
         .await?;
@@ -116,11 +133,17 @@ impl ProtocolServer for WsServer {
             format!("/{path}")
         };
 
-        let method = get(|ws: WebSocketUpgrade, _: State<()>| async {
-            ws.on_upgrade(async move |socket| {
-                callback(WsServerChannel { inner: socket }).await;
-            })
-        });
+        let method = get(
+            |ws: WebSocketUpgrade, ConnectInfo(peer): ConnectInfo<WsPeer>| async {
+                ws.on_upgrade(async move |socket| {
+                    callback(WsServerChannel {
+                        inner: socket,
+                        peer,
+                    })
+                    .await;
+                })
+            },
+        );
 
         self.router = self.router.route(&path, method);
 
@@ -176,6 +199,11 @@ impl CommunicationChannel for WsServerChannel {
 }
 
 impl WsServerChannel {
+    /// The client at the other end of the channel.
+    pub fn peer(&self) -> &WsPeer {
+        &self.peer
+    }
+
     /// Split into independently-owned send and receive halves, so a writer task and a reader loop
     /// can run concurrently over one full-duplex socket.
     pub fn split(self) -> (WsServerSink, WsServerStream) {
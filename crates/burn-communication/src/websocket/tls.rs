@@ -0,0 +1,170 @@
+use std::sync::Arc;
+
+pub use rustls;
+
+use rustls::{
+    ClientConfig, RootCertStore, ServerConfig,
+    crypto::{CryptoProvider, ring},
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+    server::WebPkiClientVerifier,
+};
+
+/// TLS configuration of a [`WsServer`](super::WsServer).
+///
+/// Clients connect with the `wss` scheme. With [`mutual`](Self::mutual), clients must also
+/// present a certificate signed by the given authority, which the server exposes as the
+/// [peer certificate](super::WsPeer::certificate) of each channel.
+#[derive(Clone, Debug)]
+pub struct TlsServerConfig {
+    pub(crate) config: Arc<ServerConfig>,
+}
+
+/// TLS configuration used by a [`WsClient`](super::WsClient) to connect to a `wss` address.
+#[derive(Clone, Debug)]
+pub struct TlsClientConfig {
+    pub(crate) config: Arc<ClientConfig>,
+}
+
+/// Error raised when building a TLS configuration.
+#[derive(Debug)]
+pub enum TlsError {
+    /// A PEM document couldn't be parsed.
+    Pem(rustls::pki_types::pem::Error),
+    /// A PEM document doesn't contain any certificate.
+    NoCertificate,
+    /// Rustls rejected the configuration (e.g. a key not matching the certificate).
+    Rustls(rustls::Error),
+    /// The client certificate verifier couldn't be built from the authority.
+    ClientVerifier(rustls::server::VerifierBuilderError),
+}
+
+impl TlsServerConfig {
+    /// Serve with the given PEM-encoded certificate chain and private key, without
+    /// authenticating clients at the TLS layer.
+    pub fn new(certificate_chain: &[u8], private_key: &[u8]) -> Result<Self, TlsError> {
+        let config = ServerConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()?
+            .with_no_client_auth()
+            .with_single_cert(certificates(certificate_chain)?, key(private_key)?)?;
+
+        Ok(Self::from(config))
+    }
+
+    /// Serve with the given PEM-encoded certificate chain and private key, and only accept
+    /// clients presenting a certificate signed by one of the PEM-encoded `client_authorities`.
+    pub fn mutual(
+        certificate_chain: &[u8],
+        private_key: &[u8],
+        client_authorities: &[u8],
+    ) -> Result<Self, TlsError> {
+        let verifier = WebPkiClientVerifier::builder_with_provider(
+            Arc::new(roots(client_authorities)?),
+            provider(),
+        )
+        .build()
+        .map_err(TlsError::ClientVerifier)?;
+
+        let config = ServerConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()?
+            .with_client_cert_verifier(verifier)
+            .with_single_cert(certificates(certificate_chain)?, key(private_key)?)?;
+
+        Ok(Self::from(config))
+    }
+}
+
+impl From<ServerConfig> for TlsServerConfig {
+    fn from(mut config: ServerConfig) -> Self {
+        // WebSocket upgrades are HTTP/1.1 requests.
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl TlsClientConfig {
+    /// Trust the servers whose certificate is signed by one of the PEM-encoded `authorities`.
+    pub fn new(authorities: &[u8]) -> Result<Self, TlsError> {
+        let config = ClientConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots(authorities)?)
+            .with_no_client_auth();
+
+        Ok(Self::from(config))
+    }
+
+    /// Like [`new`](Self::new), but also present the PEM-encoded certificate chain and private
+    /// key to servers requiring client authentication.
+    pub fn mutual(
+        authorities: &[u8],
+        certificate_chain: &[u8],
+        private_key: &[u8],
+    ) -> Result<Self, TlsError> {
+        let config = ClientConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots(authorities)?)
+            .with_client_auth_cert(certificates(certificate_chain)?, key(private_key)?)?;
+
+        Ok(Self::from(config))
+    }
+}
+
+impl From<ClientConfig> for TlsClientConfig {
+    fn from(config: ClientConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl core::fmt::Display for TlsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Pem(err) => write!(f, "invalid PEM document: {err}"),
+            Self::NoCertificate => write!(f, "no certificate found in the PEM document"),
+            Self::Rustls(err) => write!(f, "invalid TLS configuration: {err}"),
+            Self::ClientVerifier(err) => write!(f, "invalid client authorities: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {}
+
+impl From<rustls::pki_types::pem::Error> for TlsError {
+    fn from(err: rustls::pki_types::pem::Error) -> Self {
+        Self::Pem(err)
+    }
+}
+
+impl From<rustls::Error> for TlsError {
+    fn from(err: rustls::Error) -> Self {
+        Self::Rustls(err)
+    }
+}
+
+/// The crypto provider is explicit, so that the configuration doesn't depend on the process-wide
+/// default installed by other crates.
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(ring::default_provider())
+}
+
+fn certificates(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let certificates = CertificateDer::pem_slice_iter(pem).collect::<Result<Vec<_>, _>>()?;
+    if certificates.is_empty() {
+        return Err(TlsError::NoCertificate);
+    }
+    Ok(certificates)
+}
+
+fn key(pem: &[u8]) -> Result<PrivateKeyDer<'static>, TlsError> {
+    Ok(PrivateKeyDer::from_pem_slice(pem)?)
+}
+
+fn roots(pem: &[u8]) -> Result<RootCertStore, TlsError> {
+    let mut roots = RootCertStore::empty();
+    for certificate in certificates(pem)? {
+        roots.add(certificate)?;
+    }
+    Ok(roots)
+}
@@ -0,0 +1,222 @@
+use alloc::{format, string::String, string::ToString};
+pub use burn_derive::Config;
+use core::fmt::Debug;
+
+/// Configuration IO error.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// Invalid format.
+    InvalidFormat(String),
+
+    /// File not found.
+    FileNotFound(String),
+
+    /// A field breaks a constraint declared with `#[config(...)]`.
+    InvalidValue {
+        /// Path of the field, e.g. `optimizer.beta_1`.
+        field: String,
+        /// What the constraint expects.
+        reason: String,
+    },
+
+    /// A command-line override can't be applied.
+    InvalidOverride(String),
+}
+
+impl ConfigError {
+    /// Prefix the path of an invalid field with the field of the config holding it.
+    pub fn nested(self, parent: &str) -> Self {
+        match self {
+            Self::InvalidValue { field, reason } => Self::InvalidValue {
+                field: format!("{parent}.{field}"),
+                reason,
+            },
+            err => err,
+        }
+    }
+}
+
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut message = "Config error => ".to_string();
+
+        match self {
+            Self::InvalidFormat(err) => {
+                message += format!("Invalid format: {err}").as_str();
+            }
+            Self::FileNotFound(err) => {
+                message += format!("File not found: {err}").as_str();
+            }
+            Self::InvalidValue { field, reason } => {
+                message += format!("Invalid value: `{field}` {reason}").as_str();
+            }
+            Self::InvalidOverride(err) => {
+                message += format!("Invalid override: {err}").as_str();
+            }
+        };
+
+        f.write_str(message.as_str())
+    }
+}
+
+impl core::error::Error for ConfigError {}
+
+/// Configuration trait.
+pub trait Config: Debug + serde::Serialize + serde::de::DeserializeOwned {
+    /// Saves the configuration to a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - File to save the configuration to.
+    ///
+    /// # Returns
+    ///
+    /// The output of the save operation.
+    #[cfg(feature = "std")]
+    fn save<P: AsRef<std::path::Path>>(&self, file: P) -> std::io::Result<()> {
+        std::fs::write(file, config_to_json(self))
+    }
+
+    /// Loads the configuration from a file.
+    ///
+    /// The loaded configuration is [validated](Config::validate).
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - File to load the configuration from.
+    ///
+    /// # Returns
+    ///
+    /// The loaded configuration.
+    #[cfg(feature = "std")]
+    fn load<P: AsRef<std::path::Path>>(file: P) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(file.as_ref())
+            .map_err(|_| ConfigError::FileNotFound(file.as_ref().to_string_lossy().to_string()))?;
+        config_from_str(&content)
+    }
+
+    /// Loads the configuration from a binary buffer.
+    ///
+    /// The loaded configuration is [validated](Config::validate).
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Binary buffer to load the configuration from.
+    ///
+    /// # Returns
+    ///
+    /// The loaded configuration.
+    fn load_binary(data: &[u8]) -> Result<Self, ConfigError> {
+        let content = core::str::from_utf8(data).map_err(|_| {
+            ConfigError::InvalidFormat("Could not parse data as utf-8.".to_string())
+        })?;
+        config_from_str(content)
+    }
+
+    /// Checks the constraints declared on the fields with `#[config(...)]`, and those of the
+    /// nested configs.
+    ///
+    /// Loading and [overriding](Config::with_overrides) a configuration validate it. A
+    /// configuration built in code is **not** validated: `new`, the `with_` methods and the
+    /// `init` methods building modules from a config never check the constraints, so finish the
+    /// chain with [validated](Config::validated) to do so.
+    ///
+    /// # Returns
+    ///
+    /// The first constraint broken, as [`ConfigError::InvalidValue`].
+    fn validate(&self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+
+    /// Finishes building a configuration in code, checking its [constraints](Config::validate).
+    ///
+    /// # Example
+    ///
+    /// ```rust, ignore
+    /// let config = TrainingConfig::new(model)
+    ///     .with_learning_rate(learning_rate)
+    ///     .validated()?;
+    /// ```
+    ///
+    /// # Returns
+    ///
+    /// The configuration, or the first constraint broken as [`ConfigError::InvalidValue`].
+    fn validated(self) -> Result<Self, ConfigError>
+    where
+        Self: Sized,
+    {
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Applies command-line overrides of the form `--a.b.c=value`.
+    ///
+    /// Each path names a field, through nested configs; the variant of an enum config is a path
+    /// segment of its own (`--optimizer.Adam.beta_1=0.95`), and a whole field can be replaced
+    /// (`--optimizer=Sgd`, or `--optimizer={"Adam": {...}}`). Values are read as JSON, and as plain
+    /// strings when they aren't JSON or when the field holds a string. A flag without a value sets
+    /// `true`.
+    ///
+    /// # Arguments
+    ///
+    /// * `overrides` - The overrides, applied in order.
+    ///
+    /// # Returns
+    ///
+    /// The overridden configuration, [validated](Config::validate).
+    fn with_overrides<I, S>(self, overrides: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        super::overrides::apply(self, overrides)
+    }
+
+    /// The JSON Schema of the configuration, without the `$schema` keyword so it can be nested
+    /// in the schema of another configuration.
+    ///
+    /// See [`config_json_schema`] for a complete schema document.
+    fn json_schema() -> serde_json::Value
+    where
+        Self: Sized,
+    {
+        serde_json::Value::Object(serde_json::Map::new())
+    }
+}
+
+/// Converts a configuration to a JSON string.
+///
+/// # Arguments
+///
+/// * `config` - Configuration to convert.
+///
+/// # Returns
+///
+/// The JSON string.
+pub fn config_to_json<C: Config>(config: &C) -> String {
+    serde_json::to_string_pretty(config).unwrap()
+}
+
+/// The JSON Schema document (draft 2020-12) describing the files a configuration loads from.
+///
+/// # Returns
+///
+/// The schema as a JSON string.
+pub fn config_json_schema<C: Config>() -> String {
+    let mut schema = C::json_schema();
+    if let serde_json::Value::Object(schema) = &mut schema {
+        schema.insert(
+            "$schema".to_string(),
+            "https://json-schema.org/draft/2020-12/schema".into(),
+        );
+    }
+    serde_json::to_string_pretty(&schema).unwrap()
+}
+
+fn config_from_str<C: Config>(content: &str) -> Result<C, ConfigError> {
+    let config: C = serde_json::from_str(content)
+        .map_err(|err| ConfigError::InvalidFormat(format!("{err}")))?;
+    config.validate()?;
+    Ok(config)
+}
@@ -0,0 +1,14 @@
+mod base;
+mod overrides;
+mod schema;
+mod validation;
+
+pub use base::*;
+
+/// Items used by the code `#[derive(Config)]` generates.
+#[doc(hidden)]
+pub mod __private {
+    pub use super::schema::*;
+    pub use super::validation::*;
+    pub use serde_json::Value;
+}
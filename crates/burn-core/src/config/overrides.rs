@@ -0,0 +1,115 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use serde_json::Value;
+
+use super::{Config, ConfigError};
+
+pub(super) fn apply<C, I, S>(config: C, overrides: I) -> Result<C, ConfigError>
+where
+    C: Config,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut value = serde_json::to_value(&config)
+        .map_err(|err| ConfigError::InvalidFormat(format!("{err}")))?;
+
+    for item in overrides {
+        let item = item.as_ref();
+        let (path, raw) = parse(item)?;
+        let target = lookup(&mut value, &path, item)?;
+        *target = read_value(target, raw);
+    }
+
+    let config: C = serde_json::from_value(value)
+        .map_err(|err| ConfigError::InvalidOverride(format!("{err}")))?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Split `--a.b.c=value` into its path and its value, if any.
+fn parse(item: &str) -> Result<(Vec<&str>, Option<&str>), ConfigError> {
+    let Some(item) = item.strip_prefix("--") else {
+        return Err(ConfigError::InvalidOverride(format!(
+            "`{item}` should look like `--field.subfield=value`"
+        )));
+    };
+    let (path, raw) = match item.split_once('=') {
+        Some((path, raw)) => (path, Some(raw)),
+        None => (item, None),
+    };
+    let path: Vec<&str> = path.split('.').collect();
+    if path.iter().any(|segment| segment.is_empty()) {
+        return Err(ConfigError::InvalidOverride(format!(
+            "`--{item}` has an empty field name"
+        )));
+    }
+    Ok((path, raw))
+}
+
+/// The value at `path`, which must already exist: overrides can't add fields.
+fn lookup<'a>(
+    mut value: &'a mut Value,
+    path: &[&str],
+    item: &str,
+) -> Result<&'a mut Value, ConfigError> {
+    for (depth, segment) in path.iter().enumerate() {
+        let parent = path[..depth].join(".");
+        value = match value {
+            Value::Object(fields) => {
+                if !fields.contains_key(*segment) {
+                    let known: Vec<&str> = fields.keys().map(String::as_str).collect();
+                    return Err(ConfigError::InvalidOverride(format!(
+                        "`{item}`: no field `{segment}`{} (expected one of: {})",
+                        in_parent(&parent),
+                        known.join(", ")
+                    )));
+                }
+                fields.get_mut(*segment).unwrap()
+            }
+            Value::Array(items) => {
+                let len = items.len();
+                segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| items.get_mut(index))
+                    .ok_or_else(|| {
+                        ConfigError::InvalidOverride(format!(
+                            "`{item}`: `{segment}` isn't an index{} (it has {len} items)",
+                            in_parent(&parent)
+                        ))
+                    })?
+            }
+            other => {
+                return Err(ConfigError::InvalidOverride(format!(
+                    "`{item}`: `{parent}` has no fields (its value is `{other}`)"
+                )));
+            }
+        };
+    }
+    Ok(value)
+}
+
+fn in_parent(parent: &str) -> String {
+    if parent.is_empty() {
+        String::new()
+    } else {
+        format!(" in `{parent}`")
+    }
+}
+
+/// Read an override of `current`.
+///
+/// A string takes the raw text, so `--name=123` stays a string, unless it is quoted or is an
+/// object, which switches a unit variant of an enum config to one holding data.
+fn read_value(current: &Value, raw: Option<&str>) -> Value {
+    let Some(raw) = raw else {
+        return Value::Bool(true);
+    };
+    if current.is_string() && !raw.starts_with(['"', '{']) {
+        return Value::String(raw.to_string());
+    }
+    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+}
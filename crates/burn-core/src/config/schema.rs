@@ -0,0 +1,179 @@
+//! The pieces of the JSON Schema generated by `#[derive(Config)]`.
+
+use alloc::{boxed::Box, string::String, string::ToString, vec::Vec};
+use core::marker::PhantomData;
+
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+/// Types with a JSON Schema of their own, as fields of a config.
+///
+/// `#[derive(Config)]` implements it for the config, with [`Config::json_schema`](super::Config::json_schema).
+pub trait FieldSchema {
+    /// The schema of the values of the type, as serialized in a config file.
+    fn field_schema() -> Value;
+}
+
+macro_rules! field_schema {
+    ($schema:tt => $($ty:ty),*) => {
+        $(
+            impl FieldSchema for $ty {
+                fn field_schema() -> Value {
+                    json!($schema)
+                }
+            }
+        )*
+    };
+}
+
+field_schema!({ "type": "boolean" } => bool);
+field_schema!({ "type": "integer" } => i8, i16, i32, i64, isize);
+field_schema!({ "type": "integer", "minimum": 0 } => u8, u16, u32, u64, usize);
+field_schema!({ "type": "number" } => f32, f64);
+field_schema!({ "type": "string" } => String, char);
+#[cfg(feature = "std")]
+field_schema!({ "type": "string" } => std::path::PathBuf);
+
+impl<T: FieldSchema> FieldSchema for Option<T> {
+    fn field_schema() -> Value {
+        nullable(T::field_schema())
+    }
+}
+
+impl<T: FieldSchema> FieldSchema for Vec<T> {
+    fn field_schema() -> Value {
+        json!({ "type": "array", "items": T::field_schema() })
+    }
+}
+
+impl<T: FieldSchema, const N: usize> FieldSchema for [T; N] {
+    fn field_schema() -> Value {
+        json!({ "type": "array", "items": T::field_schema(), "minItems": N, "maxItems": N })
+    }
+}
+
+impl<T: FieldSchema> FieldSchema for Box<T> {
+    fn field_schema() -> Value {
+        T::field_schema()
+    }
+}
+
+/// The type of a field, described with [`SchemaWithTrait`] when it implements [`FieldSchema`]
+/// and left open by [`SchemaOther`] otherwise.
+///
+/// The derive calls `(&&SchemaOf::<T>::new()).schema()`, resolved like
+/// [`Nested`](super::validation::Nested).
+pub struct SchemaOf<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> SchemaOf<T> {
+    /// The schema of `T`.
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: ?Sized> Default for SchemaOf<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Describes the types implementing [`FieldSchema`].
+pub trait SchemaWithTrait {
+    /// The schema of the type.
+    fn schema(&self) -> Value;
+}
+
+impl<T: FieldSchema> SchemaWithTrait for &SchemaOf<T> {
+    fn schema(&self) -> Value {
+        T::field_schema()
+    }
+}
+
+/// Accepts any value for the types without a schema.
+pub trait SchemaOther {
+    /// The schema accepting anything.
+    fn schema(&self) -> Value {
+        Value::Object(Map::new())
+    }
+}
+
+impl<T: ?Sized> SchemaOther for SchemaOf<T> {}
+
+/// Also accept `null`, for an optional field.
+pub fn nullable(schema: Value) -> Value {
+    json!({ "anyOf": [schema, { "type": "null" }] })
+}
+
+/// Set `keyword` to `value`, e.g. a bound or a default.
+pub fn set(schema: &mut Value, keyword: &str, value: impl Serialize) {
+    if let (Value::Object(schema), Ok(value)) = (schema, serde_json::to_value(value)) {
+        schema.insert(keyword.to_string(), value);
+    }
+}
+
+/// Require a string, an array or an object to hold something.
+pub fn non_empty(schema: &mut Value) {
+    let keyword = match schema.get("type").and_then(Value::as_str) {
+        Some("string") => "minLength",
+        Some("array") => "minItems",
+        Some("object") => "minProperties",
+        _ => return,
+    };
+    set(schema, keyword, 1);
+}
+
+/// The doc comment of an item, as a description.
+pub fn describe(schema: &mut Value, docs: &[&str]) {
+    let description = docs
+        .iter()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let description = description.trim();
+    if !description.is_empty() {
+        set(schema, "description", description);
+    }
+}
+
+/// An object with the given properties, each `(name, schema, required)`, and no others.
+pub fn object<'a>(properties: impl IntoIterator<Item = (&'a str, Value, bool)>) -> Value {
+    let properties: Vec<_> = properties.into_iter().collect();
+    let required: Vec<&str> = properties
+        .iter()
+        .filter(|(_, _, required)| *required)
+        .map(|(name, _, _)| *name)
+        .collect();
+    let properties: Map<String, Value> = properties
+        .into_iter()
+        .map(|(name, schema, _)| (name.to_string(), schema))
+        .collect();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// A tuple, as serialized: an array of exactly these items.
+pub fn tuple(items: impl IntoIterator<Item = Value>) -> Value {
+    let items: Vec<_> = items.into_iter().collect();
+    let len = items.len();
+    json!({ "type": "array", "prefixItems": items, "minItems": len, "maxItems": len })
+}
+
+/// A unit variant of an enum, serialized as its name.
+pub fn unit_variant(name: &str) -> Value {
+    json!({ "const": name })
+}
+
+/// A variant of an enum holding data, serialized as an object with the name as its single key.
+pub fn variant(name: &str, content: Value) -> Value {
+    object([(name, content, true)])
+}
+
+/// An enum: exactly one of its variants.
+pub fn one_of(variants: impl IntoIterator<Item = Value>) -> Value {
+    json!({ "oneOf": variants.into_iter().collect::<Vec<_>>() })
+}
@@ -0,0 +1,142 @@
+//! The checks behind the validation attributes of `#[derive(Config)]`.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Debug;
+
+use super::{Config, ConfigError};
+
+fn invalid(field: &str, reason: String) -> ConfigError {
+    ConfigError::InvalidValue {
+        field: field.into(),
+        reason,
+    }
+}
+
+/// `#[config(min = ...)]`: the value is at least `min`. NaN is never valid.
+pub fn check_min<T: PartialOrd + Debug>(field: &str, value: &T, min: T) -> Result<(), ConfigError> {
+    match value.partial_cmp(&min) {
+        Some(core::cmp::Ordering::Less) | None => Err(invalid(
+            field,
+            format!("must be at least {min:?}, got {value:?}"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// `#[config(max = ...)]`: the value is at most `max`. NaN is never valid.
+pub fn check_max<T: PartialOrd + Debug>(field: &str, value: &T, max: T) -> Result<(), ConfigError> {
+    match value.partial_cmp(&max) {
+        Some(core::cmp::Ordering::Greater) | None => Err(invalid(
+            field,
+            format!("must be at most {max:?}, got {value:?}"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Values `#[config(non_empty)]` applies to.
+pub trait Emptiness {
+    /// Whether the value holds nothing.
+    fn is_empty_value(&self) -> bool;
+}
+
+impl Emptiness for String {
+    fn is_empty_value(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<T> Emptiness for Vec<T> {
+    fn is_empty_value(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Emptiness for std::path::PathBuf {
+    fn is_empty_value(&self) -> bool {
+        self.as_os_str().is_empty()
+    }
+}
+
+/// `#[config(non_empty)]`: the string or the collection holds something.
+pub fn check_non_empty<T: Emptiness>(field: &str, value: &T) -> Result<(), ConfigError> {
+    if value.is_empty_value() {
+        return Err(invalid(field, "must not be empty".into()));
+    }
+    Ok(())
+}
+
+/// `#[config(one_of = [...])]`: the value is one of `allowed`.
+pub fn check_one_of<T, U>(field: &str, value: &T, allowed: &[U]) -> Result<(), ConfigError>
+where
+    T: PartialEq<U> + Debug,
+    U: Debug,
+{
+    if allowed.iter().any(|candidate| value == candidate) {
+        return Ok(());
+    }
+    Err(invalid(
+        field,
+        format!("must be one of {allowed:?}, got {value:?}"),
+    ))
+}
+
+/// `#[config(validate = path)]`: the value passes a custom check, which explains its refusal.
+pub fn check_with<T>(
+    field: &str,
+    value: &T,
+    check: impl FnOnce(&T) -> Result<(), String>,
+) -> Result<(), ConfigError> {
+    check(value).map_err(|reason| invalid(field, reason))
+}
+
+/// A field of a config, validated with [`ValidateConfig`] when it holds configs and skipped by
+/// [`ValidateOther`] otherwise.
+///
+/// The derive calls `(&&Nested(&field)).validate_nested(name)`: method resolution picks the
+/// `ValidateConfig` impl when its bounds hold, and falls back to `ValidateOther` one dereference
+/// later.
+pub struct Nested<'a, T>(pub &'a T);
+
+/// Validates the configs a field holds.
+pub trait ValidateConfig {
+    /// Validate the field named `field`.
+    fn validate_nested(&self, field: &str) -> Result<(), ConfigError>;
+}
+
+impl<C: Config> ValidateConfig for &Nested<'_, C> {
+    fn validate_nested(&self, field: &str) -> Result<(), ConfigError> {
+        self.0.validate().map_err(|err| err.nested(field))
+    }
+}
+
+impl<C: Config> ValidateConfig for &Nested<'_, Option<C>> {
+    fn validate_nested(&self, field: &str) -> Result<(), ConfigError> {
+        match self.0 {
+            Some(config) => config.validate().map_err(|err| err.nested(field)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<C: Config> ValidateConfig for &Nested<'_, Vec<C>> {
+    fn validate_nested(&self, field: &str) -> Result<(), ConfigError> {
+        for (index, config) in self.0.iter().enumerate() {
+            config
+                .validate()
+                .map_err(|err| err.nested(&format!("{field}.{index}")))?;
+        }
+        Ok(())
+    }
+}
+
+/// Accepts the fields that hold no config.
+pub trait ValidateOther {
+    /// Nothing to validate.
+    fn validate_nested(&self, _field: &str) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+impl<T> ValidateOther for Nested<'_, T> {}
@@ -1,4 +1,4 @@
-use crate::module::{Lora, ParamGroup, QLora};
+use crate::module::{ApplyPruning, Lora, ParamGroup, Pruner, QLora};
 
 use super::{ApplyReparameterization, Param, ParamId, Quantizer, Reparameterizer};
 use alloc::{
@@ -254,6 +254,19 @@ pub trait Module: Clone + Send + core::fmt::Debug {
         self.apply_reparameterization(qlora)
     }
 
+    /// Attach [pruning masks](crate::module::PruningMask) to the module's weights, or update the
+    /// existing ones with the pruner's sparsity.
+    ///
+    /// Pruned weights materialize as `base * mask`; the masks are saved in the record, so a module
+    /// must have its masks attached (with any sparsity) before loading a pruned record.
+    fn apply_pruning(self, pruner: Pruner) -> Self
+    where
+        Self: Sized,
+    {
+        let mut mapper = ApplyPruning::new(pruner, &self);
+        self.map(&mut mapper)
+    }
+
     /// Collect this module's parameters into a [`ModuleRecord`](crate::store::ModuleRecord).
     ///
     /// The record can be saved to a burnpack file or byte buffer and applied back with
@@ -377,6 +390,17 @@ pub trait ModuleVisitor {
     #[allow(unused_variables)]
     fn visit_bool<const D: usize>(&mut self, param: &Param<Tensor<D, Bool>>) {}
 
+    /// Visit a derived module before its parameters and submodules.
+    ///
+    /// It is called by the [derive](burn_derive::Module) on every struct and enum module with
+    /// fields, after the [enter_module](ModuleVisitor::enter_module) of its own path (the root
+    /// module has an empty path). Containers such as `Vec` and `Option` aren't visited.
+    ///
+    /// # Parameters
+    /// - `module`: The module being visited
+    #[allow(unused_variables)]
+    fn visit_module<M: Module>(&mut self, module: &M) {}
+
     /// Called when entering a submodule.
     ///
     /// # Parameters
@@ -0,0 +1,794 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::cell::RefCell;
+
+use burn_tensor::{
+    DType, Device, Tensor,
+    quantization::{CalibrationRange, QuantScheme, QuantizationParameters, compute_q_params},
+};
+
+use crate::module::{
+    AutodiffModule, Content, Devices, ForwardHooks, Module, ModuleDisplay, ModuleDisplayDefault,
+    ModuleMapper, ModuleVisitor, Param, ParamId,
+};
+use crate::store::{ModuleRecord, RecordError};
+
+/// The method used to derive a static quantization range from observed activations.
+///
+/// Activations are quantized symmetrically, so every method picks a clipping threshold on the
+/// absolute values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ActivationCalibration {
+    /// Uses the smallest and largest observed values.
+    MinMax,
+    /// Clips the absolute values at the given percentile (e.g. `99.99`), ignoring rare outliers.
+    Percentile(f64),
+    /// Picks the threshold minimizing the KL divergence between the observed distribution and its
+    /// quantized version, as done by TensorRT's entropy calibration.
+    Entropy,
+}
+
+/// Statistics of the activations observed at one module input.
+///
+/// The absolute values are accumulated in a histogram over `[0, absmax]`. When a larger value is
+/// observed the histogram range grows and the existing counts are redistributed, so it only ever
+/// holds `num_bins` counters regardless of the amount of calibration data.
+#[derive(Debug, Clone)]
+pub struct ActivationStatistics {
+    min: f32,
+    max: f32,
+    count: u64,
+    range: f32,
+    histogram: Vec<u64>,
+}
+
+impl ActivationStatistics {
+    /// Create empty statistics with a histogram of `num_bins` bins.
+    pub fn new(num_bins: usize) -> Self {
+        assert!(num_bins > 0, "The histogram should have at least one bin");
+
+        Self {
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+            count: 0,
+            range: 0.0,
+            histogram: vec![0; num_bins],
+        }
+    }
+
+    /// Accumulate the values of the tensor.
+    ///
+    /// The values are read back from the device, which is fine for an offline calibration pass.
+    pub fn observe<const D: usize>(&mut self, tensor: &Tensor<D>) {
+        let tensor = match tensor.dtype() {
+            DType::QFloat(_) => tensor.clone().dequantize(),
+            _ => tensor.clone(),
+        };
+        let values: Vec<f32> = tensor.into_data().iter::<f32>().collect();
+        self.observe_values(&values);
+    }
+
+    /// Accumulate the values.
+    pub fn observe_values(&mut self, values: &[f32]) {
+        if values.is_empty() {
+            return;
+        }
+
+        let (min, max) = values
+            .iter()
+            .fold((self.min, self.max), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+        self.min = min;
+        self.max = max;
+
+        let absmax = f32::max(min.abs(), max.abs());
+        if absmax > self.range {
+            self.rebin(absmax);
+        }
+
+        let last = self.histogram.len() - 1;
+        let width = self.bin_width();
+        for value in values {
+            let bin = if width > 0.0 {
+                ((value.abs() / width) as usize).min(last)
+            } else {
+                0
+            };
+            self.histogram[bin] += 1;
+        }
+        self.count += values.len() as u64;
+    }
+
+    /// The smallest observed value.
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    /// The largest observed value.
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// The number of observed values.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The histogram of the absolute values over `[0, num_bins * bin_width]`.
+    pub fn histogram(&self) -> &[u64] {
+        &self.histogram
+    }
+
+    /// The width of a histogram bin.
+    pub fn bin_width(&self) -> f32 {
+        self.range / self.histogram.len() as f32
+    }
+
+    /// The absolute value below which `percentile` percent of the observed values fall, up to the
+    /// resolution of the histogram.
+    pub fn percentile(&self, percentile: f64) -> f32 {
+        assert!(
+            (0.0..=100.0).contains(&percentile),
+            "The percentile should be in [0, 100], got {percentile}"
+        );
+
+        let target = (percentile / 100.0 * self.count as f64).ceil() as u64;
+        let mut cumulative = 0;
+        for (bin, count) in self.histogram.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= target {
+                return (bin + 1) as f32 * self.bin_width();
+            }
+        }
+        self.range
+    }
+
+    /// The clipping threshold minimizing the KL divergence between the distribution of the
+    /// absolute values and its quantization to `num_levels` levels.
+    ///
+    /// Every candidate threshold folds the values above it into its last bin; the reference
+    /// distribution is then merged into `num_levels` levels and expanded back over the non-empty
+    /// bins to measure the information lost by quantizing at that threshold.
+    pub fn entropy_threshold(&self, num_levels: usize) -> f32 {
+        let num_bins = self.histogram.len();
+        if self.count == 0 || num_levels >= num_bins {
+            return self.range;
+        }
+
+        let mut outliers = vec![0u64; num_bins + 1];
+        for bin in (0..num_bins).rev() {
+            outliers[bin] = outliers[bin + 1] + self.histogram[bin];
+        }
+
+        let mut best = (f64::INFINITY, num_bins);
+        for threshold in num_levels..=num_bins {
+            let reference = &self.histogram[..threshold];
+
+            let mut p: Vec<f64> = reference.iter().map(|&count| count as f64).collect();
+            p[threshold - 1] += outliers[threshold] as f64;
+
+            let mut q = vec![0.0; threshold];
+            for level in 0..num_levels {
+                let start = level * threshold / num_levels;
+                let end = (level + 1) * threshold / num_levels;
+                let merged = &reference[start..end];
+                let non_empty = merged.iter().filter(|&&count| count > 0).count();
+                if non_empty == 0 {
+                    continue;
+                }
+                let value = merged.iter().sum::<u64>() as f64 / non_empty as f64;
+                for (offset, &count) in merged.iter().enumerate() {
+                    if count > 0 {
+                        q[start + offset] = value;
+                    }
+                }
+            }
+
+            let divergence = kl_divergence(&p, &q);
+            if divergence < best.0 {
+                best = (divergence, threshold);
+            }
+        }
+
+        best.1 as f32 * self.bin_width()
+    }
+
+    /// The quantization range given by the calibration method.
+    pub fn range(&self, calibration: &ActivationCalibration, scheme: &QuantScheme) -> (f32, f32) {
+        let threshold = match calibration {
+            ActivationCalibration::MinMax => return (self.min, self.max),
+            ActivationCalibration::Percentile(percentile) => self.percentile(*percentile),
+            ActivationCalibration::Entropy => {
+                let (_, max) = scheme.value.range();
+                self.entropy_threshold(max as usize + 1)
+            }
+        };
+
+        (self.min.max(-threshold), self.max.min(threshold))
+    }
+
+    fn rebin(&mut self, range: f32) {
+        let num_bins = self.histogram.len();
+        let old_width = self.bin_width();
+        let new_width = range / num_bins as f32;
+
+        let mut histogram = vec![0; num_bins];
+        for (bin, &count) in self.histogram.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let center = (bin as f32 + 0.5) * old_width;
+            histogram[((center / new_width) as usize).min(num_bins - 1)] += count;
+        }
+
+        self.histogram = histogram;
+        self.range = range;
+    }
+}
+
+/// The KL divergence of two unnormalized distributions, with empty bins of `q` smoothed.
+fn kl_divergence(p: &[f64], q: &[f64]) -> f64 {
+    const EPSILON: f64 = 1e-4;
+
+    let p_sum: f64 = p.iter().sum();
+    let q_sum: f64 = q.iter().sum();
+    if p_sum == 0.0 || q_sum == 0.0 {
+        return f64::INFINITY;
+    }
+
+    p.iter()
+        .zip(q)
+        .filter(|&(&p, _)| p > 0.0)
+        .map(|(&p, &q)| {
+            let p = p / p_sum;
+            let q = (q / q_sum).max(EPSILON * p);
+            p * (p / q).ln()
+        })
+        .sum()
+}
+
+/// Computes static activation quantization parameters by running representative data through a
+/// module.
+///
+/// The inputs of every [hookable](crate::module::hookable) module method taking a single float
+/// tensor are observed with [forward hooks](ForwardHooks), and the calibration method turns the
+/// [statistics](ActivationStatistics) of each module path into per-tensor quantization
+/// parameters.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let calibrator = ActivationCalibrator::new(ActivationCalibration::Entropy, scheme);
+/// let params = calibrator.calibrate(&model, dataloader.iter(), |model, batch| {
+///     model.forward(batch.inputs)
+/// });
+/// params.clone().save_file("activations")?;
+///
+/// // Static quantization of the calibrated inputs during inference.
+/// let mut hooks = params.hooks(&scheme);
+/// let (output, _) = hooks.run(&model, |model| model.forward(input));
+/// ```
+pub struct ActivationCalibrator {
+    /// The calibration method.
+    pub calibration: ActivationCalibration,
+    /// The activation quantization scheme.
+    pub scheme: QuantScheme,
+    /// The number of histogram bins used to collect the statistics.
+    pub num_bins: usize,
+    paths: Option<Vec<String>>,
+}
+
+impl ActivationCalibrator {
+    /// Create a new [ActivationCalibrator] observing every module path.
+    pub fn new(calibration: ActivationCalibration, scheme: QuantScheme) -> Self {
+        assert!(
+            scheme.block_size().is_none(),
+            "Static activation quantization only supports per-tensor schemes, got {scheme:?}"
+        );
+
+        Self {
+            calibration,
+            scheme,
+            num_bins: 2048,
+            paths: None,
+        }
+    }
+
+    /// Only observe the inputs of the modules at the given paths.
+    pub fn set_paths(&mut self, paths: Vec<impl Into<String>>) {
+        self.paths = Some(paths.into_iter().map(Into::into).collect());
+    }
+
+    /// Run every batch through `forward` and collect the statistics of the observed inputs by
+    /// module path.
+    pub fn collect<M: Module, I, O>(
+        &self,
+        module: &M,
+        batches: impl IntoIterator<Item = I>,
+        mut forward: impl FnMut(&M, I) -> O,
+    ) -> BTreeMap<String, ActivationStatistics> {
+        let statistics = Rc::new(RefCell::new(BTreeMap::new()));
+        let paths = match &self.paths {
+            Some(paths) => paths.clone(),
+            None => {
+                let mut collector = ModulePaths::default();
+                module.visit(&mut collector);
+                collector.paths
+            }
+        };
+
+        let mut hooks = ForwardHooks::new();
+        for path in paths {
+            observe_input::<1>(&mut hooks, &path, &statistics, self.num_bins);
+            observe_input::<2>(&mut hooks, &path, &statistics, self.num_bins);
+            observe_input::<3>(&mut hooks, &path, &statistics, self.num_bins);
+            observe_input::<4>(&mut hooks, &path, &statistics, self.num_bins);
+            observe_input::<5>(&mut hooks, &path, &statistics, self.num_bins);
+            observe_input::<6>(&mut hooks, &path, &statistics, self.num_bins);
+        }
+
+        for batch in batches {
+            hooks.run(module, |module| forward(module, batch));
+        }
+
+        core::mem::take(&mut *statistics.borrow_mut())
+    }
+
+    /// Compute the quantization parameters of the collected statistics.
+    pub fn quantization_params(
+        &self,
+        statistics: &BTreeMap<String, ActivationStatistics>,
+        device: &Device,
+    ) -> ActivationQuantParams {
+        let scales = statistics
+            .iter()
+            .filter(|(_, statistics)| statistics.count() > 0)
+            .map(|(path, statistics)| {
+                let (min, max) = statistics.range(&self.calibration, &self.scheme);
+                // A constant-zero input still needs a non-zero scale to be quantized.
+                let max = max.max(f32::EPSILON);
+                let range = CalibrationRange {
+                    min: Tensor::from_data([min], device),
+                    max: Tensor::from_data([max], device),
+                };
+                let qparams = compute_q_params(&self.scheme, range);
+                (
+                    path.clone(),
+                    Param::initialized(ParamId::new(), qparams.scales),
+                )
+            })
+            .collect();
+
+        ActivationQuantParams { scales }
+    }
+
+    /// Run every batch through `forward` and compute the static quantization parameters of the
+    /// observed inputs.
+    pub fn calibrate<M: Module, I, O>(
+        &self,
+        module: &M,
+        batches: impl IntoIterator<Item = I>,
+        forward: impl FnMut(&M, I) -> O,
+    ) -> ActivationQuantParams {
+        let statistics = self.collect(module, batches, forward);
+        let device = module.devices().into_iter().next().unwrap_or_default();
+        self.quantization_params(&statistics, &device)
+    }
+}
+
+fn observe_input<const D: usize>(
+    hooks: &mut ForwardHooks,
+    path: &str,
+    statistics: &Rc<RefCell<BTreeMap<String, ActivationStatistics>>>,
+    num_bins: usize,
+) {
+    let statistics = statistics.clone();
+    let key = path.to_string();
+    hooks.register_pre_hook(path, move |input: Tensor<D>| {
+        statistics
+            .borrow_mut()
+            .entry(key.clone())
+            .or_insert_with(|| ActivationStatistics::new(num_bins))
+            .observe(&input);
+        input
+    });
+}
+
+fn quantize_input<const D: usize>(
+    hooks: &mut ForwardHooks,
+    path: &str,
+    scheme: QuantScheme,
+    scales: Tensor<1>,
+) {
+    hooks.register_pre_hook(path, move |input: Tensor<D>| match input.dtype() {
+        DType::QFloat(_) => input,
+        _ => input.quantize(
+            &scheme,
+            QuantizationParameters {
+                scales: scales.clone(),
+                global: None,
+            },
+        ),
+    });
+}
+
+/// Collects the path of every derived module.
+#[derive(Default)]
+struct ModulePaths {
+    path: Vec<String>,
+    paths: Vec<String>,
+}
+
+impl ModuleVisitor for ModulePaths {
+    fn visit_module<M: Module>(&mut self, _module: &M) {
+        self.paths.push(self.path.join("."));
+    }
+
+    fn enter_module(&mut self, name: &str, _container_type: &str) {
+        self.path.push(name.to_string());
+    }
+
+    fn exit_module(&mut self, _name: &str, _container_type: &str) {
+        self.path.pop();
+    }
+}
+
+/// Static activation quantization parameters by module path, computed by an
+/// [ActivationCalibrator].
+///
+/// The scales are parameters of this module, so they are saved and loaded with the usual
+/// [record](crate::store::ModuleRecord) methods; the scale of a module input is recorded at the
+/// module path followed by `scale` (e.g. `encoder.linear.scale`).
+#[derive(Debug, Clone, Default)]
+pub struct ActivationQuantParams {
+    scales: BTreeMap<String, Param<Tensor<1>>>,
+}
+
+const CONTAINER_TYPE: &str = "Struct:ActivationQuantParams";
+
+impl ActivationQuantParams {
+    /// The quantization parameters of the input of the module at `path`.
+    pub fn get(&self, path: &str) -> Option<QuantizationParameters> {
+        self.scales.get(path).map(|scales| QuantizationParameters {
+            scales: scales.val(),
+            global: None,
+        })
+    }
+
+    /// The calibrated module paths.
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.scales.keys().map(String::as_str)
+    }
+
+    /// The number of calibrated module paths.
+    pub fn len(&self) -> usize {
+        self.scales.len()
+    }
+
+    /// If no module path was calibrated.
+    pub fn is_empty(&self) -> bool {
+        self.scales.is_empty()
+    }
+
+    /// Forward hooks quantizing the input of every calibrated module with its fixed scale.
+    ///
+    /// The scheme should be the one used for the calibration.
+    pub fn hooks(&self, scheme: &QuantScheme) -> ForwardHooks {
+        let mut hooks = ForwardHooks::new();
+        for (path, scales) in self.scales.iter() {
+            let scales = scales.val();
+            quantize_input::<1>(&mut hooks, path, *scheme, scales.clone());
+            quantize_input::<2>(&mut hooks, path, *scheme, scales.clone());
+            quantize_input::<3>(&mut hooks, path, *scheme, scales.clone());
+            quantize_input::<4>(&mut hooks, path, *scheme, scales.clone());
+            quantize_input::<5>(&mut hooks, path, *scheme, scales.clone());
+            quantize_input::<6>(&mut hooks, path, *scheme, scales);
+        }
+        hooks
+    }
+
+    /// Create the parameters from the record of an [ActivationQuantParams].
+    ///
+    /// Unlike [load_record](Module::load_record), the calibrated paths don't need to be known in
+    /// advance: they are read from the record.
+    pub fn from_record(record: ModuleRecord, device: &Device) -> Self {
+        let scales = record
+            .into_tensors()
+            .filter_map(|(path, id, data)| {
+                let path = match path.as_str() {
+                    "scale" => String::new(),
+                    path => path.strip_suffix(".scale")?.to_string(),
+                };
+                Some((
+                    path,
+                    Param::initialized(id, Tensor::from_data(data, device)),
+                ))
+            })
+            .collect();
+
+        Self { scales }
+    }
+
+    /// Load the parameters saved with [save_file](Module::save_file).
+    pub fn from_file<P: AsRef<std::path::Path>>(
+        path: P,
+        device: &Device,
+    ) -> Result<Self, RecordError> {
+        Ok(Self::from_record(ModuleRecord::load(path)?, device))
+    }
+
+    fn map_scales(self, mut func: impl FnMut(Param<Tensor<1>>) -> Param<Tensor<1>>) -> Self {
+        let scales = self
+            .scales
+            .into_iter()
+            .map(|(path, scales)| (path, func(scales)))
+            .collect();
+        Self { scales }
+    }
+}
+
+/// The record path segments of the scale of a module input.
+fn scale_path(path: &str) -> impl Iterator<Item = &str> + Clone {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .chain(["scale"])
+}
+
+impl Module for ActivationQuantParams {
+    fn num_params(&self) -> usize {
+        self.scales.values().map(Module::num_params).sum()
+    }
+
+    fn visit<V: ModuleVisitor>(&self, visitor: &mut V) {
+        for (path, scales) in self.scales.iter() {
+            let segments = scale_path(path);
+            segments
+                .clone()
+                .for_each(|segment| visitor.enter_module(segment, CONTAINER_TYPE));
+            Module::visit(scales, visitor);
+            segments
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .for_each(|segment| visitor.exit_module(segment, CONTAINER_TYPE));
+        }
+    }
+
+    fn map<M: ModuleMapper>(self, mapper: &mut M) -> Self {
+        let scales = self
+            .scales
+            .into_iter()
+            .map(|(path, scales)| {
+                let segments: Vec<String> = scale_path(&path).map(String::from).collect();
+                segments
+                    .iter()
+                    .for_each(|segment| mapper.enter_module(segment, CONTAINER_TYPE));
+                let scales = Module::map(scales, mapper);
+                segments
+                    .iter()
+                    .rev()
+                    .for_each(|segment| mapper.exit_module(segment, CONTAINER_TYPE));
+                (path, scales)
+            })
+            .collect();
+
+        Self { scales }
+    }
+
+    fn to_device(self, device: &Device) -> Self {
+        self.map_scales(|scales| scales.to_device(device))
+    }
+
+    fn fork(self, device: &Device) -> Self {
+        self.map_scales(|scales| scales.fork(device))
+    }
+
+    fn collect_devices(&self, devices: Devices) -> Devices {
+        self.scales
+            .values()
+            .fold(devices, |devices, scales| scales.collect_devices(devices))
+    }
+}
+
+impl AutodiffModule for ActivationQuantParams {
+    fn valid(&self) -> Self {
+        self.clone().map_scales(|scales| scales.valid())
+    }
+
+    fn from_inner(module: Self) -> Self {
+        module.map_scales(Param::from_inner)
+    }
+}
+
+impl ModuleDisplayDefault for ActivationQuantParams {
+    fn content(&self, content: Content) -> Option<Content> {
+        self.scales
+            .iter()
+            .fold(content, |content, (path, scales)| {
+                content.add(&format!("{path:?}"), scales)
+            })
+            .set_top_level_type("ActivationQuantParams")
+            .optional()
+    }
+
+    fn num_params(&self) -> usize {
+        Module::num_params(self)
+    }
+}
+
+impl ModuleDisplay for ActivationQuantParams {}
+
+#[cfg(all(test, not(feature = "tch")))]
+mod tests {
+    use super::*;
+    use crate as burn;
+    use crate::module::hookable;
+    use crate::test_device;
+    use burn_tensor::{TensorData, Tolerance, quantization::QuantValue};
+
+    #[derive(Module, Debug)]
+    struct Probe {
+        weight: Param<Tensor<1>>,
+    }
+
+    impl Probe {
+        fn new() -> Self {
+            Self {
+                weight: Param::from_data([1.0], &test_device()),
+            }
+        }
+
+        #[hookable]
+        fn forward(&self, input: Tensor<2>) -> Tensor<2> {
+            input
+        }
+    }
+
+    #[derive(Module, Debug)]
+    struct Model {
+        first: Probe,
+        second: Probe,
+    }
+
+    impl Model {
+        fn forward(&self, input: Tensor<2>) -> Tensor<2> {
+            self.second.forward(self.first.forward(input) * 2.0)
+        }
+    }
+
+    fn model() -> Model {
+        Model {
+            first: Probe::new(),
+            second: Probe::new(),
+        }
+    }
+
+    fn scheme(device: &Device) -> QuantScheme {
+        device
+            .settings()
+            .quantization
+            .scheme
+            .with_value(QuantValue::Q8S)
+    }
+
+    fn batches() -> Vec<Tensor<2>> {
+        let device = test_device();
+        vec![
+            Tensor::from_data([[1.0, -0.5]], &device),
+            Tensor::from_data([[0.25, -3.0]], &device),
+        ]
+    }
+
+    fn expected_scale(min: f32, max: f32, scheme: &QuantScheme) -> TensorData {
+        let device = test_device();
+        let range = CalibrationRange {
+            min: Tensor::from_data([min], &device),
+            max: Tensor::from_data([max], &device),
+        };
+        compute_q_params(scheme, range).scales.into_data()
+    }
+
+    #[test]
+    fn statistics_should_track_range_and_percentile() {
+        let values: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+        let mut statistics = ActivationStatistics::new(2048);
+        statistics.observe_values(&values[..500]);
+        statistics.observe_values(&values[500..]);
+
+        assert_eq!(statistics.min(), 0.0);
+        assert_eq!(statistics.max(), 999.0);
+        assert_eq!(statistics.count(), 1000);
+        assert_eq!(statistics.histogram().iter().sum::<u64>(), 1000);
+        assert!((statistics.percentile(99.0) - 990.0).abs() < 2.0);
+        assert!((statistics.percentile(100.0) - 999.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn entropy_threshold_should_clip_outliers() {
+        let mut values: Vec<f32> = (0..10_000).map(|i| (i as f32 * 0.37).sin()).collect();
+        values.push(100.0);
+        let mut statistics = ActivationStatistics::new(2048);
+        statistics.observe_values(&values);
+
+        let threshold = statistics.entropy_threshold(128);
+
+        assert!((1.0..10.0).contains(&threshold), "{threshold}");
+    }
+
+    #[test]
+    fn calibrator_should_compute_min_max_scales_by_path() {
+        let device = test_device();
+        let model = model();
+        let scheme = scheme(&device);
+        let calibrator = ActivationCalibrator::new(ActivationCalibration::MinMax, scheme);
+
+        let params = calibrator.calibrate(&model, batches(), |model, batch| model.forward(batch));
+
+        assert_eq!(params.paths().collect::<Vec<_>>(), ["first", "second"]);
+        let scale = |path| params.get(path).unwrap().scales.into_data();
+        scale("first")
+            .assert_approx_eq::<f32>(&expected_scale(-3.0, 1.0, &scheme), Tolerance::default());
+        scale("second")
+            .assert_approx_eq::<f32>(&expected_scale(-6.0, 2.0, &scheme), Tolerance::default());
+    }
+
+    #[test]
+    fn calibrator_should_only_observe_selected_paths() {
+        let device = test_device();
+        let model = model();
+        let mut calibrator =
+            ActivationCalibrator::new(ActivationCalibration::Percentile(99.9), scheme(&device));
+        calibrator.set_paths(vec!["second"]);
+
+        let statistics = calibrator.collect(&model, batches(), |model, batch| model.forward(batch));
+
+        assert_eq!(statistics.keys().collect::<Vec<_>>(), ["second"]);
+        assert_eq!(statistics["second"].count(), 4);
+    }
+
+    #[test]
+    fn params_should_round_trip_through_record() {
+        let device = test_device();
+        let model = model();
+        let calibrator = ActivationCalibrator::new(ActivationCalibration::MinMax, scheme(&device));
+        let params = calibrator.calibrate(&model, batches(), |model, batch| model.forward(batch));
+
+        let loaded = ActivationQuantParams::from_record(params.clone().into_record(), &device);
+
+        assert_eq!(loaded.paths().collect::<Vec<_>>(), ["first", "second"]);
+        for path in params.paths() {
+            loaded
+                .get(path)
+                .unwrap()
+                .scales
+                .into_data()
+                .assert_eq(&params.get(path).unwrap().scales.into_data(), true);
+        }
+    }
+
+    #[test]
+    fn params_hooks_should_quantize_inputs() {
+        let device = test_device();
+        let model = model();
+        let scheme = scheme(&device);
+        let calibrator = ActivationCalibrator::new(ActivationCalibration::MinMax, scheme);
+        let params = calibrator.calibrate(&model, batches(), |model, batch| model.forward(batch));
+        let input = Tensor::<2>::from_data([[0.5, -2.0]], &device);
+
+        let mut hooks = params.hooks(&scheme);
+        let (output, _) = hooks.run(&model, |model| model.first.forward(input.clone()));
+
+        assert!(matches!(output.dtype(), DType::QFloat(_)));
+        output
+            .dequantize()
+            .into_data()
+            .assert_approx_eq::<f32>(&input.into_data(), Tolerance::permissive());
+    }
+}
@@ -0,0 +1,482 @@
+//! Forward hooks observing or replacing the inputs and outputs of module methods.
+//!
+//! Methods opt in with the [hookable] attribute. Hooks are registered on [ForwardHooks] by module
+//! path (e.g. `"encoder.layers.0.linear"`, the same path as the one used for parameters without
+//! the parameter name) and are only active during [ForwardHooks::run].
+//!
+//! During a profiling session, the calls to hookable methods made in [ForwardHooks::run] are also
+//! recorded with their module path.
+
+pub use burn_derive::hookable;
+
+#[cfg(feature = "std")]
+pub use registry::*;
+
+/// Runs a [hookable] method body, applying the active forward hooks registered on the module.
+///
+/// This is called by the code generated by [hookable] and shouldn't be called directly.
+#[doc(hidden)]
+pub fn hooked_forward<M, I: 'static, O: Clone + 'static>(
+    module: &M,
+    input: I,
+    forward: impl FnOnce(I) -> O,
+) -> O {
+    #[cfg(feature = "std")]
+    {
+        registry::run_hooked(module, input, forward)
+    }
+
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = module;
+        forward(input)
+    }
+}
+
+#[cfg(feature = "std")]
+mod registry {
+    use core::any::{Any, type_name};
+    use core::cell::RefCell;
+    use std::collections::{HashMap, HashSet};
+
+    use alloc::boxed::Box;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use burn_std::profile::ProfileScope;
+
+    use crate::module::{Module, ModuleVisitor};
+
+    type Hook = Box<dyn FnMut(Box<dyn Any>) -> Box<dyn Any>>;
+
+    /// A module instance, identified by its address and type.
+    ///
+    /// A struct and its first field can share an address, but not a type.
+    type ModuleKey = (usize, &'static str);
+
+    std::thread_local! {
+        static ACTIVE: RefCell<Option<ActiveHooks>> = const { RefCell::new(None) };
+    }
+
+    struct ActiveHooks {
+        hooks: ForwardHooks,
+        paths: HashMap<ModuleKey, String>,
+        activations: Activations,
+    }
+
+    /// Forward hooks registered by module path.
+    ///
+    /// Pre-hooks receive the arguments of a [hookable](super::hookable) method (a tuple when there
+    /// are several) and post-hooks its returned value; both return the value to use instead, so
+    /// they can observe or replace it. A hook only applies to values of the type it was registered
+    /// with and leaves other values untouched. Hooks on the same path run in registration order,
+    /// and captures see the output after the post-hooks.
+    ///
+    /// Modules are addressed by the path of their fields from the root module given to
+    /// [run](ForwardHooks::run), joined with `.` (vector items use their index, enum modules
+    /// their variant name), and the root module has the empty path. Only derived modules with
+    /// fields have a path: stateless modules (e.g. activations) share their address with other
+    /// modules and can't be addressed.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut hooks = ForwardHooks::new();
+    /// hooks
+    ///     .capture("encoder.layers.0")
+    ///     .register_post_hook("encoder.layers.1.linear", |output: Tensor<3>| output.clamp(-1.0, 1.0));
+    ///
+    /// let (output, activations) = hooks.run(&model, |model| model.forward(input));
+    /// let features: Tensor<3> = activations.get("encoder.layers.0").unwrap();
+    /// ```
+    #[derive(Default)]
+    pub struct ForwardHooks {
+        pre: HashMap<String, Vec<Hook>>,
+        post: HashMap<String, Vec<Hook>>,
+        captures: HashSet<String>,
+    }
+
+    /// Activations captured by [ForwardHooks] during a [run](ForwardHooks::run).
+    ///
+    /// A module called several times during the run (e.g. a shared layer) captures one value per
+    /// call, in call order.
+    #[derive(Default)]
+    pub struct Activations {
+        values: HashMap<String, Vec<Box<dyn Any>>>,
+    }
+
+    impl ForwardHooks {
+        /// Create an empty set of forward hooks.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Register a hook called with the arguments of the module at `path`, returning the
+        /// arguments to use instead.
+        pub fn register_pre_hook<I: 'static>(
+            &mut self,
+            path: impl Into<String>,
+            mut hook: impl FnMut(I) -> I + 'static,
+        ) -> &mut Self {
+            self.pre
+                .entry(path.into())
+                .or_default()
+                .push(Box::new(move |value| match value.downcast::<I>() {
+                    Ok(input) => Box::new(hook(*input)),
+                    Err(value) => value,
+                }));
+            self
+        }
+
+        /// Register a hook called with the output of the module at `path`, returning the output
+        /// to use instead.
+        pub fn register_post_hook<O: 'static>(
+            &mut self,
+            path: impl Into<String>,
+            mut hook: impl FnMut(O) -> O + 'static,
+        ) -> &mut Self {
+            self.post
+                .entry(path.into())
+                .or_default()
+                .push(Box::new(move |value| match value.downcast::<O>() {
+                    Ok(output) => Box::new(hook(*output)),
+                    Err(value) => value,
+                }));
+            self
+        }
+
+        /// Capture the output of the module at `path` into the [activations](Activations)
+        /// returned by [run](ForwardHooks::run).
+        pub fn capture(&mut self, path: impl Into<String>) -> &mut Self {
+            self.captures.insert(path.into());
+            self
+        }
+
+        /// Remove every hook and capture registered on `path`.
+        pub fn remove(&mut self, path: &str) -> &mut Self {
+            self.pre.remove(path);
+            self.post.remove(path);
+            self.captures.remove(path);
+            self
+        }
+
+        /// Run `forward` on `module` with the hooks active on the current thread.
+        ///
+        /// Module paths are resolved from `module`, so the hooked methods must be reached from
+        /// the module reference given to `forward`. Hooks keep their state between runs, which
+        /// makes it possible to accumulate statistics over several batches.
+        pub fn run<M: Module, R>(
+            &mut self,
+            module: &M,
+            forward: impl FnOnce(&M) -> R,
+        ) -> (R, Activations) {
+            let mut collector = ModulePathCollector::default();
+            module.visit(&mut collector);
+
+            let active = ActiveHooks {
+                hooks: core::mem::take(self),
+                paths: collector.paths,
+                activations: Activations::default(),
+            };
+            // Hooks of an enclosing run are restored afterward, even if `forward` panics.
+            let _restore = RestoreOnDrop(ACTIVE.with(|cell| cell.replace(Some(active))));
+
+            let output = forward(module);
+            let active = ACTIVE
+                .with(|cell| cell.take())
+                .expect("Forward hooks should be active until the end of the run");
+            *self = active.hooks;
+
+            (output, active.activations)
+        }
+
+        fn apply(
+            hooks: &mut HashMap<String, Vec<Hook>>,
+            path: &str,
+            value: Box<dyn Any>,
+        ) -> Box<dyn Any> {
+            match hooks.get_mut(path) {
+                Some(hooks) => hooks.iter_mut().fold(value, |value, hook| hook(value)),
+                None => value,
+            }
+        }
+    }
+
+    impl core::fmt::Debug for ForwardHooks {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            let count = |hooks: &HashMap<String, Vec<Hook>>| {
+                hooks
+                    .iter()
+                    .map(|(path, hooks)| (path.clone(), hooks.len()))
+                    .collect::<HashMap<_, _>>()
+            };
+            f.debug_struct("ForwardHooks")
+                .field("pre", &count(&self.pre))
+                .field("post", &count(&self.post))
+                .field("captures", &self.captures)
+                .finish()
+        }
+    }
+
+    impl Activations {
+        /// The last value captured at `path`, if any was captured with type `T`.
+        pub fn get<T: Clone + 'static>(&self, path: &str) -> Option<T> {
+            self.values.get(path)?.last()?.downcast_ref::<T>().cloned()
+        }
+
+        /// Every value captured at `path` with type `T`, in call order.
+        pub fn get_all<T: Clone + 'static>(&self, path: &str) -> Vec<T> {
+            self.values
+                .get(path)
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|value| value.downcast_ref::<T>().cloned())
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        /// The paths with captured values.
+        pub fn paths(&self) -> impl Iterator<Item = &str> {
+            self.values.keys().map(String::as_str)
+        }
+
+        /// The number of paths with captured values.
+        pub fn len(&self) -> usize {
+            self.values.len()
+        }
+
+        /// If no value was captured.
+        pub fn is_empty(&self) -> bool {
+            self.values.is_empty()
+        }
+    }
+
+    impl core::fmt::Debug for Activations {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_map()
+                .entries(
+                    self.values
+                        .iter()
+                        .map(|(path, values)| (path, values.len())),
+                )
+                .finish()
+        }
+    }
+
+    struct RestoreOnDrop(Option<ActiveHooks>);
+
+    impl Drop for RestoreOnDrop {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            ACTIVE.with(|cell| *cell.borrow_mut() = previous);
+        }
+    }
+
+    /// Maps every derived module reachable from the root to its path.
+    #[derive(Default)]
+    struct ModulePathCollector {
+        path: Vec<String>,
+        paths: HashMap<ModuleKey, String>,
+    }
+
+    impl ModuleVisitor for ModulePathCollector {
+        fn visit_module<M: Module>(&mut self, module: &M) {
+            // The same instance can be reached twice through shared references; the first
+            // path wins.
+            self.paths
+                .entry(module_key(module))
+                .or_insert_with(|| self.path.join("."));
+        }
+
+        fn enter_module(&mut self, name: &str, _container_type: &str) {
+            self.path.push(name.to_string());
+        }
+
+        fn exit_module(&mut self, _name: &str, _container_type: &str) {
+            self.path.pop();
+        }
+    }
+
+    fn module_key<M>(module: &M) -> ModuleKey {
+        (core::ptr::from_ref(module).addr(), type_name::<M>())
+    }
+
+    pub(super) fn run_hooked<M, I: 'static, O: Clone + 'static>(
+        module: &M,
+        input: I,
+        forward: impl FnOnce(I) -> O,
+    ) -> O {
+        let key = module_key(module);
+        let path = ACTIVE.with(|cell| {
+            cell.borrow()
+                .as_ref()
+                .and_then(|active| active.paths.get(&key).cloned())
+        });
+        let Some(path) = path else {
+            return forward(input);
+        };
+
+        // Hooks are called without holding the thread-local borrow, so they may run modules.
+        let input =
+            with_hooks(|active| ForwardHooks::apply(&mut active.hooks.pre, &path, Box::new(input)));
+        let input = *input
+            .downcast::<I>()
+            .expect("A pre-hook should return a value of the type it received");
+
+        let output = {
+            let _scope = ProfileScope::module(&path);
+            forward(input)
+        };
+
+        let output = with_hooks(|active| {
+            let output = ForwardHooks::apply(&mut active.hooks.post, &path, Box::new(output));
+            if active.hooks.captures.contains(&path)
+                && let Some(value) = output.downcast_ref::<O>()
+            {
+                active
+                    .activations
+                    .values
+                    .entry(path.clone())
+                    .or_default()
+                    .push(Box::new(value.clone()));
+            }
+            output
+        });
+        *output
+            .downcast::<O>()
+            .expect("A post-hook should return a value of the type it received")
+    }
+
+    /// Takes the active hooks out of the thread-local for the duration of `func`.
+    fn with_hooks<R>(func: impl FnOnce(&mut ActiveHooks) -> R) -> R {
+        let mut active = ACTIVE
+            .with(|cell| cell.take())
+            .expect("Forward hooks should be active");
+        let output = func(&mut active);
+        ACTIVE.with(|cell| *cell.borrow_mut() = Some(active));
+        output
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate as burn;
+    use crate::module::{Module, Param};
+    use crate::test_device;
+    use alloc::{vec, vec::Vec};
+    use burn_tensor::{Tensor, TensorData};
+
+    #[derive(Module, Debug)]
+    struct Scale {
+        weight: Param<Tensor<1>>,
+    }
+
+    impl Scale {
+        fn new(factor: f32) -> Self {
+            Self {
+                weight: Param::from_data([factor, factor], &test_device()),
+            }
+        }
+
+        #[hookable]
+        fn forward(&self, input: Tensor<2>) -> Tensor<2> {
+            input * self.weight.val().unsqueeze()
+        }
+    }
+
+    #[derive(Module, Debug)]
+    struct Model {
+        first: Scale,
+        layers: Vec<Scale>,
+    }
+
+    impl Model {
+        fn new() -> Self {
+            Self {
+                first: Scale::new(2.0),
+                layers: vec![Scale::new(3.0), Scale::new(4.0)],
+            }
+        }
+
+        #[hookable]
+        fn forward(&self, input: Tensor<2>, offset: f32) -> Tensor<2> {
+            let x = self.first.forward(input) + offset;
+            self.layers.iter().fold(x, |x, layer| layer.forward(x))
+        }
+    }
+
+    fn input() -> Tensor<2> {
+        Tensor::from_data([[1.0, -1.0]], &test_device())
+    }
+
+    #[test]
+    fn hooks_capture_activations_by_path() {
+        let model = Model::new();
+        let mut hooks = ForwardHooks::new();
+        hooks.capture("").capture("first").capture("layers.1");
+
+        let (output, activations) = hooks.run(&model, |model| model.forward(input(), 1.0));
+
+        assert_eq!(activations.len(), 3);
+        let get = |path| activations.get::<Tensor<2>>(path).unwrap().into_data();
+        get("first").assert_eq(&TensorData::from([[2.0, -2.0]]), false);
+        get("layers.1").assert_eq(&TensorData::from([[36.0, -12.0]]), false);
+        get("").assert_eq(&output.into_data(), false);
+        assert!(activations.get::<Tensor<2>>("layers.0").is_none());
+    }
+
+    #[test]
+    fn hooks_replace_inputs_and_outputs() {
+        let model = Model::new();
+        let mut hooks = ForwardHooks::new();
+        hooks
+            .register_pre_hook("", |(input, _offset): (Tensor<2>, f32)| (input, 0.0))
+            .register_post_hook("layers.0", |output: Tensor<2>| output.zeros_like() + 1.0);
+
+        let (output, _) = hooks.run(&model, |model| model.forward(input(), 1.0));
+
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[4.0, 4.0]]), false);
+    }
+
+    #[test]
+    fn hooks_keep_state_between_runs() {
+        let model = Model::new();
+        let mut hooks = ForwardHooks::new();
+        let counter = std::rc::Rc::new(core::cell::Cell::new(0));
+        let count = counter.clone();
+        hooks.register_post_hook("first", move |output: Tensor<2>| {
+            count.set(count.get() + 1);
+            output
+        });
+
+        for _ in 0..3 {
+            hooks.run(&model, |model| model.forward(input(), 0.0));
+        }
+        // Outside of a run, hooks aren't applied.
+        model.forward(input(), 0.0);
+
+        assert_eq!(counter.get(), 3);
+    }
+
+    #[test]
+    fn hooks_ignore_values_of_other_types() {
+        let model = Model::new();
+        let mut hooks = ForwardHooks::new();
+        hooks
+            .register_post_hook("first", |output: Tensor<1>| output * 0.0)
+            .capture("first");
+
+        let (_, activations) = hooks.run(&model, |model| model.forward(input(), 0.0));
+
+        assert!(activations.get::<Tensor<1>>("first").is_none());
+        activations
+            .get::<Tensor<2>>("first")
+            .unwrap()
+            .into_data()
+            .assert_eq(&TensorData::from([[2.0, -2.0]]), false);
+    }
+}
@@ -1,13 +1,21 @@
 mod base;
+#[cfg(feature = "std")]
+mod calibration;
 mod display;
+mod hook;
 mod initializer;
 mod lora;
 mod param;
+mod pruning;
 mod quantize;
 
 pub use base::*;
+#[cfg(feature = "std")]
+pub use calibration::*;
 pub use display::*;
+pub use hook::*;
 pub use initializer::*;
 pub use lora::*;
 pub use param::*;
+pub use pruning::*;
 pub use quantize::*;
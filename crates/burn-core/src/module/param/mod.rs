@@ -4,6 +4,7 @@ mod group;
 mod id;
 mod lora;
 mod primitive;
+mod pruning;
 mod reparameterization;
 mod reparameterization_dyn;
 mod running;
@@ -16,6 +17,7 @@ pub use constant::*;
 pub use group::*;
 pub use id::*;
 pub use lora::*;
+pub use pruning::*;
 pub use reparameterization::*;
 pub use running::*;
 pub use visitor::*;
@@ -0,0 +1,67 @@
+use super::{Param, Reparameterization};
+use crate as burn;
+use crate::module::Module;
+use burn_tensor::Tensor;
+
+/// A pruning mask attached to a weight [parameter](Param).
+///
+/// When present, the parameter materializes its effective value as `base * mask`, so the pruned
+/// entries are zero in the forward pass and receive no gradient. The mask is stored flattened as
+/// a regular, non-trainable parameter, so it is saved in the record alongside the base at
+/// `<param>.pruning.mask`. Masks are attached and updated with
+/// [`Module::apply_pruning`](crate::module::Module::apply_pruning).
+#[derive(Debug, Module)]
+pub struct PruningMask {
+    /// Flattened mask of ones (kept) and zeros (pruned), with as many entries as the base.
+    pub mask: Param<Tensor<1>>,
+}
+
+impl Reparameterization for PruningMask {
+    const NAME: &'static str = "pruning";
+
+    fn materialize<const D: usize>(&self, base: Tensor<D>) -> Tensor<D> {
+        let mask = self.mask.val().reshape(base.shape());
+        let mask = if base.dtype().is_float() && base.dtype() != mask.dtype() {
+            mask.cast(base.dtype())
+        } else {
+            mask
+        };
+        base * mask
+    }
+}
+
+impl PruningMask {
+    /// The fraction of pruned entries.
+    pub fn sparsity(&self) -> f64 {
+        let mask = self.mask.val();
+        let numel = mask.dims()[0];
+        if numel == 0 {
+            return 0.0;
+        }
+        let kept = mask.sum().into_scalar::<f32>() as f64;
+        1.0 - kept / numel as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_device;
+    use burn_tensor::TensorData;
+
+    #[test]
+    fn materialize_zeroes_the_pruned_entries() {
+        let device = test_device();
+        let base = Tensor::<2>::from_data([[1.0, 2.0], [3.0, 4.0]], &device);
+        let pruning = PruningMask {
+            mask: Param::from_data([1.0, 0.0, 0.0, 1.0], &device),
+        };
+
+        let materialized = pruning.materialize(base);
+
+        materialized
+            .into_data()
+            .assert_eq(&TensorData::from([[1.0, 0.0], [0.0, 4.0]]), false);
+        assert_eq!(pruning.sparsity(), 0.5);
+    }
+}
@@ -0,0 +1,606 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use burn_tensor::{Tensor, TensorData};
+
+use crate::module::{
+    Module, ModuleMapper, ModuleVisitor, Param, ParamGroup, ParamId, PruningMask, Reparameterizer,
+};
+
+/// How the entries of a weight are selected for pruning.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PruningMethod {
+    /// Prunes the entries with the smallest magnitude, anywhere in the weight.
+    Magnitude,
+    /// Keeps the `n` largest entries of every `m` consecutive entries along `dim` (e.g. 2:4
+    /// sparsity along the input dimension `0` of a `Linear` weight), which some hardware
+    /// accelerates. The sparsity is fixed to `1 - n / m`.
+    SemiStructured {
+        /// The number of kept entries per group.
+        n: usize,
+        /// The size of the groups.
+        m: usize,
+        /// The dimension along which the groups are formed.
+        dim: usize,
+    },
+    /// Prunes whole slices along `dim` with the smallest L2 norm, in groups of `group_size`
+    /// consecutive slices (e.g. the output dimension `1` of a `Linear` weight to remove features,
+    /// with `group_size = head_dim` to remove attention heads, or the output channels `0` of a
+    /// `Conv2d` weight).
+    ///
+    /// The bias of the module is pruned with the same mask, so the removed outputs are exactly
+    /// zero and can be physically removed once training is done.
+    Structured {
+        /// The pruned dimension.
+        dim: usize,
+        /// The number of consecutive slices pruned together.
+        group_size: usize,
+    },
+}
+
+/// Attaches or updates [pruning masks](PruningMask) on the module's weights.
+///
+/// It is applied via [`Module::apply_pruning`](crate::module::Module::apply_pruning). Only
+/// parameters of rank 2 or more that match the parameter group are pruned, apart from the bias
+/// following its weight with [structured pruning](PruningMethod::Structured).
+///
+/// The masks are computed from the magnitude of the effective weights, so entries pruned earlier
+/// stay pruned when the pruning is applied again with a higher sparsity.
+#[derive(Debug, Clone)]
+pub struct Pruner {
+    /// How the pruned entries are selected.
+    pub method: PruningMethod,
+    /// The fraction of pruned entries (or slices for structured pruning) of every weight.
+    pub sparsity: f64,
+    /// The parameter group to prune.
+    pub param_group: ParamGroup,
+    channels: BTreeMap<String, Vec<bool>>,
+}
+
+impl Pruner {
+    /// Create a new pruner with the given method and sparsity.
+    pub fn new(method: PruningMethod, sparsity: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&sparsity),
+            "The sparsity should be in [0, 1], got {sparsity}"
+        );
+
+        Self {
+            method,
+            sparsity,
+            param_group: ParamGroup::all(),
+            channels: BTreeMap::new(),
+        }
+    }
+
+    /// Set the parameter group to prune.
+    pub fn set_param_group(mut self, group: ParamGroup) -> Self {
+        self.param_group = group;
+        self
+    }
+
+    /// Set the fraction of pruned entries.
+    pub fn set_sparsity(mut self, sparsity: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&sparsity),
+            "The sparsity should be in [0, 1], got {sparsity}"
+        );
+        self.sparsity = sparsity;
+        self
+    }
+
+    /// If the parameter at `path` is pruned.
+    fn prunes(&self, id: &ParamId, path: &str, rank: usize) -> bool {
+        if rank < 2 {
+            // With structured pruning, the bias follows the channels pruned in its weight.
+            let (module, name) = path.rsplit_once('.').unwrap_or(("", path));
+            return name == "bias" && self.channels.contains_key(module);
+        }
+        self.param_group.matches(id, Some(path))
+    }
+
+    /// Compute the mask of the parameter at `path` from its effective values.
+    fn mask(&mut self, path: &str, data: TensorData) -> Vec<f32> {
+        let shape = data.shape.to_vec();
+        let values: Vec<f32> = data.iter::<f32>().map(f32::abs).collect();
+
+        if shape.len() < 2 {
+            let module = path.rsplit_once('.').map_or("", |(module, _)| module);
+            let channels = &self.channels[module];
+            assert_eq!(
+                channels.len(),
+                values.len(),
+                "The bias '{path}' doesn't match the pruned channels of its weight"
+            );
+            return to_mask(channels);
+        }
+
+        let mask = match self.method {
+            PruningMethod::Magnitude => {
+                let num_pruned = (self.sparsity * values.len() as f64).round() as usize;
+                let mut kept = vec![true; values.len()];
+                for index in smallest(&values, num_pruned) {
+                    kept[index] = false;
+                }
+                kept
+            }
+            PruningMethod::SemiStructured { n, m, dim } => {
+                semi_structured(&values, &shape, n, m, dim)
+            }
+            PruningMethod::Structured { dim, group_size } => {
+                let channels = self.structured(&values, &shape, dim, group_size);
+                let stride: usize = shape[dim + 1..].iter().product();
+                let kept = (0..values.len())
+                    .map(|index| channels[(index / stride) % shape[dim]])
+                    .collect();
+                let module = path.rsplit_once('.').map_or("", |(module, _)| module);
+                self.channels.insert(module.to_string(), channels);
+                kept
+            }
+        };
+
+        to_mask(&mask)
+    }
+
+    /// The kept slices along `dim`.
+    fn structured(
+        &self,
+        values: &[f32],
+        shape: &[usize],
+        dim: usize,
+        group_size: usize,
+    ) -> Vec<bool> {
+        assert!(
+            dim < shape.len(),
+            "Can't prune dimension {dim} of a weight of shape {shape:?}"
+        );
+        let num_slices = shape[dim];
+        assert!(
+            group_size > 0 && num_slices.is_multiple_of(group_size),
+            "Dimension {dim} of size {num_slices} can't be pruned in groups of {group_size}"
+        );
+
+        let stride: usize = shape[dim + 1..].iter().product();
+        let mut norms = vec![0.0f32; num_slices / group_size];
+        for (index, value) in values.iter().enumerate() {
+            norms[(index / stride) % num_slices / group_size] += value * value;
+        }
+
+        let num_pruned = (self.sparsity * norms.len() as f64).round() as usize;
+        let mut kept_groups = vec![true; norms.len()];
+        for group in smallest(&norms, num_pruned) {
+            kept_groups[group] = false;
+        }
+
+        (0..num_slices)
+            .map(|slice| kept_groups[slice / group_size])
+            .collect()
+    }
+}
+
+impl Reparameterizer for Pruner {
+    type Reparam = PruningMask;
+
+    fn reparameterize<const D: usize>(
+        &mut self,
+        path: &str,
+        param: Param<Tensor<D>>,
+    ) -> (Param<Tensor<D>>, Option<Self::Reparam>) {
+        if !self.prunes(&param.id, path, D) {
+            return (param, None);
+        }
+
+        let mask = self.mask(path, param.val().into_data());
+        let mask = new_mask(mask, &param.lazy_device());
+        (param, Some(PruningMask { mask }))
+    }
+}
+
+/// The indices of the `count` smallest values, the first ones winning ties.
+fn smallest(values: &[f32], count: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..values.len()).collect();
+    indices.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    indices.truncate(count);
+    indices
+}
+
+fn semi_structured(values: &[f32], shape: &[usize], n: usize, m: usize, dim: usize) -> Vec<bool> {
+    assert!(
+        dim < shape.len() && n <= m && m > 0 && shape[dim].is_multiple_of(m),
+        "Can't prune {n}:{m} along dimension {dim} of a weight of shape {shape:?}"
+    );
+
+    let size = shape[dim];
+    let stride: usize = shape[dim + 1..].iter().product();
+    let outer: usize = shape[..dim].iter().product();
+    let mut kept = vec![true; values.len()];
+    for outer in 0..outer {
+        for inner in 0..stride {
+            for start in (0..size).step_by(m) {
+                let indices: Vec<usize> = (start..start + m)
+                    .map(|i| (outer * size + i) * stride + inner)
+                    .collect();
+                let group: Vec<f32> = indices.iter().map(|&index| values[index]).collect();
+                let pruned = smallest(&group, m - n);
+                for (position, index) in indices.into_iter().enumerate() {
+                    kept[index] = !pruned.contains(&position);
+                }
+            }
+        }
+    }
+    kept
+}
+
+fn to_mask(kept: &[bool]) -> Vec<f32> {
+    kept.iter()
+        .map(|&kept| if kept { 1.0 } else { 0.0 })
+        .collect()
+}
+
+fn new_mask(mask: Vec<f32>, device: &burn_tensor::Device) -> Param<Tensor<1>> {
+    let numel = mask.len();
+    Param::initialized(
+        ParamId::new(),
+        Tensor::from_data(TensorData::new(mask, [numel]), device),
+    )
+}
+
+/// Collects the paths of the parameters that already have a pruning mask.
+#[derive(Default)]
+struct PrunedParams {
+    path: Vec<String>,
+    paths: BTreeSet<String>,
+}
+
+impl ModuleVisitor for PrunedParams {
+    fn enter_module(&mut self, name: &str, _container_type: &str) {
+        self.path.push(name.to_string());
+    }
+
+    fn exit_module(&mut self, _name: &str, _container_type: &str) {
+        self.path.pop();
+    }
+
+    fn visit_float<const D: usize>(&mut self, _param: &Param<Tensor<D>>) {
+        let path = self.path.join(".");
+        if let Some(param) = path.strip_suffix(".pruning.mask") {
+            self.paths.insert(param.to_string());
+        }
+    }
+}
+
+/// Attaches the masks of unpruned parameters and recomputes the existing masks.
+///
+/// The existing masks are reattached after their base is mapped, so they are updated when the
+/// traversal reaches them, from the base values kept aside.
+pub(crate) struct ApplyPruning {
+    pruner: Pruner,
+    path: Vec<String>,
+    pruned: BTreeSet<String>,
+    bases: BTreeMap<String, (ParamId, TensorData)>,
+}
+
+impl ApplyPruning {
+    pub(crate) fn new<M: Module>(pruner: Pruner, module: &M) -> Self {
+        let mut pruned = PrunedParams::default();
+        module.visit(&mut pruned);
+
+        Self {
+            pruner,
+            path: Vec::new(),
+            pruned: pruned.paths,
+            bases: BTreeMap::new(),
+        }
+    }
+}
+
+impl ModuleMapper for ApplyPruning {
+    fn enter_module(&mut self, name: &str, _container_type: &str) {
+        self.path.push(name.to_string());
+    }
+
+    fn exit_module(&mut self, _name: &str, _container_type: &str) {
+        self.path.pop();
+    }
+
+    fn map_float<const D: usize>(&mut self, param: Param<Tensor<D>>) -> Param<Tensor<D>> {
+        let path = self.path.join(".");
+
+        if let Some(pruned) = path.strip_suffix(".pruning.mask")
+            && let Some((id, base)) = self.bases.remove(pruned)
+        {
+            let (mask_id, mask, mapper) = param.consume();
+            let device = mask.device();
+            if !self.pruner.prunes(&id, pruned, base.shape.len()) {
+                // The parameter left the pruned group: unprune it.
+                return Param::from_mapped_value(mask_id, mask.ones_like(), mapper);
+            }
+
+            let mask = mask.into_data();
+            let values: Vec<f32> = base
+                .iter::<f32>()
+                .zip(mask.iter::<f32>())
+                .map(|(base, mask)| base * mask)
+                .collect();
+            let mask = self
+                .pruner
+                .mask(pruned, TensorData::new(values, base.shape.clone()));
+            let numel = mask.len();
+            let mask = Tensor::from_data(TensorData::new(mask, [numel]), &device);
+            return Param::from_mapped_value(mask_id, mask.reshape(mask_shape::<D>(numel)), mapper);
+        }
+
+        if self.pruned.contains(&path) {
+            self.bases.insert(path, (param.id, param.val().into_data()));
+            return param;
+        }
+
+        let (base, pruning) = self.pruner.reparameterize(&path, param);
+        match pruning {
+            Some(pruning) => base.with_reparameterization(pruning),
+            None => base,
+        }
+    }
+}
+
+/// The shape of a flattened mask seen as a tensor of rank `D` (always 1).
+fn mask_shape<const D: usize>(numel: usize) -> [usize; D] {
+    let mut shape = [1; D];
+    shape[D - 1] = numel;
+    shape
+}
+
+/// A gradual sparsity schedule, increasing the sparsity from `initial` at step `start` to
+/// `target` at step `end` along a cubic curve (Zhu & Gupta, 2017), so most of the weights are
+/// pruned early while the network can still recover.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SparsitySchedule {
+    /// The sparsity at the first pruning step.
+    pub initial: f64,
+    /// The final sparsity.
+    pub target: f64,
+    /// The step of the first pruning.
+    pub start: usize,
+    /// The step reaching the target sparsity.
+    pub end: usize,
+    /// The number of steps between two updates of the masks.
+    pub frequency: usize,
+}
+
+impl SparsitySchedule {
+    /// Create a schedule from no sparsity at `start` to `target` at `end`, updating the masks
+    /// every step.
+    pub fn new(target: f64, start: usize, end: usize) -> Self {
+        assert!(start <= end, "The schedule should start before it ends");
+
+        Self {
+            initial: 0.0,
+            target,
+            start,
+            end,
+            frequency: 1,
+        }
+    }
+
+    /// Set the sparsity at the first pruning step.
+    pub fn with_initial(mut self, initial: f64) -> Self {
+        self.initial = initial;
+        self
+    }
+
+    /// Set the number of steps between two updates of the masks.
+    pub fn with_frequency(mut self, frequency: usize) -> Self {
+        assert!(frequency > 0, "The frequency should be at least one step");
+        self.frequency = frequency;
+        self
+    }
+
+    /// The sparsity at the given step.
+    pub fn sparsity(&self, step: usize) -> f64 {
+        if step <= self.start {
+            return self.initial;
+        }
+        if step >= self.end {
+            return self.target;
+        }
+
+        let progress = (step - self.start) as f64 / (self.end - self.start) as f64;
+        self.target + (self.initial - self.target) * (1.0 - progress).powi(3)
+    }
+
+    /// If the masks are updated at the given step.
+    pub fn is_update_step(&self, step: usize) -> bool {
+        (self.start..=self.end).contains(&step)
+            && ((step - self.start).is_multiple_of(self.frequency) || step == self.end)
+    }
+}
+
+/// Prunes a module during training following a [sparsity schedule](SparsitySchedule).
+///
+/// Call [step](Self::step) after every optimizer step, as the
+/// [learner](https://docs.rs/burn-train) does when configured with it.
+#[derive(Debug, Clone)]
+pub struct GradualPruning {
+    /// The pruner, whose sparsity is overridden by the schedule.
+    pub pruner: Pruner,
+    /// The sparsity schedule.
+    pub schedule: SparsitySchedule,
+    step: usize,
+}
+
+impl GradualPruning {
+    /// Create a new gradual pruning.
+    pub fn new(pruner: Pruner, schedule: SparsitySchedule) -> Self {
+        Self {
+            pruner,
+            schedule,
+            step: 0,
+        }
+    }
+
+    /// The number of steps done.
+    pub fn current_step(&self) -> usize {
+        self.step
+    }
+
+    /// Advance the schedule by one step, updating the masks of the module if scheduled.
+    pub fn step<M: Module>(&mut self, module: M) -> M {
+        self.step += 1;
+        if !self.schedule.is_update_step(self.step) {
+            return module;
+        }
+
+        let sparsity = self.schedule.sparsity(self.step);
+        module.apply_pruning(self.pruner.clone().set_sparsity(sparsity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_device;
+    use crate::test_utils::SimpleLinear;
+    use burn_tensor::Tolerance;
+
+    fn mask_of(param: &Param<Tensor<2>>) -> Vec<f32> {
+        param
+            .reparameterization::<PruningMask>()
+            .expect("pruning mask should be attached")
+            .mask
+            .val()
+            .into_data()
+            .to_vec()
+            .unwrap()
+    }
+
+    #[test]
+    fn magnitude_pruning_masks_the_smallest_weights() {
+        let device = test_device();
+        let mut model = SimpleLinear::new(2, 2, &device);
+        model.weight = Param::from_data([[0.1, -4.0], [3.0, -0.2]], &device);
+
+        let model = model.apply_pruning(Pruner::new(PruningMethod::Magnitude, 0.5));
+
+        assert_eq!(mask_of(&model.weight), [0.0, 1.0, 1.0, 0.0]);
+        assert!(
+            model
+                .bias
+                .as_ref()
+                .unwrap()
+                .reparameterization::<PruningMask>()
+                .is_none()
+        );
+        model.weight.val().into_data().assert_approx_eq::<f32>(
+            &TensorData::from([[0.0, -4.0], [3.0, 0.0]]),
+            Tolerance::default(),
+        );
+    }
+
+    #[test]
+    fn semi_structured_pruning_keeps_n_of_every_m() {
+        let values = [1.0, 4.0, 2.0, 3.0, 8.0, 5.0, 7.0, 6.0];
+
+        let kept = semi_structured(&values, &[4, 2], 2, 4, 0);
+
+        // Columns are `[1, 2, 8, 7]` and `[4, 3, 5, 6]`.
+        assert_eq!(kept, [false, false, false, false, true, true, true, true]);
+    }
+
+    #[test]
+    fn structured_pruning_removes_channels_and_their_bias() {
+        let device = test_device();
+        let mut model = SimpleLinear::new(2, 3, &device);
+        // SimpleLinear stores its weight as `[d_output, d_input]`.
+        model.weight = Param::from_data([[1.0, 1.0], [0.1, 0.1], [2.0, 2.0]], &device);
+
+        let pruner = Pruner::new(
+            PruningMethod::Structured {
+                dim: 0,
+                group_size: 1,
+            },
+            0.34,
+        );
+        let model = model.apply_pruning(pruner);
+
+        assert_eq!(mask_of(&model.weight), [1.0, 1.0, 0.0, 0.0, 1.0, 1.0]);
+        let bias = model.bias.as_ref().unwrap();
+        let bias = bias.reparameterization::<PruningMask>().unwrap();
+        assert_eq!(
+            bias.mask.val().into_data().to_vec::<f32>().unwrap(),
+            [1.0, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn reapplying_pruning_updates_the_masks_monotonically() {
+        let device = test_device();
+        let mut model = SimpleLinear::new(2, 2, &device);
+        model.weight = Param::from_data([[1.0, 2.0], [3.0, 4.0]], &device);
+        let pruner = Pruner::new(PruningMethod::Magnitude, 0.25);
+
+        let model = model.apply_pruning(pruner.clone());
+        let mask_id = model
+            .weight
+            .reparameterization::<PruningMask>()
+            .unwrap()
+            .mask
+            .id;
+        let model = model.apply_pruning(pruner.set_sparsity(0.75));
+
+        let pruning = model.weight.reparameterization::<PruningMask>().unwrap();
+        assert_eq!(pruning.mask.id, mask_id);
+        assert_eq!(mask_of(&model.weight), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(pruning.sparsity(), 0.75);
+    }
+
+    #[test]
+    fn masks_roundtrip_through_the_record() {
+        let device = test_device();
+        let pruner = Pruner::new(PruningMethod::Magnitude, 0.5);
+        let model = SimpleLinear::new(4, 4, &device).apply_pruning(pruner.clone());
+
+        let target = SimpleLinear::new(4, 4, &device).apply_pruning(pruner);
+        let loaded = target.load_record(model.clone().into_record());
+
+        assert_eq!(mask_of(&loaded.weight), mask_of(&model.weight));
+        loaded
+            .weight
+            .val()
+            .into_data()
+            .assert_eq(&model.weight.val().into_data(), true);
+    }
+
+    #[test]
+    fn sparsity_schedule_is_cubic() {
+        let schedule = SparsitySchedule::new(0.8, 10, 20).with_frequency(5);
+
+        assert_eq!(schedule.sparsity(0), 0.0);
+        assert_eq!(schedule.sparsity(15), 0.8 - 0.8 * 0.125);
+        assert_eq!(schedule.sparsity(30), 0.8);
+        let updates: Vec<_> = (0..30)
+            .filter(|&step| schedule.is_update_step(step))
+            .collect();
+        assert_eq!(updates, [10, 15, 20]);
+    }
+
+    #[test]
+    fn gradual_pruning_reaches_the_target_sparsity() {
+        let device = test_device();
+        let mut pruning = GradualPruning::new(
+            Pruner::new(PruningMethod::Magnitude, 0.0),
+            SparsitySchedule::new(0.5, 1, 4),
+        );
+        let mut model = SimpleLinear::new(4, 4, &device);
+
+        for _ in 0..4 {
+            model = pruning.step(model);
+        }
+
+        let pruning = model.weight.reparameterization::<PruningMask>().unwrap();
+        assert_eq!(pruning.sparsity(), 0.5);
+    }
+}
@@ -186,6 +186,12 @@ impl ModuleRecord {
         Self::from_reader(Reader::from_file(path)?)
     }
 
+    /// The recorded tensors as `(path, id, data)`, for a module whose structure is only known
+    /// from its record.
+    pub(crate) fn into_tensors(self) -> impl Iterator<Item = (String, ParamId, TensorData)> {
+        self.tensors.into_iter().map(|t| (t.path, t.id, t.data))
+    }
+
     fn pack_tensors(self) -> Vec<burn_pack::Tensor> {
         self.tensors
             .into_iter()
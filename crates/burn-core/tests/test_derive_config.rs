@@ -1,4 +1,4 @@
-use burn::config::{Config, config_to_json};
+use burn::config::{Config, ConfigError, config_json_schema, config_to_json};
 use burn_core as burn;
 
 #[derive(Config, Debug, PartialEq, Eq)]
@@ -111,3 +111,213 @@ fn struct_config_can_load_binary() {
     let config_loaded = TestStructConfig::load_binary(&binary).unwrap();
     assert_eq!(config, config_loaded);
 }
+
+fn check_even(value: &usize) -> Result<(), String> {
+    if value.is_multiple_of(2) {
+        Ok(())
+    } else {
+        Err(format!("must be even, got {value}"))
+    }
+}
+
+/// Optimizer settings.
+#[derive(Config, Debug, PartialEq)]
+pub struct TestOptimizerConfig {
+    /// The learning rate.
+    #[config(default = 1e-3, min = 0.0, max = 1.0)]
+    learning_rate: f64,
+    #[config(default = "\"adam\".to_string()", one_of = ["adam", "sgd"])]
+    name: String,
+    #[config(min = 1)]
+    warmup_steps: Option<usize>,
+}
+
+#[derive(Config, Debug, PartialEq)]
+pub enum TestScheduleConfig {
+    Constant,
+    Linear { steps: usize },
+    Nested(TestOptimizerConfig),
+}
+
+#[derive(Config, Debug, PartialEq)]
+pub struct TestExperimentConfig {
+    #[config(non_empty)]
+    tags: Vec<String>,
+    #[config(default = 2, validate = check_even)]
+    batch_size: usize,
+    optimizer: TestOptimizerConfig,
+    #[config(default = "TestScheduleConfig::Constant")]
+    schedule: TestScheduleConfig,
+}
+
+fn experiment() -> TestExperimentConfig {
+    TestExperimentConfig::new(vec!["baseline".to_string()], TestOptimizerConfig::new())
+}
+
+fn invalid_field(result: Result<(), ConfigError>) -> String {
+    match result {
+        Err(ConfigError::InvalidValue { field, .. }) => field,
+        other => panic!("expected an invalid value, got {other:?}"),
+    }
+}
+
+#[test]
+fn config_validates_field_constraints() {
+    assert!(experiment().validate().is_ok());
+
+    let mut config = experiment();
+    config.tags = Vec::new();
+    assert_eq!(invalid_field(config.validate()), "tags");
+
+    let config = experiment().with_batch_size(3);
+    assert_eq!(invalid_field(config.validate()), "batch_size");
+
+    let config = TestOptimizerConfig::new().with_name("rmsprop".to_string());
+    assert_eq!(invalid_field(config.validate()), "name");
+
+    let config = TestOptimizerConfig::new().with_learning_rate(f64::NAN);
+    assert_eq!(invalid_field(config.validate()), "learning_rate");
+
+    let config = TestOptimizerConfig::new().with_warmup_steps(Some(0));
+    assert_eq!(invalid_field(config.validate()), "warmup_steps");
+}
+
+#[test]
+fn config_validates_nested_configs() {
+    let optimizer = TestOptimizerConfig::new().with_learning_rate(2.0);
+    let mut config = experiment();
+    config.optimizer = optimizer.clone();
+    assert_eq!(invalid_field(config.validate()), "optimizer.learning_rate");
+
+    let config = experiment().with_schedule(TestScheduleConfig::Nested(optimizer));
+    assert_eq!(
+        invalid_field(config.validate()),
+        "schedule.Nested.learning_rate"
+    );
+}
+
+#[test]
+fn config_built_in_code_is_validated() {
+    let config = experiment()
+        .with_batch_size(8)
+        .with_optimizer(TestOptimizerConfig::new().with_name("sgd".to_string()))
+        .validated()
+        .unwrap();
+    assert_eq!(config.batch_size, 8);
+    assert_eq!(config.optimizer.name, "sgd");
+
+    let result = experiment().with_batch_size(7).validated();
+    assert_eq!(invalid_field(result.map(|_| ())), "batch_size");
+
+    let result = TestOptimizerConfig::new()
+        .with_learning_rate(-1.0)
+        .validated();
+    assert_eq!(invalid_field(result.map(|_| ())), "learning_rate");
+
+    let result = experiment()
+        .with_schedule(TestScheduleConfig::Nested(
+            TestOptimizerConfig::new().with_warmup_steps(Some(0)),
+        ))
+        .validated();
+    assert_eq!(
+        invalid_field(result.map(|_| ())),
+        "schedule.Nested.warmup_steps"
+    );
+}
+
+#[test]
+fn config_load_rejects_invalid_values() {
+    let json = config_to_json(&experiment().with_batch_size(5));
+
+    let result = TestExperimentConfig::load_binary(json.as_bytes());
+    assert!(matches!(result, Err(ConfigError::InvalidValue { .. })));
+}
+
+#[test]
+fn config_applies_overrides() {
+    let config = experiment()
+        .with_overrides([
+            "--optimizer.learning_rate=0.1",
+            "--optimizer.name=sgd",
+            "--optimizer.warmup_steps=100",
+            "--tags=[\"a\", \"b\"]",
+        ])
+        .unwrap();
+
+    assert_eq!(config.optimizer.learning_rate, 0.1);
+    assert_eq!(config.optimizer.name, "sgd");
+    assert_eq!(config.optimizer.warmup_steps, Some(100));
+    assert_eq!(config.tags, vec!["a".to_string(), "b".to_string()]);
+}
+
+#[test]
+fn config_overrides_nested_enum_configs() {
+    let config = experiment()
+        .with_overrides(["--schedule={\"Linear\": {\"steps\": 10}}"])
+        .unwrap();
+    assert_eq!(config.schedule, TestScheduleConfig::Linear { steps: 10 });
+
+    let config = config
+        .with_overrides(["--schedule.Linear.steps=20"])
+        .unwrap();
+    assert_eq!(config.schedule, TestScheduleConfig::Linear { steps: 20 });
+
+    let config = config.with_overrides(["--schedule=Constant"]).unwrap();
+    assert_eq!(config.schedule, TestScheduleConfig::Constant);
+}
+
+#[test]
+fn config_rejects_invalid_overrides() {
+    let result = experiment().with_overrides(["--optimizer.momentum=0.9"]);
+    assert!(matches!(result, Err(ConfigError::InvalidOverride(_))));
+
+    let result = experiment().with_overrides(["optimizer.learning_rate=0.1"]);
+    assert!(matches!(result, Err(ConfigError::InvalidOverride(_))));
+
+    let result = experiment().with_overrides(["--batch_size=large"]);
+    assert!(matches!(result, Err(ConfigError::InvalidOverride(_))));
+
+    let result = experiment().with_overrides(["--optimizer.learning_rate=-1"]);
+    assert!(matches!(result, Err(ConfigError::InvalidValue { .. })));
+}
+
+#[test]
+fn config_generates_json_schema() {
+    let schema = TestExperimentConfig::json_schema();
+
+    assert_eq!(schema["title"], "TestExperimentConfig");
+    assert_eq!(
+        schema["required"],
+        serde_json::json!(["tags", "optimizer", "batch_size", "schedule"])
+    );
+    assert_eq!(schema["properties"]["tags"]["minItems"], 1);
+    assert_eq!(schema["properties"]["batch_size"]["default"], 2);
+
+    let optimizer = &schema["properties"]["optimizer"];
+    assert_eq!(optimizer["description"], "Optimizer settings.");
+    let learning_rate = &optimizer["properties"]["learning_rate"];
+    assert_eq!(learning_rate["type"], "number");
+    assert_eq!(learning_rate["minimum"], 0.0);
+    assert_eq!(learning_rate["maximum"], 1.0);
+    assert_eq!(learning_rate["description"], "The learning rate.");
+    assert_eq!(
+        optimizer["properties"]["name"]["enum"],
+        serde_json::json!(["adam", "sgd"])
+    );
+    assert_eq!(
+        optimizer["properties"]["warmup_steps"]["anyOf"][0]["minimum"],
+        1
+    );
+
+    let variants = schema["properties"]["schedule"]["oneOf"]
+        .as_array()
+        .unwrap();
+    assert_eq!(variants[0], serde_json::json!({ "const": "Constant" }));
+    assert_eq!(
+        variants[1]["properties"]["Linear"]["required"],
+        serde_json::json!(["steps"])
+    );
+
+    let document = config_json_schema::<TestExperimentConfig>();
+    assert!(document.contains("https://json-schema.org/draft/2020-12/schema"));
+}
@@ -64,7 +64,7 @@ pub fn attention<R: CubeRuntime>(
         }
         AttentionStrategy::Fallback => {
             return Ok(attention_fallback::<CubeBackend<R>>(
-                query, key, value, mask, attn_bias, options,
+                query, key, value, mask, attn_bias, None, options,
             ));
         }
         #[cfg(feature = "autotune")]
@@ -96,7 +96,7 @@ pub fn attention<R: CubeRuntime>(
         flash,
     ) {
         Err(AttentionSetupError::InvalidConfig(_)) => Ok(attention_fallback::<CubeBackend<R>>(
-            query, key, value, mask, attn_bias, options,
+            query, key, value, mask, attn_bias, None, options,
         )),
         other => other,
     }
@@ -1,9 +1,11 @@
 mod base;
 #[cfg(feature = "autotune")]
 mod bounds;
+mod positional;
 #[cfg(feature = "autotune")]
 mod tune;
 
 pub use base::*;
+pub use positional::*;
 #[cfg(feature = "autotune")]
 pub use tune::*;
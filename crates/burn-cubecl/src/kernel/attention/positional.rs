@@ -0,0 +1,312 @@
+use burn_backend::cubecl::dtype_to_storage_type;
+use burn_backend::{
+    DType, Shape, TensorData,
+    ops::{AttentionModuleOptions, attention::packed_sequence_ids},
+};
+use cubecl::{calculate_cube_count_elemwise, prelude::*};
+
+use crate::{
+    CubeBackend, CubeRuntime,
+    kernel::{attention::init_attention_output, utils::address_type},
+    ops::{expand, from_data},
+    tensor::CubeTensor,
+};
+
+/// Number of keys scored at once by each query row.
+const TILE_KV: usize = 32;
+
+#[derive(CubeLaunch, CubeType)]
+struct PositionalAttentionArgs {
+    scale: f32,
+    softcap: f32,
+    window: u32,
+}
+
+/// Flash attention with position-dependent masking and biases.
+///
+/// Each thread owns one query row and walks the keys in tiles of `tile_kv`, with an online
+/// softmax: the scores of a tile are computed, masked and biased in registers, then folded into
+/// the running maximum, sum and value accumulator, so the `[seq_q, seq_k]` scores are never
+/// materialized.
+///
+/// Query and key positions are aligned at the bottom-right corner, like the causal mask. A key
+/// is masked when it is in the future of the query (`causal`), at least `window` positions away
+/// (`has_window`), in another packed sequence, or masked by `mask`. ALiBi subtracts the head's
+/// slope times the distance from the score.
+///
+/// Masked scores use a finite sentinel, since WGSL can't express `-inf`. Rows without any
+/// unmasked key are written as zeros, like the fallback.
+#[cube(launch, address_type = "dynamic")]
+fn positional_attention_kernel<F: Float, B: Int, I: Int>(
+    query: &Tensor<F>,                        // [batch, heads, seq_q, head_dim]
+    key: &Tensor<F>,                          // [batch, kv_heads, seq_k, head_dim]
+    value: &Tensor<F>,                        // [batch, kv_heads, seq_k, val_dim]
+    mask: ComptimeOption<&Tensor<B>>,         // [batch, heads, seq_q, seq_k]
+    attn_bias: ComptimeOption<&Tensor<F>>,    // [batch, heads, seq_q, seq_k]
+    sequence_ids: ComptimeOption<&Tensor<I>>, // [seq_k]
+    alibi_slopes: ComptimeOption<&Tensor<F>>, // [heads]
+    output: &mut Tensor<F>,                   // [batch, heads, seq_q, val_dim]
+    args: &PositionalAttentionArgs,
+    #[comptime] tile_kv: usize,
+    #[comptime] val_dim: usize,
+    #[comptime] causal: bool,
+    #[comptime] has_softcap: bool,
+    #[comptime] has_window: bool,
+    #[define(F, B, I)] _dtypes: [ElemType; 3],
+) {
+    let heads = query.shape(1);
+    let seq_q = query.shape(2);
+    let head_dim = query.shape(3);
+    let seq_k = key.shape(2);
+    if ABSOLUTE_POS >= query.shape(0) * heads * seq_q {
+        terminate!();
+    }
+
+    let qi = ABSOLUTE_POS % seq_q;
+    let batch_head = ABSOLUTE_POS / seq_q;
+    let head = batch_head % heads;
+    let batch = batch_head / heads;
+    // Grouped-query attention: consecutive query heads share a key/value head.
+    let kv_head = head / (heads / key.shape(1));
+
+    let q_base = batch * query.stride(0) + head * query.stride(1) + qi * query.stride(2);
+    let k_base = batch * key.stride(0) + kv_head * key.stride(1);
+    let v_base = batch * value.stride(0) + kv_head * value.stride(1);
+    let mask_base = mask
+        .as_ref()
+        .map(|mask| batch * mask.stride(0) + head * mask.stride(1) + qi * mask.stride(2));
+    let bias_base = attn_bias
+        .as_ref()
+        .map(|bias| batch * bias.stride(0) + head * bias.stride(1) + qi * bias.stride(2));
+    #[comptime]
+    let slope = match &alibi_slopes {
+        ComptimeOption::Some(slopes) => f32::cast_from(slopes[head * slopes.stride(0)]),
+        ComptimeOption::None => f32::new(0.0),
+    };
+    #[comptime]
+    let query_id = match &sequence_ids {
+        ComptimeOption::Some(ids) => u32::cast_from(ids[qi * ids.stride(0)]),
+        ComptimeOption::None => 0u32,
+    };
+
+    let query_position = i32::cast_from(qi + seq_k) - i32::cast_from(seq_q);
+    let window = i32::cast_from(args.window);
+    let masked = f32::new(-3.0e38);
+
+    let mut scores = Array::<f32>::new(tile_kv);
+    let mut acc = Array::<f32>::new(val_dim);
+    for d in 0..val_dim {
+        acc[d] = f32::new(0.0);
+    }
+    let mut row_max = masked;
+    let mut row_sum = f32::new(0.0);
+
+    let mut tile_start = 0;
+    while tile_start < seq_k {
+        let mut tile_len = seq_k - tile_start;
+        if tile_len > tile_kv {
+            tile_len = tile_kv;
+        }
+
+        // Score the tile, masking and biasing each key by its position.
+        let mut tile_max = masked;
+        for t in 0..tile_len {
+            let kj = tile_start + t;
+            let relative = query_position - i32::cast_from(kj);
+            let mut distance = relative;
+            if relative < 0 {
+                distance = -relative;
+            }
+
+            let mut visible = true;
+            if causal && relative < 0 {
+                visible = false;
+            }
+            if has_window && distance >= window {
+                visible = false;
+            }
+            #[comptime]
+            let other_sequence = match &sequence_ids {
+                ComptimeOption::Some(ids) => u32::cast_from(ids[kj * ids.stride(0)]) != query_id,
+                ComptimeOption::None => false,
+            };
+            if other_sequence {
+                visible = false;
+            }
+            #[comptime]
+            let user_masked = match mask.zip::<usize>(mask_base) {
+                ComptimeOption::Some((mask, base)) => {
+                    u32::cast_from(mask[base + kj * mask.stride(3)]) != 0
+                }
+                ComptimeOption::None => false,
+            };
+            if user_masked {
+                visible = false;
+            }
+
+            let mut score = masked;
+            if visible {
+                let k_row = k_base + kj * key.stride(2);
+                let mut dot = f32::new(0.0);
+                for d in 0..head_dim {
+                    dot += f32::cast_from(query[q_base + d * query.stride(3)])
+                        * f32::cast_from(key[k_row + d * key.stride(3)]);
+                }
+                score = dot * args.scale;
+                if has_softcap {
+                    score = args.softcap * (score / args.softcap).tanh();
+                }
+                score -= slope * f32::cast_from(distance);
+                #[comptime]
+                let bias = match attn_bias.zip::<usize>(bias_base) {
+                    ComptimeOption::Some((bias, base)) => {
+                        f32::cast_from(bias[base + kj * bias.stride(3)])
+                    }
+                    ComptimeOption::None => f32::new(0.0),
+                };
+                score += bias;
+                // A `-inf` bias masks the key too.
+                if score < masked {
+                    score = masked;
+                }
+            }
+
+            scores[t] = score;
+            if score > tile_max {
+                tile_max = score;
+            }
+        }
+
+        // Fold the tile into the online softmax.
+        if tile_max > masked {
+            let mut new_max = row_max;
+            if tile_max > new_max {
+                new_max = tile_max;
+            }
+            let correction = (row_max - new_max).exp();
+            row_sum *= correction;
+            for d in 0..val_dim {
+                acc[d] *= correction;
+            }
+
+            for t in 0..tile_len {
+                let score = scores[t];
+                if score > masked {
+                    let weight = (score - new_max).exp();
+                    row_sum += weight;
+                    let v_row = v_base + (tile_start + t) * value.stride(2);
+                    for d in 0..val_dim {
+                        acc[d] += weight * f32::cast_from(value[v_row + d * value.stride(3)]);
+                    }
+                }
+            }
+            row_max = new_max;
+        }
+
+        tile_start += tile_kv;
+    }
+
+    let mut inv_sum = f32::new(0.0);
+    if row_sum > f32::new(0.0) {
+        inv_sum = f32::new(1.0) / row_sum;
+    }
+    let out_base = batch * output.stride(0) + head * output.stride(1) + qi * output.stride(2);
+    for d in 0..val_dim {
+        output[out_base + d * output.stride(3)] = F::cast_from(acc[d] * inv_sum);
+    }
+}
+
+/// Launch the flash attention kernel supporting sliding windows, ALiBi and packed sequences.
+///
+/// Also applies the causal mask, the custom scale, the softcap, the bool mask and the additive
+/// bias, all inside the key tiles.
+#[allow(clippy::too_many_arguments)]
+pub fn positional_attention<R: CubeRuntime>(
+    query: CubeTensor<R>,
+    key: CubeTensor<R>,
+    value: CubeTensor<R>,
+    mask: Option<CubeTensor<R>>,
+    attn_bias: Option<CubeTensor<R>>,
+    seq_offsets: Option<CubeTensor<R>>,
+    options: AttentionModuleOptions,
+) -> CubeTensor<R> {
+    let [batch_size, num_heads, seq_q, head_dim] = query.meta.shape().dims::<4>();
+    let seq_k = key.meta.shape()[2];
+    let val_dim = value.meta.shape()[3];
+    let scores_shape = Shape::new([batch_size, num_heads, seq_q, seq_k]);
+
+    if let Some(softcap) = options.softcap {
+        assert!(softcap > 0.0, "softcap must be positive, got {softcap}");
+    }
+    if let Some(window) = options.sliding_window {
+        assert!(window > 0, "sliding_window must be non-zero");
+    }
+
+    let client = query.client.clone();
+    let device = query.device.clone();
+    let dtype = query.dtype;
+
+    let sequence_ids = seq_offsets.map(|seq_offsets| {
+        assert_eq!(
+            seq_q, seq_k,
+            "Packed sequences need the same query and key sequence length"
+        );
+        packed_sequence_ids::<CubeBackend<R>>(seq_offsets, seq_k)
+    });
+    let alibi_slopes = options.alibi_slopes(num_heads).map(|slopes| {
+        let slopes: Vec<f32> = slopes.into_iter().map(|slope| slope as f32).collect();
+        from_data::<R>(
+            TensorData::new(slopes, [num_heads]).convert_dtype(dtype),
+            &device,
+        )
+    });
+    // Broadcast masks and biases are read through zero strides.
+    let mask = mask.map(|mask| expand(mask, scores_shape.clone()));
+    let attn_bias = attn_bias.map(|bias| expand(bias, scores_shape));
+
+    let output = init_attention_output(&query, &value);
+    let mask_dtype = mask.as_ref().map(|mask| mask.dtype).unwrap_or(DType::U8);
+    let ids_dtype = sequence_ids
+        .as_ref()
+        .map(|ids| ids.dtype)
+        .unwrap_or(DType::I32);
+
+    let num_rows = batch_size * num_heads * seq_q;
+    let cube_dim = CubeDim::new(&client, num_rows);
+    let cube_count = calculate_cube_count_elemwise(&client, num_rows, cube_dim);
+    let scale = options
+        .scale
+        .unwrap_or_else(|| 1.0 / (head_dim as f64).sqrt());
+
+    positional_attention_kernel::launch::<R>(
+        &client,
+        cube_count,
+        cube_dim,
+        address_type!(query, key, value, mask, attn_bias, output),
+        query.into_tensor_arg(),
+        key.into_tensor_arg(),
+        value.into_tensor_arg(),
+        mask.map(|mask| mask.into_tensor_arg()).into(),
+        attn_bias.map(|bias| bias.into_tensor_arg()).into(),
+        sequence_ids.map(|ids| ids.into_tensor_arg()).into(),
+        alibi_slopes.map(|slopes| slopes.into_tensor_arg()).into(),
+        output.clone().into_tensor_arg(),
+        PositionalAttentionArgsLaunch::new(
+            scale as f32,
+            options.softcap.unwrap_or(1.0) as f32,
+            options.sliding_window.unwrap_or(0) as u32,
+        ),
+        TILE_KV,
+        val_dim,
+        options.is_causal,
+        options.softcap.is_some(),
+        options.sliding_window.is_some(),
+        [
+            dtype_to_storage_type(dtype),
+            dtype_to_storage_type(mask_dtype),
+            dtype_to_storage_type(ids_dtype),
+        ],
+    );
+
+    output
+}
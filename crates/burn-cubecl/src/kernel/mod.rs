@@ -49,6 +49,8 @@ pub mod prng;
 pub mod quantization;
 /// Reduction algorithms
 pub mod reduce;
+/// Selective state-space scan kernels
+pub mod selective_scan;
 
 pub(crate) use clamp::*;
 pub(crate) use comparison::*;
@@ -0,0 +1,266 @@
+use burn_backend::cubecl::dtype_to_storage_type;
+use cubecl::{calculate_cube_count_elemwise, prelude::*};
+
+use crate::{
+    CubeRuntime,
+    kernel::{into_contiguous, utils::address_type},
+    ops::numeric::{empty_device_dtype, zeros_client},
+    tensor::CubeTensor,
+};
+use burn_backend::{Shape, TensorMetadata};
+
+/// Selective scan forward kernel.
+///
+/// Each thread owns one `(batch, channel)` pair and runs the recurrence
+/// sequentially over time. The `d_state` hidden vector lives in the `state`
+/// scratch buffer (zero-initialized by the launcher) so the kernel needs no
+/// comptime bound on the state size.
+#[cube(launch, address_type = "dynamic")]
+fn selective_scan_kernel<F: Float>(
+    x: &Tensor<F>,          // [B, L, D]
+    delta: &Tensor<F>,      // [B, L, D]
+    a: &Tensor<F>,          // [D, N]
+    b: &Tensor<F>,          // [B, L, N]
+    c: &Tensor<F>,          // [B, L, N]
+    state: &mut Tensor<F>,  // [B, D, N]
+    output: &mut Tensor<F>, // [B, L, D]
+    #[define(F)] _dtype: ElemType,
+) {
+    let seq_len = x.shape(1);
+    let d_inner = x.shape(2);
+    let d_state = a.shape(1);
+    if ABSOLUTE_POS >= x.shape(0) * d_inner {
+        terminate!();
+    }
+
+    let batch = ABSOLUTE_POS / d_inner;
+    let d = ABSOLUTE_POS % d_inner;
+    let state_offset = ABSOLUTE_POS * d_state;
+
+    for t in 0..seq_len {
+        let row = batch * seq_len + t;
+        let dt = delta[row * d_inner + d];
+        let dt_x = dt * x[row * d_inner + d];
+
+        let mut y = F::new(0.0_f32);
+        for n in 0..d_state {
+            let h = (dt * a[d * d_state + n]).exp() * state[state_offset + n]
+                + dt_x * b[row * d_state + n];
+            state[state_offset + n] = h;
+            y += c[row * d_state + n] * h;
+        }
+        output[row * d_inner + d] = y;
+    }
+}
+
+/// Selective scan backward kernel.
+///
+/// Same thread layout as the forward kernel. Each thread first replays the
+/// forward recurrence into `states`, then walks the sequence in reverse while
+/// carrying the adjoint of the hidden state in `state_grad`.
+///
+/// Gradients that sum over the thread grid are written per thread and reduced
+/// by the launcher: `a_grad` holds one `[D, N]` slab per batch element and
+/// `b_grad`/`c_grad` hold one `[N]` row per `(batch, step, channel)`.
+#[cube(launch, address_type = "dynamic")]
+fn selective_scan_backward_kernel<F: Float>(
+    x: &Tensor<F>,              // [B, L, D]
+    delta: &Tensor<F>,          // [B, L, D]
+    a: &Tensor<F>,              // [D, N]
+    b: &Tensor<F>,              // [B, L, N]
+    c: &Tensor<F>,              // [B, L, N]
+    output_grad: &Tensor<F>,    // [B, L, D]
+    states: &mut Tensor<F>,     // [B, D, L, N]
+    state_grad: &mut Tensor<F>, // [B, D, N]
+    x_grad: &mut Tensor<F>,     // [B, L, D]
+    delta_grad: &mut Tensor<F>, // [B, L, D]
+    a_grad: &mut Tensor<F>,     // [B, D, N]
+    b_grad: &mut Tensor<F>,     // [B, L, D, N]
+    c_grad: &mut Tensor<F>,     // [B, L, D, N]
+    #[define(F)] _dtype: ElemType,
+) {
+    let seq_len = x.shape(1);
+    let d_inner = x.shape(2);
+    let d_state = a.shape(1);
+    if ABSOLUTE_POS >= x.shape(0) * d_inner {
+        terminate!();
+    }
+
+    let batch = ABSOLUTE_POS / d_inner;
+    let d = ABSOLUTE_POS % d_inner;
+    let pair_offset = ABSOLUTE_POS * d_state;
+    let states_offset = ABSOLUTE_POS * seq_len * d_state;
+
+    for t in 0..seq_len {
+        let row = batch * seq_len + t;
+        let dt = delta[row * d_inner + d];
+        let dt_x = dt * x[row * d_inner + d];
+        for n in 0..d_state {
+            let mut prev = F::new(0.0_f32);
+            if t > 0 {
+                prev = states[states_offset + (t - 1) * d_state + n];
+            }
+            states[states_offset + t * d_state + n] =
+                (dt * a[d * d_state + n]).exp() * prev + dt_x * b[row * d_state + n];
+        }
+    }
+
+    for t_rev in 0..seq_len {
+        let t = seq_len - 1 - t_rev;
+        let row = batch * seq_len + t;
+        let dt = delta[row * d_inner + d];
+        let xv = x[row * d_inner + d];
+        let grad_y = output_grad[row * d_inner + d];
+
+        let mut grad_dt = F::new(0.0_f32);
+        let mut grad_x = F::new(0.0_f32);
+        for n in 0..d_state {
+            let bc = row * d_state + n;
+            let partial = (row * d_inner + d) * d_state + n;
+            c_grad[partial] = grad_y * states[states_offset + t * d_state + n];
+            let grad_h = state_grad[pair_offset + n] + grad_y * c[bc];
+
+            let a_dn = a[d * d_state + n];
+            let decay = (dt * a_dn).exp();
+            let mut prev = F::new(0.0_f32);
+            if t > 0 {
+                prev = states[states_offset + (t - 1) * d_state + n];
+            }
+            let grad_decay = grad_h * prev * decay;
+
+            grad_dt += grad_decay * a_dn + grad_h * b[bc] * xv;
+            grad_x += grad_h * dt * b[bc];
+            a_grad[pair_offset + n] += grad_decay * dt;
+            b_grad[partial] = grad_h * dt * xv;
+
+            // Carry the adjoint back to h[t-1] through the transition.
+            state_grad[pair_offset + n] = grad_h * decay;
+        }
+        delta_grad[row * d_inner + d] = grad_dt;
+        x_grad[row * d_inner + d] = grad_x;
+    }
+}
+
+/// Host entry point for the selective scan forward kernel.
+pub fn selective_scan<R: CubeRuntime>(
+    x: CubeTensor<R>,
+    delta: CubeTensor<R>,
+    a: CubeTensor<R>,
+    b: CubeTensor<R>,
+    c: CubeTensor<R>,
+) -> CubeTensor<R> {
+    // The kernel indexes with contiguous offsets.
+    let x = into_contiguous(x);
+    let delta = into_contiguous(delta);
+    let a = into_contiguous(a);
+    let b = into_contiguous(b);
+    let c = into_contiguous(c);
+
+    let [batch_size, _seq_len, d_inner] = x.shape().dims::<3>();
+    let d_state = a.shape().dims::<2>()[1];
+
+    let client = x.client.clone();
+    let device = x.device.clone();
+    let dtype = x.dtype;
+    let output = empty_device_dtype::<R>(client.clone(), device.clone(), x.shape(), dtype);
+    let state = zeros_client::<R>(
+        client.clone(),
+        device,
+        Shape::new([batch_size, d_inner, d_state]),
+        dtype,
+    );
+
+    let num_pairs = batch_size * d_inner;
+    let cube_dim = CubeDim::new(&client, num_pairs);
+    let cube_count = calculate_cube_count_elemwise(&client, num_pairs, cube_dim);
+
+    selective_scan_kernel::launch::<R>(
+        &client,
+        cube_count,
+        cube_dim,
+        address_type!(x, delta, a, b, c, state, output),
+        x.into_tensor_arg(),
+        delta.into_tensor_arg(),
+        a.into_tensor_arg(),
+        b.into_tensor_arg(),
+        c.into_tensor_arg(),
+        state.into_tensor_arg(),
+        output.clone().into_tensor_arg(),
+        dtype_to_storage_type(dtype),
+    );
+
+    output
+}
+
+/// Host entry point for the selective scan backward kernel.
+///
+/// Returns `(x_grad, delta_grad, a_grad, b_grad, c_grad)` where `x_grad` and
+/// `delta_grad` are final, while the others are per-thread partials with shapes
+/// `[B, D, N]`, `[B, L, D, N]` and `[B, L, D, N]` that still need to be summed
+/// over dimensions 0, 2 and 2 respectively.
+pub fn selective_scan_backward<R: CubeRuntime>(
+    x: CubeTensor<R>,
+    delta: CubeTensor<R>,
+    a: CubeTensor<R>,
+    b: CubeTensor<R>,
+    c: CubeTensor<R>,
+    output_grad: CubeTensor<R>,
+) -> (
+    CubeTensor<R>,
+    CubeTensor<R>,
+    CubeTensor<R>,
+    CubeTensor<R>,
+    CubeTensor<R>,
+) {
+    let x = into_contiguous(x);
+    let delta = into_contiguous(delta);
+    let a = into_contiguous(a);
+    let b = into_contiguous(b);
+    let c = into_contiguous(c);
+    let output_grad = into_contiguous(output_grad);
+
+    let [batch_size, seq_len, d_inner] = x.shape().dims::<3>();
+    let d_state = a.shape().dims::<2>()[1];
+
+    let client = x.client.clone();
+    let device = x.device.clone();
+    let dtype = x.dtype;
+    let empty =
+        |shape: Shape| empty_device_dtype::<R>(client.clone(), device.clone(), shape, dtype);
+    let zeros = |shape: Shape| zeros_client::<R>(client.clone(), device.clone(), shape, dtype);
+
+    let states = empty(Shape::new([batch_size, d_inner, seq_len, d_state]));
+    let state_grad = zeros(Shape::new([batch_size, d_inner, d_state]));
+    let x_grad = empty(x.shape());
+    let delta_grad = empty(x.shape());
+    let a_grad = zeros(Shape::new([batch_size, d_inner, d_state]));
+    let b_grad = empty(Shape::new([batch_size, seq_len, d_inner, d_state]));
+    let c_grad = empty(Shape::new([batch_size, seq_len, d_inner, d_state]));
+
+    let num_pairs = batch_size * d_inner;
+    let cube_dim = CubeDim::new(&client, num_pairs);
+    let cube_count = calculate_cube_count_elemwise(&client, num_pairs, cube_dim);
+
+    selective_scan_backward_kernel::launch::<R>(
+        &client,
+        cube_count,
+        cube_dim,
+        address_type!(states, b_grad, c_grad),
+        x.into_tensor_arg(),
+        delta.into_tensor_arg(),
+        a.into_tensor_arg(),
+        b.into_tensor_arg(),
+        c.into_tensor_arg(),
+        output_grad.into_tensor_arg(),
+        states.into_tensor_arg(),
+        state_grad.into_tensor_arg(),
+        x_grad.clone().into_tensor_arg(),
+        delta_grad.clone().into_tensor_arg(),
+        a_grad.clone().into_tensor_arg(),
+        b_grad.clone().into_tensor_arg(),
+        c_grad.clone().into_tensor_arg(),
+        dtype_to_storage_type(dtype),
+    );
+
+    (x_grad, delta_grad, a_grad, b_grad, c_grad)
+}
@@ -7,7 +7,8 @@ use burn_backend::{
     TensorMetadata,
     ops::{
         AttentionModuleOptions, ConvOptions, ConvTransposeOptions, DeformConv2dBackward,
-        DeformConvOptions, InterpolateOptions, MaxPool2dBackward, MaxPool2dWithIndices, ModuleOps,
+        DeformConvOptions, FloatTensorOps, InterpolateOptions, MaxPool2dBackward,
+        MaxPool2dWithIndices, ModuleOps, SelectiveScanBackward,
     },
 };
 use burn_std::IntDType;
@@ -329,12 +330,29 @@ where
         value: FloatTensor<Self>,
         mask: Option<BoolTensor<Self>>,
         attn_bias: Option<FloatTensor<Self>>,
+        seq_offsets: Option<IntTensor<Self>>,
         options: AttentionModuleOptions,
     ) -> FloatTensor<Self> {
+        // Windows, ALiBi and packed sequences are applied in the key tiles of their own kernel.
+        if options.sliding_window.is_some()
+            || options.alibi_max_bias.is_some()
+            || seq_offsets.is_some()
+        {
+            return kernel::attention::positional_attention(
+                query,
+                key,
+                value,
+                mask,
+                attn_bias,
+                seq_offsets,
+                options,
+            );
+        }
+
         // Fall back to naive attention for features the flash kernel doesn't support.
         if attn_bias.is_some() || options.softcap.is_some() || options.scale.is_some() {
             return burn_backend::ops::attention::attention_fallback::<Self>(
-                query, key, value, mask, attn_bias, options,
+                query, key, value, mask, attn_bias, None, options,
             );
         }
 
@@ -391,6 +409,41 @@ where
         )
     }
 
+    fn selective_scan(
+        x: FloatTensor<Self>,
+        delta: FloatTensor<Self>,
+        a: FloatTensor<Self>,
+        b: FloatTensor<Self>,
+        c: FloatTensor<Self>,
+    ) -> FloatTensor<Self> {
+        kernel::selective_scan::selective_scan(x, delta, a, b, c)
+    }
+
+    fn has_selective_scan_backward() -> bool {
+        true
+    }
+
+    fn selective_scan_backward(
+        x: FloatTensor<Self>,
+        delta: FloatTensor<Self>,
+        a: FloatTensor<Self>,
+        b: FloatTensor<Self>,
+        c: FloatTensor<Self>,
+        output_grad: FloatTensor<Self>,
+    ) -> SelectiveScanBackward<Self> {
+        let a_shape = a.shape();
+        let b_shape = b.shape();
+        let (x_grad, delta_grad, a_grad, b_grad, c_grad) =
+            kernel::selective_scan::selective_scan_backward(x, delta, a, b, c, output_grad);
+
+        // The kernel leaves the cross-thread reductions to us.
+        let a_grad = Self::float_reshape(Self::float_sum_dim(a_grad, 0), a_shape);
+        let b_grad = Self::float_reshape(Self::float_sum_dim(b_grad, 2), b_shape.clone());
+        let c_grad = Self::float_reshape(Self::float_sum_dim(c_grad, 2), b_shape);
+
+        SelectiveScanBackward::new(x_grad, delta_grad, a_grad, b_grad, c_grad)
+    }
+
     fn rfft(
         signal: FloatTensor<Self>,
         dim: usize,
@@ -1,6 +1,6 @@
-use super::ConfigEnumAnalyzer;
+use super::{ConfigEnumAnalyzer, ConfigFieldAttributes, doc_lines};
 use crate::config::ConfigStructAnalyzer;
-use crate::shared::{attribute::AttributeItem, field::FieldTypeAnalyzer};
+use crate::shared::field::FieldTypeAnalyzer;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Field, Ident};
@@ -27,30 +27,33 @@ impl ConfigAnalyzerFactory {
 
     pub fn create_analyzer(&self, item: &syn::DeriveInput) -> Box<dyn ConfigAnalyzer> {
         let name = item.ident.clone();
+        let docs = doc_lines(&item.attrs);
         let config_type = parse_asm(item);
 
         match config_type {
-            ConfigType::Struct(data) => Box::new(self.create_struct_analyzer(name, data)),
-            ConfigType::Enum(data) => Box::new(self.create_enum_analyzer(name, data)),
+            ConfigType::Struct(data) => Box::new(self.create_struct_analyzer(name, docs, data)),
+            ConfigType::Enum(data) => Box::new(self.create_enum_analyzer(name, docs, data)),
         }
     }
 
-    fn create_struct_analyzer(&self, name: Ident, fields: Vec<Field>) -> ConfigStructAnalyzer {
+    fn create_struct_analyzer(
+        &self,
+        name: Ident,
+        docs: Vec<String>,
+        fields: Vec<Field>,
+    ) -> ConfigStructAnalyzer {
         let fields = fields.into_iter().map(FieldTypeAnalyzer::new);
 
         let mut fields_required = Vec::new();
         let mut fields_option = Vec::new();
         let mut fields_default = Vec::new();
+        let mut constraints = Vec::new();
 
         for field in fields {
-            let attributes: Vec<AttributeItem> = field
-                .attributes()
-                .filter(|attr| attr.has_name("config"))
-                .map(|attr| attr.item())
-                .collect();
+            let attributes = ConfigFieldAttributes::parse(&field);
+            constraints.push((field.ident(), attributes.constraints));
 
-            if !attributes.is_empty() {
-                let item = attributes.first().unwrap().clone();
+            if let Some(item) = attributes.default {
                 fields_default.push((field.clone(), item));
                 continue;
             }
@@ -63,11 +66,23 @@ impl ConfigAnalyzerFactory {
             fields_required.push(field.clone());
         }
 
-        ConfigStructAnalyzer::new(name, fields_required, fields_option, fields_default)
+        ConfigStructAnalyzer::new(
+            name,
+            docs,
+            fields_required,
+            fields_option,
+            fields_default,
+            constraints,
+        )
     }
 
-    fn create_enum_analyzer(&self, name: Ident, data: syn::DataEnum) -> ConfigEnumAnalyzer {
-        ConfigEnumAnalyzer::new(name, data)
+    fn create_enum_analyzer(
+        &self,
+        name: Ident,
+        docs: Vec<String>,
+        data: syn::DataEnum,
+    ) -> ConfigEnumAnalyzer {
+        ConfigEnumAnalyzer::new(name, docs, data)
     }
 }
 
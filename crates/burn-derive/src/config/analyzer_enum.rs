@@ -1,17 +1,155 @@
 use crate::shared::enum_variant::map_enum_variant;
 
-use super::ConfigAnalyzer;
-use proc_macro2::{Ident, TokenStream};
+use super::{ConfigAnalyzer, doc_lines};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 
 pub struct ConfigEnumAnalyzer {
     name: Ident,
+    docs: Vec<String>,
     data: syn::DataEnum,
 }
 
 impl ConfigEnumAnalyzer {
-    pub fn new(name: Ident, data: syn::DataEnum) -> Self {
-        Self { name, data }
+    pub fn new(name: Ident, docs: Vec<String>, data: syn::DataEnum) -> Self {
+        Self { name, docs, data }
+    }
+
+    /// The fields of a variant: the identifiers they are bound to in a pattern, and their path
+    /// from the variant as serialized (`Variant.field`, or `Variant.0` in a tuple of several).
+    fn variant_fields(variant: &syn::Variant) -> Vec<(Ident, String, &syn::Field)> {
+        let variant_name = variant.ident.to_string();
+        match &variant.fields {
+            syn::Fields::Named(fields) => fields
+                .named
+                .iter()
+                .map(|field| {
+                    let ident = field.ident.clone().unwrap();
+                    let path = format!("{variant_name}.{ident}");
+                    (ident, path, field)
+                })
+                .collect(),
+            syn::Fields::Unnamed(fields) => {
+                let newtype = fields.unnamed.len() == 1;
+                fields
+                    .unnamed
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        let ident = Ident::new(&format!("arg_{i}"), Span::call_site());
+                        let path = if newtype {
+                            variant_name.clone()
+                        } else {
+                            format!("{variant_name}.{i}")
+                        };
+                        (ident, path, field)
+                    })
+                    .collect()
+            }
+            syn::Fields::Unit => Vec::new(),
+        }
+    }
+
+    fn gen_validate_fn(&self) -> TokenStream {
+        let variants = self.data.variants.iter().map(|variant| {
+            let variant_name = &variant.ident;
+            let (inputs, _) = map_enum_variant(variant, |ident| quote! { #ident });
+            let checks = Self::variant_fields(variant)
+                .into_iter()
+                .map(|(ident, path, _)| {
+                    quote! {
+                        (&&burn::config::__private::Nested(#ident)).validate_nested(#path)?;
+                    }
+                });
+
+            quote! { Self::#variant_name #inputs => { #(#checks)* } }
+        });
+
+        quote! {
+            fn validate(&self) -> Result<(), burn::config::ConfigError> {
+                #[allow(unused_imports)]
+                use burn::config::__private::{ValidateConfig as _, ValidateOther as _};
+
+                match self {
+                    #(#variants),*
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn gen_schema_fn(&self) -> TokenStream {
+        let name = self.name.to_string();
+        let docs = &self.docs;
+        let variants = self.data.variants.iter().map(|variant| {
+            let variant_name = variant.ident.to_string();
+            let variant_docs = doc_lines(&variant.attrs);
+            let field_schema = |field: &syn::Field| {
+                let ty = &field.ty;
+                let field_docs = doc_lines(&field.attrs);
+                quote! {
+                    {
+                        let mut schema = (&&burn::config::__private::SchemaOf::<#ty>::new()).schema();
+                        burn::config::__private::describe(&mut schema, &[#(#field_docs),*]);
+                        schema
+                    }
+                }
+            };
+
+            let schema = match &variant.fields {
+                syn::Fields::Unit => quote! {
+                    burn::config::__private::unit_variant(#variant_name)
+                },
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    let content = field_schema(&fields.unnamed[0]);
+                    quote! { burn::config::__private::variant(#variant_name, #content) }
+                }
+                syn::Fields::Unnamed(fields) => {
+                    let items = fields.unnamed.iter().map(field_schema);
+                    quote! {
+                        burn::config::__private::variant(
+                            #variant_name,
+                            burn::config::__private::tuple([#(#items),*]),
+                        )
+                    }
+                }
+                syn::Fields::Named(fields) => {
+                    let properties = fields.named.iter().map(|field| {
+                        let field_name = field.ident.as_ref().unwrap().to_string();
+                        let schema = field_schema(field);
+                        let required = !crate::shared::field::FieldTypeAnalyzer::new(field.clone())
+                            .is_of_type(&["Option"]);
+                        quote! { (#field_name, #schema, #required) }
+                    });
+                    quote! {
+                        burn::config::__private::variant(
+                            #variant_name,
+                            burn::config::__private::object([#(#properties),*]),
+                        )
+                    }
+                }
+            };
+
+            quote! {
+                {
+                    let mut schema = #schema;
+                    burn::config::__private::describe(&mut schema, &[#(#variant_docs),*]);
+                    schema
+                }
+            }
+        });
+
+        quote! {
+            fn json_schema() -> burn::config::__private::Value {
+                #[allow(unused_imports)]
+                use burn::config::__private::{SchemaOther as _, SchemaWithTrait as _};
+
+                let mut schema = burn::config::__private::one_of([#(#variants),*]);
+                burn::config::__private::set(&mut schema, "title", #name);
+                burn::config::__private::describe(&mut schema, &[#(#docs),*]);
+                schema
+            }
+        }
     }
 
     fn serde_enum_ident(&self) -> Ident {
@@ -132,9 +270,19 @@ impl ConfigAnalyzer for ConfigEnumAnalyzer {
 
     fn gen_config_impl(&self) -> TokenStream {
         let name = &self.name;
+        let validate = self.gen_validate_fn();
+        let schema = self.gen_schema_fn();
 
         quote! {
             impl burn::config::Config for #name {
+                #validate
+                #schema
+            }
+
+            impl burn::config::__private::FieldSchema for #name {
+                fn field_schema() -> burn::config::__private::Value {
+                    <Self as burn::config::Config>::json_schema()
+                }
             }
         }
     }
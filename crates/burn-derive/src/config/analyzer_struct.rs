@@ -1,27 +1,149 @@
-use super::ConfigAnalyzer;
+use super::{ConfigAnalyzer, FieldConstraint, default_expr, doc_lines};
 use crate::shared::{attribute::AttributeItem, field::FieldTypeAnalyzer};
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
 
 pub struct ConfigStructAnalyzer {
     name: Ident,
+    docs: Vec<String>,
     fields_required: Vec<FieldTypeAnalyzer>,
     fields_option: Vec<FieldTypeAnalyzer>,
     fields_default: Vec<(FieldTypeAnalyzer, AttributeItem)>,
+    constraints: Vec<(Ident, Vec<FieldConstraint>)>,
 }
 
 impl ConfigStructAnalyzer {
     pub fn new(
         name: Ident,
+        docs: Vec<String>,
         fields_required: Vec<FieldTypeAnalyzer>,
         fields_option: Vec<FieldTypeAnalyzer>,
         fields_default: Vec<(FieldTypeAnalyzer, AttributeItem)>,
+        constraints: Vec<(Ident, Vec<FieldConstraint>)>,
     ) -> Self {
         Self {
             name,
+            docs,
             fields_required,
             fields_option,
             fields_default,
+            constraints,
+        }
+    }
+
+    fn constraints(&self, field: &FieldTypeAnalyzer) -> &[FieldConstraint] {
+        let name = field.ident();
+        self.constraints
+            .iter()
+            .find(|(ident, _)| *ident == name)
+            .map(|(_, constraints)| constraints.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn gen_validate_fn(&self) -> TokenStream {
+        let checks = self.names().into_iter().map(|field| {
+            let name = field.ident();
+            let field_name = name.to_string();
+            let constraints = self
+                .constraints(&field)
+                .iter()
+                .map(|constraint| constraint.gen_check(&field_name));
+
+            let checks = if self.constraints(&field).is_empty() {
+                quote! {}
+            } else if field.is_of_type(&["Option"]) {
+                quote! {
+                    if let Some(value) = &self.#name {
+                        #(#constraints)*
+                    }
+                }
+            } else {
+                quote! {
+                    let value = &self.#name;
+                    #(#constraints)*
+                }
+            };
+
+            quote! {
+                {
+                    #checks
+                    (&&burn::config::__private::Nested(&self.#name)).validate_nested(#field_name)?;
+                }
+            }
+        });
+
+        quote! {
+            fn validate(&self) -> Result<(), burn::config::ConfigError> {
+                #[allow(unused_imports)]
+                use burn::config::__private::{ValidateConfig as _, ValidateOther as _};
+
+                #(#checks)*
+                Ok(())
+            }
+        }
+    }
+
+    fn gen_schema_fn(&self) -> TokenStream {
+        let name = self.name.to_string();
+        let docs = &self.docs;
+        let defaults: Vec<_> = self
+            .fields_default
+            .iter()
+            .map(|(field, attribute)| (field.ident(), default_expr(attribute)))
+            .collect();
+
+        let properties = self.names().into_iter().map(|field| {
+            let ident = field.ident();
+            let field_name = ident.to_string();
+            let ty = &field.field.ty;
+            let constraints = self.constraints(&field);
+            let is_option = field.is_of_type(&["Option"]);
+            let field_docs = doc_lines(&field.field.attrs);
+
+            // The constraints of an optional field apply to the value it holds.
+            let schema = if is_option && !constraints.is_empty() {
+                let inner = field.first_generic_field();
+                quote! { (&&burn::config::__private::SchemaOf::<#inner>::new()).schema() }
+            } else {
+                quote! { (&&burn::config::__private::SchemaOf::<#ty>::new()).schema() }
+            };
+            let constraints = constraints.iter().map(FieldConstraint::gen_schema);
+            let nullable = if is_option && !self.constraints(&field).is_empty() {
+                quote! { schema = burn::config::__private::nullable(schema); }
+            } else {
+                quote! {}
+            };
+            let default = match defaults.iter().find(|(name, _)| *name == ident) {
+                Some((_, value)) => quote! {
+                    let default: #ty = #value;
+                    burn::config::__private::set(&mut schema, "default", default);
+                },
+                None => quote! {},
+            };
+            let required = !is_option;
+
+            quote! {
+                {
+                    let mut schema = #schema;
+                    #(#constraints)*
+                    #nullable
+                    #default
+                    burn::config::__private::describe(&mut schema, &[#(#field_docs),*]);
+                    (#field_name, schema, #required)
+                }
+            }
+        });
+
+        quote! {
+            fn json_schema() -> burn::config::__private::Value {
+                #[allow(unused_imports)]
+                use burn::config::__private::{SchemaOther as _, SchemaWithTrait as _};
+
+                let mut schema = burn::config::__private::object([#(#properties),*]);
+                burn::config::__private::set(&mut schema, "title", #name);
+                burn::config::__private::describe(&mut schema, &[#(#docs),*]);
+                schema
+            }
         }
     }
 
@@ -228,20 +350,10 @@ impl ConfigAnalyzer for ConfigStructAnalyzer {
             let value = &attribute.value;
             let docs = field.docs();
 
-            match value {
-                syn::Lit::Str(value) => {
-                    let stream: proc_macro2::TokenStream = value.value().parse().unwrap();
-
-                    body.extend(quote! {
-                        #name: #stream,
-                    });
-                }
-                _ => {
-                    body.extend(quote! {
-                        #name: #value,
-                    });
-                }
-            };
+            let stream = default_expr(attribute);
+            body.extend(quote! {
+                #name: #stream,
+            });
             docs_header(&mut fn_docs, false, false, true);
             let default_doc = format!("- Defaults to `{}`", quote!(#value));
             let doc_str = format!("###### `{}`\n", quote!(#name));
@@ -254,6 +366,9 @@ impl ConfigAnalyzer for ConfigStructAnalyzer {
 
         let body = quote! {
             #[doc = "Create a new instance of the config."]
+            #[doc = ""]
+            #[doc = "The constraints declared with `#[config(...)]` aren't checked here nor by the"]
+            #[doc = "`with_` methods; finish the chain with `validated()` to check them."]
             #fn_docs
             #[allow(clippy::too_many_arguments)]
             pub fn new(
@@ -371,9 +486,19 @@ impl ConfigAnalyzer for ConfigStructAnalyzer {
 
     fn gen_config_impl(&self) -> TokenStream {
         let name = &self.name;
+        let validate = self.gen_validate_fn();
+        let schema = self.gen_schema_fn();
 
         quote! {
             impl burn::config::Config for #name {
+                #validate
+                #schema
+            }
+
+            impl burn::config::__private::FieldSchema for #name {
+                fn field_schema() -> burn::config::__private::Value {
+                    <Self as burn::config::Config>::json_schema()
+                }
             }
         }
     }
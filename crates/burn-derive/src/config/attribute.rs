@@ -0,0 +1,152 @@
+use crate::shared::{attribute::AttributeItem, field::FieldTypeAnalyzer};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Expr, ExprArray, Lit, Meta, Path};
+
+/// A constraint declared on a config field, checked by `Config::validate` and described in the
+/// JSON schema.
+#[derive(Clone)]
+pub enum FieldConstraint {
+    /// `min = <expr>`: the value is at least the bound.
+    Min(Expr),
+    /// `max = <expr>`: the value is at most the bound.
+    Max(Expr),
+    /// `non_empty`: the string or collection holds something.
+    NonEmpty,
+    /// `one_of = [<expr>, ...]`: the value is one of those listed.
+    OneOf(ExprArray),
+    /// `validate = <path>`: a `fn(&T) -> Result<(), String>` accepts the value.
+    Validate(Path),
+}
+
+/// Everything declared with `#[config(...)]` on a field.
+#[derive(Default)]
+pub struct ConfigFieldAttributes {
+    pub default: Option<AttributeItem>,
+    pub constraints: Vec<FieldConstraint>,
+}
+
+impl ConfigFieldAttributes {
+    pub fn parse(field: &FieldTypeAnalyzer) -> Self {
+        let mut attributes = Self::default();
+
+        for attr in field.field.attrs.iter() {
+            if attr.path().is_ident("config") {
+                attributes.parse_attr(attr);
+            }
+        }
+
+        attributes
+    }
+
+    fn parse_attr(&mut self, attr: &Attribute) {
+        // `#[config = <lit>]` is a legacy spelling of the default.
+        if let Meta::NameValue(meta) = &attr.meta {
+            match &meta.value {
+                Expr::Lit(lit) => {
+                    self.default = Some(AttributeItem {
+                        value: lit.lit.clone(),
+                    });
+                    return;
+                }
+                _ => panic!("Only literal is supported"),
+            }
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("default") {
+                let value: Lit = meta.value()?.parse()?;
+                self.default = Some(AttributeItem { value });
+            } else if meta.path.is_ident("min") {
+                self.constraints
+                    .push(FieldConstraint::Min(meta.value()?.parse()?));
+            } else if meta.path.is_ident("max") {
+                self.constraints
+                    .push(FieldConstraint::Max(meta.value()?.parse()?));
+            } else if meta.path.is_ident("non_empty") {
+                self.constraints.push(FieldConstraint::NonEmpty);
+            } else if meta.path.is_ident("one_of") {
+                self.constraints
+                    .push(FieldConstraint::OneOf(meta.value()?.parse()?));
+            } else if meta.path.is_ident("validate") {
+                self.constraints
+                    .push(FieldConstraint::Validate(meta.value()?.parse()?));
+            } else {
+                return Err(meta.error(
+                    "unsupported config attribute, expected one of `default`, `min`, `max`, \
+                     `non_empty`, `one_of` or `validate`",
+                ));
+            }
+            Ok(())
+        })
+        .unwrap_or_else(|err| panic!("Invalid config attribute: {err}"));
+    }
+}
+
+impl FieldConstraint {
+    /// Check `value`, a reference to the field's value, in `Config::validate`.
+    pub fn gen_check(&self, field_name: &str) -> TokenStream {
+        match self {
+            FieldConstraint::Min(min) => quote! {
+                burn::config::__private::check_min(#field_name, value, #min)?;
+            },
+            FieldConstraint::Max(max) => quote! {
+                burn::config::__private::check_max(#field_name, value, #max)?;
+            },
+            FieldConstraint::NonEmpty => quote! {
+                burn::config::__private::check_non_empty(#field_name, value)?;
+            },
+            FieldConstraint::OneOf(allowed) => quote! {
+                burn::config::__private::check_one_of(#field_name, value, &#allowed)?;
+            },
+            FieldConstraint::Validate(check) => quote! {
+                burn::config::__private::check_with(#field_name, value, #check)?;
+            },
+        }
+    }
+
+    /// Describe the constraint in `schema`, the field's schema.
+    pub fn gen_schema(&self) -> TokenStream {
+        match self {
+            FieldConstraint::Min(min) => quote! {
+                burn::config::__private::set(&mut schema, "minimum", #min);
+            },
+            FieldConstraint::Max(max) => quote! {
+                burn::config::__private::set(&mut schema, "maximum", #max);
+            },
+            FieldConstraint::NonEmpty => quote! {
+                burn::config::__private::non_empty(&mut schema);
+            },
+            FieldConstraint::OneOf(allowed) => quote! {
+                burn::config::__private::set(&mut schema, "enum", #allowed);
+            },
+            // A custom check can't be described.
+            FieldConstraint::Validate(_) => quote! {},
+        }
+    }
+}
+
+/// The text of the doc comments among `attrs`.
+pub fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(meta) => match &meta.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(doc), ..
+                }) => Some(doc.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// The expression of a `default = ...` attribute: a string literal holds Rust code.
+pub fn default_expr(item: &AttributeItem) -> TokenStream {
+    match &item.value {
+        Lit::Str(value) => value.value().parse().unwrap(),
+        value => quote! { #value },
+    }
+}
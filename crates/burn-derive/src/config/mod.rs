@@ -1,9 +1,11 @@
 mod analyzer;
 mod analyzer_enum;
 mod analyzer_struct;
+mod attribute;
 mod base;
 
 pub(crate) use analyzer::*;
 pub(crate) use analyzer_enum::*;
 pub(crate) use analyzer_struct::*;
+pub(crate) use attribute::*;
 pub(crate) use base::*;
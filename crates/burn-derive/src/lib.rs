@@ -66,7 +66,58 @@ pub fn module_derive(input: TokenStream) -> TokenStream {
     module::derive_impl(&input)
 }
 
+/// Attribute macro making a module method observable by forward hooks.
+///
+/// The method must take `&self`. When forward hooks are running on the thread (see
+/// `burn::module::ForwardHooks`) and the module was reached from the hooked root, the hooks
+/// registered on the module's path are applied: pre-hooks receive the arguments (a tuple when
+/// there are several) and post-hooks receive the returned value, and both may replace them.
+/// Otherwise the method runs unchanged.
+///
+/// The arguments and the returned value must be `'static`, and the returned value must be
+/// `Clone` so it can be captured.
+///
+/// # Example
+///
+/// ```ignore
+/// impl Block {
+///     #[hookable]
+///     pub fn forward(&self, input: Tensor<3>) -> Tensor<3> {
+///         self.linear.forward(input)
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn hookable(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`#[hookable]` doesn't take arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let item = syn::parse_macro_input!(item as syn::ImplItemFn);
+    module::hookable::hookable_impl(item)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Derive macro for the config.
+///
+/// # Field Attributes
+///
+/// - `#[config(default = <lit>)]`: the value `new` sets; a string literal holds Rust code.
+/// - `#[config(min = <expr>)]` and `#[config(max = <expr>)]`: inclusive bounds.
+/// - `#[config(non_empty)]`: the string or collection holds something.
+/// - `#[config(one_of = [<expr>, ...])]`: the value is one of those listed.
+/// - `#[config(validate = <path>)]`: a `fn(&T) -> Result<(), String>` accepts the value.
+///
+/// Several can be combined, e.g. `#[config(default = 0.1, min = 0.0, max = 1.0)]`. The
+/// constraints of an optional field apply to the value it holds. They are checked by
+/// `Config::validate`, which also validates the nested configs, and described in the schema of
+/// `Config::json_schema`.
 #[proc_macro_derive(Config, attributes(config))]
 pub fn config_derive(input: TokenStream) -> TokenStream {
     let item = syn::parse(input).unwrap();
@@ -54,6 +54,7 @@ impl ModuleCodegen for StructModuleCodegen {
 
         quote! {
             fn visit<Visitor: burn::module::ModuleVisitor>(&self, visitor: &mut Visitor) {
+                visitor.visit_module(self);
                 #body
             }
         }
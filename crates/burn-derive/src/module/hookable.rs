@@ -0,0 +1,87 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{FnArg, ImplItemFn, Pat, PatIdent, ReturnType, spanned::Spanned};
+
+/// Wraps the body of a module method so it runs through `burn::module::hooked_forward`.
+///
+/// The arguments are packed into a single value (the argument itself, or a tuple when there are
+/// several) so that pre-hooks can inspect and replace them, and the body becomes a closure over
+/// that value. Identifier patterns keep their name in the signature so the generated docs don't
+/// change; other patterns are bound to a generated name and destructured in the closure.
+pub(crate) fn hookable_impl(mut item: ImplItemFn) -> syn::Result<TokenStream> {
+    match item.sig.inputs.first() {
+        Some(FnArg::Receiver(receiver))
+            if receiver.reference.is_some() && receiver.mutability.is_none() => {}
+        _ => {
+            return Err(syn::Error::new(
+                item.sig.span(),
+                "`#[hookable]` can only be applied to methods taking `&self`",
+            ));
+        }
+    }
+    if item.sig.asyncness.is_some() {
+        return Err(syn::Error::new(
+            item.sig.asyncness.span(),
+            "`#[hookable]` doesn't support async methods",
+        ));
+    }
+
+    let mut args = Vec::new();
+    let mut patterns = Vec::new();
+    let mut types = Vec::new();
+
+    for (index, input) in item.sig.inputs.iter_mut().skip(1).enumerate() {
+        let FnArg::Typed(arg) = input else {
+            unreachable!("Only the first argument can be a receiver");
+        };
+        patterns.push((*arg.pat).clone());
+        types.push((*arg.ty).clone());
+
+        let ident = match arg.pat.as_mut() {
+            // The binding is only moved into the closure, so `mut` belongs to the closure pattern.
+            Pat::Ident(PatIdent {
+                ident,
+                mutability,
+                subpat: None,
+                by_ref: None,
+                ..
+            }) => {
+                *mutability = None;
+                ident.clone()
+            }
+            pat => {
+                let ident = format_ident!("__hook_arg_{}", index);
+                *pat = syn::parse_quote!(#ident);
+                ident
+            }
+        };
+        args.push(ident);
+    }
+
+    let (input, closure_pat, closure_ty) = match args.len() {
+        1 => {
+            let (arg, pat, ty) = (&args[0], &patterns[0], &types[0]);
+            (quote!(#arg), quote!(#pat), quote!(#ty))
+        }
+        _ => (
+            quote!((#(#args,)*)),
+            quote!((#(#patterns,)*)),
+            quote!((#(#types,)*)),
+        ),
+    };
+    let output = match &item.sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+
+    let block = &item.block;
+    item.block = syn::parse_quote!({
+        burn::module::hooked_forward(
+            self,
+            #input,
+            move |#closure_pat: #closure_ty| -> #output #block,
+        )
+    });
+
+    Ok(quote!(#item))
+}
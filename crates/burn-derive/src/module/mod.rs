@@ -3,6 +3,7 @@ pub(crate) mod codegen_enum;
 pub(crate) mod codegen_struct;
 pub(crate) mod display;
 pub(crate) mod generics;
+pub(crate) mod hookable;
 
 mod base;
 
@@ -728,6 +728,245 @@ impl AutodiffBackend for Dispatch {
         tensor
     }
 
+    fn tangent(tensor: &DispatchTensor) -> Option<DispatchTensor> {
+        let DispatchTensor {
+            kind,
+            checkpointing,
+        } = tensor;
+        let tangent: Option<DispatchTensorKind> = match &kind {
+            DispatchTensorKind::Autodiff(inner_kind) => match &**inner_kind {
+                #[cfg(feature = "cpu")]
+                DispatchTensorKind::Cpu(tensor) => tensor.as_autodiff().tangent().map(|tangent| {
+                    DispatchTensorKind::Cpu(crate::BackendTensor::Autodiff(tangent))
+                }),
+                #[cfg(feature = "cuda")]
+                DispatchTensorKind::Cuda(tensor) => tensor.as_autodiff().tangent().map(|tangent| {
+                    DispatchTensorKind::Cuda(crate::BackendTensor::Autodiff(tangent))
+                }),
+                #[cfg(feature = "metal")]
+                DispatchTensorKind::Metal(tensor) => {
+                    tensor.as_autodiff().tangent().map(|tangent| {
+                        DispatchTensorKind::Metal(crate::BackendTensor::Autodiff(tangent))
+                    })
+                }
+                #[cfg(feature = "rocm")]
+                DispatchTensorKind::Rocm(tensor) => tensor.as_autodiff().tangent().map(|tangent| {
+                    DispatchTensorKind::Rocm(crate::BackendTensor::Autodiff(tangent))
+                }),
+                #[cfg(feature = "vulkan")]
+                DispatchTensorKind::Vulkan(tensor) => {
+                    tensor.as_autodiff().tangent().map(|tangent| {
+                        DispatchTensorKind::Vulkan(crate::BackendTensor::Autodiff(tangent))
+                    })
+                }
+                #[cfg(feature = "wgpu")]
+                DispatchTensorKind::Wgpu(tensor) => tensor.as_autodiff().tangent().map(|tangent| {
+                    DispatchTensorKind::Wgpu(crate::BackendTensor::Autodiff(tangent))
+                }),
+                #[cfg(feature = "webgpu")]
+                DispatchTensorKind::WebGpu(tensor) => {
+                    tensor.as_autodiff().tangent().map(|tangent| {
+                        DispatchTensorKind::WebGpu(crate::BackendTensor::Autodiff(tangent))
+                    })
+                }
+                #[cfg(any(feature = "flex", default_backend))]
+                DispatchTensorKind::Flex(tensor) => tensor.as_autodiff().tangent().map(|tangent| {
+                    DispatchTensorKind::Flex(crate::BackendTensor::Autodiff(tangent))
+                }),
+                #[cfg(feature = "ndarray")]
+                DispatchTensorKind::NdArray(tensor) => {
+                    tensor.as_autodiff().tangent().map(|tangent| {
+                        DispatchTensorKind::NdArray(crate::BackendTensor::Autodiff(tangent))
+                    })
+                }
+                #[cfg(feature = "tch")]
+                DispatchTensorKind::LibTorch(tensor) => {
+                    tensor.as_autodiff().tangent().map(|tangent| {
+                        DispatchTensorKind::LibTorch(crate::BackendTensor::Autodiff(tangent))
+                    })
+                }
+                #[cfg(feature = "remote")]
+                DispatchTensorKind::Remote(tensor) => {
+                    tensor.as_autodiff().tangent().map(|tangent| {
+                        DispatchTensorKind::Remote(crate::BackendTensor::Autodiff(tangent))
+                    })
+                }
+                #[cfg(feature = "capture")]
+                DispatchTensorKind::Capture(_) => {
+                    panic!("Capture tensors do not support autodiff")
+                }
+                DispatchTensorKind::Autodiff(_) => {
+                    panic!("Autodiff should not wrap an autodiff tensor.")
+                }
+            },
+            _ => panic!("Requires autodiff tensor."),
+        };
+        tangent.map(|kind| DispatchTensor {
+            kind: DispatchTensorKind::Autodiff(Box::new(kind)),
+            checkpointing: *checkpointing,
+        })
+    }
+
+    fn with_tangent(tensor: DispatchTensor, tangent: Option<DispatchTensor>) -> DispatchTensor {
+        let DispatchTensor {
+            kind,
+            checkpointing,
+        } = tensor;
+        let tangent = tangent.map(|tangent| match tangent.kind {
+            DispatchTensorKind::Autodiff(inner_kind) => *inner_kind,
+            _ => panic!("Requires autodiff tensor."),
+        });
+
+        let kind = match kind {
+            DispatchTensorKind::Autodiff(inner_kind) => match *inner_kind {
+                #[cfg(feature = "cpu")]
+                DispatchTensorKind::Cpu(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::Cpu(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::Cpu(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "cuda")]
+                DispatchTensorKind::Cuda(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::Cuda(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::Cuda(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "metal")]
+                DispatchTensorKind::Metal(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::Metal(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::Metal(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "rocm")]
+                DispatchTensorKind::Rocm(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::Rocm(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::Rocm(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "vulkan")]
+                DispatchTensorKind::Vulkan(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::Vulkan(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::Vulkan(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "wgpu")]
+                DispatchTensorKind::Wgpu(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::Wgpu(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::Wgpu(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "webgpu")]
+                DispatchTensorKind::WebGpu(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::WebGpu(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::WebGpu(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(any(feature = "flex", default_backend))]
+                DispatchTensorKind::Flex(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::Flex(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::Flex(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "ndarray")]
+                DispatchTensorKind::NdArray(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::NdArray(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::NdArray(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "tch")]
+                DispatchTensorKind::LibTorch(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::LibTorch(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::LibTorch(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "remote")]
+                DispatchTensorKind::Remote(tensor) => {
+                    let tangent = tangent.map(|tangent| match tangent {
+                        DispatchTensorKind::Remote(tangent) => tangent.autodiff(),
+                        other => panic!(
+                            "The tangent is not on the same backend as the tensor. Got backend {other:?}."
+                        ),
+                    });
+                    DispatchTensorKind::Remote(crate::BackendTensor::Autodiff(
+                        tensor.autodiff().with_tangent(tangent),
+                    ))
+                }
+                #[cfg(feature = "capture")]
+                DispatchTensorKind::Capture(_) => {
+                    panic!("Capture tensors do not support autodiff")
+                }
+                DispatchTensorKind::Autodiff(_) => {
+                    panic!("Autodiff should not wrap an autodiff tensor.")
+                }
+            },
+            _ => panic!("Requires autodiff tensor."),
+        };
+        DispatchTensor {
+            kind: DispatchTensorKind::Autodiff(Box::new(kind)),
+            checkpointing,
+        }
+    }
+
     // Only the collective-capable backends (Cuda/Remote) carry distributed params; in builds
     // without them the match arms cfg out, leaving the bindings unused and the tail unreachable.
     #[allow(unused_variables, unreachable_code)]
@@ -895,6 +1134,14 @@ impl AutodiffBackend for Dispatch {
     fn q_from_inner(_tensor: DispatchTensor) -> DispatchTensor {
         unimplemented!("Requires `autodiff` feature")
     }
+
+    fn tangent(_tensor: &DispatchTensor) -> Option<DispatchTensor> {
+        unimplemented!("Requires `autodiff` feature")
+    }
+
+    fn with_tangent(_tensor: DispatchTensor, _tangent: Option<DispatchTensor>) -> DispatchTensor {
+        unimplemented!("Requires `autodiff` feature")
+    }
 }
 
 impl Dispatch {
@@ -42,6 +42,8 @@ pub mod backend;
 /// Dispatch device module.
 pub mod device;
 mod ops;
+#[cfg(feature = "std")]
+mod profile;
 /// Dispatch tensor module.
 pub mod tensor;
 
@@ -79,6 +79,51 @@ macro_rules! with_autodiff_backend {
     };
 }
 
+/// Records the dispatched operation in the running profiling session.
+///
+/// The operation is named after the function calling the macro. With `tensor`, the body returns a
+/// [`DispatchTensor`](crate::DispatchTensor) whose size is recorded, otherwise the body may
+/// `.await` and only its duration is recorded. Outside of a session, the macro only checks that
+/// profiling is inactive before running the body.
+#[cfg(feature = "std")]
+macro_rules! profiled {
+    (tensor $body:expr) => {{
+        fn marker() {}
+        let timer = if $crate::profile::is_active() {
+            $crate::profile::start_operation(core::any::type_name_of_val(&marker))
+        } else {
+            None
+        };
+        let output = $body;
+        $crate::profile::finish_operation(timer, &output);
+        output
+    }};
+    ($body:expr) => {{
+        fn marker() {}
+        let timer = if $crate::profile::is_active() {
+            $crate::profile::start_async_operation(core::any::type_name_of_val(&marker))
+        } else {
+            None
+        };
+        let output = $body;
+        if let Some(timer) = timer {
+            timer.finish();
+        }
+        output
+    }};
+}
+
+/// Profiling requires `std`, the body is returned as is.
+#[cfg(not(feature = "std"))]
+macro_rules! profiled {
+    (tensor $body:expr) => {
+        $body
+    };
+    ($body:expr) => {
+        $body
+    };
+}
+
 /// Match arm generator for `dispatch_device`.
 /// Maps each backend variant to a block where the specific backend type is bound to `B`.
 macro_rules! dispatch_device_arms {
@@ -274,7 +319,7 @@ macro_rules! to_device_arms {
 /// and cross-backend dispatches.
 macro_rules! to_device {
     ($kind:ident, $inner_fn:ident, $tensor:expr, $device:expr, $to_device:ident, |$inner:ident, $device_ident:ident| $body:expr) => {
-        backend_matrix!(
+        profiled!(tensor backend_matrix!(
             to_device_arms,
             $kind,
             $inner_fn,
@@ -282,7 +327,7 @@ macro_rules! to_device {
             $device,
             $to_device,
             |$inner, $device_ident| $body
-        )
+        ))
     };
 }
 
@@ -403,13 +448,13 @@ macro_rules! float_to_device_arms {
 /// Handles float tensor movement between devices (that might support autodiff).
 macro_rules! float_to_device {
     ($kind:ident, $inner_fn:ident, $tensor:expr, $device:expr, $to_device:ident, |$inner:ident, $device_ident:ident| $body:expr) => {
-        backend_matrix!(
+        profiled!(tensor backend_matrix!(
             float_to_device_arms,
             $tensor,
             $device,
             $to_device,
             |$inner, $device_ident| $body
-        )
+        ))
     };
 }
 
@@ -417,7 +462,7 @@ macro_rules! float_to_device {
 /// based on the provided device.
 macro_rules! creation_op {
     ($kind:ident, $device:expr, |$inner:ident| $body:expr) => {
-        backend_list!(creation_op_arms, $kind, $device, |$inner| $body)
+        profiled!(tensor backend_list!(creation_op_arms, $kind, $device, |$inner| $body))
     };
 }
 
@@ -611,12 +656,12 @@ macro_rules! unary_op_arms {
 /// When the return `=> Kind` is not provided, the operation output is not wrapped in a dispatch tensor (e.g., `into_data(..)`)
 macro_rules! unary_op {
     ($tensor:expr, $inner_kind:ident, |$inner:ident| $body:expr => $kind:ident) => {
-        backend_list!(unary_op_arms, $kind, $inner_kind, $tensor, |$inner| {
+        profiled!(tensor backend_list!(unary_op_arms, $kind, $inner_kind, $tensor, |$inner| {
             $body
-        })
+        }))
     };
     ($tensor:expr, $inner_kind:ident, |$inner:ident| $body:expr) => {
-        backend_list!(unary_op_arms, $inner_kind, $tensor, |$inner| { $body })
+        profiled!(backend_list!(unary_op_arms, $inner_kind, $tensor, |$inner| { $body }))
     };
 }
 
@@ -789,19 +834,19 @@ macro_rules! unary_float {
 
     // --- Internal implementation workers that accept the list macro dynamically ---
     (@internal $list_macro:ident, $tensor:expr, $inner_kind:ident, |$inner:ident| $body:expr => $kind:ident) => {
-        $list_macro!(
+        profiled!(tensor $list_macro!(
             unary_float_arms,
             owned,
             $kind,
             $inner_kind,
             $tensor,
             |$inner| { $body }
-        )
+        ))
     };
     (@internal $list_macro:ident, $tensor:expr, $inner_kind:ident, |$inner:ident| $body:expr) => {
-        $list_macro!(unary_float_arms, owned, $inner_kind, $tensor, |$inner| {
+        profiled!($list_macro!(unary_float_arms, owned, $inner_kind, $tensor, |$inner| {
             $body
-        })
+        }))
     };
     (@internal $list_macro:ident, ref $tensor:expr, $inner_kind:ident, |$inner:ident| $body:expr) => {
         $list_macro!(unary_float_arms, ref, $inner_kind, $tensor, |$inner| {
@@ -853,13 +898,13 @@ macro_rules! binary_op_arms {
 /// Automatically verifies that both tensors reside on the same backend.
 macro_rules! binary_op {
     (($lhs:expr, $lhs_kind:ident), ($rhs:expr, $rhs_kind:ident), |$lhs_inner:ident, $rhs_inner:ident| $body:expr => $kind:ident) => {
-        backend_list!(
+        profiled!(tensor backend_list!(
             binary_op_arms,
             $kind,
             ($lhs, $lhs_kind),
             ($rhs, $rhs_kind),
             |$lhs_inner, $rhs_inner| { $body }
-        )
+        ))
     };
 }
 
@@ -1096,13 +1141,13 @@ macro_rules! binary_float_arms {
 /// Automatically verifies that both tensors reside on the same backend.
 macro_rules! binary_float {
     (($lhs:expr, $lhs_kind:ident), ($rhs:expr, $rhs_kind:ident), |$lhs_inner:ident, $rhs_inner:ident| $body:expr => $kind:ident) => {
-        backend_list!(
+        profiled!(tensor backend_list!(
             binary_float_arms,
             $kind,
             ($lhs, $lhs_kind),
             ($rhs, $rhs_kind),
             |$lhs_inner, $rhs_inner| { $body }
-        )
+        ))
     };
 }
 
@@ -1205,7 +1250,7 @@ macro_rules! multi_op_arm_autodiff {
                 };
             )+
 
-            // Optional inputs (always assumed to be float / autodiff)
+            // Optional inputs
             $(
                 let $opt_in = $opt_in.map(|o| match o.kind {
                     $crate::DispatchTensorKind::Autodiff(inner) => {
@@ -1214,6 +1259,9 @@ macro_rules! multi_op_arm_autodiff {
                             _ => panic!("Input tensor {} is on the wrong device", stringify!($opt_in)),
                         }
                     },
+                    // Only reachable when the input is int
+                    $crate::DispatchTensorKind::$Backend(inner) => wrap_input_autodiff!($Backend, inner, $opt_kind),
+                    #[allow(unreachable_patterns)]
                     _ => panic!("Optional tensor {} is on the wrong device", stringify!($opt_in)),
                 });
             )*
@@ -1347,14 +1395,14 @@ macro_rules! multi_op {
         => Float,
         $body:expr
     ) => {
-        multi_op!(
+        profiled!(tensor multi_op!(
             inputs[$( ($x, $kind) ),+],
             opt_inputs[],
             outputs[(out, Float)],
             opt_outputs[],
             { ($body,) }
         )
-        .0
+        .0)
     };
     (
         inputs[$( ($x:ident, $kind:ident) ),+],
@@ -1362,14 +1410,14 @@ macro_rules! multi_op {
         => $out_kind:ident,
         $body:expr
     ) => {
-        multi_op!(
+        profiled!(tensor multi_op!(
             inputs[$( ($x, $kind) ),+],
             opt_inputs[ $(($opt_in, $opt_kind)),* ],
             outputs[(out, $out_kind)],
             opt_outputs[],
             { ($body,) }
         )
-        .0
+        .0)
     };
     // Int/Bool op specialization (not marked for autodiff)
     (
@@ -1377,14 +1425,14 @@ macro_rules! multi_op {
         => $out_kind:ident,
         $body:expr
     ) => {
-        backend_list!(
+        profiled!(tensor backend_list!(
             multi_op_arms,
             [ $(($x, $kind)),+ ],
             [],
             [ (out, $out_kind) ],
             [],
             { ($body,) }
-        ).0
+        ).0)
     };
 
     // --- Required + optional for both inputs and outputs ---
@@ -1395,14 +1443,14 @@ macro_rules! multi_op {
         opt_outputs[ $($opt_out:ident),* ],
         $body:expr
     ) => {
-        backend_list!(
+        profiled!(backend_list!(
             multi_op_arms_autodiff,
             [ $(($x, $kind)),+ ],
             [ $(($opt_in, $opt_kind)),* ],
             [ $(($out, $out_kind)),+ ],
             [ $($opt_out),* ],
             $body
-        )
+        ))
     };
 
     (
@@ -1535,9 +1583,9 @@ macro_rules! vec_op_arms {
 /// Automatically verifies that tensors reside on the first backend.
 macro_rules! vec_op {
     ($tensors:expr, $inner_kind:ident, |$inner:ident| $body:expr => $kind:ident) => {
-        backend_list!(vec_op_arms, $kind, $inner_kind, $tensors, |$inner| {
+        profiled!(tensor backend_list!(vec_op_arms, $kind, $inner_kind, $tensors, |$inner| {
             $body
-        })
+        }))
     };
 }
 
@@ -1591,6 +1639,6 @@ macro_rules! transaction_op_arms {
 /// Helper to dispatch a transaction based on the first available tensor.
 macro_rules! transaction_op {
     ($tx:ident, $first:expr) => {
-        backend_list!(transaction_op_arms, $tx, $first)
+        profiled!(backend_list!(transaction_op_arms, $tx, $first))
     };
 }
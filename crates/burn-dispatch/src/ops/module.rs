@@ -2,7 +2,7 @@ use burn_backend::{
     IntDType,
     ops::{
         DeformConv2dBackward, MaxPool1dBackward, MaxPool1dWithIndices, MaxPool2dBackward,
-        MaxPool2dWithIndices, ModuleOps,
+        MaxPool2dWithIndices, ModuleOps, SelectiveScanBackward,
     },
     tensor::{FloatTensor, IntTensor},
 };
@@ -652,13 +652,14 @@ impl ModuleOps<Self> for Dispatch {
         value: FloatTensor<Self>,
         mask: Option<burn_backend::tensor::BoolTensor<Self>>,
         attn_bias: Option<FloatTensor<Self>>,
+        seq_offsets: Option<IntTensor<Self>>,
         options: burn_backend::ops::AttentionModuleOptions,
     ) -> FloatTensor<Self> {
         multi_op!(
             inputs[(query, float), (key, float), (value, float)],
-            opt_inputs[(mask, bool), (attn_bias, float)],
+            opt_inputs[(mask, bool), (attn_bias, float), (seq_offsets, int)],
             => Float,
-            B::attention(query, key, value, mask, attn_bias, options)
+            B::attention(query, key, value, mask, attn_bias, seq_offsets, options)
         )
     }
 
@@ -745,6 +746,45 @@ impl ModuleOps<Self> for Dispatch {
         )
     }
 
+    fn has_selective_scan_backward() -> bool {
+        // Same reasoning as `has_ctc_loss_backward`: the flag is queried statically,
+        // so autodiff always differentiates through the decomposed default.
+        false
+    }
+
+    fn selective_scan(
+        x: FloatTensor<Self>,
+        delta: FloatTensor<Self>,
+        a: FloatTensor<Self>,
+        b: FloatTensor<Self>,
+        c: FloatTensor<Self>,
+    ) -> FloatTensor<Self> {
+        multi_op!(
+            inputs[(x, float), (delta, float), (a, float), (b, float), (c, float)],
+            => Float,
+            B::selective_scan(x, delta, a, b, c)
+        )
+    }
+
+    fn selective_scan_backward(
+        x: FloatTensor<Self>,
+        delta: FloatTensor<Self>,
+        a: FloatTensor<Self>,
+        b: FloatTensor<Self>,
+        c: FloatTensor<Self>,
+        output_grad: FloatTensor<Self>,
+    ) -> SelectiveScanBackward<Self> {
+        let (x_grad, delta_grad, a_grad, b_grad, c_grad) = multi_op!(
+            inputs[(x, float), (delta, float), (a, float), (b, float), (c, float), (output_grad, float)],
+            outputs[(x_grad, Float), (delta_grad, Float), (a_grad, Float), (b_grad, Float), (c_grad, Float)],
+            {
+                let res = B::selective_scan_backward(x, delta, a, b, c, output_grad);
+                (res.x_grad, res.delta_grad, res.a_grad, res.b_grad, res.c_grad)
+            }
+        );
+        SelectiveScanBackward::new(x_grad, delta_grad, a_grad, b_grad, c_grad)
+    }
+
     // TODO: linear ops
     // fn linear(
     //         x: FloatTensor<Self>,
@@ -0,0 +1,38 @@
+use burn_backend::{
+    Backend, TensorMetadata,
+    profile::{EventTimer, operation_name, sync_operations},
+};
+
+pub(crate) use burn_backend::profile::is_active;
+
+use crate::{Dispatch, DispatchTensor};
+
+/// Start measuring the operation enclosing the `marker` function.
+///
+/// Only called within an active session, since resolving the name isn't free.
+pub(crate) fn start_operation(marker: &'static str) -> Option<EventTimer> {
+    EventTimer::operation(operation_name(marker))
+}
+
+/// Start measuring the operation enclosing the `marker` function, which may be suspended.
+pub(crate) fn start_async_operation(marker: &'static str) -> Option<EventTimer> {
+    EventTimer::async_operation(operation_name(marker))
+}
+
+/// Record the operation with the size of its output tensor.
+///
+/// When the session measures the execution time of every operation, the device of the output is
+/// synchronized first, since most backends only enqueue the operation.
+pub(crate) fn finish_operation(timer: Option<EventTimer>, output: &DispatchTensor) {
+    let Some(timer) = timer else {
+        return;
+    };
+
+    if sync_operations() {
+        // An execution error will be surfaced by the next read, not by the profiler.
+        let _ = Dispatch::sync(&output.device());
+    }
+
+    let bytes = output.shape().num_elements() * output.dtype().size();
+    timer.with_bytes(bytes as u64).finish();
+}
@@ -6,9 +6,12 @@
 //! surface (`Channel` enum, opaque `Device` argument) lives in `burn-tensor`.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use burn_remote::Endpoint;
-use burn_remote::server::{CustomOpRegistry, IrohRemoteProtocol, PeerAuthorizer, RemoteProtocol};
+use burn_remote::server::{
+    CustomOpRegistry, IrohRemoteProtocol, PeerAuthorizer, RemoteProtocol, ServerQuotas,
+};
 use burn_remote::telemetry::TelemetryProbe;
 
 use crate::backends::*;
@@ -133,22 +136,45 @@ macro_rules! with_backend {
 /// Start a remote-execution server for `device`'s backend, blocking the current thread.
 ///
 /// `device` selects the backend; `channel` selects the transport. The server hosts that backend's
-/// devices, indexed by hardware device index. Use [`start_async`] for the async counterpart.
+/// devices, indexed by hardware device index, and accepts every session. Use [`start_async`] for
+/// the async counterpart, and [`start_with_auth`] to authenticate clients.
 #[cfg(not(target_family = "wasm"))]
 pub fn start(device: DispatchDevice, channel: Channel) {
+    start_with_auth(device, channel, burn_remote::server::ServerAuth::default())
+}
+
+/// Async counterpart of [`start`]; runs on the caller's tokio runtime instead of blocking.
+#[cfg(not(target_family = "wasm"))]
+pub async fn start_async(device: DispatchDevice, channel: Channel) {
+    start_async_with_auth(device, channel, burn_remote::server::ServerAuth::default()).await
+}
+
+/// Like [`start`], but only admits the sessions allowed by `auth`.
+#[cfg(not(target_family = "wasm"))]
+pub fn start_with_auth(
+    device: DispatchDevice,
+    channel: Channel,
+    auth: burn_remote::server::ServerAuth,
+) {
     with_backend!(device, |B, devices| {
         burn_remote::server::RemoteServerBuilder::<B>::new(devices)
             .channel(channel)
+            .auth(auth)
             .start()
     })
 }
 
-/// Async counterpart of [`start`]; runs on the caller's tokio runtime instead of blocking.
+/// Async counterpart of [`start_with_auth`].
 #[cfg(not(target_family = "wasm"))]
-pub async fn start_async(device: DispatchDevice, channel: Channel) {
+pub async fn start_async_with_auth(
+    device: DispatchDevice,
+    channel: Channel,
+    auth: burn_remote::server::ServerAuth,
+) {
     with_backend!(device, |B, devices| {
         burn_remote::server::RemoteServerBuilder::<B>::new(devices)
             .channel(channel)
+            .auth(auth)
             .start_async()
             .await
     })
@@ -163,12 +189,16 @@ pub fn remote_protocol(
     endpoint: &Endpoint,
     probe: TelemetryProbe,
     authorizer: Arc<dyn PeerAuthorizer>,
+    quotas: ServerQuotas,
+    resume_grace: Option<Duration>,
 ) -> RemoteProtocol {
     with_backend!(device, |B, devices| {
         RemoteProtocol::new(IrohRemoteProtocol::<B>::new(
             endpoint.clone(),
             devices,
             authorizer,
+            quotas,
+            resume_grace,
             probe,
             CustomOpRegistry::default(),
         ))
@@ -49,6 +49,7 @@ pub fn attention(
     value: FlexTensor,
     mask: Option<FlexTensor>,
     attn_bias: Option<FlexTensor>,
+    seq_offsets: Option<FlexTensor>,
     options: AttentionModuleOptions,
 ) -> FlexTensor {
     debug_assert!(
@@ -64,19 +65,20 @@ pub fn attention(
     let seq_q = query.layout().shape()[2];
     let seq_kv = key.layout().shape()[2];
     if seq_q * seq_kv <= NAIVE_SCORE_BUDGET {
-        return attention_naive(query, key, value, mask, attn_bias, options);
+        return attention_naive(query, key, value, mask, attn_bias, seq_offsets, options);
     }
-    attention_flash(query, key, value, mask, attn_bias, options)
+    attention_flash(query, key, value, mask, attn_bias, seq_offsets, options)
 }
 
 /// Dispatch attention by dtype, casting f16/bf16 to f32 for computation.
 macro_rules! dispatch_attention_dtype {
-    ($query:expr, $key:expr, $value:expr, $mask:expr, $attn_bias:expr, $options:expr, $impl_fn:ident) => {{
+    ($query:expr, $key:expr, $value:expr, $mask:expr, $attn_bias:expr, $seq_offsets:expr, $options:expr, $impl_fn:ident) => {{
         let query = $query;
         let key = $key;
         let value = $value;
         let mask = $mask;
         let attn_bias = $attn_bias;
+        let seq_offsets = $seq_offsets;
         let options = $options;
         let dtype = query.dtype();
         debug_assert_eq!(key.dtype(), dtype, "attention: key dtype mismatch");
@@ -85,8 +87,8 @@ macro_rules! dispatch_attention_dtype {
             debug_assert_eq!(b.dtype(), dtype, "attention: attn_bias dtype mismatch");
         }
         match dtype {
-            DType::F32 => $impl_fn::<f32>(query, key, value, mask, attn_bias, options),
-            DType::F64 => $impl_fn::<f64>(query, key, value, mask, attn_bias, options),
+            DType::F32 => $impl_fn::<f32>(query, key, value, mask, attn_bias, seq_offsets, options),
+            DType::F64 => $impl_fn::<f64>(query, key, value, mask, attn_bias, seq_offsets, options),
             DType::F16 => {
                 use burn_std::f16;
                 let r = $impl_fn::<f32>(
@@ -95,6 +97,7 @@ macro_rules! dispatch_attention_dtype {
                     cast_to_f32(value, f16::to_f32),
                     mask,
                     attn_bias.map(|b| cast_to_f32(b, f16::to_f32)),
+                    seq_offsets,
                     options,
                 );
                 cast_from_f32(r, f16::from_f32)
@@ -107,6 +110,7 @@ macro_rules! dispatch_attention_dtype {
                     cast_to_f32(value, bf16::to_f32),
                     mask,
                     attn_bias.map(|b| cast_to_f32(b, bf16::to_f32)),
+                    seq_offsets,
                     options,
                 );
                 cast_from_f32(r, bf16::from_f32)
@@ -187,9 +191,19 @@ pub fn attention_flash(
     value: FlexTensor,
     mask: Option<FlexTensor>,
     attn_bias: Option<FlexTensor>,
+    seq_offsets: Option<FlexTensor>,
     options: AttentionModuleOptions,
 ) -> FlexTensor {
-    dispatch_attention_dtype!(query, key, value, mask, attn_bias, options, attention_impl)
+    dispatch_attention_dtype!(
+        query,
+        key,
+        value,
+        mask,
+        attn_bias,
+        seq_offsets,
+        options,
+        attention_impl
+    )
 }
 
 fn cast_to_f32<E: burn_backend::Element + Pod + Copy>(
@@ -230,13 +244,14 @@ fn cast_from_f32<E: burn_backend::Element + Pod + Copy>(
 ///   value: \[batch, heads, seq_kv, val_dim\]
 ///   mask:  \[batch, heads, seq_q, seq_kv\] (optional, u8 where nonzero = masked out)
 ///   attn_bias: \[batch, heads, seq_q, seq_kv\] (optional)
+///   seq_offsets: \[num_sequences + 1\] (optional, int cumulative offsets of packed sequences)
 ///
 /// Output: \[batch, heads, seq_q, val_dim\]
 ///
 /// Algorithm per (batch, head):
 ///   For each KV tile of size TILE_KV:
 ///     1. Score matmul: scores\[seq_q, tile_kv\] = Q @ K_tile^T  (gemm)
-///     2. Per query row: apply scale, softcap, mask, bias
+///     2. Per query row: apply scale, softcap, mask, window/packing/ALiBi, bias
 ///     3. Per query row: online softmax update (running max/sum, rescale accumulator)
 ///     4. Value matmul: output += P @ V_tile  (gemm)
 ///   Final: output\[qi\] /= row_sum\[qi\] for each query row
@@ -246,6 +261,7 @@ fn attention_impl<T>(
     value: FlexTensor,
     mask: Option<FlexTensor>,
     attn_bias: Option<FlexTensor>,
+    seq_offsets: Option<FlexTensor>,
     options: AttentionModuleOptions,
 ) -> FlexTensor
 where
@@ -308,6 +324,7 @@ where
     } else {
         None
     };
+    let positions = PositionOptions::new(&options, seq_offsets, heads, seq_q, seq_kv);
 
     let q_data: &[T] = query.storage();
     let k_data: &[T] = key.storage();
@@ -340,6 +357,8 @@ where
         scale,
         softcap,
         causal_offset,
+        positions: positions.as_ref(),
+        head: 0,
         seq_q,
         seq_kv,
         head_dim,
@@ -371,7 +390,7 @@ where
                 &mut output[o_off..o_off + o_head_stride],
                 mask_data.map(|m| &m[mask_off..mask_off + mask_tile_len]),
                 bias_data.map(|b| &b[bias_off..bias_off + mask_tile_len]),
-                &params,
+                &AttentionParams { head: h, ..params },
                 &mut scratch,
             );
         }
@@ -459,16 +478,118 @@ struct ScratchBuffers<T> {
 }
 
 /// Parameters for a single (batch, head) flash attention computation.
-struct AttentionParams<T> {
+struct AttentionParams<'a, T> {
     scale: T,
     softcap: Option<T>,
     causal_offset: Option<isize>,
+    positions: Option<&'a PositionOptions<T>>,
+    head: usize,
     seq_q: usize,
     seq_kv: usize,
     head_dim: usize,
     val_dim: usize,
 }
 
+/// The position-dependent options: sliding window, packed sequences and ALiBi.
+struct PositionOptions<T> {
+    /// Offset aligning query and key positions at the bottom-right corner, like the causal mask.
+    offset: isize,
+    window: Option<isize>,
+    sequence_ids: Option<Vec<usize>>,
+    alibi_slopes: Option<Vec<T>>,
+}
+
+impl<T: Float> PositionOptions<T> {
+    /// Returns `None` when no position-dependent option is set.
+    fn new(
+        options: &AttentionModuleOptions,
+        seq_offsets: Option<FlexTensor>,
+        heads: usize,
+        seq_q: usize,
+        seq_kv: usize,
+    ) -> Option<Self> {
+        if options.sliding_window.is_none()
+            && seq_offsets.is_none()
+            && options.alibi_max_bias.is_none()
+        {
+            return None;
+        }
+
+        let window = options.sliding_window.map(|window| {
+            assert!(window > 0, "sliding_window must be non-zero");
+            window as isize
+        });
+        let sequence_ids = seq_offsets.map(|offsets| packed_sequence_ids(offsets, seq_kv));
+        if sequence_ids.is_some() {
+            assert_eq!(
+                seq_q, seq_kv,
+                "Packed sequences need the same query and key sequence length"
+            );
+        }
+        let alibi_slopes = options.alibi_slopes(heads).map(|slopes| {
+            slopes
+                .into_iter()
+                .map(|slope| T::from(slope).unwrap())
+                .collect()
+        });
+
+        Some(Self {
+            offset: seq_kv as isize - seq_q as isize,
+            window,
+            sequence_ids,
+            alibi_slopes,
+        })
+    }
+
+    /// The additive bias of a query and key position, or `None` when the key is masked.
+    #[inline]
+    fn bias(&self, head: usize, qi: usize, kv_idx: usize) -> Option<T> {
+        let distance = (qi as isize + self.offset - kv_idx as isize).abs();
+
+        if let Some(window) = self.window
+            && distance >= window
+        {
+            return None;
+        }
+
+        if let Some(ids) = &self.sequence_ids
+            && ids[qi] != ids[kv_idx]
+        {
+            return None;
+        }
+
+        Some(match &self.alibi_slopes {
+            Some(slopes) => -slopes[head] * T::from(distance).unwrap(),
+            None => T::zero(),
+        })
+    }
+}
+
+/// The sequence of each of the `seq_len` positions of a row, from the cumulative offsets of the
+/// packed sequences.
+///
+/// # Panics
+///
+/// If the offsets don't start at 0, decrease, or don't end at `seq_len`.
+fn packed_sequence_ids(seq_offsets: FlexTensor, seq_len: usize) -> Vec<usize> {
+    let data = seq_offsets.into_data();
+    let offsets: Vec<i64> = data.iter::<i64>().collect();
+    assert!(
+        offsets.first() == Some(&0) && offsets.last() == Some(&(seq_len as i64)),
+        "seq_offsets should go from 0 to the sequence length ({seq_len}), got {offsets:?}"
+    );
+    assert!(
+        offsets.windows(2).all(|pair| pair[0] <= pair[1]),
+        "seq_offsets should be non-decreasing, got {offsets:?}"
+    );
+
+    let mut ids = Vec::with_capacity(seq_len);
+    for (id, pair) in offsets.windows(2).enumerate() {
+        ids.extend(core::iter::repeat_n(id, (pair[1] - pair[0]) as usize));
+    }
+    ids
+}
+
 #[allow(clippy::too_many_arguments)]
 /// Process a single (batch, head) pair with flash attention.
 ///
@@ -498,6 +619,8 @@ fn flash_attention_head<T: FlashGemm>(
         scale,
         softcap,
         causal_offset,
+        positions,
+        head,
         seq_q,
         seq_kv,
         head_dim,
@@ -571,6 +694,13 @@ fn flash_attention_head<T: FlashGemm>(
                     val = neg_inf;
                 }
 
+                if let Some(positions) = positions {
+                    match positions.bias(head, qi, kv_idx) {
+                        Some(bias) => val += bias,
+                        None => val = neg_inf,
+                    }
+                }
+
                 if let Some(b) = bias {
                     val += b[qi * seq_kv + kv_idx];
                 }
@@ -673,6 +803,7 @@ pub fn attention_naive(
     value: FlexTensor,
     mask: Option<FlexTensor>,
     attn_bias: Option<FlexTensor>,
+    seq_offsets: Option<FlexTensor>,
     options: AttentionModuleOptions,
 ) -> FlexTensor {
     dispatch_attention_dtype!(
@@ -681,6 +812,7 @@ pub fn attention_naive(
         value,
         mask,
         attn_bias,
+        seq_offsets,
         options,
         attention_naive_impl
     )
@@ -692,6 +824,7 @@ fn attention_naive_impl<T>(
     value: FlexTensor,
     mask: Option<FlexTensor>,
     attn_bias: Option<FlexTensor>,
+    seq_offsets: Option<FlexTensor>,
     options: AttentionModuleOptions,
 ) -> FlexTensor
 where
@@ -757,6 +890,7 @@ where
     } else {
         None
     };
+    let positions = PositionOptions::new(&options, seq_offsets, heads, seq_q, seq_kv);
 
     let q_data: &[T] = query.storage();
     let k_data: &[T] = key.storage();
@@ -789,6 +923,8 @@ where
         scale,
         softcap,
         causal_offset,
+        positions: positions.as_ref(),
+        head: 0,
         seq_q,
         seq_kv,
         head_dim,
@@ -812,7 +948,7 @@ where
                 &v_data[v_off..v_off + v_head_stride],
                 &mut output[o_off..o_off + o_head_stride],
                 &mut scores,
-                &params,
+                &AttentionParams { head: h, ..params },
                 (
                     mask_data.map(|m| &m[mask_off..mask_off + mask_tile_len]),
                     bias_data.map(|b| &b[bias_off..bias_off + mask_tile_len]),
@@ -849,6 +985,8 @@ fn naive_attention_head<T: FlashGemm>(
         scale,
         softcap,
         causal_offset,
+        positions,
+        head,
         seq_q,
         seq_kv,
         head_dim,
@@ -899,6 +1037,13 @@ fn naive_attention_head<T: FlashGemm>(
                 val = neg_inf;
             }
 
+            if let Some(positions) = positions {
+                match positions.bias(head, qi, ki) {
+                    Some(bias) => val += bias,
+                    None => val = neg_inf,
+                }
+            }
+
             if let Some(b) = bias {
                 val += b[qi * seq_kv + ki];
             }
@@ -1068,6 +1213,7 @@ mod tests {
             FlexTensor,
             Option<FlexTensor>,
             Option<FlexTensor>,
+            Option<FlexTensor>,
             AttentionModuleOptions,
         ) -> FlexTensor;
         let cases: [(&str, AttnFn); 2] = [
@@ -1082,6 +1228,7 @@ mod tests {
                 flex_f32(v.clone(), &[batch, kv_heads, seq_kv, head_dim]),
                 None,
                 None,
+                None,
                 Default::default(),
             );
             let out_ref = run(
@@ -1090,6 +1237,7 @@ mod tests {
                 flex_f32(v_full.clone(), &[batch, q_heads, seq_kv, head_dim]),
                 None,
                 None,
+                None,
                 Default::default(),
             );
             assert_attention_outputs_close(
@@ -1160,9 +1308,11 @@ mod tests {
             v.clone(),
             None,
             Some(bias_bcast),
+            None,
             Default::default(),
         );
-        let out_full = super::attention_flash(q, k, v, None, Some(bias_full), Default::default());
+        let out_full =
+            super::attention_flash(q, k, v, None, Some(bias_full), None, Default::default());
 
         let bcast: &[f32] = out_bcast.storage();
         let full: &[f32] = out_full.storage();
@@ -1215,9 +1365,11 @@ mod tests {
             v.clone(),
             Some(mask_bcast),
             None,
+            None,
             Default::default(),
         );
-        let out_full = super::attention_flash(q, k, v, Some(mask_full), None, Default::default());
+        let out_full =
+            super::attention_flash(q, k, v, Some(mask_full), None, None, Default::default());
 
         let bcast: &[f32] = out_bcast.storage();
         let full: &[f32] = out_full.storage();
@@ -1270,7 +1422,7 @@ mod tests {
         let k = flex_f32(k_data, &[1, 1, seq_kv, head_dim]);
         let v = flex_f32(v_data, &[1, 1, seq_kv, val_dim]);
 
-        let result = super::attention(q, k, v, None, None, Default::default());
+        let result = super::attention(q, k, v, None, None, None, Default::default());
         let data: &[f32] = result.storage();
 
         // All scores equal -> output = mean of 0..127 = 63.5 for both cols.
@@ -1315,7 +1467,7 @@ mod tests {
             is_causal: true,
             ..Default::default()
         };
-        let result = super::attention(q, k, v, None, None, opts);
+        let result = super::attention(q, k, v, None, None, None, opts);
         let data: &[f32] = result.storage();
 
         // causal_offset = seq_kv - seq_q = 124
@@ -1347,7 +1499,7 @@ mod tests {
         let v = flex_f32(v_data, &[1, 1, seq_kv, val_dim]);
         let mask = flex_bool(mask_data, &[1, 1, seq_q, seq_kv]);
 
-        let result = super::attention(q, k, v, Some(mask), None, Default::default());
+        let result = super::attention(q, k, v, Some(mask), None, None, Default::default());
         let data: &[f32] = result.storage();
 
         // Visible = 64..128 -> mean of 64..=127 = 95.5.
@@ -1385,7 +1537,7 @@ mod tests {
         let k = flex_f32(k_data, &[1, 1, seq_kv, head_dim]);
         let v = flex_f32(v_data, &[1, 1, seq_kv, val_dim]);
 
-        let result = super::attention(q, k, v, None, None, Default::default());
+        let result = super::attention(q, k, v, None, None, None, Default::default());
         let data: &[f32] = result.storage();
 
         // Large-score keys (tile 2) dominate softmax -> output ~ 1.0.
@@ -1410,7 +1562,7 @@ mod tests {
         let k = flex_f32(k_data, &[1, 1, seq_kv, head_dim]);
         let v = flex_f32(v_data, &[1, 1, seq_kv, val_dim]);
 
-        let result = super::attention(q, k, v, None, None, Default::default());
+        let result = super::attention(q, k, v, None, None, None, Default::default());
         let data: &[f32] = result.storage();
 
         // Uniform attention -> mean of 0..99 = 49.5.
@@ -1484,10 +1636,14 @@ mod tests {
                 v.clone(),
                 mask.clone(),
                 bias.clone(),
+                None,
                 options,
             );
-            let naive = super::attention_naive(q, k, v, mask, bias, options);
+            let naive = super::attention_naive(q, k, v, mask, bias, None, options);
+            assert_flash_matches_naive(&flash, &naive, label);
+        }
 
+        fn assert_flash_matches_naive(flash: &FlexTensor, naive: &FlexTensor, label: &str) {
             let flash_data: &[f32] = flash.storage();
             let naive_data: &[f32] = naive.storage();
             assert_eq!(
@@ -1515,6 +1671,16 @@ mod tests {
             scale: Some(0.05),
             softcap: Some(30.0),
             is_causal: true,
+            ..Default::default()
+        };
+        let sliding_window = AttentionModuleOptions {
+            is_causal: true,
+            sliding_window: Some(24),
+            ..Default::default()
+        };
+        let alibi = AttentionModuleOptions {
+            alibi_max_bias: Some(8.0),
+            ..Default::default()
         };
 
         run_both(1, 1, 4, 4, 8, 8, false, false, default, "basic_4x4");
@@ -1562,6 +1728,41 @@ mod tests {
             causal,
             "partial_tile_causal",
         );
+        // Windows, ALiBi and documents spanning several KV tiles.
+        run_both(
+            1,
+            2,
+            32,
+            128,
+            16,
+            16,
+            false,
+            false,
+            sliding_window,
+            "sliding_window",
+        );
+        run_both(1, 3, 32, 128, 16, 16, false, false, alibi, "alibi");
+
+        let q = make_tensor(&[2, 2, 128, 16], DType::F32);
+        let offsets = || {
+            FlexTensor::new(
+                Bytes::from_elems(vec![0i64, 50, 51, 128]),
+                Layout::contiguous(Shape::from(vec![4])),
+                DType::I64,
+            )
+        };
+        let flash = super::attention_flash(
+            q.clone(),
+            q.clone(),
+            q.clone(),
+            None,
+            None,
+            Some(offsets()),
+            causal,
+        );
+        let naive =
+            super::attention_naive(q.clone(), q.clone(), q, None, None, Some(offsets()), causal);
+        assert_flash_matches_naive(&flash, &naive, "packed");
     }
 
     #[test]
@@ -1570,7 +1771,7 @@ mod tests {
         let k = q.clone();
         let v = flex_f64(vec![10.0f64, 20.0], &[1, 1, 2, 1]);
 
-        let result = super::attention(q, k, v, None, None, Default::default());
+        let result = super::attention(q, k, v, None, None, None, Default::default());
         let data: &[f64] = result.storage();
 
         assert!((data[0] - 13.30).abs() < 0.1, "got {}", data[0]);
@@ -1596,9 +1797,10 @@ mod tests {
             v.clone(),
             None,
             None,
+            None,
             Default::default(),
         );
-        let naive = super::attention_naive(q, k, v, None, None, Default::default());
+        let naive = super::attention_naive(q, k, v, None, None, None, Default::default());
 
         let flash_data: &[f16] = flash.storage();
         let naive_data: &[f16] = naive.storage();
@@ -115,6 +115,7 @@ pub mod pool;
 mod qtensor;
 pub mod reduce;
 pub mod repeat_dim;
+pub mod selective_scan;
 pub mod slice;
 pub mod sort;
 mod transaction;
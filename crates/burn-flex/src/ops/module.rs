@@ -9,7 +9,7 @@ use burn_backend::{
     ops::{
         AttentionModuleOptions, ConvOptions, ConvTransposeOptions, DeformConv2dBackward,
         DeformConvOptions, FloatTensorOps, IntTensorOps, InterpolateMode, InterpolateOptions,
-        MaxPool2dBackward, MaxPool2dWithIndices, ModuleOps,
+        MaxPool2dBackward, MaxPool2dWithIndices, ModuleOps, SelectiveScanBackward,
     },
     tensor::{BoolTensor, FloatTensor, IntTensor},
 };
@@ -723,9 +723,37 @@ impl ModuleOps<Flex> for Flex {
         value: FloatTensor<Flex>,
         mask: Option<BoolTensor<Flex>>,
         attn_bias: Option<FloatTensor<Flex>>,
+        seq_offsets: Option<IntTensor<Flex>>,
         options: AttentionModuleOptions,
     ) -> FloatTensor<Flex> {
-        crate::ops::attention::attention(query, key, value, mask, attn_bias, options)
+        crate::ops::attention::attention(query, key, value, mask, attn_bias, seq_offsets, options)
+    }
+
+    fn selective_scan(
+        x: FloatTensor<Flex>,
+        delta: FloatTensor<Flex>,
+        a: FloatTensor<Flex>,
+        b: FloatTensor<Flex>,
+        c: FloatTensor<Flex>,
+    ) -> FloatTensor<Flex> {
+        crate::ops::selective_scan::selective_scan(x, delta, a, b, c)
+    }
+
+    fn has_selective_scan_backward() -> bool {
+        true
+    }
+
+    fn selective_scan_backward(
+        x: FloatTensor<Flex>,
+        delta: FloatTensor<Flex>,
+        a: FloatTensor<Flex>,
+        b: FloatTensor<Flex>,
+        c: FloatTensor<Flex>,
+        output_grad: FloatTensor<Flex>,
+    ) -> SelectiveScanBackward<Flex> {
+        let [x_grad, delta_grad, a_grad, b_grad, c_grad] =
+            crate::ops::selective_scan::selective_scan_backward(x, delta, a, b, c, output_grad);
+        SelectiveScanBackward::new(x_grad, delta_grad, a_grad, b_grad, c_grad)
     }
 
     fn rfft(
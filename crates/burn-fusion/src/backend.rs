@@ -243,7 +243,7 @@ pub type Client<R> = GlobalFusionClient<R>;
 /// Trait that defines a runtime that will benefits from fused operations.
 pub trait FusionRuntime: Send + Sync + Sized + core::fmt::Debug + 'static {
     /// The state that can be serialized for an optimization.
-    type OptimizationState: Serialize + DeserializeOwned;
+    type OptimizationState: Serialize + DeserializeOwned + Send + 'static;
     /// Optimization type for the backend.
     type Optimization: Optimization<Self>;
     /// Handle used to store tensor dynamically.
@@ -154,7 +154,7 @@ where
         let id = StreamId::current();
         self.server
             .submit_blocking(move |server| {
-                server.drain_stream(id);
+                server.sync_stream(id);
                 sync_fn()
             })
             .unwrap()
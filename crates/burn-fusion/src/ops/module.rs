@@ -6,7 +6,7 @@ use burn_backend::{
     ops::{
         ConvOptions, ConvTransposeOptions, DeformConv2dBackward, DeformConvOptions,
         InterpolateOptions, MaxPool1dBackward, MaxPool1dWithIndices, MaxPool2dBackward,
-        MaxPool2dWithIndices, ModuleOps,
+        MaxPool2dWithIndices, ModuleOps, SelectiveScanBackward,
     },
     tensor::{FloatTensor, IntTensor},
 };
@@ -1542,6 +1542,7 @@ impl<B: FusionBackend> ModuleOps<Fusion<B>> for Fusion<B> {
         value: FloatTensor<Fusion<B>>,
         mask: Option<burn_backend::tensor::BoolTensor<Fusion<B>>>,
         attn_bias: Option<FloatTensor<Fusion<B>>>,
+        seq_offsets: Option<IntTensor<Fusion<B>>>,
         options: burn_backend::ops::AttentionModuleOptions,
     ) -> FloatTensor<Fusion<B>> {
         make_ops!(
@@ -1556,6 +1557,10 @@ impl<B: FusionBackend> ModuleOps<Fusion<B>> for Fusion<B> {
                     .attn_bias
                     .as_ref()
                     .map(|ab| handles.get_float_tensor::<B>(ab));
+                let seq_offsets = args
+                    .seq_offsets
+                    .as_ref()
+                    .map(|so| handles.get_int_tensor::<B>(so));
 
                 let output = B::attention(
                     query,
@@ -1563,6 +1568,7 @@ impl<B: FusionBackend> ModuleOps<Fusion<B>> for Fusion<B> {
                     value,
                     mask,
                     attn_bias,
+                    seq_offsets,
                     args.options.clone().into(),
                 );
 
@@ -1579,6 +1585,7 @@ impl<B: FusionBackend> ModuleOps<Fusion<B>> for Fusion<B> {
             value.into_ir(),
             mask.map(|m| m.into_ir()),
             attn_bias.map(|ab| ab.into_ir()),
+            seq_offsets.map(|so| so.into_ir()),
             options.into(),
             || client.create_empty_handle(),
         );
@@ -1765,4 +1772,111 @@ impl<B: FusionBackend> ModuleOps<Fusion<B>> for Fusion<B> {
             )
             .output()
     }
+
+    fn has_selective_scan_backward() -> bool {
+        B::has_selective_scan_backward()
+    }
+
+    fn selective_scan(
+        x: FloatTensor<Fusion<B>>,
+        delta: FloatTensor<Fusion<B>>,
+        a: FloatTensor<Fusion<B>>,
+        b: FloatTensor<Fusion<B>>,
+        c: FloatTensor<Fusion<B>>,
+    ) -> FloatTensor<Fusion<B>> {
+        // Like `ctc_loss`, the scan is a non-fuseable IR node that runs on the
+        // inner backend, either through a native kernel or the decomposed default.
+        make_ops!(
+            SelectiveScanOps,
+            SelectiveScanOpIr,
+            |args: &SelectiveScanOpIr, handles: &mut HandleContainer<B::Handle>| {
+                let x = handles.get_float_tensor::<B>(&args.x);
+                let delta = handles.get_float_tensor::<B>(&args.delta);
+                let a = handles.get_float_tensor::<B>(&args.a);
+                let b = handles.get_float_tensor::<B>(&args.b);
+                let c = handles.get_float_tensor::<B>(&args.c);
+                let output = B::selective_scan(x, delta, a, b, c);
+                handles.register_float_tensor::<B>(&args.out.id, output);
+            }
+        );
+
+        let streams = StreamId::current();
+        let client = x.client.clone();
+        let desc = SelectiveScanOpIr::create(
+            x.into_ir(),
+            delta.into_ir(),
+            a.into_ir(),
+            b.into_ir(),
+            c.into_ir(),
+            || client.create_empty_handle(),
+        );
+
+        client
+            .register(
+                streams,
+                OperationIr::Module(ModuleOperationIr::SelectiveScan(desc.clone())),
+                SelectiveScanOps::<B>::new(desc),
+            )
+            .output()
+    }
+
+    fn selective_scan_backward(
+        x: FloatTensor<Fusion<B>>,
+        delta: FloatTensor<Fusion<B>>,
+        a: FloatTensor<Fusion<B>>,
+        b: FloatTensor<Fusion<B>>,
+        c: FloatTensor<Fusion<B>>,
+        output_grad: FloatTensor<Fusion<B>>,
+    ) -> SelectiveScanBackward<Fusion<B>> {
+        make_ops!(
+            SelectiveScanBackwardOps,
+            SelectiveScanBackwardOpIr,
+            |args: &SelectiveScanBackwardOpIr, handles: &mut HandleContainer<B::Handle>| {
+                let x = handles.get_float_tensor::<B>(&args.x);
+                let delta = handles.get_float_tensor::<B>(&args.delta);
+                let a = handles.get_float_tensor::<B>(&args.a);
+                let b = handles.get_float_tensor::<B>(&args.b);
+                let c = handles.get_float_tensor::<B>(&args.c);
+                let output_grad = handles.get_float_tensor::<B>(&args.out_grad);
+
+                let output = B::selective_scan_backward(x, delta, a, b, c, output_grad);
+
+                handles.register_float_tensor::<B>(&args.x_grad.id, output.x_grad);
+                handles.register_float_tensor::<B>(&args.delta_grad.id, output.delta_grad);
+                handles.register_float_tensor::<B>(&args.a_grad.id, output.a_grad);
+                handles.register_float_tensor::<B>(&args.b_grad.id, output.b_grad);
+                handles.register_float_tensor::<B>(&args.c_grad.id, output.c_grad);
+            }
+        );
+
+        let streams = StreamId::current();
+        let client = x.client.clone();
+        let desc = SelectiveScanBackwardOpIr::create(
+            x.into_ir(),
+            delta.into_ir(),
+            a.into_ir(),
+            b.into_ir(),
+            c.into_ir(),
+            output_grad.into_ir(),
+            || client.create_empty_handle(),
+        );
+
+        let mut outputs = client
+            .register(
+                streams,
+                OperationIr::Module(ModuleOperationIr::SelectiveScanBackward(Box::new(
+                    desc.clone(),
+                ))),
+                SelectiveScanBackwardOps::<B>::new(desc),
+            )
+            .into_iter();
+
+        SelectiveScanBackward::new(
+            outputs.next().unwrap(),
+            outputs.next().unwrap(),
+            outputs.next().unwrap(),
+            outputs.next().unwrap(),
+            outputs.next().unwrap(),
+        )
+    }
 }
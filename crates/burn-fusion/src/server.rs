@@ -71,6 +71,12 @@ where
         self.streams.drain(&mut self.handles, id)
     }
 
+    /// Drain a stream and write the execution plans found so far to the plan cache.
+    pub fn sync_stream(&mut self, id: StreamId) {
+        self.drain_stream(id);
+        self.streams.flush_plans();
+    }
+
     /// Ready `id`'s stream for reading `tensor` and return the IR the handle
     /// lookup must use.
     ///
@@ -607,6 +607,10 @@ impl RelativeOps for ModuleOperationIr {
                 value: desc.value.to_relative(converter),
                 mask: desc.mask.as_ref().map(|m| m.to_relative(converter)),
                 attn_bias: desc.attn_bias.as_ref().map(|ab| ab.to_relative(converter)),
+                seq_offsets: desc
+                    .seq_offsets
+                    .as_ref()
+                    .map(|so| so.to_relative(converter)),
                 options: desc.options.clone(),
                 out: desc.out.to_relative(converter),
             }),
@@ -629,6 +633,31 @@ impl RelativeOps for ModuleOperationIr {
                     out: desc.out.to_relative(converter),
                 })
             }
+            ModuleOperationIr::SelectiveScan(desc) => {
+                ModuleOperationIr::SelectiveScan(SelectiveScanOpIr {
+                    x: desc.x.to_relative(converter),
+                    delta: desc.delta.to_relative(converter),
+                    a: desc.a.to_relative(converter),
+                    b: desc.b.to_relative(converter),
+                    c: desc.c.to_relative(converter),
+                    out: desc.out.to_relative(converter),
+                })
+            }
+            ModuleOperationIr::SelectiveScanBackward(desc) => {
+                ModuleOperationIr::SelectiveScanBackward(Box::new(SelectiveScanBackwardOpIr {
+                    x: desc.x.to_relative(converter),
+                    delta: desc.delta.to_relative(converter),
+                    a: desc.a.to_relative(converter),
+                    b: desc.b.to_relative(converter),
+                    c: desc.c.to_relative(converter),
+                    out_grad: desc.out_grad.to_relative(converter),
+                    x_grad: desc.x_grad.to_relative(converter),
+                    delta_grad: desc.delta_grad.to_relative(converter),
+                    a_grad: desc.a_grad.to_relative(converter),
+                    b_grad: desc.b_grad.to_relative(converter),
+                    c_grad: desc.c_grad.to_relative(converter),
+                }))
+            }
             ModuleOperationIr::LayerNorm(desc) => ModuleOperationIr::LayerNorm(LayerNormOpIr {
                 input: desc.input.to_relative(converter),
                 gamma: desc.gamma.to_relative(converter),
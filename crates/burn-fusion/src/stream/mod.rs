@@ -30,3 +30,4 @@ pub use base::*;
 pub use context::*;
 pub use execution::*;
 pub use multi::*;
+pub use store::PlanCache;
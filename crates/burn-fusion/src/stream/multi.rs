@@ -2,7 +2,7 @@ use super::{
     StreamId,
     execution::{ExecutionMode, Processor, StreamSegment},
     queue::OperationQueue,
-    store::{ExecutionPlanId, ExecutionPlanStore},
+    store::{ExecutionPlanId, ExecutionPlanStore, PlanCache},
 };
 use crate::{FusionRuntime, UnfusedOp, search::BlockOptimization};
 use burn_ir::{HandleContainer, OperationIr, TensorId};
@@ -108,6 +108,8 @@ pub struct MultiStream<R: FusionRuntime> {
     shared_sources: HashSet<TensorId>,
     streams: HashMap<StreamId, Stream<R>>,
     optimizations: ExecutionPlanStore<R::Optimization>,
+    /// Where the plans are saved when the on-disk [plan cache](PlanCache) is enabled.
+    plan_cache: Option<PlanCache<R>>,
     device: R::FusionDevice,
     #[cfg(feature = "memory-checks")]
     memory_checks: super::memory_checks::MemoryChecks,
@@ -115,10 +117,22 @@ pub struct MultiStream<R: FusionRuntime> {
 
 impl<R: FusionRuntime> MultiStream<R> {
     pub(crate) fn new(device: R::FusionDevice) -> Self {
+        let plan_cache = PlanCache::from_config(&device);
+        Self::with_plan_cache(device, plan_cache)
+    }
+
+    /// Create the streams of a device, preloading the plans saved in the `plan_cache`.
+    fn with_plan_cache(device: R::FusionDevice, mut plan_cache: Option<PlanCache<R>>) -> Self {
+        let mut optimizations = ExecutionPlanStore::new();
+        if let Some(cache) = &mut plan_cache {
+            cache.preload(&device, &mut optimizations);
+        }
+
         Self {
             shared_sources: HashSet::new(),
             streams: HashMap::new(),
-            optimizations: ExecutionPlanStore::new(),
+            optimizations,
+            plan_cache,
             device,
             #[cfg(feature = "memory-checks")]
             memory_checks: super::memory_checks::MemoryChecks::default(),
@@ -215,6 +229,22 @@ impl<R: FusionRuntime> MultiStream<R> {
         s.cursor += (len_before - len_after) as u64;
     }
 
+    /// Save the plans found so far in the background when the [plan cache](PlanCache) is
+    /// enabled.
+    fn save_plans(&mut self) {
+        if let Some(cache) = &mut self.plan_cache {
+            cache.save(&self.optimizations);
+        }
+    }
+
+    /// Write the plans found so far when the [plan cache](PlanCache) is enabled, waiting for the
+    /// cache file to be written.
+    pub(crate) fn flush_plans(&mut self) {
+        if let Some(cache) = &mut self.plan_cache {
+            cache.flush(&self.optimizations);
+        }
+    }
+
     /// Mark a tensor as read.
     #[allow(unused_variables)]
     pub fn mark_read(
@@ -259,6 +289,7 @@ impl<R: FusionRuntime> MultiStream<R> {
                 stream.queue.flush_deferred(handles);
             }
         });
+        self.save_plans();
         #[cfg(feature = "test-util")]
         crate::inspect::emit_handle_snapshot(id, handles.handle_ids().copied());
     }
@@ -325,6 +356,12 @@ impl<R: FusionRuntime> MultiStream<R> {
     }
 }
 
+impl<R: FusionRuntime> Drop for MultiStream<R> {
+    fn drop(&mut self) {
+        self.flush_plans();
+    }
+}
+
 /// How a cross-thread read of a tensor on another stream must be served.
 ///
 /// A cross-thread event lands at an arbitrary point between the home
@@ -390,6 +427,7 @@ impl<R: FusionRuntime> Stream<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stream::store::{PlanCacheFile, VERSION};
     use crate::{
         FuserProperties, FuserStatus, NumOperations, OperationFuser, Optimization, UnfusedOp,
         stream::{Context, Operation, OrderedExecution},
@@ -551,6 +589,33 @@ mod tests {
             }
         }
 
+        fn with_plan_cache(directory: &std::path::Path) -> Self {
+            let cache = PlanCache::new(directory, &TestDevice);
+            Self {
+                streams: MultiStream::with_plan_cache(TestDevice, Some(cache)),
+                handles: HandleContainer::new(),
+                id: StreamId::current(),
+            }
+        }
+
+        /// Register `exp(exp(t0))`, drain the stream and write the plans to the cache.
+        fn run_exp_chain(&mut self) {
+            let (t0, t1, t2) = (TensorId::new(0), TensorId::new(1), TensorId::new(2));
+            self.handles.register_handle(t0, TestHandle);
+            self.register_exp(t0, t1);
+            self.register_exp(t1, t2);
+            self.streams.drain(&mut self.handles, self.id);
+            self.streams.flush_plans();
+        }
+
+        fn num_explorations(&self) -> usize {
+            self.streams
+                .streams
+                .get(&self.id)
+                .map(|stream| stream.processor.num_explorations())
+                .unwrap_or(0)
+        }
+
         fn register_exp(&mut self, input: TensorId, out: TensorId) {
             self.streams.register(
                 self.id,
@@ -813,4 +878,103 @@ mod tests {
             .is_some_and(|stream| stream.queue.variables.contains_key(&t0));
         assert!(!stale, "the stale variables entry is cleaned up");
     }
+
+    #[test]
+    fn plan_cache_preloads_explored_plans() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let mut first = TestSetup::with_plan_cache(directory.path());
+        first.run_exp_chain();
+        assert!(first.num_explorations() > 0);
+
+        let cache = PlanCache::<TestRuntime>::new(directory.path(), &TestDevice);
+        let num_plans = first.streams.optimizations.plans().len();
+        assert_eq!(cache.len(), num_plans, "every explored plan is saved");
+
+        let mut second = TestSetup::with_plan_cache(directory.path());
+        assert_eq!(second.streams.optimizations.plans().len(), num_plans);
+
+        second.run_exp_chain();
+        assert_eq!(
+            second.num_explorations(),
+            0,
+            "the preloaded plan is executed without exploring"
+        );
+        assert_eq!(second.streams.optimizations.plans().len(), num_plans);
+    }
+
+    #[test]
+    fn plan_cache_ignores_plans_saved_by_another_version() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let mut first = TestSetup::with_plan_cache(directory.path());
+        first.run_exp_chain();
+
+        let cache = PlanCache::<TestRuntime>::new(directory.path(), &TestDevice);
+        let bytes = std::fs::read(cache.path()).unwrap();
+        let mut file: PlanCacheFile<()> = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(file.version, VERSION);
+        file.version = "0.0.0".to_string();
+        std::fs::write(cache.path(), rmp_serde::to_vec(&file).unwrap()).unwrap();
+        assert!(cache.is_empty());
+
+        let mut second = TestSetup::with_plan_cache(directory.path());
+        assert!(second.streams.optimizations.plans().is_empty());
+
+        second.run_exp_chain();
+        assert!(
+            second.num_explorations() > 0,
+            "the stale plans are explored again"
+        );
+        assert_eq!(
+            cache.len(),
+            file.plans.len(),
+            "the stale file is overwritten"
+        );
+    }
+
+    #[test]
+    fn plan_cache_debounces_saves_until_flushed() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let mut setup = TestSetup::with_plan_cache(directory.path());
+        setup.run_exp_chain();
+        let cache = PlanCache::<TestRuntime>::new(directory.path(), &TestDevice);
+        let saved = std::fs::read(cache.path()).unwrap();
+
+        // A longer chain is explored right after the last save.
+        for (input, out) in [(2, 3), (3, 4), (4, 5)] {
+            setup.register_exp(TensorId::new(input), TensorId::new(out));
+        }
+        setup.streams.drain(&mut setup.handles, setup.id);
+        let num_plans = setup.streams.optimizations.plans().len();
+        assert!(
+            num_plans > saved_len(&saved),
+            "the longer chain is a new plan"
+        );
+        assert_eq!(
+            std::fs::read(cache.path()).unwrap(),
+            saved,
+            "the save is postponed"
+        );
+
+        setup.streams.flush_plans();
+        assert_eq!(cache.len(), num_plans, "the flush writes every plan");
+
+        // Dropping the streams writes the plans found since the last flush.
+        for (input, out) in [(5, 6), (6, 7), (7, 8), (8, 9)] {
+            setup.register_exp(TensorId::new(input), TensorId::new(out));
+        }
+        setup.streams.drain(&mut setup.handles, setup.id);
+        let num_plans = setup.streams.optimizations.plans().len();
+        drop(setup);
+        assert_eq!(cache.len(), num_plans);
+    }
+
+    fn saved_len(bytes: &[u8]) -> usize {
+        rmp_serde::from_slice::<PlanCacheFile<()>>(bytes)
+            .unwrap()
+            .plans
+            .len()
+    }
 }
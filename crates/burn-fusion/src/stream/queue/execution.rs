@@ -1,9 +1,12 @@
 use burn_ir::{HandleContainer, TensorStatus};
-use burn_std::config::{fusion::FusionLogLevel, log_fusion};
+use burn_std::{
+    config::{fusion::FusionLogLevel, log_fusion},
+    profile::{EventTimer, ProfileEventKind},
+};
 use std::sync::Arc;
 
 use crate::{
-    FusionRuntime, UnfusedOp,
+    FusionRuntime, NumOperations, UnfusedOp,
     search::BlockOptimization,
     stream::{
         Context, ContextGuard, OperationConverter, OrderedExecution, RelativeOps, StreamId,
@@ -154,10 +157,20 @@ fn execute_strategy<R: FusionRuntime>(
 ) {
     match strategy {
         ExecutionStrategy::Optimization { ordering, opt, .. } => {
+            let timer = EventTimer::start(ProfileEventKind::Fusion, opt.name())
+                .map(|timer| timer.with_num_operations(opt.len()));
             execution.execute_optimization(opt, context, ordering.clone());
+            if let Some(timer) = timer {
+                timer.finish();
+            }
         }
         ExecutionStrategy::Operations { ordering } => {
+            let timer = EventTimer::start(ProfileEventKind::Fusion, "Operations")
+                .map(|timer| timer.with_num_operations(ordering.len()));
             execution.execute_operations(&mut context.handles, ordering);
+            if let Some(timer) = timer {
+                timer.finish();
+            }
         }
         ExecutionStrategy::Composed(items) => {
             for item in items.iter_mut() {
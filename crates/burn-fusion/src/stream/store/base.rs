@@ -11,6 +11,8 @@ use serde::{Deserialize, Serialize};
 pub(crate) struct ExecutionPlanStore<O> {
     plans: Vec<ExecutionPlan<O>>,
     index: ExecutionPlanIndex,
+    /// Incremented every time a plan or a trigger is added.
+    revision: u64,
 }
 
 /// How a list of operations should be executed.
@@ -42,7 +44,7 @@ impl<O: crate::NumOperations> ExecutionStrategy<O> {
 }
 
 /// The trigger that indicates when to stop exploring.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum ExecutionTrigger {
     OnOperations(Vec<OperationIr>),
     OnSync,
@@ -68,6 +70,7 @@ impl<O: core::fmt::Debug> ExecutionPlanStore<O> {
         Self {
             plans: Vec::new(),
             index: ExecutionPlanIndex::default(),
+            revision: 0,
         }
     }
 
@@ -88,6 +91,7 @@ impl<O: core::fmt::Debug> ExecutionPlanStore<O> {
         });
 
         self.plans.push(exploration);
+        self.revision += 1;
 
         id
     }
@@ -106,6 +110,17 @@ impl<O: core::fmt::Debug> ExecutionPlanStore<O> {
 
         if !criteria.contains(&trigger) {
             criteria.push(trigger);
+            self.revision += 1;
         }
     }
+
+    /// All the plans in the store, indexed by their [id](ExecutionPlanId).
+    pub fn plans(&self) -> &[ExecutionPlan<O>] {
+        &self.plans
+    }
+
+    /// The number of modifications made to the store, used to know when it changed.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
 }
@@ -0,0 +1,388 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use burn_backend::DeviceOps;
+use burn_ir::OperationIr;
+use burn_std::config::{config, fusion::FusionLogLevel, log_fusion};
+use serde::{Deserialize, Serialize, de::DeserializeOwned, de::IgnoredAny};
+
+use super::{ExecutionPlan, ExecutionPlanStore, ExecutionStrategy, ExecutionTrigger};
+use crate::{FusionRuntime, Optimization, search::BlockOptimization};
+
+/// The version written in every cache file.
+///
+/// Plans saved by another version are ignored, since neither the operation representation nor
+/// the optimization states are guaranteed to be compatible between versions.
+pub(crate) const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "+plans.1");
+
+/// The minimum delay between two saves started from the execution path.
+const SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// On-disk cache of the [execution plans](ExecutionPlan) explored on a device.
+///
+/// Each backend and device has its own file in the cache directory, holding the plans keyed by
+/// the hash of their operation sequence. The plans are preloaded when the streams of the device
+/// are created. When new plans or triggers are found, the file is rewritten on a background
+/// thread, at most once per second, and a last time when the device is synchronized or its
+/// streams are dropped. A file written by another version of Burn is ignored, then overwritten
+/// with the new plans.
+///
+/// The cache is opt-in: it is enabled by setting the `plan_cache` directory of the
+/// [fusion config](burn_std::config::fusion::FusionConfig), or with the
+/// `BURN_FUSION_PLAN_CACHE` environment variable.
+pub struct PlanCache<R: FusionRuntime> {
+    path: PathBuf,
+    backend: String,
+    device: String,
+    revision: u64,
+    /// The serializable form of the plans of the store, each converted once.
+    snapshot: Arc<Mutex<Vec<PlanState<R::OptimizationState>>>>,
+    writer: Option<JoinHandle<()>>,
+    last_save: Option<Instant>,
+    _runtime: PhantomData<R>,
+}
+
+/// The content of a cache file.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PlanCacheFile<S> {
+    pub(crate) version: String,
+    pub(crate) backend: String,
+    pub(crate) device: String,
+    pub(crate) plans: Vec<PlanState<S>>,
+}
+
+/// The content of a cache file, borrowed from the snapshot when saving.
+#[derive(Serialize)]
+struct PlanCacheFileRef<'a, S> {
+    version: &'a str,
+    backend: &'a str,
+    device: &'a str,
+    plans: &'a [PlanState<S>],
+}
+
+/// The serializable form of an [execution plan](ExecutionPlan).
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PlanState<S> {
+    /// The hash of the operation sequence.
+    key: u64,
+    operations: Vec<OperationIr>,
+    triggers: Vec<ExecutionTrigger>,
+    strategy: StrategyState<S>,
+    ordering: Vec<usize>,
+}
+
+/// The serializable form of an [execution strategy](ExecutionStrategy).
+#[derive(Serialize, Deserialize)]
+enum StrategyState<S> {
+    Optimization {
+        state: S,
+        ordering: Vec<usize>,
+        score: u64,
+    },
+    Operations {
+        ordering: Vec<usize>,
+    },
+    Composed(Vec<StrategyState<S>>),
+}
+
+impl<R: FusionRuntime> PlanCache<R> {
+    /// Create the cache of the given device, stored in `directory`.
+    pub fn new(directory: impl AsRef<Path>, device: &R::FusionDevice) -> Self {
+        let backend = core::any::type_name::<R>().to_string();
+        let id = device.id();
+        let file = format!(
+            "fusion-{:016x}-{}-{}.mpk",
+            hash(&backend),
+            id.type_id,
+            id.index_id
+        );
+
+        Self {
+            path: directory.as_ref().join(file),
+            backend,
+            device: format!("{}:{}", id.type_id, id.index_id),
+            revision: 0,
+            snapshot: Arc::new(Mutex::new(Vec::new())),
+            writer: None,
+            last_save: None,
+            _runtime: PhantomData,
+        }
+    }
+
+    /// Create the cache of the given device if a cache directory is configured.
+    pub fn from_config(device: &R::FusionDevice) -> Option<Self> {
+        config()
+            .fusion()
+            .plan_cache
+            .as_ref()
+            .map(|directory| Self::new(directory, device))
+    }
+
+    /// The path of the cache file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The number of valid plans currently saved in the cache file.
+    pub fn len(&self) -> usize {
+        self.read::<IgnoredAny>()
+            .map(|file| file.plans.len())
+            .unwrap_or(0)
+    }
+
+    /// If no valid plan is saved in the cache file.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Restore the saved plans on the device, returning how many could be restored.
+    ///
+    /// This decodes every optimization state, which is useful to check that a cache file
+    /// populated ahead of time is compatible with the device it is deployed on.
+    pub fn validate(&self, device: &R::FusionDevice) -> usize {
+        let mut store = ExecutionPlanStore::new();
+        self.restore(device, &mut store);
+        store.plans().len()
+    }
+
+    /// Add the saved plans to the store.
+    pub(crate) fn preload(
+        &mut self,
+        device: &R::FusionDevice,
+        store: &mut ExecutionPlanStore<R::Optimization>,
+    ) {
+        self.restore(device, store);
+        self.revision = store.revision();
+    }
+
+    /// Save the plans of the store on a background thread if it changed since the last save.
+    ///
+    /// The save is skipped while the previous one is running or was started less than a second
+    /// ago, the plans will then be saved by a later call or by [flush](Self::flush).
+    pub(crate) fn save(&mut self, store: &ExecutionPlanStore<R::Optimization>) {
+        let writing = self
+            .writer
+            .as_ref()
+            .is_some_and(|writer| !writer.is_finished());
+        let recent = self
+            .last_save
+            .is_some_and(|last_save| last_save.elapsed() < SAVE_INTERVAL);
+        if store.revision() == self.revision || writing || recent {
+            return;
+        }
+
+        self.join();
+        self.update(store);
+
+        let snapshot = self.snapshot.clone();
+        let (path, backend, device) =
+            (self.path.clone(), self.backend.clone(), self.device.clone());
+        self.writer = Some(std::thread::spawn(move || {
+            let plans = snapshot.lock().unwrap();
+            write(&path, &backend, &device, &plans);
+        }));
+    }
+
+    /// Save the plans of the store if it changed since the last save, and wait until the cache
+    /// file is written.
+    pub(crate) fn flush(&mut self, store: &ExecutionPlanStore<R::Optimization>) {
+        self.join();
+        if store.revision() == self.revision {
+            return;
+        }
+
+        self.update(store);
+        let plans = self.snapshot.lock().unwrap();
+        write(&self.path, &self.backend, &self.device, &plans);
+    }
+
+    /// Convert the plans added to the store since the last save, and refresh the triggers.
+    fn update(&mut self, store: &ExecutionPlanStore<R::Optimization>) {
+        self.revision = store.revision();
+        self.last_save = Some(Instant::now());
+
+        let mut snapshot = self.snapshot.lock().unwrap();
+        let plans = store.plans();
+        for (state, plan) in snapshot.iter_mut().zip(plans) {
+            // Triggers are only ever added to a plan.
+            if state.triggers.len() != plan.triggers.len() {
+                state.triggers = plan.triggers.clone();
+            }
+        }
+        let num_saved = snapshot.len();
+        snapshot.extend(plans[num_saved..].iter().map(PlanState::new::<R>));
+    }
+
+    /// Wait for the running save, if any.
+    fn join(&mut self) {
+        if let Some(writer) = self.writer.take() {
+            // A panicking writer has nothing left to save.
+            let _ = writer.join();
+        }
+    }
+
+    /// Add the valid plans of the cache file to the store.
+    fn restore(&self, device: &R::FusionDevice, store: &mut ExecutionPlanStore<R::Optimization>) {
+        let Some(file) = self.read::<R::OptimizationState>() else {
+            return;
+        };
+        let num_plans = file.plans.len();
+
+        for plan in file.plans {
+            // Skip the plans whose key doesn't match, the hasher may have changed.
+            if plan.operations.is_empty() || plan.key != hash(&plan.operations) {
+                continue;
+            }
+
+            store.add(ExecutionPlan {
+                operations: plan.operations,
+                triggers: plan.triggers,
+                optimization: BlockOptimization::new(
+                    plan.strategy.restore::<R>(device),
+                    plan.ordering,
+                ),
+            });
+        }
+
+        let num_restored = store.plans().len();
+        let path = self.path.display().to_string();
+        log_fusion(FusionLogLevel::Basic, move || {
+            format!("[plan cache] restored {num_restored}/{num_plans} plans from {path}")
+        });
+    }
+
+    /// Read the cache file, returning `None` if it doesn't exist or is invalid.
+    fn read<S: DeserializeOwned>(&self) -> Option<PlanCacheFile<S>> {
+        let bytes = std::fs::read(&self.path).ok()?;
+        let path = self.path.display();
+
+        let file: PlanCacheFile<S> = match rmp_serde::from_slice(&bytes) {
+            Ok(file) => file,
+            Err(err) => {
+                let msg = format!("[plan cache] ignoring {path}, it failed to decode: {err}");
+                log_fusion(FusionLogLevel::Basic, move || msg);
+                return None;
+            }
+        };
+
+        if file.version != VERSION || file.backend != self.backend || file.device != self.device {
+            let msg = format!(
+                "[plan cache] ignoring {path}, it was saved by version {} for {} on device {}",
+                file.version, file.backend, file.device
+            );
+            log_fusion(FusionLogLevel::Basic, move || msg);
+            return None;
+        }
+
+        Some(file)
+    }
+}
+
+impl<S> PlanState<S> {
+    fn new<R: FusionRuntime<OptimizationState = S>>(plan: &ExecutionPlan<R::Optimization>) -> Self {
+        Self {
+            key: hash(&plan.operations),
+            operations: plan.operations.clone(),
+            triggers: plan.triggers.clone(),
+            strategy: StrategyState::new::<R>(&plan.optimization.strategy),
+            ordering: plan.optimization.ordering.clone(),
+        }
+    }
+}
+
+impl<S> StrategyState<S> {
+    fn new<R: FusionRuntime<OptimizationState = S>>(
+        strategy: &ExecutionStrategy<R::Optimization>,
+    ) -> Self {
+        match strategy {
+            ExecutionStrategy::Optimization {
+                opt,
+                ordering,
+                score,
+            } => Self::Optimization {
+                state: opt.to_state(),
+                ordering: ordering.as_ref().clone(),
+                score: *score,
+            },
+            ExecutionStrategy::Operations { ordering } => Self::Operations {
+                ordering: ordering.as_ref().clone(),
+            },
+            ExecutionStrategy::Composed(items) => Self::Composed(
+                items
+                    .iter()
+                    .map(|item| Self::new::<R>(item.as_ref()))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn restore<R: FusionRuntime<OptimizationState = S>>(
+        self,
+        device: &R::FusionDevice,
+    ) -> ExecutionStrategy<R::Optimization> {
+        match self {
+            Self::Optimization {
+                state,
+                ordering,
+                score,
+            } => ExecutionStrategy::Optimization {
+                opt: <R::Optimization as Optimization<R>>::from_state(device, state),
+                ordering: Arc::new(ordering),
+                score,
+            },
+            Self::Operations { ordering } => ExecutionStrategy::Operations {
+                ordering: Arc::new(ordering),
+            },
+            Self::Composed(items) => ExecutionStrategy::Composed(
+                items
+                    .into_iter()
+                    .map(|item| Box::new(item.restore::<R>(device)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Write the cache file, logging the failures.
+fn write<S: Serialize>(path: &Path, backend: &str, device: &str, plans: &[PlanState<S>]) {
+    let file = PlanCacheFileRef {
+        version: VERSION,
+        backend,
+        device,
+        plans,
+    };
+
+    if let Err(err) = write_atomic(path, &file) {
+        let path = path.display().to_string();
+        log_fusion(FusionLogLevel::Basic, move || {
+            format!("[plan cache] failed to save the plans to {path}: {err}")
+        });
+    }
+}
+
+/// Write the cache file through a temporary file, so that concurrent processes never read a
+/// partially written cache.
+fn write_atomic<T: Serialize>(path: &Path, file: &T) -> std::io::Result<()> {
+    let bytes = rmp_serde::to_vec(file).map_err(std::io::Error::other)?;
+
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
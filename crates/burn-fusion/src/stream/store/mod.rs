@@ -1,5 +1,9 @@
 mod base;
+mod cache;
 mod index;
 
 pub(crate) use base::*;
+pub use cache::PlanCache;
+#[cfg(test)]
+pub(crate) use cache::{PlanCacheFile, VERSION};
 pub(super) use index::*;
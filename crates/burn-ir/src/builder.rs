@@ -1362,6 +1362,7 @@ impl_ir_create!(
         value: TensorIr,
         mask: Option<TensorIr>,
         attn_bias: Option<TensorIr>,
+        seq_offsets: Option<TensorIr>,
         options: AttentionOptionsIr,
     },
     shape = Shape::new([query.shape[0], query.shape[1], query.shape[2], value.shape[3]]),
@@ -1393,6 +1394,18 @@ impl_ir_create!(
     dtype = log_probs.dtype
 );
 
+impl_ir_create!(
+    SelectiveScanOpIr {
+        x: TensorIr,
+        delta: TensorIr,
+        a: TensorIr,
+        b: TensorIr,
+        c: TensorIr,
+    },
+    shape = x.shape.clone(),
+    dtype = x.dtype
+);
+
 impl DequantizeOpIr {
     pub fn create(input: TensorIr, dtype: DType, new_id: impl FnOnce() -> TensorId) -> Self {
         let out = TensorIr::uninit(new_id(), input.shape.clone(), dtype);
@@ -1500,6 +1513,39 @@ impl DeformConv2dBackwardOpIr {
     }
 }
 
+impl SelectiveScanBackwardOpIr {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        x: TensorIr,
+        delta: TensorIr,
+        a: TensorIr,
+        b: TensorIr,
+        c: TensorIr,
+        out_grad: TensorIr,
+        mut new_id: impl FnMut() -> TensorId,
+    ) -> Self {
+        let x_grad = TensorIr::uninit(new_id(), x.shape.clone(), x.dtype);
+        let delta_grad = TensorIr::uninit(new_id(), delta.shape.clone(), delta.dtype);
+        let a_grad = TensorIr::uninit(new_id(), a.shape.clone(), a.dtype);
+        let b_grad = TensorIr::uninit(new_id(), b.shape.clone(), b.dtype);
+        let c_grad = TensorIr::uninit(new_id(), c.shape.clone(), c.dtype);
+
+        SelectiveScanBackwardOpIr {
+            x,
+            delta,
+            a,
+            b,
+            c,
+            out_grad,
+            x_grad,
+            delta_grad,
+            a_grad,
+            b_grad,
+            c_grad,
+        }
+    }
+}
+
 impl MaxPool1dWithIndicesOpIr {
     #[allow(clippy::too_many_arguments)]
     pub fn create(
@@ -329,6 +329,11 @@ pub enum ModuleOperationIr {
     /// Operation corresponding to
     /// [ctc_loss_backward](burn_backend::ops::ModuleOps::ctc_loss_backward).
     CtcLossBackward(CtcLossBackwardOpIr),
+    /// Operation corresponding to [selective_scan](burn_backend::ops::ModuleOps::selective_scan).
+    SelectiveScan(SelectiveScanOpIr),
+    /// Operation corresponding to
+    /// [selective_scan_backward](burn_backend::ops::ModuleOps::selective_scan_backward).
+    SelectiveScanBackward(Box<SelectiveScanBackwardOpIr>),
     /// Operation corresponding to [layer_norm](burn_backend::ops::ModuleOps::layer_norm).
     LayerNorm(LayerNormOpIr),
     /// Operation corresponding to [unfold4d](burn_backend::ops::ModuleOps::unfold4d).
@@ -1994,6 +1999,8 @@ pub struct AttentionOptionsIr {
     pub scale: Option<ScalarIr>,
     pub softcap: Option<ScalarIr>,
     pub is_causal: bool,
+    pub sliding_window: Option<usize>,
+    pub alibi_max_bias: Option<ScalarIr>,
 }
 
 impl From<AttentionOptionsIr> for AttentionModuleOptions {
@@ -2002,6 +2009,8 @@ impl From<AttentionOptionsIr> for AttentionModuleOptions {
             scale: ir.scale.map(|s| s.elem()),
             softcap: ir.softcap.map(|s| s.elem()),
             is_causal: ir.is_causal,
+            sliding_window: ir.sliding_window,
+            alibi_max_bias: ir.alibi_max_bias.map(|s| s.elem()),
         }
     }
 }
@@ -2012,6 +2021,8 @@ impl From<AttentionModuleOptions> for AttentionOptionsIr {
             scale: ir.scale.map(ScalarIr::Float),
             softcap: ir.softcap.map(ScalarIr::Float),
             is_causal: ir.is_causal,
+            sliding_window: ir.sliding_window,
+            alibi_max_bias: ir.alibi_max_bias.map(ScalarIr::Float),
         }
     }
 }
@@ -2024,6 +2035,7 @@ pub struct AttentionOpIr {
     pub value: TensorIr,
     pub mask: Option<TensorIr>,
     pub attn_bias: Option<TensorIr>,
+    pub seq_offsets: Option<TensorIr>,
     pub options: AttentionOptionsIr,
     pub out: TensorIr,
 }
@@ -2051,6 +2063,33 @@ pub struct CtcLossBackwardOpIr {
     pub out: TensorIr,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct SelectiveScanOpIr {
+    pub x: TensorIr,
+    pub delta: TensorIr,
+    pub a: TensorIr,
+    pub b: TensorIr,
+    pub c: TensorIr,
+    pub out: TensorIr,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct SelectiveScanBackwardOpIr {
+    pub x: TensorIr,
+    pub delta: TensorIr,
+    pub a: TensorIr,
+    pub b: TensorIr,
+    pub c: TensorIr,
+    pub out_grad: TensorIr,
+    pub x_grad: TensorIr,
+    pub delta_grad: TensorIr,
+    pub a_grad: TensorIr,
+    pub b_grad: TensorIr,
+    pub c_grad: TensorIr,
+}
+
 impl From<InterpolateModeIr> for InterpolateMode {
     fn from(val: InterpolateModeIr) -> Self {
         match val {
@@ -3919,19 +3958,13 @@ impl ModuleOperationIr {
             ModuleOperationIr::IRfft(repr) => {
                 Box::new([&repr.input_re, &repr.input_im].into_iter())
             }
-            ModuleOperationIr::Attention(repr) => {
-                if let Some(mask) = &repr.mask {
-                    if let Some(attn_bias) = &repr.attn_bias {
-                        Box::new([&repr.query, &repr.key, &repr.value, mask, attn_bias].into_iter())
-                    } else {
-                        Box::new([&repr.query, &repr.key, &repr.value, mask].into_iter())
-                    }
-                } else if let Some(attn_bias) = &repr.attn_bias {
-                    Box::new([&repr.query, &repr.key, &repr.value, attn_bias].into_iter())
-                } else {
-                    Box::new([&repr.query, &repr.key, &repr.value].into_iter())
-                }
-            }
+            ModuleOperationIr::Attention(repr) => Box::new(
+                [&repr.query, &repr.key, &repr.value]
+                    .into_iter()
+                    .chain(repr.mask.as_ref())
+                    .chain(repr.attn_bias.as_ref())
+                    .chain(repr.seq_offsets.as_ref()),
+            ),
             ModuleOperationIr::CtcLoss(repr) => Box::new(
                 [
                     &repr.log_probs,
@@ -3951,6 +3984,20 @@ impl ModuleOperationIr {
                 ]
                 .into_iter(),
             ),
+            ModuleOperationIr::SelectiveScan(repr) => {
+                Box::new([&repr.x, &repr.delta, &repr.a, &repr.b, &repr.c].into_iter())
+            }
+            ModuleOperationIr::SelectiveScanBackward(repr) => Box::new(
+                [
+                    &repr.x,
+                    &repr.delta,
+                    &repr.a,
+                    &repr.b,
+                    &repr.c,
+                    &repr.out_grad,
+                ]
+                .into_iter(),
+            ),
             ModuleOperationIr::LayerNorm(repr) => match &repr.beta {
                 Some(beta) => Box::new([&repr.input, &repr.gamma, beta].into_iter()),
                 None => Box::new([&repr.input, &repr.gamma].into_iter()),
@@ -4067,6 +4114,17 @@ impl ModuleOperationIr {
             ModuleOperationIr::Attention(repr) => Box::new([&repr.out].into_iter()),
             ModuleOperationIr::CtcLoss(repr) => Box::new([&repr.out].into_iter()),
             ModuleOperationIr::CtcLossBackward(repr) => Box::new([&repr.out].into_iter()),
+            ModuleOperationIr::SelectiveScan(repr) => Box::new([&repr.out].into_iter()),
+            ModuleOperationIr::SelectiveScanBackward(repr) => Box::new(
+                [
+                    &repr.x_grad,
+                    &repr.delta_grad,
+                    &repr.a_grad,
+                    &repr.b_grad,
+                    &repr.c_grad,
+                ]
+                .into_iter(),
+            ),
             ModuleOperationIr::LayerNorm(repr) => Box::new([&repr.out].into_iter()),
             ModuleOperationIr::Unfold4d(repr) => Box::new([&repr.out].into_iter()),
             ModuleOperationIr::ConvTranspose1dWeightBackward(repr) => {
@@ -4333,6 +4391,9 @@ impl ModuleOperationIr {
                 if let Some(attn_bias) = &mut repr.attn_bias {
                     attn_bias.mark_read_only(nodes, &mut output);
                 }
+                if let Some(seq_offsets) = &mut repr.seq_offsets {
+                    seq_offsets.mark_read_only(nodes, &mut output);
+                }
             }
             ModuleOperationIr::CtcLoss(repr) => {
                 repr.log_probs.mark_read_only(nodes, &mut output);
@@ -4347,6 +4408,21 @@ impl ModuleOperationIr {
                 repr.target_lengths.mark_read_only(nodes, &mut output);
                 repr.grad_loss.mark_read_only(nodes, &mut output);
             }
+            ModuleOperationIr::SelectiveScan(repr) => {
+                repr.x.mark_read_only(nodes, &mut output);
+                repr.delta.mark_read_only(nodes, &mut output);
+                repr.a.mark_read_only(nodes, &mut output);
+                repr.b.mark_read_only(nodes, &mut output);
+                repr.c.mark_read_only(nodes, &mut output);
+            }
+            ModuleOperationIr::SelectiveScanBackward(repr) => {
+                repr.x.mark_read_only(nodes, &mut output);
+                repr.delta.mark_read_only(nodes, &mut output);
+                repr.a.mark_read_only(nodes, &mut output);
+                repr.b.mark_read_only(nodes, &mut output);
+                repr.c.mark_read_only(nodes, &mut output);
+                repr.out_grad.mark_read_only(nodes, &mut output);
+            }
             ModuleOperationIr::LayerNorm(repr) => {
                 repr.input.mark_read_only(nodes, &mut output);
                 repr.gamma.mark_read_only(nodes, &mut output);
@@ -4675,6 +4751,9 @@ impl ModuleOperationIr {
                 if let Some(attn_bias) = &mut repr.attn_bias {
                     v.visit_tensor_mut(attn_bias);
                 }
+                if let Some(seq_offsets) = &mut repr.seq_offsets {
+                    v.visit_tensor_mut(seq_offsets);
+                }
                 v.visit_tensor_mut(&mut repr.out);
                 if let Some(scale) = &mut repr.options.scale {
                     v.visit_scalar_mut(scale);
@@ -4682,6 +4761,9 @@ impl ModuleOperationIr {
                 if let Some(softcap) = &mut repr.options.softcap {
                     v.visit_scalar_mut(softcap);
                 }
+                if let Some(max_bias) = &mut repr.options.alibi_max_bias {
+                    v.visit_scalar_mut(max_bias);
+                }
             }
             ModuleOperationIr::CtcLoss(repr) => {
                 v.visit_tensor_mut(&mut repr.log_probs);
@@ -4698,6 +4780,27 @@ impl ModuleOperationIr {
                 v.visit_tensor_mut(&mut repr.grad_loss);
                 v.visit_tensor_mut(&mut repr.out);
             }
+            ModuleOperationIr::SelectiveScan(repr) => {
+                v.visit_tensor_mut(&mut repr.x);
+                v.visit_tensor_mut(&mut repr.delta);
+                v.visit_tensor_mut(&mut repr.a);
+                v.visit_tensor_mut(&mut repr.b);
+                v.visit_tensor_mut(&mut repr.c);
+                v.visit_tensor_mut(&mut repr.out);
+            }
+            ModuleOperationIr::SelectiveScanBackward(repr) => {
+                v.visit_tensor_mut(&mut repr.x);
+                v.visit_tensor_mut(&mut repr.delta);
+                v.visit_tensor_mut(&mut repr.a);
+                v.visit_tensor_mut(&mut repr.b);
+                v.visit_tensor_mut(&mut repr.c);
+                v.visit_tensor_mut(&mut repr.out_grad);
+                v.visit_tensor_mut(&mut repr.x_grad);
+                v.visit_tensor_mut(&mut repr.delta_grad);
+                v.visit_tensor_mut(&mut repr.a_grad);
+                v.visit_tensor_mut(&mut repr.b_grad);
+                v.visit_tensor_mut(&mut repr.c_grad);
+            }
             ModuleOperationIr::LayerNorm(repr) => {
                 v.visit_tensor_mut(&mut repr.input);
                 v.visit_tensor_mut(&mut repr.gamma);
@@ -392,9 +392,10 @@ impl ModuleOps<Self> for NdArray {
         value: FloatTensor<Self>,
         mask: Option<burn_backend::tensor::BoolTensor<Self>>,
         attn_bias: Option<FloatTensor<Self>>,
+        seq_offsets: Option<IntTensor<Self>>,
         options: AttentionModuleOptions,
     ) -> FloatTensor<Self> {
-        attention_fallback::<Self>(query, key, value, mask, attn_bias, options)
+        attention_fallback::<Self>(query, key, value, mask, attn_bias, seq_offsets, options)
     }
 
     fn rfft(
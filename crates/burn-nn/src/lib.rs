@@ -19,6 +19,10 @@ pub use activation::{
     tanh::*, thresholded_relu::*,
 };
 
+/// Activation-aware weight quantization.
+#[cfg(feature = "std")]
+pub mod quantization;
+
 mod padding;
 
 pub use padding::*;
@@ -1,11 +1,13 @@
+use alloc::vec::Vec;
 use burn_core as burn;
 
 use crate::activation::Gelu;
-use crate::cache::TensorCache;
+use crate::cache::{PagedKvCache, TensorCache};
 use crate::{Dropout, DropoutConfig, Linear, LinearConfig};
 use burn::config::Config;
+use burn::module::hookable;
 use burn::module::{Content, DisplaySettings, Initializer, Module, ModuleDisplay};
-use burn::tensor::{Bool, Device, Tensor};
+use burn::tensor::{Bool, Device, Int, Tensor, TensorData};
 
 use burn::tensor::activation::{quiet_softmax, softmax};
 #[cfg(not(feature = "std"))]
@@ -209,6 +211,7 @@ impl MultiHeadAttention {
     /// - key: `[batch_size, seq_length_2, d_model]`
     /// - value: `[batch_size, seq_length_2, d_model]`
     /// - output: `[batch_size, seq_length_1, d_model]`
+    #[hookable]
     pub fn forward(&self, input: MhaInput) -> MhaOutput {
         let [batch_size, seq_length_1, d_model] = input.query.dims();
 
@@ -262,6 +265,115 @@ impl MultiHeadAttention {
         MhaOutput { weights, context }
     }
 
+    /// Applies the forward pass on the new positions of a batch of sequences, using a
+    /// [paged cache](PagedKvCache) for the keys and values of the previous ones.
+    ///
+    /// Each batch entry continues the cache sequence at the same index in `sequences`, which
+    /// can all have different lengths. The new keys and values are appended to the cache, and
+    /// each new position attends to the previous positions of its sequence and to the new ones
+    /// up to itself. The masks are built from the cache, those of the input are ignored.
+    ///
+    /// The attention walks the [block tables](PagedKvCache::block_tables) one block at a time
+    /// with an online softmax, so the padded history of the batch is never gathered. The
+    /// attention weights aren't materialized either, only the context is returned.
+    ///
+    /// # Shapes
+    ///
+    /// - query: `[batch_size, seq_length_1, d_model]`
+    /// - key: `[batch_size, seq_length_1, d_model]`
+    /// - value: `[batch_size, seq_length_1, d_model]`
+    /// - output: `[batch_size, seq_length_1, d_model]`
+    pub fn forward_paged(
+        &self,
+        input: MhaInput,
+        cache: &mut PagedKvCache,
+        sequences: &[usize],
+    ) -> Tensor<3> {
+        let [batch_size, seq_length_1, d_model] = input.query.dims();
+        assert_eq!(
+            batch_size,
+            sequences.len(),
+            "Each batch entry should have a cache sequence"
+        );
+        let offsets: Vec<usize> = sequences.iter().map(|id| cache.len(*id)).collect();
+
+        let query = self.attention_linear(input.query, &self.query);
+        let key = self.attention_linear(input.key, &self.key);
+        let value = self.attention_linear(input.value, &self.value);
+
+        for (index, id) in sequences.iter().enumerate() {
+            cache.append(
+                *id,
+                key.clone().slice([index..index + 1]).squeeze_dim(0),
+                value.clone().slice([index..index + 1]).squeeze_dim(0),
+            );
+        }
+        let block_tables = cache.block_tables(sequences);
+        let num_blocks = block_tables.dims()[1];
+        let block_size = cache.block_size();
+
+        // The new position `j` of a sequence is at `offset + j` and sees the positions up to
+        // it, which also masks the padding past the end of the sequence.
+        let device = query.device();
+        let limits = offsets
+            .iter()
+            .flat_map(|offset| (0..seq_length_1).map(move |j| (offset + j) as i64))
+            .collect();
+        let limits = Tensor::<3, Int>::from_data(
+            TensorData::new(limits, [batch_size, seq_length_1, 1]),
+            &device,
+        )
+        .expand([batch_size, seq_length_1, block_size]);
+
+        let stats_shape = [batch_size, self.n_heads, seq_length_1, 1];
+        let mut max = Tensor::<4>::full(stats_shape, self.min_float, &device);
+        let mut sum = Tensor::<4>::zeros(stats_shape, &device);
+        let mut context =
+            Tensor::<4>::zeros([batch_size, self.n_heads, seq_length_1, self.d_k], &device);
+
+        for index in 0..num_blocks {
+            let blocks = block_tables
+                .clone()
+                .slice([0..batch_size, index..index + 1])
+                .reshape([batch_size]);
+            let (key, value) = cache.read_blocks(blocks);
+
+            let start = (index * block_size) as i64;
+            let positions = Tensor::<1, Int>::arange(start..start + block_size as i64, &device)
+                .reshape([1, 1, block_size])
+                .expand([batch_size, seq_length_1, block_size]);
+            let mask_attn = positions.greater(limits.clone()).reshape([
+                batch_size,
+                1,
+                seq_length_1,
+                block_size,
+            ]);
+            let attn_scores = self
+                .attn_scores(query.clone(), key)
+                .mask_fill(mask_attn.clone(), self.min_float);
+
+            // Rescale what was accumulated so far to the new running maximum.
+            let block_max = max.clone().max_pair(attn_scores.clone().max_dim(3));
+            let correction = (max - block_max.clone()).exp();
+            let weights = (attn_scores - block_max.clone())
+                .exp()
+                .mask_fill(mask_attn, 0.0);
+
+            sum = sum * correction.clone() + weights.clone().sum_dim(3);
+            context = context * correction + weights.matmul(value);
+            max = block_max;
+        }
+
+        if self.quiet_softmax {
+            sum = sum + max.neg().exp();
+        }
+        let context = (context / sum)
+            .swap_dims(1, 2)
+            .reshape([batch_size, seq_length_1, d_model]);
+
+        self.output.forward(context)
+    }
+
     fn attn_scores(&self, query: Tensor<4>, key: Tensor<4>) -> Tensor<4> {
         let attn_scores = query
             .matmul(key.transpose())
@@ -346,9 +458,60 @@ impl MhaCache {
             output: MhaLinearCache::Autoregressive(TensorCache::empty(), 1),
         }
     }
+
+    /// Initialize a cache for autoregressive inference on sequences of at most `capacity`
+    /// positions.
+    ///
+    /// The memory is allocated once and each step writes the new positions in place, instead
+    /// of concatenating them to the whole history. The input of each step should hold the
+    /// previous positions followed by the new ones, which can be several at once, e.g. to
+    /// fill the cache with a prompt.
+    pub fn preallocated(capacity: usize) -> Self {
+        Self {
+            query: MhaLinearCache::Autoregressive(TensorCache::preallocated(capacity), 2),
+            key: MhaLinearCache::Autoregressive(TensorCache::preallocated(capacity), 2),
+            value: MhaLinearCache::Autoregressive(TensorCache::preallocated(capacity), 2),
+            output: MhaLinearCache::Autoregressive(TensorCache::preallocated(capacity), 1),
+        }
+    }
+
+    /// Initialize a [preallocated](Self::preallocated) cache, but with a fixed memory used for
+    /// keys and values (cross-attention).
+    pub fn preallocated_cross_attention(capacity: usize) -> Self {
+        Self {
+            query: MhaLinearCache::Autoregressive(TensorCache::preallocated(capacity), 2),
+            key: MhaLinearCache::Full(TensorCache::empty()),
+            value: MhaLinearCache::Full(TensorCache::empty()),
+            output: MhaLinearCache::Autoregressive(TensorCache::preallocated(capacity), 1),
+        }
+    }
+
+    /// Clear the cache to decode a new sequence, keeping the memory of a
+    /// [preallocated](Self::preallocated) cache.
+    pub fn reset(&mut self) {
+        self.query.cache().reset();
+        self.key.cache().reset();
+        self.value.cache().reset();
+        self.output.cache().reset();
+    }
+
+    /// Reorder the cached batch: entry `i` becomes the entry `indices[i]` of the current batch.
+    pub fn reorder(&mut self, indices: Tensor<1, Int>) {
+        self.query.cache().reorder(indices.clone());
+        self.key.cache().reorder(indices.clone());
+        self.value.cache().reorder(indices.clone());
+        self.output.cache().reorder(indices);
+    }
 }
 
 impl<const D: usize> MhaLinearCache<D> {
+    fn cache(&mut self) -> &mut TensorCache<D> {
+        match self {
+            MhaLinearCache::Autoregressive(cache, _) => cache,
+            MhaLinearCache::Full(cache) => cache,
+        }
+    }
+
     pub fn forward<F: Fn(Tensor<3>) -> Tensor<D>>(
         &mut self,
         tensor: Tensor<3>,
@@ -367,7 +530,8 @@ impl<const D: usize> MhaLinearCache<D> {
 mod tests {
     use super::*;
     use crate::attention::generate_autoregressive_mask;
-    use alloc::vec::Vec;
+    use crate::cache::PagedKvCacheConfig;
+    use alloc::vec;
     use burn::tensor::Int;
     use burn::tensor::Tolerance;
     use burn::tensor::{Distribution, Shape};
@@ -564,6 +728,95 @@ mod tests {
             .assert_approx_eq::<f32>(&output_2.into_data(), Tolerance::default());
     }
 
+    #[test]
+    fn test_preallocated_cache_should_have_same_output_as_autoregressive_cache() {
+        let [batch_size, seq_length, d_model, n_heads] = [3, 5, 12, 2];
+        let device = Default::default();
+        let mha = MultiHeadAttentionConfig::new(d_model, n_heads).init(&device);
+
+        let tensor = Tensor::<3>::random(
+            [batch_size, seq_length, d_model],
+            Distribution::Default,
+            &device,
+        );
+        let mut cache_1 = MhaCache::autoregressive();
+        let mut cache_2 = MhaCache::preallocated(8);
+
+        for i in 1..seq_length + 1 {
+            let tensor = tensor.clone().slice([0..batch_size, 0..i, 0..d_model]);
+            let output_1 = mha.forward_cache(MhaInput::self_attn(tensor.clone()), &mut cache_1);
+            let output_2 = mha.forward_cache(MhaInput::self_attn(tensor), &mut cache_2);
+
+            output_1
+                .context
+                .into_data()
+                .assert_approx_eq::<f32>(&output_2.context.into_data(), Tolerance::default());
+        }
+
+        // A reset cache starts a new sequence, here filled with a whole prompt at once.
+        cache_2.reset();
+        let mask_attn = generate_autoregressive_mask(batch_size, seq_length, &device);
+        let output_1 =
+            mha.forward(MhaInput::self_attn(tensor.clone()).mask_attn(mask_attn.clone()));
+        let output_2 = mha.forward_cache(
+            MhaInput::self_attn(tensor).mask_attn(mask_attn),
+            &mut cache_2,
+        );
+        output_1
+            .context
+            .into_data()
+            .assert_approx_eq::<f32>(&output_2.context.into_data(), Tolerance::default());
+    }
+
+    #[test]
+    fn test_paged_cache_should_have_same_output_as_masked_forward() {
+        let [d_model, n_heads] = [12, 2];
+        let device = Default::default();
+        let mha = MultiHeadAttentionConfig::new(d_model, n_heads).init(&device);
+        let mut cache = PagedKvCacheConfig::new(8, n_heads, d_model / n_heads)
+            .with_block_size(2)
+            .init(&device);
+
+        let short = Tensor::<3>::random([1, 3, d_model], Distribution::Default, &device);
+        let long = Tensor::<3>::random([1, 6, d_model], Distribution::Default, &device);
+        let ids = [cache.add_sequence(), cache.add_sequence()];
+
+        // Fill the cache with prompts of different lengths, then decode one position of both.
+        mha.forward_paged(
+            MhaInput::self_attn(short.clone().slice([0..1, 0..2])),
+            &mut cache,
+            &ids[..1],
+        );
+        mha.forward_paged(
+            MhaInput::self_attn(long.clone().slice([0..1, 0..5])),
+            &mut cache,
+            &ids[1..],
+        );
+        let next = Tensor::cat(
+            vec![
+                short.clone().slice([0..1, 2..3]),
+                long.clone().slice([0..1, 5..6]),
+            ],
+            0,
+        );
+        let output = mha.forward_paged(MhaInput::self_attn(next), &mut cache, &ids);
+
+        for (index, tensor) in [short, long].into_iter().enumerate() {
+            let seq_length = tensor.dims()[1];
+            let mask_attn = generate_autoregressive_mask(1, seq_length, &device);
+            let expected = mha
+                .forward(MhaInput::self_attn(tensor).mask_attn(mask_attn))
+                .context
+                .slice([0..1, seq_length - 1..seq_length]);
+
+            output
+                .clone()
+                .slice([index..index + 1])
+                .into_data()
+                .assert_approx_eq::<f32>(&expected.into_data(), Tolerance::default());
+        }
+    }
+
     #[test]
     fn display() {
         let config = MultiHeadAttentionConfig::new(2, 4);
@@ -1,5 +1,7 @@
 use alloc::vec;
+use alloc::vec::Vec;
 use burn_core as burn;
+use core::ops::Range;
 
 use super::{CacheState, TensorCache};
 use burn::tensor::Tensor;
@@ -14,6 +16,10 @@ impl<const D: usize> TensorCache<D> {
     where
         F: Fn(Tensor<3>) -> Tensor<D>,
     {
+        if let Some(capacity) = self.capacity {
+            return self.forward_preallocated(tensor, dim_cat, capacity, func);
+        }
+
         let mut tensor_old = CacheState::Empty;
         core::mem::swap(&mut self.state, &mut tensor_old);
 
@@ -33,6 +39,57 @@ impl<const D: usize> TensorCache<D> {
         tensor_new
     }
 
+    /// Computes the positions of `tensor` that aren't cached yet and writes them in place.
+    fn forward_preallocated<F>(
+        &mut self,
+        tensor: Tensor<3>,
+        dim_cat: usize,
+        capacity: usize,
+        func: F,
+    ) -> Tensor<D>
+    where
+        F: Fn(Tensor<3>) -> Tensor<D>,
+    {
+        let mut state = CacheState::Empty;
+        core::mem::swap(&mut self.state, &mut state);
+
+        let (buffer, cached) = match state {
+            CacheState::Preallocated(buffer, len) => (Some(buffer), len),
+            _ => (None, 0),
+        };
+
+        let [batch_size, seq_length, d_model] = tensor.dims();
+        assert!(
+            seq_length <= capacity,
+            "The sequence length ({seq_length}) exceeds the cache capacity ({capacity})"
+        );
+        assert!(
+            seq_length > cached,
+            "The input should hold the {cached} cached positions followed by at least one new one"
+        );
+
+        let new = func(tensor.slice([0..batch_size, cached..seq_length, 0..d_model]));
+        let dims = new.dims();
+
+        let buffer = buffer.unwrap_or_else(|| {
+            let mut shape = dims;
+            shape[dim_cat] = capacity;
+            Tensor::zeros(shape, &new.device())
+        });
+
+        let ranges: Vec<Range<usize>> = (0..D)
+            .map(|dim| match dim == dim_cat {
+                true => cached..seq_length,
+                false => 0..dims[dim],
+            })
+            .collect();
+        let buffer = buffer.slice_assign(ranges.as_slice(), new);
+        let output = buffer.clone().slice_dim(dim_cat, 0..seq_length);
+
+        self.state = CacheState::Preallocated(buffer, seq_length);
+        output
+    }
+
     pub(crate) fn forward_full<F>(&mut self, tensor: Tensor<3>, func: F) -> Tensor<D>
     where
         F: Fn(Tensor<3>) -> Tensor<D>,
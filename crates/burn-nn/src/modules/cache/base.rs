@@ -1,15 +1,18 @@
 use burn_core as burn;
 
-use burn::tensor::Tensor;
+use burn::tensor::{Int, Tensor};
 
 pub(crate) enum CacheState<T> {
     Value(T),
+    /// A buffer with a fixed capacity along the cached dimension, filled up to the given length.
+    Preallocated(T, usize),
     Empty,
 }
 
 /// A cache for a tensor.
 pub struct TensorCache<const D: usize> {
     pub(crate) state: CacheState<Tensor<D>>,
+    pub(crate) capacity: Option<usize>,
 }
 
 impl<const D: usize> TensorCache<D> {
@@ -21,6 +24,67 @@ impl<const D: usize> TensorCache<D> {
     pub fn empty() -> Self {
         Self {
             state: CacheState::Empty,
+            capacity: None,
         }
     }
+
+    /// Creates a new empty cache holding at most `capacity` positions along the cached dimension.
+    ///
+    /// The memory is allocated on the first write, and new positions are written in place
+    /// instead of concatenated to the previous ones, so a decoding step doesn't copy the whole
+    /// history.
+    ///
+    /// # Returns
+    ///
+    /// The empty cache.
+    pub fn preallocated(capacity: usize) -> Self {
+        Self {
+            state: CacheState::Empty,
+            capacity: Some(capacity),
+        }
+    }
+
+    /// The number of cached positions of a [preallocated](Self::preallocated) cache.
+    pub fn len(&self) -> usize {
+        match &self.state {
+            CacheState::Preallocated(_, len) => *len,
+            _ => 0,
+        }
+    }
+
+    /// Whether nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        match &self.state {
+            CacheState::Preallocated(_, len) => *len == 0,
+            CacheState::Value(_) => false,
+            CacheState::Empty => true,
+        }
+    }
+
+    /// Clear the cache, keeping the memory of a [preallocated](Self::preallocated) cache for
+    /// the next sequence.
+    pub fn reset(&mut self) {
+        let mut state = CacheState::Empty;
+        core::mem::swap(&mut self.state, &mut state);
+
+        if let CacheState::Preallocated(buffer, _) = state {
+            self.state = CacheState::Preallocated(buffer, 0);
+        }
+    }
+
+    /// Reorder the cached batch: entry `i` becomes the entry `indices[i]` of the current batch.
+    ///
+    /// Used by beam search, where beams are duplicated and dropped between steps.
+    pub fn reorder(&mut self, indices: Tensor<1, Int>) {
+        let mut state = CacheState::Empty;
+        core::mem::swap(&mut self.state, &mut state);
+
+        self.state = match state {
+            CacheState::Value(tensor) => CacheState::Value(tensor.select(0, indices)),
+            CacheState::Preallocated(buffer, len) => {
+                CacheState::Preallocated(buffer.select(0, indices), len)
+            }
+            CacheState::Empty => CacheState::Empty,
+        };
+    }
 }
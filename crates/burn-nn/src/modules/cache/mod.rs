@@ -1,4 +1,6 @@
 mod autoregressive;
 mod base;
+mod paged;
 
 pub use base::*;
+pub use paged::*;
@@ -0,0 +1,325 @@
+use alloc::vec::Vec;
+use burn_core as burn;
+
+use burn::config::Config;
+use burn::tensor::{Device, Int, Tensor, TensorData};
+
+/// Configuration to create a [paged key-value cache](PagedKvCache) using the
+/// [init function](PagedKvCacheConfig::init).
+#[derive(Config, Debug)]
+pub struct PagedKvCacheConfig {
+    /// The number of blocks in the pool, shared by every sequence.
+    pub num_blocks: usize,
+    /// The number of positions held by a block.
+    #[config(default = 16)]
+    pub block_size: usize,
+    /// The number of attention heads.
+    pub n_heads: usize,
+    /// The size of each head.
+    pub d_k: usize,
+}
+
+impl PagedKvCacheConfig {
+    /// Initialize a new [paged key-value cache](PagedKvCache), allocating the whole pool.
+    pub fn init(&self, device: &Device) -> PagedKvCache {
+        assert!(
+            self.num_blocks > 0 && self.block_size > 0,
+            "A paged cache needs at least one block of at least one position"
+        );
+        let shape = [self.num_blocks, self.n_heads, self.block_size, self.d_k];
+
+        PagedKvCache {
+            key: Tensor::zeros(shape, device),
+            value: Tensor::zeros(shape, device),
+            block_size: self.block_size,
+            free: (0..self.num_blocks).rev().collect(),
+            sequences: Vec::new(),
+        }
+    }
+}
+
+/// The blocks and length of one sequence of a [paged cache](PagedKvCache).
+struct PagedSequence {
+    blocks: Vec<usize>,
+    len: usize,
+}
+
+/// A key-value cache for batched serving of sequences of different lengths.
+///
+/// Keys and values live in a pool of fixed-size blocks allocated once. Each sequence owns a
+/// block table mapping its positions to blocks, and takes a new block from the pool only when
+/// its last one is full, so memory follows the actual length of each sequence instead of the
+/// longest one, and is returned to the pool as soon as a sequence [ends](Self::remove_sequence).
+///
+/// Use it with [MultiHeadAttention::forward_paged](crate::attention::MultiHeadAttention::forward_paged).
+/// A [Transformer Decoder](crate::transformer::TransformerDecoder) holds one per layer, see
+/// [new_paged_cache](crate::transformer::TransformerDecoder::new_paged_cache).
+pub struct PagedKvCache {
+    /// `[num_blocks, n_heads, block_size, d_k]`
+    key: Tensor<4>,
+    /// `[num_blocks, n_heads, block_size, d_k]`
+    value: Tensor<4>,
+    block_size: usize,
+    free: Vec<usize>,
+    sequences: Vec<Option<PagedSequence>>,
+}
+
+impl PagedKvCache {
+    /// Start a new, empty sequence and return its id.
+    ///
+    /// Ids of removed sequences are reused.
+    pub fn add_sequence(&mut self) -> usize {
+        let sequence = PagedSequence {
+            blocks: Vec::new(),
+            len: 0,
+        };
+
+        match self.sequences.iter().position(Option::is_none) {
+            Some(id) => {
+                self.sequences[id] = Some(sequence);
+                id
+            }
+            None => {
+                self.sequences.push(Some(sequence));
+                self.sequences.len() - 1
+            }
+        }
+    }
+
+    /// End a sequence, returning its blocks to the pool.
+    pub fn remove_sequence(&mut self, id: usize) {
+        let sequence = self.sequences[id]
+            .take()
+            .expect("The sequence should exist in the cache");
+        self.free.extend(sequence.blocks.into_iter().rev());
+    }
+
+    /// The number of cached positions of a sequence.
+    pub fn len(&self, id: usize) -> usize {
+        self.sequence(id).len
+    }
+
+    /// The blocks holding the positions of a sequence, in order.
+    pub fn block_table(&self, id: usize) -> &[usize] {
+        &self.sequence(id).blocks
+    }
+
+    /// The number of positions held by a block.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// The number of blocks not used by any sequence.
+    pub fn num_free_blocks(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Whether `num_positions` more positions fit in the cache for the sequence, so a
+    /// scheduler can hold back a sequence instead of running out of blocks mid-batch.
+    pub fn can_append(&self, id: usize, num_positions: usize) -> bool {
+        self.blocks_needed(id, num_positions) <= self.free.len()
+    }
+
+    /// Append the keys and values of new positions to a sequence.
+    ///
+    /// # Shapes
+    ///
+    /// - key: `[n_heads, num_positions, d_k]`
+    /// - value: `[n_heads, num_positions, d_k]`
+    ///
+    /// # Panics
+    ///
+    /// If the pool doesn't have enough free blocks, see [can_append](Self::can_append).
+    pub fn append(&mut self, id: usize, key: Tensor<3>, value: Tensor<3>) {
+        let [n_heads, num_positions, d_k] = key.dims();
+        let needed = self.blocks_needed(id, num_positions);
+        assert!(
+            needed <= self.free.len(),
+            "The paged cache is out of blocks: {needed} needed, {} free",
+            self.free.len()
+        );
+
+        let block_size = self.block_size;
+        let new_blocks: Vec<usize> = (0..needed).map(|_| self.free.pop().unwrap()).collect();
+        let sequence = self.sequences[id]
+            .as_mut()
+            .expect("The sequence should exist in the cache");
+        sequence.blocks.extend(new_blocks);
+
+        // Take the pools out of the cache so the writes can happen in place.
+        let device = self.key.device();
+        let mut key_pool = core::mem::replace(&mut self.key, Tensor::empty([0; 4], &device));
+        let mut value_pool = core::mem::replace(&mut self.value, Tensor::empty([0; 4], &device));
+
+        let mut written = 0;
+        while written < num_positions {
+            let position = sequence.len + written;
+            let block = sequence.blocks[position / block_size];
+            let offset = position % block_size;
+            let count = usize::min(block_size - offset, num_positions - written);
+
+            let range = written..written + count;
+            let key_chunk = key
+                .clone()
+                .slice([0..n_heads, range.clone(), 0..d_k])
+                .unsqueeze_dim(0);
+            let value_chunk = value
+                .clone()
+                .slice([0..n_heads, range, 0..d_k])
+                .unsqueeze_dim(0);
+            let slices = [block..block + 1, 0..n_heads, offset..offset + count, 0..d_k];
+
+            key_pool = key_pool.slice_assign(slices.clone(), key_chunk);
+            value_pool = value_pool.slice_assign(slices, value_chunk);
+            written += count;
+        }
+
+        self.key = key_pool;
+        self.value = value_pool;
+        sequence.len += num_positions;
+    }
+
+    /// The block tables of a batch of sequences, padded to the longest one.
+    ///
+    /// Shorter tables are padded with block 0: the positions it holds past the
+    /// [length](Self::len) of a sequence must be masked.
+    ///
+    /// # Returns
+    ///
+    /// The block indices `[batch_size, max_blocks]`, with at least one column.
+    pub fn block_tables(&self, ids: &[usize]) -> Tensor<2, Int> {
+        let max_blocks = ids
+            .iter()
+            .map(|id| self.block_table(*id).len())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        let mut indices = Vec::with_capacity(ids.len() * max_blocks);
+        for id in ids {
+            let blocks = self.block_table(*id);
+            indices.extend(blocks.iter().map(|block| *block as i64));
+            indices.extend((blocks.len()..max_blocks).map(|_| 0));
+        }
+
+        Tensor::from_data(
+            TensorData::new(indices, [ids.len(), max_blocks]),
+            &self.key.device(),
+        )
+    }
+
+    /// Read the keys and values held by one block per batch entry, usually a column of the
+    /// [block tables](Self::block_tables).
+    ///
+    /// # Shapes
+    ///
+    /// - blocks: `[batch_size]`
+    /// - key: `[batch_size, n_heads, block_size, d_k]`
+    /// - value: `[batch_size, n_heads, block_size, d_k]`
+    pub fn read_blocks(&self, blocks: Tensor<1, Int>) -> (Tensor<4>, Tensor<4>) {
+        let key = self.key.clone().select(0, blocks.clone());
+        let value = self.value.clone().select(0, blocks);
+
+        (key, value)
+    }
+
+    fn sequence(&self, id: usize) -> &PagedSequence {
+        self.sequences
+            .get(id)
+            .and_then(Option::as_ref)
+            .expect("The sequence should exist in the cache")
+    }
+
+    fn blocks_needed(&self, id: usize, num_positions: usize) -> usize {
+        let sequence = self.sequence(id);
+        (sequence.len + num_positions).div_ceil(self.block_size) - sequence.blocks.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn::tensor::Distribution;
+    use burn::tensor::Tolerance;
+
+    fn cache(num_blocks: usize) -> PagedKvCache {
+        PagedKvCacheConfig::new(num_blocks, 2, 3)
+            .with_block_size(4)
+            .init(&Default::default())
+    }
+
+    #[test]
+    fn blocks_are_taken_only_when_needed() {
+        let mut cache = cache(4);
+        let device = Default::default();
+        let id = cache.add_sequence();
+
+        let tokens = Tensor::<3>::zeros([2, 5, 3], &device);
+        cache.append(id, tokens.clone(), tokens);
+        assert_eq!(cache.len(id), 5);
+        assert_eq!(cache.block_table(id).len(), 2);
+        assert_eq!(cache.num_free_blocks(), 2);
+
+        assert!(cache.can_append(id, 11));
+        assert!(!cache.can_append(id, 12));
+
+        cache.remove_sequence(id);
+        assert_eq!(cache.num_free_blocks(), 4);
+    }
+
+    #[test]
+    fn block_tables_map_each_sequence_to_its_blocks() {
+        let mut cache = cache(8);
+        let device = Default::default();
+        let short = cache.add_sequence();
+        let long = cache.add_sequence();
+
+        let key_short = Tensor::<3>::random([2, 3, 3], Distribution::Default, &device);
+        let key_long = Tensor::<3>::random([2, 6, 3], Distribution::Default, &device);
+        cache.append(short, key_short.clone(), key_short.clone());
+        // Interleave the writes so the blocks of a sequence aren't contiguous in the pool.
+        cache.append(
+            long,
+            key_long.clone().slice([0..2, 0..5, 0..3]),
+            key_long.clone().slice([0..2, 0..5, 0..3]),
+        );
+        cache.append(
+            long,
+            key_long.clone().slice([0..2, 5..6, 0..3]),
+            key_long.clone().slice([0..2, 5..6, 0..3]),
+        );
+
+        let [short_blocks, long_blocks] =
+            [short, long].map(|id| cache.block_table(id).iter().map(|block| *block as i64));
+        let expected: Vec<i64> = short_blocks.chain([0]).chain(long_blocks).collect();
+        let tables = cache.block_tables(&[short, long]);
+        tables
+            .clone()
+            .into_data()
+            .assert_eq(&TensorData::new(expected, [2, 2]), false);
+
+        let (key, _value) = cache.read_blocks(tables.clone().slice([0..2, 0..1]).reshape([2]));
+        assert_eq!(key.dims(), [2, 2, 4, 3]);
+        key.clone()
+            .slice([0..1, 0..2, 0..3, 0..3])
+            .squeeze_dim::<3>(0)
+            .into_data()
+            .assert_approx_eq::<f32>(&key_short.into_data(), Tolerance::default());
+        key.slice([1..2])
+            .squeeze_dim::<3>(0)
+            .into_data()
+            .assert_approx_eq::<f32>(
+                &key_long.clone().slice([0..2, 0..4, 0..3]).into_data(),
+                Tolerance::default(),
+            );
+
+        let (key, _value) = cache.read_blocks(tables.slice([1..2, 1..2]).reshape([1]));
+        key.slice([0..1, 0..2, 0..2, 0..3])
+            .squeeze_dim::<3>(0)
+            .into_data()
+            .assert_approx_eq::<f32>(
+                &key_long.slice([0..2, 4..6, 0..3]).into_data(),
+                Tolerance::default(),
+            );
+    }
+}
@@ -3,6 +3,7 @@ use alloc::format;
 use burn_core as burn;
 
 use crate::{PaddingConfig1d, conv::checks};
+use burn::module::hookable;
 use burn::tensor::{Device, Tensor, module::conv1d, ops::PaddedConvOptions};
 use burn::{
     config::Config,
@@ -144,6 +145,7 @@ impl Conv1d {
     ///
     /// - input: `[batch_size, channels_in, length_in]`
     /// - output: `[batch_size, channels_out, length_out]`
+    #[hookable]
     pub fn forward(&self, input: Tensor<3>) -> Tensor<3> {
         let length = input.dims()[2];
 
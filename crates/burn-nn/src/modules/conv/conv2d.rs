@@ -1,10 +1,11 @@
-use alloc::format;
+use alloc::{format, vec::Vec};
 
 use burn_core as burn;
 
 use crate::PaddingConfig2d;
 use burn::config::Config;
 use burn::module::Initializer;
+use burn::module::hookable;
 use burn::module::{Content, DisplaySettings, Module, ModuleDisplay, Param};
 use burn::tensor::Device;
 use burn::tensor::Tensor;
@@ -12,6 +13,7 @@ use burn::tensor::module::conv2d;
 use burn::tensor::ops::PaddedConvOptions;
 
 use crate::conv::checks;
+use crate::modules::pruning::{index_tensor, nonzero_indices, with_value};
 
 /// Configuration to create a [2D convolution](Conv2d) layer, using the [init function](Conv2dConfig::init).
 #[derive(Config, Debug)]
@@ -161,6 +163,7 @@ impl Conv2d {
     ///
     /// println!("{:?}", y.dims()); // [1, 8, 26, 26]
     /// ```
+    #[hookable]
     pub fn forward(&self, input: Tensor<4>) -> Tensor<4> {
         let [_batch_size, _channels_in, height_in, width_in] = input.dims();
 
@@ -187,6 +190,67 @@ impl Conv2d {
             options,
         )
     }
+
+    /// Fold the [pruning masks](burn::module::PruningMask) into the parameters and remove the
+    /// output channels whose weights and bias are all zero, as left by structured pruning of the
+    /// output channels.
+    ///
+    /// Returns the layer with the indices of the kept output channels, to remove the matching
+    /// input channels of the next layer with [retain_inputs](Conv2d::retain_inputs).
+    ///
+    /// # Panics
+    ///
+    /// If the convolution is grouped.
+    pub fn finalize_pruning(self) -> (Self, Vec<usize>) {
+        assert_eq!(
+            self.groups, 1,
+            "Can't remove the channels of a grouped convolution"
+        );
+        let weight = self.weight.val();
+        let bias = self.bias.as_ref().map(|bias| bias.val());
+
+        let mut magnitude = weight
+            .clone()
+            .abs()
+            .flatten::<2>(1, 3)
+            .sum_dim(1)
+            .squeeze_dim::<1>(1);
+        if let Some(bias) = &bias {
+            magnitude = magnitude + bias.clone().abs();
+        }
+        let kept = nonzero_indices(magnitude);
+        let indices = index_tensor(&kept, &weight.device());
+
+        let conv = Conv2d {
+            weight: with_value(self.weight, weight.select(0, indices.clone())),
+            bias: self
+                .bias
+                .zip(bias)
+                .map(|(param, bias)| with_value(param, bias.select(0, indices))),
+            ..self
+        };
+        (conv, kept)
+    }
+
+    /// Keep only the input channels at the given indices, e.g. the output channels kept by the
+    /// previous layer's [finalize_pruning](Conv2d::finalize_pruning).
+    ///
+    /// # Panics
+    ///
+    /// If the convolution is grouped.
+    pub fn retain_inputs(self, indices: &[usize]) -> Self {
+        assert_eq!(
+            self.groups, 1,
+            "Can't remove the channels of a grouped convolution"
+        );
+        let weight = self.weight.val();
+        let indices = index_tensor(indices, &weight.device());
+
+        Conv2d {
+            weight: with_value(self.weight, weight.select(1, indices)),
+            ..self
+        }
+    }
 }
 
 #[cfg(test)]
@@ -343,4 +407,35 @@ mod tests {
         // Width: 5 + 2 + 2 = 9, output = (9 - 3) / 1 + 1 = 7
         assert_eq!(output.dims(), [1, 3, 6, 7]);
     }
+
+    #[test]
+    fn finalize_pruning_removes_pruned_channels() {
+        use burn::module::{Pruner, PruningMethod};
+
+        let device = Device::default();
+        let pruner = Pruner::new(
+            PruningMethod::Structured {
+                dim: 0,
+                group_size: 1,
+            },
+            0.5,
+        );
+        let first = Conv2dConfig::new([2, 4], [3, 3])
+            .init(&device)
+            .apply_pruning(pruner);
+        let second = Conv2dConfig::new([4, 2], [3, 3]).init(&device);
+        let input = Tensor::<4>::random([1, 2, 7, 7], burn::tensor::Distribution::Default, &device);
+        let expected = second.forward(first.forward(input.clone()));
+
+        let (first, kept) = first.finalize_pruning();
+        let second = second.retain_inputs(&kept);
+
+        assert_eq!(kept.len(), 2);
+        assert_eq!(first.weight.dims(), [2, 2, 3, 3]);
+        assert_eq!(second.weight.dims(), [2, 2, 3, 3]);
+        second
+            .forward(first.forward(input))
+            .into_data()
+            .assert_approx_eq::<FT>(&expected.into_data(), Tolerance::default());
+    }
 }
@@ -5,6 +5,7 @@ use burn_core as burn;
 use crate::PaddingConfig3d;
 use burn::config::Config;
 use burn::module::Initializer;
+use burn::module::hookable;
 use burn::module::{Content, DisplaySettings, Module, ModuleDisplay, Param};
 use burn::tensor::Device;
 use burn::tensor::Tensor;
@@ -151,6 +152,7 @@ impl Conv3d {
     ///
     /// - input: `[batch_size, channels_in, depth_in, height_in, width_in]`
     /// - output: `[batch_size, channels_out, depth_out, height_out, width_out]`
+    #[hookable]
     pub fn forward(&self, input: Tensor<5>) -> Tensor<5> {
         let [_batch_size, _channels_in, depth_in, height_in, width_in] = input.dims();
         let padding = self.padding.calculate_padding_3d(
@@ -270,4 +272,28 @@ mod tests {
         let input = Tensor::<5>::zeros([1, 4, 10, 10, 10], &Default::default());
         let _ = conv.forward(input);
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn calibration_should_observe_the_input() {
+        use burn::module::{ActivationCalibration, ActivationCalibrator};
+        use burn::tensor::quantization::QuantValue;
+
+        let device = Device::default();
+        let conv = Conv3dConfig::new([1, 2], [1, 1, 1]).init(&device);
+        let scheme = device
+            .settings()
+            .quantization
+            .scheme
+            .with_value(QuantValue::Q8S);
+        let calibrator = ActivationCalibrator::new(ActivationCalibration::MinMax, scheme);
+        let input = Tensor::<5>::from_data([[[[[-2.0, 0.5]]]]], &device);
+
+        let statistics = calibrator.collect(&conv, [input], |conv, input| conv.forward(input));
+
+        assert_eq!(statistics.keys().collect::<Vec<_>>(), [""]);
+        let statistics = &statistics[""];
+        assert_eq!(statistics.count(), 2);
+        assert_eq!((statistics.min(), statistics.max()), (-2.0, 0.5));
+    }
 }
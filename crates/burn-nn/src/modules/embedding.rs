@@ -4,6 +4,7 @@ use burn::config::Config;
 use burn::module::Initializer;
 use burn::module::Module;
 use burn::module::Param;
+use burn::module::hookable;
 use burn::module::{Content, DisplaySettings, ModuleDisplay};
 use burn::tensor::Int;
 use burn::tensor::{Device, Tensor};
@@ -69,6 +70,7 @@ impl Embedding {
     ///
     /// - input: `[batch_size, seq_length]`
     /// - output: `[batch_size, seq_length, d_model]`
+    #[hookable]
     pub fn forward(&self, input: Tensor<2, Int>) -> Tensor<3> {
         embedding(self.weight.val(), input)
     }
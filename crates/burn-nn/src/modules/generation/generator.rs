@@ -0,0 +1,583 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use burn_core as burn;
+
+use burn::config::Config;
+use burn::tensor::activation::{log_softmax, softmax};
+use burn::tensor::{Device, Int, Tensor, TensorData};
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use num_traits::Float as _;
+
+use super::{
+    LogitsProcessor, LogitsProcessors, MinP, PresencePenalty, RepetitionPenalty, Temperature, TopK,
+    TopP,
+};
+
+/// A model generating tokens one at a time, such as a [transformer decoder](crate::transformer::TransformerDecoder)
+/// with its autoregressive cache.
+///
+/// Any `FnMut(Tensor<2, Int>) -> Tensor<2>` closure is a model without state.
+pub trait GenerationModel {
+    /// The logits of the next token of each sequence.
+    ///
+    /// # Shapes
+    ///
+    /// - tokens: `[batch_size, seq_length]`, the tokens so far, prompt included.
+    /// - output: `[batch_size, vocab_size]`
+    fn next_logits(&mut self, tokens: Tensor<2, Int>) -> Tensor<2>;
+
+    /// Reorder the decoding state along the batch: entry `i` becomes the entry `indices[i]`.
+    ///
+    /// Only called by beam search, where beams are duplicated and dropped between steps, so a
+    /// model with an autoregressive cache should [reorder](crate::cache::TensorCache::reorder)
+    /// it. The default does nothing, which is right for models without state.
+    fn reorder(&mut self, _indices: Tensor<1, Int>) {}
+}
+
+impl<F: FnMut(Tensor<2, Int>) -> Tensor<2>> GenerationModel for F {
+    fn next_logits(&mut self, tokens: Tensor<2, Int>) -> Tensor<2> {
+        self(tokens)
+    }
+}
+
+/// Configuration to create a [generator](Generator) using the [init function](GenerationConfig::init).
+#[derive(Config, Debug)]
+pub struct GenerationConfig {
+    /// The maximum number of tokens generated for each sequence.
+    pub max_new_tokens: usize,
+    /// The tokens ending a sequence. Default: none
+    #[config(default = "Vec::new()")]
+    pub stop_tokens: Vec<usize>,
+    /// The token appended to finished sequences while the rest of the batch goes on. Default: 0
+    #[config(default = 0)]
+    pub pad_token: usize,
+    /// Sample the next token instead of picking the most likely one. Default: false
+    #[config(default = false)]
+    pub sample: bool,
+    /// The [temperature](Temperature) of the logits. Default: 1.0
+    #[config(default = 1.0)]
+    pub temperature: f64,
+    /// Keep only the [k most likely tokens](TopK). Default: None
+    #[config(default = "None")]
+    pub top_k: Option<usize>,
+    /// Keep only the [nucleus](TopP) of probability `p`. Default: None
+    #[config(default = "None")]
+    pub top_p: Option<f64>,
+    /// Remove the [unlikely tokens](MinP) relative to the most likely one. Default: None
+    #[config(default = "None")]
+    pub min_p: Option<f64>,
+    /// The [repetition penalty](RepetitionPenalty). Default: 1.0
+    #[config(default = 1.0)]
+    pub repetition_penalty: f64,
+    /// The [presence penalty](PresencePenalty). Default: 0.0
+    #[config(default = 0.0)]
+    pub presence_penalty: f64,
+    /// The [frequency penalty](PresencePenalty::with_frequency). Default: 0.0
+    #[config(default = 0.0)]
+    pub frequency_penalty: f64,
+    /// The number of beams, beam search being used when above 1. Default: 1
+    #[config(default = 1)]
+    pub num_beams: usize,
+    /// The exponent of the length the beam scores are divided by: above 0 favors longer
+    /// sequences, below 0 shorter ones. Default: 1.0
+    #[config(default = 1.0)]
+    pub length_penalty: f64,
+}
+
+impl GenerationConfig {
+    /// Initialize a new [generator](Generator).
+    ///
+    /// The penalties are applied first, then the temperature, top-k, top-p and min-p.
+    pub fn init(&self) -> Generator {
+        assert!(self.num_beams > 0, "Generation needs at least one beam");
+        assert!(
+            !(self.sample && self.num_beams > 1),
+            "Beam search doesn't sample"
+        );
+
+        let mut processors = LogitsProcessors::new();
+        if self.repetition_penalty != 1.0 {
+            processors.push(RepetitionPenalty::new(self.repetition_penalty));
+        }
+        if self.presence_penalty != 0.0 || self.frequency_penalty != 0.0 {
+            processors.push(
+                PresencePenalty::new(self.presence_penalty).with_frequency(self.frequency_penalty),
+            );
+        }
+        if self.temperature != 1.0 {
+            processors.push(Temperature::new(self.temperature));
+        }
+        if let Some(k) = self.top_k {
+            processors.push(TopK::new(k));
+        }
+        if let Some(p) = self.top_p {
+            processors.push(TopP::new(p));
+        }
+        if let Some(min_p) = self.min_p {
+            processors.push(MinP::new(min_p));
+        }
+
+        Generator {
+            config: self.clone(),
+            processors,
+        }
+    }
+}
+
+/// Why a sequence stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FinishReason {
+    /// A stop token was generated.
+    StopToken,
+    /// The maximum number of new tokens was generated.
+    MaxLength,
+}
+
+/// The [generated](Generator::generate) sequences.
+#[derive(Clone, Debug)]
+pub struct GenerationOutput {
+    /// The tokens generated for each sequence, without the prompt nor the stop token.
+    pub tokens: Vec<Vec<usize>>,
+    /// Why each sequence stopped.
+    pub finish_reasons: Vec<FinishReason>,
+    /// The log-probability of each sequence under the processed logits, stop token included.
+    /// With beam search, it is divided by the length to the power of the length penalty.
+    pub scores: Vec<f64>,
+}
+
+/// Generates sequences with a [model](GenerationModel): greedily, by sampling, or with beam search.
+pub struct Generator {
+    config: GenerationConfig,
+    processors: LogitsProcessors,
+}
+
+/// A finished beam search sequence.
+#[derive(Clone)]
+struct Hypothesis {
+    tokens: Vec<usize>,
+    score: f64,
+    reason: FinishReason,
+}
+
+impl Generator {
+    /// Append a [logits processor](LogitsProcessor), applied after those of the config.
+    pub fn with_processor(mut self, processor: impl LogitsProcessor + 'static) -> Self {
+        self.processors.push(processor);
+        self
+    }
+
+    /// Generate a continuation of each prompt.
+    ///
+    /// The batch goes on until every sequence stopped; a stopped sequence is fed the
+    /// [pad token](GenerationConfig::pad_token) meanwhile.
+    ///
+    /// # Shapes
+    ///
+    /// - prompts: `[batch_size, prompt_length]`
+    pub fn generate<M: GenerationModel>(
+        &self,
+        model: &mut M,
+        prompts: Tensor<2, Int>,
+    ) -> GenerationOutput {
+        match self.config.num_beams {
+            1 => self.decode(model, prompts),
+            _ => self.beam_search(model, prompts),
+        }
+    }
+
+    fn decode<M: GenerationModel>(
+        &self,
+        model: &mut M,
+        prompts: Tensor<2, Int>,
+    ) -> GenerationOutput {
+        let [batch_size, _] = prompts.dims();
+        let device = prompts.device();
+
+        let mut history = rows(&prompts);
+        let mut generated = vec![Vec::new(); batch_size];
+        let mut finished = vec![None; batch_size];
+        let mut scores = vec![0.0; batch_size];
+        let mut tokens = prompts;
+
+        for _ in 0..self.config.max_new_tokens {
+            let logits = model.next_logits(tokens.clone());
+            let logits = self.processors.process(logits, &history);
+
+            let next = match self.config.sample {
+                true => softmax(logits.clone(), 1).categorical(1),
+                false => logits.clone().argmax(1),
+            };
+            let log_probs = floats(log_softmax(logits, 1).gather(1, next.clone()));
+            let next = ints(next);
+
+            let mut next_tokens = Vec::with_capacity(batch_size);
+            for index in 0..batch_size {
+                if finished[index].is_some() {
+                    next_tokens.push(self.config.pad_token);
+                    continue;
+                }
+
+                let token = next[index];
+                scores[index] += log_probs[index];
+                history[index].push(token);
+                match self.config.stop_tokens.contains(&token) {
+                    true => finished[index] = Some(FinishReason::StopToken),
+                    false => generated[index].push(token),
+                }
+                next_tokens.push(token);
+            }
+
+            if finished.iter().all(Option::is_some) {
+                break;
+            }
+            tokens = Tensor::cat(vec![tokens, column(next_tokens, &device)], 1);
+        }
+
+        GenerationOutput {
+            tokens: generated,
+            finish_reasons: finished
+                .into_iter()
+                .map(|reason| reason.unwrap_or(FinishReason::MaxLength))
+                .collect(),
+            scores,
+        }
+    }
+
+    fn beam_search<M: GenerationModel>(
+        &self,
+        model: &mut M,
+        prompts: Tensor<2, Int>,
+    ) -> GenerationOutput {
+        let num_beams = self.config.num_beams;
+        let [batch_size, prompt_length] = prompts.dims();
+        let device = prompts.device();
+
+        // Entry `index * num_beams + beam` of the expanded batch is a beam of prompt `index`.
+        let prompt_rows = rows(&prompts);
+        let mut history: Vec<Vec<usize>> = (0..batch_size * num_beams)
+            .map(|entry| prompt_rows[entry / num_beams].clone())
+            .collect();
+        let mut tokens = prompts
+            .unsqueeze_dim::<3>(1)
+            .repeat_dim(1, num_beams)
+            .reshape([batch_size * num_beams, prompt_length]);
+        let mut beams = vec![Vec::new(); batch_size * num_beams];
+        // Only the first beam is alive at first, so the others don't duplicate it.
+        let mut beam_scores: Vec<f64> = (0..batch_size * num_beams)
+            .map(|entry| match entry % num_beams {
+                0 => 0.0,
+                _ => f64::NEG_INFINITY,
+            })
+            .collect();
+        let mut hypotheses = vec![Vec::<Hypothesis>::new(); batch_size];
+        let mut done = vec![false; batch_size];
+
+        for step in 0..self.config.max_new_tokens {
+            let logits = model.next_logits(tokens.clone());
+            let logits = self.processors.process(logits, &history);
+            let [_, vocab_size] = logits.dims();
+
+            let previous = Tensor::<2>::from_data(
+                TensorData::new(
+                    beam_scores.iter().map(|score| *score as f32).collect(),
+                    [batch_size * num_beams, 1],
+                ),
+                &device,
+            );
+            let scores =
+                log_softmax(logits, 1) + previous.expand([batch_size * num_beams, vocab_size]);
+            let (top_scores, top_indices) = scores
+                .reshape([batch_size, num_beams * vocab_size])
+                .topk_with_indices(2 * num_beams, 1);
+            let top_scores = floats(top_scores);
+            let top_indices = ints(top_indices);
+
+            let mut origins = Vec::with_capacity(batch_size * num_beams);
+            let mut next_tokens = Vec::with_capacity(batch_size * num_beams);
+            let mut next_scores = Vec::with_capacity(batch_size * num_beams);
+
+            for index in 0..batch_size {
+                let first = index * num_beams;
+                if done[index] {
+                    origins.extend(first..first + num_beams);
+                    next_tokens.extend((0..num_beams).map(|_| self.config.pad_token));
+                    next_scores.extend(&beam_scores[first..first + num_beams]);
+                    continue;
+                }
+
+                // Twice as many candidates as beams, so enough remain when some stop.
+                let mut kept = 0;
+                for rank in 0..2 * num_beams {
+                    let score = top_scores[index * 2 * num_beams + rank];
+                    let candidate = top_indices[index * 2 * num_beams + rank];
+                    if score == f64::NEG_INFINITY || kept == num_beams {
+                        break;
+                    }
+                    let origin = first + candidate / vocab_size;
+                    let token = candidate % vocab_size;
+
+                    if self.config.stop_tokens.contains(&token) {
+                        // Only a stop among the best candidates ends a sequence.
+                        if rank < num_beams {
+                            self.add_hypothesis(
+                                &mut hypotheses[index],
+                                &beams[origin],
+                                score,
+                                FinishReason::StopToken,
+                            );
+                        }
+                        continue;
+                    }
+
+                    origins.push(origin);
+                    next_tokens.push(token);
+                    next_scores.push(score);
+                    kept += 1;
+                }
+                // Fewer live candidates than beams: fill with dead beams.
+                for _ in kept..num_beams {
+                    origins.push(first);
+                    next_tokens.push(self.config.pad_token);
+                    next_scores.push(f64::NEG_INFINITY);
+                }
+
+                // Done when no running beam can beat the worst kept hypothesis anymore.
+                let best_running = self.normalize(next_scores[first], step + 1);
+                done[index] = best_running == f64::NEG_INFINITY
+                    || (hypotheses[index].len() == num_beams
+                        && hypotheses[index]
+                            .iter()
+                            .all(|hypothesis| hypothesis.score >= best_running));
+            }
+
+            beams = origins
+                .iter()
+                .zip(&next_tokens)
+                .map(|(origin, token)| {
+                    let mut beam = beams[*origin].clone();
+                    beam.push(*token);
+                    beam
+                })
+                .collect();
+            history = origins
+                .iter()
+                .zip(&next_tokens)
+                .map(|(origin, token)| {
+                    let mut tokens = history[*origin].clone();
+                    tokens.push(*token);
+                    tokens
+                })
+                .collect();
+            beam_scores = next_scores;
+
+            if done.iter().all(|done| *done) {
+                break;
+            }
+
+            let origins = Tensor::<1, Int>::from_data(
+                TensorData::new(
+                    origins.iter().map(|origin| *origin as i64).collect(),
+                    [batch_size * num_beams],
+                ),
+                &device,
+            );
+            model.reorder(origins.clone());
+            tokens = Tensor::cat(
+                vec![tokens.select(0, origins), column(next_tokens, &device)],
+                1,
+            );
+        }
+
+        // The beams still running reached the maximum length.
+        for index in (0..batch_size).filter(|index| !done[*index]) {
+            for entry in index * num_beams..(index + 1) * num_beams {
+                if beam_scores[entry] != f64::NEG_INFINITY {
+                    let hypothesis = Hypothesis {
+                        score: self.normalize(beam_scores[entry], beams[entry].len()),
+                        tokens: beams[entry].clone(),
+                        reason: FinishReason::MaxLength,
+                    };
+                    hypotheses[index].push(hypothesis);
+                }
+            }
+        }
+
+        let mut output = GenerationOutput {
+            tokens: Vec::with_capacity(batch_size),
+            finish_reasons: Vec::with_capacity(batch_size),
+            scores: Vec::with_capacity(batch_size),
+        };
+        for hypotheses in hypotheses {
+            let best = hypotheses
+                .into_iter()
+                .max_by(|a, b| a.score.total_cmp(&b.score))
+                .unwrap_or(Hypothesis {
+                    tokens: Vec::new(),
+                    score: f64::NEG_INFINITY,
+                    reason: FinishReason::MaxLength,
+                });
+            output.tokens.push(best.tokens);
+            output.finish_reasons.push(best.reason);
+            output.scores.push(best.score);
+        }
+        output
+    }
+
+    /// Keep the `num_beams` best finished sequences of a prompt.
+    fn add_hypothesis(
+        &self,
+        hypotheses: &mut Vec<Hypothesis>,
+        tokens: &[usize],
+        score: f64,
+        reason: FinishReason,
+    ) {
+        hypotheses.push(Hypothesis {
+            tokens: tokens.to_vec(),
+            // The stop token counts in the length.
+            score: self.normalize(score, tokens.len() + 1),
+            reason,
+        });
+        hypotheses.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hypotheses.truncate(self.config.num_beams);
+    }
+
+    fn normalize(&self, score: f64, length: usize) -> f64 {
+        score / (length.max(1) as f64).powf(self.config.length_penalty)
+    }
+}
+
+/// The tokens of each sequence.
+fn rows(tokens: &Tensor<2, Int>) -> Vec<Vec<usize>> {
+    let [batch_size, seq_length] = tokens.dims();
+    let values = ints(tokens.clone());
+
+    (0..batch_size)
+        .map(|index| values[index * seq_length..(index + 1) * seq_length].to_vec())
+        .collect()
+}
+
+fn ints<const D: usize>(tensor: Tensor<D, Int>) -> Vec<usize> {
+    tensor
+        .into_data()
+        .iter::<i64>()
+        .map(|value| value as usize)
+        .collect()
+}
+
+fn floats<const D: usize>(tensor: Tensor<D>) -> Vec<f64> {
+    tensor
+        .into_data()
+        .iter::<f32>()
+        .map(|value| value as f64)
+        .collect()
+}
+
+/// The tokens as a `[batch_size, 1]` tensor.
+fn column(tokens: Vec<usize>, device: &Device) -> Tensor<2, Int> {
+    let batch_size = tokens.len();
+    Tensor::from_data(
+        TensorData::new(
+            tokens.into_iter().map(|token| token as i64).collect(),
+            [batch_size, 1],
+        ),
+        device,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A model whose next-token probabilities only depend on the last token.
+    fn markov(probs: fn(usize) -> Vec<f32>) -> impl FnMut(Tensor<2, Int>) -> Tensor<2> {
+        move |tokens: Tensor<2, Int>| {
+            let device = tokens.device();
+            let rows = rows(&tokens);
+            let vocab_size = probs(0).len();
+            let logits: Vec<f32> = rows
+                .iter()
+                .flat_map(|row| probs(*row.last().unwrap()))
+                .map(f32::ln)
+                .collect();
+            Tensor::from_data(TensorData::new(logits, [rows.len(), vocab_size]), &device)
+        }
+    }
+
+    fn counter(last: usize) -> Vec<f32> {
+        let mut probs = vec![0.01; 5];
+        probs[(last + 1) % 5] = 0.96;
+        probs
+    }
+
+    fn prompts(tokens: Vec<i64>) -> Tensor<2, Int> {
+        let batch_size = tokens.len();
+        Tensor::from_data(
+            TensorData::new(tokens, [batch_size, 1]),
+            &Default::default(),
+        )
+    }
+
+    #[test]
+    fn greedy_stops_each_sequence_on_its_own() {
+        let generator = GenerationConfig::new(10).with_stop_tokens(vec![4]).init();
+        let output = generator.generate(&mut markov(counter), prompts(vec![0, 2]));
+
+        assert_eq!(output.tokens, vec![vec![1, 2, 3], vec![3]]);
+        assert_eq!(
+            output.finish_reasons,
+            vec![FinishReason::StopToken, FinishReason::StopToken]
+        );
+        assert!((output.scores[1] - 2.0 * 0.96f64.ln()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn max_new_tokens_cuts_generation() {
+        let generator = GenerationConfig::new(2).with_stop_tokens(vec![4]).init();
+        let output = generator.generate(&mut markov(counter), prompts(vec![0, 2]));
+
+        assert_eq!(output.tokens, vec![vec![1, 2], vec![3]]);
+        assert_eq!(
+            output.finish_reasons,
+            vec![FinishReason::MaxLength, FinishReason::StopToken]
+        );
+    }
+
+    /// Token 2 starts and stops sequences. Token 0 is the most likely first, but leads nowhere,
+    /// while token 1 is likely to be followed by the stop.
+    fn trap(last: usize) -> Vec<f32> {
+        match last {
+            0 => vec![0.34, 0.33, 0.33],
+            1 => vec![0.05, 0.05, 0.9],
+            _ => vec![0.6, 0.4, 1e-6],
+        }
+    }
+
+    #[test]
+    fn beam_search_finds_what_greedy_misses() {
+        let config = GenerationConfig::new(3).with_stop_tokens(vec![2]);
+
+        let greedy = config.init().generate(&mut markov(trap), prompts(vec![2]));
+        assert_eq!(greedy.tokens, vec![vec![0, 0, 0]]);
+
+        let beam = config
+            .with_num_beams(2)
+            .init()
+            .generate(&mut markov(trap), prompts(vec![2]));
+        assert_eq!(beam.tokens, vec![vec![1]]);
+        assert_eq!(beam.finish_reasons, vec![FinishReason::StopToken]);
+        assert!((beam.scores[0] - (0.4f64 * 0.9).ln() / 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn sampling_respects_top_k() {
+        let generator = GenerationConfig::new(4)
+            .with_sample(true)
+            .with_top_k(Some(1))
+            .init();
+        let output = generator.generate(&mut markov(counter), prompts(vec![0]));
+
+        assert_eq!(output.tokens, vec![vec![1, 2, 3, 4]]);
+    }
+}
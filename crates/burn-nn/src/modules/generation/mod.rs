@@ -0,0 +1,5 @@
+mod generator;
+mod processor;
+
+pub use generator::*;
+pub use processor::*;
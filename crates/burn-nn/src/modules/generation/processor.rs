@@ -0,0 +1,361 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use burn_core as burn;
+
+use burn::tensor::activation::softmax;
+use burn::tensor::{Device, Tensor, TensorData};
+
+/// Transforms the logits of the next token before it is picked.
+///
+/// Processors are applied in order by a [generator](super::Generator), and can be combined with
+/// [LogitsProcessors].
+pub trait LogitsProcessor {
+    /// Process the logits of the next token of each sequence.
+    ///
+    /// `tokens` holds the tokens of each sequence so far, prompt included.
+    ///
+    /// # Shapes
+    ///
+    /// - logits: `[batch_size, vocab_size]`
+    /// - output: `[batch_size, vocab_size]`
+    fn process(&self, logits: Tensor<2>, tokens: &[Vec<usize>]) -> Tensor<2>;
+}
+
+/// A list of [logits processors](LogitsProcessor), applied in order.
+#[derive(Default)]
+pub struct LogitsProcessors {
+    processors: Vec<Box<dyn LogitsProcessor>>,
+}
+
+impl LogitsProcessors {
+    /// Create an empty list, which leaves the logits unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a processor to the list.
+    pub fn with(mut self, processor: impl LogitsProcessor + 'static) -> Self {
+        self.processors.push(Box::new(processor));
+        self
+    }
+
+    /// Append a processor to the list.
+    pub fn push(&mut self, processor: impl LogitsProcessor + 'static) {
+        self.processors.push(Box::new(processor));
+    }
+
+    /// The number of processors.
+    pub fn len(&self) -> usize {
+        self.processors.len()
+    }
+
+    /// Whether the list leaves the logits unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.processors.is_empty()
+    }
+}
+
+impl LogitsProcessor for LogitsProcessors {
+    fn process(&self, logits: Tensor<2>, tokens: &[Vec<usize>]) -> Tensor<2> {
+        self.processors.iter().fold(logits, |logits, processor| {
+            processor.process(logits, tokens)
+        })
+    }
+}
+
+/// Divides the logits by a temperature: below 1 sharpens the distribution, above 1 flattens it.
+#[derive(Clone, Debug)]
+pub struct Temperature {
+    temperature: f64,
+}
+
+impl Temperature {
+    /// Create a new temperature processor.
+    ///
+    /// # Panics
+    ///
+    /// If the temperature isn't strictly positive.
+    pub fn new(temperature: f64) -> Self {
+        assert!(
+            temperature > 0.0,
+            "The temperature should be strictly positive"
+        );
+        Self { temperature }
+    }
+}
+
+impl LogitsProcessor for Temperature {
+    fn process(&self, logits: Tensor<2>, _tokens: &[Vec<usize>]) -> Tensor<2> {
+        logits.div_scalar(self.temperature)
+    }
+}
+
+/// Keeps only the `k` most likely tokens.
+#[derive(Clone, Debug)]
+pub struct TopK {
+    k: usize,
+}
+
+impl TopK {
+    /// Create a new top-k processor.
+    ///
+    /// # Panics
+    ///
+    /// If `k` is zero.
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0, "Top-k should keep at least one token");
+        Self { k }
+    }
+}
+
+impl LogitsProcessor for TopK {
+    fn process(&self, logits: Tensor<2>, _tokens: &[Vec<usize>]) -> Tensor<2> {
+        let [batch_size, vocab_size] = logits.dims();
+        if self.k >= vocab_size {
+            return logits;
+        }
+
+        let kth = logits
+            .clone()
+            .topk(self.k, 1)
+            .slice_dim(1, self.k - 1..self.k)
+            .expand([batch_size, vocab_size]);
+        let mask = logits.clone().lower(kth);
+        logits.mask_fill(mask, f32::NEG_INFINITY)
+    }
+}
+
+/// Keeps the smallest set of most likely tokens whose probabilities add up to at least `p`,
+/// also known as nucleus sampling.
+#[derive(Clone, Debug)]
+pub struct TopP {
+    p: f64,
+}
+
+impl TopP {
+    /// Create a new top-p processor.
+    ///
+    /// # Panics
+    ///
+    /// If `p` isn't in `(0, 1]`.
+    pub fn new(p: f64) -> Self {
+        assert!(0.0 < p && p <= 1.0, "Top-p should be in (0, 1]");
+        Self { p }
+    }
+}
+
+impl LogitsProcessor for TopP {
+    fn process(&self, logits: Tensor<2>, _tokens: &[Vec<usize>]) -> Tensor<2> {
+        let [batch_size, vocab_size] = logits.dims();
+        let (sorted, _indices) = logits.clone().sort_descending_with_indices(1);
+        let probs = softmax(sorted.clone(), 1);
+
+        // A token is removed when the more likely ones already reach `p`, so the most likely
+        // token is always kept.
+        let preceding = probs.clone().cumsum(1) - probs;
+        let removed = preceding.greater_elem(self.p);
+        let threshold = sorted
+            .mask_fill(removed, f32::INFINITY)
+            .min_dim(1)
+            .expand([batch_size, vocab_size]);
+
+        let mask = logits.clone().lower(threshold);
+        logits.mask_fill(mask, f32::NEG_INFINITY)
+    }
+}
+
+/// Removes the tokens whose probability is below `min_p` times the probability of the most
+/// likely token, which adapts the cutoff to how confident the model is.
+#[derive(Clone, Debug)]
+pub struct MinP {
+    min_p: f64,
+}
+
+impl MinP {
+    /// Create a new min-p processor.
+    ///
+    /// # Panics
+    ///
+    /// If `min_p` isn't in `[0, 1]`.
+    pub fn new(min_p: f64) -> Self {
+        assert!((0.0..=1.0).contains(&min_p), "Min-p should be in [0, 1]");
+        Self { min_p }
+    }
+}
+
+impl LogitsProcessor for MinP {
+    fn process(&self, logits: Tensor<2>, _tokens: &[Vec<usize>]) -> Tensor<2> {
+        let [batch_size, vocab_size] = logits.dims();
+        let probs = softmax(logits.clone(), 1);
+        let threshold = probs
+            .clone()
+            .max_dim(1)
+            .mul_scalar(self.min_p)
+            .expand([batch_size, vocab_size]);
+
+        let mask = probs.lower(threshold);
+        logits.mask_fill(mask, f32::NEG_INFINITY)
+    }
+}
+
+/// Penalizes the tokens already in a sequence by dividing their positive logits by the
+/// penalty and multiplying their negative ones, as in the CTRL paper.
+#[derive(Clone, Debug)]
+pub struct RepetitionPenalty {
+    penalty: f64,
+}
+
+impl RepetitionPenalty {
+    /// Create a new repetition penalty processor. A penalty of 1 leaves the logits unchanged.
+    ///
+    /// # Panics
+    ///
+    /// If the penalty isn't strictly positive.
+    pub fn new(penalty: f64) -> Self {
+        assert!(
+            penalty > 0.0,
+            "The repetition penalty should be strictly positive"
+        );
+        Self { penalty }
+    }
+}
+
+impl LogitsProcessor for RepetitionPenalty {
+    fn process(&self, logits: Tensor<2>, tokens: &[Vec<usize>]) -> Tensor<2> {
+        let seen = token_counts(tokens, logits.dims(), &logits.device()).greater_elem(0.0);
+        let positive = logits.clone().greater_elem(0.0);
+
+        let penalized = logits
+            .clone()
+            .mul_scalar(self.penalty)
+            .mask_where(positive, logits.clone().div_scalar(self.penalty));
+        logits.mask_where(seen, penalized)
+    }
+}
+
+/// Subtracts a fixed penalty from the logits of the tokens already in a sequence, plus a
+/// penalty per occurrence, as in the OpenAI API.
+#[derive(Clone, Debug)]
+pub struct PresencePenalty {
+    presence: f64,
+    frequency: f64,
+}
+
+impl PresencePenalty {
+    /// Create a new processor subtracting `presence` from the logits of every token already in
+    /// the sequence.
+    pub fn new(presence: f64) -> Self {
+        Self {
+            presence,
+            frequency: 0.0,
+        }
+    }
+
+    /// Also subtract `frequency` times the number of occurrences of each token.
+    pub fn with_frequency(mut self, frequency: f64) -> Self {
+        self.frequency = frequency;
+        self
+    }
+}
+
+impl LogitsProcessor for PresencePenalty {
+    fn process(&self, logits: Tensor<2>, tokens: &[Vec<usize>]) -> Tensor<2> {
+        let counts = token_counts(tokens, logits.dims(), &logits.device());
+        let seen = counts.clone().greater_elem(0.0).float();
+
+        let penalty = counts.mul_scalar(self.frequency) + seen.mul_scalar(self.presence);
+        logits - penalty
+    }
+}
+
+/// The number of occurrences of each token in each sequence, `[batch_size, vocab_size]`.
+fn token_counts(
+    tokens: &[Vec<usize>],
+    [batch_size, vocab_size]: [usize; 2],
+    device: &Device,
+) -> Tensor<2> {
+    let mut counts = vec![0.0f32; batch_size * vocab_size];
+    for (index, sequence) in tokens.iter().enumerate() {
+        for token in sequence.iter().filter(|token| **token < vocab_size) {
+            counts[index * vocab_size + token] += 1.0;
+        }
+    }
+
+    Tensor::from_data(TensorData::new(counts, [batch_size, vocab_size]), device)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn::tensor::Tolerance;
+
+    const NEG_INF: f32 = f32::NEG_INFINITY;
+
+    fn logits() -> Tensor<2> {
+        Tensor::from_data([[2.0, 1.0, 0.0, -1.0]], &Default::default())
+    }
+
+    #[test]
+    fn top_k_keeps_the_k_largest() {
+        let output = TopK::new(2).process(logits(), &[vec![]]);
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[2.0, 1.0, NEG_INF, NEG_INF]]), false);
+    }
+
+    #[test]
+    fn top_p_keeps_the_nucleus() {
+        // Probabilities: [0.644, 0.237, 0.087, 0.032].
+        let output = TopP::new(0.8).process(logits(), &[vec![]]);
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[2.0, 1.0, NEG_INF, NEG_INF]]), false);
+
+        // The most likely token is always kept.
+        let output = TopP::new(0.1).process(logits(), &[vec![]]);
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[2.0, NEG_INF, NEG_INF, NEG_INF]]), false);
+    }
+
+    #[test]
+    fn min_p_scales_with_the_top_probability() {
+        // The third token is 0.135 times as likely as the first.
+        let output = MinP::new(0.1).process(logits(), &[vec![]]);
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[2.0, 1.0, 0.0, NEG_INF]]), false);
+    }
+
+    #[test]
+    fn repetition_penalty_pushes_seen_tokens_down() {
+        let output = RepetitionPenalty::new(2.0).process(logits(), &[vec![0, 3]]);
+        output.into_data().assert_approx_eq::<f32>(
+            &TensorData::from([[1.0, 1.0, 0.0, -2.0]]),
+            Tolerance::default(),
+        );
+    }
+
+    #[test]
+    fn presence_penalty_counts_occurrences() {
+        let output = PresencePenalty::new(0.5)
+            .with_frequency(0.25)
+            .process(logits(), &[vec![1, 1, 2]]);
+        output.into_data().assert_approx_eq::<f32>(
+            &TensorData::from([[2.0, 0.0, -0.75, -1.0]]),
+            Tolerance::default(),
+        );
+    }
+
+    #[test]
+    fn processors_apply_in_order() {
+        let processors = LogitsProcessors::new()
+            .with(Temperature::new(0.5))
+            .with(TopK::new(1));
+        let output = processors.process(logits(), &[vec![]]);
+        output
+            .into_data()
+            .assert_eq(&TensorData::from([[4.0, NEG_INF, NEG_INF, NEG_INF]]), false);
+    }
+}
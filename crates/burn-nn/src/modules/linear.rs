@@ -2,10 +2,14 @@ use burn_core as burn;
 
 use burn::config::Config;
 use burn::module::Param;
+use burn::module::hookable;
 use burn::module::{Content, DisplaySettings, Initializer, Module, ModuleDisplay};
 use burn::tensor::module::linear;
 use burn::tensor::{Device, Tensor};
 
+use crate::modules::pruning::{index_tensor, nonzero_indices, with_value};
+use alloc::vec::Vec;
+
 /// Configuration to create a [`Linear`] layer using the [init function](LinearConfig::init).
 #[derive(Config, Debug)]
 pub struct LinearConfig {
@@ -125,6 +129,7 @@ impl Linear {
     /// # Returns
     ///
     /// The transformed tensor of shape `[..., d_output]`.
+    #[hookable]
     pub fn forward<const D: usize>(&self, input: Tensor<D>) -> Tensor<D> {
         linear(
             input,
@@ -132,6 +137,45 @@ impl Linear {
             self.bias.as_ref().map(|b| b.val()),
         )
     }
+
+    /// Fold the [pruning masks](burn::module::PruningMask) into the parameters and remove the
+    /// output features whose weights and bias are all zero, as left by structured pruning of the
+    /// output dimension.
+    ///
+    /// Returns the layer with the indices of the kept output features, to remove the matching
+    /// inputs of the next layer with [retain_inputs](Linear::retain_inputs).
+    pub fn finalize_pruning(self) -> (Self, Vec<usize>) {
+        let weight = self.weight.val();
+        let bias = self.bias.as_ref().map(|bias| bias.val());
+
+        let mut magnitude = weight.clone().abs().sum_dim(0).squeeze_dim::<1>(0);
+        if let Some(bias) = &bias {
+            magnitude = magnitude + bias.clone().abs();
+        }
+        let kept = nonzero_indices(magnitude);
+        let indices = index_tensor(&kept, &weight.device());
+
+        let linear = Linear {
+            weight: with_value(self.weight, weight.select(1, indices.clone())),
+            bias: self
+                .bias
+                .zip(bias)
+                .map(|(param, bias)| with_value(param, bias.select(0, indices))),
+        };
+        (linear, kept)
+    }
+
+    /// Keep only the input features at the given indices, e.g. the output features kept by the
+    /// previous layer's [finalize_pruning](Linear::finalize_pruning).
+    pub fn retain_inputs(self, indices: &[usize]) -> Self {
+        let weight = self.weight.val();
+        let indices = index_tensor(indices, &weight.device());
+
+        Linear {
+            weight: with_value(self.weight, weight.select(0, indices)),
+            bias: self.bias,
+        }
+    }
 }
 
 impl ModuleDisplay for Linear {
@@ -314,4 +358,33 @@ mod tests {
 
         data_1.assert_approx_eq::<f32>(&data_2, Default::default());
     }
+
+    #[test]
+    fn finalize_pruning_removes_pruned_outputs() {
+        use burn::module::{Pruner, PruningMethod};
+
+        let device = Device::default();
+        let pruner = Pruner::new(
+            PruningMethod::Structured {
+                dim: 1,
+                group_size: 1,
+            },
+            0.5,
+        );
+        let first = LinearConfig::new(4, 6).init(&device).apply_pruning(pruner);
+        let second = LinearConfig::new(6, 2).init(&device);
+        let signal = Tensor::<2>::random([3, 4], burn::tensor::Distribution::Default, &device);
+        let expected = second.forward(first.forward(signal.clone()));
+
+        let (first, kept) = first.finalize_pruning();
+        let second = second.retain_inputs(&kept);
+
+        assert_eq!(kept.len(), 3);
+        assert_eq!(first.weight.dims(), [4, 3]);
+        assert_eq!(second.weight.dims(), [3, 2]);
+        second
+            .forward(first.forward(signal))
+            .into_data()
+            .assert_approx_eq::<FT>(&expected.into_data(), Tolerance::default());
+    }
 }
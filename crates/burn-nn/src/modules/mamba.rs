@@ -0,0 +1,345 @@
+use burn_core as burn;
+
+use crate::conv::{Conv1d, Conv1dConfig};
+use crate::{Linear, LinearConfig, PaddingConfig1d};
+use alloc::vec;
+use burn::config::Config;
+use burn::module::hookable;
+use burn::module::{Content, DisplaySettings, Initializer, Module, ModuleDisplay, Param};
+use burn::tensor::activation::{silu, softplus};
+use burn::tensor::module::selective_scan;
+use burn::tensor::{Device, Distribution, Int, Tensor};
+
+/// Configuration to create a [Mamba](Mamba) block using the [init function](MambaConfig::init).
+#[derive(Config, Debug)]
+pub struct MambaConfig {
+    /// The size of the input and output features.
+    pub d_model: usize,
+    /// The size of the state of each channel. Default: 16
+    #[config(default = 16)]
+    pub d_state: usize,
+    /// The kernel size of the causal convolution. Default: 4
+    #[config(default = 4)]
+    pub d_conv: usize,
+    /// The expansion factor of the inner features, `d_inner = expand * d_model`. Default: 2
+    #[config(default = 2)]
+    pub expand: usize,
+    /// The rank of the step size projection. When `None`, `ceil(d_model / 16)` is used.
+    #[config(default = "None")]
+    pub dt_rank: Option<usize>,
+    /// The smallest initial step size. Default: 0.001
+    #[config(default = 0.001)]
+    pub dt_min: f64,
+    /// The largest initial step size. Default: 0.1
+    #[config(default = 0.1)]
+    pub dt_max: f64,
+    /// If bias should be added to the causal convolution. Default: true
+    #[config(default = true)]
+    pub conv_bias: bool,
+    /// If bias should be added to the input and output projections. Default: false
+    #[config(default = false)]
+    pub bias: bool,
+    /// The type of function used to initialize neural network parameters
+    #[config(
+        default = "Initializer::KaimingUniform{gain:1.0/num_traits::Float::sqrt(3.0), fan_out_only:false}"
+    )]
+    pub initializer: Initializer,
+}
+
+/// A Mamba block: a selective state-space sequence layer, as described in
+/// [Mamba: Linear-Time Sequence Modeling with Selective State Spaces](https://arxiv.org/abs/2312.00752).
+///
+/// The input is projected to an inner branch and a gate. The inner branch goes through a causal
+/// depthwise convolution and a selective scan whose step size `delta` and projections `B`, `C`
+/// depend on the input, then is gated by `silu(gate)` and projected back to `d_model`.
+///
+/// Training uses [forward](Mamba::forward), which runs the scan over the whole sequence with the
+/// backend's [selective scan](burn::tensor::module::selective_scan). Autoregressive inference
+/// uses [step](Mamba::step), which keeps a constant-size [state](MambaState) instead of the
+/// sequence.
+///
+/// Should be created using [MambaConfig].
+#[derive(Module, Debug)]
+#[module(custom_display)]
+pub struct Mamba {
+    /// Projection of the input to the inner branch and the gate, `d_model -> 2 * d_inner`.
+    pub in_proj: Linear,
+    /// Causal depthwise convolution over the inner branch.
+    pub conv1d: Conv1d,
+    /// Projection of the inner branch to the step size rank and the `B` and `C` projections.
+    pub x_proj: Linear,
+    /// Projection of the low-rank step size to every channel.
+    pub dt_proj: Linear,
+    /// Log of the negated state matrix, `A = -exp(a_log)`, `[d_inner, d_state]`.
+    pub a_log: Param<Tensor<2>>,
+    /// Skip connection weight of each channel, `[d_inner]`.
+    pub d: Param<Tensor<1>>,
+    /// Projection of the inner branch back to `d_model`.
+    pub out_proj: Linear,
+    /// The size of the state of each channel.
+    pub d_state: usize,
+    /// The rank of the step size projection.
+    pub dt_rank: usize,
+}
+
+/// The recurrent state of a [Mamba](Mamba) block, used by [step](Mamba::step).
+#[derive(Clone, Debug)]
+pub struct MambaState {
+    /// The last `d_conv - 1` inputs of the causal convolution, `[batch_size, d_inner, d_conv - 1]`.
+    pub conv: Tensor<3>,
+    /// The hidden state of the selective scan, `[batch_size, d_inner, d_state]`.
+    pub ssm: Tensor<3>,
+}
+
+impl ModuleDisplay for Mamba {
+    fn custom_settings(&self) -> Option<DisplaySettings> {
+        DisplaySettings::new()
+            .with_new_line_after_attribute(false)
+            .optional()
+    }
+
+    fn custom_content(&self, content: Content) -> Option<Content> {
+        let [d_inner, d_model] = self.out_proj.weight.shape().dims();
+        let [_, _, d_conv] = self.conv1d.weight.shape().dims();
+
+        content
+            .add("d_model", &d_model)
+            .add("d_inner", &d_inner)
+            .add("d_state", &self.d_state)
+            .add("d_conv", &d_conv)
+            .add("dt_rank", &self.dt_rank)
+            .optional()
+    }
+}
+
+impl MambaConfig {
+    /// Initialize a new [Mamba](Mamba) block.
+    pub fn init(&self, device: &Device) -> Mamba {
+        assert!(
+            self.d_conv > 0,
+            "The convolution kernel size should be positive"
+        );
+        assert!(
+            0.0 < self.dt_min && self.dt_min <= self.dt_max,
+            "The step size range should satisfy 0 < dt_min <= dt_max, got [{}, {}]",
+            self.dt_min,
+            self.dt_max
+        );
+
+        let d_inner = self.expand * self.d_model;
+        let dt_rank = self.dt_rank.unwrap_or(self.d_model.div_ceil(16));
+        let linear = |d_input, d_output, bias| {
+            LinearConfig::new(d_input, d_output)
+                .with_bias(bias)
+                .with_initializer(self.initializer.clone())
+                .init(device)
+        };
+
+        // Step sizes are sampled log-uniformly in [dt_min, dt_max], and the bias is set to
+        // their inverse softplus so that `softplus(dt_proj(0))` lands in that range.
+        let (ln_min, ln_max) = (
+            num_traits::Float::ln(self.dt_min),
+            num_traits::Float::ln(self.dt_max),
+        );
+        let dt = Tensor::<1>::random([d_inner], Distribution::Uniform(ln_min, ln_max), device)
+            .exp()
+            .clamp_min(1e-4);
+        let dt_bias = dt.clone() + (-(dt.neg().exp()) + 1.0).log();
+        let mut dt_proj = linear(dt_rank, d_inner, true);
+        dt_proj.bias = Some(Param::from_tensor(dt_bias));
+
+        // S4D-Real initialization: A[d, n] = -(n + 1).
+        let a = Tensor::<1, Int>::arange(1..self.d_state as i64 + 1, device)
+            .float()
+            .unsqueeze::<2>()
+            .repeat_dim(0, d_inner);
+
+        Mamba {
+            in_proj: linear(self.d_model, 2 * d_inner, self.bias),
+            conv1d: Conv1dConfig::new(d_inner, d_inner, self.d_conv)
+                .with_groups(d_inner)
+                .with_padding(PaddingConfig1d::Explicit(self.d_conv - 1, 0))
+                .with_bias(self.conv_bias)
+                .with_initializer(self.initializer.clone())
+                .init(device),
+            x_proj: linear(d_inner, dt_rank + 2 * self.d_state, false),
+            dt_proj,
+            a_log: Param::from_tensor(a.log()),
+            d: Param::from_tensor(Tensor::ones([d_inner], device)),
+            out_proj: linear(d_inner, self.d_model, self.bias),
+            d_state: self.d_state,
+            dt_rank,
+        }
+    }
+}
+
+impl Mamba {
+    /// Applies the forward pass on the input tensor.
+    ///
+    /// # Shapes
+    ///
+    /// - input: `[batch_size, seq_length, d_model]`
+    /// - output: `[batch_size, seq_length, d_model]`
+    #[hookable]
+    pub fn forward(&self, input: Tensor<3>) -> Tensor<3> {
+        let d_inner = self.d.dims()[0];
+        let xz = self.in_proj.forward(input);
+        let x = xz.clone().narrow(2, 0, d_inner);
+        let z = xz.narrow(2, d_inner, d_inner);
+
+        let x = self.conv1d.forward(x.swap_dims(1, 2)).swap_dims(1, 2);
+        let x = silu(x);
+
+        let x_dbl = self.x_proj.forward(x.clone());
+        let dt = x_dbl.clone().narrow(2, 0, self.dt_rank);
+        let b = x_dbl.clone().narrow(2, self.dt_rank, self.d_state);
+        let c = x_dbl.narrow(2, self.dt_rank + self.d_state, self.d_state);
+        let delta = softplus(self.dt_proj.forward(dt), 1.0);
+
+        let y = selective_scan(x.clone(), delta, self.a(), b, c);
+        let y = y + x * self.d.val().unsqueeze::<3>();
+
+        self.out_proj.forward(y * silu(z))
+    }
+
+    /// Creates a zeroed recurrent state for [step](Mamba::step).
+    pub fn init_state(&self, batch_size: usize, device: &Device) -> MambaState {
+        let [d_inner, _, d_conv] = self.conv1d.weight.shape().dims();
+
+        MambaState {
+            conv: Tensor::zeros([batch_size, d_inner, d_conv - 1], device),
+            ssm: Tensor::zeros([batch_size, d_inner, self.d_state], device),
+        }
+    }
+
+    /// Applies the block on a single time step, updating the recurrent state.
+    ///
+    /// The memory and compute of a step don't depend on the number of previous steps.
+    /// Running every step of a sequence from a [zeroed state](Mamba::init_state) gives the same
+    /// outputs as [forward](Mamba::forward).
+    ///
+    /// # Shapes
+    ///
+    /// - input: `[batch_size, d_model]`
+    /// - output: `[batch_size, d_model]`
+    pub fn step(&self, input: Tensor<2>, state: MambaState) -> (Tensor<2>, MambaState) {
+        let [d_inner, _, d_conv] = self.conv1d.weight.shape().dims();
+        let xz = self.in_proj.forward(input);
+        let x = xz.clone().narrow(1, 0, d_inner);
+        let z = xz.narrow(1, d_inner, d_inner);
+
+        // Causal depthwise convolution over the window of the last `d_conv` inputs.
+        let window = Tensor::cat(vec![state.conv, x.unsqueeze_dim(2)], 2);
+        let conv = window.clone().narrow(2, 1, d_conv - 1);
+        let weight = self.conv1d.weight.val().reshape([1, d_inner, d_conv]);
+        let mut x = (window * weight).sum_dim(2).squeeze_dim::<2>(2);
+        if let Some(bias) = &self.conv1d.bias {
+            x = x + bias.val().unsqueeze();
+        }
+        let x = silu(x);
+
+        let x_dbl = self.x_proj.forward(x.clone());
+        let dt = x_dbl.clone().narrow(1, 0, self.dt_rank);
+        let b = x_dbl.clone().narrow(1, self.dt_rank, self.d_state);
+        let c = x_dbl.narrow(1, self.dt_rank + self.d_state, self.d_state);
+        let delta = softplus(self.dt_proj.forward(dt), 1.0);
+
+        let decay = (delta.clone().unsqueeze_dim::<3>(2) * self.a().unsqueeze()).exp();
+        let input = (delta * x.clone()).unsqueeze_dim::<3>(2) * b.unsqueeze_dim(1);
+        let ssm = state.ssm * decay + input;
+        let y = (ssm.clone() * c.unsqueeze_dim(1))
+            .sum_dim(2)
+            .squeeze_dim::<2>(2);
+        let y = y + x * self.d.val().unsqueeze();
+
+        let output = self.out_proj.forward(y * silu(z));
+        (output, MambaState { conv, ssm })
+    }
+
+    /// The state matrix, `[d_inner, d_state]`.
+    fn a(&self) -> Tensor<2> {
+        -self.a_log.val().exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn::tensor::{TensorData, Tolerance};
+
+    #[test]
+    fn initializer_default() {
+        let device = Default::default();
+        let config = MambaConfig::new(32);
+        let mamba = config.init(&device);
+
+        assert_eq!(mamba.in_proj.weight.shape().dims(), [32, 128]);
+        assert_eq!(mamba.conv1d.weight.shape().dims(), [64, 1, 4]);
+        assert_eq!(mamba.x_proj.weight.shape().dims(), [64, 2 + 2 * 16]);
+        assert_eq!(mamba.dt_proj.weight.shape().dims(), [2, 64]);
+        assert_eq!(mamba.out_proj.weight.shape().dims(), [64, 32]);
+
+        // The initial step sizes stay within [dt_min, dt_max].
+        let delta = softplus(mamba.dt_proj.bias.unwrap().val(), 1.0);
+        assert!(delta.clone().min().into_scalar::<f32>() >= 0.001 - 1e-6);
+        assert!(delta.max().into_scalar::<f32>() <= 0.1 + 1e-6);
+        mamba
+            .a_log
+            .val()
+            .exp()
+            .slice_dim(0, 0..1)
+            .into_data()
+            .assert_approx_eq::<f32>(
+                &TensorData::from([[
+                    1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0,
+                    15.0, 16.0,
+                ]]),
+                Tolerance::default(),
+            );
+    }
+
+    #[test]
+    fn forward_shape() {
+        let device = Default::default();
+        let mamba = MambaConfig::new(8).with_d_state(4).init(&device);
+        let input = Tensor::<3>::random([2, 5, 8], Distribution::Default, &device);
+
+        assert_eq!(mamba.forward(input).dims(), [2, 5, 8]);
+    }
+
+    #[test]
+    fn step_matches_forward() {
+        let device = Default::default();
+        let mamba = MambaConfig::new(8)
+            .with_d_state(4)
+            .with_d_conv(3)
+            .with_conv_bias(true)
+            .init(&device);
+        let input = Tensor::<3>::random([2, 6, 8], Distribution::Default, &device);
+
+        let expected = mamba.forward(input.clone());
+
+        let mut state = mamba.init_state(2, &device);
+        let mut outputs = alloc::vec::Vec::new();
+        for t in 0..6 {
+            let (output, next) = mamba.step(input.clone().narrow(1, t, 1).squeeze_dim(1), state);
+            outputs.push(output.unsqueeze_dim(1));
+            state = next;
+        }
+
+        Tensor::cat(outputs, 1)
+            .into_data()
+            .assert_approx_eq::<f32>(&expected.into_data(), Tolerance::rel_abs(1e-4, 1e-4));
+    }
+
+    #[test]
+    fn display() {
+        let config = MambaConfig::new(16);
+        let mamba = config.init(&Default::default());
+
+        assert_eq!(
+            alloc::format!("{mamba}"),
+            "Mamba {d_model: 16, d_inner: 32, d_state: 16, d_conv: 4, dt_rank: 1, params: 3360}"
+        );
+    }
+}
@@ -7,6 +7,9 @@ pub mod cache;
 /// Convolution module
 pub mod conv;
 
+/// Text generation module
+pub mod generation;
+
 /// Pooling module
 pub mod pool;
 
@@ -22,10 +25,12 @@ mod dropout;
 mod embedding;
 mod fold;
 mod linear;
+mod mamba;
 mod noise;
 mod pairwise_distance;
 mod pixel_shuffle;
 mod pos_encoding;
+mod pruning;
 mod rnn;
 mod rope_encoding;
 mod unfold;
@@ -40,6 +45,7 @@ pub use embedding::*;
 pub use fold::*;
 pub use identity::*;
 pub use linear::*;
+pub use mamba::*;
 pub use noise::*;
 pub use pairwise_distance::*;
 pub use pixel_shuffle::*;
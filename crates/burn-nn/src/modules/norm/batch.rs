@@ -1,6 +1,7 @@
 use burn_core as burn;
 
 use burn::module::Initializer;
+use burn::module::hookable;
 use burn::module::{Content, DisplaySettings, ModuleDisplay};
 use burn::tensor::{Device, Tensor};
 use burn::{
@@ -89,6 +90,7 @@ impl BatchNorm {
     /// # Panics
     ///
     /// This function will panic if the input tensor has rank < 2.
+    #[hookable]
     pub fn forward<const D: usize>(&self, input: Tensor<D>) -> Tensor<D> {
         // Should be move to a compilation error when const generic support that kind of
         // validation. https://github.com/rust-lang/rust/issues/76560
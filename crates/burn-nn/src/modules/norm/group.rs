@@ -1,4 +1,5 @@
 use burn::module::Initializer;
+use burn::module::hookable;
 use burn_core as burn;
 
 use burn::config::Config;
@@ -111,6 +112,7 @@ impl GroupNorm {
     ///
     /// - input: `[batch_size, num_channels, *]`
     /// - output: `[batch_size, num_channels, *]`
+    #[hookable]
     pub fn forward<const D: usize>(&self, input: Tensor<D>) -> Tensor<D> {
         if input.shape()[1] != self.num_channels {
             panic!(
@@ -1,3 +1,4 @@
+use burn::module::hookable;
 use burn::tensor::DType;
 
 use burn_core as burn;
@@ -68,6 +69,7 @@ impl RmsNorm {
     ///
     /// - input: `[..., any, d_model]`
     /// - output: `[..., any, d_model]`
+    #[hookable]
     pub fn forward<const D: usize>(&self, x: Tensor<D>) -> Tensor<D> {
         // Calculate the root-mean-square norm of the input tensor along the last dimension
         let dtype = x.dtype();
@@ -0,0 +1,32 @@
+use burn_core as burn;
+
+use alloc::vec::Vec;
+use burn::module::Param;
+use burn::tensor::{Device, Int, Tensor, TensorData};
+
+/// The indices of the non-zero entries, i.e. the channels left by structured pruning.
+pub(crate) fn nonzero_indices(magnitude: Tensor<1>) -> Vec<usize> {
+    magnitude
+        .into_data()
+        .iter::<f32>()
+        .enumerate()
+        .filter_map(|(index, value)| (value != 0.0).then_some(index))
+        .collect()
+}
+
+/// The indices as a tensor, to [select](Tensor::select) the kept channels.
+pub(crate) fn index_tensor(indices: &[usize], device: &Device) -> Tensor<1, Int> {
+    let indices: Vec<i64> = indices.iter().map(|&index| index as i64).collect();
+    let num_indices = indices.len();
+    Tensor::from_data(TensorData::new(indices, [num_indices]), device)
+}
+
+/// Replace the value of the parameter, dropping its reparameterization but keeping its id and
+/// mappers.
+pub(crate) fn with_value<const D: usize>(
+    param: Param<Tensor<D>>,
+    value: Tensor<D>,
+) -> Param<Tensor<D>> {
+    let (id, _, mapper) = param.consume();
+    Param::from_mapped_value(id, value, mapper)
+}
@@ -4,10 +4,10 @@ use alloc::vec::Vec;
 
 use burn::config::Config;
 use burn::module::{Content, DisplaySettings, Initializer, Module, ModuleDisplay};
-use burn::tensor::{Bool, Device, Tensor};
+use burn::tensor::{Bool, Device, Int, Tensor};
 
 use crate::activation::ActivationConfig;
-use crate::cache::TensorCache;
+use crate::cache::{PagedKvCache, PagedKvCacheConfig, TensorCache};
 use crate::{
     Dropout, DropoutConfig, LayerNorm, LayerNormConfig,
     attention::{MhaCache, MhaInput, MultiHeadAttention, MultiHeadAttentionConfig},
@@ -226,6 +226,40 @@ impl TransformerDecoderLayerAutoregressiveCache {
             norm_3: TensorCache::empty(),
         }
     }
+
+    /// Create an empty cache for sequences of at most `capacity` positions, written in place.
+    ///
+    /// See [MhaCache::preallocated].
+    pub fn preallocated(capacity: usize) -> Self {
+        Self {
+            cross_attn: MhaCache::preallocated_cross_attention(capacity),
+            self_attn: MhaCache::preallocated(capacity),
+            pwff: TensorCache::preallocated(capacity),
+            norm_1: TensorCache::preallocated(capacity),
+            norm_2: TensorCache::preallocated(capacity),
+            norm_3: TensorCache::preallocated(capacity),
+        }
+    }
+
+    /// Clear the cache to decode a new sequence.
+    pub fn reset(&mut self) {
+        self.cross_attn.reset();
+        self.self_attn.reset();
+        self.pwff.reset();
+        self.norm_1.reset();
+        self.norm_2.reset();
+        self.norm_3.reset();
+    }
+
+    /// Reorder the cached batch: entry `i` becomes the entry `indices[i]` of the current batch.
+    pub fn reorder(&mut self, indices: Tensor<1, Int>) {
+        self.cross_attn.reorder(indices.clone());
+        self.self_attn.reorder(indices.clone());
+        self.pwff.reorder(indices.clone());
+        self.norm_1.reorder(indices.clone());
+        self.norm_2.reorder(indices.clone());
+        self.norm_3.reorder(indices);
+    }
 }
 
 /// Autoregressive cache for the [Transformer Decoder](TransformerDecoder) layer.
@@ -243,6 +277,71 @@ impl TransformerDecoderAutoregressiveCache {
                 .collect(),
         }
     }
+
+    fn preallocated(num_layers: usize, capacity: usize) -> Self {
+        Self {
+            layers: (0..num_layers)
+                .map(|_| TransformerDecoderLayerAutoregressiveCache::preallocated(capacity))
+                .collect(),
+        }
+    }
+
+    /// Clear the cache to decode a new sequence, keeping the memory of a
+    /// [preallocated](TransformerDecoder::new_preallocated_cache) cache.
+    pub fn reset(&mut self) {
+        self.layers.iter_mut().for_each(|layer| layer.reset());
+    }
+
+    /// Reorder the cached batch: entry `i` becomes the entry `indices[i]` of the current batch.
+    ///
+    /// Used by [beam search](crate::generation::GenerationModel::reorder), where beams are
+    /// duplicated and dropped between steps.
+    pub fn reorder(&mut self, indices: Tensor<1, Int>) {
+        self.layers
+            .iter_mut()
+            .for_each(|layer| layer.reorder(indices.clone()));
+    }
+}
+
+/// Paged key-value cache for the [Transformer Decoder](TransformerDecoder), with one
+/// [paged cache](PagedKvCache) per layer.
+///
+/// To be used when serving a batch of sequences of different lengths, see
+/// [forward_paged](TransformerDecoder::forward_paged).
+pub struct TransformerDecoderPagedCache {
+    layers: Vec<PagedKvCache>,
+}
+
+impl TransformerDecoderPagedCache {
+    /// Start a new, empty sequence in every layer and return its id.
+    ///
+    /// Ids of removed sequences are reused.
+    pub fn add_sequence(&mut self) -> usize {
+        let mut ids = self.layers.iter_mut().map(PagedKvCache::add_sequence);
+        let id = ids.next().unwrap_or(0);
+        // The layers are always updated together, so they hand out the same ids.
+        debug_assert!(ids.all(|other| other == id));
+        id
+    }
+
+    /// End a sequence, returning its blocks to the pool of every layer.
+    pub fn remove_sequence(&mut self, id: usize) {
+        self.layers
+            .iter_mut()
+            .for_each(|layer| layer.remove_sequence(id));
+    }
+
+    /// The number of cached positions of a sequence.
+    pub fn len(&self, id: usize) -> usize {
+        self.layers.first().map_or(0, |layer| layer.len(id))
+    }
+
+    /// Whether `num_positions` more positions fit in the cache for the sequence.
+    pub fn can_append(&self, id: usize, num_positions: usize) -> bool {
+        self.layers
+            .iter()
+            .all(|layer| layer.can_append(id, num_positions))
+    }
 }
 
 impl TransformerDecoderLayer {
@@ -288,7 +387,30 @@ impl TransformerDecoderLayer {
     }
 
     /// Applies the TransformerDecoder forward pass to the input tensor.
-    pub fn forward(&self, mut input: TransformerDecoderInput) -> TransformerDecoderInput {
+    pub fn forward(&self, input: TransformerDecoderInput) -> TransformerDecoderInput {
+        self.forward_with(input, |input| self.self_attn.forward(input).context)
+    }
+
+    /// Applies the forward pass on the new positions of a batch of sequences, using a
+    /// [paged cache](PagedKvCache) for the self-attention keys and values of the previous ones.
+    ///
+    /// See [MultiHeadAttention::forward_paged].
+    pub fn forward_paged(
+        &self,
+        input: TransformerDecoderInput,
+        cache: &mut PagedKvCache,
+        sequences: &[usize],
+    ) -> TransformerDecoderInput {
+        self.forward_with(input, |input| {
+            self.self_attn.forward_paged(input, cache, sequences)
+        })
+    }
+
+    fn forward_with(
+        &self,
+        mut input: TransformerDecoderInput,
+        self_attn: impl FnOnce(MhaInput) -> Tensor<3>,
+    ) -> TransformerDecoderInput {
         // Self attention residual path.
         let x = input.target;
         let mut residual_path = x.clone();
@@ -306,7 +428,7 @@ impl TransformerDecoderLayer {
         if let Some(mask_attn) = &input.target_mask_attn {
             self_attn_input = self_attn_input.mask_attn(mask_attn.clone());
         }
-        let residual_path = self.self_attn.forward(self_attn_input).context;
+        let residual_path = self_attn(self_attn_input);
 
         let residual_path = self.dropout.forward(residual_path);
         let mut x = x + residual_path;
@@ -477,10 +599,61 @@ impl TransformerDecoder {
 
         input.target
     }
+
+    /// Applies the forward pass on the new positions of a batch of sequences, using a
+    /// [paged cache](TransformerDecoderPagedCache) for the previous ones.
+    ///
+    /// Each batch entry continues the cache sequence at the same index in `sequences`, which can
+    /// all have different lengths, so the target holds only the new positions of each sequence.
+    /// The target masks are built from the cache, those of the input are ignored; the memory
+    /// masks apply as usual.
+    ///
+    /// # Shapes
+    ///
+    /// - target: `[batch_size, seq_length_1, d_model]`
+    /// - memory: `[batch_size, seq_length_2, d_model]`
+    /// - output: `[batch_size, seq_length_1, d_model]`
+    pub fn forward_paged(
+        &self,
+        mut input: TransformerDecoderInput,
+        cache: &mut TransformerDecoderPagedCache,
+        sequences: &[usize],
+    ) -> Tensor<3> {
+        for (layer, cache) in self.layers.iter().zip(cache.layers.iter_mut()) {
+            input = layer.forward_paged(input, cache, sequences);
+        }
+
+        input.target
+    }
+
     /// Create an empty autoregressive cache.
     pub fn new_autoregressive_cache(&self) -> TransformerDecoderAutoregressiveCache {
         TransformerDecoderAutoregressiveCache::empty(self.layers.len())
     }
+
+    /// Create an empty autoregressive cache for sequences of at most `capacity` positions.
+    ///
+    /// The memory is allocated on the first step, then each step writes the new positions in
+    /// place instead of concatenating them to the whole history.
+    pub fn new_preallocated_cache(&self, capacity: usize) -> TransformerDecoderAutoregressiveCache {
+        TransformerDecoderAutoregressiveCache::preallocated(self.layers.len(), capacity)
+    }
+
+    /// Create an empty paged cache with a pool of `num_blocks` blocks of `block_size` positions
+    /// per layer, allocated up front.
+    pub fn new_paged_cache(
+        &self,
+        num_blocks: usize,
+        block_size: usize,
+        device: &Device,
+    ) -> TransformerDecoderPagedCache {
+        let config = PagedKvCacheConfig::new(num_blocks, self.n_heads, self.d_model / self.n_heads)
+            .with_block_size(block_size);
+
+        TransformerDecoderPagedCache {
+            layers: self.layers.iter().map(|_| config.init(device)).collect(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -488,7 +661,7 @@ mod tests {
     use super::*;
     use crate::attention::generate_autoregressive_mask;
 
-    use burn::tensor::Tolerance;
+    use burn::tensor::{Distribution, Tolerance};
     type FT = f32;
 
     #[test]
@@ -532,29 +705,86 @@ mod tests {
         // Normal forward using masking.
         let output_1 = transformer.forward(input);
 
-        // Forward using the autoregressive cache.
-        let mut output_2 = Vec::new();
-        let mut cache = transformer.new_autoregressive_cache();
-
-        for i in 1..seq_length + 1 {
-            let target = target.clone().slice([0..batch_size, 0..i, 0..d_model]);
-
-            let mask_attn = generate_autoregressive_mask(batch_size, i, &target.device());
-            let input = TransformerDecoderInput::new(target.clone(), memory.clone())
-                .target_mask_attn(mask_attn);
-            let next_tok = transformer // Greedy sampling
-                .forward_autoregressive_inference(input, &mut cache)
-                .slice([0..batch_size, i - 1..i, 0..d_model]);
-            output_2.push(next_tok);
+        // Forward using the autoregressive caches, growing or preallocated.
+        let caches = [
+            transformer.new_autoregressive_cache(),
+            transformer.new_preallocated_cache(seq_length),
+        ];
+        for mut cache in caches {
+            let mut output_2 = Vec::new();
+
+            for i in 1..seq_length + 1 {
+                let target = target.clone().slice([0..batch_size, 0..i, 0..d_model]);
+
+                let mask_attn = generate_autoregressive_mask(batch_size, i, &target.device());
+                let input = TransformerDecoderInput::new(target.clone(), memory.clone())
+                    .target_mask_attn(mask_attn);
+                let next_tok =
+                    transformer // Greedy sampling
+                        .forward_autoregressive_inference(input, &mut cache)
+                        .slice([0..batch_size, i - 1..i, 0..d_model]);
+                output_2.push(next_tok);
+            }
+
+            let output_2 = Tensor::cat(output_2, 1);
+
+            // Should produce the same tokens.
+            let tolerance = Tolerance::rel_abs(5e-3, 1e-4);
+            output_1
+                .clone()
+                .into_data()
+                .assert_approx_eq::<FT>(&output_2.into_data(), tolerance);
         }
+    }
 
-        let output_2 = Tensor::cat(output_2, 1);
-
-        // Should produce the same tokens.
-        let tolerance = Tolerance::rel_abs(5e-3, 1e-4);
-        output_1
-            .into_data()
-            .assert_approx_eq::<FT>(&output_2.into_data(), tolerance);
+    #[test]
+    fn test_paged_cache_should_match_preallocated_cache() {
+        for norm_first in [false, true] {
+            let device = Device::default();
+            device.seed(0);
+            let config = TransformerDecoderConfig::new(12, 24, 2, 3).with_norm_first(norm_first);
+            let [batch_size, seq_length, d_model] = [3, 5, config.d_model];
+            let transformer = config.init(&device);
+
+            let memory =
+                Tensor::<3>::random([batch_size, 4, d_model], Distribution::Default, &device);
+            let target = Tensor::<3>::random(
+                [batch_size, seq_length, d_model],
+                Distribution::Default,
+                &device,
+            );
+
+            // One position at a time with the preallocated cache.
+            let mut cache = transformer.new_preallocated_cache(seq_length);
+            let expected = (1..seq_length + 1)
+                .map(|i| {
+                    let target = target.clone().slice([0..batch_size, 0..i]);
+                    let mask_attn = generate_autoregressive_mask(batch_size, i, &device);
+                    let input = TransformerDecoderInput::new(target, memory.clone())
+                        .target_mask_attn(mask_attn);
+                    transformer
+                        .forward_autoregressive_inference(input, &mut cache)
+                        .slice([0..batch_size, i - 1..i])
+                })
+                .collect();
+            let expected = Tensor::cat(expected, 1);
+
+            // A two-position prompt then one position at a time with the paged cache, whose
+            // blocks of 2 positions split the history across several blocks.
+            let mut cache = transformer.new_paged_cache(3 * batch_size, 2, &device);
+            let ids: Vec<usize> = (0..batch_size).map(|_| cache.add_sequence()).collect();
+            let mut output = Vec::new();
+            for range in [0..2, 2..3, 3..4, 4..5] {
+                let target = target.clone().slice([0..batch_size, range]);
+                let input = TransformerDecoderInput::new(target, memory.clone());
+                output.push(transformer.forward_paged(input, &mut cache, &ids));
+            }
+            assert!(ids.iter().all(|id| cache.len(*id) == seq_length));
+
+            Tensor::cat(output, 1)
+                .into_data()
+                .assert_approx_eq::<FT>(&expected.into_data(), Tolerance::rel_abs(5e-3, 1e-4));
+        }
     }
 
     #[test]
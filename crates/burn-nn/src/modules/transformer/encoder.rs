@@ -10,6 +10,7 @@ use crate::{
     cache::TensorCache,
 };
 use burn::config::Config;
+use burn::module::hookable;
 use burn::module::{Content, DisplaySettings, Initializer, Module, ModuleDisplay};
 use burn::tensor::{Bool, Device, Tensor};
 
@@ -163,6 +164,7 @@ impl TransformerEncoder {
     ///
     /// - tensor: `[batch_size, seq_length, d_model]`
     /// - output: `[batch_size, seq_length, d_model]`
+    #[hookable]
     pub fn forward(&self, input: TransformerEncoderInput) -> Tensor<3> {
         let mut x = input.tensor;
 
@@ -261,6 +263,7 @@ impl TransformerEncoderLayer {
     ///
     /// - input: `[batch_size, seq_length, d_model]`
     /// - output: `[batch_size, seq_length, d_model]`
+    #[hookable]
     pub fn forward(
         &self,
         input: Tensor<3>,
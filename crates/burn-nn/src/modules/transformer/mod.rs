@@ -1,7 +1,9 @@
 mod decoder;
 mod encoder;
+mod moe;
 mod pwff;
 
 pub use decoder::*;
 pub use encoder::*;
+pub use moe::*;
 pub use pwff::*;
@@ -0,0 +1,429 @@
+use burn_core as burn;
+
+use crate::activation::{Activation, ActivationConfig};
+use crate::{Linear, LinearConfig};
+use burn::config::Config;
+use burn::module::{Content, DisplaySettings, Initializer, Module, ModuleDisplay, Param};
+use burn::tensor::activation::softmax;
+use burn::tensor::{Device, IndexingUpdateOp, Int, Tensor};
+
+/// Configuration to create a [mixture of experts](MixtureOfExperts) layer using the
+/// [init function](MixtureOfExpertsConfig::init).
+#[derive(Config, Debug)]
+pub struct MixtureOfExpertsConfig {
+    /// The size of the input and output features.
+    pub d_model: usize,
+    /// The size of the hidden inner features of each expert.
+    pub d_ff: usize,
+    /// The number of experts.
+    pub num_experts: usize,
+    /// The number of experts each token is routed to. Default: 2
+    #[config(default = 2)]
+    pub top_k: usize,
+    /// The number of tokens each expert processes, as a factor of an even split of the routed
+    /// tokens between the experts. Tokens routed to a full expert are dropped for that expert.
+    /// When `None`, no token is ever dropped. Default: 1.25
+    #[config(default = "Some(1.25)")]
+    pub capacity_factor: Option<f64>,
+    /// If the gates of the top-k experts of each token are normalized to sum to one.
+    /// Default: true
+    #[config(default = true)]
+    pub normalize_gates: bool,
+    /// If the experts are gated linear units, `act(x W_gate) * (x W_inner)`, as in Mixtral.
+    /// Default: false
+    #[config(default = false)]
+    pub gated: bool,
+    /// The weight of the load-balancing loss in the [auxiliary loss](MixtureOfExpertsOutput::aux_loss).
+    /// Default: 0.01
+    #[config(default = 0.01)]
+    pub load_balancing_coefficient: f64,
+    /// The weight of the router z-loss in the [auxiliary loss](MixtureOfExpertsOutput::aux_loss).
+    /// Default: 0.001
+    #[config(default = 0.001)]
+    pub z_loss_coefficient: f64,
+    /// The type of function used to initialize neural network parameters
+    #[config(
+        default = "Initializer::KaimingUniform{gain:1.0/num_traits::Float::sqrt(3.0), fan_out_only:false}"
+    )]
+    pub initializer: Initializer,
+    /// The activation function used by the experts. Default: Gelu
+    #[config(default = "ActivationConfig::Gelu")]
+    pub activation: ActivationConfig,
+}
+
+/// A sparse mixture of experts layer, replacing the
+/// [position-wise feed-forward](super::PositionWiseFeedForward) layer of a transformer, as in the
+/// [Switch Transformer](https://arxiv.org/abs/2101.03961) and
+/// [Mixtral](https://arxiv.org/abs/2401.04088).
+///
+/// A learned router sends each token to its `top_k` most likely experts, and the output is the
+/// sum of their outputs weighted by the router's gates.
+///
+/// Each expert processes at most `capacity` tokens, in the order of the router's preferences:
+/// every token's first choice comes before any second choice. A token routed to a full expert
+/// gets nothing from it, and a token dropped by all its experts gets a zero output, to be
+/// carried by the residual connection.
+///
+/// The tokens are dispatched to a `[num_experts, capacity, d_model]` buffer so every expert is
+/// computed at once with batched matrix multiplications, instead of one at a time.
+///
+/// Should be created using [MixtureOfExpertsConfig].
+#[derive(Module, Debug)]
+#[module(custom_display)]
+pub struct MixtureOfExperts {
+    /// The router, with `d_model` input features and `num_experts` output features.
+    pub router: Linear,
+    /// The inner weights of the experts, `[num_experts, d_model, d_ff]`.
+    pub weight_inner: Param<Tensor<3>>,
+    /// The gate weights of gated experts, `[num_experts, d_model, d_ff]`.
+    pub weight_gate: Option<Param<Tensor<3>>>,
+    /// The outer weights of the experts, `[num_experts, d_ff, d_model]`.
+    pub weight_outer: Param<Tensor<3>>,
+    /// Activation function.
+    pub activation: Activation,
+    /// The number of experts each token is routed to.
+    pub top_k: usize,
+    /// The capacity of each expert, as a factor of an even split of the tokens.
+    pub capacity_factor: Option<f64>,
+    /// If the top-k gates are normalized.
+    pub normalize_gates: bool,
+    /// The weight of the load-balancing loss.
+    pub load_balancing_coefficient: f64,
+    /// The weight of the router z-loss.
+    pub z_loss_coefficient: f64,
+}
+
+/// The output of a [mixture of experts](MixtureOfExperts) layer.
+pub struct MixtureOfExpertsOutput<const D: usize> {
+    /// The output, with the shape of the input.
+    pub output: Tensor<D>,
+    /// The weighted sum of the auxiliary losses, to be added to the training loss, `[1]`.
+    pub aux_loss: Tensor<1>,
+    /// The load-balancing loss of the Switch Transformer, `[1]`. It is 1 when the tokens are
+    /// evenly routed, and grows as the router favors some experts.
+    pub load_balancing_loss: Tensor<1>,
+    /// The router z-loss of [ST-MoE](https://arxiv.org/abs/2202.08906), `[1]`, the mean squared
+    /// log-sum-exp of the router logits, which keeps them small.
+    pub router_z_loss: Tensor<1>,
+}
+
+impl ModuleDisplay for MixtureOfExperts {
+    fn custom_settings(&self) -> Option<DisplaySettings> {
+        DisplaySettings::new()
+            .with_new_line_after_attribute(false)
+            .optional()
+    }
+
+    fn custom_content(&self, content: Content) -> Option<Content> {
+        let [num_experts, d_model, d_ff] = self.weight_inner.shape().dims();
+
+        content
+            .add("d_model", &d_model)
+            .add("d_ff", &d_ff)
+            .add("num_experts", &num_experts)
+            .add("top_k", &self.top_k)
+            .optional()
+    }
+}
+
+impl MixtureOfExpertsConfig {
+    /// Initialize a new [mixture of experts](MixtureOfExperts) layer.
+    pub fn init(&self, device: &Device) -> MixtureOfExperts {
+        assert!(
+            0 < self.top_k && self.top_k <= self.num_experts,
+            "top_k should be between 1 and the number of experts ({}), got {}",
+            self.num_experts,
+            self.top_k
+        );
+        if let Some(factor) = self.capacity_factor {
+            assert!(factor > 0.0, "The capacity factor should be positive");
+        }
+
+        let inner = || {
+            self.initializer.init_with(
+                [self.num_experts, self.d_model, self.d_ff],
+                Some(self.d_model),
+                Some(self.d_ff),
+                device,
+            )
+        };
+
+        MixtureOfExperts {
+            router: LinearConfig::new(self.d_model, self.num_experts)
+                .with_bias(false)
+                .with_initializer(self.initializer.clone())
+                .init(device),
+            weight_inner: inner(),
+            weight_gate: self.gated.then(inner),
+            weight_outer: self.initializer.init_with(
+                [self.num_experts, self.d_ff, self.d_model],
+                Some(self.d_ff),
+                Some(self.d_model),
+                device,
+            ),
+            activation: self.activation.init(device),
+            top_k: self.top_k,
+            capacity_factor: self.capacity_factor,
+            normalize_gates: self.normalize_gates,
+            load_balancing_coefficient: self.load_balancing_coefficient,
+            z_loss_coefficient: self.z_loss_coefficient,
+        }
+    }
+}
+
+impl MixtureOfExperts {
+    /// Applies the forward pass on the input tensor.
+    ///
+    /// # Shapes
+    ///
+    /// - input: `[..., d_model]`
+    /// - output: `[..., d_model]`
+    pub fn forward<const D: usize>(&self, input: Tensor<D>) -> MixtureOfExpertsOutput<D> {
+        let shape = input.shape();
+        let [num_experts, d_model, _] = self.weight_inner.shape().dims();
+        let k = self.top_k;
+        let num_tokens = shape.num_elements() / d_model;
+        let x = input.reshape([num_tokens, d_model]);
+        let device = x.device();
+
+        let logits = self.router.forward(x.clone());
+        let probs = softmax(logits.clone(), 1);
+        let (gates, experts) = probs.clone().topk_with_indices(k, 1);
+        let gates = if self.normalize_gates {
+            gates.clone() / gates.sum_dim(1)
+        } else {
+            gates
+        };
+
+        // Slots are ordered choice-major, so every first choice is served before any second one.
+        let experts = experts.transpose().reshape([num_tokens * k]);
+        let gates = gates.transpose().reshape([num_tokens * k, 1]);
+        let capacity = self.capacity(num_tokens);
+
+        // The position of each slot in its expert's buffer, from the running count of the
+        // slots routed to the same expert.
+        let one_hot: Tensor<2, Int> = experts.clone().one_hot(num_experts);
+        let positions = (one_hot.clone().cumsum(0) * one_hot.clone())
+            .sum_dim(1)
+            .reshape([num_tokens * k])
+            .sub_scalar(1);
+        let dropped = positions.clone().greater_equal_elem(capacity as i64);
+        // Dropped slots go to an extra row of zeros, removed before the experts run.
+        let trash = (num_experts * capacity) as i64;
+        let slots = (experts.mul_scalar(capacity as i64) + positions).mask_fill(dropped, trash);
+
+        let tokens = x
+            .unsqueeze_dim::<3>(0)
+            .expand([k, num_tokens, d_model])
+            .reshape([num_tokens * k, d_model]);
+        let buffer = Tensor::<2>::zeros([num_experts * capacity + 1, d_model], &device)
+            .select_assign(0, slots.clone(), tokens, IndexingUpdateOp::Add)
+            .slice([0..num_experts * capacity])
+            .reshape([num_experts, capacity, d_model]);
+
+        let hidden = buffer.clone().matmul(self.weight_inner.val());
+        let hidden = match &self.weight_gate {
+            Some(gate) => self.activation.forward(buffer.matmul(gate.val())) * hidden,
+            None => self.activation.forward(hidden),
+        };
+        let expert_outputs = hidden
+            .matmul(self.weight_outer.val())
+            .reshape([num_experts * capacity, d_model]);
+
+        let expert_outputs = Tensor::cat(
+            alloc::vec![expert_outputs, Tensor::zeros([1, d_model], &device)],
+            0,
+        );
+        let output = (expert_outputs.select(0, slots) * gates)
+            .reshape([k, num_tokens, d_model])
+            .sum_dim(0)
+            .reshape(shape);
+
+        let load_balancing_loss = Self::load_balancing_loss(probs, one_hot, num_tokens * k);
+        let router_z_loss = Self::router_z_loss(logits);
+        let aux_loss = load_balancing_loss
+            .clone()
+            .mul_scalar(self.load_balancing_coefficient)
+            + router_z_loss.clone().mul_scalar(self.z_loss_coefficient);
+
+        MixtureOfExpertsOutput {
+            output,
+            aux_loss,
+            load_balancing_loss,
+            router_z_loss,
+        }
+    }
+
+    /// The number of tokens each expert processes in a batch of `num_tokens` tokens.
+    pub fn capacity(&self, num_tokens: usize) -> usize {
+        let [num_experts, _, _] = self.weight_inner.shape().dims();
+        match self.capacity_factor {
+            Some(factor) => {
+                let even = (num_tokens * self.top_k) as f64 / num_experts as f64;
+                let capacity = num_traits::Float::ceil(even * factor) as usize;
+                capacity.clamp(1, num_tokens.max(1))
+            }
+            None => num_tokens.max(1),
+        }
+    }
+
+    /// `num_experts * sum(fraction of the slots routed to e * mean probability of e)`
+    fn load_balancing_loss(
+        probs: Tensor<2>,
+        one_hot: Tensor<2, Int>,
+        num_slots: usize,
+    ) -> Tensor<1> {
+        let [_, num_experts] = probs.dims();
+        let fractions = one_hot.sum_dim(0).float().div_scalar(num_slots as f64);
+        let mean_probs = probs.mean_dim(0);
+
+        (fractions * mean_probs)
+            .sum()
+            .mul_scalar(num_experts as f64)
+    }
+
+    /// `mean(logsumexp(logits)^2)`
+    fn router_z_loss(logits: Tensor<2>) -> Tensor<1> {
+        let max = logits.clone().max_dim(1).detach();
+        let log_sum_exp = (logits - max.clone()).exp().sum_dim(1).log() + max;
+
+        log_sum_exp.powi_scalar(2).mean()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use burn::tensor::{Distribution, TensorData, Tolerance};
+
+    fn config() -> MixtureOfExpertsConfig {
+        MixtureOfExpertsConfig::new(4, 8, 3).with_activation(ActivationConfig::Relu)
+    }
+
+    /// A dense reference: every expert runs on every token.
+    fn expert_outputs(moe: &MixtureOfExperts, x: Tensor<2>) -> Vec<Tensor<2>> {
+        let [num_experts, d_model, d_ff] = moe.weight_inner.shape().dims();
+        (0..num_experts)
+            .map(|e| {
+                let inner = moe
+                    .weight_inner
+                    .val()
+                    .slice([e..e + 1])
+                    .reshape([d_model, d_ff]);
+                let outer = moe
+                    .weight_outer
+                    .val()
+                    .slice([e..e + 1])
+                    .reshape([d_ff, d_model]);
+                moe.activation
+                    .forward(x.clone().matmul(inner))
+                    .matmul(outer)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn all_experts_without_capacity_is_the_dense_mixture() {
+        let device = Default::default();
+        let moe = config()
+            .with_top_k(3)
+            .with_capacity_factor(None)
+            .init(&device);
+        let input = Tensor::<3>::random([2, 5, 4], Distribution::Default, &device);
+
+        let output = moe.forward(input.clone()).output;
+
+        let x = input.reshape([10, 4]);
+        let probs = softmax(moe.router.forward(x.clone()), 1);
+        let expected = expert_outputs(&moe, x)
+            .into_iter()
+            .enumerate()
+            .map(|(e, out)| out * probs.clone().slice([0..10, e..e + 1]))
+            .reduce(|a, b| a + b)
+            .unwrap()
+            .reshape([2, 5, 4]);
+        output
+            .into_data()
+            .assert_approx_eq::<f32>(&expected.into_data(), Tolerance::default());
+    }
+
+    #[test]
+    fn full_experts_drop_tokens() {
+        let device = Default::default();
+        let mut moe = config()
+            .with_top_k(1)
+            .with_capacity_factor(Some(0.5))
+            .init(&device);
+        // Every token prefers expert 0.
+        let weight = Tensor::from_data(
+            TensorData::new(alloc::vec![1.0f32, 0.0, 0.0].repeat(4), [4, 3]),
+            &device,
+        );
+        moe.router.weight = Param::from_tensor(weight);
+        let input = Tensor::<2>::random([6, 4], Distribution::Uniform(0.5, 1.0), &device);
+
+        // ceil(0.5 * 6 tokens / 3 experts) = 1 token per expert.
+        assert_eq!(moe.capacity(6), 1);
+        let output = moe.forward(input.clone()).output;
+
+        let expected = expert_outputs(&moe, input.slice([0..1])).remove(0);
+        output
+            .clone()
+            .slice([0..1])
+            .into_data()
+            .assert_approx_eq::<f32>(&expected.into_data(), Tolerance::default());
+        output
+            .slice([1..6])
+            .into_data()
+            .assert_eq(&TensorData::zeros::<f32, _>([5, 4]), false);
+    }
+
+    #[test]
+    fn even_routing_has_unit_load_balancing_loss() {
+        let device = Default::default();
+        let mut moe = config().init(&device);
+        moe.router.weight = Param::from_tensor(Tensor::zeros([4, 3], &device));
+        let input = Tensor::<2>::random([6, 4], Distribution::Default, &device);
+
+        let output = moe.forward(input);
+
+        // Uniform probabilities: the loss is 3 * sum(fraction_e * 1/3) = 1.
+        output
+            .load_balancing_loss
+            .into_data()
+            .assert_approx_eq::<f32>(&TensorData::from([1.0]), Tolerance::default());
+        // logsumexp of 3 zeros is ln(3).
+        let ln_3 = 1.098_612_3;
+        output
+            .router_z_loss
+            .into_data()
+            .assert_approx_eq::<f32>(&TensorData::from([ln_3 * ln_3]), Tolerance::default());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn backward_reaches_the_router_and_experts() {
+        let device = Device::default().autodiff();
+        let moe = config().with_gated(true).init(&device);
+        let input = Tensor::<2>::random([6, 4], Distribution::Default, &device);
+
+        let output = moe.forward(input);
+        let grads = (output.output.sum() + output.aux_loss).backward();
+
+        assert!(moe.router.weight.grad(&grads).is_some());
+        assert!(moe.weight_inner.grad(&grads).is_some());
+        assert!(moe.weight_gate.as_ref().unwrap().grad(&grads).is_some());
+        assert!(moe.weight_outer.grad(&grads).is_some());
+    }
+
+    #[test]
+    fn display() {
+        let moe = config().init(&Default::default());
+
+        assert_eq!(
+            alloc::format!("{moe}"),
+            "MixtureOfExperts {d_model: 4, d_ff: 8, num_experts: 3, top_k: 2, params: 204}"
+        );
+    }
+}
@@ -3,6 +3,7 @@ use burn_core as burn;
 use crate::activation::{Activation, ActivationConfig};
 use crate::{Dropout, DropoutConfig, Linear, LinearConfig};
 use burn::config::Config;
+use burn::module::hookable;
 use burn::module::{Content, DisplaySettings, Initializer, Module, ModuleDisplay};
 use burn::tensor::{Device, Tensor};
 
@@ -102,6 +103,7 @@ impl PositionWiseFeedForward {
     ///
     /// - tensor: `[batch_size, seq_length, d_model]`
     /// - output: `[batch_size, seq_length, d_model]`
+    #[hookable]
     pub fn forward<const D: usize>(&self, input: Tensor<D>) -> Tensor<D> {
         let x = self.linear_inner.forward(input);
         let x = self.activation.forward(x);
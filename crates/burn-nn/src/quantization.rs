@@ -0,0 +1,588 @@
+use burn_core as burn;
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::cell::RefCell;
+use core::fmt;
+
+use burn::module::{ForwardHooks, Module, ModuleMapper, ModuleVisitor, Param};
+use burn::tensor::{
+    Tensor,
+    quantization::{Calibration, QuantScheme, compute_q_params, compute_range},
+};
+
+/// Linear layers reading the output of the same module, rescaled with the same scales.
+///
+/// The previous module is a [LayerNorm](crate::LayerNorm), [RmsNorm](crate::RmsNorm) or
+/// [Linear](crate::Linear) whose output feeds the linear layers directly (e.g. the norm before
+/// the query, key and value projections of an attention block).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleGroup {
+    /// The path of the module the scales are folded into.
+    pub previous: String,
+    /// The paths of the rescaled [Linear](crate::Linear) layers.
+    pub linears: Vec<String>,
+}
+
+/// The input statistics of a [Linear](crate::Linear) layer collected during calibration.
+#[derive(Debug, Clone)]
+pub struct LinearInputStatistics {
+    /// The mean absolute value of each input channel, of shape `[d_input]`.
+    pub mean_abs: Tensor<1>,
+    /// The inputs of the first calibration batch, of shape `[n, d_input]`, used to measure the
+    /// quantization error.
+    pub sample: Tensor<2>,
+}
+
+/// The quantization error of a [Linear](crate::Linear) layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerQuantError {
+    /// The path of the layer.
+    pub path: String,
+    /// The scale exponent selected for the group of the layer.
+    pub alpha: f64,
+    /// The relative error `‖XW - X'Q(W')‖ / ‖XW‖` of the output with the rescaled weights.
+    pub relative_error: f32,
+    /// The relative error of the output when quantizing the weights without rescaling.
+    pub baseline_relative_error: f32,
+}
+
+/// The per-layer quantization errors measured on the calibration sample.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuantizationReport {
+    /// The error of every quantized layer.
+    pub layers: Vec<LayerQuantError>,
+}
+
+impl fmt::Display for QuantizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for layer in self.layers.iter() {
+            writeln!(
+                f,
+                "{}: alpha {:.2}, relative error {:.6} (baseline {:.6})",
+                layer.path, layer.alpha, layer.relative_error, layer.baseline_relative_error
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Activation-aware weight quantization of [Linear](crate::Linear) layers.
+///
+/// Low-bit weight quantization loses the most accuracy on the input channels with large
+/// activations. Following AWQ, the weights of those channels are scaled up by
+/// `s = mean(|x|)^alpha` before being quantized, and the inverse scales are folded into the
+/// module producing the inputs, so the float function of the model is unchanged. The exponent
+/// is searched per [group](ScaleGroup) to minimize the output error of the quantized layers on
+/// the calibration data, `alpha = 0` being plain weight quantization.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut quantizer = ActivationAwareQuantizer::new(Calibration::MinMax, scheme);
+/// quantizer.add_group("norm", vec!["query", "key", "value"]);
+///
+/// let (model, report) = quantizer.quantize(model, dataloader.iter(), |model, batch| {
+///     model.forward(batch.inputs)
+/// });
+/// println!("{report}");
+/// ```
+pub struct ActivationAwareQuantizer {
+    /// The calibration method used to quantize the weights.
+    pub calibration: Calibration,
+    /// The weight quantization scheme.
+    pub scheme: QuantScheme,
+    /// The number of steps of the scale exponent search over `[0, 1]`. Default: 20
+    pub grid_size: usize,
+    /// The maximum number of calibration inputs kept per layer to measure the error.
+    /// Default: 512
+    pub sample_size: usize,
+    groups: Vec<ScaleGroup>,
+}
+
+impl ActivationAwareQuantizer {
+    /// Create a new [ActivationAwareQuantizer] without any group.
+    pub fn new(calibration: Calibration, scheme: QuantScheme) -> Self {
+        Self {
+            calibration,
+            scheme,
+            grid_size: 20,
+            sample_size: 512,
+            groups: Vec::new(),
+        }
+    }
+
+    /// Add a group of linear layers whose input is the output of the module at `previous`.
+    pub fn add_group(&mut self, previous: impl Into<String>, linears: Vec<impl Into<String>>) {
+        self.groups.push(ScaleGroup {
+            previous: previous.into(),
+            linears: linears.into_iter().map(Into::into).collect(),
+        });
+    }
+
+    /// The groups of linear layers.
+    pub fn groups(&self) -> &[ScaleGroup] {
+        &self.groups
+    }
+
+    /// Run every batch through `forward` and collect the input statistics of the linear layers
+    /// of every group by path.
+    pub fn collect<M: Module, I, O>(
+        &self,
+        module: &M,
+        batches: impl IntoIterator<Item = I>,
+        mut forward: impl FnMut(&M, I) -> O,
+    ) -> BTreeMap<String, LinearInputStatistics> {
+        let statistics = Rc::new(RefCell::new(BTreeMap::new()));
+
+        let mut hooks = ForwardHooks::new();
+        for path in self.groups.iter().flat_map(|group| group.linears.iter()) {
+            observe_input::<2>(&mut hooks, path, &statistics, self.sample_size);
+            observe_input::<3>(&mut hooks, path, &statistics, self.sample_size);
+            observe_input::<4>(&mut hooks, path, &statistics, self.sample_size);
+        }
+
+        for batch in batches {
+            hooks.run(module, |module| forward(module, batch));
+        }
+
+        let statistics = core::mem::take(&mut *statistics.borrow_mut());
+        statistics
+            .into_iter()
+            .map(|(path, statistics): (String, InputAccumulator)| {
+                let mean_abs = statistics.sum / statistics.count as f64;
+                let sample = statistics.sample;
+                (path, LinearInputStatistics { mean_abs, sample })
+            })
+            .collect()
+    }
+
+    /// Search the scales of every group, fold them into the module and quantize the weights of
+    /// the linear layers.
+    ///
+    /// Folding the scales changes the outputs of the previous modules and the inputs of the
+    /// linear layers, so [activation scales](burn::module::ActivationQuantParams) calibrated on
+    /// the original module don't apply to the returned one: calibrate them after this step.
+    ///
+    /// # Panics
+    ///
+    /// If the statistics of a linear layer are missing, or a path of a group doesn't match any
+    /// parameter of the module.
+    pub fn apply<M: Module>(
+        &self,
+        module: M,
+        statistics: &BTreeMap<String, LinearInputStatistics>,
+    ) -> (M, QuantizationReport) {
+        let mut weights = WeightCollector::new(&self.groups);
+        module.visit(&mut weights);
+
+        let mut scaler = ScaleMapper {
+            quantizer: Some(self),
+            ..Default::default()
+        };
+        let mut report = QuantizationReport::default();
+        for group in self.groups.iter() {
+            let layers: Vec<_> = group
+                .linears
+                .iter()
+                .map(|path| {
+                    let weight = weights.weights.remove(path).unwrap_or_else(|| {
+                        panic!("No linear weight found at path '{path}.weight'")
+                    });
+                    let statistics = statistics
+                        .get(path)
+                        .unwrap_or_else(|| panic!("No calibration inputs for linear '{path}'"));
+                    (path, weight, statistics)
+                })
+                .collect();
+
+            let mean_abs = layers
+                .iter()
+                .map(|(_, _, statistics)| statistics.mean_abs.clone())
+                .reduce(Tensor::max_pair)
+                .expect("A scale group should have at least one linear layer")
+                .clamp_min(1e-4);
+
+            let mut best: Option<(f64, Tensor<1>, Vec<f32>)> = None;
+            let mut baseline = Vec::new();
+            for step in 0..=self.grid_size {
+                let alpha = step as f64 / self.grid_size.max(1) as f64;
+                let scales = normalize(mean_abs.clone().powf_scalar(alpha));
+                let errors: Vec<f32> = layers
+                    .iter()
+                    .map(|(_, weight, statistics)| {
+                        self.relative_error(weight.clone(), statistics.sample.clone(), &scales)
+                    })
+                    .collect();
+
+                if step == 0 {
+                    baseline = errors.clone();
+                }
+                let total: f32 = errors.iter().sum();
+                if best
+                    .as_ref()
+                    .is_none_or(|(_, _, best)| total < best.iter().sum::<f32>())
+                {
+                    best = Some((alpha, scales, errors));
+                }
+            }
+
+            let (alpha, scales, errors) = best.unwrap();
+            for (((path, _, _), relative_error), baseline_relative_error) in
+                layers.iter().zip(errors).zip(baseline)
+            {
+                report.layers.push(LayerQuantError {
+                    path: path.to_string(),
+                    alpha,
+                    relative_error,
+                    baseline_relative_error,
+                });
+                scaler.multiply.insert(path.to_string(), scales.clone());
+            }
+            scaler.divide.insert(group.previous.clone(), scales);
+        }
+
+        let module = module.map(&mut scaler);
+        for path in scaler.divide.keys().chain(scaler.multiply.keys()) {
+            assert!(
+                scaler.mapped.contains(path),
+                "No parameter found for the module at path '{path}'"
+            );
+        }
+
+        (module, report)
+    }
+
+    /// Run every batch through `forward`, then rescale and quantize the linear layers of every
+    /// group.
+    pub fn quantize<M: Module, I, O>(
+        &self,
+        module: M,
+        batches: impl IntoIterator<Item = I>,
+        forward: impl FnMut(&M, I) -> O,
+    ) -> (M, QuantizationReport) {
+        let statistics = self.collect(&module, batches, forward);
+        self.apply(module, &statistics)
+    }
+
+    /// The relative output error of the linear layer quantized with the given input scales.
+    fn relative_error(&self, weight: Tensor<2>, sample: Tensor<2>, scales: &Tensor<1>) -> f32 {
+        let reference = sample.clone().matmul(weight.clone());
+
+        let weight = self
+            .quantize_weight(weight * scales.clone().unsqueeze_dim(1))
+            .dequantize();
+        let output = (sample / scales.clone().unsqueeze_dim(0)).matmul(weight);
+
+        let error = (reference.clone() - output).square().sum();
+        let norm = reference.square().sum().clamp_min(f32::MIN_POSITIVE);
+        (error / norm).sqrt().into_scalar::<f32>()
+    }
+
+    fn quantize_weight<const D: usize>(&self, weight: Tensor<D>) -> Tensor<D> {
+        let range = compute_range(&self.scheme, &weight, &self.calibration);
+        let qparams = compute_q_params(&self.scheme, range);
+        weight.quantize(&self.scheme, qparams)
+    }
+}
+
+/// Normalize the scales so their range is centered around one.
+fn normalize(scales: Tensor<1>) -> Tensor<1> {
+    let center = (scales.clone().max() * scales.clone().min()).sqrt();
+    scales / center
+}
+
+struct InputAccumulator {
+    sum: Tensor<1>,
+    count: usize,
+    sample: Tensor<2>,
+}
+
+fn observe_input<const D: usize>(
+    hooks: &mut ForwardHooks,
+    path: &str,
+    statistics: &Rc<RefCell<BTreeMap<String, InputAccumulator>>>,
+    sample_size: usize,
+) {
+    let statistics = statistics.clone();
+    let key = path.to_string();
+    hooks.register_pre_hook(path, move |input: Tensor<D>| {
+        let d_input = input.dims()[D - 1];
+        let rows = input.clone().reshape([-1, d_input as i32]);
+        let count = rows.dims()[0];
+        let sum = rows.clone().abs().sum_dim(0).squeeze_dim::<1>(0);
+
+        let mut statistics = statistics.borrow_mut();
+        match statistics.get_mut(&key) {
+            Some(accumulator) => {
+                accumulator.sum = accumulator.sum.clone() + sum;
+                accumulator.count += count;
+            }
+            None => {
+                let sample = rows.narrow(0, 0, count.min(sample_size));
+                statistics.insert(key.clone(), InputAccumulator { sum, count, sample });
+            }
+        }
+        input
+    });
+}
+
+/// Collects the weights of the linear layers of the groups.
+struct WeightCollector {
+    path: Vec<String>,
+    targets: BTreeSet<String>,
+    weights: BTreeMap<String, Tensor<2>>,
+}
+
+impl WeightCollector {
+    fn new(groups: &[ScaleGroup]) -> Self {
+        let targets = groups
+            .iter()
+            .flat_map(|group| group.linears.iter())
+            .map(|path| format!("{path}.weight"))
+            .collect();
+
+        Self {
+            path: Vec::new(),
+            targets,
+            weights: BTreeMap::new(),
+        }
+    }
+}
+
+impl ModuleVisitor for WeightCollector {
+    fn enter_module(&mut self, name: &str, _container_type: &str) {
+        self.path.push(name.to_string());
+    }
+
+    fn exit_module(&mut self, _name: &str, _container_type: &str) {
+        self.path.pop();
+    }
+
+    fn visit_float<const D: usize>(&mut self, param: &Param<Tensor<D>>) {
+        let path = self.path.join(".");
+        if D == 2 && self.targets.contains(&path) {
+            let [d_input, d_output] = [param.shape()[0], param.shape()[1]];
+            let weight = param.val().reshape([d_input, d_output]);
+            let module = path.strip_suffix(".weight").unwrap().to_string();
+            self.weights.insert(module, weight);
+        }
+    }
+}
+
+/// Folds the inverse scales into the previous modules and the scales into the linear weights,
+/// quantizing the rescaled weights.
+#[derive(Default)]
+struct ScaleMapper<'a> {
+    quantizer: Option<&'a ActivationAwareQuantizer>,
+    path: Vec<String>,
+    divide: BTreeMap<String, Tensor<1>>,
+    multiply: BTreeMap<String, Tensor<1>>,
+    mapped: BTreeSet<String>,
+}
+
+impl ModuleMapper for ScaleMapper<'_> {
+    fn enter_module(&mut self, name: &str, _container_type: &str) {
+        self.path.push(name.to_string());
+    }
+
+    fn exit_module(&mut self, _name: &str, _container_type: &str) {
+        self.path.pop();
+    }
+
+    fn map_float<const D: usize>(&mut self, param: Param<Tensor<D>>) -> Param<Tensor<D>> {
+        let Some((name, parent)) = self.path.split_last() else {
+            return param;
+        };
+        let module = parent.join(".");
+        let (id, mut tensor, mapper) = param.consume();
+
+        // The output channels are the last dimension of the norm parameters, the linear weight
+        // of shape `[d_input, d_output]` and its bias. Any other parameter of the module doesn't
+        // scale its output per channel, so it is left as is.
+        if let Some(scales) = self.divide.get(&module)
+            && matches!(name.as_str(), "gamma" | "beta" | "weight" | "bias")
+        {
+            let mut shape = [1; D];
+            shape[D - 1] = -1;
+            tensor = tensor / scales.clone().reshape(shape);
+            self.mapped.insert(module.clone());
+        }
+        if let Some(scales) = self.multiply.get(&module)
+            && name == "weight"
+        {
+            let mut shape = [1; D];
+            shape[0] = -1;
+            tensor = tensor * scales.clone().reshape(shape);
+            if let Some(quantizer) = self.quantizer {
+                tensor = quantizer.quantize_weight(tensor);
+            }
+            self.mapped.insert(module);
+        }
+
+        Param::from_mapped_value(id, tensor, mapper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LayerNorm, LayerNormConfig, Linear, LinearConfig};
+    use alloc::vec;
+    use burn::tensor::quantization::QuantValue;
+    use burn::tensor::{DType, Device, Distribution, TensorData, Tolerance};
+
+    #[derive(Module, Debug)]
+    struct Block {
+        norm: LayerNorm,
+        query: Linear,
+        value: Linear,
+    }
+
+    impl Block {
+        fn new(device: &Device) -> Self {
+            Self {
+                norm: LayerNormConfig::new(32).init(device),
+                query: LinearConfig::new(32, 16).init(device),
+                value: LinearConfig::new(32, 16).init(device),
+            }
+        }
+
+        fn forward(&self, input: Tensor<3>) -> Tensor<3> {
+            let x = self.norm.forward(input);
+            self.query.forward(x.clone()) + self.value.forward(x)
+        }
+    }
+
+    fn batches(device: &Device) -> Vec<Tensor<3>> {
+        // Channel 0 is an activation outlier, as in LLM hidden states.
+        let mut outlier = vec![1.0; 32];
+        outlier[0] = 50.0;
+        let outlier =
+            Tensor::<1>::from_data(TensorData::new(outlier, [32]), device).reshape([1, 1, 32]);
+        (0..4)
+            .map(|_| {
+                Tensor::random([2, 8, 32], Distribution::Normal(0.0, 1.0), device) * outlier.clone()
+            })
+            .collect()
+    }
+
+    fn quantizer(device: &Device) -> ActivationAwareQuantizer {
+        let scheme = device
+            .settings()
+            .quantization
+            .scheme
+            .with_value(QuantValue::Q8S);
+        let mut quantizer = ActivationAwareQuantizer::new(Calibration::MinMax, scheme);
+        quantizer.add_group("norm", vec!["query", "value"]);
+        quantizer
+    }
+
+    #[test]
+    fn collect_should_track_linear_inputs() {
+        let device = Device::default();
+        let block = Block::new(&device);
+        let quantizer = quantizer(&device);
+
+        let statistics = quantizer.collect(&block, batches(&device), |block, batch| {
+            block.forward(batch)
+        });
+
+        assert_eq!(statistics.keys().collect::<Vec<_>>(), ["query", "value"]);
+        assert_eq!(statistics["query"].mean_abs.dims(), [32]);
+        assert_eq!(statistics["query"].sample.dims(), [16, 32]);
+    }
+
+    #[test]
+    fn scales_should_preserve_the_float_function() {
+        let device = Device::default();
+        let block = Block::new(&device);
+        let quantizer = quantizer(&device);
+        let input = batches(&device).remove(0);
+        let expected = block.forward(input.clone());
+        let statistics = quantizer.collect(&block, batches(&device), |block, batch| {
+            block.forward(batch)
+        });
+
+        let mut scaler = ScaleMapper::default();
+        let scales = normalize(statistics["query"].mean_abs.clone().clamp_min(1e-4));
+        scaler.divide.insert("norm".into(), scales.clone());
+        scaler.multiply.insert("query".into(), scales.clone());
+        scaler.multiply.insert("value".into(), scales);
+        let block = block.map(&mut scaler);
+
+        assert_eq!(scaler.mapped.len(), 3);
+        block
+            .forward(input)
+            .into_data()
+            .assert_approx_eq::<f32>(&expected.into_data(), Tolerance::permissive());
+    }
+
+    #[test]
+    fn scales_should_skip_parameters_without_channels() {
+        #[derive(Module, Debug)]
+        struct Scaled {
+            gamma: Param<Tensor<1>>,
+            temperature: Param<Tensor<1>>,
+        }
+
+        #[derive(Module, Debug)]
+        struct Model {
+            previous: Scaled,
+        }
+
+        let device = Device::default();
+        let model = Model {
+            previous: Scaled {
+                gamma: Param::from_tensor(Tensor::ones([4], &device)),
+                temperature: Param::from_tensor(Tensor::ones([1], &device)),
+            },
+        };
+
+        let mut scaler = ScaleMapper::default();
+        let scales = Tensor::<1>::from_data([1.0, 2.0, 4.0, 8.0], &device);
+        scaler.divide.insert("previous".into(), scales);
+        let model = model.map(&mut scaler);
+
+        model
+            .previous
+            .gamma
+            .val()
+            .into_data()
+            .assert_eq(&TensorData::from([1.0, 0.5, 0.25, 0.125]), false);
+        model
+            .previous
+            .temperature
+            .val()
+            .into_data()
+            .assert_eq(&TensorData::from([1.0]), false);
+    }
+
+    #[test]
+    fn apply_should_quantize_weights_and_report_errors() {
+        let device = Device::default();
+        let block = Block::new(&device);
+        let quantizer = quantizer(&device);
+
+        let (block, report) =
+            quantizer.quantize(block, batches(&device), |block, batch| block.forward(batch));
+
+        assert!(matches!(block.query.weight.val().dtype(), DType::QFloat(_)));
+        assert!(matches!(block.value.weight.val().dtype(), DType::QFloat(_)));
+        assert!(!matches!(block.norm.gamma.val().dtype(), DType::QFloat(_)));
+        let paths: Vec<_> = report
+            .layers
+            .iter()
+            .map(|layer| layer.path.as_str())
+            .collect();
+        assert_eq!(paths, ["query", "value"]);
+        for layer in report.layers {
+            assert!(layer.relative_error <= layer.baseline_relative_error);
+        }
+    }
+}
@@ -9,6 +9,7 @@ use alloc::vec::Vec;
 use burn_std::DType;
 use byteorder::{ByteOrder, LittleEndian};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
 
 /// Magic number identifying a Burnpack file: "BURN" in ASCII (0x4255524E)
 /// When written to file in little-endian format, appears as "NRUB" bytes
@@ -281,6 +282,66 @@ impl TryFrom<Scalar> for bool {
     }
 }
 
+/// A SHA-256 digest, as stored in the metadata of a checksummed container.
+///
+/// Serialized as a CBOR byte string, so its encoded size does not depend on its value: the
+/// writer relies on that to reserve the metadata before the digests are known.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Digest(#[serde(with = "serde_bytes")] pub [u8; 32]);
+
+impl Digest {
+    /// Hash `data` in one go.
+    pub fn of(data: &[u8]) -> Self {
+        let mut hasher = Hasher::default();
+        hasher.update(data);
+        hasher.finish()
+    }
+}
+
+impl core::fmt::Display for Digest {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl core::fmt::Debug for Digest {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Digest({self})")
+    }
+}
+
+/// Incremental SHA-256, for data that arrives (or is read) in chunks.
+#[derive(Default, Clone)]
+pub(crate) struct Hasher(Sha256);
+
+impl Hasher {
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    /// Feed `count` zero bytes, as written for alignment padding.
+    pub fn update_zeros(&mut self, mut count: usize) {
+        const ZEROS: [u8; TENSOR_ALIGNMENT as usize] = [0; TENSOR_ALIGNMENT as usize];
+        while count > 0 {
+            let chunk = count.min(ZEROS.len());
+            self.0.update(&ZEROS[..chunk]);
+            count -= chunk;
+        }
+    }
+
+    /// Return the digest of everything fed so far and start over.
+    pub fn finish_reset(&mut self) -> Digest {
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&self.0.finalize_reset());
+        Digest(digest)
+    }
+
+    pub fn finish(mut self) -> Digest {
+        self.finish_reset()
+    }
+}
+
 /// Metadata structure serialized with CBOR
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Metadata {
@@ -294,6 +355,11 @@ pub(crate) struct Metadata {
     /// Defaulted on read for backward compatibility with files written before scalar support.
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub scalars: BTreeMap<String, Scalar>,
+    /// SHA-256 of the whole data section, padding included, for checksummed containers.
+    ///
+    /// Absent in files written without checksums (and in every file predating them).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_digest: Option<Digest>,
 }
 
 /// Individual tensor descriptor
@@ -309,6 +375,9 @@ pub(crate) struct TensorDescriptor {
     /// Generated automatically if not present during loading.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub param_id: Option<u64>,
+    /// SHA-256 of the tensor's bytes, for checksummed containers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<Digest>,
 }
 
 /// Error types for Burnpack operations
@@ -323,6 +392,11 @@ pub enum Error {
     TensorNotFound(String),
     TensorBytesSizeMismatch(String),
     ValidationError(String),
+    /// Stored bytes do not hash to the digest recorded for them: the file was truncated,
+    /// corrupted, or modified after it was written.
+    ChecksumMismatch(String),
+    /// A manifest signature is missing, malformed, or does not verify.
+    SignatureError(String),
 }
 
 impl core::fmt::Display for Error {
@@ -343,6 +417,8 @@ impl core::fmt::Display for Error {
                 write!(f, "Tensor bytes size mismatch: {}", e)
             }
             Error::ValidationError(e) => write!(f, "Validation error: {}", e),
+            Error::ChecksumMismatch(e) => write!(f, "Checksum mismatch: {}", e),
+            Error::SignatureError(e) => write!(f, "Signature error: {}", e),
         }
     }
 }
@@ -359,7 +435,9 @@ impl Error {
             | Error::MetadataDeserializationError(message)
             | Error::IoError(message)
             | Error::TensorBytesSizeMismatch(message)
-            | Error::ValidationError(message) => *message = format!("tensor '{name}': {message}"),
+            | Error::ValidationError(message)
+            | Error::ChecksumMismatch(message)
+            | Error::SignatureError(message) => *message = format!("tensor '{name}': {message}"),
             // Header failures carry no message to annotate (and are not expected from an
             // entry), while `TensorNotFound`'s payload is a tensor name, not a sentence -
             // prefixing either would garble its Display output.
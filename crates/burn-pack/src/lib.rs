@@ -69,7 +69,9 @@
 //! │     shape        : list<u64>                                  │
 //! │     data_offsets : (start, end)  relative to the data section │
 //! │     param_id     : optional u64  (training-state identity)    │
+//! │     checksum     : optional SHA-256 of the tensor's bytes     │
 //! │   metadata : map<string, string>  user key/value pairs       │
+//! │   data_digest : optional SHA-256 of the whole data section   │
 //! ├──────────────────────────────────────────────────────────────┤
 //! │ Padding to the next 256-byte boundary                        │
 //! │   ([`aligned_data_section_start`])                            │
@@ -90,6 +92,26 @@
 //! and GPU coalesced access. 256 bytes matches the choice made by GGUF, MLX, ncnn, and
 //! other major formats.
 //!
+//! ## Integrity
+//!
+//! A container written [with checksums](Writer::with_checksums) records a SHA-256 [`Digest`]
+//! for every tensor and for the whole data section (padding included). A [`Reader`] checks a
+//! tensor's digest when its bytes are accessed, and [`Reader::verify`] checks everything at
+//! once, so a truncated or bit-flipped file fails with [`Error::ChecksumMismatch`] instead of
+//! loading garbage. Containers without checksums, including every file written before them,
+//! read unchanged.
+//!
+//! With the `signature` feature, the metadata (the *manifest*) can also carry a detached
+//! ed25519 signature ([`sign_manifest`], [`Reader::verify_signature`]). Since a checksummed
+//! manifest pins every byte of the data, the signature covers the whole container.
+//!
+//! ## Sharding
+//!
+//! [`Writer::write_sharded`] splits a container into several ordinary burnpack files under a
+//! size cap, plus a JSON index ([`ShardIndex`]) mapping each tensor to its shard.
+//! [`Reader::from_file`] opens the index transparently and only opens a shard when one of its
+//! tensors is accessed. See [`ShardIndex`] for the layout (std only).
+//!
 //! ## Safety limits
 //!
 //! Reading is hardened against malicious or corrupt inputs. The reader rejects files
@@ -106,23 +128,37 @@
 //!
 //! ## Feature Flags
 //!
-//! - `std`: Enables file I/O ([`Reader::from_file`] / [`Writer::write_to_file`]) (default)
+//! - `std`: Enables file I/O ([`Reader::from_file`] / [`Writer::write_to_file`]) and sharded
+//!   containers (default)
+//! - `signature`: Enables detached ed25519 manifest signatures
 
 extern crate alloc;
 
 mod base;
 mod reader;
+#[cfg(feature = "std")]
+mod shard;
+#[cfg(feature = "signature")]
+mod signature;
 mod tensor;
 mod writer;
 
 #[cfg(feature = "std")]
 pub use base::MAX_FILE_SIZE;
 pub use base::{
-    Error, FORMAT_VERSION, HEADER_SIZE, Header, MAGIC_NUMBER, MAX_CBOR_RECURSION_DEPTH,
+    Digest, Error, FORMAT_VERSION, HEADER_SIZE, Header, MAGIC_NUMBER, MAX_CBOR_RECURSION_DEPTH,
     MAX_METADATA_SIZE, MAX_TENSOR_COUNT, MAX_TENSOR_SIZE, Scalar, ScalarConversionError,
     TENSOR_ALIGNMENT, aligned_data_section_start,
 };
 pub use reader::Reader;
+#[cfg(feature = "std")]
+pub use shard::{INDEX_SUFFIX, ShardEntry, ShardIndex, ShardTensor, index_path};
+#[cfg(feature = "signature")]
+pub use signature::{
+    PUBLIC_KEY_SIZE, SECRET_KEY_SIZE, SIGNATURE_SIZE, public_key, sign_manifest, verify_manifest,
+};
+#[cfg(all(feature = "signature", feature = "std"))]
+pub use signature::{SIGNATURE_EXTENSION, read_signature, sign_file, signature_path};
 pub use tensor::Tensor;
 pub use writer::Writer;
 
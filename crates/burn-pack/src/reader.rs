@@ -1,6 +1,7 @@
 use super::base::{
-    Error, FORMAT_VERSION, HEADER_SIZE, Header, MAX_CBOR_RECURSION_DEPTH, MAX_METADATA_SIZE,
-    MAX_TENSOR_COUNT, MAX_TENSOR_SIZE, Metadata, TensorDescriptor, aligned_data_section_start,
+    Digest, Error, FORMAT_VERSION, HEADER_SIZE, Hasher, Header, MAX_CBOR_RECURSION_DEPTH,
+    MAX_METADATA_SIZE, MAX_TENSOR_COUNT, MAX_TENSOR_SIZE, Metadata, TensorDescriptor,
+    aligned_data_section_start,
 };
 use super::tensor::Tensor;
 use alloc::format;
@@ -11,6 +12,8 @@ use burn_std::{Bytes, Shape};
 #[cfg(feature = "std")]
 use super::base::MAX_FILE_SIZE;
 #[cfg(feature = "std")]
+use super::shard::{self, ShardSet};
+#[cfg(feature = "std")]
 use alloc::vec;
 #[cfg(feature = "std")]
 use std::fs::File;
@@ -19,12 +22,24 @@ use std::io::Read;
 #[cfg(feature = "std")]
 use std::path::Path;
 
+/// Size of the chunks read from the source while hashing the whole data section.
+const VERIFY_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
 /// Reader for loading burnpack containers.
+///
+/// Containers written [with checksums](crate::Writer::with_checksums) are verified lazily:
+/// a tensor's bytes are hashed and compared with the recorded digest when they are accessed,
+/// and [`verify`](Self::verify) checks everything up front, including the digest of the whole
+/// data section. Containers without checksums read exactly as before.
 pub struct Reader {
     metadata: Metadata,
+    /// The raw CBOR metadata, as signed by [`sign_manifest`](crate::sign_manifest).
+    manifest: Vec<u8>,
     source: Source,
     /// Absolute byte offset where the (256-byte aligned) tensor data section starts.
     data_offset: usize,
+    /// Whether recorded checksums are checked when tensor data is accessed.
+    verify_checksums: bool,
 }
 
 impl Reader {
@@ -42,10 +57,17 @@ impl Reader {
         if bytes.len() < metadata_end {
             return Err(Error::InvalidHeader);
         }
-        let metadata = parse_metadata(&bytes[HEADER_SIZE..metadata_end])?;
+        let manifest = bytes[HEADER_SIZE..metadata_end].to_vec();
+        let metadata = parse_metadata(&manifest)?;
 
         let available = bytes.len();
-        Self::assemble(&header, metadata, Source::Memory(bytes), available)
+        Self::assemble(
+            &header,
+            metadata,
+            manifest,
+            Source::Memory(bytes),
+            available,
+        )
     }
 
     /// Load a pack from a file.
@@ -57,6 +79,10 @@ impl Reader {
     ///
     /// If `path` has no extension and does not exist as given, the canonical
     /// [`crate::EXTENSION`] (`.bpk`) is appended.
+    ///
+    /// A [sharded](crate::Writer::write_sharded) container is opened transparently, given
+    /// either its index or the path the unsharded container would have had. Only the index is
+    /// read here; each shard is opened the first time one of its tensors is accessed.
     #[cfg(feature = "std")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let path = path.as_ref();
@@ -66,6 +92,16 @@ impl Reader {
             path.to_path_buf()
         };
 
+        if shard::is_index(&path) {
+            return Self::from_index(&path);
+        }
+        if !path.exists() {
+            let index = shard::index_path(&path);
+            if index.exists() {
+                return Self::from_index(&index);
+            }
+        }
+
         let mut file = File::open(&path).map_err(io_err)?;
 
         let file_size = file.metadata().map_err(io_err)?.len();
@@ -84,7 +120,33 @@ impl Reader {
         let metadata = parse_metadata(&metadata_bytes)?;
 
         let source = Source::File(Bytes::from_file(path.as_path(), file_size, 0));
-        Self::assemble(&header, metadata, source, file_size as usize)
+        Self::assemble(
+            &header,
+            metadata,
+            metadata_bytes,
+            source,
+            file_size as usize,
+        )
+    }
+
+    /// Open the index of a sharded container, without opening any shard.
+    #[cfg(feature = "std")]
+    fn from_index(path: &Path) -> Result<Self, Error> {
+        let (shards, manifest) = ShardSet::open(path)?;
+        let metadata = Metadata {
+            tensors: Default::default(),
+            metadata: shards.index.metadata.clone(),
+            scalars: shards.index.scalars.clone(),
+            data_digest: None,
+        };
+
+        Ok(Self {
+            metadata,
+            manifest,
+            source: Source::Sharded(shards),
+            data_offset: 0,
+            verify_checksums: true,
+        })
     }
 
     /// Finish construction once the header, metadata, and data source are known.
@@ -95,6 +157,7 @@ impl Reader {
     fn assemble(
         header: &Header,
         metadata: Metadata,
+        manifest: Vec<u8>,
         source: Source,
         available: usize,
     ) -> Result<Self, Error> {
@@ -103,11 +166,162 @@ impl Reader {
 
         Ok(Self {
             metadata,
+            manifest,
             source,
             data_offset: aligned_data_section_start(header.metadata_size as usize),
+            verify_checksums: true,
         })
     }
 
+    /// Enable or disable checking recorded checksums when tensor data is accessed.
+    ///
+    /// Has no effect on containers written without checksums, and does not affect
+    /// [`verify`](Self::verify), which always checks. Disabling it also voids what a
+    /// [manifest signature](Self::verify_signature) says about the tensor data.
+    ///
+    /// Default: `true`
+    pub fn verify_checksums(mut self, verify: bool) -> Self {
+        self.verify_checksums = verify;
+        #[cfg(feature = "std")]
+        if let Source::Sharded(shards) = &mut self.source {
+            shards.verify_checksums = verify;
+        }
+        self
+    }
+
+    /// Whether the container records integrity checksums.
+    ///
+    /// For a sharded container, whether every shard does and the index pins each shard's
+    /// manifest.
+    pub fn has_checksums(&self) -> bool {
+        match &self.source {
+            #[cfg(feature = "std")]
+            Source::Sharded(shards) => shards.index.has_checksums(),
+            _ => self.metadata.data_digest.is_some(),
+        }
+    }
+
+    /// The recorded checksum of a tensor's bytes, without reading them.
+    ///
+    /// `None` if the tensor does not exist or was written without a checksum, and always for
+    /// a sharded container, whose index only pins each shard's manifest.
+    pub fn tensor_checksum(&self, name: &str) -> Option<Digest> {
+        match &self.source {
+            #[cfg(feature = "std")]
+            Source::Sharded(_) => None,
+            _ => self.metadata.tensors.get(name)?.checksum,
+        }
+    }
+
+    /// The raw CBOR metadata of the container: the bytes a manifest signature covers.
+    ///
+    /// For a checksummed container this pins down every byte of the file, since the
+    /// metadata records the digest of every tensor and of the whole data section. For a
+    /// sharded container it is the raw JSON index, which records the digest of every shard's
+    /// manifest in turn.
+    pub fn manifest(&self) -> &[u8] {
+        &self.manifest
+    }
+
+    /// Check every recorded checksum now, reading the whole data section once.
+    ///
+    /// Returns `Ok` without reading anything for a container written without checksums;
+    /// use [`has_checksums`](Self::has_checksums) to tell the two apart.
+    ///
+    /// For a sharded container, this opens and verifies every shard.
+    pub fn verify(&self) -> Result<(), Error> {
+        #[cfg(feature = "std")]
+        if let Source::Sharded(shards) = &self.source {
+            return shards.verify();
+        }
+
+        let Some(expected) = self.metadata.data_digest else {
+            return Ok(());
+        };
+
+        for (name, descriptor) in &self.metadata.tensors {
+            if let Some(checksum) = descriptor.checksum {
+                let (start, end) = tensor_range(self.data_offset, name, descriptor)?;
+                let mut hasher = Hasher::default();
+                self.read_range(start, end, |chunk| hasher.update(chunk))?;
+                check_digest(name, checksum, hasher.finish())?;
+            }
+        }
+
+        let data_size = self
+            .metadata
+            .tensors
+            .values()
+            .map(|descriptor| descriptor.data_offsets.1)
+            .max()
+            .unwrap_or(0);
+        let end = usize::try_from(data_size)
+            .ok()
+            .and_then(|size| self.data_offset.checked_add(size))
+            .ok_or_else(|| {
+                Error::ValidationError(format!("Data section size {data_size} overflows"))
+            })?;
+        let mut hasher = Hasher::default();
+        self.read_range(self.data_offset, end, |chunk| hasher.update(chunk))?;
+        let actual = hasher.finish();
+        if actual != expected {
+            return Err(Error::ChecksumMismatch(format!(
+                "data section hashes to {actual}, expected {expected}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Verify a detached ed25519 `signature` over the [manifest](Self::manifest) against
+    /// `public_key`.
+    ///
+    /// The manifest must carry checksums, or the signature would say nothing about the tensor
+    /// data; those checksums are then enforced as the data is accessed (or by
+    /// [`verify`](Self::verify)), so keep [checksum verification](Self::verify_checksums) on.
+    #[cfg(feature = "signature")]
+    pub fn verify_signature(
+        &self,
+        signature: &[u8; crate::SIGNATURE_SIZE],
+        public_key: &[u8; crate::PUBLIC_KEY_SIZE],
+    ) -> Result<(), Error> {
+        if !self.has_checksums() {
+            return Err(Error::SignatureError(
+                "the container has no checksums, so its manifest does not cover the tensor data"
+                    .into(),
+            ));
+        }
+        crate::verify_manifest(&self.manifest, signature, public_key)
+    }
+
+    /// Feed the source's `[start, end)` range to `f`, one bounded chunk at a time.
+    fn read_range(&self, start: usize, end: usize, mut f: impl FnMut(&[u8])) -> Result<(), Error> {
+        match &self.source {
+            #[cfg(feature = "std")]
+            Source::File(bytes) => {
+                let mut offset = start;
+                while offset < end {
+                    let chunk_end = (offset + VERIFY_CHUNK_SIZE).min(end);
+                    let view = bytes.view(offset, chunk_end).map_err(|_| {
+                        Error::ValidationError(format!(
+                            "Data range {offset}..{chunk_end} could not be viewed"
+                        ))
+                    })?;
+                    f(&view[..]);
+                    offset = chunk_end;
+                }
+            }
+            Source::Memory(bytes) => {
+                memory_chunk(bytes, start, end)?
+                    .chunks(VERIFY_CHUNK_SIZE)
+                    .for_each(f);
+            }
+            #[cfg(feature = "std")]
+            Source::Sharded(_) => unreachable!("sharded readers delegate to their shards"),
+        }
+        Ok(())
+    }
+
     /// Consume the reader, returning all tensors in sorted (alphabetical) name order.
     ///
     /// Each tensor's bytes are a zero-copy [`Bytes::view`] window into the source — no per-tensor
@@ -115,11 +329,21 @@ impl Reader {
     /// `Arc` move, never a data copy) to make those views available; for a file source the windows
     /// are file-backed and read lazily on access. Consuming `self` lets us hand the source's
     /// ownership to the views directly, so loading never reads or copies tensor data eagerly.
+    ///
+    /// A tensor with a recorded checksum (while [verification](Self::verify_checksums) is on)
+    /// is returned [deferred](Tensor::deferred) over the same view instead: its bytes are
+    /// hashed every time they are taken, and a mismatch surfaces as
+    /// [`Error::ChecksumMismatch`] from [`Tensor::into_bytes`] and friends.
+    ///
+    /// A sharded container's tensors are all deferred, described from the index alone; each
+    /// opens its shard on first access.
     pub fn into_tensors(self) -> Result<Vec<Tensor>, Error> {
         let Reader {
             metadata,
             source,
             data_offset,
+            verify_checksums,
+            ..
         } = self;
 
         // Make the source view-capable: a plain in-memory buffer has no zero-copy window until
@@ -129,6 +353,8 @@ impl Reader {
             Source::Memory(bytes) => bytes.shared(),
             #[cfg(feature = "std")]
             Source::File(bytes) => bytes,
+            #[cfg(feature = "std")]
+            Source::Sharded(shards) => return shards.into_tensors(),
         };
 
         let mut tensors = Vec::with_capacity(metadata.tensors.len());
@@ -140,7 +366,12 @@ impl Reader {
                     source.len()
                 ))
             })?;
-            tensors.push(make_tensor(name, descriptor, bytes)?);
+            let tensor = make_tensor(name, descriptor, bytes)?;
+            let tensor = match descriptor.checksum {
+                Some(checksum) if verify_checksums => checked_tensor(tensor, checksum)?,
+                _ => tensor,
+            };
+            tensors.push(tensor);
         }
         Ok(tensors)
     }
@@ -162,13 +393,46 @@ impl Reader {
 
     /// The names of all tensors in the pack, in sorted (alphabetical) order.
     pub fn tensor_names(&self) -> Vec<&str> {
-        self.metadata.tensors.keys().map(|n| n.as_str()).collect()
+        match &self.source {
+            #[cfg(feature = "std")]
+            Source::Sharded(shards) => shards.index.tensors.keys().map(|n| n.as_str()).collect(),
+            _ => self.metadata.tensors.keys().map(|n| n.as_str()).collect(),
+        }
     }
 
     /// Read a single tensor's raw little-endian bytes by name (always copies).
     ///
-    /// Returns [`Error::TensorNotFound`] if no tensor with that name exists.
+    /// Returns [`Error::TensorNotFound`] if no tensor with that name exists, and
+    /// [`Error::ChecksumMismatch`] if the bytes do not match their recorded checksum.
+    ///
+    /// For a sharded container, only the shard holding the tensor is opened.
     pub fn tensor_data(&self, name: &str) -> Result<Vec<u8>, Error> {
+        #[cfg(feature = "std")]
+        if let Source::Sharded(shards) = &self.source {
+            return shards.tensor_data(name);
+        }
+
+        let descriptor = self
+            .metadata
+            .tensors
+            .get(name)
+            .ok_or_else(|| Error::TensorNotFound(name.to_string()))?;
+        let (start, end) = tensor_range(self.data_offset, name, descriptor)?;
+
+        let data = self.copy_range(name, start, end)?;
+        if let Some(checksum) = descriptor.checksum
+            && self.verify_checksums
+        {
+            check_digest(name, checksum, Digest::of(&data))?;
+        }
+        Ok(data)
+    }
+
+    /// A single tensor's bytes as a view into the source, checked against its recorded
+    /// checksum (if verification is on). Used by a sharded container's tensors, each of which
+    /// reads through its shard's reader.
+    #[cfg(feature = "std")]
+    pub(crate) fn tensor_bytes(&self, name: &str) -> Result<Bytes, Error> {
         let descriptor = self
             .metadata
             .tensors
@@ -176,6 +440,24 @@ impl Reader {
             .ok_or_else(|| Error::TensorNotFound(name.to_string()))?;
         let (start, end) = tensor_range(self.data_offset, name, descriptor)?;
 
+        let bytes = match &self.source {
+            Source::File(bytes) => bytes.view(start, end).map_err(|_| {
+                Error::ValidationError(format!(
+                    "Tensor '{name}' data range {start}..{end} could not be viewed"
+                ))
+            })?,
+            _ => Bytes::from_bytes_vec(self.copy_range(name, start, end)?),
+        };
+        if let Some(checksum) = descriptor.checksum
+            && self.verify_checksums
+        {
+            check_digest(name, checksum, Digest::of(&bytes))?;
+        }
+        Ok(bytes)
+    }
+
+    /// Copy the source's `[start, end)` range holding tensor `name`.
+    fn copy_range(&self, name: &str, start: usize, end: usize) -> Result<Vec<u8>, Error> {
         match &self.source {
             #[cfg(feature = "std")]
             Source::File(bytes) => {
@@ -189,10 +471,44 @@ impl Reader {
                 Ok(slice.to_vec())
             }
             Source::Memory(bytes) => Ok(memory_chunk(bytes, start, end)?.to_vec()),
+            #[cfg(feature = "std")]
+            Source::Sharded(_) => unreachable!("sharded readers delegate to their shards"),
         }
     }
 }
 
+/// Compare a tensor's recorded checksum with the digest of the bytes actually read.
+fn check_digest(name: &str, expected: Digest, actual: Digest) -> Result<(), Error> {
+    if actual != expected {
+        return Err(Error::ChecksumMismatch(format!(
+            "tensor '{name}' hashes to {actual}, expected {expected}"
+        )));
+    }
+    Ok(())
+}
+
+/// Wrap a tensor read from the source so its bytes are checked against `checksum` on access.
+///
+/// The view is taken up front, so nothing is read until the bytes are; the provider only
+/// adds the hash over what was read.
+fn checked_tensor(tensor: Tensor, checksum: Digest) -> Result<Tensor, Error> {
+    let len = tensor.byte_len();
+    let (name, dtype, shape, param_id, bytes) = tensor.into_parts()?;
+    let label = name.clone();
+
+    Ok(Tensor::deferred(
+        name,
+        dtype,
+        shape,
+        param_id,
+        len,
+        move || {
+            check_digest(&label, checksum, Digest::of(&bytes))?;
+            Ok(bytes.clone())
+        },
+    ))
+}
+
 /// Compute and validate the absolute `[start, end)` byte range of a tensor.
 fn tensor_range(
     data_offset: usize,
@@ -338,6 +654,9 @@ enum Source {
     /// The pack lives in a file; tensor data is read lazily via file-backed [`Bytes::view`].
     #[cfg(feature = "std")]
     File(Bytes),
+    /// The pack is split across shard files, opened on demand through their index.
+    #[cfg(feature = "std")]
+    Sharded(ShardSet),
 }
 
 #[cfg(feature = "std")]
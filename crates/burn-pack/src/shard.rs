@@ -0,0 +1,436 @@
+//! Sharded containers: tensors split across several burnpack files, plus an index.
+//!
+//! [`Writer::write_sharded`] splits a model into shard files under a size cap, each an
+//! ordinary, self-contained burnpack container, and writes a JSON index next to them that maps
+//! every tensor to its shard (in the spirit of safetensors' `model.safetensors.index.json`):
+//!
+//! ```text
+//! model-00001-of-00003.bpk
+//! model-00002-of-00003.bpk
+//! model-00003-of-00003.bpk
+//! model.bpk.index.json
+//! ```
+//!
+//! [`Reader::from_file`] opens the index transparently, either given its path or given the
+//! path the unsharded container would have had (`model` or `model.bpk`). The index records
+//! each tensor's dtype, shape and length, so nothing but the index is read up front: a shard
+//! is opened the first time one of its tensors is accessed, and shards holding only tensors
+//! that are never accessed are never opened at all.
+
+use crate::base::{Digest, Error, MAX_METADATA_SIZE, MAX_TENSOR_COUNT, Scalar, TENSOR_ALIGNMENT};
+use crate::reader::Reader;
+use crate::tensor::Tensor;
+use crate::writer::{Writer, write_file_atomic};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use burn_std::{Bytes, DType, Shape};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Suffix appended to a container's path to name the index of its sharded form.
+pub const INDEX_SUFFIX: &str = "index.json";
+
+/// Where the index of the sharded container at `path` lives: `<path>.index.json`.
+pub fn index_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut name = path.as_ref().as_os_str().to_os_string();
+    name.push(".");
+    name.push(INDEX_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Whether `path` names a shard index rather than a container.
+pub(crate) fn is_index(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(&format!(".{INDEX_SUFFIX}")))
+}
+
+/// The index of a sharded container, serialized as JSON next to its shards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardIndex {
+    /// User key/value metadata, also stored in every shard.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Typed scalars, also stored in every shard.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub scalars: BTreeMap<String, Scalar>,
+    /// Total size of all shards, in bytes.
+    pub total_size: u64,
+    /// The shard files, in order.
+    pub shards: Vec<ShardEntry>,
+    /// Every tensor, by name, with the shard holding it.
+    pub tensors: BTreeMap<String, ShardTensor>,
+}
+
+/// A shard file listed in a [`ShardIndex`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardEntry {
+    /// File name of the shard, relative to the index's directory.
+    pub file: String,
+    /// Size of the shard file, in bytes.
+    pub size: u64,
+    /// Hex SHA-256 of the shard's manifest, when it was written with checksums.
+    ///
+    /// Together with the shard's own checksums, this is what lets a signature over the index
+    /// cover every shard.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_sha256: Option<String>,
+}
+
+/// A tensor listed in a [`ShardIndex`], described well enough to be handed out before its
+/// shard is opened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardTensor {
+    /// File name of the shard holding the tensor.
+    pub shard: String,
+    /// Data type of the tensor.
+    pub dtype: DType,
+    /// Tensor shape dimensions.
+    pub shape: Vec<u64>,
+    /// Number of bytes the tensor occupies.
+    pub byte_len: u64,
+    /// Parameter ID for training state persistence matching.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param_id: Option<u64>,
+}
+
+impl ShardIndex {
+    /// Whether every shard was written with checksums and is pinned by the index.
+    pub fn has_checksums(&self) -> bool {
+        !self.shards.is_empty()
+            && self
+                .shards
+                .iter()
+                .all(|shard| shard.manifest_sha256.is_some())
+    }
+}
+
+impl Writer {
+    /// Write the container as several shard files of at most `max_shard_size` bytes of tensor
+    /// data each, plus an index mapping every tensor to its shard.
+    ///
+    /// `path` is where the unsharded container would go (the canonical [`crate::EXTENSION`]
+    /// is appended if it has none); the index is written to [`index_path`] of it and the
+    /// shards beside it, as `<stem>-00001-of-0000N.<ext>`. Tensors are assigned to shards in
+    /// order, starting a new shard whenever the next tensor would exceed the cap; a tensor
+    /// larger than the cap gets a shard of its own.
+    ///
+    /// Every shard is an ordinary container carrying the writer's metadata, scalars and
+    /// checksum setting, written with [`write_to_file_atomic`](Self::write_to_file_atomic).
+    /// The index is written last, the same way, so a reader never sees an index whose
+    /// shards are not all in place. Shards left over from an earlier save with more shards
+    /// are not removed.
+    pub fn write_sharded<P: AsRef<Path>>(
+        self,
+        path: P,
+        max_shard_size: usize,
+    ) -> Result<ShardIndex, Error> {
+        if max_shard_size == 0 {
+            return Err(Error::ValidationError(
+                "the maximum shard size must be positive".into(),
+            ));
+        }
+
+        let path = Self::resolve_path(path.as_ref());
+        let Writer {
+            tensors,
+            metadata,
+            scalars,
+            checksums,
+        } = self;
+
+        // Assign tensors to shards, counting each at its aligned size as it will be laid out.
+        let mut groups: Vec<Vec<Tensor>> = Vec::new();
+        let mut current_size = 0usize;
+        for tensor in tensors {
+            let size = (tensor.byte_len() as u64).div_ceil(TENSOR_ALIGNMENT) as usize
+                * TENSOR_ALIGNMENT as usize;
+            match groups.last_mut() {
+                Some(group) if current_size.saturating_add(size) <= max_shard_size => {
+                    group.push(tensor);
+                    current_size += size;
+                }
+                _ => {
+                    groups.push(alloc::vec![tensor]);
+                    current_size = size;
+                }
+            }
+        }
+
+        let count = groups.len();
+        let mut index = ShardIndex {
+            metadata: metadata.clone(),
+            scalars: scalars.clone(),
+            total_size: 0,
+            shards: Vec::with_capacity(count),
+            tensors: BTreeMap::new(),
+        };
+
+        for (position, group) in groups.into_iter().enumerate() {
+            let file = shard_file_name(&path, position, count)?;
+
+            for tensor in &group {
+                let entry = ShardTensor {
+                    shard: file.clone(),
+                    dtype: tensor.dtype,
+                    shape: tensor.shape.iter().map(|&dim| dim as u64).collect(),
+                    byte_len: tensor.byte_len() as u64,
+                    param_id: tensor.param_id,
+                };
+                if index.tensors.insert(tensor.name.clone(), entry).is_some() {
+                    return Err(Error::ValidationError(format!(
+                        "Duplicate tensor name '{}'",
+                        tensor.name
+                    )));
+                }
+            }
+
+            let shard_path = path.with_file_name(&file);
+            Writer {
+                tensors: group,
+                metadata: metadata.clone(),
+                scalars: scalars.clone(),
+                checksums,
+            }
+            .write_to_file_atomic(&shard_path)?;
+
+            let size = std::fs::metadata(&shard_path)
+                .map_err(|e| {
+                    Error::IoError(format!("cannot stat '{}': {e}", shard_path.display()))
+                })?
+                .len();
+            let manifest_sha256 = checksums
+                .then(|| Reader::from_file(&shard_path))
+                .transpose()?
+                .map(|reader| Digest::of(reader.manifest()).to_string());
+
+            index.total_size += size;
+            index.shards.push(ShardEntry {
+                file,
+                size,
+                manifest_sha256,
+            });
+        }
+
+        let json = serde_json::to_vec_pretty(&index)
+            .map_err(|e| Error::MetadataSerializationError(e.to_string()))?;
+        write_file_atomic(&index_path(&path), &json)?;
+
+        Ok(index)
+    }
+}
+
+/// `<stem>-00001-of-00003.<ext>` for the shard at `position` of `count`.
+fn shard_file_name(path: &Path, position: usize, count: usize) -> Result<String, Error> {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| {
+            Error::IoError(format!(
+                "cannot shard '{}': not a UTF-8 file path",
+                path.display()
+            ))
+        })?;
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or(crate::EXTENSION);
+
+    Ok(format!(
+        "{stem}-{:05}-of-{count:05}.{extension}",
+        position + 1
+    ))
+}
+
+/// The shards of a sharded container, each opened on first use.
+pub(crate) struct ShardSet {
+    /// Directory holding the index and its shards.
+    dir: PathBuf,
+    pub(crate) index: ShardIndex,
+    /// Position of each shard in `index.shards`, by file name.
+    positions: BTreeMap<String, usize>,
+    /// Each shard's reader, in `index.shards` order, opened on first access.
+    readers: Vec<OnceLock<Reader>>,
+    /// Whether opened shards check their tensors' checksums.
+    pub(crate) verify_checksums: bool,
+}
+
+impl ShardSet {
+    /// Read and validate the index at `path`, returning the set and the raw index bytes.
+    ///
+    /// No shard is opened here.
+    pub(crate) fn open(path: &Path) -> Result<(Self, Vec<u8>), Error> {
+        let size = std::fs::metadata(path)
+            .map_err(|e| Error::IoError(format!("cannot open '{}': {e}", path.display())))?
+            .len();
+        if size > MAX_METADATA_SIZE as u64 {
+            return Err(Error::ValidationError(format!(
+                "Index size {size} exceeds maximum allowed size of {MAX_METADATA_SIZE} bytes (potential DoS attack)"
+            )));
+        }
+
+        let raw = std::fs::read(path)
+            .map_err(|e| Error::IoError(format!("cannot read '{}': {e}", path.display())))?;
+        let index: ShardIndex = serde_json::from_slice(&raw)
+            .map_err(|e| Error::MetadataDeserializationError(e.to_string()))?;
+
+        if index.tensors.len() > MAX_TENSOR_COUNT {
+            return Err(Error::ValidationError(format!(
+                "Index lists {} tensors, exceeding maximum of {MAX_TENSOR_COUNT} (potential DoS attack)",
+                index.tensors.len()
+            )));
+        }
+
+        let mut positions = BTreeMap::new();
+        for (position, shard) in index.shards.iter().enumerate() {
+            // Shards must sit next to the index: a name with a directory in it could point
+            // the reader anywhere on the filesystem.
+            if Path::new(&shard.file).file_name() != Some(shard.file.as_ref()) {
+                return Err(Error::ValidationError(format!(
+                    "Shard '{}' is not a plain file name",
+                    shard.file
+                )));
+            }
+            positions.insert(shard.file.clone(), position);
+        }
+        for (name, tensor) in &index.tensors {
+            if !positions.contains_key(&tensor.shard) {
+                return Err(Error::ValidationError(format!(
+                    "Tensor '{name}' is mapped to unknown shard '{}'",
+                    tensor.shard
+                )));
+            }
+        }
+
+        let dir = match path.parent() {
+            Some(parent) => parent.to_path_buf(),
+            None => PathBuf::from("."),
+        };
+        let readers = index.shards.iter().map(|_| OnceLock::new()).collect();
+
+        Ok((
+            Self {
+                dir,
+                index,
+                positions,
+                readers,
+                verify_checksums: true,
+            },
+            raw,
+        ))
+    }
+
+    /// The reader of the shard at `position`, opening it if this is its first use.
+    ///
+    /// A shard whose manifest does not hash to the digest the index recorded for it is
+    /// rejected before any of its tensors are read.
+    fn shard(&self, position: usize) -> Result<&Reader, Error> {
+        if let Some(reader) = self.readers[position].get() {
+            return Ok(reader);
+        }
+
+        let entry = &self.index.shards[position];
+        let reader =
+            Reader::from_file(self.dir.join(&entry.file))?.verify_checksums(self.verify_checksums);
+        if let Some(expected) = &entry.manifest_sha256 {
+            let actual = Digest::of(reader.manifest()).to_string();
+            if &actual != expected {
+                return Err(Error::ChecksumMismatch(format!(
+                    "shard '{}' manifest hashes to {actual}, expected {expected}",
+                    entry.file
+                )));
+            }
+        }
+
+        // Another thread may have opened it meanwhile; either reader will do.
+        let _ = self.readers[position].set(reader);
+        Ok(self.readers[position]
+            .get()
+            .expect("shard reader was just set"))
+    }
+
+    /// The reader of the shard holding tensor `name`.
+    fn shard_of(&self, name: &str) -> Result<&Reader, Error> {
+        let tensor = self
+            .index
+            .tensors
+            .get(name)
+            .ok_or_else(|| Error::TensorNotFound(name.to_string()))?;
+        self.shard(self.positions[&tensor.shard])
+    }
+
+    /// Copy a single tensor's raw bytes, opening only its shard.
+    pub(crate) fn tensor_data(&self, name: &str) -> Result<Vec<u8>, Error> {
+        self.shard_of(name)?.tensor_data(name)
+    }
+
+    /// Open every shard and check all of its checksums, and that each holds the tensors the
+    /// index maps to it.
+    pub(crate) fn verify(&self) -> Result<(), Error> {
+        for position in 0..self.readers.len() {
+            self.shard(position)?.verify()?;
+        }
+        for (name, tensor) in &self.index.tensors {
+            let shard = self.shard(self.positions[&tensor.shard])?;
+            // `tensor_names` is sorted.
+            if shard.tensor_names().binary_search(&name.as_str()).is_err() {
+                return Err(Error::TensorNotFound(format!(
+                    "{name} (expected in shard '{}')",
+                    tensor.shard
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Describe every tensor from the index alone, in sorted name order.
+    ///
+    /// Each tensor is [deferred](Tensor::deferred): the first access opens its shard, and
+    /// every access reads (and, if enabled, checks) just that tensor's bytes.
+    pub(crate) fn into_tensors(self) -> Result<Vec<Tensor>, Error> {
+        let set = Arc::new(self);
+
+        set.index
+            .tensors
+            .iter()
+            .map(|(name, tensor)| {
+                let shape = tensor
+                    .shape
+                    .iter()
+                    .map(|&dim| {
+                        usize::try_from(dim).map_err(|_| {
+                            Error::ValidationError(format!(
+                                "Tensor '{name}' has corrupted shape data: dimension {dim} exceeds platform maximum"
+                            ))
+                        })
+                    })
+                    .collect::<Result<Vec<usize>, Error>>()?;
+                let byte_len = usize::try_from(tensor.byte_len).map_err(|_| {
+                    Error::ValidationError(format!(
+                        "Tensor '{name}' size {} exceeds platform maximum",
+                        tensor.byte_len
+                    ))
+                })?;
+
+                let provider_set = set.clone();
+                let provider_name = name.clone();
+                Ok(Tensor::deferred(
+                    name.clone(),
+                    tensor.dtype,
+                    Shape::from(shape),
+                    tensor.param_id,
+                    byte_len,
+                    move || -> Result<Bytes, Error> {
+                        provider_set
+                            .shard_of(&provider_name)?
+                            .tensor_bytes(&provider_name)
+                    },
+                ))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,134 @@
+//! Detached ed25519 signatures over a burnpack manifest.
+//!
+//! A signature covers the raw CBOR metadata ([`Reader::manifest`](crate::Reader::manifest)),
+//! not the file. For a container written [with checksums](crate::Writer::with_checksums)
+//! that is enough: the metadata records the digest of every tensor and of the whole data
+//! section, so signing it pins every byte, while keeping the signature cheap to produce and
+//! to check no matter how large the model is.
+//!
+//! Signatures are detached. On disk they live in a sidecar file next to the container
+//! ([`signature_path`]), so signing never rewrites the container itself.
+
+use crate::base::Error;
+use alloc::format;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+/// Size of an ed25519 secret key, in bytes.
+pub const SECRET_KEY_SIZE: usize = 32;
+
+/// Size of an ed25519 public key, in bytes.
+pub const PUBLIC_KEY_SIZE: usize = 32;
+
+/// Size of an ed25519 signature, in bytes.
+pub const SIGNATURE_SIZE: usize = 64;
+
+/// Extension appended to a container's file name to locate its detached signature.
+#[cfg(feature = "std")]
+pub const SIGNATURE_EXTENSION: &str = "sig";
+
+/// The public key matching `secret_key`, to hand to whoever verifies the signatures.
+pub fn public_key(secret_key: &[u8; SECRET_KEY_SIZE]) -> [u8; PUBLIC_KEY_SIZE] {
+    SigningKey::from_bytes(secret_key)
+        .verifying_key()
+        .to_bytes()
+}
+
+/// Sign a manifest with an ed25519 `secret_key`.
+pub fn sign_manifest(manifest: &[u8], secret_key: &[u8; SECRET_KEY_SIZE]) -> [u8; SIGNATURE_SIZE] {
+    SigningKey::from_bytes(secret_key).sign(manifest).to_bytes()
+}
+
+/// Check a detached `signature` over `manifest` against `public_key`.
+///
+/// Prefer [`Reader::verify_signature`](crate::Reader::verify_signature), which also makes
+/// sure the manifest carries the checksums that extend the signature to the tensor data.
+pub fn verify_manifest(
+    manifest: &[u8],
+    signature: &[u8; SIGNATURE_SIZE],
+    public_key: &[u8; PUBLIC_KEY_SIZE],
+) -> Result<(), Error> {
+    let key = VerifyingKey::from_bytes(public_key)
+        .map_err(|e| Error::SignatureError(format!("invalid public key: {e}")))?;
+    key.verify(manifest, &Signature::from_bytes(signature))
+        .map_err(|_| Error::SignatureError("the manifest signature does not verify".into()))
+}
+
+/// Where the detached signature of the container at `path` lives: `<path>.sig`.
+#[cfg(feature = "std")]
+pub fn signature_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(SIGNATURE_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Sign the container at `path`, writing the signature to its [sidecar](signature_path).
+///
+/// The container must have been written with checksums. Returns the signature as well, for
+/// callers that distribute it some other way.
+#[cfg(feature = "std")]
+pub fn sign_file<P: AsRef<Path>>(
+    path: P,
+    secret_key: &[u8; SECRET_KEY_SIZE],
+) -> Result<[u8; SIGNATURE_SIZE], Error> {
+    let path = path.as_ref();
+    let reader = crate::Reader::from_file(path)?;
+    if !reader.has_checksums() {
+        return Err(Error::SignatureError(format!(
+            "'{}' has no checksums, so its manifest does not cover the tensor data",
+            path.display()
+        )));
+    }
+
+    let signature = sign_manifest(reader.manifest(), secret_key);
+    let sidecar = signature_path(path);
+    std::fs::write(&sidecar, signature)
+        .map_err(|e| Error::IoError(format!("cannot write '{}': {e}", sidecar.display())))?;
+
+    Ok(signature)
+}
+
+/// Read the detached signature of the container at `path` from its [sidecar](signature_path).
+#[cfg(feature = "std")]
+pub fn read_signature<P: AsRef<Path>>(path: P) -> Result<[u8; SIGNATURE_SIZE], Error> {
+    let sidecar = signature_path(path);
+    let bytes = std::fs::read(&sidecar)
+        .map_err(|e| Error::SignatureError(format!("cannot read '{}': {e}", sidecar.display())))?;
+
+    bytes.try_into().map_err(|bytes: alloc::vec::Vec<u8>| {
+        Error::SignatureError(format!(
+            "'{}' holds {} bytes, not a {SIGNATURE_SIZE}-byte signature",
+            sidecar.display(),
+            bytes.len()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_verifies_only_the_signed_manifest() {
+        let secret = [7u8; SECRET_KEY_SIZE];
+        let signature = sign_manifest(b"manifest", &secret);
+
+        verify_manifest(b"manifest", &signature, &public_key(&secret)).unwrap();
+        assert!(matches!(
+            verify_manifest(b"manifesT", &signature, &public_key(&secret)),
+            Err(Error::SignatureError(_))
+        ));
+        assert!(matches!(
+            verify_manifest(
+                b"manifest",
+                &signature,
+                &public_key(&[8u8; SECRET_KEY_SIZE])
+            ),
+            Err(Error::SignatureError(_))
+        ));
+    }
+}
@@ -111,7 +111,7 @@ impl Source {
 /// Construct one with [`new`](Self::new) when the data is already in memory, or
 /// [`deferred`](Self::deferred) when it is not. A [`Reader`](crate::Reader) produces resident
 /// tensors, though for a file-backed source the bytes are still only read from disk on
-/// access.
+/// access; tensors with a recorded checksum come back deferred, so the check runs on access.
 #[derive(Clone)]
 pub struct Tensor {
     /// Fully-qualified tensor name (e.g. `"encoder.layer1.weight"`).
@@ -224,9 +224,9 @@ impl Tensor {
 
     /// Take the tensor's raw little-endian bytes, producing them if deferred.
     ///
-    /// Infallible in practice for a tensor from a [`Reader`](crate::Reader), which only
-    /// produces resident tensors; the [`Result`] is there for deferred ones, whose provider
-    /// can fail.
+    /// Infallible in practice for a resident tensor; the [`Result`] is there for deferred
+    /// ones, whose provider can fail. That includes a checksummed tensor from a
+    /// [`Reader`](crate::Reader), whose bytes may not match their recorded digest.
     pub fn into_bytes(self) -> Result<Bytes, Error> {
         self.source.into_bytes()
     }
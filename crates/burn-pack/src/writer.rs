@@ -1,6 +1,6 @@
 use super::base::{
-    Error, FORMAT_VERSION, HEADER_SIZE, Header, MAGIC_NUMBER, Metadata, Scalar, TENSOR_ALIGNMENT,
-    TensorDescriptor, aligned_data_section_start,
+    Digest, Error, FORMAT_VERSION, HEADER_SIZE, Hasher, Header, MAGIC_NUMBER, Metadata, Scalar,
+    TENSOR_ALIGNMENT, TensorDescriptor, aligned_data_section_start,
 };
 use super::tensor::Tensor;
 use alloc::collections::BTreeMap;
@@ -13,7 +13,7 @@ use burn_std::{Bytes, DType, Shape};
 #[cfg(feature = "std")]
 use std::fs::File;
 #[cfg(feature = "std")]
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 #[cfg(feature = "std")]
 use std::path::Path;
 
@@ -51,6 +51,8 @@ pub struct Writer {
     pub(crate) metadata: BTreeMap<String, String>,
     /// Typed scalars keyed by name
     pub(crate) scalars: BTreeMap<String, Scalar>,
+    /// Record a SHA-256 digest per tensor and for the whole data section
+    pub(crate) checksums: bool,
 }
 
 impl Writer {
@@ -60,6 +62,7 @@ impl Writer {
             tensors,
             metadata: BTreeMap::new(),
             scalars: BTreeMap::new(),
+            checksums: false,
         }
     }
 
@@ -75,6 +78,19 @@ impl Writer {
         self
     }
 
+    /// Builder pattern: record integrity checksums and return self.
+    ///
+    /// When enabled, the metadata carries the SHA-256 of every tensor's bytes and of the whole
+    /// data section, which a [`Reader`](crate::Reader) checks when the data is accessed. The
+    /// digests are computed while the data streams out, so deferred tensors are still drawn
+    /// only once; the metadata is patched in place once the last tensor is written.
+    ///
+    /// Default: `false`, which writes exactly the format readers predating checksums expect.
+    pub fn with_checksums(mut self, enabled: bool) -> Self {
+        self.checksums = enabled;
+        self
+    }
+
     /// Calculate the total size needed for the burnpack data.
     ///
     /// This is useful when you want to pre-allocate a buffer for `write_into()`.
@@ -207,7 +223,7 @@ impl Writer {
 
     /// Append the canonical extension when the caller left one off.
     #[cfg(feature = "std")]
-    fn resolve_path(path: &Path) -> std::path::PathBuf {
+    pub(crate) fn resolve_path(path: &Path) -> std::path::PathBuf {
         if path.extension().is_none() {
             path.with_extension(crate::EXTENSION)
         } else {
@@ -217,17 +233,20 @@ impl Writer {
 
     /// Build the complete on-disk layout: header, serialized metadata, and the
     /// position and size of the (aligned) tensor data section.
+    ///
+    /// With checksums enabled, the digests are not known yet: the metadata is serialized with
+    /// placeholders of the same encoded size, which [`write_container`](Self::write_container)
+    /// overwrites once the data has been hashed.
     fn plan(&self) -> Result<Layout, Error> {
         let (tensors, placements, data_size) = self.build_descriptors()?;
         let metadata = Metadata {
             tensors,
             metadata: self.metadata.clone(),
             scalars: self.scalars.clone(),
+            data_digest: self.checksums.then(Digest::default),
         };
 
-        let mut metadata_bytes = Vec::new();
-        ciborium::ser::into_writer(&metadata, &mut metadata_bytes)
-            .map_err(|e| Error::MetadataSerializationError(e.to_string()))?;
+        let metadata_bytes = serialize_metadata(&metadata)?;
 
         let metadata_size: u32 = metadata_bytes.len().try_into().map_err(|_| {
             Error::IoError(format!(
@@ -246,6 +265,7 @@ impl Writer {
         let data_section_start = aligned_data_section_start(metadata_bytes.len());
 
         Ok(Layout {
+            metadata,
             metadata_bytes,
             placements,
             header,
@@ -294,6 +314,7 @@ impl Writer {
                         shape: shape.iter().map(|&s| s as u64).collect(),
                         data_offsets: (aligned_start, end),
                         param_id: tensor.param_id,
+                        checksum: self.checksums.then(Digest::default),
                     },
                 )
                 .is_some()
@@ -344,6 +365,9 @@ impl Writer {
 
     /// Emit the full container — header, metadata, alignment padding, then tensor data
     /// — into `sink`, which decides where the bytes ultimately land.
+    ///
+    /// With checksums enabled, the data is hashed on its way into `sink` and the placeholder
+    /// metadata is then rewritten with the real digests.
     fn write_container(self, layout: &Layout, sink: &mut impl Sink) -> Result<(), Error> {
         sink.write(&layout.header.into_bytes())?;
         sink.write(&layout.metadata_bytes)?;
@@ -354,7 +378,32 @@ impl Writer {
             sink.pad(layout.data_section_start - unaligned_data_start)?;
         }
 
-        self.write_tensors(&layout.placements, sink)
+        if !self.checksums {
+            return self.write_tensors(&layout.placements, sink, None);
+        }
+
+        let mut checksums = Checksums::default();
+        self.write_tensors(&layout.placements, sink, Some(&mut checksums))?;
+
+        let mut metadata = layout.metadata.clone();
+        for (name, digest) in checksums.tensors {
+            if let Some(descriptor) = metadata.tensors.get_mut(&name) {
+                descriptor.checksum = Some(digest);
+            }
+        }
+        metadata.data_digest = Some(checksums.section.finish());
+
+        let metadata_bytes = serialize_metadata(&metadata)?;
+        // Digests encode as fixed-size byte strings, so this only fails if that stops being
+        // true - and then the header's metadata size and every offset would be wrong.
+        if metadata_bytes.len() != layout.metadata_bytes.len() {
+            return Err(Error::MetadataSerializationError(format!(
+                "checksummed metadata is {} bytes but {} were reserved",
+                metadata_bytes.len(),
+                layout.metadata_bytes.len()
+            )));
+        }
+        sink.rewrite(HEADER_SIZE, &metadata_bytes)
     }
 
     /// Write each tensor's data into `sink`, inserting alignment padding between
@@ -363,7 +412,14 @@ impl Writer {
     /// `placements` was built by walking `self.tensors` in this same order, so zipping the
     /// two pairs each tensor with its own offset by construction, with no lookup to get out
     /// of step.
-    fn write_tensors(self, placements: &[Placement], sink: &mut impl Sink) -> Result<(), Error> {
+    ///
+    /// `checksums`, when given, is fed every byte of the data section, padding included.
+    fn write_tensors(
+        self,
+        placements: &[Placement],
+        sink: &mut impl Sink,
+        mut checksums: Option<&mut Checksums>,
+    ) -> Result<(), Error> {
         // The zip below would silently drop tensors if the two ever diverged in length, and
         // that is a corrupt container; one integer comparison per write buys a loud abort
         // (which the scratch-file guard turns into a clean one) in release builds too.
@@ -374,12 +430,20 @@ impl Writer {
 
         for (tensor, placement) in self.tensors.into_iter().zip(placements) {
             if placement.offset > data_offset {
-                sink.pad(placement.offset - data_offset)?;
+                let padding = placement.offset - data_offset;
+                sink.pad(padding)?;
+                if let Some(checksums) = checksums.as_deref_mut() {
+                    checksums.section.update_zeros(padding);
+                }
                 data_offset = placement.offset;
             }
 
             let data = Self::materialize(tensor, placement)?;
-            write_tensor_data(&data, sink)?;
+            write_tensor_data(&data, sink, checksums.as_deref_mut())?;
+            if let Some(checksums) = checksums.as_deref_mut() {
+                let digest = checksums.tensor.finish_reset();
+                checksums.tensors.push((placement.name.clone(), digest));
+            }
             data_offset += data.len();
         }
 
@@ -410,6 +474,25 @@ struct Placement {
     offset: usize,
 }
 
+/// Serialize the metadata blob to CBOR.
+fn serialize_metadata(metadata: &Metadata) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(metadata, &mut bytes)
+        .map_err(|e| Error::MetadataSerializationError(e.to_string()))?;
+    Ok(bytes)
+}
+
+/// Running digests of a container being written with checksums.
+#[derive(Default)]
+struct Checksums {
+    /// Everything written to the data section so far.
+    section: Hasher,
+    /// The tensor currently being written.
+    tensor: Hasher,
+    /// Digests of the tensors already written, by name.
+    tensors: Vec<(String, Digest)>,
+}
+
 /// Stream a single tensor's bytes into `sink`, materializing at most
 /// [`WRITE_CHUNK_SIZE`] bytes at a time.
 ///
@@ -423,22 +506,36 @@ struct Placement {
 /// Backings without a zero-copy window (e.g. a plain heap `Vec`) are already
 /// host-resident, so [`Bytes::view`] reports it can't window them and the
 /// remaining bytes are written in a single pass.
-fn write_tensor_data(data: &Bytes, sink: &mut impl Sink) -> Result<(), Error> {
+///
+/// Each chunk is also fed to `checksums`, if given, while it is still in hand.
+fn write_tensor_data(
+    data: &Bytes,
+    sink: &mut impl Sink,
+    mut checksums: Option<&mut Checksums>,
+) -> Result<(), Error> {
     let len = data.len();
     let mut offset = 0;
 
+    let mut write = |chunk: &[u8]| {
+        if let Some(checksums) = checksums.as_deref_mut() {
+            checksums.section.update(chunk);
+            checksums.tensor.update(chunk);
+        }
+        sink.write(chunk)
+    };
+
     while offset < len {
         let end = (offset + WRITE_CHUNK_SIZE).min(len);
         match data.view(offset, end) {
             Ok(chunk) => {
-                sink.write(&chunk)?;
+                write(&chunk)?;
                 offset = end;
             }
             // No zero-copy window available (already host-resident): write
             // whatever remains in one shot. View support is a property of the
             // backing, so this only ever happens on the first iteration.
             Err(_) => {
-                sink.write(&data[offset..])?;
+                write(&data[offset..])?;
                 break;
             }
         }
@@ -454,6 +551,8 @@ fn write_tensor_data(data: &Bytes, sink: &mut impl Sink) -> Result<(), Error> {
 /// via [`Writer::plan`] and shared by `size`, `write_into`, `to_bytes`, `write_to_file` and
 /// `write_to_file_atomic`.
 struct Layout {
+    /// The metadata as planned, with placeholder digests when checksums are enabled.
+    metadata: Metadata,
     metadata_bytes: Vec<u8>,
     /// Where each tensor's bytes go, in `Writer::tensors` order.
     placements: Vec<Placement>,
@@ -473,12 +572,16 @@ impl Layout {
 ///
 /// Padding and data are written in order; each implementation advances its own
 /// cursor, letting the writer stay agnostic about whether bytes land in a buffer
-/// or a file.
+/// or a file. The one exception is [`rewrite`](Sink::rewrite), which patches the
+/// checksummed metadata once the data has been hashed.
 trait Sink {
     /// Write `count` zero bytes of alignment padding.
     fn pad(&mut self, count: usize) -> Result<(), Error>;
     /// Write `data` verbatim.
     fn write(&mut self, data: &[u8]) -> Result<(), Error>;
+    /// Overwrite already-written bytes at absolute `offset` with `data`, leaving the
+    /// cursor where it was.
+    fn rewrite(&mut self, offset: usize, data: &[u8]) -> Result<(), Error>;
 }
 
 /// Sink that copies into a caller-provided buffer.
@@ -499,6 +602,20 @@ impl Sink for BufferSink<'_> {
         self.offset += data.len();
         Ok(())
     }
+
+    fn rewrite(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        self.buffer[offset..offset + data.len()].copy_from_slice(data);
+        Ok(())
+    }
+}
+
+/// Write `data` to `path` with the all-or-nothing guarantee of
+/// [`Writer::write_to_file_atomic`], for the files that accompany a container (a shard index).
+#[cfg(feature = "std")]
+pub(crate) fn write_file_atomic(path: &Path, data: &[u8]) -> Result<(), Error> {
+    let (scratch, mut sink) = ScratchFile::create(path)?;
+    sink.write(data)?;
+    scratch.persist(sink, path)
 }
 
 /// A scratch file next to the eventual destination, deleted unless it is persisted.
@@ -693,4 +810,13 @@ impl Sink for FileSink {
             .write_all(data)
             .map_err(|e| Error::IoError(format!("cannot write to '{}': {e}", self.path.display())))
     }
+
+    fn rewrite(&mut self, offset: usize, data: &[u8]) -> Result<(), Error> {
+        self.file
+            .seek(SeekFrom::Start(offset as u64))
+            .and_then(|_| self.file.write_all(data))
+            .and_then(|_| self.file.seek(SeekFrom::End(0)))
+            .map(|_| ())
+            .map_err(|e| Error::IoError(format!("cannot write to '{}': {e}", self.path.display())))
+    }
 }
@@ -0,0 +1,202 @@
+//! Integrity checksums and manifest signatures.
+
+mod common;
+
+use burn_pack::{Bytes, Digest, Error, Header, Reader, Writer, aligned_data_section_start};
+use common::{f32_tensor, read_f32};
+
+/// Two tensors with an alignment gap between them, written with or without checksums.
+fn packed(checksums: bool) -> Vec<u8> {
+    let bytes = Writer::new(vec![
+        f32_tensor("a", &[1.0, 2.0, 3.0], &[3], None),
+        f32_tensor("b", &[4.0, 5.0], &[2], Some(3)),
+    ])
+    .with_metadata("producer", "tests")
+    .with_checksums(checksums)
+    .into_bytes()
+    .unwrap();
+    bytes.to_vec()
+}
+
+/// Absolute offset of the data section of a packed container.
+fn data_start(packed: &[u8]) -> usize {
+    let header = Header::from_bytes(packed).unwrap();
+    aligned_data_section_start(header.metadata_size as usize)
+}
+
+fn reader(bytes: Vec<u8>) -> Reader {
+    Reader::from_bytes(Bytes::from_bytes_vec(bytes)).unwrap()
+}
+
+#[test]
+fn checksummed_container_round_trips() {
+    let reader = reader(packed(true));
+    assert!(reader.has_checksums());
+    reader.verify().unwrap();
+    assert_eq!(reader.metadata()["producer"], "tests");
+    let a = reader.tensor_data("a").unwrap();
+    assert_eq!(reader.tensor_checksum("a"), Some(Digest::of(&a)));
+
+    let tensors = reader.into_tensors().unwrap();
+    assert_eq!(read_f32(&tensors[0]), vec![1.0, 2.0, 3.0]);
+    assert_eq!(read_f32(&tensors[1]), vec![4.0, 5.0]);
+    assert_eq!(tensors[1].param_id, Some(3));
+}
+
+/// Checksums only grow the metadata: the data lands at the same offsets, so a container
+/// without them is exactly what every earlier writer produced.
+#[test]
+fn containers_without_checksums_still_read() {
+    let reader = reader(packed(false));
+    assert!(!reader.has_checksums());
+    reader.verify().unwrap();
+    assert_eq!(reader.tensor_checksum("a"), None);
+
+    let tensors = reader.into_tensors().unwrap();
+    assert_eq!(read_f32(&tensors[0]), vec![1.0, 2.0, 3.0]);
+}
+
+/// A flipped bit loads nothing quietly: the header and metadata still parse, so the failure
+/// must come from the tensor whose bytes are damaged, when they are accessed.
+#[test]
+fn corrupted_tensor_fails_on_access() {
+    let mut bytes = packed(true);
+    let start = data_start(&bytes);
+    bytes[start + 1] ^= 0x01;
+
+    let reader = reader(bytes.clone());
+    assert!(matches!(reader.verify(), Err(Error::ChecksumMismatch(_))));
+    assert!(matches!(
+        reader.tensor_data("a"),
+        Err(Error::ChecksumMismatch(m)) if m.contains("'a'")
+    ));
+    // The undamaged tensor is unaffected.
+    assert!(reader.tensor_data("b").is_ok());
+
+    let tensors = reader.into_tensors().unwrap();
+    assert!(matches!(
+        tensors[0].to_bytes(),
+        Err(Error::ChecksumMismatch(_))
+    ));
+    assert_eq!(read_f32(&tensors[1]), vec![4.0, 5.0]);
+
+    // Opting out reads the damaged bytes as they are.
+    let tensors = self::reader(bytes)
+        .verify_checksums(false)
+        .into_tensors()
+        .unwrap();
+    assert_ne!(read_f32(&tensors[0]), vec![1.0, 2.0, 3.0]);
+}
+
+/// Bytes outside every tensor are still covered by the data-section digest.
+#[test]
+fn corrupted_padding_fails_full_verification() {
+    let mut bytes = packed(true);
+    let start = data_start(&bytes);
+    // Tensor `a` takes 12 bytes; `b` starts at the next 256-byte boundary.
+    bytes[start + 100] = 0xFF;
+
+    let reader = reader(bytes);
+    assert!(reader.tensor_data("a").is_ok());
+    assert!(
+        matches!(reader.verify(), Err(Error::ChecksumMismatch(m)) if m.contains("data section"))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn checksummed_file_verifies_lazily() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.bpk");
+    Writer::new(vec![f32_tensor("w", &[1.0, 2.0], &[2], None)])
+        .with_checksums(true)
+        .write_to_file_atomic(&path)
+        .unwrap();
+
+    let reader = Reader::from_file(&path).unwrap();
+    reader.verify().unwrap();
+    let tensors = reader.into_tensors().unwrap();
+    assert_eq!(read_f32(&tensors[0]), vec![1.0, 2.0]);
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let start = data_start(&bytes);
+    bytes[start] ^= 0x80;
+    std::fs::write(&path, bytes).unwrap();
+
+    let tensors = Reader::from_file(&path).unwrap().into_tensors().unwrap();
+    assert!(matches!(
+        tensors[0].to_bytes(),
+        Err(Error::ChecksumMismatch(_))
+    ));
+}
+
+#[cfg(feature = "signature")]
+mod signature {
+    use super::*;
+    use burn_pack::{public_key, sign_manifest};
+
+    const SECRET: [u8; 32] = [42; 32];
+
+    #[test]
+    fn signed_manifest_verifies() {
+        let reader = reader(packed(true));
+        let signature = sign_manifest(reader.manifest(), &SECRET);
+
+        reader
+            .verify_signature(&signature, &public_key(&SECRET))
+            .unwrap();
+        assert!(matches!(
+            reader.verify_signature(&signature, &public_key(&[1; 32])),
+            Err(Error::SignatureError(_))
+        ));
+    }
+
+    /// Editing the manifest (here, a metadata value of the same length) breaks the signature.
+    #[test]
+    fn tampered_manifest_fails_signature() {
+        let bytes = packed(true);
+        let signature = sign_manifest(reader(bytes.clone()).manifest(), &SECRET);
+
+        let mut tampered = bytes;
+        let at = tampered
+            .windows(5)
+            .position(|window| window == b"tests")
+            .unwrap();
+        tampered[at..at + 5].copy_from_slice(b"evil!");
+
+        assert!(matches!(
+            reader(tampered).verify_signature(&signature, &public_key(&SECRET)),
+            Err(Error::SignatureError(_))
+        ));
+    }
+
+    /// Without checksums the manifest says nothing about the data, so a signature over it
+    /// must not be accepted as covering the container.
+    #[test]
+    fn signature_requires_checksums() {
+        let reader = reader(packed(false));
+        let signature = sign_manifest(reader.manifest(), &SECRET);
+
+        assert!(matches!(
+            reader.verify_signature(&signature, &public_key(&SECRET)),
+            Err(Error::SignatureError(_))
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn signature_sidecar_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.bpk");
+        std::fs::write(&path, packed(true)).unwrap();
+
+        let signature = burn_pack::sign_file(&path, &SECRET).unwrap();
+        assert!(dir.path().join("model.bpk.sig").exists());
+        assert_eq!(burn_pack::read_signature(&path).unwrap(), signature);
+
+        Reader::from_file(&path)
+            .unwrap()
+            .verify_signature(&signature, &public_key(&SECRET))
+            .unwrap();
+    }
+}
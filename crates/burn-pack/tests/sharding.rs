@@ -0,0 +1,139 @@
+//! Sharded containers: splitting under a size cap, the index, and lazy shard opening.
+
+#![cfg(feature = "std")]
+
+mod common;
+
+use burn_pack::{Error, Reader, Writer, index_path};
+use common::{f32_tensor, read_f32};
+
+/// Four 1 KiB tensors, so a 2 KiB cap puts two in each shard.
+fn writer() -> Writer {
+    let tensors = ["a", "b", "c", "d"]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let values: Vec<f32> = (0..256).map(|v| (i * 1000 + v) as f32).collect();
+            f32_tensor(name, &values, &[16, 16], Some(i as u64))
+        })
+        .collect();
+    Writer::new(tensors).with_metadata("producer", "tests")
+}
+
+#[test]
+fn tensors_are_split_under_the_cap() {
+    let dir = tempfile::tempdir().unwrap();
+    let index = writer()
+        .write_sharded(dir.path().join("model"), 2048)
+        .unwrap();
+
+    assert_eq!(index.shards.len(), 2);
+    assert_eq!(index.shards[0].file, "model-00001-of-00002.bpk");
+    assert_eq!(index.tensors["a"].shard, index.shards[0].file);
+    assert_eq!(index.tensors["d"].shard, index.shards[1].file);
+    for shard in &index.shards {
+        assert!(dir.path().join(&shard.file).exists());
+    }
+    assert!(dir.path().join("model.bpk.index.json").exists());
+    assert!(!dir.path().join("model.bpk").exists());
+}
+
+#[test]
+fn a_tensor_over_the_cap_gets_its_own_shard() {
+    let dir = tempfile::tempdir().unwrap();
+    let index = writer()
+        .write_sharded(dir.path().join("model"), 100)
+        .unwrap();
+
+    assert_eq!(index.shards.len(), 4);
+}
+
+/// Opening by the unsharded path and by the index path are the same thing.
+#[test]
+fn reader_opens_the_index_transparently() {
+    let dir = tempfile::tempdir().unwrap();
+    writer()
+        .write_sharded(dir.path().join("model"), 2048)
+        .unwrap();
+
+    for path in [
+        dir.path().join("model"),
+        dir.path().join("model.bpk"),
+        index_path(dir.path().join("model.bpk")),
+    ] {
+        let reader = Reader::from_file(&path).unwrap();
+        assert_eq!(reader.metadata()["producer"], "tests");
+        assert_eq!(reader.tensor_names(), vec!["a", "b", "c", "d"]);
+
+        let tensors = reader.into_tensors().unwrap();
+        assert_eq!(tensors.len(), 4);
+        assert_eq!(tensors[2].name, "c");
+        assert_eq!(tensors[2].shape.to_vec(), vec![16, 16]);
+        assert_eq!(tensors[2].param_id, Some(2));
+        assert_eq!(read_f32(&tensors[2])[..2], [2000.0, 2001.0]);
+    }
+}
+
+/// Only the shard holding a requested tensor is ever opened: deleting the other one goes
+/// unnoticed until one of its tensors is accessed.
+#[test]
+fn shards_are_opened_lazily() {
+    let dir = tempfile::tempdir().unwrap();
+    let index = writer()
+        .write_sharded(dir.path().join("model"), 2048)
+        .unwrap();
+    std::fs::remove_file(dir.path().join(&index.shards[1].file)).unwrap();
+
+    let reader = Reader::from_file(dir.path().join("model")).unwrap();
+    assert_eq!(reader.tensor_data("a").unwrap().len(), 1024);
+    assert!(matches!(reader.tensor_data("d"), Err(Error::IoError(_))));
+
+    let tensors = reader.into_tensors().unwrap();
+    assert_eq!(read_f32(&tensors[1])[0], 1000.0);
+    assert!(tensors[3].to_bytes().is_err());
+}
+
+/// With checksums, the index pins each shard's manifest, so swapping in a different (valid)
+/// shard is caught when it is opened.
+#[test]
+fn swapped_shard_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let index = writer()
+        .with_checksums(true)
+        .write_sharded(dir.path().join("model"), 2048)
+        .unwrap();
+
+    let reader = Reader::from_file(dir.path().join("model")).unwrap();
+    assert!(reader.has_checksums());
+    reader.verify().unwrap();
+
+    std::fs::copy(
+        dir.path().join(&index.shards[0].file),
+        dir.path().join(&index.shards[1].file),
+    )
+    .unwrap();
+
+    let reader = Reader::from_file(dir.path().join("model")).unwrap();
+    assert!(reader.tensor_data("a").is_ok());
+    assert!(matches!(
+        reader.tensor_data("c"),
+        Err(Error::ChecksumMismatch(_))
+    ));
+}
+
+#[test]
+fn index_rejects_shards_outside_its_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    writer()
+        .write_sharded(dir.path().join("model"), 2048)
+        .unwrap();
+
+    let path = index_path(dir.path().join("model.bpk"));
+    let json = std::fs::read_to_string(&path).unwrap();
+    std::fs::write(&path, json.replace("model-00002", "../model-00002")).unwrap();
+
+    assert!(matches!(
+        Reader::from_file(&path),
+        Err(Error::ValidationError(_))
+    ));
+}
@@ -0,0 +1,91 @@
+//! Watch a compute node from the terminal.
+//!
+//! ```sh
+//! burn-remote-monitor ws://localhost:3000 --token secret --record run.tlm
+//! burn-remote-monitor <endpoint-id>
+//! burn-remote-monitor --replay run.tlm --speed 4
+//! ```
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use burn_remote::monitor::{MonitorInput, MonitorTarget, run};
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(about = "Watch the sessions, throughput and bandwidth of a Burn Remote compute node.")]
+struct Args {
+    /// The node to monitor: a `ws://` or `wss://` address, or an Iroh endpoint id.
+    #[arg(required_unless_present = "replay", conflicts_with = "replay")]
+    target: Option<String>,
+
+    /// Credential handed to the node's authorizer.
+    #[arg(long, default_value = "")]
+    token: String,
+
+    /// Record the feed to this file, to replay it later.
+    #[arg(long, value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    /// Replay a recording instead of watching a node.
+    #[arg(long, value_name = "FILE", conflicts_with = "record")]
+    replay: Option<PathBuf>,
+
+    /// How many times faster than recorded a replay plays.
+    #[arg(long, default_value_t = 1.0, requires = "replay")]
+    speed: f64,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let args = Args::parse();
+    if args.speed <= 0.0 {
+        return Err("--speed must be positive".into());
+    }
+
+    let input = match (args.replay, args.target) {
+        (Some(path), _) => MonitorInput::Replay {
+            path,
+            speed: args.speed,
+        },
+        (None, Some(target)) => MonitorInput::Live {
+            target: resolve(&target, args.token.into_bytes()).await?,
+            record: args.record,
+        },
+        (None, None) => unreachable!("clap requires a target without --replay"),
+    };
+
+    run(input).await
+}
+
+async fn resolve(
+    target: &str,
+    authorization: Vec<u8>,
+) -> Result<MonitorTarget, Box<dyn Error + Send + Sync>> {
+    if target.starts_with("ws://") || target.starts_with("wss://") {
+        #[cfg(feature = "websocket")]
+        return Ok(MonitorTarget::websocket(target, authorization));
+        #[cfg(not(feature = "websocket"))]
+        return Err(
+            "this build has no WebSocket transport (enable the `websocket` feature)".into(),
+        );
+    }
+
+    #[cfg(feature = "iroh")]
+    {
+        use burn_remote::{Endpoint, EndpointAddr, EndpointId};
+        use iroh::endpoint::presets;
+
+        let peer: EndpointId = target.parse().map_err(|err| {
+            format!("`{target}` is neither a WebSocket address nor an Iroh endpoint id: {err}")
+        })?;
+        let endpoint = Endpoint::builder(presets::N0).bind().await?;
+        Ok(MonitorTarget::iroh(
+            &endpoint,
+            EndpointAddr::from(peer),
+            authorization,
+        ))
+    }
+    #[cfg(not(feature = "iroh"))]
+    Err(format!("`{target}` is not a WebSocket address").into())
+}
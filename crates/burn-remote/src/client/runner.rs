@@ -6,8 +6,6 @@ use burn_ir::TensorIr;
 use burn_router::{MultiBackendBridge, RouterClient, RouterTensor, get_client};
 use burn_std::DeviceSettings;
 use burn_std::{backtrace::BackTrace, future::DynFut};
-#[cfg(feature = "websocket")]
-use std::sync::Arc;
 use std::sync::Mutex;
 
 use super::runtime::Executor;
@@ -43,7 +41,16 @@ impl RouterClient for RemoteClient {
 
         Box::pin(async move {
             match rx.await {
-                Ok(TaskResponseContent::ReadTensor(res)) => res,
+                Ok(TaskResponseContent::ReadTensor(res)) => res.and_then(|payload| {
+                    payload.decode().map_err(|err| ExecutionError::Generic {
+                        reason: format!("Failed to decode tensor: {err}"),
+                        backtrace: BackTrace::capture(),
+                    })
+                }),
+                Ok(TaskResponseContent::QuotaExceeded(err)) => Err(ExecutionError::Generic {
+                    reason: format!("Failed to read tensor: {err}"),
+                    backtrace: BackTrace::capture(),
+                }),
                 Ok(_) => panic!("Invalid response type for ReadTensor"),
                 Err(e) => Err(ExecutionError::Generic {
                     reason: format!("Failed to read tensor: {e:?}"),
@@ -179,10 +186,53 @@ impl RemoteDevice {
     /// Create a legacy WebSocket device from a URL.
     #[cfg(feature = "websocket")]
     pub fn websocket(address: &str, device_index: usize) -> Self {
-        let endpoint = RemoteEndpoint::WebSocket {
-            address: burn_communication::Address::from(address),
-            authorization: Arc::from([]),
-        };
+        Self::websocket_authorized(address, device_index, Vec::new())
+    }
+
+    /// Like [`websocket`](Self::websocket), but carries an authorization credential (e.g. a
+    /// token) the server's PeerAuthorizer will check.
+    ///
+    /// A plain `ws` address sends the credential in the clear; prefer a `wss` address on
+    /// untrusted networks.
+    #[cfg(feature = "websocket")]
+    pub fn websocket_authorized(
+        address: &str,
+        device_index: usize,
+        authorization: Vec<u8>,
+    ) -> Self {
+        Self::from_websocket_endpoint(
+            RemoteEndpoint::WebSocket {
+                address: burn_communication::Address::from(address),
+                authorization: authorization.into(),
+                #[cfg(feature = "websocket-tls")]
+                tls: None,
+            },
+            device_index,
+        )
+    }
+
+    /// Like [`websocket_authorized`](Self::websocket_authorized), but connects to a `wss` address
+    /// with the given TLS configuration: its authorities verify the server, and its client
+    /// certificate, if any, authenticates this client to a server requiring mutual TLS.
+    #[cfg(feature = "websocket-tls")]
+    pub fn websocket_tls(
+        address: &str,
+        device_index: usize,
+        authorization: Vec<u8>,
+        tls: burn_communication::websocket::TlsClientConfig,
+    ) -> Self {
+        Self::from_websocket_endpoint(
+            RemoteEndpoint::WebSocket {
+                address: burn_communication::Address::from(address),
+                authorization: authorization.into(),
+                tls: Some(tls),
+            },
+            device_index,
+        )
+    }
+
+    #[cfg(feature = "websocket")]
+    fn from_websocket_endpoint(endpoint: RemoteEndpoint, device_index: usize) -> Self {
         let device_index = device_index as u32;
         let id = service::register_endpoint(endpoint.clone(), Executor::capture(), device_index);
         Self {
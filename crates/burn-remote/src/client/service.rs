@@ -1,9 +1,12 @@
 use crate::metrics::{MetricSide, TelemetryLogger, logger_task};
 use crate::shared::{
-    LocalTransferId, PROTOCOL_VERSION, RemoteMessage, RequestId, SessionId, SessionInfo,
-    SessionInit, Task, TaskResponse, TaskResponseContent, TensorRemote, TransferCapability,
+    LocalTransferId, PROTOCOL_VERSION, PayloadEncoding, PayloadOffer, RemoteMessage, RequestId,
+    SessionId, SessionInfo, SessionInit, SessionResume, Task, TaskResponse, TaskResponseContent,
+    TensorPayload, TensorRemote, TransferCapability,
+};
+use crate::telemetry::{
+    CHANNEL_CAPACITY, PayloadRoute, TelemetryEvent, TelemetryProbe, serialized_len,
 };
-use crate::telemetry::{CHANNEL_CAPACITY, TelemetryEvent, TelemetryProbe, serialized_len};
 use burn_backend::{
     DTypeUsageSet, ExecutionError, TensorData,
     backend::{DeviceId, DeviceService, ServerUtilitiesHandle},
@@ -20,15 +23,19 @@ mod batch;
 mod conn;
 mod pending;
 mod registry;
+mod resume;
 mod writer;
 
 use batch::OutgoingBatch;
-use conn::{ResponseChannel, open_channels};
+use conn::open_channels;
 use pending::{PendingResponses, Responder};
+use resume::{Reconnect, Resend};
 use writer::SubmitWriter;
 
 use super::runtime::Executor;
-pub(crate) use conn::{RemoteEndpoint, SubmitChannel};
+#[cfg(feature = "monitor")]
+pub(crate) use conn::open_telemetry_channels;
+pub(crate) use conn::{RemoteEndpoint, ResponseChannel, SubmitChannel};
 use registry::{device_count_cell, executor_for, settings_cell};
 pub(crate) use registry::{device_count_for, has_settings, new_tensor_id, settings_for};
 pub(crate) use registry::{endpoint_for, register_endpoint};
@@ -75,6 +82,8 @@ pub struct RemoteService {
     settings: Arc<OnceLock<DeviceSettings>>,
     /// Shared cell populated from the init handshake (read by `RemoteDevice::enumerate`).
     device_count: Arc<OnceLock<u32>>,
+    /// How tensor payloads are encoded, settled by the init handshake.
+    payload: PayloadEncoding,
     session_id: SessionId,
     closed: bool,
 }
@@ -116,6 +125,7 @@ impl DeviceService for RemoteService {
             probe,
             settings: settings_cell(id),
             device_count: device_count_cell(id),
+            payload: PayloadEncoding::default(),
             session_id,
             closed: false,
         }
@@ -157,54 +167,57 @@ impl RemoteService {
     /// Send the session-init handshake on both streams and wait for the device settings the
     /// server replies with on the response stream. Both streams carry the same `Vec<RemoteMessage>`
     /// wire format; the handshake is just a single-element batch.
+    ///
+    /// The session asks to outlive a dropped link; the server grants it when configured to (see
+    /// [`resume`]). It also offers the payload encoding configured under `[remote.payload]`.
     async fn handshake_async(
         request: &mut SubmitChannel,
         response: &mut ResponseChannel,
         endpoint: &RemoteEndpoint,
         session_id: SessionId,
         device_index: u32,
-    ) -> (DeviceSettings, u32) {
-        let init_bytes: bytes::Bytes = rmp_serde::to_vec(&vec![RemoteMessage::Init(
-            SessionInit::new(session_id, device_index, endpoint.authorization().to_vec()),
-        )])
-        .expect("Can serialize RemoteMessage::Init")
-        .into();
-
-        let result: Result<(DeviceSettings, u32), String> = async {
-            request.send(init_bytes).await?;
-
-            let msg = response
-                .recv()
-                .await?
-                .expect("Server disconnected during initialization");
-            let reply: TaskResponse =
-                rmp_serde::from_slice(&msg).expect("Can deserialize init handshake payload");
-
-            match reply.content {
-                TaskResponseContent::Init(SessionInfo {
-                    version,
-                    settings,
-                    device_count,
-                    ..
-                }) => {
-                    if version != PROTOCOL_VERSION {
-                        panic!(
-                            "Server uses Burn Remote protocol version {version}, expected {PROTOCOL_VERSION}"
-                        );
-                    }
-                    Ok((settings, device_count))
-                }
-                other => panic!("Expected Init response, got {other:?}"),
-            }
-        }
-        .await;
+    ) -> SessionInfo {
+        let init = SessionInit::new(session_id, device_index, endpoint.authorization().to_vec())
+            .with_resume(SessionResume::Resumable)
+            .with_payload(PayloadOffer::from_config());
+
+        Self::init_session(request, response, init)
+            .await
+            .unwrap_or_else(|err| {
+                panic!(
+                    "Failed to initialize remote session at {}: {err}",
+                    endpoint.peer_addr()
+                )
+            })
+    }
 
-        result.unwrap_or_else(|err| {
-            panic!(
-                "Failed to initialize remote session at {}: {err}",
-                endpoint.peer_addr()
-            )
-        })
+    /// Send `init` and read the server's [`SessionInfo`] back, for a new session or a resumed one.
+    async fn init_session(
+        request: &mut SubmitChannel,
+        response: &mut ResponseChannel,
+        init: SessionInit,
+    ) -> Result<SessionInfo, String> {
+        let init_bytes: bytes::Bytes = rmp_serde::to_vec(&vec![RemoteMessage::Init(init)])
+            .expect("Can serialize RemoteMessage::Init")
+            .into();
+        request.send(init_bytes).await?;
+
+        let msg = response
+            .recv()
+            .await?
+            .ok_or_else(|| "Server disconnected during initialization".to_string())?;
+        let reply: TaskResponse = rmp_serde::from_slice(&msg)
+            .map_err(|err| format!("Invalid init handshake payload: {err}"))?;
+
+        match reply.content {
+            TaskResponseContent::Init(info) if info.version != PROTOCOL_VERSION => Err(format!(
+                "Server uses Burn Remote protocol version {}, expected {PROTOCOL_VERSION}",
+                info.version
+            )),
+            TaskResponseContent::Init(info) => Ok(info),
+            TaskResponseContent::QuotaExceeded(err) => Err(err.to_string()),
+            other => Err(format!("Expected Init response, got {other:?}")),
+        }
     }
 
     /// Native synchronous wrapper over [`handshake_async`](Self::handshake_async).
@@ -216,7 +229,7 @@ impl RemoteService {
         endpoint: &RemoteEndpoint,
         session_id: SessionId,
         device_index: u32,
-    ) -> (DeviceSettings, u32) {
+    ) -> SessionInfo {
         executor.block_on(Self::handshake_async(
             request,
             response,
@@ -229,15 +242,26 @@ impl RemoteService {
     /// Spawn the response-demux task: route each [`TaskResponse`] to its pending callback by
     /// [`RequestId`] via the [`Responder`]. Lives on the service runtime; exits when the
     /// response stream closes.
+    ///
+    /// With `reconnect`, the session is resumable: when its link drops, the demux resumes it on a
+    /// new one and carries on from there.
     fn spawn_response_demux(
         executor: &Executor,
         mut response: ResponseChannel,
         responder: Responder,
+        mut reconnect: Option<Reconnect>,
     ) {
         // Detached: the task owns the response stream and runs until it closes.
         let _demux = executor.spawn(async move {
             loop {
-                match response.recv().await {
+                let received = match reconnect.as_ref() {
+                    Some(reconnect) => tokio::select! {
+                        received = response.recv() => received,
+                        () = reconnect.send_failed() => Err("submit stream failed".to_string()),
+                    },
+                    None => response.recv().await,
+                };
+                match received {
                     Ok(Some(msg)) => {
                         let reply: TaskResponse = match rmp_serde::from_slice(&msg) {
                             Ok(r) => r,
@@ -246,8 +270,29 @@ impl RemoteService {
                                 continue;
                             }
                         };
-                        if !responder.complete(reply.id, reply.content) {
-                            log::warn!("No pending callback for response id {:?}", reply.id);
+                        match reply.content {
+                            TaskResponseContent::Acknowledge(received) => {
+                                if let Some(reconnect) = &reconnect {
+                                    reconnect.frames_acknowledged(received);
+                                }
+                            }
+                            TaskResponseContent::QuotaExceeded(err) => {
+                                // The server closed the session; it won't answer anything else.
+                                log::error!("Remote session closed: {err}");
+                                responder.fail(err);
+                                break;
+                            }
+                            content => {
+                                if let Some(reconnect) = &reconnect {
+                                    reconnect.response_received();
+                                }
+                                if !responder.complete(reply.id, content) {
+                                    log::warn!(
+                                        "No pending callback for response id {:?}",
+                                        reply.id
+                                    );
+                                }
+                            }
                         }
                     }
                     Ok(None) => {
@@ -255,8 +300,18 @@ impl RemoteService {
                         break;
                     }
                     Err(err) => {
-                        log::warn!("Remote response stream error: {err:?}");
-                        break;
+                        let Some(reconnect) = reconnect.as_mut() else {
+                            log::warn!("Remote response stream error: {err:?}");
+                            break;
+                        };
+                        log::warn!("Remote session link dropped ({err}); resuming");
+                        match reconnect.resume(response).await {
+                            Ok(resumed) => response = resumed,
+                            Err(err) => {
+                                log::error!("Failed to resume remote session: {err}");
+                                break;
+                            }
+                        }
                     }
                 }
             }
@@ -267,6 +322,24 @@ impl RemoteService {
             responder.disconnect();
         });
     }
+
+    /// Split the session into its demux and writer halves for resumption, if the server granted
+    /// it (see [`resume`]).
+    fn resumption(
+        endpoint: &RemoteEndpoint,
+        session_id: SessionId,
+        device_index: u32,
+        info: &SessionInfo,
+    ) -> (Option<Reconnect>, Option<Resend>) {
+        match info.resume.clone() {
+            Some(grant) => {
+                let (reconnect, resend) =
+                    resume::resumable(endpoint.clone(), session_id, device_index, grant);
+                (Some(reconnect), Some(resend))
+            }
+            None => (None, None),
+        }
+    }
 }
 
 /// Session state captured from a [`RemoteService`] to drive an asynchronous (wasm) connect.
@@ -284,6 +357,7 @@ pub(crate) struct WasmConnected {
     writer: SubmitWriter,
     settings: DeviceSettings,
     device_count: u32,
+    payload: PayloadEncoding,
 }
 
 /// Open and hand-shake a session on the browser event loop.
@@ -299,7 +373,7 @@ pub(crate) async fn wasm_connect(plan: WasmConnectPlan) -> WasmConnected {
     let (mut request, mut response) = open_channels(&plan.endpoint)
         .await
         .unwrap_or_else(|err| panic!("{err}"));
-    let (settings, device_count) = RemoteService::handshake_async(
+    let info = RemoteService::handshake_async(
         &mut request,
         &mut response,
         &plan.endpoint,
@@ -308,13 +382,17 @@ pub(crate) async fn wasm_connect(plan: WasmConnectPlan) -> WasmConnected {
     )
     .await;
 
-    RemoteService::spawn_response_demux(&executor, response, plan.responder);
-    let writer = SubmitWriter::spawn(&executor, request);
+    let (reconnect, resend) =
+        RemoteService::resumption(&plan.endpoint, plan.session_id, plan.device_index, &info);
+
+    RemoteService::spawn_response_demux(&executor, response, plan.responder, reconnect);
+    let writer = SubmitWriter::spawn(&executor, request, resend);
 
     WasmConnected {
         writer,
-        settings,
-        device_count,
+        settings: info.settings,
+        device_count: info.device_count,
+        payload: info.payload,
     }
 }
 
@@ -384,7 +462,12 @@ impl RemoteService {
     }
 
     pub fn register_tensor(&mut self, stream_id: StreamId, id: TensorId, data: TensorData) {
-        self.submit_task(Task::RegisterTensor(stream_id, id, data));
+        // The payload is encoded as the handshake settled, so the session must be up first.
+        self.ensure_connected();
+        let payload = TensorPayload::encode(data, self.payload);
+        self.probe
+            .payload(self.session_id, PayloadRoute::Upload, &payload);
+        self.submit_task(Task::RegisterTensor(stream_id, id, payload));
         self.flush();
     }
 
@@ -497,6 +580,10 @@ impl RemoteService {
             let rx = self.submit_request(|id| Task::SyncBackend(id, stream_id));
             match self.executor.block_on(rx) {
                 Ok(TaskResponseContent::SyncBackend(res)) => res,
+                Ok(TaskResponseContent::QuotaExceeded(err)) => Err(ExecutionError::Generic {
+                    reason: err.to_string(),
+                    backtrace: BackTrace::capture(),
+                }),
                 Ok(other) => panic!("Invalid response for SyncBackend: {other:?}"),
                 Err(_) => Err(ExecutionError::Generic {
                     reason: "Remote response channel closed before sync completed".into(),
@@ -520,6 +607,7 @@ impl RemoteService {
         let rx = self.submit_request(|id| Task::DTypeUsage(id, dtype));
         match self.executor.block_on(rx) {
             Ok(TaskResponseContent::DTypeUsage(set)) => set,
+            Ok(TaskResponseContent::QuotaExceeded(err)) => panic!("Remote session closed: {err}"),
             Ok(other) => panic!("Invalid response for DTypeUsage: {other:?}"),
             Err(_) => panic!("Remote response channel closed before dtype_usage completed"),
         }
@@ -586,7 +674,7 @@ impl RemoteService {
                 self.device_index
             );
             let (mut request, mut response) = Self::connect_streams(&self.executor, &self.endpoint);
-            let (settings, device_count) = Self::handshake(
+            let info = Self::handshake(
                 &self.executor,
                 &mut request,
                 &mut response,
@@ -594,13 +682,21 @@ impl RemoteService {
                 self.session_id,
                 self.device_index,
             );
+            let (reconnect, resend) =
+                Self::resumption(&self.endpoint, self.session_id, self.device_index, &info);
 
             // Publish to the shared cells so `RemoteDevice::defaults`/`enumerate` can read them.
-            let _ = self.settings.set(settings);
-            let _ = self.device_count.set(device_count);
+            let _ = self.settings.set(info.settings);
+            let _ = self.device_count.set(info.device_count);
+            self.payload = info.payload;
 
-            Self::spawn_response_demux(&self.executor, response, self.pending.responder());
-            self.writer = Some(SubmitWriter::spawn(&self.executor, request));
+            Self::spawn_response_demux(
+                &self.executor,
+                response,
+                self.pending.responder(),
+                reconnect,
+            );
+            self.writer = Some(SubmitWriter::spawn(&self.executor, request, resend));
         }
 
         #[cfg(target_family = "wasm")]
@@ -639,6 +735,7 @@ impl RemoteService {
         }
         let _ = self.settings.set(connected.settings);
         let _ = self.device_count.set(connected.device_count);
+        self.payload = connected.payload;
         self.writer = Some(connected.writer);
     }
 
@@ -26,11 +26,11 @@ pub(crate) struct OutgoingBatch {
     bytes_threshold: usize,
 }
 
-/// Size of the bulk tensor data a message carries, in bytes (0 for metadata-only messages). Drives
-/// the byte-based flush threshold.
+/// Size of the bulk tensor data a message carries on the wire, in bytes (0 for metadata-only
+/// messages). Drives the byte-based flush threshold.
 fn data_len(msg: &RemoteMessage) -> usize {
     match msg {
-        RemoteMessage::Task(Task::RegisterTensor(_, _, data)) => data.bytes.len(),
+        RemoteMessage::Task(Task::RegisterTensor(_, _, payload)) => payload.wire_len(),
         _ => 0,
     }
 }
@@ -69,7 +69,7 @@ impl OutgoingBatch {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::shared::Task;
+    use crate::shared::{Task, TensorPayload};
     use burn_backend::{StreamId, TensorData};
     use burn_ir::TensorId;
 
@@ -83,7 +83,7 @@ mod tests {
         RemoteMessage::Task(Task::RegisterTensor(
             StreamId::current(),
             TensorId::new(0),
-            TensorData::new(vec![0u8; n], [n]),
+            TensorPayload::Raw(TensorData::new(vec![0u8; n], [n])),
         ))
     }
 
@@ -28,6 +28,10 @@ pub(crate) enum RemoteEndpoint {
     WebSocket {
         address: Address,
         authorization: Arc<[u8]>,
+        /// Verifies `wss` servers against private authorities, and presents the client certificate
+        /// under mutual TLS. `None` uses the bundled web PKI roots.
+        #[cfg(feature = "websocket-tls")]
+        tls: Option<burn_communication::websocket::TlsClientConfig>,
     },
 }
 
@@ -145,32 +149,83 @@ impl ResponseChannel {
     }
 }
 
+/// What a link opened to a compute peer carries.
+#[derive(Clone, Copy)]
+enum LinkKind {
+    Session,
+    #[cfg_attr(not(feature = "monitor"), allow(dead_code))]
+    Telemetry,
+}
+
+impl LinkKind {
+    #[cfg(feature = "iroh")]
+    fn stream(self) -> crate::transport::iroh::node::StreamKind {
+        use crate::transport::iroh::node::StreamKind;
+        match self {
+            Self::Session => StreamKind::Session,
+            Self::Telemetry => StreamKind::Telemetry,
+        }
+    }
+
+    #[cfg(feature = "websocket")]
+    fn route(self) -> &'static str {
+        match self {
+            Self::Session => "session",
+            Self::Telemetry => "telemetry",
+        }
+    }
+}
+
 /// Open the session for `endpoint`, returning its submit + response halves.
 ///
 /// Done up front so a missing server surfaces here rather than on the first op, and the demux /
 /// writer tasks can be spawned on already-open streams.
 pub(crate) async fn open_channels(
     endpoint: &RemoteEndpoint,
+) -> Result<(SubmitChannel, ResponseChannel), String> {
+    open_link(endpoint, LinkKind::Session).await
+}
+
+/// Open a telemetry feed from the compute peer at `endpoint`, for a monitor.
+#[cfg(feature = "monitor")]
+pub(crate) async fn open_telemetry_channels(
+    endpoint: &RemoteEndpoint,
+) -> Result<(SubmitChannel, ResponseChannel), String> {
+    open_link(endpoint, LinkKind::Telemetry).await
+}
+
+async fn open_link(
+    endpoint: &RemoteEndpoint,
+    kind: LinkKind,
 ) -> Result<(SubmitChannel, ResponseChannel), String> {
     match endpoint {
         #[cfg(feature = "iroh")]
         RemoteEndpoint::Iroh { node, peer, .. } => {
             let (send, recv) = node
-                .open_stream(
-                    &PeerAddr::Iroh(peer.clone()),
-                    crate::transport::iroh::node::StreamKind::Session,
-                )
+                .open_stream(&PeerAddr::Iroh(peer.clone()), kind.stream())
                 .await?;
             Ok((SubmitChannel::Iroh(send), ResponseChannel::Iroh(recv)))
         }
         #[cfg(feature = "websocket")]
-        RemoteEndpoint::WebSocket { address, .. } => {
+        RemoteEndpoint::WebSocket {
+            address,
+            #[cfg(feature = "websocket-tls")]
+            tls,
+            ..
+        } => {
             use burn_communication::websocket::WsClient;
             // One full-duplex socket per session, split into the submit (sink) + response (source)
             // halves — matching the Iroh single-stream model.
-            let channel = WsClient::connect(address.clone(), "session")
+            let route = kind.route();
+            let connect = WsClient::connect(address.clone(), route);
+            #[cfg(feature = "websocket-tls")]
+            let connect = match tls {
+                Some(tls) => WsClient::connect_tls(address.clone(), route, tls),
+                None => connect,
+            };
+            let channel = connect
                 .await
-                .map_err(|err| connect_error("session", &endpoint.peer_addr(), &err))?;
+                .map_err(|err| connect_error(route, &endpoint.peer_addr(), &err))?;
             let (sink, source) = channel.split();
             Ok((
                 SubmitChannel::WebSocket(Box::new(sink)),
@@ -1,6 +1,6 @@
 //! Request/response correlation.
 
-use crate::shared::{RequestId, TaskResponseContent};
+use crate::shared::{QuotaExceeded, RequestId, TaskResponseContent};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
@@ -18,6 +18,9 @@ struct State {
     /// Cleared by [`Responder::disconnect`] once the response stream is gone; gates new
     /// registrations so a post-disconnect request fails fast instead of parking forever.
     connected: bool,
+    /// Set by [`Responder::fail`] when the server closed the session over a quota; later
+    /// registrations get it back right away.
+    failure: Option<QuotaExceeded>,
 }
 
 type SharedState = Arc<Mutex<State>>;
@@ -43,6 +46,7 @@ impl PendingResponses {
             state: Arc::new(Mutex::new(State {
                 callbacks: HashMap::new(),
                 connected: true,
+                failure: None,
             })),
             next_id: 0,
         }
@@ -59,11 +63,14 @@ impl PendingResponses {
     ///
     /// If the connection has already dropped (the demux task called [`Responder::disconnect`]),
     /// the sender is dropped immediately, so the returned receiver resolves to a `RecvError` right
-    /// away rather than blocking on a response the dead server will never send.
+    /// away rather than blocking on a response the dead server will never send. If the server
+    /// closed the session over a quota, the receiver gets that error instead.
     pub(crate) fn register(&self, id: RequestId) -> oneshot::Receiver<TaskResponseContent> {
         let (tx, rx) = oneshot::channel();
         let mut state = self.state.lock().unwrap();
-        if state.connected {
+        if let Some(error) = &state.failure {
+            let _ = tx.send(TaskResponseContent::QuotaExceeded(error.clone()));
+        } else if state.connected {
             state.callbacks.insert(id, tx);
         }
         // Disconnected: drop `tx` here, leaving `rx` already-closed.
@@ -111,6 +118,17 @@ impl Responder {
         state.connected = false;
         state.callbacks.clear();
     }
+
+    /// The server closed the session because it went over a quota: hand `error` to every pending
+    /// caller, and to every later one, so they report why instead of a bare disconnect.
+    pub(crate) fn fail(&self, error: QuotaExceeded) {
+        let mut state = self.state.lock().unwrap();
+        state.connected = false;
+        for (_, tx) in state.callbacks.drain() {
+            let _ = tx.send(TaskResponseContent::QuotaExceeded(error.clone()));
+        }
+        state.failure = Some(error);
+    }
 }
 
 #[cfg(test)]
@@ -198,4 +216,33 @@ mod tests {
         // And it was never inserted, so a late response for it finds nothing.
         assert!(!pending.responder().complete(id, content()));
     }
+
+    #[test]
+    fn fail_reports_the_quota_to_pending_and_later_callers() {
+        use crate::shared::{QuotaResource, QuotaScope};
+
+        let error = QuotaExceeded {
+            scope: QuotaScope::Session,
+            resource: QuotaResource::ResidentBytes,
+            limit: 1024,
+            requested: 2048,
+        };
+        let mut pending = PendingResponses::new();
+        let id = pending.next_id();
+        let mut rx = pending.register(id);
+
+        pending.responder().fail(error.clone());
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(TaskResponseContent::QuotaExceeded(e)) if e == error
+        ));
+
+        // A request issued afterwards gets the same error instead of a bare disconnect.
+        let id = pending.next_id();
+        let mut rx = pending.register(id);
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(TaskResponseContent::QuotaExceeded(e)) if e == error
+        ));
+    }
 }
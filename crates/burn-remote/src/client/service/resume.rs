@@ -0,0 +1,232 @@
+//! Resuming a session after its link drops.
+//!
+//! A server started with a resume grace answers the handshake with a [`ResumeGrant`], and keeps
+//! the session for that long after its link drops. Both sides keep what they send until the other
+//! acknowledges it, so nothing sent across the drop is lost: this side keeps its frames in a
+//! [`SentLog`], and counts the responses it receives.
+//!
+//! The response demux owns reconnecting, through its [`Reconnect`] half: when the response stream
+//! breaks, or the writer reports a failed send, it opens a new link and takes the session over
+//! with the grant's token. It then hands the new submit half to the writer's [`Resend`] half,
+//! which re-sends every frame the server has not received before carrying on.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::sync::{Notify, mpsc};
+
+use super::RemoteService;
+use super::conn::{RemoteEndpoint, ResponseChannel, SubmitChannel, open_channels};
+use crate::shared::{ResumeGrant, ResumeToken, SentLog, SessionId, SessionInit, SessionResume};
+
+/// First delay between two attempts to resume; it doubles up to [`MAX_RETRY_DELAY`].
+const MIN_RETRY_DELAY: Duration = Duration::from_millis(50);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// State shared by the two halves of a resumable session.
+struct Session {
+    endpoint: RemoteEndpoint,
+    session_id: SessionId,
+    device_index: u32,
+    token: ResumeToken,
+    grace: Duration,
+    /// The frames sent that the server has not acknowledged yet.
+    frames: Mutex<SentLog>,
+    /// Responses received on the session, across its links.
+    responses_received: AtomicU64,
+    /// The count last reported to the server, with a `RemoteMessage::Acknowledge`.
+    responses_acknowledged: AtomicU64,
+    /// The link the writer last failed to send on (links count from 1), signaled on `send_failed`.
+    failed_link: AtomicU64,
+    send_failed: Notify,
+}
+
+/// Split a session granted resumption into its demux and writer halves.
+pub(crate) fn resumable(
+    endpoint: RemoteEndpoint,
+    session_id: SessionId,
+    device_index: u32,
+    grant: ResumeGrant,
+) -> (Reconnect, Resend) {
+    let session = Arc::new(Session {
+        endpoint,
+        session_id,
+        device_index,
+        token: grant.token,
+        grace: grant.grace,
+        frames: Mutex::default(),
+        responses_received: AtomicU64::new(0),
+        responses_acknowledged: AtomicU64::new(0),
+        failed_link: AtomicU64::new(0),
+        send_failed: Notify::new(),
+    });
+    let (relinks, links) = mpsc::unbounded_channel();
+    (
+        Reconnect {
+            session: session.clone(),
+            link: 1,
+            relinks,
+        },
+        Resend {
+            session,
+            link: 1,
+            links,
+        },
+    )
+}
+
+/// The response demux's half: counts responses and reconnects.
+pub(crate) struct Reconnect {
+    session: Arc<Session>,
+    /// The current link.
+    link: u64,
+    /// Hands the submit half of each new link to the writer.
+    relinks: mpsc::UnboundedSender<SubmitChannel>,
+}
+
+impl Reconnect {
+    /// Count a response received from the server.
+    pub(crate) fn response_received(&self) {
+        self.session
+            .responses_received
+            .fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// The server has received the first `received` frames.
+    pub(crate) fn frames_acknowledged(&self, received: u64) {
+        self.session.frames.lock().unwrap().acknowledge(received);
+    }
+
+    /// Resolves once the writer fails to send on the current link.
+    pub(crate) async fn send_failed(&self) {
+        loop {
+            self.session.send_failed.notified().await;
+            if self.session.failed_link.load(Ordering::Acquire) == self.link {
+                return;
+            }
+        }
+    }
+
+    /// Take the session over on a new link, retrying until the server's grace period runs out.
+    /// Returns the new response stream; the writer gets the new submit stream.
+    pub(crate) async fn resume(
+        &mut self,
+        broken: ResponseChannel,
+    ) -> Result<ResponseChannel, String> {
+        // Close what's left of the old link, so the server sees it drop.
+        drop(broken);
+
+        let mut last_error = String::from("no attempt finished");
+        let attempts = async {
+            let mut delay = MIN_RETRY_DELAY;
+            loop {
+                if self.relinks.is_closed() {
+                    return Err("the session is shutting down".to_string());
+                }
+                match self.try_resume().await {
+                    Ok(channels) => return Ok(channels),
+                    Err(err) => {
+                        log::debug!(
+                            "Failed to resume remote session {}: {err}; retrying in {delay:?}",
+                            self.session.session_id
+                        );
+                        last_error = err;
+                    }
+                }
+                crate::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+            }
+        };
+        let (request, response) = match crate::time::timeout(self.session.grace, attempts).await {
+            Ok(result) => result?,
+            Err(()) => {
+                return Err(format!(
+                    "gave up after the server's grace period of {:?}: {last_error}",
+                    self.session.grace
+                ));
+            }
+        };
+
+        self.link += 1;
+        self.relinks
+            .send(request)
+            .map_err(|_| "the session is shutting down".to_string())?;
+        log::info!(
+            "Resumed remote session {} at {}",
+            self.session.session_id,
+            self.session.endpoint.peer_addr()
+        );
+        Ok(response)
+    }
+
+    async fn try_resume(&self) -> Result<(SubmitChannel, ResponseChannel), String> {
+        let session = &self.session;
+        let (mut request, mut response) = open_channels(&session.endpoint).await?;
+        let init = SessionInit::new(
+            session.session_id,
+            session.device_index,
+            session.endpoint.authorization().to_vec(),
+        )
+        .with_resume(SessionResume::Resume {
+            token: session.token,
+            responses_received: session.responses_received.load(Ordering::Acquire),
+        });
+        let info = RemoteService::init_session(&mut request, &mut response, init).await?;
+        let grant = info
+            .resume
+            .ok_or_else(|| "the server did not resume the session".to_string())?;
+        session
+            .frames
+            .lock()
+            .unwrap()
+            .acknowledge(grant.frames_received);
+        Ok((request, response))
+    }
+}
+
+/// The writer's half: keeps the frames sent and re-sends them on each new link.
+pub(crate) struct Resend {
+    session: Arc<Session>,
+    /// The current link.
+    link: u64,
+    links: mpsc::UnboundedReceiver<SubmitChannel>,
+}
+
+impl Resend {
+    /// The number of responses received, if it changed since it was last reported.
+    pub(crate) fn acknowledgement(&self) -> Option<u64> {
+        let received = self.session.responses_received.load(Ordering::Acquire);
+        let reported = self
+            .session
+            .responses_acknowledged
+            .swap(received, Ordering::AcqRel);
+        (received != reported).then_some(received)
+    }
+
+    /// Keep a frame until the server acknowledges it.
+    pub(crate) fn sent(&self, frame: Bytes) {
+        self.session.frames.lock().unwrap().push(frame);
+    }
+
+    /// Report a failed send on the current link, for the demux to reconnect.
+    pub(crate) fn send_failed(&self) {
+        self.session.failed_link.store(self.link, Ordering::Release);
+        self.session.send_failed.notify_one();
+    }
+
+    /// The submit half of the next link, with the frames the server hasn't received re-sent on
+    /// it. `None` once the demux has given up on the session.
+    pub(crate) async fn next_link(&mut self) -> Option<Result<SubmitChannel, String>> {
+        let mut channel = self.links.recv().await?;
+        self.link += 1;
+        let frames = self.session.frames.lock().unwrap().replay(0);
+        for frame in frames {
+            if let Err(err) = channel.send(frame).await {
+                return Some(Err(err));
+            }
+        }
+        Some(Ok(channel))
+    }
+}
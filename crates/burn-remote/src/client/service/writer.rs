@@ -2,6 +2,7 @@
 
 use crate::client::runtime::{Executor, SpawnHandle};
 use crate::client::service::SubmitChannel;
+use crate::client::service::resume::Resend;
 use crate::shared::RemoteMessage;
 use tokio::sync::mpsc;
 
@@ -39,14 +40,48 @@ pub(crate) struct SubmitWriter {
 
 impl SubmitWriter {
     /// Spawn the writer task on `runtime`, taking ownership of the submit `channel`.
-    pub(crate) fn spawn(runtime: &Executor, mut channel: SubmitChannel) -> Self {
+    ///
+    /// With `resend`, the session is resumable: a failed send is reported to the response demux
+    /// instead of ending the writer, and the batches keep going out once the demux hands over a
+    /// new link.
+    pub(crate) fn spawn(
+        runtime: &Executor,
+        channel: SubmitChannel,
+        mut resend: Option<Resend>,
+    ) -> Self {
         #[cfg(not(target_family = "wasm"))]
         let (tx, mut rx) = mpsc::channel::<Vec<RemoteMessage>>(WRITE_QUEUE_CAP);
         #[cfg(target_family = "wasm")]
         let (tx, mut rx) = mpsc::unbounded_channel::<Vec<RemoteMessage>>();
 
         let handle = runtime.spawn(async move {
-            while let Some(batch) = rx.recv().await {
+            // `None` while a resumable session waits for its next link.
+            let mut channel = Some(channel);
+            loop {
+                let batch = match resend.as_mut() {
+                    Some(resend) => tokio::select! {
+                        batch = rx.recv() => batch,
+                        link = resend.next_link() => {
+                            match link {
+                                Some(Ok(link)) => channel = Some(link),
+                                Some(Err(err)) => {
+                                    log::warn!("Remote submit writer resend failed: {err:?}");
+                                    resend.send_failed();
+                                }
+                                // The session could not be resumed.
+                                None => return,
+                            }
+                            continue;
+                        }
+                    },
+                    None => rx.recv().await,
+                };
+                let Some(mut batch) = batch else {
+                    break;
+                };
+                if let Some(resend) = &resend {
+                    batch.extend(resend.acknowledgement().map(RemoteMessage::Acknowledge));
+                }
                 let bytes: bytes::Bytes = match rmp_serde::to_vec(&batch) {
                     Ok(b) => b.into(),
                     Err(err) => {
@@ -54,12 +89,26 @@ impl SubmitWriter {
                         continue;
                     }
                 };
-                if let Err(err) = channel.send(bytes).await {
-                    log::warn!("Remote submit writer send failed: {err:?}; closing writer");
-                    return;
+                if let Some(resend) = &resend {
+                    resend.sent(bytes.clone());
                 }
+                // Without a link, the frame waits in the resend log for the next one.
+                let Some(link) = channel.as_mut() else {
+                    continue;
+                };
+                if let Err(err) = link.send(bytes).await {
+                    let Some(resend) = &resend else {
+                        log::warn!("Remote submit writer send failed: {err:?}; closing writer");
+                        return;
+                    };
+                    log::warn!("Remote submit writer send failed: {err:?}; resuming the session");
+                    channel = None;
+                    resend.send_failed();
+                }
+            }
+            if let Some(link) = channel.as_mut() {
+                let _ = link.close().await;
             }
-            let _ = channel.close().await;
         });
         Self {
             tx: Some(tx),
@@ -13,8 +13,12 @@ mod client;
 #[cfg(feature = "server")]
 pub mod server;
 
+#[cfg(all(feature = "monitor", not(target_family = "wasm")))]
+pub mod monitor;
 pub(crate) mod shared;
 pub mod telemetry;
+#[cfg(any(feature = "client", feature = "server"))]
+pub(crate) mod time;
 mod transport;
 
 pub use burn_ir as ir;
@@ -27,12 +31,17 @@ pub(crate) mod metrics;
 
 #[cfg(feature = "iroh")]
 pub use iroh::{Endpoint, EndpointAddr, EndpointId};
+pub use shared::{QuotaExceeded, QuotaResource, QuotaScope};
 #[cfg(feature = "iroh")]
 pub use transport::iroh::RemoteSecret;
 #[cfg(feature = "iroh")]
 pub use transport::iroh::node::BURN_REMOTE_ALPN;
 pub use transport::{PeerAddr, PeerId};
 
+/// TLS configuration of the WebSocket transport.
+#[cfg(feature = "websocket-tls")]
+pub use burn_communication::websocket::{TlsClientConfig, TlsError, TlsServerConfig};
+
 #[cfg(feature = "client")]
 mod __client {
     use super::*;
@@ -1,18 +1,23 @@
 //! Logs op-graph caching (fusion) savings as `[remote ...]` lines, gated behind the runtime log
 //! level (`[remote]` in `burn.toml`, or `BURN_REMOTE_LOG`). A best-effort [`TelemetryProbe`]
 //! subscriber; client and server each run their own, distinguished by a [`MetricSide`] label.
+//! The server also logs its sessions' quota usage, and every session it refuses or closes for
+//! going over a quota. Both sides log the bytes saved by encoding the tensor payloads they send.
 //!
 //! [`TelemetryProbe`]: crate::telemetry::TelemetryProbe
 
 use core::fmt;
 
 use burn_ir::GraphId;
+
+use crate::shared::{QuotaExceeded, SessionId};
 use burn_std::config::config;
 use burn_std::config::log_remote;
 use burn_std::config::remote::RemoteLogLevel;
 
 use crate::telemetry::{
-    OpClass, TelemetryEvent, TelemetryProbe, TelemetrySubscription, TrafficAggregator,
+    OpClass, PayloadRoute, QuotaUsage, TelemetryEvent, TelemetryProbe, TelemetrySubscription,
+    TrafficAggregator,
 };
 
 const MIB: u64 = 1024 * 1024;
@@ -42,6 +47,8 @@ pub(crate) struct TelemetryLogger {
     /// Highest whole-MiB savings already reported, so `Basic` logging emits at most one line per
     /// additional mebibyte saved instead of one per replay.
     logged_mib: u64,
+    /// Same, for the savings of payload encoding.
+    logged_payload_mib: u64,
 }
 
 impl TelemetryLogger {
@@ -50,6 +57,7 @@ impl TelemetryLogger {
             side,
             aggregator: TrafficAggregator::default(),
             logged_mib: 0,
+            logged_payload_mib: 0,
         }
     }
 
@@ -67,6 +75,18 @@ impl TelemetryLogger {
                     graph, ops, bytes, ..
                 } => self.log_registration(*graph, ops.len(), *bytes),
                 TelemetryEvent::GraphExecuted { .. } => self.log_progress(),
+                TelemetryEvent::QuotaUsage { session, usage } => {
+                    self.log_quota_usage(*session, usage)
+                }
+                TelemetryEvent::QuotaExceeded { session, error } => {
+                    self.log_quota_exceeded(*session, error)
+                }
+                TelemetryEvent::PayloadEncoded {
+                    route,
+                    raw_bytes,
+                    wire_bytes,
+                    ..
+                } => self.log_payload(*route, *raw_bytes, *wire_bytes),
                 _ => {}
             }
         }
@@ -81,6 +101,28 @@ impl TelemetryLogger {
         });
     }
 
+    fn log_quota_usage(&self, session: SessionId, usage: &QuotaUsage) {
+        let side = self.side;
+        log_remote(RemoteLogLevel::Full, || {
+            format!(
+                "[remote {side}] session {session} holds {} bytes with {} ops in flight; its peer \
+                 holds {} bytes with {} ops in flight over {} sessions",
+                usage.resident_bytes,
+                usage.in_flight_ops,
+                usage.peer_resident_bytes,
+                usage.peer_in_flight_ops,
+                usage.peer_sessions
+            )
+        });
+    }
+
+    fn log_quota_exceeded(&self, session: SessionId, error: &QuotaExceeded) {
+        let side = self.side;
+        log_remote(RemoteLogLevel::Basic, || {
+            format!("[remote {side}] session {session} closed: {error}")
+        });
+    }
+
     fn log_progress(&mut self) {
         let snapshot = self.aggregator.snapshot();
         let saved = snapshot.saved();
@@ -111,6 +153,37 @@ impl TelemetryLogger {
             }
         }
     }
+
+    fn log_payload(&mut self, route: PayloadRoute, raw_bytes: usize, wire_bytes: usize) {
+        let snapshot = self.aggregator.payload_snapshot();
+        let saved = snapshot.saved();
+        let saved_pct = percentage(saved, snapshot.raw);
+        let side = self.side;
+
+        if level() >= RemoteLogLevel::Full {
+            let route = route.label();
+            log_remote(RemoteLogLevel::Full, || {
+                format!(
+                    "[remote {side}] encoded {route} payload: {raw_bytes} -> {wire_bytes} bytes; \
+                     payload encoding saved {saved} bytes ({saved_pct:.1}%) over {} payloads",
+                    snapshot.payloads
+                )
+            });
+        } else {
+            // Basic: self-throttle to one summary per additional mebibyte saved.
+            let mib = saved / MIB;
+            if mib > self.logged_payload_mib {
+                self.logged_payload_mib = mib;
+                log_remote(RemoteLogLevel::Basic, || {
+                    format!(
+                        "[remote {side}] payload encoding has saved ~{mib} MiB ({saved} bytes, \
+                         {saved_pct:.1}% of {} bytes) of network traffic",
+                        snapshot.raw
+                    )
+                });
+            }
+        }
+    }
 }
 
 /// The logger's drain loop for `probe`, or `None` when remote logging is off. Spawn it detached.
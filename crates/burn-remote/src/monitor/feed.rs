@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use crate::PeerAddr;
+use crate::client::service::{
+    RemoteEndpoint, ResponseChannel, SubmitChannel, open_telemetry_channels,
+};
+use crate::shared::{MonitorFrame, MonitorInit};
+
+/// A compute node to monitor, and the credential its authorizer expects.
+///
+/// The node must be configured to serve its telemetry (`remote.monitoring` in `burn.toml`, or
+/// `BURN_REMOTE_MONITORING=1`).
+#[derive(Clone, Debug)]
+pub struct MonitorTarget {
+    endpoint: RemoteEndpoint,
+}
+
+impl MonitorTarget {
+    /// A node served over WebSocket, e.g. `ws://localhost:3000`.
+    #[cfg(feature = "websocket")]
+    pub fn websocket(address: &str, authorization: Vec<u8>) -> Self {
+        Self {
+            endpoint: RemoteEndpoint::WebSocket {
+                address: burn_communication::Address::from(address),
+                authorization: Arc::from(authorization),
+                #[cfg(feature = "websocket-tls")]
+                tls: None,
+            },
+        }
+    }
+
+    /// Like [`websocket`](Self::websocket), but connects to a `wss` address with the given TLS
+    /// configuration.
+    #[cfg(feature = "websocket-tls")]
+    pub fn websocket_tls(
+        address: &str,
+        authorization: Vec<u8>,
+        tls: burn_communication::websocket::TlsClientConfig,
+    ) -> Self {
+        Self {
+            endpoint: RemoteEndpoint::WebSocket {
+                address: burn_communication::Address::from(address),
+                authorization: Arc::from(authorization),
+                tls: Some(tls),
+            },
+        }
+    }
+
+    /// A node served over Iroh, dialed from `endpoint`.
+    #[cfg(feature = "iroh")]
+    pub fn iroh(
+        endpoint: &iroh::Endpoint,
+        peer: iroh::EndpointAddr,
+        authorization: Vec<u8>,
+    ) -> Self {
+        Self {
+            endpoint: RemoteEndpoint::Iroh {
+                node: crate::transport::iroh::node::RemoteNode::from_endpoint(endpoint.clone()),
+                peer,
+                authorization: Arc::from(authorization),
+            },
+        }
+    }
+
+    /// The address of the node.
+    pub fn peer_addr(&self) -> PeerAddr {
+        self.endpoint.peer_addr()
+    }
+}
+
+/// The telemetry a compute node streams to a monitor.
+pub struct TelemetryFeed {
+    // Kept open for the lifetime of the feed: the node stops serving it once this half closes.
+    _request: SubmitChannel,
+    response: ResponseChannel,
+}
+
+impl TelemetryFeed {
+    /// Open a feed from `target`. Fails if the node can't be reached, or refuses the feed.
+    pub async fn connect(target: &MonitorTarget) -> Result<Self, String> {
+        let (mut request, response) = open_telemetry_channels(&target.endpoint).await?;
+        let init = rmp_serde::to_vec(&MonitorInit::new(target.endpoint.authorization().to_vec()))
+            .map_err(|err| format!("Failed to encode monitor handshake: {err}"))?;
+        request.send(init.into()).await?;
+
+        Ok(Self {
+            _request: request,
+            response,
+        })
+    }
+
+    /// The next frame, or `None` once the node closes the feed.
+    ///
+    /// A [`MonitorFrame::Refused`] is turned into an error.
+    pub async fn next(&mut self) -> Result<Option<MonitorFrame>, String> {
+        let Some(bytes) = self.response.recv().await? else {
+            return Ok(None);
+        };
+        match rmp_serde::from_slice(&bytes) {
+            Ok(MonitorFrame::Refused(reason)) => Err(format!("Telemetry feed refused: {reason}")),
+            Ok(frame) => Ok(Some(frame)),
+            Err(err) => Err(format!("Invalid telemetry frame: {err}")),
+        }
+    }
+}
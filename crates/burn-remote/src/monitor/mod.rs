@@ -0,0 +1,190 @@
+//! A terminal monitor for compute nodes.
+//!
+//! A node configured with `remote.monitoring` (or `BURN_REMOTE_MONITORING=1`) serves its telemetry
+//! to the monitors its authorizer admits: over WebSocket on the `/telemetry` route, over Iroh on a
+//! stream of its own. [`run`] shows that feed live, with the node's sessions and their throughput,
+//! the bandwidth of each payload route and the savings of caching and encoding. A feed can be
+//! recorded to a file and replayed later, at its original pace or faster.
+//!
+//! The `burn-remote-monitor` binary wraps [`run`] in a command line.
+
+mod feed;
+mod record;
+mod state;
+mod view;
+
+pub use feed::{MonitorTarget, TelemetryFeed};
+pub use record::{Recorder, Replay};
+pub use state::{MonitorState, SessionStats, SessionStatus};
+
+use std::error::Error;
+use std::io;
+use std::panic::{set_hook, take_hook};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    Terminal,
+    crossterm::{
+        event::{self, Event, KeyCode},
+        execute,
+        terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    },
+    prelude::CrosstermBackend,
+};
+use tokio::sync::mpsc;
+
+use crate::shared::MonitorFrame;
+use crate::time::sleep;
+
+/// How often the monitor redraws when no frame arrives.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Where the monitor reads its frames from.
+pub enum MonitorInput {
+    /// A live node, its feed optionally recorded to a file.
+    Live {
+        target: MonitorTarget,
+        record: Option<PathBuf>,
+    },
+    /// A recording, played back `speed` times faster than it was recorded.
+    Replay { path: PathBuf, speed: f64 },
+}
+
+enum Update {
+    Frame(Duration, MonitorFrame),
+    Ended(String),
+}
+
+/// Show `input` in the terminal until the user quits with `q` or `Esc`.
+///
+/// Fails if the feed can't be opened; once it runs, a feed that ends is reported in the header
+/// and the monitor stays up with what it last showed.
+pub async fn run(input: MonitorInput) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (name, speed) = match &input {
+        MonitorInput::Live { target, .. } => (target.peer_addr().to_string(), 1.0),
+        MonitorInput::Replay { path, speed } => (path.display().to_string(), *speed),
+    };
+    let (tx, rx) = mpsc::unbounded_channel();
+    match input {
+        MonitorInput::Live { target, record } => {
+            let feed = TelemetryFeed::connect(&target).await?;
+            let recorder = record.map(Recorder::create).transpose()?;
+            tokio::spawn(read_live(feed, recorder, tx));
+        }
+        MonitorInput::Replay { path, speed } => {
+            let replay = Replay::open(path)?;
+            tokio::spawn(read_replay(replay, speed, tx));
+        }
+    }
+
+    tokio::task::spawn_blocking(move || show(name, speed, rx)).await?
+}
+
+async fn read_live(
+    mut feed: TelemetryFeed,
+    mut recorder: Option<Recorder>,
+    tx: mpsc::UnboundedSender<Update>,
+) {
+    let start = Instant::now();
+    let reason = loop {
+        let frame = match feed.next().await {
+            Ok(Some(frame)) => frame,
+            Ok(None) => break "the node closed the feed".to_string(),
+            Err(err) => break err,
+        };
+        let at = start.elapsed();
+        if let Some(recorder) = &mut recorder
+            && let Err(err) = recorder.record(at, &frame)
+        {
+            break format!("failed to record: {err}");
+        }
+        if tx.send(Update::Frame(at, frame)).is_err() {
+            return;
+        }
+    };
+    let _ = tx.send(Update::Ended(reason));
+}
+
+async fn read_replay(mut replay: Replay, speed: f64, tx: mpsc::UnboundedSender<Update>) {
+    let start = Instant::now();
+    let reason = loop {
+        let (at, frame) = match replay.next_frame() {
+            Ok(Some(frame)) => frame,
+            Ok(None) => break "end of the recording".to_string(),
+            Err(err) => break format!("failed to replay: {err}"),
+        };
+        let due = at.div_f64(speed);
+        if let Some(wait) = due.checked_sub(start.elapsed()) {
+            sleep(wait).await;
+        }
+        if tx.send(Update::Frame(at, frame)).is_err() {
+            return;
+        }
+    };
+    let _ = tx.send(Update::Ended(reason));
+}
+
+/// The terminal loop: fold the updates, redraw, and watch for the quit key.
+///
+/// The clock of the state runs `speed` times faster than the wall clock, like the frames of a
+/// replay, and stops once the feed ends.
+fn show(
+    name: String,
+    speed: f64,
+    mut rx: mpsc::UnboundedReceiver<Update>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    // Restore the terminal before a panic message is printed, so it stays readable.
+    let previous_panic_hook = Arc::new(take_hook());
+    set_hook(Box::new({
+        let previous_panic_hook = previous_panic_hook.clone();
+        move |panic_info| {
+            let _ = disable_raw_mode();
+            let _ = execute!(io::stdout(), LeaveAlternateScreen);
+            previous_panic_hook(panic_info);
+        }
+    }));
+
+    let result = (|| -> Result<(), Box<dyn Error + Send + Sync>> {
+        let mut state = MonitorState::default();
+        let mut source = view::Source { name, ended: None };
+        let start = Instant::now();
+
+        loop {
+            while let Ok(update) = rx.try_recv() {
+                match update {
+                    Update::Frame(at, frame) => state.apply(at, &frame),
+                    Update::Ended(reason) => source.ended = Some(reason),
+                }
+            }
+            if source.ended.is_none() {
+                state.tick(start.elapsed().mul_f64(speed));
+            }
+
+            terminal.draw(|frame| view::render(frame, &state, &source))?;
+
+            if event::poll(REDRAW_INTERVAL)?
+                && let Event::Key(key) = event::read()?
+                && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+            {
+                return Ok(());
+            }
+        }
+    })();
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    let _ = take_hook();
+    if let Some(previous_panic_hook) = Arc::into_inner(previous_panic_hook) {
+        set_hook(previous_panic_hook);
+    }
+
+    result
+}
@@ -0,0 +1,145 @@
+//! Recording a telemetry feed to a file, and replaying it.
+//!
+//! A recording is a short header followed by the frames of the feed, each stamped with the time it
+//! arrived at relative to the start of the recording and prefixed with its length, so a replay
+//! plays the frames back at their original pace.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::shared::MonitorFrame;
+
+/// Opens every recording, followed by its format version.
+const MAGIC: &[u8; 8] = b"burn-tlm";
+const FORMAT_VERSION: u16 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct RecordedFrame {
+    /// Milliseconds since the start of the recording.
+    at: u64,
+    frame: MonitorFrame,
+}
+
+/// Writes the frames of a telemetry feed to a file.
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    /// Create (or truncate) the recording at `path`.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        Ok(Self { writer })
+    }
+
+    /// Append `frame`, received `at` after the start of the recording.
+    pub fn record(&mut self, at: Duration, frame: &MonitorFrame) -> io::Result<()> {
+        let bytes = rmp_serde::to_vec(&RecordedFrame {
+            at: at.as_millis() as u64,
+            frame: frame.clone(),
+        })
+        .map_err(io::Error::other)?;
+        self.writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&bytes)?;
+        // Flushed frame by frame, so a monitor killed mid-run leaves a readable recording.
+        self.writer.flush()
+    }
+}
+
+/// Reads back the frames of a recording, in order.
+pub struct Replay {
+    reader: BufReader<File>,
+}
+
+impl Replay {
+    /// Open the recording at `path`.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut header = [0; MAGIC.len() + 2];
+        reader.read_exact(&mut header)?;
+        if &header[..MAGIC.len()] != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a Burn Remote telemetry recording",
+            ));
+        }
+        let version = u16::from_le_bytes([header[MAGIC.len()], header[MAGIC.len() + 1]]);
+        if version != FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported recording version {version} (expected {FORMAT_VERSION})"),
+            ));
+        }
+        Ok(Self { reader })
+    }
+
+    /// The next frame and the time it was received at, or `None` at the end of the recording.
+    ///
+    /// A recording cut short mid-frame ends at the last complete one.
+    pub fn next_frame(&mut self) -> io::Result<Option<(Duration, MonitorFrame)>> {
+        let mut len = [0; 4];
+        match self.reader.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let mut bytes = vec![0; u32::from_le_bytes(len) as usize];
+        match self.reader.read_exact(&mut bytes) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let recorded: RecordedFrame = rmp_serde::from_slice(&bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(Some((Duration::from_millis(recorded.at), recorded.frame)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::SessionId;
+    use crate::telemetry::TelemetryEvent;
+
+    #[test]
+    fn replays_what_was_recorded() {
+        let path =
+            std::env::temp_dir().join(format!("burn-remote-recording-{}.tlm", std::process::id()));
+        let session = SessionId::new();
+
+        let mut recorder = Recorder::create(&path).unwrap();
+        recorder
+            .record(
+                Duration::from_millis(5),
+                &MonitorFrame::Events(vec![TelemetryEvent::SessionOpened { session, device: 1 }]),
+            )
+            .unwrap();
+        recorder
+            .record(Duration::from_millis(120), &MonitorFrame::Lagged(3))
+            .unwrap();
+        drop(recorder);
+
+        let mut replay = Replay::open(&path).unwrap();
+        let (at, frame) = replay.next_frame().unwrap().unwrap();
+        assert_eq!(at, Duration::from_millis(5));
+        let MonitorFrame::Events(events) = frame else {
+            panic!("expected events, got {frame:?}");
+        };
+        assert!(matches!(
+            events.as_slice(),
+            [TelemetryEvent::SessionOpened { device: 1, .. }]
+        ));
+        let (at, frame) = replay.next_frame().unwrap().unwrap();
+        assert_eq!(at, Duration::from_millis(120));
+        assert!(matches!(frame, MonitorFrame::Lagged(3)));
+        assert!(replay.next_frame().unwrap().is_none());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,471 @@
+//! What a monitor shows, folded from the telemetry of a compute node.
+//!
+//! Telemetry events carry no time of their own: the state is fed the time each frame arrived at,
+//! relative to the start of the monitor (or of a recording), and averages throughput and bandwidth
+//! over the last [`RATE_WINDOW`] of that clock.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Duration;
+
+use burn_ir::GraphId;
+
+use crate::shared::{MonitorFrame, SessionId};
+use crate::telemetry::{PayloadRoute, TelemetryEvent, TrafficAggregator, TransferPhase};
+
+/// How far back throughput and bandwidth are averaged.
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// How long a closed session stays listed.
+const CLOSED_LINGER: Duration = Duration::from_secs(10);
+
+/// The routes a payload travels, in display order.
+pub const ROUTES: [PayloadRoute; 3] = [
+    PayloadRoute::Upload,
+    PayloadRoute::Download,
+    PayloadRoute::Transfer,
+];
+
+/// A running sum over the last [`RATE_WINDOW`].
+#[derive(Default)]
+struct Rate {
+    samples: VecDeque<(Duration, u64)>,
+}
+
+impl Rate {
+    fn add(&mut self, at: Duration, amount: u64) {
+        self.samples.push_back((at, amount));
+        self.prune(at);
+    }
+
+    fn prune(&mut self, now: Duration) {
+        while let Some((at, _)) = self.samples.front()
+            && *at + RATE_WINDOW < now
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// The average per second over the window ending at `now`.
+    fn per_second(&self, now: Duration) -> f64 {
+        let sum: u64 = self
+            .samples
+            .iter()
+            .filter(|(at, _)| *at + RATE_WINDOW >= now)
+            .map(|(_, amount)| amount)
+            .sum();
+        sum as f64 / RATE_WINDOW.as_secs_f64()
+    }
+}
+
+/// Where a session stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    Active,
+    /// Its link dropped, and it waits to be resumed.
+    Suspended,
+    Closed,
+}
+
+impl SessionStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            SessionStatus::Active => "active",
+            SessionStatus::Suspended => "suspended",
+            SessionStatus::Closed => "closed",
+        }
+    }
+}
+
+/// One session, as seen by the monitor.
+pub struct SessionStats {
+    /// `None` for a session opened before the monitor attached.
+    pub device: Option<u32>,
+    pub status: SessionStatus,
+    /// Operations executed, streamed one by one or replayed from cached graphs.
+    pub ops: u64,
+    /// Estimated bytes of the session's resident tensors, as last reported.
+    pub resident_bytes: u64,
+    /// Tasks submitted and not executed yet, as last reported.
+    pub in_flight_ops: u64,
+    /// Tensor reads served.
+    pub reads: u64,
+    op_rate: Rate,
+    /// The number of operations of each cached graph.
+    graphs: HashMap<GraphId, u64>,
+    closed_at: Option<Duration>,
+}
+
+impl SessionStats {
+    fn new(device: Option<u32>) -> Self {
+        Self {
+            device,
+            status: SessionStatus::Active,
+            ops: 0,
+            resident_bytes: 0,
+            in_flight_ops: 0,
+            reads: 0,
+            op_rate: Rate::default(),
+            graphs: HashMap::new(),
+            closed_at: None,
+        }
+    }
+
+    /// Operations executed per second, averaged over the last few seconds.
+    pub fn ops_per_second(&self, now: Duration) -> f64 {
+        self.op_rate.per_second(now)
+    }
+
+    /// The number of op-graphs the session has cached.
+    pub fn cached_graphs(&self) -> usize {
+        self.graphs.len()
+    }
+
+    fn executed(&mut self, at: Duration, ops: u64) {
+        self.ops += ops;
+        self.op_rate.add(at, ops);
+    }
+}
+
+/// The tensor payloads moved along one route.
+#[derive(Default)]
+pub struct RouteTraffic {
+    /// Bytes moved, as sent on the wire.
+    pub bytes: u64,
+    pub payloads: u64,
+    rate: Rate,
+}
+
+impl RouteTraffic {
+    /// Bytes per second, averaged over the last few seconds.
+    pub fn bytes_per_second(&self, now: Duration) -> f64 {
+        self.rate.per_second(now)
+    }
+}
+
+/// Transfers between compute nodes, by how they ended.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TransferCounts {
+    pub started: u64,
+    pub completed: u64,
+    pub failed: u64,
+}
+
+/// The state of a compute node, folded from its telemetry.
+#[derive(Default)]
+pub struct MonitorState {
+    now: Duration,
+    sessions: BTreeMap<SessionId, SessionStats>,
+    traffic: [RouteTraffic; ROUTES.len()],
+    transfers: TransferCounts,
+    aggregator: TrafficAggregator,
+    events: u64,
+    lagged: u64,
+}
+
+impl MonitorState {
+    /// Fold a frame of the feed, received `at`.
+    pub fn apply(&mut self, at: Duration, frame: &MonitorFrame) {
+        self.tick(at);
+        match frame {
+            MonitorFrame::Events(events) => {
+                for event in events {
+                    self.apply_event(event);
+                }
+            }
+            MonitorFrame::Lagged(lagged) => self.lagged += lagged,
+            MonitorFrame::Refused(_) => {}
+        }
+    }
+
+    /// Move the clock to `now`, forgetting the sessions closed long enough ago.
+    pub fn tick(&mut self, now: Duration) {
+        self.now = self.now.max(now);
+        let now = self.now;
+        self.sessions.retain(|_, session| {
+            session
+                .closed_at
+                .is_none_or(|closed_at| closed_at + CLOSED_LINGER >= now)
+        });
+    }
+
+    fn apply_event(&mut self, event: &TelemetryEvent) {
+        let now = self.now;
+        self.events += 1;
+        self.aggregator.apply(event);
+        match event {
+            TelemetryEvent::SessionOpened { session, device } => {
+                self.sessions
+                    .insert(*session, SessionStats::new(Some(*device)));
+            }
+            TelemetryEvent::SessionClosed { session } => {
+                let stats = self.session(*session);
+                stats.status = SessionStatus::Closed;
+                stats.closed_at = Some(now);
+                stats.resident_bytes = 0;
+                stats.in_flight_ops = 0;
+            }
+            TelemetryEvent::SessionSuspended { session } => {
+                self.session(*session).status = SessionStatus::Suspended;
+            }
+            TelemetryEvent::SessionResumed { session } => {
+                self.session(*session).status = SessionStatus::Active;
+            }
+            TelemetryEvent::Op { session, .. } => self.session(*session).executed(now, 1),
+            TelemetryEvent::GraphRegistered {
+                session,
+                graph,
+                ops,
+                ..
+            } => {
+                self.session(*session)
+                    .graphs
+                    .insert(*graph, ops.len() as u64);
+            }
+            TelemetryEvent::GraphExecuted { session, graph, .. } => {
+                let stats = self.session(*session);
+                let ops = stats.graphs.get(graph).copied().unwrap_or(0);
+                stats.executed(now, ops);
+            }
+            TelemetryEvent::Read { session, .. } => self.session(*session).reads += 1,
+            TelemetryEvent::QuotaUsage { session, usage } => {
+                let stats = self.session(*session);
+                stats.resident_bytes = usage.resident_bytes;
+                stats.in_flight_ops = usage.in_flight_ops;
+            }
+            TelemetryEvent::Transfer { phase, .. } => match phase {
+                TransferPhase::Started => self.transfers.started += 1,
+                TransferPhase::Completed => self.transfers.completed += 1,
+                TransferPhase::Failed => self.transfers.failed += 1,
+            },
+            TelemetryEvent::Payload { route, bytes, .. } => {
+                let traffic = &mut self.traffic[route_index(*route)];
+                traffic.bytes += *bytes as u64;
+                traffic.payloads += 1;
+                traffic.rate.add(now, *bytes as u64);
+            }
+            TelemetryEvent::TensorDropped { .. }
+            | TelemetryEvent::Sync { .. }
+            | TelemetryEvent::QuotaExceeded { .. }
+            | TelemetryEvent::PayloadEncoded { .. } => {}
+        }
+    }
+
+    /// A session the monitor may have attached too late to see open.
+    fn session(&mut self, session: SessionId) -> &mut SessionStats {
+        self.sessions
+            .entry(session)
+            .or_insert_with(|| SessionStats::new(None))
+    }
+
+    /// The time of the latest frame or tick.
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// The sessions listed, closed ones included for a few seconds.
+    pub fn sessions(&self) -> impl Iterator<Item = (&SessionId, &SessionStats)> {
+        self.sessions.iter()
+    }
+
+    /// The estimated bytes of every resident tensor on the node.
+    pub fn resident_bytes(&self) -> u64 {
+        self.sessions
+            .values()
+            .map(|session| session.resident_bytes)
+            .sum()
+    }
+
+    /// Operations executed per second across the node.
+    pub fn ops_per_second(&self) -> f64 {
+        self.sessions
+            .values()
+            .map(|session| session.ops_per_second(self.now))
+            .sum()
+    }
+
+    /// The payloads moved along `route`.
+    pub fn traffic(&self, route: PayloadRoute) -> &RouteTraffic {
+        &self.traffic[route_index(route)]
+    }
+
+    pub fn transfers(&self) -> TransferCounts {
+        self.transfers
+    }
+
+    /// The savings of op-graph caching and payload encoding, and the op mix.
+    pub fn aggregator(&self) -> &TrafficAggregator {
+        &self.aggregator
+    }
+
+    /// Events received.
+    pub fn events(&self) -> u64 {
+        self.events
+    }
+
+    /// Events the node dropped because the monitor fell behind.
+    pub fn lagged(&self) -> u64 {
+        self.lagged
+    }
+}
+
+fn route_index(route: PayloadRoute) -> usize {
+    match route {
+        PayloadRoute::Upload => 0,
+        PayloadRoute::Download => 1,
+        PayloadRoute::Transfer => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::{GraphOp, OpClass, QuotaUsage};
+    use burn_std::id::StreamId;
+
+    fn events(events: Vec<TelemetryEvent>) -> MonitorFrame {
+        MonitorFrame::Events(events)
+    }
+
+    fn op(session: SessionId) -> TelemetryEvent {
+        TelemetryEvent::Op {
+            session,
+            stream: StreamId::current(),
+            kind: OpClass::Elementwise,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    fn graph_op() -> GraphOp {
+        GraphOp {
+            kind: OpClass::Matmul,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn counts_streamed_and_replayed_ops() {
+        let session = SessionId::new();
+        let graph = GraphId(7);
+        let mut state = MonitorState::default();
+
+        state.apply(
+            Duration::ZERO,
+            &events(vec![
+                TelemetryEvent::SessionOpened { session, device: 2 },
+                op(session),
+                TelemetryEvent::GraphRegistered {
+                    session,
+                    graph,
+                    ops: vec![graph_op(), graph_op(), graph_op()],
+                    bytes: 100,
+                },
+            ]),
+        );
+        state.apply(
+            Duration::from_secs(1),
+            &events(vec![
+                TelemetryEvent::GraphExecuted {
+                    session,
+                    graph,
+                    stream: StreamId::current(),
+                    bindings_bytes: 10,
+                },
+                TelemetryEvent::GraphExecuted {
+                    session,
+                    graph,
+                    stream: StreamId::current(),
+                    bindings_bytes: 10,
+                },
+            ]),
+        );
+
+        let (_, stats) = state.sessions().next().unwrap();
+        assert_eq!(stats.device, Some(2));
+        assert_eq!(stats.ops, 7);
+        assert_eq!(stats.cached_graphs(), 1);
+        assert_eq!(stats.ops_per_second(state.now()), 7.0 / 5.0);
+
+        // Past the window, the rate falls back to zero but the total stays.
+        state.tick(Duration::from_secs(10));
+        let (_, stats) = state.sessions().next().unwrap();
+        assert_eq!(stats.ops_per_second(state.now()), 0.0);
+        assert_eq!(stats.ops, 7);
+    }
+
+    #[test]
+    fn tracks_sessions_opened_before_attaching() {
+        let session = SessionId::new();
+        let mut state = MonitorState::default();
+
+        state.apply(
+            Duration::ZERO,
+            &events(vec![TelemetryEvent::QuotaUsage {
+                session,
+                usage: QuotaUsage {
+                    resident_bytes: 4096,
+                    ..QuotaUsage::default()
+                },
+            }]),
+        );
+
+        let (_, stats) = state.sessions().next().unwrap();
+        assert_eq!(stats.device, None);
+        assert_eq!(state.resident_bytes(), 4096);
+    }
+
+    #[test]
+    fn closed_sessions_linger_then_go() {
+        let session = SessionId::new();
+        let mut state = MonitorState::default();
+
+        state.apply(
+            Duration::ZERO,
+            &events(vec![
+                TelemetryEvent::SessionOpened { session, device: 0 },
+                TelemetryEvent::SessionClosed { session },
+            ]),
+        );
+        let (_, stats) = state.sessions().next().unwrap();
+        assert_eq!(stats.status, SessionStatus::Closed);
+
+        state.tick(CLOSED_LINGER + Duration::from_secs(1));
+        assert!(state.sessions().next().is_none());
+    }
+
+    #[test]
+    fn sums_bandwidth_by_route() {
+        let session = SessionId::new();
+        let mut state = MonitorState::default();
+
+        state.apply(
+            Duration::from_secs(1),
+            &events(vec![
+                TelemetryEvent::Payload {
+                    session,
+                    route: PayloadRoute::Upload,
+                    bytes: 1000,
+                },
+                TelemetryEvent::Payload {
+                    session,
+                    route: PayloadRoute::Upload,
+                    bytes: 4000,
+                },
+                TelemetryEvent::Payload {
+                    session,
+                    route: PayloadRoute::Download,
+                    bytes: 500,
+                },
+            ]),
+        );
+        state.apply(Duration::from_secs(2), &MonitorFrame::Lagged(4));
+
+        let upload = state.traffic(PayloadRoute::Upload);
+        assert_eq!((upload.bytes, upload.payloads), (5000, 2));
+        assert_eq!(upload.bytes_per_second(state.now()), 1000.0);
+        assert_eq!(state.traffic(PayloadRoute::Download).bytes, 500);
+        assert_eq!(state.traffic(PayloadRoute::Transfer).payloads, 0);
+        assert_eq!(state.lagged(), 4);
+    }
+}
@@ -0,0 +1,243 @@
+//! Drawing the monitor.
+
+use std::time::Duration;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Row, Table},
+};
+
+use super::state::{MonitorState, ROUTES, SessionStatus};
+
+/// What the header says about where the frames come from.
+pub struct Source {
+    /// The node monitored, or the recording replayed.
+    pub name: String,
+    /// Set once the feed or the replay has ended, with the reason.
+    pub ended: Option<String>,
+}
+
+/// Draw the whole monitor.
+pub fn render(frame: &mut Frame<'_>, state: &MonitorState, source: &Source) {
+    let [header, sessions, bottom, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(6),
+        Constraint::Length(8),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [traffic, ops] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(bottom);
+
+    render_header(frame, header, state, source);
+    render_sessions(frame, sessions, state);
+    render_traffic(frame, traffic, state);
+    render_ops(frame, ops, state);
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::from(" q / Esc ").bold().yellow(),
+            Span::from("quit"),
+        ])),
+        footer,
+    );
+}
+
+fn title(title: &str) -> Span<'static> {
+    Span::from(format!(" {title} ")).bold().yellow()
+}
+
+fn value(value: String) -> Span<'static> {
+    Span::from(value).italic()
+}
+
+fn render_header(frame: &mut Frame<'_>, area: Rect, state: &MonitorState, source: &Source) {
+    let mut line = vec![
+        title("Node"),
+        value(source.name.clone()),
+        title("Uptime"),
+        value(format_duration(state.now())),
+        title("Sessions"),
+        value(state.sessions().count().to_string()),
+        title("Ops/s"),
+        value(format!("{:.1}", state.ops_per_second())),
+        title("Resident"),
+        value(format_bytes(state.resident_bytes() as f64)),
+        title("Events"),
+        value(state.events().to_string()),
+    ];
+    if state.lagged() > 0 {
+        line.push(title("Lagged"));
+        line.push(Span::from(state.lagged().to_string()).red());
+    }
+    if let Some(reason) = &source.ended {
+        line.push(title("Ended"));
+        line.push(Span::from(reason.clone()).red());
+    }
+
+    frame.render_widget(
+        Paragraph::new(Line::from(line))
+            .block(Block::default().borders(Borders::ALL).title("Burn Remote"))
+            .style(Style::default().fg(Color::Gray)),
+        area,
+    );
+}
+
+fn render_sessions(frame: &mut Frame<'_>, area: Rect, state: &MonitorState) {
+    let now = state.now();
+    let header = Row::new([
+        "Session",
+        "Device",
+        "Status",
+        "Ops/s",
+        "Ops",
+        "Graphs",
+        "Reads",
+        "Resident",
+        "In flight",
+    ])
+    .style(Style::default().bold().yellow());
+    let rows = state.sessions().map(|(session, stats)| {
+        let style = match stats.status {
+            SessionStatus::Active => Style::default().fg(Color::Gray),
+            SessionStatus::Suspended => Style::default().fg(Color::Yellow),
+            SessionStatus::Closed => Style::default().fg(Color::DarkGray),
+        };
+        Row::new([
+            session.to_string(),
+            stats
+                .device
+                .map_or_else(|| "?".to_string(), |device| device.to_string()),
+            stats.status.label().to_string(),
+            format!("{:.1}", stats.ops_per_second(now)),
+            stats.ops.to_string(),
+            stats.cached_graphs().to_string(),
+            stats.reads.to_string(),
+            format_bytes(stats.resident_bytes as f64),
+            stats.in_flight_ops.to_string(),
+        ])
+        .style(style)
+    });
+
+    frame.render_widget(
+        Table::new(
+            rows,
+            [
+                Constraint::Min(24),
+                Constraint::Length(7),
+                Constraint::Length(10),
+                Constraint::Length(9),
+                Constraint::Length(10),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(10),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title("Sessions")),
+        area,
+    );
+}
+
+fn render_traffic(frame: &mut Frame<'_>, area: Rect, state: &MonitorState) {
+    let now = state.now();
+    let mut lines: Vec<Line<'static>> = ROUTES
+        .iter()
+        .map(|route| {
+            let traffic = state.traffic(*route);
+            Line::from(vec![
+                title(&format!("{: <8} :", route.label())),
+                value(format!(
+                    "{}/s, {} in {} payloads",
+                    format_bytes(traffic.bytes_per_second(now)),
+                    format_bytes(traffic.bytes as f64),
+                    traffic.payloads
+                )),
+            ])
+        })
+        .collect();
+
+    let transfers = state.transfers();
+    lines.push(Line::from(vec![
+        title("Peer transfers :"),
+        value(format!(
+            "{} started, {} completed, {} failed",
+            transfers.started, transfers.completed, transfers.failed
+        )),
+    ]));
+    let payload = state.aggregator().payload_snapshot();
+    lines.push(Line::from(vec![
+        title("Encoding saved :"),
+        value(format!(
+            "{} over {} payloads",
+            format_bytes(payload.saved() as f64),
+            payload.payloads
+        )),
+    ]));
+    let fusion = state.aggregator().snapshot();
+    lines.push(Line::from(vec![
+        title("Caching saved  :"),
+        value(format!(
+            "{} ({} of {} ops replayed)",
+            format_bytes(fusion.saved() as f64),
+            fusion.fused_ops,
+            fusion.total_ops()
+        )),
+    ]));
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Traffic"))
+            .style(Style::default().fg(Color::Gray)),
+        area,
+    );
+}
+
+fn render_ops(frame: &mut Frame<'_>, area: Rect, state: &MonitorState) {
+    let by_kind = state.aggregator().unfused_by_kind();
+    let width = by_kind
+        .iter()
+        .map(|(kind, _)| kind.label().len())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line<'static>> = by_kind
+        .into_iter()
+        .map(|(kind, count)| {
+            Line::from(vec![
+                title(&format!("{: <width$} :", kind.label())),
+                value(count.to_string()),
+            ])
+        })
+        .collect();
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Streamed ops"))
+            .style(Style::default().fg(Color::Gray)),
+        area,
+    );
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024.0 {
+        return format!("{bytes:.0}B");
+    }
+
+    let mut value = bytes / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1}{}", UNITS[unit])
+}
@@ -0,0 +1,168 @@
+//! Session authorization, shared by every transport.
+//!
+//! Each transport authenticates the connecting peer its own way (an Iroh endpoint key, a
+//! WebSocket client certificate) and hands the result to the same [`PeerAuthorizer`], together
+//! with the credential the client attached to its session.
+
+use std::sync::Arc;
+
+/// The authenticated identity of a peer opening a compute session.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum PeerIdentity {
+    /// An Iroh endpoint, authenticated by its public key.
+    #[cfg(feature = "iroh")]
+    Iroh(iroh::EndpointId),
+    /// A WebSocket client. Its [certificate](burn_communication::websocket::WsPeer::certificate)
+    /// is only present when the server requires mutual TLS.
+    #[cfg(feature = "websocket")]
+    WebSocket(burn_communication::websocket::WsPeer),
+}
+
+/// Information presented to a compute node before a remote session is accepted.
+pub struct AuthorizationRequest<'a> {
+    /// Identity of the connecting peer, as authenticated by the transport.
+    pub peer: PeerIdentity,
+    /// Compute-device index requested by the peer.
+    pub device_index: u32,
+    /// Whether the peer asks to monitor the node's telemetry rather than open a compute session
+    /// (`device_index` is then 0).
+    pub monitor: bool,
+    /// Opaque credential supplied by the application when creating the remote device.
+    pub credential: &'a [u8],
+}
+
+/// Application authorization policy for incoming compute sessions.
+pub trait PeerAuthorizer: Send + Sync + 'static {
+    /// Return `Ok(())` to allow the session, or a user-facing rejection reason.
+    fn authorize(&self, request: AuthorizationRequest<'_>) -> Result<(), String>;
+}
+
+impl<F> PeerAuthorizer for F
+where
+    F: Fn(AuthorizationRequest<'_>) -> Result<(), String> + Send + Sync + 'static,
+{
+    fn authorize(&self, request: AuthorizationRequest<'_>) -> Result<(), String> {
+        self(request)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AllowAll;
+
+impl PeerAuthorizer for AllowAll {
+    fn authorize(&self, _request: AuthorizationRequest<'_>) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Accepts the sessions whose credential is one of a set of shared secret tokens.
+///
+/// Clients pass the token as the authorization of their remote device (e.g.
+/// `RemoteDevice::websocket_authorized`). Over a plaintext WebSocket the token travels in the
+/// clear, so pair it with TLS on untrusted networks.
+#[derive(Clone)]
+pub struct TokenAuthorizer {
+    tokens: Vec<Vec<u8>>,
+}
+
+impl TokenAuthorizer {
+    /// Accept sessions presenting `token`.
+    pub fn new(token: impl Into<Vec<u8>>) -> Self {
+        Self {
+            tokens: vec![token.into()],
+        }
+    }
+
+    /// Also accept sessions presenting `token`, e.g. to rotate tokens without downtime.
+    pub fn with_token(mut self, token: impl Into<Vec<u8>>) -> Self {
+        self.tokens.push(token.into());
+        self
+    }
+}
+
+impl core::fmt::Debug for TokenAuthorizer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Never print the secrets.
+        f.debug_struct("TokenAuthorizer")
+            .field("tokens", &self.tokens.len())
+            .finish()
+    }
+}
+
+impl PeerAuthorizer for TokenAuthorizer {
+    fn authorize(&self, request: AuthorizationRequest<'_>) -> Result<(), String> {
+        // Compare against every token so the timing doesn't reveal which one nearly matched.
+        let accepted = self.tokens.iter().fold(false, |accepted, token| {
+            constant_time_eq(token, request.credential) | accepted
+        });
+
+        if accepted {
+            Ok(())
+        } else {
+            Err("invalid authorization token".to_string())
+        }
+    }
+}
+
+/// Compare two byte strings in a time that only depends on their lengths.
+pub(crate) fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    if lhs.len() != rhs.len() {
+        return false;
+    }
+    let diff = lhs
+        .iter()
+        .zip(rhs)
+        .fold(0u8, |diff, (lhs, rhs)| diff | (lhs ^ rhs));
+    core::hint::black_box(diff) == 0
+}
+
+/// How a turnkey server authenticates its clients and encrypts their sessions.
+///
+/// The [authorizer](Self::with_authorizer) gates the sessions of every transport and defaults to
+/// [`AllowAll`]. TLS (`websocket-tls` feature) only applies to the WebSocket transport: Iroh
+/// connections are always encrypted and authenticated by the endpoint keys.
+#[derive(Clone)]
+pub struct ServerAuth {
+    pub(crate) authorizer: Arc<dyn PeerAuthorizer>,
+    #[cfg(feature = "websocket-tls")]
+    pub(crate) tls: Option<burn_communication::websocket::TlsServerConfig>,
+}
+
+impl Default for ServerAuth {
+    fn default() -> Self {
+        Self {
+            authorizer: Arc::new(AllowAll),
+            #[cfg(feature = "websocket-tls")]
+            tls: None,
+        }
+    }
+}
+
+impl core::fmt::Debug for ServerAuth {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut debug = f.debug_struct("ServerAuth");
+        #[cfg(feature = "websocket-tls")]
+        debug.field("tls", &self.tls.is_some());
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl ServerAuth {
+    /// Authorize or reject each incoming compute session.
+    pub fn with_authorizer(mut self, authorizer: impl PeerAuthorizer) -> Self {
+        self.authorizer = Arc::new(authorizer);
+        self
+    }
+
+    /// Serve the WebSocket transport over TLS; clients connect with a `wss` address.
+    ///
+    /// With [`TlsServerConfig::mutual`](burn_communication::websocket::TlsServerConfig::mutual),
+    /// only clients holding a certificate signed by the configured authorities can connect, and
+    /// the authorizer sees that certificate in [`PeerIdentity::WebSocket`].
+    #[cfg(feature = "websocket-tls")]
+    pub fn with_tls(mut self, config: burn_communication::websocket::TlsServerConfig) -> Self {
+        self.tls = Some(config);
+        self
+    }
+}
@@ -1,7 +1,11 @@
+use core::time::Duration;
+
 use burn_backend::tensor::Device;
 use burn_ir::{BackendIr, CustomOpIr, HandleContainer};
 use burn_router::CustomOpRegistry;
 
+use super::{ServerAuth, ServerQuotas};
+
 /// Transport used to serve remote clients.
 ///
 /// Selects the protocol the [`RemoteServerBuilder`] serves on.
@@ -52,9 +56,12 @@ impl Default for Channel {
 
 /// Builder for a remote-execution server.
 ///
-/// Configures the transport ([`channel`](Self::channel) / [`port`](Self::port)) and the custom
-/// operation handlers ([`custom_op`](Self::custom_op) / [`custom_ops`](Self::custom_ops)), then
-/// starts the server with [`start`](Self::start) (blocking) or [`start_async`](Self::start_async).
+/// Configures the transport ([`channel`](Self::channel) / [`port`](Self::port)), who may open
+/// sessions ([`auth`](Self::auth)), how much they may use ([`quotas`](Self::quotas)) and whether
+/// they survive a dropped link ([`resume_grace`](Self::resume_grace)), and the custom operation
+/// handlers ([`custom_op`](Self::custom_op) / [`custom_ops`](Self::custom_ops)),
+/// then starts the server with [`start`](Self::start) (blocking) or
+/// [`start_async`](Self::start_async).
 ///
 /// The builder is generic over the concrete backend `B`: custom ops are typed by `B`, since their
 /// handlers call into `B`'s primitives. A backend extension hosts its ops here — the server-side
@@ -78,6 +85,9 @@ pub struct RemoteServerBuilder<B: BackendIr> {
     devices: Vec<Device<B>>,
     channel: Channel,
     custom_ops: CustomOpRegistry<B>,
+    auth: ServerAuth,
+    quotas: ServerQuotas,
+    resume_grace: Option<Duration>,
 }
 
 impl<B: BackendIr> RemoteServerBuilder<B> {
@@ -85,12 +95,16 @@ impl<B: BackendIr> RemoteServerBuilder<B> {
     ///
     /// `devices` is indexed by the device index a client selects at session init; `devices[0]` is
     /// the default device. Must be non-empty. Defaults to WebSocket on port `3000` (or Iroh when
-    /// WebSocket is not compiled in) with no custom ops.
+    /// WebSocket is not compiled in), accepting every session without quotas, ending each session
+    /// with its link, with no custom ops.
     pub fn new(devices: Vec<Device<B>>) -> Self {
         Self {
             devices,
             channel: Channel::default(),
             custom_ops: CustomOpRegistry::default(),
+            auth: ServerAuth::default(),
+            quotas: ServerQuotas::default(),
+            resume_grace: None,
         }
     }
 
@@ -107,6 +121,26 @@ impl<B: BackendIr> RemoteServerBuilder<B> {
         self
     }
 
+    /// Authenticate and authorize incoming sessions, e.g. with a
+    /// [`TokenAuthorizer`](super::TokenAuthorizer) or TLS client certificates.
+    pub fn auth(mut self, auth: ServerAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Limit the resources each session and each peer may use.
+    pub fn quotas(mut self, quotas: ServerQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// Keep a session for `grace` after its link drops, so the client can reconnect and resume it
+    /// with its tensors intact. Without it, a session ends with its link.
+    pub fn resume_grace(mut self, grace: Duration) -> Self {
+        self.resume_grace = Some(grace);
+        self
+    }
+
     /// Register a handler for a [custom operation](burn_ir::OperationIr::Custom), keyed by `id`.
     ///
     /// The `id` must match the one the client puts in its [`CustomOpIr`]. Chainable; registering
@@ -139,6 +173,9 @@ impl<B: BackendIr> RemoteServerBuilder<B> {
                     self.devices,
                     port,
                     self.custom_ops,
+                    self.auth,
+                    self.quotas,
+                    self.resume_grace,
                 )
                 .await;
             }
@@ -148,6 +185,9 @@ impl<B: BackendIr> RemoteServerBuilder<B> {
                     *secret,
                     self.devices,
                     self.custom_ops,
+                    self.auth.authorizer,
+                    self.quotas,
+                    self.resume_grace,
                 )
                 .await;
             }
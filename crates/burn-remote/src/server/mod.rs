@@ -1,17 +1,24 @@
 pub(crate) mod local_comm;
+#[cfg(any(feature = "websocket", feature = "iroh"))]
+pub(crate) mod monitor;
 pub(crate) mod pump;
+pub(crate) mod quota;
+pub(crate) mod resume;
 pub(crate) mod service;
 pub(crate) mod session;
 pub(crate) mod spawn;
 pub(crate) mod transfer;
 pub(crate) mod worker;
 
+mod auth;
 mod builder;
 
+pub use auth::{
+    AllowAll, AuthorizationRequest, PeerAuthorizer, PeerIdentity, ServerAuth, TokenAuthorizer,
+};
 pub use builder::{Channel, RemoteServerBuilder};
 pub use burn_router::{CustomOpHandler, CustomOpRegistry};
+pub use quota::{QuotaLimits, ServerQuotas};
 
 #[cfg(feature = "iroh")]
-pub use crate::transport::iroh::protocol::{
-    AllowAll, AuthorizationRequest, IrohRemoteProtocol, PeerAuthorizer, RemoteProtocol,
-};
+pub use crate::transport::iroh::protocol::{IrohRemoteProtocol, RemoteProtocol};
@@ -0,0 +1,108 @@
+//! The telemetry feed served to monitors.
+//!
+//! A monitor opens its own link to a compute node and sends a single [`MonitorInit`]. Once the
+//! node's authorizer admits it, the node streams its [`TelemetryEvent`](crate::telemetry::TelemetryEvent)s
+//! down the link in [`MonitorFrame`]s, batched every [`FLUSH_INTERVAL`] so a busy node sends a few
+//! frames a second rather than one per op. A monitor only reads: like any other subscriber, one
+//! that falls behind loses events (it is told how many) and never slows the sessions down.
+
+use core::time::Duration;
+
+use bytes::Bytes;
+use tokio::sync::oneshot;
+
+use crate::server::spawn::spawn_detached;
+use crate::server::{AuthorizationRequest, PeerAuthorizer, PeerIdentity};
+use crate::shared::{MonitorFrame, MonitorInit, PROTOCOL_VERSION};
+use crate::telemetry::{DrainStatus, TelemetryProbe};
+use crate::time::sleep;
+use crate::transport::link::{FrameSink, FrameSource};
+
+/// How long events are gathered before being sent as one frame.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Serve `probe`'s events to one monitor, until it disconnects or the node shuts down.
+///
+/// `authorizer` checks `peer` once, with the credential of the monitor's [`MonitorInit`]. A
+/// refused monitor is sent the reason in a [`MonitorFrame::Refused`] before the link closes.
+pub(crate) async fn drive_monitor<Src, Snk>(
+    mut source: Src,
+    mut sink: Snk,
+    probe: &TelemetryProbe,
+    peer: PeerIdentity,
+    authorizer: &dyn PeerAuthorizer,
+) -> Result<(), String>
+where
+    Src: FrameSource,
+    Snk: FrameSink,
+{
+    let init = source
+        .recv()
+        .await?
+        .ok_or_else(|| "Telemetry stream closed before initialization".to_string())?;
+    let init: MonitorInit = rmp_serde::from_slice(&init)
+        .map_err(|err| format!("Failed to decode monitor handshake: {err:?}"))?;
+
+    let admitted = if init.version != PROTOCOL_VERSION {
+        Err(format!(
+            "Unsupported Burn Remote protocol version {} (expected {PROTOCOL_VERSION})",
+            init.version
+        ))
+    } else {
+        authorizer.authorize(AuthorizationRequest {
+            peer,
+            device_index: 0,
+            monitor: true,
+            credential: &init.authorization,
+        })
+    };
+    let subscription = admitted.and_then(|()| {
+        probe
+            .subscribe()
+            .ok_or_else(|| "telemetry is disabled on this node".to_string())
+    });
+    let mut events = match subscription {
+        Ok(events) => events,
+        Err(reason) => {
+            sink.send(encode(&MonitorFrame::Refused(reason.clone()))?)
+                .await?;
+            sink.close().await?;
+            return Err(format!("Monitor refused: {reason}"));
+        }
+    };
+
+    // The monitor sends nothing more; watch for it hanging up.
+    let (hung_up, mut disconnected) = oneshot::channel();
+    spawn_detached(async move {
+        while let Ok(Some(_)) = source.recv().await {}
+        let _ = hung_up.send(());
+    });
+
+    let mut batch = Vec::new();
+    loop {
+        tokio::select! {
+            _ = &mut disconnected => return Ok(()),
+            () = sleep(FLUSH_INTERVAL) => {}
+        }
+
+        let status = events.drain_into(&mut batch);
+        if let DrainStatus::Open { lagged } = status
+            && lagged > 0
+        {
+            sink.send(encode(&MonitorFrame::Lagged(lagged))?).await?;
+        }
+        if !batch.is_empty() {
+            let frame = MonitorFrame::Events(batch.drain(..).map(|e| e.as_ref().clone()).collect());
+            sink.send(encode(&frame)?).await?;
+        }
+        if let DrainStatus::Closed = status {
+            return sink.close().await;
+        }
+    }
+}
+
+fn encode(frame: &MonitorFrame) -> Result<Bytes, String> {
+    rmp_serde::to_vec(frame)
+        .map(Into::into)
+        .map_err(|err| format!("Failed to encode telemetry frame: {err}"))
+}
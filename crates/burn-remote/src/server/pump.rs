@@ -1,42 +1,54 @@
 //! The transport-agnostic session pump.
 //!
 //! One session is one duplex link. [`drive_session`] reads the init handshake, authorizes the peer,
-//! binds the session, replies with the device settings, then concurrently drains task responses to
-//! the sink (a detached writer) while forwarding submitted task batches from the source to the
-//! session worker. This is the single implementation both transports (iroh, websocket) drive — the
-//! per-transport modules only build the [`FrameSource`]/[`FrameSink`] halves and the authorizer.
+//! admits and binds the session, replies with the device settings, then concurrently drains task
+//! responses to the sink (a detached writer) while forwarding submitted task batches from the source
+//! to the session worker. This is the single implementation both transports (iroh, websocket) drive
+//! — the per-transport modules only build the [`FrameSource`]/[`FrameSink`] halves and the peer
+//! identity.
 
 use std::sync::Arc;
 
+use bytes::Bytes;
+use tokio::sync::{mpsc, oneshot};
+
 use crate::PeerId;
-use crate::server::service::{SessionService, parse_init_handshake};
+use crate::server::resume::SessionLink;
+use crate::server::service::{OpenError, SessionService, parse_init_handshake};
 use crate::server::spawn::spawn_detached;
+use crate::server::{AuthorizationRequest, PeerAuthorizer, PeerIdentity};
 use crate::shared::{
-    PROTOCOL_VERSION, RemoteMessage, SessionInfo, SessionInit, TaskResponse, TaskResponseContent,
+    PROTOCOL_VERSION, PayloadEncoding, RemoteMessage, SessionInfo, SessionResume, TaskResponse,
+    TaskResponseContent,
 };
+use crate::time::sleep;
 use crate::transport::link::{FrameSink, FrameSource};
 
 /// Drive one session to completion over a duplex link.
 ///
-/// `authorize` runs once, after the init handshake is parsed and before the session is bound — it
-/// is where a transport with an authenticated peer identity (iroh) enforces its policy; transports
-/// without one (websocket) pass an allow-all closure. `server_peer_id` is echoed to the client in
-/// the handshake response (the server's own identity, or `None` for websocket).
+/// `authorizer` checks `peer` (as authenticated by the transport) once, after the init handshake is
+/// parsed and before the session is bound; the session is then admitted against the server quotas.
+/// A peer over its session quota is sent a [`QuotaExceeded`](TaskResponseContent::QuotaExceeded)
+/// response in place of the handshake response. `server_peer_id` is echoed to the client in the
+/// handshake response (the server's own identity, or `None` for websocket).
+///
+/// When the link of a resumable session drops, the session is suspended rather than closed, and
+/// expires unless a new link resumes it within its grace period (see [`crate::server::resume`]).
 ///
-/// Returns `Err` on a protocol violation or a transport error; the caller logs it. A clean client
-/// `Close` (or stream end) returns `Ok(())`.
-pub(crate) async fn drive_session<Src, Snk, S, A>(
+/// Returns `Err` on a protocol violation, a rejected session or a transport error; the caller logs
+/// it. A clean client `Close` (or stream end) returns `Ok(())`.
+pub(crate) async fn drive_session<Src, Snk, S>(
     mut source: Src,
     mut sink: Snk,
     service: Arc<S>,
     server_peer_id: Option<PeerId>,
-    authorize: A,
+    peer: PeerIdentity,
+    authorizer: &dyn PeerAuthorizer,
 ) -> Result<(), String>
 where
     Src: FrameSource,
     Snk: FrameSink,
     S: SessionService,
-    A: FnOnce(&SessionInit) -> Result<(), String>,
 {
     // The session stream opens with exactly one `Init` frame.
     let handshake = source
@@ -46,18 +58,48 @@ where
     let init = parse_init_handshake(&handshake)?;
 
     // Authorize before any session state is created.
-    authorize(&init)?;
+    authorizer.authorize(AuthorizationRequest {
+        peer: peer.clone(),
+        device_index: init.device_index,
+        monitor: false,
+        credential: &init.authorization,
+    })?;
 
-    // Bind the session (creating it + its worker on demand) and claim its response receiver.
-    let task_sender = service
-        .session_task_sender(init.session_id, init.device_index)
-        .await;
-    let mut responses = service
-        .take_response_receiver(init.session_id, init.device_index)
-        .await?;
+    // Admit and bind the session (creating it + its worker on demand, or taking over a suspended
+    // one) and claim its response receiver.
+    let tasks = match service
+        .open_session(
+            init.session_id,
+            init.device_index,
+            &peer,
+            &init.resume,
+            PayloadEncoding::negotiate(&init.payload),
+        )
+        .await
+    {
+        Ok(tasks) => tasks,
+        Err(OpenError::QuotaExceeded(error)) => {
+            // Tell the client why, in place of the handshake response.
+            let reason = format!("Session {} refused: {error}", init.session_id);
+            let refusal = TaskResponse {
+                id: 0,
+                content: TaskResponseContent::QuotaExceeded(error),
+            };
+            sink.send(encode(&refusal)?).await?;
+            sink.close().await?;
+            return Err(reason);
+        }
+        Err(OpenError::Resume(reason)) => {
+            let _ = sink.close().await;
+            return Err(reason);
+        }
+    };
+    let link = tasks.link().cloned();
+    let mut responses = service.take_response_receiver(init.session_id).await?;
 
     // Reply with the selected device's settings + this server's identity, so the client can fill in
-    // `RemoteDevice::defaults`/`enumerate` without an extra round-trip.
+    // `RemoteDevice::defaults`/`enumerate` without an extra round-trip. A resumed session re-sends
+    // the responses its client missed right after.
     let info = TaskResponse {
         id: 0,
         content: TaskResponseContent::Init(SessionInfo {
@@ -65,79 +107,169 @@ where
             settings: service.device_settings(init.device_index),
             device_count: service.device_count(),
             peer_id: server_peer_id,
+            resume: link.as_ref().map(|link| link.grant()),
+            payload: tasks.payload(),
         }),
     };
-    let info = rmp_serde::to_vec(&info)
-        .map_err(|err| format!("Failed to encode session handshake response: {err}"))?;
-    sink.send(info.into()).await?;
+    let replay = match (&init.resume, &link) {
+        (
+            SessionResume::Resume {
+                responses_received, ..
+            },
+            Some(link),
+        ) => link.replay(*responses_received),
+        _ => Vec::new(),
+    };
 
     // Detached writer: drain the session's responses onto the sink until the queue closes (every
-    // sender — the worker and any in-flight readback task — has dropped).
-    let (writer_done, writer_result) = tokio::sync::oneshot::channel();
+    // sender — the worker and any in-flight readback task — has dropped), or until `stop` fires
+    // to hand the queue back to a suspended session.
+    let (stop, stopped) = oneshot::channel();
+    let (writer_done, writer_result) = oneshot::channel();
+    let writer_link = link.clone();
     spawn_detached(async move {
-        let result = async {
-            while let Some(response) = responses.recv().await {
-                let bytes = rmp_serde::to_vec(&response)
-                    .map_err(|err| format!("Failed to encode task response: {err}"))?;
-                sink.send(bytes.into()).await?;
-            }
-            sink.close().await
-        }
+        let result = write_responses(
+            &mut sink,
+            info,
+            replay,
+            &mut responses,
+            writer_link.as_deref(),
+            stopped,
+        )
         .await;
-        let _ = writer_done.send(result);
+        let _ = writer_done.send((result, responses));
     });
 
-    // Reader loop: forward each submitted task batch to the session worker in arrival order.
-    let result = loop {
-        let Some(frame) = source.recv().await? else {
-            break Ok(());
-        };
-        let messages: Vec<RemoteMessage> = rmp_serde::from_slice(&frame)
-            .map_err(|err| format!("Invalid remote task batch: {err}"))?;
-        let mut close = false;
-        let mut protocol_error = None;
-        for message in messages {
-            match message {
-                RemoteMessage::Task(task) => {
-                    task_sender
-                        .send(task)
-                        .await
-                        .map_err(|_| "Session worker stopped".to_string())?;
-                }
-                RemoteMessage::Close(id) if id == init.session_id => {
-                    close = true;
-                    break;
-                }
-                RemoteMessage::Close(id) => {
-                    protocol_error = Some(format!(
-                        "Session {} attempted to close unrelated session {id}",
-                        init.session_id
-                    ));
-                    break;
-                }
-                RemoteMessage::Init(_) => {
-                    protocol_error = Some("A session stream cannot be initialized twice".into());
-                    break;
+    // Reader loop: forward each submitted task batch to the session worker in arrival order. Runs
+    // as its own future so every exit, errors included, goes through the teardown below.
+    let result = async {
+        loop {
+            let frame = match source.recv().await {
+                Ok(Some(frame)) => frame,
+                Ok(None) => return Ok(LinkEnd::Dropped(None)),
+                Err(err) => return Ok(LinkEnd::Dropped(Some(err))),
+            };
+            let messages: Vec<RemoteMessage> = rmp_serde::from_slice(&frame)
+                .map_err(|err| format!("Invalid remote task batch: {err}"))?;
+            for message in messages {
+                match message {
+                    RemoteMessage::Task(task) => tasks.send(task).await?,
+                    RemoteMessage::Acknowledge(received) => {
+                        if let Some(link) = &link {
+                            link.acknowledge(received);
+                        }
+                    }
+                    RemoteMessage::Close(id) if id == init.session_id => {
+                        return Ok(LinkEnd::Closed);
+                    }
+                    RemoteMessage::Close(id) => {
+                        return Err(format!(
+                            "Session {} attempted to close unrelated session {id}",
+                            init.session_id
+                        ));
+                    }
+                    RemoteMessage::Init(_) => {
+                        return Err("A session stream cannot be initialized twice".into());
+                    }
                 }
             }
+            if let Some(link) = &link {
+                tasks.acknowledge(link.frame_received()).await?;
+            }
         }
-        if let Some(err) = protocol_error {
-            break Err(err);
-        }
-        if close {
-            break Ok(());
+    }
+    .await;
+
+    if let (Ok(LinkEnd::Dropped(reason)), Some(link)) = (&result, &link) {
+        // Keep the session for its client to resume: stop the writer, and hand the response queue
+        // back to the session for the next link.
+        let _ = stop.send(());
+        drop(tasks);
+        if let Ok((_, responses)) = writer_result.await {
+            let suspension = service.suspend(init.session_id, responses).await;
+            let grace = link.grace();
+            log::warn!(
+                "Session {} lost its link ({}); keeping it for {grace:?}",
+                init.session_id,
+                reason.as_deref().unwrap_or("stream closed")
+            );
+            let session_id = init.session_id;
+            spawn_detached(async move {
+                sleep(grace).await;
+                service.expire(session_id, suspension).await;
+            });
+            return Ok(());
         }
-    };
+        // The writer panicked and took the queue with it: the session can't be resumed.
+        service.close(init.session_id).await;
+        return Err(format!(
+            "Session {} lost its response queue",
+            init.session_id
+        ));
+    }
 
-    // Teardown: drop our task sender and close the session so its worker drains and exits, which
+    // Teardown: drop our task handle and close the session so its worker drains and exits, which
     // closes the response queue and ends the writer; then await the writer so we don't tear the
     // runtime down mid-send.
-    drop(task_sender);
+    drop(tasks);
     service.close(init.session_id).await;
     match writer_result.await {
-        Ok(Ok(())) => {}
-        Ok(Err(err)) => log::warn!("Session response writer failed: {err}"),
+        Ok((Ok(()), _)) => {}
+        Ok((Err(err), _)) => log::warn!("Session response writer failed: {err}"),
         Err(_) => log::warn!("Session response writer stopped before finishing"),
     }
-    result
+    drop(stop);
+    match result? {
+        LinkEnd::Closed | LinkEnd::Dropped(None) => Ok(()),
+        LinkEnd::Dropped(Some(err)) => Err(err),
+    }
+}
+
+/// How a session's link ended, short of a protocol error.
+enum LinkEnd {
+    /// The client closed the session.
+    Closed,
+    /// The link dropped, with the transport error if it didn't close cleanly.
+    Dropped(Option<String>),
+}
+
+/// Send the handshake response and the responses to replay, then the session's responses as they
+/// come, until the queue closes or `stop` fires. A resumable session keeps every response it sends
+/// in its `link` until the client acknowledges it.
+async fn write_responses<Snk: FrameSink>(
+    sink: &mut Snk,
+    handshake: TaskResponse,
+    replay: Vec<Bytes>,
+    responses: &mut mpsc::Receiver<TaskResponse>,
+    link: Option<&SessionLink>,
+    mut stop: oneshot::Receiver<()>,
+) -> Result<(), String> {
+    sink.send(encode(&handshake)?).await?;
+    for frame in replay {
+        sink.send(frame).await?;
+    }
+    loop {
+        let response = tokio::select! {
+            response = responses.recv() => response,
+            _ = &mut stop => return Ok(()),
+        };
+        let Some(response) = response else {
+            break;
+        };
+        let frame = encode(&response)?;
+        // Acknowledgements are regenerated on every link; anything else must reach the client.
+        if let Some(link) = link
+            && !matches!(response.content, TaskResponseContent::Acknowledge(_))
+        {
+            link.record(frame.clone());
+        }
+        sink.send(frame).await?;
+    }
+    sink.close().await
+}
+
+fn encode(response: &TaskResponse) -> Result<Bytes, String> {
+    rmp_serde::to_vec(response)
+        .map(Into::into)
+        .map_err(|err| format!("Failed to encode task response: {err}"))
 }
@@ -0,0 +1,504 @@
+//! Per-session and per-peer resource quotas.
+//!
+//! A [`QuotaTracker`] admits sessions against the per-peer session limit and hands each one a
+//! [`SessionUsage`], which the session pump and worker charge as tasks arrive and tensors are
+//! allocated. Usage is released when the last holder of a [`SessionUsage`] drops it, i.e. once the
+//! session worker has exited and freed the session's tensors.
+
+use std::collections::{HashMap, hash_map::Entry};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use burn_ir::{OperationIr, TensorId, TensorIr, TensorStatus};
+
+use super::PeerIdentity;
+use crate::shared::{QuotaExceeded, QuotaResource, QuotaScope};
+use crate::telemetry::QuotaUsage;
+
+/// Limits on the resources of one session, or of every session of one peer combined.
+///
+/// Unlimited by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct QuotaLimits {
+    max_resident_bytes: Option<u64>,
+    max_in_flight_ops: Option<u64>,
+}
+
+impl QuotaLimits {
+    /// Limit the bytes held by tensors resident on the device.
+    ///
+    /// Tensor sizes are estimated from their shape and dtype when they are created, so the
+    /// backend's own allocation overhead isn't counted.
+    pub fn with_max_resident_bytes(mut self, bytes: u64) -> Self {
+        self.max_resident_bytes = Some(bytes);
+        self
+    }
+
+    /// Limit the tasks submitted to the server that it hasn't executed yet.
+    ///
+    /// Clients batch their tasks, so keep this above the client flush threshold.
+    pub fn with_max_in_flight_ops(mut self, ops: u64) -> Self {
+        self.max_in_flight_ops = Some(ops);
+        self
+    }
+}
+
+/// Resource quotas enforced by a remote server.
+///
+/// A client going over a quota gets a [`QuotaExceeded`] error back and its session is closed:
+/// a rejected op would leave the client's view of its tensors out of sync with the server, so the
+/// session can't carry on. Unlimited by default.
+///
+/// Peers are identified by their Iroh endpoint id, their certificate under mutual TLS, or
+/// otherwise their IP address, so WebSocket clients behind the same NAT share the peer quotas.
+///
+/// ```rust,ignore
+/// let quotas = ServerQuotas::default()
+///     .with_session_limits(QuotaLimits::default().with_max_resident_bytes(4 << 30))
+///     .with_peer_limits(QuotaLimits::default().with_max_in_flight_ops(4096))
+///     .with_max_sessions_per_peer(8)
+///     .with_max_sessions(64);
+/// RemoteServerBuilder::<B>::new(devices).quotas(quotas).start();
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ServerQuotas {
+    session: QuotaLimits,
+    peer: QuotaLimits,
+    max_sessions_per_peer: Option<u64>,
+    max_sessions: Option<u64>,
+}
+
+impl ServerQuotas {
+    /// Limits applied to each session.
+    pub fn with_session_limits(mut self, limits: QuotaLimits) -> Self {
+        self.session = limits;
+        self
+    }
+
+    /// Limits applied to the combined usage of every session of a peer.
+    pub fn with_peer_limits(mut self, limits: QuotaLimits) -> Self {
+        self.peer = limits;
+        self
+    }
+
+    /// Limit the sessions a peer can have open at once.
+    pub fn with_max_sessions_per_peer(mut self, sessions: u64) -> Self {
+        self.max_sessions_per_peer = Some(sessions);
+        self
+    }
+
+    /// Limit the sessions open at once on the server, whatever their peer.
+    ///
+    /// Peers are told apart by their address when they don't authenticate, so this is what
+    /// bounds a client that connects from many addresses.
+    pub fn with_max_sessions(mut self, sessions: u64) -> Self {
+        self.max_sessions = Some(sessions);
+        self
+    }
+}
+
+/// What peers are told apart by when accounting their combined usage.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum PeerKey {
+    #[cfg(feature = "iroh")]
+    Iroh(iroh::EndpointId),
+    #[cfg(feature = "websocket")]
+    Certificate(Vec<u8>),
+    #[cfg(feature = "websocket")]
+    Address(std::net::IpAddr),
+}
+
+impl From<&PeerIdentity> for PeerKey {
+    fn from(peer: &PeerIdentity) -> Self {
+        match peer {
+            #[cfg(feature = "iroh")]
+            PeerIdentity::Iroh(id) => Self::Iroh(*id),
+            #[cfg(feature = "websocket")]
+            PeerIdentity::WebSocket(peer) => match peer.certificate() {
+                Some(certificate) => Self::Certificate(certificate.to_vec()),
+                None => Self::Address(peer.address().ip()),
+            },
+        }
+    }
+}
+
+/// Admits sessions and tracks the combined usage of each peer.
+#[derive(Default)]
+pub(crate) struct QuotaTracker {
+    quotas: ServerQuotas,
+    peers: Mutex<HashMap<PeerKey, Arc<PeerUsage>>>,
+    /// The sessions open on the server.
+    sessions: Arc<AtomicU64>,
+}
+
+#[derive(Default)]
+struct PeerUsage {
+    sessions: AtomicU64,
+    resident_bytes: AtomicU64,
+    in_flight_ops: AtomicU64,
+}
+
+impl QuotaTracker {
+    pub(crate) fn new(quotas: ServerQuotas) -> Self {
+        Self {
+            quotas,
+            peers: Mutex::default(),
+            sessions: Arc::default(),
+        }
+    }
+
+    /// Open a session for `peer`, unless it or the server already has as many sessions as it
+    /// may.
+    pub(crate) fn admit(&self, peer: &PeerIdentity) -> Result<Arc<SessionUsage>, QuotaExceeded> {
+        let mut peers = self.peers.lock().unwrap();
+        // Forget the peers whose sessions are all gone.
+        peers.retain(|_, usage| Arc::strong_count(usage) > 1);
+
+        let usage = peers.entry(PeerKey::from(peer)).or_default().clone();
+        // Admissions are serialized by the lock, so the checks can't race another admission.
+        charge(
+            &self.sessions,
+            1,
+            self.quotas.max_sessions,
+            QuotaScope::Server,
+            QuotaResource::Sessions,
+        )?;
+        charge(
+            &usage.sessions,
+            1,
+            self.quotas.max_sessions_per_peer,
+            QuotaScope::Peer,
+            QuotaResource::Sessions,
+        )
+        .inspect_err(|_| {
+            self.sessions.fetch_sub(1, Ordering::Relaxed);
+        })?;
+
+        Ok(Arc::new(SessionUsage {
+            quotas: self.quotas,
+            server_sessions: self.sessions.clone(),
+            peer: usage,
+            resident_bytes: AtomicU64::new(0),
+            in_flight_ops: AtomicU64::new(0),
+        }))
+    }
+}
+
+/// The usage of one session, charged against its own limits and its peer's.
+pub(crate) struct SessionUsage {
+    quotas: ServerQuotas,
+    server_sessions: Arc<AtomicU64>,
+    peer: Arc<PeerUsage>,
+    resident_bytes: AtomicU64,
+    in_flight_ops: AtomicU64,
+}
+
+impl SessionUsage {
+    /// A task was submitted to the session.
+    pub(crate) fn begin_op(&self) -> Result<(), QuotaExceeded> {
+        self.charge(
+            &self.in_flight_ops,
+            &self.peer.in_flight_ops,
+            1,
+            |limits| limits.max_in_flight_ops,
+            QuotaResource::InFlightOps,
+        )
+    }
+
+    /// A task submitted with [`begin_op`](Self::begin_op) was executed.
+    pub(crate) fn end_op(&self) {
+        self.in_flight_ops.fetch_sub(1, Ordering::Relaxed);
+        self.peer.in_flight_ops.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// `bytes` of tensors are about to be allocated on the device.
+    fn allocate(&self, bytes: u64) -> Result<(), QuotaExceeded> {
+        self.charge(
+            &self.resident_bytes,
+            &self.peer.resident_bytes,
+            bytes,
+            |limits| limits.max_resident_bytes,
+            QuotaResource::ResidentBytes,
+        )
+    }
+
+    /// `bytes` of tensors were freed.
+    fn release(&self, bytes: u64) {
+        self.resident_bytes.fetch_sub(bytes, Ordering::Relaxed);
+        self.peer.resident_bytes.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    /// The current usage of the session and its peer.
+    pub(crate) fn snapshot(&self) -> QuotaUsage {
+        QuotaUsage {
+            resident_bytes: self.resident_bytes.load(Ordering::Relaxed),
+            in_flight_ops: self.in_flight_ops.load(Ordering::Relaxed),
+            peer_resident_bytes: self.peer.resident_bytes.load(Ordering::Relaxed),
+            peer_in_flight_ops: self.peer.in_flight_ops.load(Ordering::Relaxed),
+            peer_sessions: self.peer.sessions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Charge `amount` to the session counter, then to the peer counter, rolling the session back
+    /// if the peer is over its limit.
+    fn charge(
+        &self,
+        session: &AtomicU64,
+        peer: &AtomicU64,
+        amount: u64,
+        limit: fn(&QuotaLimits) -> Option<u64>,
+        resource: QuotaResource,
+    ) -> Result<(), QuotaExceeded> {
+        charge(
+            session,
+            amount,
+            limit(&self.quotas.session),
+            QuotaScope::Session,
+            resource,
+        )?;
+        charge(
+            peer,
+            amount,
+            limit(&self.quotas.peer),
+            QuotaScope::Peer,
+            resource,
+        )
+        .inspect_err(|_| {
+            session.fetch_sub(amount, Ordering::Relaxed);
+        })
+    }
+}
+
+impl Drop for SessionUsage {
+    fn drop(&mut self) {
+        // Whatever the session still held is released along with it.
+        self.server_sessions.fetch_sub(1, Ordering::Relaxed);
+        let peer = &self.peer;
+        peer.sessions.fetch_sub(1, Ordering::Relaxed);
+        peer.resident_bytes
+            .fetch_sub(*self.resident_bytes.get_mut(), Ordering::Relaxed);
+        peer.in_flight_ops
+            .fetch_sub(*self.in_flight_ops.get_mut(), Ordering::Relaxed);
+    }
+}
+
+/// Add `amount` to `counter`, unless that takes it over `limit`.
+fn charge(
+    counter: &AtomicU64,
+    amount: u64,
+    limit: Option<u64>,
+    scope: QuotaScope,
+    resource: QuotaResource,
+) -> Result<(), QuotaExceeded> {
+    let requested = counter.fetch_add(amount, Ordering::Relaxed) + amount;
+    match limit {
+        Some(limit) if requested > limit => {
+            counter.fetch_sub(amount, Ordering::Relaxed);
+            Err(QuotaExceeded {
+                scope,
+                resource,
+                limit,
+                requested,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The tensors resident in a session's interpreter and their estimated sizes, maintained by the
+/// session worker alongside the interpreter's handle container.
+#[derive(Default)]
+pub(crate) struct ResidentTensors {
+    sizes: HashMap<TensorId, u64>,
+}
+
+impl ResidentTensors {
+    /// Account for `ops` before they run, in order: charge the tensors each op creates, then
+    /// release the ones it consumes, so a graph is charged its peak usage rather than the sum of
+    /// its intermediates.
+    ///
+    /// On error the accounting is left partially applied; the session is closed anyway.
+    pub(crate) fn apply<'a>(
+        &mut self,
+        usage: &SessionUsage,
+        ops: impl IntoIterator<Item = &'a OperationIr>,
+    ) -> Result<(), QuotaExceeded> {
+        for op in ops {
+            // Count every tensor the op touches that isn't tracked yet, inputs included, so a
+            // tensor whose creation the worker didn't see is still charged once it's used.
+            let mut created = 0;
+            for tensor in op.inputs().chain(op.outputs()) {
+                if let Entry::Vacant(entry) = self.sizes.entry(tensor.id) {
+                    created += *entry.insert(size_of(tensor));
+                }
+            }
+            usage.allocate(created)?;
+
+            if let OperationIr::Drop(tensor) = op {
+                self.remove(usage, tensor.id);
+            }
+            for tensor in op.inputs() {
+                self.consume(usage, tensor);
+            }
+        }
+        Ok(())
+    }
+
+    /// Charge a tensor registered from host data.
+    pub(crate) fn register(
+        &mut self,
+        usage: &SessionUsage,
+        id: TensorId,
+        bytes: u64,
+    ) -> Result<(), QuotaExceeded> {
+        usage.allocate(bytes)?;
+        if let Some(previous) = self.sizes.insert(id, bytes) {
+            usage.release(previous);
+        }
+        Ok(())
+    }
+
+    /// Track an alias without charging it: it shares its source's buffer.
+    pub(crate) fn alias(&mut self, id: TensorId) {
+        self.sizes.entry(id).or_insert(0);
+    }
+
+    /// Release `tensor` if the task using it takes ownership of its handle.
+    pub(crate) fn consume(&mut self, usage: &SessionUsage, tensor: &TensorIr) {
+        if tensor.status == TensorStatus::ReadWrite {
+            self.remove(usage, tensor.id);
+        }
+    }
+
+    fn remove(&mut self, usage: &SessionUsage, id: TensorId) {
+        if let Some(bytes) = self.sizes.remove(&id) {
+            usage.release(bytes);
+        }
+    }
+}
+
+fn size_of(tensor: &TensorIr) -> u64 {
+    (tensor.shape.num_elements() * tensor.dtype.size()) as u64
+}
+
+#[cfg(all(test, feature = "iroh"))]
+mod tests {
+    use super::*;
+    use burn_backend::{DType, Shape};
+    use burn_ir::{BinaryOpIr, NumericOperationIr};
+
+    fn peer(seed: u8) -> PeerIdentity {
+        PeerIdentity::Iroh(iroh::SecretKey::from_bytes(&[seed; 32]).public())
+    }
+
+    fn tensor(id: u64, status: TensorStatus) -> TensorIr {
+        TensorIr {
+            id: TensorId::new(id),
+            shape: Shape::new([4]),
+            dtype: DType::F32,
+            status,
+        }
+    }
+
+    #[test]
+    fn limits_the_sessions_of_each_peer() {
+        let tracker = QuotaTracker::new(ServerQuotas::default().with_max_sessions_per_peer(2));
+        let first = tracker.admit(&peer(1)).unwrap();
+        let _second = tracker.admit(&peer(1)).unwrap();
+
+        let err = tracker.admit(&peer(1)).err().unwrap();
+        assert_eq!(err.scope, QuotaScope::Peer);
+        assert_eq!(err.resource, QuotaResource::Sessions);
+        assert_eq!(err.requested, 3);
+
+        // Another peer has its own quota, and closing a session frees its slot.
+        tracker.admit(&peer(2)).unwrap();
+        drop(first);
+        tracker.admit(&peer(1)).unwrap();
+    }
+
+    #[test]
+    fn limits_the_sessions_of_the_server() {
+        let tracker = QuotaTracker::new(
+            ServerQuotas::default()
+                .with_max_sessions(2)
+                .with_max_sessions_per_peer(1),
+        );
+        let first = tracker.admit(&peer(1)).unwrap();
+
+        // Refused by its peer quota, the session doesn't take a server slot.
+        tracker.admit(&peer(1)).err().unwrap();
+        let _second = tracker.admit(&peer(2)).unwrap();
+
+        let err = tracker.admit(&peer(3)).err().unwrap();
+        assert_eq!(err.scope, QuotaScope::Server);
+        assert_eq!(err.resource, QuotaResource::Sessions);
+        assert_eq!(err.requested, 3);
+
+        drop(first);
+        tracker.admit(&peer(3)).unwrap();
+    }
+
+    #[test]
+    fn charges_in_flight_ops_to_the_session_and_its_peer() {
+        let tracker = QuotaTracker::new(
+            ServerQuotas::default()
+                .with_session_limits(QuotaLimits::default().with_max_in_flight_ops(2))
+                .with_peer_limits(QuotaLimits::default().with_max_in_flight_ops(3)),
+        );
+        let first = tracker.admit(&peer(1)).unwrap();
+        let second = tracker.admit(&peer(1)).unwrap();
+
+        first.begin_op().unwrap();
+        first.begin_op().unwrap();
+        let err = first.begin_op().unwrap_err();
+        assert_eq!(err.scope, QuotaScope::Session);
+
+        second.begin_op().unwrap();
+        let err = second.begin_op().unwrap_err();
+        assert_eq!(err.scope, QuotaScope::Peer);
+        // The session counter was rolled back along with the peer's.
+        assert_eq!(second.snapshot().in_flight_ops, 1);
+
+        first.end_op();
+        second.begin_op().unwrap();
+        assert_eq!(second.snapshot().peer_in_flight_ops, 3);
+
+        // A closed session gives its in-flight ops back to its peer.
+        drop(first);
+        assert_eq!(second.snapshot().peer_in_flight_ops, 2);
+    }
+
+    #[test]
+    fn tracks_the_tensors_ops_create_and_consume() {
+        let tracker = QuotaTracker::new(
+            ServerQuotas::default()
+                .with_session_limits(QuotaLimits::default().with_max_resident_bytes(48)),
+        );
+        let usage = tracker.admit(&peer(1)).unwrap();
+        let mut resident = ResidentTensors::default();
+        resident.register(&usage, TensorId::new(0), 16).unwrap();
+
+        // `2 = 0 + 1`, consuming `0`. `1` came from a same-host transfer and is sized on first use.
+        let add = OperationIr::NumericFloat(
+            DType::F32,
+            NumericOperationIr::Add(BinaryOpIr {
+                lhs: tensor(0, TensorStatus::ReadWrite),
+                rhs: tensor(1, TensorStatus::ReadOnly),
+                out: tensor(2, TensorStatus::NotInit),
+            }),
+        );
+        resident.apply(&usage, [&add]).unwrap();
+        assert_eq!(usage.snapshot().resident_bytes, 32);
+
+        // A second output doesn't fit, and nothing is charged for it.
+        let err = resident.register(&usage, TensorId::new(3), 17).unwrap_err();
+        assert_eq!(err.resource, QuotaResource::ResidentBytes);
+        assert_eq!(err.requested, 49);
+        assert_eq!(usage.snapshot().resident_bytes, 32);
+
+        let drop = OperationIr::Drop(tensor(2, TensorStatus::ReadWrite));
+        resident.apply(&usage, [&drop]).unwrap();
+        assert_eq!(usage.snapshot().resident_bytes, 16);
+    }
+}
@@ -0,0 +1,98 @@
+//! Session resumption.
+//!
+//! A resumable session outlives the link it was opened on: when the link drops, the server keeps
+//! the session (its worker, and so its tensors) for a grace period, waiting for the client to
+//! reconnect with the session's [`ResumeToken`]. Nothing sent across the drop may be lost, so each
+//! side keeps what it sent until the other acknowledges it:
+//!
+//! - the client keeps its frames, and the server acknowledges every frame it receives;
+//! - the server keeps its responses in a [`SessionLink`], and the client acknowledges the responses
+//!   it receives with its next frame.
+//!
+//! On resume, the client reports the responses it received and the server the frames it received,
+//! and each re-sends the rest.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use super::auth::constant_time_eq;
+use crate::shared::{ResumeGrant, ResumeToken, SentLog};
+
+/// The state a resumable session keeps across its links.
+pub(crate) struct SessionLink {
+    token: ResumeToken,
+    grace: Duration,
+    frames_received: AtomicU64,
+    /// The responses sent that the client has not acknowledged yet.
+    responses: Mutex<SentLog>,
+}
+
+impl SessionLink {
+    pub(crate) fn new(grace: Duration) -> Self {
+        Self {
+            token: ResumeToken::random(),
+            grace,
+            frames_received: AtomicU64::new(0),
+            responses: Mutex::default(),
+        }
+    }
+
+    /// Whether `token` is the one this session was granted, compared in constant time.
+    pub(crate) fn accepts(&self, token: &ResumeToken) -> bool {
+        constant_time_eq(self.token.as_bytes(), token.as_bytes())
+    }
+
+    /// How long the session is kept after its link drops.
+    pub(crate) fn grace(&self) -> Duration {
+        self.grace
+    }
+
+    /// What the client needs to resume the session later.
+    pub(crate) fn grant(&self) -> ResumeGrant {
+        ResumeGrant {
+            token: self.token,
+            grace: self.grace,
+            frames_received: self.frames_received.load(Ordering::Acquire),
+        }
+    }
+
+    /// Count a frame received from the client, returning the number received so far.
+    pub(crate) fn frame_received(&self) -> u64 {
+        self.frames_received.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    /// Keep an encoded response until the client acknowledges it.
+    pub(crate) fn record(&self, response: Bytes) {
+        self.responses.lock().unwrap().push(response);
+    }
+
+    /// The client has received its first `received` responses.
+    pub(crate) fn acknowledge(&self, received: u64) {
+        self.responses.lock().unwrap().acknowledge(received);
+    }
+
+    /// The responses to re-send to a client that received the first `received`.
+    pub(crate) fn replay(&self, received: u64) -> Vec<Bytes> {
+        self.responses.lock().unwrap().replay(received)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grants_the_frames_received_across_links() {
+        let link = SessionLink::new(Duration::from_secs(1));
+        assert_eq!(link.frame_received(), 1);
+        assert_eq!(link.frame_received(), 2);
+
+        let grant = link.grant();
+        assert!(link.accepts(&grant.token));
+        assert!(!link.accepts(&ResumeToken::random()));
+        assert_eq!(grant.frames_received, 2);
+    }
+}
@@ -13,29 +13,46 @@ use std::future::Future;
 use burn_std::DeviceSettings;
 use tokio::sync::mpsc;
 
-use crate::shared::{SessionId, Task, TaskResponse};
+use crate::server::PeerIdentity;
+use crate::server::session::SessionTasks;
+use crate::shared::{PayloadEncoding, QuotaExceeded, SessionId, SessionResume, TaskResponse};
 
-/// What the session pump needs from the session layer: bind a session to its worker channel, claim
-/// its response receiver, read the device metadata for the handshake, and tear a session down.
+/// Why a session link was refused.
+pub(crate) enum OpenError {
+    /// The peer may not open another session.
+    QuotaExceeded(QuotaExceeded),
+    /// The session can't be taken over: it is unknown, still attached to a link, or the token
+    /// doesn't match.
+    Resume(String),
+}
+
+/// What the session pump needs from the session layer: admit and bind a session to its worker
+/// channel, claim its response receiver, read the device metadata for the handshake, and suspend
+/// or tear a session down.
 ///
 /// Async methods return `impl Future + Send` so a session future built on them stays `Send` and can
 /// be spawned by the server. The production implementation is
 /// [`SessionManager`](super::session::SessionManager).
 pub(crate) trait SessionService: Send + Sync + 'static {
-    /// The channel forwarding tasks to `session_id`'s worker, creating the session (and spawning
-    /// its worker) on demand.
-    fn session_task_sender(
+    /// The handle forwarding tasks to `session_id`'s worker, creating the session (and spawning
+    /// its worker) on demand, or taking over a suspended session as `resume` asks. A new session
+    /// encodes its tensor payloads with `payload`. Errors if `peer` may not open another session,
+    /// or the session can't be resumed.
+    fn open_session(
         &self,
         session_id: SessionId,
         device_index: u32,
-    ) -> impl Future<Output = mpsc::Sender<Task>> + Send;
+        peer: &PeerIdentity,
+        resume: &SessionResume,
+        payload: PayloadEncoding,
+    ) -> impl Future<Output = Result<SessionTasks, OpenError>> + Send;
 
-    /// Claim the session's result receiver. Errors if a receiver was already taken — the protocol
-    /// allows only one session stream per session.
+    /// Claim the result receiver of a session opened with
+    /// [`open_session`](Self::open_session). Errors if a receiver was already taken — the
+    /// protocol allows only one session stream per session.
     fn take_response_receiver(
         &self,
         session_id: SessionId,
-        device_index: u32,
     ) -> impl Future<Output = Result<mpsc::Receiver<TaskResponse>, String>> + Send;
 
     /// The default settings of the device at `device_index`, returned on the handshake so the
@@ -46,6 +63,17 @@ pub(crate) trait SessionService: Send + Sync + 'static {
     /// enumerate every device behind the address.
     fn device_count(&self) -> u32;
 
+    /// Keep a resumable session whose link dropped, with the response receiver its link claimed.
+    /// Returns the suspension to [`expire`](Self::expire) after the grace period.
+    fn suspend(
+        &self,
+        session_id: SessionId,
+        receiver: mpsc::Receiver<TaskResponse>,
+    ) -> impl Future<Output = u64> + Send;
+
+    /// Drop the session if it is still waiting to be resumed since `suspension`.
+    fn expire(&self, session_id: SessionId, suspension: u64) -> impl Future<Output = ()> + Send;
+
     /// Drop the session, letting its worker drain and exit. A `close` for an unknown session is a
     /// no-op.
     fn close(&self, session_id: SessionId) -> impl Future<Output = ()> + Send;
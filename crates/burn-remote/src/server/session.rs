@@ -2,18 +2,24 @@ use burn_backend::tensor::Device;
 use burn_ir::BackendIr;
 use burn_router::{CustomOpRegistry, TensorInterpreter};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, hash_map::Entry},
     sync::{Arc, Once},
+    time::Duration,
 };
 use tokio::sync::{Mutex, mpsc};
 
 use crate::metrics::{MetricSide, logger_task};
 use crate::server::local_comm::LocalCommService;
-use crate::server::service::SessionService;
+use crate::server::quota::{QuotaTracker, SessionUsage};
+use crate::server::resume::SessionLink;
+use crate::server::service::{OpenError, SessionService};
 use crate::server::spawn::spawn_detached;
 use crate::server::transfer::TensorTransfer;
 use crate::server::worker::SessionHandler;
-use crate::shared::{SessionId, Task, TaskResponse};
+use crate::server::{PeerIdentity, ServerQuotas};
+use crate::shared::{
+    PayloadEncoding, SessionId, SessionResume, Task, TaskResponse, TaskResponseContent,
+};
 use crate::telemetry::{TelemetryEvent, TelemetryProbe};
 
 /// Capacity for the per-session response queue.
@@ -50,6 +56,11 @@ where
     /// Custom-op handlers shared (read-only) with every session's interpreter.
     custom_ops: CustomOpRegistry<B>,
     sessions: Mutex<HashMap<SessionId, Session>>,
+    /// Admits sessions and tracks their resource usage against the server quotas.
+    quotas: QuotaTracker,
+    /// How long a resumable session is kept after its link drops; `None` ends every session with
+    /// its link.
+    resume_grace: Option<Duration>,
     probe: TelemetryProbe,
     /// Spawns the telemetry logger once, on the first session.
     logger: Once,
@@ -58,7 +69,82 @@ where
 struct Session {
     /// Inbound channel to the session's dispatcher thread; cloned once per submit connection.
     task_sender: mpsc::Sender<Task>,
+    /// Lets the pump tell the client its session is over quota, ahead of the worker's responses.
+    response_sender: mpsc::Sender<TaskResponse>,
     receiver: Option<mpsc::Receiver<TaskResponse>>,
+    usage: Arc<SessionUsage>,
+    /// Present when the session outlives a dropped link.
+    link: Option<Arc<SessionLink>>,
+    /// How the session's tensor payloads are encoded, settled when it opened.
+    payload: PayloadEncoding,
+    /// Whether a link is driving the session; a suspended session waits to be resumed.
+    attached: bool,
+    /// Bumped on every suspension, so a stale grace-period expiry leaves a session resumed since
+    /// alone.
+    suspensions: u64,
+}
+
+/// The pump's handle on a bound session: forwards submitted tasks to the session worker, charging
+/// each against the in-flight quotas.
+pub(crate) struct SessionTasks {
+    session_id: SessionId,
+    task_sender: mpsc::Sender<Task>,
+    response_sender: mpsc::Sender<TaskResponse>,
+    usage: Arc<SessionUsage>,
+    link: Option<Arc<SessionLink>>,
+    payload: PayloadEncoding,
+    probe: TelemetryProbe,
+}
+
+impl SessionTasks {
+    /// The resumption state of the session, if it outlives a dropped link.
+    pub(crate) fn link(&self) -> Option<&Arc<SessionLink>> {
+        self.link.as_ref()
+    }
+
+    /// How the session's tensor payloads are encoded.
+    pub(crate) fn payload(&self) -> PayloadEncoding {
+        self.payload
+    }
+
+    /// Tell the client how many frames the server has received, ahead of the worker's responses.
+    pub(crate) async fn acknowledge(&self, frames: u64) -> Result<(), String> {
+        self.response_sender
+            .send(TaskResponse {
+                content: TaskResponseContent::Acknowledge(frames),
+                id: 0,
+            })
+            .await
+            .map_err(|_| "Session response writer stopped".to_string())
+    }
+
+    /// Forward `task` to the session worker.
+    ///
+    /// Over the in-flight quota, the client is sent a [`QuotaExceeded`] response instead and the
+    /// error is returned, so the pump closes the session.
+    pub(crate) async fn send(&self, task: Task) -> Result<(), String> {
+        if let Err(error) = self.usage.begin_op() {
+            let reason = format!("Session {} closed: {error}", self.session_id);
+            self.probe.emit(|| TelemetryEvent::QuotaExceeded {
+                session: self.session_id,
+                error: error.clone(),
+            });
+            // Best effort: the writer may already be gone if the client disconnected.
+            let _ = self
+                .response_sender
+                .send(TaskResponse {
+                    content: TaskResponseContent::QuotaExceeded(error),
+                    id: 0,
+                })
+                .await;
+            return Err(reason);
+        }
+
+        self.task_sender.send(task).await.map_err(|_| {
+            self.usage.end_op();
+            "Session worker stopped".to_string()
+        })
+    }
 }
 
 impl<B, T> SessionManager<B, T>
@@ -77,6 +163,8 @@ where
             local_comm: Arc::new(LocalCommService::new()),
             custom_ops: CustomOpRegistry::default(),
             sessions: Mutex::new(HashMap::new()),
+            quotas: QuotaTracker::default(),
+            resume_grace: None,
             probe: TelemetryProbe::disabled(),
             logger: Once::new(),
         }
@@ -88,6 +176,18 @@ where
         self
     }
 
+    /// Enforce `quotas` on every session.
+    pub fn with_quotas(mut self, quotas: ServerQuotas) -> Self {
+        self.quotas = QuotaTracker::new(quotas);
+        self
+    }
+
+    /// Keep resumable sessions for `grace` after their link drops.
+    pub fn with_resume_grace(mut self, grace: Option<Duration>) -> Self {
+        self.resume_grace = grace;
+        self
+    }
+
     /// Emit telemetry into `probe`.
     pub fn with_telemetry(mut self, probe: TelemetryProbe) -> Self {
         self.probe = probe;
@@ -121,42 +221,57 @@ where
             })
     }
 
-    async fn with_session<R>(
+    /// Start the handler of a newly admitted session.
+    fn spawn_session(
         &self,
         session_id: SessionId,
         device_index: u32,
-        f: impl FnOnce(&mut Session) -> R,
-    ) -> R {
-        self.ensure_logger();
-        let mut sessions = self.sessions.lock().await;
-        let entry = sessions.entry(session_id).or_insert_with(|| {
-            let (sender, receiver) = mpsc::channel(RESPONSE_CHANNEL_CAPACITY);
-            // The session is pinned to its device for its whole lifetime; the first handler
-            // (request or response) to touch it fixes the device index. Spawn the handler that
-            // owns the runner — this runs inside the tokio runtime, so the handler can capture
-            // the runtime handle its worker threads need for the async parts of a task.
-            let runner = TensorInterpreter::with_custom_ops(
-                self.device(device_index),
-                self.custom_ops.clone(),
-            );
-            let task_sender = SessionHandler::spawn(
-                session_id,
-                runner,
-                sender,
-                self.transfer.clone(),
-                self.local_comm.clone(),
-                self.probe.clone(),
-            );
-            self.probe.emit(|| TelemetryEvent::SessionOpened {
-                session: session_id,
-                device: device_index,
-            });
-            Session {
-                task_sender,
-                receiver: Some(receiver),
-            }
+        usage: Arc<SessionUsage>,
+        link: Option<Arc<SessionLink>>,
+        payload: PayloadEncoding,
+    ) -> Session {
+        let (sender, receiver) = mpsc::channel(RESPONSE_CHANNEL_CAPACITY);
+        // The session is pinned to its device for its whole lifetime. Spawn the handler that owns
+        // the runner — this runs inside the tokio runtime, so the handler can capture the runtime
+        // handle its worker threads need for the async parts of a task.
+        let runner =
+            TensorInterpreter::with_custom_ops(self.device(device_index), self.custom_ops.clone());
+        let task_sender = SessionHandler::spawn(
+            session_id,
+            runner,
+            sender.clone(),
+            self.transfer.clone(),
+            self.local_comm.clone(),
+            usage.clone(),
+            payload,
+            self.probe.clone(),
+        );
+        self.probe.emit(|| TelemetryEvent::SessionOpened {
+            session: session_id,
+            device: device_index,
         });
-        f(entry)
+        Session {
+            task_sender,
+            response_sender: sender,
+            receiver: Some(receiver),
+            usage,
+            link,
+            payload,
+            attached: true,
+            suspensions: 0,
+        }
+    }
+
+    fn tasks(&self, session_id: SessionId, session: &Session) -> SessionTasks {
+        SessionTasks {
+            session_id,
+            task_sender: session.task_sender.clone(),
+            response_sender: session.response_sender.clone(),
+            usage: session.usage.clone(),
+            link: session.link.clone(),
+            payload: session.payload,
+            probe: self.probe.clone(),
+        }
     }
 }
 
@@ -165,16 +280,76 @@ where
     B: BackendIr,
     T: TensorTransfer<B>,
 {
-    /// Resolve the channel used to forward [`Task`]s to `session_id`'s dispatcher thread,
-    /// creating the session (and spawning its handler) on demand. The pump resolves this once and
-    /// reuses it for every task, instead of re-locking the sessions map per task.
-    async fn session_task_sender(
+    /// Resolve the handle used to forward [`Task`]s to `session_id`'s dispatcher thread, creating
+    /// the session (and spawning its handler) on demand once `peer` is admitted, or taking over a
+    /// suspended session with its token. The pump resolves this once and reuses it for every task,
+    /// instead of re-locking the sessions map per task.
+    async fn open_session(
         &self,
         session_id: SessionId,
         device_index: u32,
-    ) -> mpsc::Sender<Task> {
-        self.with_session(session_id, device_index, |s| s.task_sender.clone())
-            .await
+        peer: &PeerIdentity,
+        resume: &SessionResume,
+        payload: PayloadEncoding,
+    ) -> Result<SessionTasks, OpenError> {
+        self.ensure_logger();
+        let mut sessions = self.sessions.lock().await;
+        let session = match (sessions.entry(session_id), resume) {
+            (Entry::Occupied(entry), SessionResume::Resume { token, .. }) => {
+                let session = entry.into_mut();
+                match &session.link {
+                    Some(link) if link.accepts(token) && !session.attached => {}
+                    Some(link) if link.accepts(token) => {
+                        // The server hasn't noticed the old link dropped yet; the client retries.
+                        return Err(OpenError::Resume(format!(
+                            "Session {session_id} is still attached to a link"
+                        )));
+                    }
+                    _ => {
+                        return Err(OpenError::Resume(format!(
+                            "Invalid resume token for session {session_id}"
+                        )));
+                    }
+                }
+                session.attached = true;
+                self.probe.emit(|| TelemetryEvent::SessionResumed {
+                    session: session_id,
+                });
+                session
+            }
+            (Entry::Vacant(_), SessionResume::Resume { .. }) => {
+                return Err(OpenError::Resume(format!(
+                    "Session {session_id} expired or was never opened"
+                )));
+            }
+            (Entry::Occupied(entry), _) => {
+                let session = entry.into_mut();
+                // Without the token, a resumable session can't be taken over.
+                if session.link.is_some() {
+                    return Err(OpenError::Resume(format!(
+                        "Session {session_id} is already open"
+                    )));
+                }
+                session
+            }
+            (Entry::Vacant(entry), resume) => {
+                let usage = self.quotas.admit(peer).map_err(|error| {
+                    self.probe.emit(|| TelemetryEvent::QuotaExceeded {
+                        session: session_id,
+                        error: error.clone(),
+                    });
+                    OpenError::QuotaExceeded(error)
+                })?;
+                let link = match (resume, self.resume_grace) {
+                    (SessionResume::Resumable, Some(grace)) => {
+                        Some(Arc::new(SessionLink::new(grace)))
+                    }
+                    _ => None,
+                };
+                entry.insert(self.spawn_session(session_id, device_index, usage, link, payload))
+            }
+        };
+        Ok(self.tasks(session_id, session))
     }
 
     /// Take the response receiver for `session_id`.
@@ -184,14 +359,47 @@ where
     async fn take_response_receiver(
         &self,
         session_id: SessionId,
-        device_index: u32,
     ) -> Result<mpsc::Receiver<TaskResponse>, String> {
-        self.with_session(session_id, device_index, |s| {
-            s.receiver
-                .take()
-                .ok_or_else(|| format!("Response receiver already taken for session {session_id}"))
-        })
-        .await
+        let mut sessions = self.sessions.lock().await;
+        let session = sessions
+            .get_mut(&session_id)
+            .ok_or_else(|| format!("Session {session_id} is not open"))?;
+        session
+            .receiver
+            .take()
+            .ok_or_else(|| format!("Response receiver already taken for session {session_id}"))
+    }
+
+    /// Keep a resumable session whose link dropped, handing its response receiver back for the link
+    /// that resumes it. Returns the suspension to [`expire`](Self::expire) once the grace period
+    /// is over.
+    async fn suspend(&self, session_id: SessionId, receiver: mpsc::Receiver<TaskResponse>) -> u64 {
+        let mut sessions = self.sessions.lock().await;
+        let Some(session) = sessions.get_mut(&session_id) else {
+            return 0;
+        };
+        session.receiver = Some(receiver);
+        session.attached = false;
+        session.suspensions += 1;
+        self.probe.emit(|| TelemetryEvent::SessionSuspended {
+            session: session_id,
+        });
+        session.suspensions
+    }
+
+    /// Close a session still waiting to be resumed since `suspension`.
+    async fn expire(&self, session_id: SessionId, suspension: u64) {
+        let mut sessions = self.sessions.lock().await;
+        let expired = sessions
+            .get(&session_id)
+            .is_some_and(|session| !session.attached && session.suspensions == suspension);
+        if expired {
+            log::info!("Session {session_id} was not resumed in time");
+            sessions.remove(&session_id);
+            self.probe.emit(|| TelemetryEvent::SessionClosed {
+                session: session_id,
+            });
+        }
     }
 
     /// The device settings for `device_index`, used by the handshake before any session-specific
@@ -6,20 +6,22 @@
 //! [`IrohTransfer`](crate::transport::iroh::IrohTransfer) over authenticated Iroh streams,
 //! [`WebSocketTransfer`](crate::transport::websocket::WebSocketTransfer) over the legacy data
 //! service — and the session worker drives it through this trait, knowing nothing about the wire.
+//!
+//! Tensors travel as [`TensorPayload`]s, encoded the way the exposing session negotiated with its
+//! client, so the downloading server must have the same codecs compiled in.
 
 use std::future::Future;
 
-use burn_backend::TensorData;
 use burn_ir::BackendIr;
 
-use crate::shared::TransferCapability;
+use crate::shared::{TensorPayload, TransferCapability};
 use crate::{PeerAddr, PeerId};
 
 /// Server-to-server tensor movement, independent from the compute-session transport.
 pub(crate) trait TensorTransfer<B: BackendIr>: Send + Sync + 'static {
     fn expose_data(
         &self,
-        data: TensorData,
+        payload: TensorPayload,
         max_downloads: u32,
         capability: TransferCapability,
         target: PeerId,
@@ -29,7 +31,7 @@ pub(crate) trait TensorTransfer<B: BackendIr>: Send + Sync + 'static {
         &self,
         remote: PeerAddr,
         capability: TransferCapability,
-    ) -> impl Future<Output = Option<TensorData>> + Send;
+    ) -> impl Future<Output = Option<TensorPayload>> + Send;
 
     fn fail(
         &self,
@@ -35,7 +35,8 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use burn_ir::{BackendIr, GraphBindings, GraphId};
+use burn_backend::TensorMetadata;
+use burn_ir::{BackendIr, GraphBindings, GraphId, HandleKind};
 use burn_router::{Graph, TensorInterpreter};
 use burn_std::id::StreamId;
 #[cfg(not(target_family = "wasm"))]
@@ -43,11 +44,15 @@ use tokio::runtime::Handle;
 use tokio::sync::mpsc;
 
 use crate::server::local_comm::LocalCommService;
+use crate::server::quota::{ResidentTensors, SessionUsage};
 use crate::server::spawn::spawn_detached;
 use crate::server::transfer::TensorTransfer;
-use crate::shared::{RequestId, SessionId, Task, TaskResponse, TaskResponseContent};
+use crate::shared::{
+    PayloadEncoding, QuotaExceeded, RequestId, SessionId, Task, TaskResponse, TaskResponseContent,
+    TensorPayload,
+};
 use crate::telemetry::{
-    TelemetryEvent, TelemetryProbe, TransferPhase, TransferScope, serialized_len,
+    PayloadRoute, TelemetryEvent, TelemetryProbe, TransferPhase, TransferScope, serialized_len,
 };
 use burn_ir::OperationIr;
 
@@ -83,9 +88,35 @@ where
     /// the cache from becoming a serialization point if graph execution is ever driven from more
     /// than the current single-FIFO worker.
     graphs: Mutex<HashMap<GraphId, Graph>>,
+    /// The session's usage, charged against the server quotas.
+    usage: Arc<SessionUsage>,
+    /// Estimated sizes of the tensors held by `runner`, kept in step with its handles.
+    resident: ResidentTensors,
+    /// How tensor payloads are encoded on the session's link and for transfers it exposes.
+    payload: PayloadEncoding,
     probe: TelemetryProbe,
 }
 
+/// Why a task failed.
+enum TaskError {
+    /// The task failed on its own; the session carries on.
+    Failed(String),
+    /// The task would have taken the session over a quota; the session is closed.
+    QuotaExceeded(QuotaExceeded),
+}
+
+impl From<String> for TaskError {
+    fn from(reason: String) -> Self {
+        Self::Failed(reason)
+    }
+}
+
+impl From<QuotaExceeded> for TaskError {
+    fn from(error: QuotaExceeded) -> Self {
+        Self::QuotaExceeded(error)
+    }
+}
+
 impl<B, T> SessionHandler<B, T>
 where
     B: BackendIr,
@@ -99,6 +130,8 @@ where
         response_sender: mpsc::Sender<TaskResponse>,
         transfer: Arc<T>,
         local_comm: Arc<LocalCommService<B>>,
+        usage: Arc<SessionUsage>,
+        payload: PayloadEncoding,
         probe: TelemetryProbe,
     ) -> mpsc::Sender<Task> {
         let handler = SessionHandler {
@@ -108,6 +141,9 @@ where
             transfer,
             local_comm,
             graphs: Mutex::new(HashMap::new()),
+            usage,
+            resident: ResidentTensors::default(),
+            payload,
             probe,
         };
         let (sender, receiver) = mpsc::channel(TASK_CHANNEL_CAPACITY);
@@ -138,13 +174,42 @@ where
 
         log::debug!("Session {session_id} worker started");
         while let Some(task) = receiver.recv().await {
-            if let Err(err) = self.process_task(task).await {
-                // One task failing doesn't tear down the session: read/sync/dtype failures surface
-                // to the client through their response, fire-and-forget failures are logged here,
-                // and the worker keeps processing subsequent tasks.
-                log::error!("Task on session {session_id} failed: {err}");
+            let result = self.process_task(task).await;
+            self.usage.end_op();
+            match result {
+                Ok(()) => {}
+                Err(TaskError::Failed(err)) => {
+                    // One task failing doesn't tear down the session: read/sync/dtype failures
+                    // surface to the client through their response, fire-and-forget failures are
+                    // logged here, and the worker keeps processing subsequent tasks.
+                    log::error!("Task on session {session_id} failed: {err}");
+                }
+                Err(TaskError::QuotaExceeded(error)) => {
+                    // The task didn't run, so every later task that uses its outputs would fail:
+                    // stop here and tell the client why its session is over.
+                    log::warn!("Session {session_id} closed: {error}");
+                    self.probe.emit(|| TelemetryEvent::QuotaExceeded {
+                        session: session_id,
+                        error: error.clone(),
+                    });
+                    if let Err(err) = self
+                        .send_response(0, TaskResponseContent::QuotaExceeded(error))
+                        .await
+                    {
+                        log::warn!("{err}");
+                    }
+                    break;
+                }
+            }
+            if receiver.is_empty() {
+                self.probe.emit(|| TelemetryEvent::QuotaUsage {
+                    session: session_id,
+                    usage: self.usage.snapshot(),
+                });
             }
         }
+        // Refuse any further task before the teardown below.
+        drop(receiver);
 
         // Reclaim any same-host transfers this session exposed that no target ever took, so a
         // half-finished transfer doesn't strand device memory in the shared registry.
@@ -189,11 +254,12 @@ where
     /// right pending callback on the client. Async work (data-service transfers,
     /// `read_tensor_async`) runs without a stream context — the relevant stream id is captured into
     /// the future at construction time via `executes`.
-    async fn process_task(&mut self, task: Task) -> Result<(), String> {
+    async fn process_task(&mut self, task: Task) -> Result<(), TaskError> {
         match task {
             Task::RegisterOperation(stream_id, op) => {
                 // An op received individually (not as part of a cached graph) is an unfused op.
                 self.emit_op(stream_id, &op);
+                self.resident.apply(&self.usage, [&op])?;
                 stream_id.executes(|| self.runner.register_op(op));
                 Ok(())
             }
@@ -212,12 +278,9 @@ where
                     )
                 });
                 self.emit_graph_executed(graph_id, stream_id, &bindings);
-                stream_id.executes(|| {
-                    let graph = Graph::new(relative_graph);
-                    self.graphs.lock().unwrap().insert(graph_id, graph.clone());
-                    graph.replay(&mut self.runner, bindings);
-                });
-                Ok(())
+                let graph = Graph::new(relative_graph);
+                self.graphs.lock().unwrap().insert(graph_id, graph.clone());
+                self.replay(stream_id, &graph, bindings)
             }
             Task::ExecuteGraph {
                 stream_id,
@@ -232,10 +295,15 @@ where
                         .ok_or_else(|| format!("Execute of unknown graph {graph_id:?}"))?
                 };
                 self.emit_graph_executed(graph_id, stream_id, &bindings);
-                stream_id.executes(|| graph.replay(&mut self.runner, bindings));
-                Ok(())
+                self.replay(stream_id, &graph, bindings)
             }
-            Task::RegisterTensor(stream_id, id, data) => {
+            Task::RegisterTensor(stream_id, id, payload) => {
+                self.probe
+                    .payload(self.session_id, PayloadRoute::Upload, &payload);
+                // Charge the decoded size before decoding allocates it.
+                let bytes = payload.decoded_len()?;
+                self.resident.register(&self.usage, id, bytes as u64)?;
+                let data = payload.decode()?;
                 stream_id.executes(|| self.runner.register_tensor_data_id(id, data));
                 Ok(())
             }
@@ -244,6 +312,7 @@ where
                 new_id,
                 src_id,
             } => {
+                self.resident.alias(new_id);
                 stream_id.executes(|| self.runner.register_alias(new_id, src_id));
                 Ok(())
             }
@@ -260,16 +329,28 @@ where
                     .download_tensor(remote.peer.clone(), remote.capability)
                     .await
                 {
-                    Some(data) => {
+                    Some(payload) => {
                         self.emit_transfer(peer, TransferScope::Remote, TransferPhase::Completed);
-                        data
+                        self.probe
+                            .payload(self.session_id, PayloadRoute::Transfer, &payload);
+                        let decode_err = |err: String| {
+                            format!(
+                                "Failed to decode tensor for transfer {:?} from {:?}: {err}",
+                                remote.capability, remote.peer,
+                            )
+                        };
+                        // Charge the decoded size before decoding allocates it.
+                        let bytes = payload.decoded_len().map_err(decode_err)?;
+                        self.resident.register(&self.usage, new_id, bytes as u64)?;
+                        payload.decode().map_err(decode_err)?
                     }
                     None => {
                         self.emit_transfer(peer, TransferScope::Remote, TransferPhase::Failed);
                         return Err(format!(
                             "Failed to download tensor for transfer {:?} from {:?}",
                             remote.capability, remote.peer,
-                        ));
+                        )
+                        .into());
                     }
                 };
                 // Register on the client stream that will consume `new_id`, carried over the
@@ -287,6 +368,7 @@ where
                 // pick up. Runs in order on this session's worker, so it is ordered after the op
                 // that produced `tensor` — the handle is guaranteed present. Read it back on the
                 // client stream that produced it, carried over the wire.
+                self.resident.consume(&self.usage, &tensor);
                 let kind = stream_id.executes(|| self.runner.get_tensor(&tensor));
                 self.local_comm
                     .expose(self.session_id, transfer_id, kind)
@@ -304,6 +386,8 @@ where
                 // registered first — same ordering contract as `RegisterTensorRemote`. The wait
                 // blocks only this session's worker, not the source session's.
                 let kind = self.local_comm.take(transfer_id).await;
+                self.resident
+                    .register(&self.usage, new_id, handle_bytes(&kind))?;
                 stream_id.executes(|| self.runner.register_tensor_to_device(new_id, kind));
                 self.emit_transfer(None, TransferScope::Local, TransferPhase::Completed);
                 Ok(())
@@ -316,6 +400,7 @@ where
                 target,
             } => {
                 log::trace!("Exposing tensor (transfer {capability:?})");
+                self.resident.consume(&self.usage, &tensor);
                 // Same shape as `ReadTensor`: the sync part of `read_tensor_async` runs in order
                 // to preserve stream ordering, but the readback + expose are detached so a
                 // cross-server hand-off doesn't stall this session's op registration on a
@@ -325,6 +410,7 @@ where
                 let transfer = self.transfer.clone();
                 let probe = self.probe.clone();
                 let session_id = self.session_id;
+                let encoding = self.payload;
                 let peer = Some(format!("{target:?}"));
                 probe.emit(|| TelemetryEvent::Transfer {
                     session: session_id,
@@ -335,7 +421,11 @@ where
                 spawn_detached(async move {
                     match fut.await {
                         Ok(data) => {
-                            transfer.expose_data(data, count, capability, target).await;
+                            let payload = TensorPayload::encode(data, encoding);
+                            probe.payload(session_id, PayloadRoute::Transfer, &payload);
+                            transfer
+                                .expose_data(payload, count, capability, target)
+                                .await;
                             probe.emit(|| TelemetryEvent::Transfer {
                                 session: session_id,
                                 peer,
@@ -378,16 +468,24 @@ where
                     session: self.session_id,
                     request: request_id,
                 });
+                self.resident.consume(&self.usage, &tensor);
                 let fut = stream_id.executes(|| self.runner.read_tensor_async(tensor));
                 let sender = self.response_sender.clone();
+                let probe = self.probe.clone();
+                let session_id = self.session_id;
+                let encoding = self.payload;
                 spawn_detached(async move {
                     // Under an async runtime the backend reads eagerly (see `RuntimeKind::Async`),
                     // so `data` is already host-resident here — the fetch handler only serializes
                     // resident bytes and no blocking device→host copy runs on the shared runtime.
-                    let data = fut.await;
+                    let payload = fut.await.map(|data| {
+                        let payload = TensorPayload::encode(data, encoding);
+                        probe.payload(session_id, PayloadRoute::Download, &payload);
+                        payload
+                    });
                     if sender
                         .send(TaskResponse {
-                            content: TaskResponseContent::ReadTensor(data),
+                            content: TaskResponseContent::ReadTensor(payload),
                             id: request_id,
                         })
                         .await
@@ -408,15 +506,35 @@ where
                 let res = stream_id.executes(|| self.runner.sync());
                 self.send_response(request_id, TaskResponseContent::SyncBackend(res))
                     .await
+                    .map_err(TaskError::from)
             }
             Task::DTypeUsage(request_id, dtype) => {
                 let res = self.runner.dtype_usage(dtype);
                 self.send_response(request_id, TaskResponseContent::DTypeUsage(res))
                     .await
+                    .map_err(TaskError::from)
             }
         }
     }
 
+    /// Replay a cached graph with `bindings`. The whole graph is charged against the quotas before
+    /// any of it runs, so an over-quota graph doesn't execute halfway.
+    fn replay(
+        &mut self,
+        stream_id: StreamId,
+        graph: &Graph,
+        bindings: GraphBindings,
+    ) -> Result<(), TaskError> {
+        let bound = graph.bind(bindings);
+        self.resident.apply(&self.usage, &bound.operations)?;
+        stream_id.executes(|| {
+            for op in bound.operations {
+                self.runner.register_op(op);
+            }
+        });
+        Ok(())
+    }
+
     async fn send_response(
         &self,
         request_id: RequestId,
@@ -455,3 +573,14 @@ where
         });
     }
 }
+
+/// Device bytes held by a transferred tensor, for the resident quota.
+fn handle_bytes<B: BackendIr>(kind: &HandleKind<B>) -> u64 {
+    let (shape, dtype) = match kind {
+        HandleKind::Float(tensor) => (tensor.shape(), tensor.dtype()),
+        HandleKind::Int(tensor) => (tensor.shape(), tensor.dtype()),
+        HandleKind::Bool(tensor) => (tensor.shape(), tensor.dtype()),
+        HandleKind::Quantized(tensor) => (tensor.shape(), tensor.dtype()),
+    };
+    (shape.num_elements() * dtype.size()) as u64
+}
@@ -1,4 +1,16 @@
+mod monitor;
+mod payload;
+mod quota;
+#[cfg(any(feature = "client", feature = "server"))]
+mod resume;
 mod task;
 
+pub use monitor::{MonitorFrame, MonitorInit};
+#[cfg(any(feature = "client", feature = "server"))]
+pub(crate) use payload::supports;
+pub use payload::{EncodedTensor, PayloadEncoding, PayloadOffer, TensorPayload};
+pub use quota::{QuotaExceeded, QuotaResource, QuotaScope};
+#[cfg(any(feature = "client", feature = "server"))]
+pub(crate) use resume::SentLog;
 #[allow(unused_imports)]
 pub(crate) use task::*;
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use crate::telemetry::TelemetryEvent;
+
+/// Opens a telemetry feed: the single frame a monitor sends after connecting.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MonitorInit {
+    pub version: u16,
+    /// Opaque application credential interpreted by the compute node's authorizer.
+    #[serde(with = "serde_bytes")]
+    pub authorization: Vec<u8>,
+}
+
+impl MonitorInit {
+    #[cfg(feature = "client")]
+    pub fn new(authorization: Vec<u8>) -> Self {
+        Self {
+            version: super::PROTOCOL_VERSION,
+            authorization,
+        }
+    }
+}
+
+/// What a compute node sends down a telemetry feed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum MonitorFrame {
+    /// The events emitted since the previous frame, in order.
+    Events(Vec<TelemetryEvent>),
+    /// The monitor fell behind and this many events were dropped.
+    Lagged(u64),
+    /// The feed was refused; the node closes it after this frame.
+    Refused(String),
+}
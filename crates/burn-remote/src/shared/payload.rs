@@ -0,0 +1,608 @@
+//! Encoding of the tensor payloads a session moves: uploads, reads, and transfers between compute
+//! nodes.
+//!
+//! The client offers the codecs it would like, and optionally a reduced precision for float
+//! tensors, in a [`PayloadOffer`]; the server settles on a [`PayloadEncoding`] and hands it back
+//! with the handshake. Both sides then encode what they send with it. An [`EncodedTensor`]
+//! describes its own encoding, so decoding one needs no session state.
+
+use burn_backend::TensorData;
+use burn_std::config::remote::{LossyPrecision, PayloadCompression};
+#[cfg(any(feature = "client", feature = "server"))]
+use burn_std::{
+    Bytes, e4m3,
+    quantization::{
+        QPARAM_ALIGN, QuantScheme, QuantStore, QuantValue, QuantizedBytes, ScaleDtype,
+        quantized_data_len,
+    },
+};
+use burn_std::{DType, Shape};
+use serde::{Deserialize, Serialize};
+
+/// Payloads smaller than this are sent as is: encoding them saves less than it costs.
+#[cfg(any(feature = "client", feature = "server"))]
+const MIN_ENCODED_LEN: usize = 4096;
+
+/// The largest magnitude e4m3 holds; FP8 payloads are scaled so their peak lands on it.
+#[cfg(any(feature = "client", feature = "server"))]
+const FP8_MAX: f32 = 448.0;
+
+/// What a client would like its payloads encoded with, sent with its
+/// [`SessionInit`](super::SessionInit).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PayloadOffer {
+    /// Codecs the client can decode, most preferred first.
+    pub compression: Vec<PayloadCompression>,
+    /// Opt-in reduced precision for float tensors.
+    pub lossy: Option<LossyPrecision>,
+}
+
+impl PayloadOffer {
+    /// The offer configured under `[remote.payload]`, less the codecs this build lacks.
+    #[cfg(feature = "client")]
+    pub(crate) fn from_config() -> Self {
+        let config = burn_std::config::config();
+        let payload = &config.remote().payload;
+        Self {
+            compression: payload
+                .compression
+                .iter()
+                .copied()
+                .filter(|&codec| supports(codec))
+                .collect(),
+            lossy: payload.lossy,
+        }
+    }
+}
+
+/// How a session encodes its payloads, settled by the server and sent back with its
+/// [`SessionInfo`](super::SessionInfo).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayloadEncoding {
+    /// `None` when the two sides have no codec in common.
+    pub compression: Option<PayloadCompression>,
+    pub lossy: Option<LossyPrecision>,
+}
+
+impl PayloadEncoding {
+    /// Settle on the first codec of `offer` this build supports, and the precision it asks for.
+    #[cfg(feature = "server")]
+    pub(crate) fn negotiate(offer: &PayloadOffer) -> Self {
+        Self {
+            compression: offer
+                .compression
+                .iter()
+                .copied()
+                .find(|&codec| supports(codec)),
+            lossy: offer.lossy,
+        }
+    }
+}
+
+/// Whether this build can encode and decode `codec`.
+#[cfg(any(feature = "client", feature = "server"))]
+pub(crate) fn supports(codec: PayloadCompression) -> bool {
+    match codec {
+        PayloadCompression::Lz4 => cfg!(feature = "lz4"),
+        PayloadCompression::Zstd => cfg!(feature = "zstd"),
+    }
+}
+
+/// A tensor's data on the wire.
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TensorPayload {
+    /// Sent as is: the session negotiated no encoding, or it wouldn't have paid off.
+    Raw(TensorData),
+    /// Compressed and/or narrowed as the session's [`PayloadEncoding`] says. Its header comes from
+    /// the peer, so it is checked against the shape and dtype before anything is decompressed.
+    Encoded(EncodedTensor),
+}
+
+/// Tensor data compressed and/or narrowed to a lower precision.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EncodedTensor {
+    /// The shape and dtype the payload decodes to.
+    shape: Shape,
+    dtype: DType,
+    narrowed: Option<Narrowed>,
+    compression: Option<PayloadCompression>,
+    /// The length of the tensor's bytes before encoding.
+    raw_len: usize,
+    /// The length of the (narrowed) bytes before compression.
+    len: usize,
+    #[serde(with = "serde_bytes")]
+    bytes: Vec<u8>,
+}
+
+/// How a float tensor was narrowed, with what it takes to widen it back.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum Narrowed {
+    BF16,
+    /// e4m3 bits of the values divided by `scale`.
+    FP8 {
+        scale: f32,
+    },
+    /// [`QuantizedBytes`](burn_std::quantization::QuantizedBytes) of the values, flattened and zero-padded to `padded`, a whole number of
+    /// blocks.
+    BlockQuantized {
+        block_size: u8,
+        padded: usize,
+    },
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl TensorPayload {
+    /// Encode `data` as `encoding` says. Data too small to be worth it, or that no part of the
+    /// encoding applies to, is sent raw.
+    pub(crate) fn encode(data: TensorData, encoding: PayloadEncoding) -> Self {
+        let raw_len = data.bytes.len();
+        if raw_len < MIN_ENCODED_LEN || encoding == PayloadEncoding::default() {
+            return Self::Raw(data);
+        }
+        let shape = data.shape.clone();
+        let dtype = data.dtype;
+
+        let (narrowed, data) = match encoding.lossy {
+            Some(precision) if dtype.is_float() => match narrow(data, precision) {
+                Ok((narrowed, data)) => (Some(narrowed), data),
+                Err((err, data)) => {
+                    log::warn!("Sending a {dtype:?} payload at full precision: {err}");
+                    (None, data)
+                }
+            },
+            _ => (None, data),
+        };
+        let bytes = match data.into_bytes().try_into_vec::<u8>() {
+            Ok(bytes) => bytes,
+            Err(bytes) => bytes.to_vec(),
+        };
+        let len = bytes.len();
+
+        let (compression, bytes) = match encoding
+            .compression
+            .map(|codec| (codec, compress(codec, &bytes)))
+        {
+            // Already-dense data (e.g. narrowed noise) can come out larger.
+            Some((codec, Ok(compressed))) if compressed.len() < len => (Some(codec), compressed),
+            Some((codec, Err(err))) => {
+                log::warn!("Sending a payload uncompressed: {codec:?} failed: {err}");
+                (None, bytes)
+            }
+            _ => (None, bytes),
+        };
+
+        if narrowed.is_none() && compression.is_none() {
+            return Self::Raw(TensorData::from_bytes_vec(bytes, shape, dtype));
+        }
+        Self::Encoded(EncodedTensor {
+            shape,
+            dtype,
+            narrowed,
+            compression,
+            raw_len,
+            len,
+            bytes,
+        })
+    }
+
+    /// The tensor data, decompressed and widened back to its dtype.
+    pub(crate) fn decode(self) -> Result<TensorData, String> {
+        let encoded = match self {
+            Self::Raw(data) => return Ok(data),
+            Self::Encoded(encoded) => encoded,
+        };
+        // `len` sizes the decompression buffer: trust it only once it matches the tensor.
+        encoded.check_len()?;
+        let bytes = match encoded.compression {
+            Some(codec) => decompress(codec, &encoded.bytes, encoded.len)?,
+            None => encoded.bytes,
+        };
+        if bytes.len() != encoded.len {
+            return Err(format!(
+                "Payload decoded to {} bytes, expected {}",
+                bytes.len(),
+                encoded.len
+            ));
+        }
+        match encoded.narrowed {
+            Some(narrowed) => widen(bytes, narrowed, encoded.shape, encoded.dtype),
+            None => Ok(TensorData::from_bytes_vec(
+                bytes,
+                encoded.shape,
+                encoded.dtype,
+            )),
+        }
+    }
+
+    /// The length of the tensor's bytes once decoded, so it can be charged to a quota before
+    /// decoding allocates them.
+    pub(crate) fn decoded_len(&self) -> Result<usize, String> {
+        match self {
+            Self::Raw(data) => Ok(data.bytes.len()),
+            Self::Encoded(encoded) => {
+                encoded.check_len()?;
+                match encoded.narrowed {
+                    // Widened back to a float dtype, which `check_len` sized without overflow.
+                    Some(_) => Ok(encoded.shape.num_elements() * encoded.dtype.size()),
+                    None => Ok(encoded.len),
+                }
+            }
+        }
+    }
+
+    /// The tensor's bytes before and after encoding, if it was encoded.
+    pub(crate) fn encoded_len(&self) -> Option<(usize, usize)> {
+        match self {
+            Self::Raw(_) => None,
+            Self::Encoded(encoded) => Some((encoded.raw_len, encoded.bytes.len())),
+        }
+    }
+
+    /// The bytes the payload puts on the wire.
+    pub(crate) fn wire_len(&self) -> usize {
+        match self {
+            Self::Raw(data) => data.bytes.len(),
+            Self::Encoded(encoded) => encoded.bytes.len(),
+        }
+    }
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl EncodedTensor {
+    /// Check that `len` is what the shape and dtype take once narrowed, before it is used to size
+    /// a buffer.
+    fn check_len(&self) -> Result<(), String> {
+        match self.expected_len() {
+            Some((min, max)) if (min..=max).contains(&self.len) => Ok(()),
+            Some((min, max)) if min == max => Err(format!(
+                "Payload of {} bytes can't hold a {:?} tensor of shape {:?} narrowed as {:?}, \
+                 which takes {min}",
+                self.len, self.dtype, self.shape, self.narrowed
+            )),
+            Some((min, max)) => Err(format!(
+                "Payload of {} bytes can't hold a {:?} tensor of shape {:?} narrowed as {:?}, \
+                 which takes {min} to {max}",
+                self.len, self.dtype, self.shape, self.narrowed
+            )),
+            None => Err(format!(
+                "A {:?} tensor of shape {:?} can't be narrowed as {:?}",
+                self.dtype, self.shape, self.narrowed
+            )),
+        }
+    }
+
+    /// The range of lengths the narrowed bytes can have, or `None` if the header is inconsistent.
+    ///
+    /// Quantized layouts align their scales, so they may run a few bytes past the packed length.
+    fn expected_len(&self) -> Option<(usize, usize)> {
+        let numel = self
+            .shape
+            .iter()
+            .try_fold(1usize, |numel, &dim| numel.checked_mul(dim))?;
+        let quantized = |scheme: &QuantScheme, shape: &Shape| {
+            let len = quantized_data_len(scheme, shape);
+            (len, len + 2 * QPARAM_ALIGN)
+        };
+        match self.narrowed {
+            None => match self.dtype {
+                DType::QFloat(scheme) => Some(quantized(&scheme, &self.shape)),
+                dtype => numel.checked_mul(dtype.size()).map(|len| (len, len)),
+            },
+            // Only float tensors are narrowed, and they widen back to their own dtype.
+            Some(_) if !self.dtype.is_float() => None,
+            Some(_) if numel.checked_mul(self.dtype.size()).is_none() => None,
+            Some(Narrowed::BF16) => {
+                let len = numel * DType::BF16.size();
+                Some((len, len))
+            }
+            Some(Narrowed::FP8 { .. }) => Some((numel, numel)),
+            Some(Narrowed::BlockQuantized { block_size, padded }) => {
+                let block_size = block_size as usize;
+                // Padded to the next whole block, no further.
+                (numel.checked_next_multiple_of(block_size) == Some(padded))
+                    .then(|| quantized(&block_scheme(block_size as u8), &Shape::new([padded])))
+            }
+        }
+    }
+}
+
+/// Narrow a float tensor to `precision`. Gives the data back untouched when it can't be.
+#[cfg(any(feature = "client", feature = "server"))]
+fn narrow(
+    data: TensorData,
+    precision: LossyPrecision,
+) -> Result<(Narrowed, TensorData), (String, TensorData)> {
+    match precision {
+        LossyPrecision::BF16 if data.dtype.size() <= DType::BF16.size() => {
+            Err(("it is no wider than bf16".to_string(), data))
+        }
+        LossyPrecision::BF16 => Ok((Narrowed::BF16, data.convert_dtype(DType::BF16))),
+        LossyPrecision::FP8 => {
+            let values = f32_values(&data).map_err(|err| (err, data))?;
+            let scale = scale_for(peak(values.iter()), FP8_MAX);
+            let bits: Vec<u8> = values
+                .iter()
+                .map(|&value| e4m3::from_f32(value / scale).to_bits())
+                .collect();
+            let len = bits.len();
+            Ok((
+                Narrowed::FP8 { scale },
+                TensorData::from_bytes_vec(bits, [len], DType::U8),
+            ))
+        }
+        LossyPrecision::BlockQuantized { block_size: 0 } => {
+            Err(("its block size is 0".to_string(), data))
+        }
+        LossyPrecision::BlockQuantized { block_size } => {
+            let mut values = f32_values(&data).map_err(|err| (err, data))?;
+            let padded = values.len().next_multiple_of(block_size as usize);
+            values.resize(padded, 0.0);
+
+            let scales: Vec<f32> = values
+                .chunks(block_size as usize)
+                .map(|block| scale_for(peak(block.iter()), 127.0))
+                .collect();
+            let quantized: Vec<i8> = values
+                .chunks(block_size as usize)
+                .zip(&scales)
+                .flat_map(|(block, scale)| {
+                    block
+                        .iter()
+                        .map(move |value| (value / scale).round().clamp(-127.0, 127.0) as i8)
+                })
+                .collect();
+            let scheme = block_scheme(block_size);
+            Ok((
+                Narrowed::BlockQuantized { block_size, padded },
+                TensorData::quantized(quantized, [padded], scheme, &scales, None),
+            ))
+        }
+    }
+}
+
+/// Widen narrowed `bytes` back to a tensor of `shape` and `dtype`.
+#[cfg(any(feature = "client", feature = "server"))]
+fn widen(
+    bytes: Vec<u8>,
+    narrowed: Narrowed,
+    shape: Shape,
+    dtype: DType,
+) -> Result<TensorData, String> {
+    let numel = shape.num_elements();
+    let values: Vec<f32> = match narrowed {
+        Narrowed::BF16 if bytes.len() == numel * DType::BF16.size() => {
+            return TensorData::from_bytes_vec(bytes, shape, DType::BF16)
+                .try_cast(dtype)
+                .map_err(|err| err.to_string());
+        }
+        Narrowed::FP8 { scale } if bytes.len() == numel => bytes
+            .iter()
+            .map(|&bits| e4m3::from_bits(bits).to_f32() * scale)
+            .collect(),
+        Narrowed::BlockQuantized { block_size, padded }
+            if block_size > 0
+                && padded >= numel
+                && padded.is_multiple_of(block_size as usize)
+                && bytes.len()
+                    >= quantized_data_len(&block_scheme(block_size), &Shape::new([padded])) =>
+        {
+            let (values, scales) = QuantizedBytes {
+                bytes: Bytes::from_bytes_vec(bytes),
+                scheme: block_scheme(block_size),
+                shape: Shape::new([padded]),
+            }
+            .into_vec_i8();
+            values
+                .chunks(block_size as usize)
+                .zip(&scales.block)
+                .flat_map(|(block, &scale)| block.iter().map(move |&value| value as f32 * scale))
+                .take(numel)
+                .collect()
+        }
+        narrowed => {
+            return Err(format!(
+                "{} bytes can't hold {numel} values narrowed as {narrowed:?}",
+                bytes.len()
+            ));
+        }
+    };
+    if values.len() != numel {
+        return Err(format!(
+            "Narrowed payload holds {} values, expected {numel}",
+            values.len()
+        ));
+    }
+    TensorData::new(values, shape)
+        .try_cast(dtype)
+        .map_err(|err| err.to_string())
+}
+
+/// Symmetric 8-bit quantization with an f32 scale per `block_size` values.
+#[cfg(any(feature = "client", feature = "server"))]
+fn block_scheme(block_size: u8) -> QuantScheme {
+    QuantScheme::default()
+        .with_value(QuantValue::Q8S)
+        .per_block([block_size], ScaleDtype::F32)
+        .with_store(QuantStore::Native)
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+fn f32_values(data: &TensorData) -> Result<Vec<f32>, String> {
+    data.clone()
+        .try_cast(DType::F32)
+        .and_then(|data| data.try_into_vec::<f32>())
+        .map_err(|err| err.to_string())
+}
+
+/// The largest finite magnitude in `values`. Infinities saturate rather than blow up the scale.
+#[cfg(any(feature = "client", feature = "server"))]
+fn peak<'a>(values: impl Iterator<Item = &'a f32>) -> f32 {
+    values
+        .filter(|value| value.is_finite())
+        .fold(0.0, |peak, value| peak.max(value.abs()))
+}
+
+/// The scale mapping `peak` onto `max`; 1 for an all-zero tensor or block.
+#[cfg(any(feature = "client", feature = "server"))]
+fn scale_for(peak: f32, max: f32) -> f32 {
+    if peak > 0.0 { peak / max } else { 1.0 }
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+fn compress(codec: PayloadCompression, bytes: &[u8]) -> Result<Vec<u8>, String> {
+    match codec {
+        #[cfg(feature = "lz4")]
+        PayloadCompression::Lz4 => Ok(lz4_flex::block::compress(bytes)),
+        #[cfg(feature = "zstd")]
+        PayloadCompression::Zstd => zstd::bulk::compress(bytes, zstd::DEFAULT_COMPRESSION_LEVEL)
+            .map_err(|err| err.to_string()),
+        #[allow(unreachable_patterns)]
+        codec => Err(format!("{codec:?} is not compiled in")),
+    }
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+fn decompress(codec: PayloadCompression, bytes: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    match codec {
+        #[cfg(feature = "lz4")]
+        PayloadCompression::Lz4 => {
+            lz4_flex::block::decompress(bytes, len).map_err(|err| err.to_string())
+        }
+        #[cfg(feature = "zstd")]
+        PayloadCompression::Zstd => {
+            zstd::bulk::decompress(bytes, len).map_err(|err| err.to_string())
+        }
+        #[allow(unreachable_patterns)]
+        codec => Err(format!("{codec:?} is not compiled in")),
+    }
+}
+
+#[cfg(all(test, any(feature = "client", feature = "server")))]
+mod tests {
+    use super::*;
+
+    /// A smooth ramp, so it compresses and narrows predictably.
+    fn ramp(len: usize) -> TensorData {
+        let values: Vec<f32> = (0..len).map(|i| (i % 256) as f32 / 16.0 - 8.0).collect();
+        TensorData::new(values, [len / 8, 8])
+    }
+
+    fn max_error(data: &TensorData, decoded: &TensorData) -> f32 {
+        data.iter::<f32>()
+            .zip(decoded.iter::<f32>())
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f32::max)
+    }
+
+    #[test]
+    fn small_payloads_are_sent_raw() {
+        let encoding = PayloadEncoding {
+            compression: Some(PayloadCompression::Lz4),
+            lossy: Some(LossyPrecision::FP8),
+        };
+        let payload = TensorPayload::encode(TensorData::new(vec![1.0f32; 8], [8]), encoding);
+        assert!(matches!(payload, TensorPayload::Raw(_)));
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn compression_is_lossless() {
+        let data = ramp(4096);
+        let encoding = PayloadEncoding {
+            compression: Some(PayloadCompression::Lz4),
+            lossy: None,
+        };
+        let payload = TensorPayload::encode(data.clone(), encoding);
+        let (raw, wire) = payload.encoded_len().expect("the ramp compresses");
+        assert!(wire < raw);
+
+        let decoded = payload.decode().unwrap();
+        assert_eq!(decoded.shape, data.shape);
+        assert_eq!(decoded.as_bytes(), data.as_bytes());
+    }
+
+    #[test]
+    fn lossy_precisions_round_trip_within_their_error() {
+        let data = ramp(4096);
+        for (lossy, tolerance) in [
+            (LossyPrecision::BF16, 0.04),
+            (LossyPrecision::FP8, 0.5),
+            (LossyPrecision::BlockQuantized { block_size: 32 }, 0.04),
+        ] {
+            let encoding = PayloadEncoding {
+                compression: None,
+                lossy: Some(lossy),
+            };
+            let payload = TensorPayload::encode(data.clone(), encoding);
+            let (raw, wire) = payload.encoded_len().expect("float data narrows");
+            assert!(wire <= raw / 2, "{lossy:?} sent {wire} of {raw} bytes");
+
+            let decoded = payload.decode().unwrap();
+            assert_eq!(decoded.shape, data.shape);
+            assert_eq!(decoded.dtype, DType::F32);
+            let error = max_error(&data, &decoded);
+            assert!(error <= tolerance, "{lossy:?} is off by {error}");
+        }
+    }
+
+    #[test]
+    fn block_quantization_pads_a_partial_block() {
+        // 1027 values: the last block holds 3.
+        let values: Vec<f32> = (0..1027).map(|i| i as f32).collect();
+        let data = TensorData::new(values, [1027]);
+        let encoding = PayloadEncoding {
+            compression: None,
+            lossy: Some(LossyPrecision::BlockQuantized { block_size: 16 }),
+        };
+        let decoded = TensorPayload::encode(data.clone(), encoding)
+            .decode()
+            .unwrap();
+        assert_eq!(decoded.shape, data.shape);
+        assert!(max_error(&data, &decoded) <= 4.1);
+    }
+
+    #[test]
+    fn integer_tensors_are_never_narrowed() {
+        let data = TensorData::new((0..2048i32).collect::<Vec<_>>(), [2048]);
+        let encoding = PayloadEncoding {
+            compression: None,
+            lossy: Some(LossyPrecision::FP8),
+        };
+        let payload = TensorPayload::encode(data.clone(), encoding);
+        assert!(matches!(payload, TensorPayload::Raw(_)));
+    }
+
+    #[test]
+    fn a_forged_length_is_rejected_before_decoding() {
+        let encoding = PayloadEncoding {
+            compression: None,
+            lossy: Some(LossyPrecision::FP8),
+        };
+        let TensorPayload::Encoded(mut encoded) = TensorPayload::encode(ramp(4096), encoding)
+        else {
+            panic!("float data narrows");
+        };
+        // A peer claiming more bytes than the tensor takes must not get them allocated.
+        encoded.len = usize::MAX;
+        let payload = TensorPayload::Encoded(encoded.clone());
+        assert!(payload.decoded_len().is_err());
+        assert!(payload.decode().is_err());
+
+        // Nor can it describe a tensor too large to be sized.
+        encoded.len = 4096;
+        encoded.shape = Shape::new([usize::MAX, 2]);
+        assert!(TensorPayload::Encoded(encoded).decode().is_err());
+    }
+
+    #[test]
+    fn decoded_len_is_the_widened_size() {
+        let data = ramp(4096);
+        let encoding = PayloadEncoding {
+            compression: None,
+            lossy: Some(LossyPrecision::BlockQuantized { block_size: 32 }),
+        };
+        let payload = TensorPayload::encode(data.clone(), encoding);
+        assert_eq!(payload.decoded_len(), Ok(data.bytes.len()));
+    }
+}
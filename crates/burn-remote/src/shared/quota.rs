@@ -0,0 +1,63 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Who a quota applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QuotaScope {
+    /// A single session.
+    Session,
+    /// Every session of one peer, combined.
+    Peer,
+    /// Every session of the server, combined.
+    Server,
+}
+
+/// The resource a quota limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QuotaResource {
+    /// Bytes held by the tensors resident on the device.
+    ResidentBytes,
+    /// Tasks submitted to the server and not yet executed.
+    InFlightOps,
+    /// Concurrently open sessions.
+    Sessions,
+}
+
+/// A session was refused or terminated because it went over one of the server's quotas.
+///
+/// Sent to the client in place of the response it was waiting for, and reported through
+/// [`TelemetryEvent::QuotaExceeded`](crate::telemetry::TelemetryEvent::QuotaExceeded).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaExceeded {
+    /// Who the exceeded quota applies to.
+    pub scope: QuotaScope,
+    /// The limited resource.
+    pub resource: QuotaResource,
+    /// The configured limit.
+    pub limit: u64,
+    /// The usage the rejected request would have reached.
+    pub requested: u64,
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scope = match self.scope {
+            QuotaScope::Session => "session",
+            QuotaScope::Peer => "peer",
+            QuotaScope::Server => "server",
+        };
+        let resource = match self.resource {
+            QuotaResource::ResidentBytes => "resident tensor bytes",
+            QuotaResource::InFlightOps => "in-flight operations",
+            QuotaResource::Sessions => "concurrent sessions",
+        };
+        write!(
+            f,
+            "{scope} quota exceeded: {} {resource} requested, the server allows {}",
+            self.requested, self.limit
+        )
+    }
+}
+
+impl std::error::Error for QuotaExceeded {}
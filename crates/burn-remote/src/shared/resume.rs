@@ -0,0 +1,70 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+/// The frames one side of a resumable session sent and the other has not acknowledged yet: what it
+/// re-sends when the session resumes on a new link.
+#[derive(Default)]
+pub(crate) struct SentLog {
+    /// The number of frames acknowledged, i.e. the index of the first one kept.
+    acknowledged: u64,
+    unacknowledged: VecDeque<Bytes>,
+}
+
+impl SentLog {
+    /// Keep a sent frame until it is acknowledged.
+    pub(crate) fn push(&mut self, frame: Bytes) {
+        self.unacknowledged.push_back(frame);
+    }
+
+    /// The other side has received the first `received` frames.
+    pub(crate) fn acknowledge(&mut self, received: u64) {
+        // Acknowledgements can arrive out of date (a frame re-sent after a resume); only ever move
+        // forward, and never past what was sent.
+        let sent = self.acknowledged + self.unacknowledged.len() as u64;
+        let received = received.clamp(self.acknowledged, sent);
+        self.unacknowledged
+            .drain(..(received - self.acknowledged) as usize);
+        self.acknowledged = received;
+    }
+
+    /// The frames sent after the first `received`, to re-send. They are kept: the new link may
+    /// drop too.
+    pub(crate) fn replay(&mut self, received: u64) -> Vec<Bytes> {
+        self.acknowledge(received);
+        self.unacknowledged.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_with(frames: &[&'static str]) -> SentLog {
+        let mut log = SentLog::default();
+        for frame in frames {
+            log.push(Bytes::from_static(frame.as_bytes()));
+        }
+        log
+    }
+
+    #[test]
+    fn replays_the_frames_after_those_received() {
+        let mut log = log_with(&["a", "b", "c"]);
+        assert_eq!(log.replay(1), vec!["b", "c"]);
+        assert_eq!(log.replay(1), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn acknowledgements_only_move_forward() {
+        let mut log = log_with(&["a", "b", "c"]);
+        log.acknowledge(2);
+        log.acknowledge(1);
+        assert_eq!(log.replay(0), vec!["c"]);
+
+        // The other side can't have received frames that weren't sent.
+        log.acknowledge(10);
+        log.push(Bytes::from_static(b"d"));
+        assert_eq!(log.replay(3), vec!["d"]);
+    }
+}
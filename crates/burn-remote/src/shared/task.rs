@@ -1,4 +1,4 @@
-use burn_backend::{DTypeUsageSet, ExecutionError, TensorData};
+use burn_backend::{DTypeUsageSet, ExecutionError};
 use burn_ir::{GraphBindings, GraphId, OperationIr, TensorId, TensorIr};
 use burn_std::{
     DType, DeviceSettings,
@@ -6,7 +6,9 @@ use burn_std::{
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::time::Duration;
 
+use super::{PayloadEncoding, PayloadOffer, QuotaExceeded, TensorPayload};
 use crate::{PeerAddr, PeerId};
 
 /// Current Burn Remote application-protocol version.
@@ -39,23 +41,51 @@ impl From<u64> for LocalTransferId {
 }
 
 /// Unforgeable, bounded-use capability authorizing a peer-to-peer tensor download.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TransferCapability([u8; 32]);
 
+impl core::fmt::Debug for TransferCapability {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Never print the secret, it is enough to download the tensor over WebSocket.
+        f.write_str("TransferCapability(..)")
+    }
+}
+
 impl TransferCapability {
     #[cfg(feature = "client")]
     pub fn random() -> Self {
         Self(rand::random())
     }
 
-    /// Deterministic compatibility key for the legacy 64-bit WebSocket transfer service.
+    /// The secret bytes of the capability.
     #[cfg(feature = "websocket")]
-    pub(crate) fn legacy_id(self) -> u64 {
-        u64::from_le_bytes(
-            self.0[..8]
-                .try_into()
-                .expect("slice has exactly eight bytes"),
-        )
+    pub(crate) fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+}
+
+/// Secret handed out with a resumable session. A new link presenting it takes the session over after
+/// the link it was opened on dropped.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumeToken([u8; 32]);
+
+impl ResumeToken {
+    #[cfg(feature = "server")]
+    pub(crate) fn random() -> Self {
+        Self(rand::random())
+    }
+
+    /// The secret bytes of the token.
+    #[cfg(feature = "server")]
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl core::fmt::Debug for ResumeToken {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // Never log the secret itself.
+        f.write_str("ResumeToken(..)")
     }
 }
 
@@ -96,6 +126,9 @@ pub enum RemoteMessage {
     /// Open a session bound to the device at the given index on the server.
     Init(SessionInit),
     Close(SessionId),
+    /// The number of responses the client has received so far on a resumable session, so the
+    /// server can forget them.
+    Acknowledge(u64),
 }
 
 /// Client-side session handshake.
@@ -108,6 +141,11 @@ pub struct SessionInit {
     /// Opaque application credential interpreted by the compute node's authorizer.
     #[serde(with = "serde_bytes")]
     pub authorization: Vec<u8>,
+    /// Whether the session may outlive this link.
+    pub resume: SessionResume,
+    /// How the client would like tensor payloads encoded.
+    #[serde(default)]
+    pub payload: PayloadOffer,
 }
 
 impl SessionInit {
@@ -118,8 +156,52 @@ impl SessionInit {
             session_id,
             device_index,
             authorization,
+            resume: SessionResume::Unsupported,
+            payload: PayloadOffer::default(),
         }
     }
+
+    /// Set whether the session may outlive this link.
+    #[cfg(any(feature = "client", test))]
+    pub fn with_resume(mut self, resume: SessionResume) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Offer to encode tensor payloads.
+    #[cfg(feature = "client")]
+    pub fn with_payload(mut self, payload: PayloadOffer) -> Self {
+        self.payload = payload;
+        self
+    }
+}
+
+/// How a session relates to the link its [`SessionInit`] arrives on.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub enum SessionResume {
+    /// The session ends with its link.
+    #[default]
+    Unsupported,
+    /// Open a session the client will try to resume if its link drops.
+    Resumable,
+    /// Take over a session whose link dropped, presenting the token it was granted with. The
+    /// server re-sends the responses after the first `responses_received`.
+    Resume {
+        token: ResumeToken,
+        responses_received: u64,
+    },
+}
+
+/// Granted by the server with the handshake response of a resumable session.
+#[allow(missing_docs)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResumeGrant {
+    pub token: ResumeToken,
+    /// How long the server keeps the session after its link drops.
+    pub grace: Duration,
+    /// The number of frames the server has received on the session, across its links. The client
+    /// re-sends the ones after.
+    pub frames_received: u64,
 }
 
 /// Server-side session handshake response.
@@ -131,6 +213,11 @@ pub struct SessionInfo {
     pub device_count: u32,
     /// Authenticated identity of the compute node, when the transport provides one.
     pub peer_id: Option<PeerId>,
+    /// Present when the session outlives a dropped link.
+    pub resume: Option<ResumeGrant>,
+    /// How both sides encode the session's tensor payloads.
+    #[serde(default)]
+    pub payload: PayloadEncoding,
 }
 
 #[allow(missing_docs)]
@@ -170,7 +257,7 @@ pub enum Task {
         graph_id: GraphId,
         bindings: GraphBindings,
     },
-    RegisterTensor(StreamId, TensorId, TensorData),
+    RegisterTensor(StreamId, TensorId, TensorPayload),
     /// Register `new_id` as an alias of `src_id` — a second server handle over the same buffer.
     ///
     /// Emitted by the fusion layer's cross-stream sharing so a tensor used on multiple streams
@@ -230,7 +317,13 @@ pub enum TaskResponseContent {
     /// Server responds with the selected device's settings plus the total number of devices
     /// it hosts (so the client can enumerate them, see [`RemoteDevice::enumerate`]).
     Init(SessionInfo),
-    ReadTensor(Result<TensorData, ExecutionError>),
+    ReadTensor(Result<TensorPayload, ExecutionError>),
     SyncBackend(Result<(), ExecutionError>),
     DTypeUsage(DTypeUsageSet),
+    /// The session went over a server quota and is being closed. Unsolicited: the id is unused and
+    /// the reason applies to every pending and future request of the session.
+    QuotaExceeded(QuotaExceeded),
+    /// The number of frames the server has received so far on a resumable session, so the client
+    /// can forget them. Unsolicited, and not counted as a response: the id is unused.
+    Acknowledge(u64),
 }
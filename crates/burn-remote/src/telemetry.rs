@@ -14,7 +14,9 @@ use burn_std::id::StreamId;
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
-use crate::shared::{RequestId, SessionId};
+#[cfg(any(feature = "client", feature = "server"))]
+use crate::shared::TensorPayload;
+use crate::shared::{QuotaExceeded, RequestId, SessionId};
 
 /// A tensor as it appears in the dataflow graph: identity plus the shape and dtype it was
 /// produced with.
@@ -235,6 +237,43 @@ pub enum TransferPhase {
     Failed,
 }
 
+/// Which way a tensor payload travels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PayloadRoute {
+    /// From the client to its server.
+    Upload,
+    /// From the server back to its client, for a read.
+    Download,
+    /// Between two compute nodes.
+    Transfer,
+}
+
+impl PayloadRoute {
+    /// Stable lowercase label for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            PayloadRoute::Upload => "upload",
+            PayloadRoute::Download => "download",
+            PayloadRoute::Transfer => "transfer",
+        }
+    }
+}
+
+/// Resource usage of a session and of its peer, as charged against the server quotas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    /// Estimated bytes of the session's resident tensors.
+    pub resident_bytes: u64,
+    /// Tasks submitted to the session and not executed yet.
+    pub in_flight_ops: u64,
+    /// Estimated bytes of the resident tensors of every session of the peer.
+    pub peer_resident_bytes: u64,
+    /// Tasks submitted to every session of the peer and not executed yet.
+    pub peer_in_flight_ops: u64,
+    /// Sessions the peer has open.
+    pub peer_sessions: u64,
+}
+
 /// A single observation from a session worker.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TelemetryEvent {
@@ -245,6 +284,14 @@ pub enum TelemetryEvent {
     SessionClosed {
         session: SessionId,
     },
+    /// A resumable session lost its link and waits to be resumed.
+    SessionSuspended {
+        session: SessionId,
+    },
+    /// A suspended session was taken over by a new link.
+    SessionResumed {
+        session: SessionId,
+    },
     Op {
         session: SessionId,
         stream: StreamId,
@@ -282,6 +329,29 @@ pub enum TelemetryEvent {
         stream: StreamId,
         bindings_bytes: usize,
     },
+    /// Emitted whenever a session worker catches up with its queue.
+    QuotaUsage {
+        session: SessionId,
+        usage: QuotaUsage,
+    },
+    /// A session was refused or closed for going over a quota.
+    QuotaExceeded {
+        session: SessionId,
+        error: QuotaExceeded,
+    },
+    /// A tensor payload crossed the wire: `bytes` as sent, after any encoding.
+    Payload {
+        session: SessionId,
+        route: PayloadRoute,
+        bytes: usize,
+    },
+    /// A tensor payload was compressed or narrowed before going on the wire.
+    PayloadEncoded {
+        session: SessionId,
+        route: PayloadRoute,
+        raw_bytes: usize,
+        wire_bytes: usize,
+    },
 }
 
 impl TelemetryEvent {
@@ -351,6 +421,24 @@ impl TelemetryProbe {
         Self { tx: None }
     }
 
+    /// The probe of a compute node: active when remote logging or monitoring is configured, so
+    /// the logger or a monitor can subscribe to it.
+    #[cfg(feature = "server")]
+    pub(crate) fn for_server() -> Self {
+        if crate::metrics::TelemetryLogger::enabled()
+            || burn_std::config::config().remote().monitoring
+        {
+            Self::new(CHANNEL_CAPACITY)
+        } else {
+            Self::disabled()
+        }
+    }
+
+    /// Whether subscribers can attach, i.e. the probe isn't [`disabled`](Self::disabled).
+    pub fn is_enabled(&self) -> bool {
+        self.tx.is_some()
+    }
+
     /// Create an active probe with no initial subscription. Subscribers attach later with
     /// [`subscribe`](Self::subscribe); the probe stays inert until at least one is listening.
     pub fn new(capacity: usize) -> Self {
@@ -387,6 +475,25 @@ impl TelemetryProbe {
             let _ = tx.send(Arc::new(event()));
         }
     }
+
+    /// Emit a [`TelemetryEvent::Payload`] for `payload` crossing the wire, and a
+    /// [`TelemetryEvent::PayloadEncoded`] if it is encoded.
+    #[cfg(any(feature = "client", feature = "server"))]
+    pub(crate) fn payload(&self, session: SessionId, route: PayloadRoute, payload: &TensorPayload) {
+        self.emit(|| TelemetryEvent::Payload {
+            session,
+            route,
+            bytes: payload.wire_len(),
+        });
+        if let Some((raw_bytes, wire_bytes)) = payload.encoded_len() {
+            self.emit(|| TelemetryEvent::PayloadEncoded {
+                session,
+                route,
+                raw_bytes,
+                wire_bytes,
+            });
+        }
+    }
 }
 
 /// Receiving end of a [`TelemetryProbe`].
@@ -437,7 +544,8 @@ struct GraphCost {
     ops: u64,
 }
 
-/// A running fold of the op-graph caching economics over a telemetry stream.
+/// A running fold of the op-graph caching and payload encoding economics over a telemetry
+/// stream.
 #[derive(Default)]
 pub struct TrafficAggregator {
     graphs: HashMap<GraphId, GraphCost>,
@@ -446,6 +554,9 @@ pub struct TrafficAggregator {
     fused_ops: u64,
     unfused_ops: u64,
     unfused_by_kind: HashMap<OpClass, u64>,
+    payload_raw: u64,
+    payload_wire: u64,
+    payloads: u64,
 }
 
 impl TrafficAggregator {
@@ -482,6 +593,15 @@ impl TrafficAggregator {
                 self.unfused_ops += 1;
                 *self.unfused_by_kind.entry(OpClass::Drop).or_default() += 1;
             }
+            TelemetryEvent::PayloadEncoded {
+                raw_bytes,
+                wire_bytes,
+                ..
+            } => {
+                self.payload_raw += *raw_bytes as u64;
+                self.payload_wire += *wire_bytes as u64;
+                self.payloads += 1;
+            }
             _ => {}
         }
     }
@@ -495,6 +615,14 @@ impl TrafficAggregator {
         }
     }
 
+    pub fn payload_snapshot(&self) -> PayloadSnapshot {
+        PayloadSnapshot {
+            payloads: self.payloads,
+            raw: self.payload_raw,
+            wire: self.payload_wire,
+        }
+    }
+
     /// Unfused-op tally by class, highest first.
     pub fn unfused_by_kind(&self) -> Vec<(OpClass, u64)> {
         let mut entries: Vec<_> = self
@@ -525,6 +653,20 @@ impl FusionSnapshot {
     }
 }
 
+/// The tensor payloads encoded so far, and their bytes before and after encoding.
+pub struct PayloadSnapshot {
+    pub payloads: u64,
+    pub raw: u64,
+    pub wire: u64,
+}
+
+impl PayloadSnapshot {
+    /// Bytes the raw payloads would have moved, minus what their encodings moved.
+    pub fn saved(&self) -> u64 {
+        self.raw.saturating_sub(self.wire)
+    }
+}
+
 pub(crate) fn serialized_len<T: Serialize>(value: &T) -> usize {
     rmp_serde::to_vec(value)
         .map(|bytes| bytes.len())
@@ -1,3 +1,5 @@
+//! Timers on either runtime: tokio natively, the JS event loop on wasm.
+
 use core::{future::Future, time::Duration};
 
 #[cfg(not(target_family = "wasm"))]
@@ -11,11 +13,13 @@ pub(crate) async fn sleep(duration: Duration) {
 }
 
 /// `Err(())` if `duration` elapses before `future` completes.
+#[cfg_attr(not(any(feature = "client", feature = "iroh")), allow(dead_code))]
 #[cfg(not(target_family = "wasm"))]
 pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, ()> {
     tokio::time::timeout(duration, future).await.map_err(|_| ())
 }
 
+#[cfg_attr(not(any(feature = "client", feature = "iroh")), allow(dead_code))]
 #[cfg(target_family = "wasm")]
 pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, ()> {
     use futures_util::future::{Either, select};
@@ -19,6 +19,8 @@ pub const BURN_REMOTE_ALPN: &[u8] = b"burn/remote/1";
 pub(crate) enum StreamKind {
     Session,
     TensorTransfer,
+    /// A monitor reading the node's telemetry.
+    Telemetry,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -1,6 +1,6 @@
 //! Iroh protocol handler for Burn Remote compute and tensor-transfer streams.
 
-use std::{fmt, sync::Arc};
+use std::{fmt, sync::Arc, time::Duration};
 
 use burn_backend::tensor::Device;
 use burn_ir::BackendIr;
@@ -13,8 +13,11 @@ use iroh::{
 
 use crate::{
     PeerId,
-    server::{pump::drive_session, session::SessionManager, spawn::spawn_detached},
-    telemetry::TelemetryProbe,
+    server::{
+        PeerAuthorizer, PeerIdentity, ServerQuotas, monitor::drive_monitor, pump::drive_session,
+        session::SessionManager, spawn::spawn_detached,
+    },
+    telemetry::{CHANNEL_CAPACITY, TelemetryProbe},
 };
 
 use super::{
@@ -22,42 +25,11 @@ use super::{
     node::{RemoteNode, StreamKind},
 };
 
-/// Information presented to a compute node before a remote session is accepted.
-pub struct AuthorizationRequest<'a> {
-    /// Authenticated Iroh identity of the connecting peer.
-    pub peer: EndpointId,
-    /// Compute-device index requested by the peer.
-    pub device_index: u32,
-    /// Opaque credential supplied by the application when creating the remote device.
-    pub credential: &'a [u8],
-}
-
-/// Application authorization policy for incoming compute sessions.
-pub trait PeerAuthorizer: Send + Sync + 'static {
-    /// Return `Ok(())` to allow the session, or a user-facing rejection reason.
-    fn authorize(&self, request: AuthorizationRequest<'_>) -> Result<(), String>;
-}
-
-impl<F> PeerAuthorizer for F
-where
-    F: Fn(AuthorizationRequest<'_>) -> Result<(), String> + Send + Sync + 'static,
-{
-    fn authorize(&self, request: AuthorizationRequest<'_>) -> Result<(), String> {
-        self(request)
-    }
-}
-
-#[derive(Debug, Default)]
-pub struct AllowAll;
-
-impl PeerAuthorizer for AllowAll {
-    fn authorize(&self, _request: AuthorizationRequest<'_>) -> Result<(), String> {
-        Ok(())
-    }
-}
-
 /// Iroh protocol handler for Burn Remote compute and tensor-transfer streams.
 ///
+/// With monitoring configured (`remote.monitoring`), it also serves the node's telemetry to the
+/// monitors its authorizer admits.
+///
 /// Register this handler in an existing Iroh `Router` to compose Burn with other application
 /// protocols on the same endpoint.
 pub struct IrohRemoteProtocol<B: BackendIr> {
@@ -65,6 +37,8 @@ pub struct IrohRemoteProtocol<B: BackendIr> {
     sessions: Arc<SessionManager<B, IrohTransfer<B>>>,
     transfer: Arc<IrohTransfer<B>>,
     authorizer: Arc<dyn PeerAuthorizer>,
+    /// Present when the node serves its telemetry to monitors.
+    monitoring: Option<TelemetryProbe>,
 }
 
 impl<B: BackendIr> fmt::Debug for IrohRemoteProtocol<B> {
@@ -76,27 +50,41 @@ impl<B: BackendIr> fmt::Debug for IrohRemoteProtocol<B> {
 }
 
 impl<B: BackendIr> IrohRemoteProtocol<B> {
-    /// Create a handler hosting `devices` on `node`.
+    /// Create a handler hosting `devices` on `node`, admitting the sessions `authorizer` accepts
+    /// within `quotas`, and keeping them for `resume_grace` after their connection drops.
     pub fn new(
         endpoint: Endpoint,
         devices: Vec<Device<B>>,
         authorizer: Arc<dyn PeerAuthorizer>,
+        quotas: ServerQuotas,
+        resume_grace: Option<Duration>,
         probe: TelemetryProbe,
         custom_ops: CustomOpRegistry<B>,
     ) -> Self {
         let node = RemoteNode::from_endpoint(endpoint);
+        let monitoring = burn_std::config::config().remote().monitoring;
+        // Monitors subscribe to the probe, so it must be live even if the caller passed a
+        // disabled one.
+        let probe = if monitoring && !probe.is_enabled() {
+            TelemetryProbe::new(CHANNEL_CAPACITY)
+        } else {
+            probe
+        };
         let transfer = Arc::new(IrohTransfer::new(node.clone()));
 
         let sessions = Arc::new(
             SessionManager::new(devices.to_vec(), transfer.clone())
                 .with_telemetry(probe.clone())
-                .with_custom_ops(custom_ops.clone()),
+                .with_custom_ops(custom_ops.clone())
+                .with_quotas(quotas)
+                .with_resume_grace(resume_grace),
         );
         Self {
             node,
             sessions,
             transfer,
             authorizer,
+            monitoring: monitoring.then_some(probe),
         }
     }
 
@@ -117,13 +105,8 @@ impl<B: BackendIr> IrohRemoteProtocol<B> {
             send,
             sessions,
             Some(PeerId::Iroh(server_id)),
-            |init| {
-                authorizer.authorize(AuthorizationRequest {
-                    peer: remote_id,
-                    device_index: init.device_index,
-                    credential: &init.authorization,
-                })
-            },
+            PeerIdentity::Iroh(remote_id),
+            &*authorizer,
         )
         .await
     }
@@ -183,6 +166,23 @@ impl<B: BackendIr> ProtocolHandler for IrohRemoteProtocol<B> {
                         }
                     });
                 }
+                StreamKind::Telemetry => {
+                    let Some(probe) = self.monitoring.clone() else {
+                        log::warn!(
+                            "Refused a telemetry stream from {remote_id}: monitoring is off"
+                        );
+                        continue;
+                    };
+                    let authorizer = self.authorizer.clone();
+                    spawn_detached(async move {
+                        let peer = PeerIdentity::Iroh(remote_id);
+                        if let Err(err) =
+                            drive_monitor(recv, send, &probe, peer, &*authorizer).await
+                        {
+                            log::warn!("Iroh telemetry feed failed: {err}");
+                        }
+                    });
+                }
                 StreamKind::TensorTransfer => {
                     let transfer = self.transfer.clone();
                     spawn_detached(async move {
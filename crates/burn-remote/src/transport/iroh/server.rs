@@ -1,7 +1,8 @@
 use crate::server::spawn::os_shutdown_signal;
+use crate::server::{PeerAuthorizer, ServerQuotas};
 use crate::telemetry::TelemetryProbe;
 use crate::transport::iroh::node::BURN_REMOTE_ALPN;
-use crate::transport::iroh::protocol::{AllowAll, IrohRemoteProtocol};
+use crate::transport::iroh::protocol::IrohRemoteProtocol;
 #[cfg(not(target_family = "wasm"))]
 use burn_backend::tensor::Device;
 #[cfg(not(target_family = "wasm"))]
@@ -10,17 +11,24 @@ use burn_ir::BackendIr;
 use burn_router::CustomOpRegistry;
 use iroh::{Endpoint, endpoint::presets, protocol::Router};
 use std::sync::Arc;
+#[cfg(not(target_family = "wasm"))]
+use std::time::Duration;
 
 /// Serve Burn Remote over Iroh until the process receives its shutdown signal.
 ///
 /// Binds a server endpoint with the stable identity carried by `secret` and hosts `devices` as the
-/// sole protocol on it. Reached through [`RemoteServerBuilder`](super::RemoteServerBuilder) (the
-/// single turnkey entry point); use [`RemoteNode::protocol`] for composition with other protocols.
+/// sole protocol on it, admitting the sessions `authorizer` accepts within `quotas` and keeping
+/// them for `resume_grace` after their connection drops. Reached through
+/// [`RemoteServerBuilder`](super::RemoteServerBuilder) (the single turnkey entry point); use
+/// [`RemoteNode::protocol`] for composition with other protocols.
 #[cfg(not(target_family = "wasm"))]
 pub(crate) async fn start_iroh_async<B: BackendIr>(
     secret: crate::RemoteSecret,
     devices: Vec<Device<B>>,
     custom_ops: CustomOpRegistry<B>,
+    authorizer: Arc<dyn PeerAuthorizer>,
+    quotas: ServerQuotas,
+    resume_grace: Option<Duration>,
 ) {
     let endpoint = Endpoint::builder(presets::N0)
         .secret_key(secret.secret_key())
@@ -29,16 +37,14 @@ pub(crate) async fn start_iroh_async<B: BackendIr>(
         .await
         .expect("Can bind the Burn Remote server endpoint");
 
-    let probe = if crate::metrics::TelemetryLogger::enabled() {
-        TelemetryProbe::new(crate::telemetry::CHANNEL_CAPACITY)
-    } else {
-        TelemetryProbe::disabled()
-    };
+    let probe = TelemetryProbe::for_server();
 
     let protocol = IrohRemoteProtocol::new(
         endpoint.clone(),
         devices,
-        Arc::new(AllowAll),
+        authorizer,
+        quotas,
+        resume_grace,
         probe,
         custom_ops,
     );
@@ -7,19 +7,18 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use burn_backend::TensorData;
 use burn_ir::BackendIr;
 use tokio::sync::{Mutex, Notify};
 
 use super::node::{RemoteNode, StreamKind, recv_frame, send_frame};
 use crate::server::transfer::TensorTransfer;
-use crate::shared::TransferCapability;
+use crate::shared::{TensorPayload, TransferCapability};
 use crate::{PeerAddr, PeerId};
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 enum TransferMessage {
     Request(TransferCapability),
-    Tensor(TensorData),
+    Tensor(TensorPayload),
     Denied(String),
 }
 
@@ -80,7 +79,7 @@ impl<B: BackendIr> IrohTransfer<B> {
         capability: TransferCapability,
         remote: iroh::EndpointId,
     ) -> Result<bytes::Bytes, String> {
-        super::time::timeout(TRANSFER_WAIT_TIMEOUT, async {
+        crate::time::timeout(TRANSFER_WAIT_TIMEOUT, async {
             loop {
                 let notified = self.exposed_notify.notified();
                 tokio::pin!(notified);
@@ -129,7 +128,7 @@ impl<B: BackendIr> IrohTransfer<B> {
 
         let exposed = self.exposed.clone();
         crate::server::spawn::spawn_detached(async move {
-            super::time::sleep(TRANSFER_CAPABILITY_TTL).await;
+            crate::time::sleep(TRANSFER_CAPABILITY_TTL).await;
             exposed.lock().await.remove(&capability);
         });
     }
@@ -141,7 +140,7 @@ const TRANSFER_CAPABILITY_TTL: core::time::Duration = core::time::Duration::from
 impl<B: BackendIr> TensorTransfer<B> for IrohTransfer<B> {
     async fn expose_data(
         &self,
-        data: TensorData,
+        payload: TensorPayload,
         max_downloads: u32,
         capability: TransferCapability,
         target: PeerId,
@@ -150,7 +149,7 @@ impl<B: BackendIr> TensorTransfer<B> for IrohTransfer<B> {
             log::error!("An Iroh tensor transfer cannot target a non-Iroh peer");
             return;
         };
-        let bytes = match rmp_serde::to_vec(&TransferMessage::Tensor(data)) {
+        let bytes = match rmp_serde::to_vec(&TransferMessage::Tensor(payload)) {
             Ok(bytes) => bytes::Bytes::from(bytes),
             Err(err) => {
                 log::error!("Failed to encode tensor transfer {capability:?}: {err}");
@@ -165,7 +164,7 @@ impl<B: BackendIr> TensorTransfer<B> for IrohTransfer<B> {
         &self,
         remote: PeerAddr,
         capability: TransferCapability,
-    ) -> Option<TensorData> {
+    ) -> Option<TensorPayload> {
         match &remote {
             PeerAddr::Iroh(_) => {}
             #[cfg(feature = "websocket")]
@@ -209,7 +208,7 @@ impl<B: BackendIr> TensorTransfer<B> for IrohTransfer<B> {
             }
         };
         match rmp_serde::from_slice(&response) {
-            Ok(TransferMessage::Tensor(data)) => Some(data),
+            Ok(TransferMessage::Tensor(payload)) => Some(payload),
             Ok(TransferMessage::Denied(reason)) => {
                 log::error!("Tensor transfer denied: {reason}");
                 None
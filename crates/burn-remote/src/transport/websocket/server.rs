@@ -1,6 +1,7 @@
 //! The turnkey WebSocket compute server.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use burn_backend::tensor::Device;
 use burn_ir::BackendIr;
@@ -14,48 +15,100 @@ use burn_communication::{
 };
 
 use super::transfer::WebSocketTransfer;
-use crate::server::{pump::drive_session, session::SessionManager, spawn::os_shutdown_signal};
+use crate::server::{
+    PeerIdentity, ServerAuth, ServerQuotas, monitor::drive_monitor, pump::drive_session,
+    session::SessionManager, spawn::os_shutdown_signal,
+};
 
 /// Serve a WebSocket compute node on the given port, until shutdown.
 ///
 /// The session protocol is a single full-duplex `/session` socket per session (split into a sink +
 /// source and driven by the shared [`drive_session`] pump); cross-server tensor transfers ride the
 /// same server via [`route_external_comm`](ExternalCommServer::route_external_comm). Driven through
-/// [`RemoteServerBuilder`](crate::server::RemoteServerBuilder) rather than called directly.
+/// [`RemoteServerBuilder`](crate::server::RemoteServerBuilder) rather than called directly. With
+/// monitoring configured, monitors read the node's telemetry from a `/telemetry` socket.
+///
+/// Every session is checked by the `auth` authorizer, which sees the client's socket address (and
+/// certificate, under mutual TLS) along with the credential sent at session init, then admitted
+/// within `quotas`. Sessions are kept for `resume_grace` after their socket drops. Peer servers
+/// aren't authenticated on `/data`: a tensor is only served to the peer presenting the random
+/// capability of its transfer, which the client shares with the source and target servers only.
 #[cfg(not(target_family = "wasm"))]
 pub(crate) async fn start_websocket_async<B: BackendIr>(
     devices: Vec<Device<B>>,
     port: u16,
     custom_ops: CustomOpRegistry<B>,
+    auth: ServerAuth,
+    quotas: ServerQuotas,
+    resume_grace: Option<Duration>,
 ) {
     let cancel_token = CancellationToken::new();
     let external = Arc::new(ExternalCommService::<B, WebSocket>::new(cancel_token));
     let transfer = Arc::new(WebSocketTransfer {
         inner: external.clone(),
     });
-    let probe = if crate::metrics::TelemetryLogger::enabled() {
-        crate::telemetry::TelemetryProbe::new(crate::telemetry::CHANNEL_CAPACITY)
-    } else {
-        crate::telemetry::TelemetryProbe::disabled()
-    };
+    let probe = crate::telemetry::TelemetryProbe::for_server();
     let sessions = Arc::new(
         SessionManager::new(devices, transfer)
             .with_custom_ops(custom_ops)
-            .with_telemetry(probe),
+            .with_quotas(quotas)
+            .with_resume_grace(resume_grace)
+            .with_telemetry(probe.clone()),
     );
 
-    let server = WsServer::new(port)
+    let server = WsServer::new(port);
+    #[cfg(feature = "websocket-tls")]
+    let server = match auth.tls {
+        Some(tls) => server.tls(tls),
+        None => server,
+    };
+
+    let authorizer = auth.authorizer;
+    // Monitors get a socket of their own, when the node is configured to serve its telemetry.
+    let server = if burn_std::config::config().remote().monitoring {
+        let authorizer = authorizer.clone();
+        server.route("/telemetry", move |channel: WsServerChannel| {
+            let probe = probe.clone();
+            let authorizer = authorizer.clone();
+            async move {
+                let peer = channel.peer().clone();
+                let (sink, source) = channel.split();
+                let result = drive_monitor(
+                    source,
+                    sink,
+                    &probe,
+                    PeerIdentity::WebSocket(peer),
+                    &*authorizer,
+                )
+                .await;
+                if let Err(err) = result {
+                    log::warn!("WebSocket telemetry feed failed: {err}");
+                }
+            }
+        })
+    } else {
+        server
+    };
+    let server = server
         .route("/session", {
             let sessions = sessions.clone();
             move |channel: WsServerChannel| {
                 let sessions = sessions.clone();
+                let authorizer = authorizer.clone();
                 async move {
+                    let peer = channel.peer().clone();
                     let (sink, source) = channel.split();
-                    // WebSocket has no authenticated peer identity, so the server presents none
-                    // (`peer_id: None`) and authorizes every session.
-                    if let Err(err) =
-                        drive_session(source, sink, sessions, None, |_init| Ok(())).await
-                    {
+                    // The server has no stable identity to present over WebSocket (`peer_id: None`).
+                    let result = drive_session(
+                        source,
+                        sink,
+                        sessions,
+                        None,
+                        PeerIdentity::WebSocket(peer),
+                        &*authorizer,
+                    )
+                    .await;
+                    if let Err(err) = result {
                         log::warn!("WebSocket remote session failed: {err}");
                     }
                 }
@@ -1,12 +1,15 @@
 //! Legacy WebSocket tensor transfer, carried over the `burn_communication` data service.
+//!
+//! The data service only moves [`TensorData`], so a [`TensorPayload`] crosses it serialized into a
+//! flat `u8` tensor.
 
 use std::sync::Arc;
 
-use burn_backend::TensorData;
+use burn_backend::{DType, TensorData};
 use burn_ir::BackendIr;
 
 use crate::server::transfer::TensorTransfer;
-use crate::shared::TransferCapability;
+use crate::shared::{TensorPayload, TransferCapability};
 use crate::{PeerAddr, PeerId};
 
 pub(crate) struct WebSocketTransfer<B: BackendIr> {
@@ -29,13 +32,22 @@ impl<B: BackendIr> Clone for WebSocketTransfer<B> {
 impl<B: BackendIr> TensorTransfer<B> for WebSocketTransfer<B> {
     async fn expose_data(
         &self,
-        data: TensorData,
+        payload: TensorPayload,
         max_downloads: u32,
         capability: TransferCapability,
         _target: PeerId,
     ) {
+        let bytes = match rmp_serde::to_vec(&payload) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                log::error!("Failed to encode tensor transfer {capability:?}: {err}");
+                return;
+            }
+        };
+        let len = bytes.len();
+        let data = TensorData::from_bytes_vec(bytes, [len], DType::U8);
         self.inner
-            .expose_data(data, max_downloads, capability_to_legacy_id(capability))
+            .expose_data(data, max_downloads, transfer_id(capability))
             .await;
     }
 
@@ -43,7 +55,7 @@ impl<B: BackendIr> TensorTransfer<B> for WebSocketTransfer<B> {
         &self,
         remote: PeerAddr,
         capability: TransferCapability,
-    ) -> Option<TensorData> {
+    ) -> Option<TensorPayload> {
         // Only the WebSocket arm remains when the Iroh transport is compiled out.
         #[cfg_attr(not(feature = "iroh"), allow(clippy::infallible_destructuring_match))]
         let address = match remote {
@@ -54,9 +66,17 @@ impl<B: BackendIr> TensorTransfer<B> for WebSocketTransfer<B> {
                 return None;
             }
         };
-        self.inner
-            .download_tensor(address, capability_to_legacy_id(capability))
-            .await
+        let data = self
+            .inner
+            .download_tensor(address, transfer_id(capability))
+            .await?;
+        match rmp_serde::from_slice(data.as_bytes()) {
+            Ok(payload) => Some(payload),
+            Err(err) => {
+                log::error!("Invalid tensor-transfer payload: {err}");
+                None
+            }
+        }
     }
 
     async fn fail(&self, _capability: TransferCapability, _target: PeerId, reason: String) {
@@ -64,11 +84,11 @@ impl<B: BackendIr> TensorTransfer<B> for WebSocketTransfer<B> {
     }
 }
 
-fn capability_to_legacy_id(
+fn transfer_id(
     capability: TransferCapability,
 ) -> burn_communication::external_comm::TensorTransferId {
-    // WebSocket is a compatibility transport without authenticated peer identity. Preserve its old
-    // transfer service while deriving a collision-resistant-enough rendezvous key from the
-    // capability. Iroh uses the complete capability and enforces the destination identity.
-    capability.legacy_id().into()
+    // WebSocket peers have no authenticated identity, so the `/data` route serves anyone who can
+    // connect: the whole capability keys the transfer, so that only its holders can download it.
+    // Iroh uses the complete capability as well, and also enforces the destination identity.
+    capability.to_bytes().into()
 }
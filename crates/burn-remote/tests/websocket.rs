@@ -0,0 +1,348 @@
+//! Authentication, encryption, quotas, session resumption and tensor transfers of the WebSocket
+//! transport, over localhost.
+//!
+//! The TLS tests use the fixtures in `tests/certs`: a test CA, a server certificate for
+//! `localhost`/`127.0.0.1` and a client certificate, all signed by that CA.
+//!
+//! Run with: `cargo test -p burn-remote --features websocket-tls --test websocket`.
+#![cfg(all(feature = "client", feature = "server", feature = "websocket"))]
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use burn_communication::{
+    CommunicationChannel, Message, ProtocolClient, external_comm::TensorTransferId,
+    websocket::WsClient,
+};
+use burn_flex::Flex;
+use burn_remote::{
+    RemoteDevice,
+    server::{QuotaLimits, RemoteServerBuilder, ServerAuth, ServerQuotas, TokenAuthorizer},
+};
+use burn_tensor::{Device, Tensor};
+
+/// Serve a Flex device on `port` with `auth`, on a runtime kept alive by the returned handle.
+fn start_server(port: u16, auth: ServerAuth) -> tokio::runtime::Runtime {
+    serve(
+        RemoteServerBuilder::<Flex>::new(vec![Default::default()])
+            .port(port)
+            .auth(auth),
+    )
+}
+
+fn serve(server: RemoteServerBuilder<Flex>) -> tokio::runtime::Runtime {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    runtime.spawn(server.start_async());
+
+    // Give the server a moment to bind before clients try to connect.
+    std::thread::sleep(Duration::from_millis(500));
+    runtime
+}
+
+fn assert_round_trip(remote: RemoteDevice) {
+    remote.connect();
+    let device = Device::new(remote);
+
+    let output = Tensor::<1>::from_floats([1.0, 2.0, 3.0], &device) * 2.0;
+    assert_eq!(
+        output.try_into_vec_as::<f32>().unwrap(),
+        vec![2.0, 4.0, 6.0]
+    );
+}
+
+fn assert_rejected(remote: RemoteDevice) {
+    let connected = catch_unwind(AssertUnwindSafe(|| remote.connect()));
+    assert!(connected.is_err(), "the session should have been rejected");
+}
+
+#[test]
+fn accepts_sessions_with_a_valid_token() {
+    let server = start_server(
+        3400,
+        ServerAuth::default().with_authorizer(TokenAuthorizer::new("secret")),
+    );
+
+    assert_round_trip(RemoteDevice::websocket_authorized(
+        "ws://localhost:3400",
+        0,
+        b"secret".to_vec(),
+    ));
+
+    server.shutdown_background();
+}
+
+#[test]
+fn rejects_sessions_without_a_valid_token() {
+    let server = start_server(
+        3410,
+        ServerAuth::default().with_authorizer(TokenAuthorizer::new("secret")),
+    );
+
+    assert_rejected(RemoteDevice::websocket("ws://localhost:3410", 0));
+    assert_rejected(RemoteDevice::websocket_authorized(
+        "ws://localhost:3410",
+        0,
+        b"not-the-secret".to_vec(),
+    ));
+
+    server.shutdown_background();
+}
+
+#[test]
+fn resumes_sessions_across_a_dropped_link() {
+    let server = serve(
+        RemoteServerBuilder::<Flex>::new(vec![Default::default()])
+            .port(3420)
+            .resume_grace(Duration::from_secs(10)),
+    );
+    let link = faulty::FaultyLink::start(&server, 3421, 3420);
+
+    let remote = RemoteDevice::websocket("ws://127.0.0.1:3421", 0);
+    remote.connect();
+    let device = Device::new(remote);
+    let weights = Tensor::<1>::from_floats([1.0, 2.0, 3.0], &device);
+    assert_eq!(
+        weights.clone().try_into_vec_as::<f32>().unwrap(),
+        vec![1.0, 2.0, 3.0]
+    );
+
+    link.sever();
+
+    // The tensors held by the server outlive the link, and the session carries on.
+    let output = weights * 2.0;
+    assert_eq!(
+        output.try_into_vec_as::<f32>().unwrap(),
+        vec![2.0, 4.0, 6.0]
+    );
+
+    server.shutdown_background();
+}
+
+/// The requests of the `/data` route, as encoded by the server downloading a tensor.
+#[derive(serde::Serialize)]
+enum DataRequest {
+    TensorRequest(TensorTransferId),
+}
+
+#[test]
+fn data_route_only_serves_transfers_to_their_capability_holders() {
+    let auth = ServerAuth::default().with_authorizer(TokenAuthorizer::new("secret"));
+    let source = start_server(3440, auth.clone());
+    let target = start_server(3441, auth);
+    let device = |port: u16| {
+        let address = format!("ws://localhost:{port}");
+        let remote = RemoteDevice::websocket_authorized(&address, 0, b"secret".to_vec());
+        remote.connect();
+        Device::new(remote)
+    };
+
+    // Servers download the tensors moved between them from the `/data` route.
+    let tensor = Tensor::<1>::from_floats([1.0, 2.0, 3.0], &device(3440));
+    let moved = tensor.to_device(&device(3441));
+    assert_eq!(moved.try_into_vec_as::<f32>().unwrap(), vec![1.0, 2.0, 3.0]);
+
+    source.block_on(async {
+        let connect = || WsClient::connect("ws://localhost:3440".into(), "data");
+
+        // A malformed request closes the connection instead of panicking the handler.
+        let mut channel = connect().await.unwrap();
+        channel
+            .send(Message::new(Bytes::from_static(b"not a request")))
+            .await
+            .unwrap();
+        let reply = tokio::time::timeout(Duration::from_secs(5), channel.recv())
+            .await
+            .expect("the connection should be closed");
+        assert!(!matches!(reply, Ok(Some(_))));
+
+        // Transfers are keyed by their whole random capability, so counters can't be guessed.
+        let mut channel = connect().await.unwrap();
+        for guess in 0..16u64 {
+            let mut id = [0; 32];
+            id[..8].copy_from_slice(&guess.to_le_bytes());
+            let request = rmp_serde::to_vec(&DataRequest::TensorRequest(id.into())).unwrap();
+            channel.send(Message::new(request.into())).await.unwrap();
+        }
+        let reply = tokio::time::timeout(Duration::from_millis(500), channel.recv()).await;
+        assert!(
+            reply.is_err(),
+            "no tensor should be served for a guessed id"
+        );
+    });
+
+    source.shutdown_background();
+    target.shutdown_background();
+}
+
+#[test]
+fn closes_sessions_going_over_their_quota() {
+    let limits = QuotaLimits::default().with_max_resident_bytes(1024);
+    let server = serve(
+        RemoteServerBuilder::<Flex>::new(vec![Default::default()])
+            .port(3450)
+            .quotas(ServerQuotas::default().with_session_limits(limits)),
+    );
+    let remote = RemoteDevice::websocket("ws://localhost:3450", 0);
+    remote.connect();
+    let device = Device::new(remote);
+
+    // 1024 f32 values take 4 KiB on the server.
+    let tensor = Tensor::<1>::ones([1024], &device);
+    let err = tensor.try_into_vec_as::<f32>().unwrap_err().to_string();
+    assert!(
+        err.contains("session quota exceeded: 4096 resident tensor bytes requested"),
+        "{err}"
+    );
+
+    server.shutdown_background();
+}
+
+#[test]
+fn refuses_sessions_over_the_server_quota() {
+    let server = serve(
+        RemoteServerBuilder::<Flex>::new(vec![Default::default()])
+            .port(3460)
+            .quotas(ServerQuotas::default().with_max_sessions(1)),
+    );
+
+    assert_round_trip(RemoteDevice::websocket("ws://localhost:3460", 0));
+    // Another address of the same server is another session, over the server's cap while the
+    // first one is open.
+    assert_rejected(RemoteDevice::websocket("ws://127.0.0.1:3460", 0));
+
+    server.shutdown_background();
+}
+
+mod faulty {
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::runtime::Runtime;
+    use tokio::sync::broadcast;
+
+    /// A TCP proxy standing in for an unreliable network: forwards connections to a local port
+    /// until [`sever`](Self::sever) drops every connection open through it.
+    pub struct FaultyLink {
+        severed: broadcast::Sender<()>,
+    }
+
+    impl FaultyLink {
+        /// Forward `port` to `target` on `runtime`.
+        pub fn start(runtime: &Runtime, port: u16, target: u16) -> Self {
+            let (severed, _) = broadcast::channel(1);
+            let sever = severed.clone();
+            let listener = runtime
+                .block_on(TcpListener::bind(("127.0.0.1", port)))
+                .unwrap();
+            runtime.spawn(async move {
+                while let Ok((mut client, _)) = listener.accept().await {
+                    let mut severed = sever.subscribe();
+                    tokio::spawn(async move {
+                        let Ok(mut server) = TcpStream::connect(("127.0.0.1", target)).await else {
+                            return;
+                        };
+                        tokio::select! {
+                            _ = tokio::io::copy_bidirectional(&mut client, &mut server) => {}
+                            _ = severed.recv() => {}
+                        }
+                    });
+                }
+            });
+            Self { severed }
+        }
+
+        /// Drop every connection currently open through the link. New connections go through.
+        pub fn sever(&self) {
+            let _ = self.severed.send(());
+        }
+    }
+}
+
+#[cfg(feature = "websocket-tls")]
+mod tls {
+    use super::*;
+    use burn_remote::{
+        TlsClientConfig, TlsServerConfig,
+        server::{AuthorizationRequest, PeerIdentity},
+    };
+
+    const CA: &[u8] = include_bytes!("certs/ca.pem");
+    const SERVER_CERT: &[u8] = include_bytes!("certs/server.pem");
+    const SERVER_KEY: &[u8] = include_bytes!("certs/server.key");
+    const CLIENT_CERT: &[u8] = include_bytes!("certs/client.pem");
+    const CLIENT_KEY: &[u8] = include_bytes!("certs/client.key");
+
+    #[test]
+    fn serves_token_sessions_over_tls() {
+        let auth = ServerAuth::default()
+            .with_authorizer(TokenAuthorizer::new("secret"))
+            .with_tls(TlsServerConfig::new(SERVER_CERT, SERVER_KEY).unwrap());
+        let server = start_server(3420, auth);
+        let tls = TlsClientConfig::new(CA).unwrap();
+
+        assert_round_trip(RemoteDevice::websocket_tls(
+            "wss://localhost:3420",
+            0,
+            b"secret".to_vec(),
+            tls.clone(),
+        ));
+        // A plaintext client can't talk to a TLS server.
+        assert_rejected(RemoteDevice::websocket_authorized(
+            "ws://localhost:3420",
+            0,
+            b"secret".to_vec(),
+        ));
+        // The token is still enforced over TLS.
+        assert_rejected(RemoteDevice::websocket_tls(
+            "wss://127.0.0.1:3420",
+            0,
+            Vec::new(),
+            tls,
+        ));
+
+        server.shutdown_background();
+    }
+
+    #[test]
+    fn authorizes_client_certificates_over_mutual_tls() {
+        // The authorizer sees the verified client certificate; here it pins the expected one.
+        let expected = certificate_der(CLIENT_CERT);
+        let auth = ServerAuth::default()
+            .with_authorizer(
+                move |request: AuthorizationRequest<'_>| match &request.peer {
+                    PeerIdentity::WebSocket(peer) if peer.certificate() == Some(&expected[..]) => {
+                        Ok(())
+                    }
+                    _ => Err("unknown client certificate".to_string()),
+                },
+            )
+            .with_tls(TlsServerConfig::mutual(SERVER_CERT, SERVER_KEY, CA).unwrap());
+        let server = start_server(3430, auth);
+
+        assert_round_trip(RemoteDevice::websocket_tls(
+            "wss://localhost:3430",
+            0,
+            Vec::new(),
+            TlsClientConfig::mutual(CA, CLIENT_CERT, CLIENT_KEY).unwrap(),
+        ));
+        // Without a client certificate the handshake fails.
+        assert_rejected(RemoteDevice::websocket_tls(
+            "wss://127.0.0.1:3430",
+            0,
+            Vec::new(),
+            TlsClientConfig::new(CA).unwrap(),
+        ));
+
+        server.shutdown_background();
+    }
+
+    /// Decode the first certificate of a PEM document to DER.
+    fn certificate_der(pem: &[u8]) -> Vec<u8> {
+        use burn_communication::websocket::rustls::pki_types::{CertificateDer, pem::PemObject};
+
+        CertificateDer::from_pem_slice(pem).unwrap().to_vec()
+    }
+}
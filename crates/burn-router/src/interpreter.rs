@@ -1906,6 +1906,10 @@ impl<B: BackendIr> TensorInterpreter<B> {
                         .attn_bias
                         .as_ref()
                         .map(|ab| handles.get_float_tensor::<B>(ab));
+                    let seq_offsets = desc
+                        .seq_offsets
+                        .as_ref()
+                        .map(|so| handles.get_int_tensor::<B>(so));
 
                     let output = B::attention(
                         query,
@@ -1913,6 +1917,7 @@ impl<B: BackendIr> TensorInterpreter<B> {
                         value,
                         mask,
                         attn_bias,
+                        seq_offsets,
                         desc.options.clone().into(),
                     );
 
@@ -1952,6 +1957,33 @@ impl<B: BackendIr> TensorInterpreter<B> {
 
                     handles.register_float_tensor::<B>(&desc.out.id, output);
                 }
+                ModuleOperationIr::SelectiveScan(desc) => {
+                    let x = handles.get_float_tensor::<B>(&desc.x);
+                    let delta = handles.get_float_tensor::<B>(&desc.delta);
+                    let a = handles.get_float_tensor::<B>(&desc.a);
+                    let b = handles.get_float_tensor::<B>(&desc.b);
+                    let c = handles.get_float_tensor::<B>(&desc.c);
+
+                    let output = B::selective_scan(x, delta, a, b, c);
+
+                    handles.register_float_tensor::<B>(&desc.out.id, output);
+                }
+                ModuleOperationIr::SelectiveScanBackward(desc) => {
+                    let x = handles.get_float_tensor::<B>(&desc.x);
+                    let delta = handles.get_float_tensor::<B>(&desc.delta);
+                    let a = handles.get_float_tensor::<B>(&desc.a);
+                    let b = handles.get_float_tensor::<B>(&desc.b);
+                    let c = handles.get_float_tensor::<B>(&desc.c);
+                    let output_grad = handles.get_float_tensor::<B>(&desc.out_grad);
+
+                    let output = B::selective_scan_backward(x, delta, a, b, c, output_grad);
+
+                    handles.register_float_tensor::<B>(&desc.x_grad.id, output.x_grad);
+                    handles.register_float_tensor::<B>(&desc.delta_grad.id, output.delta_grad);
+                    handles.register_float_tensor::<B>(&desc.a_grad.id, output.a_grad);
+                    handles.register_float_tensor::<B>(&desc.b_grad.id, output.b_grad);
+                    handles.register_float_tensor::<B>(&desc.c_grad.id, output.c_grad);
+                }
                 ModuleOperationIr::LayerNorm(desc) => {
                     let input = handles.get_float_tensor::<B>(&desc.input);
                     let gamma = handles.get_float_tensor::<B>(&desc.gamma);
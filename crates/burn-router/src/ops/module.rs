@@ -1087,6 +1087,7 @@ impl<R: RouterChannel> ModuleOps<Self> for BackendRouter<R> {
         value: FloatTensor<Self>,
         mask: Option<BoolTensor<Self>>,
         attn_bias: Option<FloatTensor<Self>>,
+        seq_offsets: Option<IntTensor<Self>>,
         options: AttentionModuleOptions,
     ) -> FloatTensor<Self> {
         let client = query.client.clone();
@@ -1096,6 +1097,7 @@ impl<R: RouterChannel> ModuleOps<Self> for BackendRouter<R> {
             value.into_ir(),
             mask.map(|m: BoolTensor<Self>| m.into_ir()),
             attn_bias.map(|ab| ab.into_ir()),
+            seq_offsets.map(|so| so.into_ir()),
             options.into(),
             || client.create_empty_handle(),
         );
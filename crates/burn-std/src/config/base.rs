@@ -63,7 +63,7 @@ impl RuntimeConfig for BurnConfig {
     ))]
     fn override_from_env(mut self) -> Self {
         use super::fusion::FusionLogLevel;
-        use super::remote::RemoteLogLevel;
+        use super::remote::{PayloadCompression, RemoteLogLevel};
 
         if let Ok(val) = std::env::var("BURN_FUSION_LOG") {
             let level = match val.to_ascii_lowercase().as_str() {
@@ -86,6 +86,12 @@ impl RuntimeConfig for BurnConfig {
             self.fusion.beam_search.max_explorations = Some(n);
         }
 
+        if let Ok(val) = std::env::var("BURN_FUSION_PLAN_CACHE")
+            && !val.is_empty()
+        {
+            self.fusion.plan_cache = Some(val);
+        }
+
         if let Ok(val) = std::env::var("BURN_REMOTE_LOG") {
             let level = match val.to_ascii_lowercase().as_str() {
                 "disabled" | "off" | "0" => RemoteLogLevel::Disabled,
@@ -99,6 +105,26 @@ impl RuntimeConfig for BurnConfig {
             }
         }
 
+        if let Ok(val) = std::env::var("BURN_REMOTE_COMPRESSION") {
+            // A comma-separated preference list, e.g. `zstd,lz4`; `none` (or empty) disables it.
+            self.remote.payload.compression = val
+                .split(',')
+                .filter_map(|codec| match codec.trim().to_ascii_lowercase().as_str() {
+                    "lz4" => Some(PayloadCompression::Lz4),
+                    "zstd" => Some(PayloadCompression::Zstd),
+                    _ => None,
+                })
+                .collect();
+        }
+
+        if let Ok(val) = std::env::var("BURN_REMOTE_MONITORING") {
+            match val.to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" => self.remote.monitoring = true,
+                "0" | "false" | "off" => self.remote.monitoring = false,
+                _ => {}
+            }
+        }
+
         self
     }
 }
@@ -1,3 +1,4 @@
+use alloc::string::String;
 use cubecl_environment::config::logger::{LogLevel, LoggerConfig};
 
 /// Configuration for operation fusion in Burn.
@@ -30,6 +31,15 @@ pub struct FusionConfig {
     /// score.
     #[serde(default = "default_growth_patience")]
     pub growth_patience: usize,
+
+    /// Directory of the on-disk execution plan cache.
+    ///
+    /// When set, the execution plans explored on a device are saved in this directory and
+    /// preloaded by later processes using the same backend and device, so previously seen
+    /// operation sequences skip the exploration. Plans saved by another version of Burn are
+    /// ignored and overwritten. `None` (the default) keeps the plans in memory only.
+    #[serde(default)]
+    pub plan_cache: Option<String>,
 }
 
 impl Default for FusionConfig {
@@ -39,6 +49,7 @@ impl Default for FusionConfig {
             beam_search: BeamSearchConfig::default(),
             max_graph_size: None,
             growth_patience: default_growth_patience(),
+            plan_cache: None,
         }
     }
 }
@@ -23,6 +23,16 @@ pub struct RemoteConfig {
     /// batching. Larger batches fewer/bigger frames; smaller cuts latency for data-heavy streams.
     #[serde(default = "default_flush_bytes_threshold")]
     pub flush_bytes_threshold: usize,
+
+    /// How tensor payloads (uploads, reads and transfers between compute nodes) are encoded on
+    /// the wire.
+    #[serde(default)]
+    pub payload: PayloadConfig,
+
+    /// Serve a compute node's telemetry to the monitors its authorizer admits (see the
+    /// `burn-remote-monitor` tool). Off by default: the feed describes every session on the node.
+    #[serde(default)]
+    pub monitoring: bool,
 }
 
 impl Default for RemoteConfig {
@@ -31,6 +41,8 @@ impl Default for RemoteConfig {
             logger: LoggerConfig::default(),
             flush_threshold: default_flush_threshold(),
             flush_bytes_threshold: default_flush_bytes_threshold(),
+            payload: PayloadConfig::default(),
+            monitoring: false,
         }
     }
 }
@@ -43,6 +55,56 @@ fn default_flush_bytes_threshold() -> usize {
     1024 * 1024
 }
 
+/// Encoding of the tensor payloads a client's sessions send and receive.
+///
+/// Negotiated with the server when a session opens: the server picks the first of the
+/// [`compression`](Self::compression) codecs it was built with, and honors
+/// [`lossy`](Self::lossy) as is. The default sends raw bytes.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct PayloadConfig {
+    /// Lossless codecs to offer the server, most preferred first. Empty sends payloads
+    /// uncompressed.
+    #[serde(default)]
+    pub compression: Vec<PayloadCompression>,
+
+    /// Send float tensors at reduced precision. Their values come back rounded, so this is off
+    /// unless set.
+    #[serde(default)]
+    pub lossy: Option<LossyPrecision>,
+}
+
+/// Lossless compression of tensor payloads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum PayloadCompression {
+    /// LZ4: cheap on the CPU, for fast links.
+    #[serde(rename = "lz4")]
+    Lz4,
+
+    /// Zstandard: a better ratio for more CPU time, for slow links.
+    #[serde(rename = "zstd")]
+    Zstd,
+}
+
+/// Reduced precision float tensor payloads are sent at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum LossyPrecision {
+    /// Brain floating point: f32's range with 8 bits of mantissa. Halves f32 payloads.
+    #[serde(rename = "bf16")]
+    BF16,
+
+    /// 8-bit floating point (e4m3), scaled per tensor. Quarters f32 payloads.
+    #[serde(rename = "fp8")]
+    FP8,
+
+    /// Symmetric 8-bit integers with one f32 scale per `block_size` values, so an outlier only
+    /// costs its own block precision.
+    #[serde(rename = "block_quantized")]
+    BlockQuantized {
+        /// Values sharing a scale.
+        block_size: u8,
+    },
+}
+
 /// Log levels for remote-backend logging.
 #[derive(
     Default,
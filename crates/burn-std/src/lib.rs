@@ -48,6 +48,10 @@ pub use ops::*;
 /// Burn runtime configurations.
 pub mod config;
 
+/// Profiling of tensor operations, fusion blocks and module calls.
+#[cfg(feature = "std")]
+pub mod profile;
+
 /// Common Errors.
 pub use cubecl_zspace::errors::{self, *};
 
@@ -1,6 +1,7 @@
 //! Configuration types for tensor operations.
 
 use crate::ElementConversion;
+use alloc::vec::Vec;
 use core::num::NonZeroUsize;
 
 /// Check that the parameter value is non-zero.
@@ -385,6 +386,40 @@ pub struct AttentionModuleOptions {
     /// passing an explicit lower-triangular bool mask because backends can use optimized
     /// kernel paths (e.g. flash attention with causal mode).
     pub is_causal: bool,
+
+    /// Sliding-window (local) attention, as used by Mistral: each query position only attends
+    /// to the key positions less than `sliding_window` positions away from it. Combined with
+    /// `is_causal`, a query sees itself and the `sliding_window - 1` positions before it.
+    /// Positions are aligned at the bottom-right corner like the causal mask. Must be non-zero.
+    pub sliding_window: Option<usize>,
+
+    /// ALiBi (Attention with Linear Biases): adds `-slope * |i - j|` to the scores of each head,
+    /// where `i` and `j` are the query and key positions and the slopes are given by
+    /// [alibi_slopes](Self::alibi_slopes) with this maximum bias (8 in the ALiBi paper).
+    pub alibi_max_bias: Option<f64>,
+}
+
+impl AttentionModuleOptions {
+    /// The ALiBi slope of each head, or `None` when ALiBi isn't enabled.
+    ///
+    /// For `n` heads, the slopes are the geometric sequence `2^(-max_bias * h / n)` for `h` in
+    /// `1..=n`. When `n` isn't a power of two, the slopes of the closest smaller power of two
+    /// are completed with every other slope of the next one, as in the ALiBi paper.
+    pub fn alibi_slopes(&self, num_heads: usize) -> Option<Vec<f64>> {
+        let max_bias = self.alibi_max_bias?;
+        let slopes = |n: usize| {
+            (1..=n).map(move |h| num_traits::Float::powf(2.0f64, -max_bias * h as f64 / n as f64))
+        };
+
+        let closest = if num_heads == 0 {
+            0
+        } else {
+            1 << num_heads.ilog2()
+        };
+        let mut output: Vec<f64> = slopes(closest).collect();
+        output.extend(slopes(2 * closest).step_by(2).take(num_heads - closest));
+        Some(output)
+    }
 }
 
 /// Computation to be used to update the existing values in indexed assignment operations (scatter/select).
@@ -477,4 +512,22 @@ mod tests {
     fn unfold_options_dilation_zero() {
         let _opt = UnfoldOptions::new([1, 1], [0, 0], [0, 0]);
     }
+
+    #[test]
+    fn attention_alibi_slopes() {
+        let options = AttentionModuleOptions {
+            alibi_max_bias: Some(8.0),
+            ..Default::default()
+        };
+
+        let slopes = options.alibi_slopes(4).unwrap();
+        assert_eq!(slopes, [0.25, 0.0625, 0.015625, 0.00390625]);
+
+        // Not a power of two: the 4 slopes above, then every other slope of 8 heads.
+        let slopes = options.alibi_slopes(6).unwrap();
+        assert_eq!(slopes[..4], [0.25, 0.0625, 0.015625, 0.00390625]);
+        assert_eq!(slopes[4..], [0.5, 0.125]);
+
+        assert_eq!(AttentionModuleOptions::default().alibi_slopes(4), None);
+    }
 }
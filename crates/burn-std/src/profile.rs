@@ -0,0 +1,644 @@
+//! Events are only recorded while a profiling session is running, which is started with
+//! [start_session] and finished with [finish_session], returning a [ProfileReport]. Outside a
+//! session, every recording function returns immediately after checking an atomic flag.
+//!
+//! Operations are timed when they are dispatched to a backend. Backends executing operations
+//! asynchronously (e.g. with fusion) return before the work is done, so the session can
+//! [synchronize](sync_operations) the device after every operation to measure the actual
+//! execution time, at the cost of disabling fusion across operations.
+
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    cell::{Cell, RefCell},
+    fmt::{Display, Write},
+    time::Duration,
+};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static SYNC_OPERATIONS: AtomicBool = AtomicBool::new(false);
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+
+std::thread_local! {
+    static THREAD: u64 = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+    static MODULES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static IN_OPERATION: Cell<bool> = const { Cell::new(false) };
+}
+
+struct Session {
+    start: Instant,
+    events: Vec<ProfileEvent>,
+    threads: HashMap<u64, String>,
+}
+
+/// The kind of a [profiled event](ProfileEvent).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ProfileEventKind {
+    /// A tensor operation dispatched to a backend.
+    Operation,
+    /// A block of operations executed by the fusion engine.
+    Fusion,
+    /// A call to a module method.
+    Module,
+    /// A scope annotated by the user.
+    Scope,
+}
+
+impl ProfileEventKind {
+    fn category(&self) -> &'static str {
+        match self {
+            Self::Operation => "operation",
+            Self::Fusion => "fusion",
+            Self::Module => "module",
+            Self::Scope => "scope",
+        }
+    }
+}
+
+/// An event recorded during a profiling session.
+#[derive(Clone, Debug)]
+pub struct ProfileEvent {
+    /// The name of the operation, fusion block, module path or scope.
+    pub name: String,
+    /// The kind of event.
+    pub kind: ProfileEventKind,
+    /// When the event started, relative to the start of the session.
+    pub start: Duration,
+    /// How long the event lasted.
+    pub duration: Duration,
+    /// The thread on which the event was recorded.
+    pub thread: u64,
+    /// The innermost module path the event was recorded in, if any.
+    pub module: Option<String>,
+    /// The number of bytes allocated for the outputs of an operation.
+    pub bytes: Option<u64>,
+    /// The number of operations executed by a fusion block.
+    pub num_operations: Option<usize>,
+}
+
+/// Returns true when a profiling session is running.
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Returns true when the running session synchronizes the device after every operation.
+pub fn sync_operations() -> bool {
+    is_active() && SYNC_OPERATIONS.load(Ordering::Relaxed)
+}
+
+/// Start a profiling session.
+///
+/// # Panics
+///
+/// If a session is already running.
+pub fn start_session(sync_operations: bool) {
+    let mut session = SESSION.lock().unwrap();
+    assert!(session.is_none(), "A profiling session is already running");
+
+    *session = Some(Session {
+        start: Instant::now(),
+        events: Vec::new(),
+        threads: HashMap::new(),
+    });
+    SYNC_OPERATIONS.store(sync_operations, Ordering::Relaxed);
+    ACTIVE.store(true, Ordering::Relaxed);
+}
+
+/// Finish the running profiling session and return its report.
+///
+/// # Panics
+///
+/// If no session is running.
+pub fn finish_session() -> ProfileReport {
+    ACTIVE.store(false, Ordering::Relaxed);
+    let session = SESSION
+        .lock()
+        .unwrap()
+        .take()
+        .expect("No profiling session is running");
+
+    let mut events = session.events;
+    events.sort_by_key(|event| event.start);
+
+    ProfileReport {
+        events,
+        duration: session.start.elapsed(),
+        threads: session.threads,
+    }
+}
+
+/// Extract the name of an operation from the type name of a marker function nested in it.
+///
+/// `burn_dispatch::ops::tensor::<impl ...>::float_add::marker` gives `float_add`, and closures
+/// (including the ones generated for async functions) are skipped.
+pub fn operation_name(type_name: &'static str) -> &'static str {
+    let mut name = type_name
+        .rsplit_once("::")
+        .map_or(type_name, |(name, _marker)| name);
+    while let Some(stripped) = name.strip_suffix("::{{closure}}") {
+        name = stripped;
+    }
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Measures an event, recorded when [finished](EventTimer::finish).
+#[derive(Debug)]
+pub struct EventTimer {
+    name: Cow<'static, str>,
+    kind: ProfileEventKind,
+    start: Instant,
+    module: Option<String>,
+    bytes: Option<u64>,
+    num_operations: Option<usize>,
+    operation: bool,
+}
+
+impl EventTimer {
+    /// Start measuring an event, returning `None` when no session is running.
+    pub fn start(kind: ProfileEventKind, name: impl Into<Cow<'static, str>>) -> Option<Self> {
+        if !is_active() {
+            return None;
+        }
+
+        Some(Self {
+            name: name.into(),
+            kind,
+            start: Instant::now(),
+            module: current_module(),
+            bytes: None,
+            num_operations: None,
+            operation: false,
+        })
+    }
+
+    /// Start measuring a tensor operation.
+    ///
+    /// Returns `None` when no session is running, or when called from an operation already
+    /// being measured on this thread, so that operations implemented with other operations are
+    /// only recorded once.
+    pub fn operation(name: &'static str) -> Option<Self> {
+        if !is_active() || IN_OPERATION.with(|cell| cell.replace(true)) {
+            return None;
+        }
+
+        let mut timer = Self::start(ProfileEventKind::Operation, name);
+        match &mut timer {
+            Some(timer) => timer.operation = true,
+            None => IN_OPERATION.with(|cell| cell.set(false)),
+        }
+        timer
+    }
+
+    /// Start measuring a tensor operation that may be suspended, like reading tensor data.
+    ///
+    /// Same as [operation](Self::operation), except that operations called while it is measured
+    /// are also recorded, since the operation may resume on another thread.
+    pub fn async_operation(name: &'static str) -> Option<Self> {
+        if IN_OPERATION.with(Cell::get) {
+            return None;
+        }
+
+        Self::start(ProfileEventKind::Operation, name)
+    }
+
+    /// Set the number of bytes allocated by the event.
+    pub fn with_bytes(mut self, bytes: u64) -> Self {
+        self.bytes = Some(bytes);
+        self
+    }
+
+    /// Set the number of operations executed by the event.
+    pub fn with_num_operations(mut self, num_operations: usize) -> Self {
+        self.num_operations = Some(num_operations);
+        self
+    }
+
+    /// Stop measuring the event and record it in the running session.
+    pub fn finish(mut self) {
+        let end = Instant::now();
+        let event = ProfileEvent {
+            name: core::mem::take(&mut self.name).into_owned(),
+            kind: self.kind,
+            start: Duration::ZERO,
+            duration: end.duration_since(self.start),
+            thread: THREAD.with(|thread| *thread),
+            module: self.module.take(),
+            bytes: self.bytes,
+            num_operations: self.num_operations,
+        };
+        record(event, self.start);
+    }
+}
+
+impl Drop for EventTimer {
+    fn drop(&mut self) {
+        if self.operation {
+            IN_OPERATION.with(|cell| cell.set(false));
+        }
+    }
+}
+
+fn record(mut event: ProfileEvent, start: Instant) {
+    let mut session = SESSION.lock().unwrap();
+    // The session may have finished while the event was measured.
+    let Some(session) = session.as_mut() else {
+        return;
+    };
+
+    event.start = start.saturating_duration_since(session.start);
+    session.threads.entry(event.thread).or_insert_with(|| {
+        let thread = std::thread::current();
+        thread
+            .name()
+            .map(ToString::to_string)
+            .unwrap_or_else(|| format!("thread {}", event.thread))
+    });
+    session.events.push(event);
+}
+
+fn current_module() -> Option<String> {
+    MODULES.with(|modules| modules.borrow().last().cloned())
+}
+
+/// A profiled scope, recorded when dropped.
+///
+/// Module scopes also annotate the events recorded inside them with the module path.
+#[must_use = "The scope is recorded when dropped"]
+pub struct ProfileScope {
+    timer: Option<EventTimer>,
+    module: bool,
+}
+
+impl ProfileScope {
+    /// Start a scope annotated with `name`.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            timer: EventTimer::start(ProfileEventKind::Scope, name),
+            module: false,
+        }
+    }
+
+    /// Start the scope of a call to the module at `path`.
+    pub fn module(path: &str) -> Self {
+        let timer = EventTimer::start(ProfileEventKind::Module, path.to_string());
+        let module = timer.is_some();
+        if module {
+            MODULES.with(|modules| modules.borrow_mut().push(path.to_string()));
+        }
+
+        Self { timer, module }
+    }
+}
+
+impl Drop for ProfileScope {
+    fn drop(&mut self) {
+        if self.module {
+            MODULES.with(|modules| modules.borrow_mut().pop());
+        }
+        if let Some(timer) = self.timer.take() {
+            timer.finish();
+        }
+    }
+}
+
+/// Run `func` in a [profiled scope](ProfileScope) annotated with `name`.
+pub fn scope<R>(name: impl Into<Cow<'static, str>>, func: impl FnOnce() -> R) -> R {
+    let _scope = ProfileScope::new(name);
+    func()
+}
+
+/// The events recorded during a profiling session.
+#[derive(Clone, Debug)]
+pub struct ProfileReport {
+    events: Vec<ProfileEvent>,
+    duration: Duration,
+    threads: HashMap<u64, String>,
+}
+
+/// Statistics of the events of the same kind and name in a [ProfileReport].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProfileSummary {
+    /// The kind of the events.
+    pub kind: ProfileEventKind,
+    /// The name of the events.
+    pub name: String,
+    /// The number of events.
+    pub count: usize,
+    /// The total duration of the events.
+    pub total: Duration,
+    /// The longest duration of an event.
+    pub max: Duration,
+    /// The bytes allocated by the events, or by the operations called in a module.
+    pub bytes: u64,
+}
+
+impl ProfileSummary {
+    /// The mean duration of the events.
+    pub fn mean(&self) -> Duration {
+        self.total / self.count.max(1) as u32
+    }
+}
+
+impl ProfileReport {
+    /// The recorded events, sorted by start time.
+    pub fn events(&self) -> &[ProfileEvent] {
+        &self.events
+    }
+
+    /// The duration of the session.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The statistics of the events grouped by kind and name, sorted by decreasing total
+    /// duration.
+    ///
+    /// The bytes of a module include the bytes of the operations called directly in it, not in
+    /// its submodules.
+    pub fn summary(&self) -> Vec<ProfileSummary> {
+        let mut groups: HashMap<(ProfileEventKind, &str), ProfileSummary> = HashMap::new();
+
+        for event in self.events.iter() {
+            let summary = groups
+                .entry((event.kind, event.name.as_str()))
+                .or_insert_with(|| ProfileSummary {
+                    kind: event.kind,
+                    name: event.name.clone(),
+                    count: 0,
+                    total: Duration::ZERO,
+                    max: Duration::ZERO,
+                    bytes: 0,
+                });
+            summary.count += 1;
+            summary.total += event.duration;
+            summary.max = summary.max.max(event.duration);
+            summary.bytes += event.bytes.unwrap_or(0);
+        }
+
+        for event in self.events.iter() {
+            if let (Some(module), Some(bytes)) = (&event.module, event.bytes)
+                && let Some(summary) = groups.get_mut(&(ProfileEventKind::Module, module.as_str()))
+            {
+                summary.bytes += bytes;
+            }
+        }
+
+        let mut summary: Vec<_> = groups.into_values().collect();
+        summary.sort_by(|a, b| {
+            b.total
+                .cmp(&a.total)
+                .then(a.kind.cmp(&b.kind))
+                .then(a.name.cmp(&b.name))
+        });
+        summary
+    }
+
+    /// Export the events in the Chrome trace event format, which can be opened with
+    /// `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+    pub fn chrome_trace(&self) -> String {
+        let mut entries = Vec::with_capacity(self.events.len() + self.threads.len());
+
+        let mut threads: Vec<_> = self.threads.iter().collect();
+        threads.sort();
+        for (thread, name) in threads {
+            entries.push(format!(
+                r#"{{"name":"thread_name","ph":"M","pid":1,"tid":{thread},"args":{{"name":{}}}}}"#,
+                json_string(name)
+            ));
+        }
+
+        for event in self.events.iter() {
+            let mut args = Vec::new();
+            if let Some(module) = &event.module {
+                args.push(format!(r#""module":{}"#, json_string(module)));
+            }
+            if let Some(bytes) = event.bytes {
+                args.push(format!(r#""bytes":{bytes}"#));
+            }
+            if let Some(num_operations) = event.num_operations {
+                args.push(format!(r#""num_operations":{num_operations}"#));
+            }
+
+            entries.push(format!(
+                r#"{{"name":{},"cat":"{}","ph":"X","ts":{:.3},"dur":{:.3},"pid":1,"tid":{},"args":{{{}}}}}"#,
+                json_string(&event.name),
+                event.kind.category(),
+                event.start.as_secs_f64() * 1e6,
+                event.duration.as_secs_f64() * 1e6,
+                event.thread,
+                args.join(",")
+            ));
+        }
+
+        format!(
+            r#"{{"displayTimeUnit":"ms","traceEvents":[{}]}}"#,
+            entries.join(",")
+        )
+    }
+
+    /// Save the events in the [Chrome trace event format](Self::chrome_trace).
+    pub fn save_chrome_trace(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.chrome_trace())
+    }
+}
+
+impl Display for ProfileReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let summary = self.summary();
+        let width = summary
+            .iter()
+            .map(|row| row.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        writeln!(
+            f,
+            "Profile: {} events in {}",
+            self.events.len(),
+            format_duration(self.duration)
+        )?;
+        writeln!(
+            f,
+            "{:<9}  {:<width$}  {:>7}  {:>10}  {:>10}  {:>10}  {:>10}",
+            "Kind", "Name", "Count", "Total", "Mean", "Max", "Memory"
+        )?;
+
+        for row in summary {
+            writeln!(
+                f,
+                "{:<9}  {:<width$}  {:>7}  {:>10}  {:>10}  {:>10}  {:>10}",
+                row.kind.category(),
+                row.name,
+                row.count,
+                format_duration(row.total),
+                format_duration(row.mean()),
+                format_duration(row.max),
+                format_bytes(row.bytes)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let micros = duration.as_secs_f64() * 1e6;
+    if micros < 1e3 {
+        format!("{micros:.1}us")
+    } else if micros < 1e6 {
+        format!("{:.2}ms", micros / 1e3)
+    } else {
+        format!("{:.2}s", micros / 1e6)
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1}{}", UNITS[unit])
+}
+
+fn json_string(value: &str) -> String {
+    let mut output = String::with_capacity(value.len() + 2);
+    output.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(output, "\\u{:04x}", c as u32);
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    fn operation_name_is_the_enclosing_function() {
+        assert_eq!(
+            operation_name(
+                "burn_dispatch::ops::tensor::<impl burn_backend::FloatTensorOps<burn_dispatch::Dispatch> for burn_dispatch::Dispatch>::float_add::marker"
+            ),
+            "float_add"
+        );
+        assert_eq!(
+            operation_name(
+                "burn_dispatch::ops::tensor::<impl ...>::float_into_data::{{closure}}::marker"
+            ),
+            "float_into_data"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn nothing_is_recorded_outside_a_session() {
+        assert!(EventTimer::operation("float_add").is_none());
+        assert!(ProfileScope::module("encoder").timer.is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn records_operations_in_modules() {
+        start_session(false);
+        {
+            let _module = ProfileScope::module("encoder.linear");
+            EventTimer::operation("float_matmul")
+                .unwrap()
+                .with_bytes(4096)
+                .finish();
+            // Operations called by an operation are not recorded.
+            let outer = EventTimer::operation("float_linear").unwrap();
+            assert!(EventTimer::operation("float_add").is_none());
+            assert!(EventTimer::async_operation("float_into_data").is_none());
+            outer.finish();
+        }
+        EventTimer::operation("float_add")
+            .unwrap()
+            .with_bytes(1024)
+            .finish();
+        let report = finish_session();
+
+        let names: Vec<_> = report.events().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names.len(), 4);
+        assert!(names.contains(&"encoder.linear"));
+
+        let matmul = report
+            .events()
+            .iter()
+            .find(|event| event.name == "float_matmul")
+            .unwrap();
+        assert_eq!(matmul.module.as_deref(), Some("encoder.linear"));
+
+        let summary = report.summary();
+        let module = summary
+            .iter()
+            .find(|row| row.kind == ProfileEventKind::Module)
+            .unwrap();
+        assert_eq!(module.bytes, 4096);
+        let add = summary.iter().find(|row| row.name == "float_add").unwrap();
+        assert_eq!((add.count, add.bytes), (1, 1024));
+
+        assert!(report.to_string().contains("float_matmul"));
+    }
+
+    #[test]
+    #[serial]
+    fn exports_chrome_trace() {
+        start_session(false);
+        scope("step \"1\"", || {
+            EventTimer::start(ProfileEventKind::Fusion, "ElemwiseOptimization")
+                .unwrap()
+                .with_num_operations(3)
+                .finish();
+        });
+        let report = finish_session();
+
+        let trace: serde_json::Value = serde_json::from_str(&report.chrome_trace()).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        let complete: Vec<_> = events.iter().filter(|event| event["ph"] == "X").collect();
+        assert_eq!(complete.len(), 2);
+        assert!(events.iter().any(|event| event["ph"] == "M"));
+
+        let fusion = complete
+            .iter()
+            .find(|event| event["cat"] == "fusion")
+            .unwrap();
+        assert_eq!(fusion["args"]["num_operations"], 3);
+        assert!(complete.iter().any(|event| event["name"] == "step \"1\""));
+    }
+}
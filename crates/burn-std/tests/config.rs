@@ -8,6 +8,7 @@
 mod config_loading {
     use burn_std::config::autodiff::AutodiffLogLevel;
     use burn_std::config::fusion::FusionLogLevel;
+    use burn_std::config::remote::{LossyPrecision, PayloadCompression};
     use burn_std::config::{BurnConfig, RuntimeConfig};
     use std::io::Write;
     use tempfile::NamedTempFile;
@@ -36,6 +37,15 @@ level = "full"
 [cubecl.compilation.logger]
 level = "full"
 stdout = true
+"#;
+
+    const REMOTE_TOML: &str = r#"
+[remote]
+monitoring = true
+
+[remote.payload]
+compression = ["zstd", "lz4"]
+lossy = { block_quantized = { block_size = 32 } }
 "#;
 
     fn write_toml(content: &str) -> NamedTempFile {
@@ -53,6 +63,7 @@ stdout = true
         assert_eq!(config.fusion().beam_search.max_blocks, 5);
         assert_eq!(config.fusion().logger.level, FusionLogLevel::Disabled);
         assert_eq!(config.autodiff().logger.level, AutodiffLogLevel::Disabled);
+        assert!(!config.remote().monitoring);
     }
 
     #[test]
@@ -77,4 +88,21 @@ stdout = true
         assert!(config.fusion().logger.stdout);
         assert_eq!(config.autodiff().logger.level, AutodiffLogLevel::Basic);
     }
+
+    #[test]
+    fn load_remote_section() {
+        let file = write_toml(REMOTE_TOML);
+        let config = BurnConfig::from_file_path(file.path()).expect("parse remote toml");
+
+        let payload = &config.remote().payload;
+        assert_eq!(
+            payload.compression,
+            [PayloadCompression::Zstd, PayloadCompression::Lz4]
+        );
+        assert_eq!(
+            payload.lossy,
+            Some(LossyPrecision::BlockQuantized { block_size: 32 })
+        );
+        assert!(config.remote().monitoring);
+    }
 }
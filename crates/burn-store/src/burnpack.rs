@@ -3,6 +3,8 @@ use std::path::PathBuf;
 
 #[cfg(feature = "std")]
 use crate::KeyRemapper;
+#[cfg(feature = "std")]
+use crate::delta::{self, DeltaBase};
 use crate::{
     IdentityAdapter, ModuleAdapter, ModuleSnapshot, ModuleStore, PathFilter, TensorSnapshot,
 };
@@ -33,11 +35,28 @@ pub struct BurnpackStore {
     allow_partial: bool,
     /// Validate tensors during loading (check shapes and dtypes)
     validate: bool,
+    /// Record integrity checksums when saving (default: false)
+    checksums: bool,
+    /// Secret key used to sign the manifest when saving
+    #[cfg(feature = "burnpack-signature")]
+    signing_key: Option<[u8; burn_pack::SECRET_KEY_SIZE]>,
+    /// Public key a valid manifest signature is required from when loading
+    #[cfg(feature = "burnpack-signature")]
+    trusted_key: Option<[u8; burn_pack::PUBLIC_KEY_SIZE]>,
+    /// Detached manifest signature, produced by the last save or supplied for loading
+    #[cfg(feature = "burnpack-signature")]
+    signature: Option<[u8; burn_pack::SIGNATURE_SIZE]>,
     /// Allow overwriting existing files (default: false)
     overwrite: bool,
     /// Automatically append .bpk extension if not present (default: true)
     #[cfg(feature = "std")]
     auto_extension: bool,
+    /// Split saves into shards of at most this many bytes of tensor data
+    #[cfg(feature = "std")]
+    max_shard_size: Option<usize>,
+    /// Base snapshot saves are written as deltas against
+    #[cfg(feature = "std")]
+    delta_base: Option<PathBuf>,
     /// Key remapper for tensor name transformations
     #[cfg(feature = "std")]
     remapper: KeyRemapper,
@@ -94,10 +113,21 @@ impl BurnpackStore {
             metadata: Self::default_metadata(),
             allow_partial: false,
             validate: true,
+            checksums: false,
+            #[cfg(feature = "burnpack-signature")]
+            signing_key: None,
+            #[cfg(feature = "burnpack-signature")]
+            trusted_key: None,
+            #[cfg(feature = "burnpack-signature")]
+            signature: None,
             overwrite: false,
             #[cfg(feature = "std")]
             auto_extension: true,
             #[cfg(feature = "std")]
+            max_shard_size: None,
+            #[cfg(feature = "std")]
+            delta_base: None,
+            #[cfg(feature = "std")]
             remapper: KeyRemapper::new(),
             from_adapter: Box::new(IdentityAdapter),
             to_adapter: Box::new(IdentityAdapter),
@@ -114,10 +144,21 @@ impl BurnpackStore {
             metadata: Self::default_metadata(),
             allow_partial: false,
             validate: true,
+            checksums: false,
+            #[cfg(feature = "burnpack-signature")]
+            signing_key: None,
+            #[cfg(feature = "burnpack-signature")]
+            trusted_key: None,
+            #[cfg(feature = "burnpack-signature")]
+            signature: None,
             overwrite: false,
             #[cfg(feature = "std")]
             auto_extension: false, // Not used for bytes mode
             #[cfg(feature = "std")]
+            max_shard_size: None,
+            #[cfg(feature = "std")]
+            delta_base: None,
+            #[cfg(feature = "std")]
             remapper: KeyRemapper::new(),
             from_adapter: Box::new(IdentityAdapter),
             to_adapter: Box::new(IdentityAdapter),
@@ -153,10 +194,21 @@ impl BurnpackStore {
             metadata: Self::default_metadata(),
             allow_partial: false,
             validate: true,
+            checksums: false,
+            #[cfg(feature = "burnpack-signature")]
+            signing_key: None,
+            #[cfg(feature = "burnpack-signature")]
+            trusted_key: None,
+            #[cfg(feature = "burnpack-signature")]
+            signature: None,
             overwrite: false,
             #[cfg(feature = "std")]
             auto_extension: false,
             #[cfg(feature = "std")]
+            max_shard_size: None,
+            #[cfg(feature = "std")]
+            delta_base: None,
+            #[cfg(feature = "std")]
             remapper: KeyRemapper::new(),
             from_adapter: Box::new(IdentityAdapter),
             to_adapter: Box::new(IdentityAdapter),
@@ -203,6 +255,55 @@ impl BurnpackStore {
         self
     }
 
+    /// Enable or disable integrity checksums when saving
+    ///
+    /// When enabled, the saved file records a SHA-256 digest of every tensor and of the
+    /// whole data section, which is checked as each tensor is loaded. Files saved without
+    /// checksums (including every file saved before they existed) still load.
+    ///
+    /// Default: `false`
+    pub fn checksums(mut self, enabled: bool) -> Self {
+        self.checksums = enabled;
+        self
+    }
+
+    /// Sign the manifest with an ed25519 `secret_key` when saving
+    ///
+    /// Implies checksums, which are what extend the signature from the manifest to the
+    /// tensor data. In file mode the signature is written to a sidecar next to the file
+    /// (`model.bpk.sig`); in bytes mode, retrieve it with
+    /// [`get_signature`](Self::get_signature).
+    #[cfg(feature = "burnpack-signature")]
+    pub fn sign_with(mut self, secret_key: [u8; burn_pack::SECRET_KEY_SIZE]) -> Self {
+        self.signing_key = Some(secret_key);
+        self
+    }
+
+    /// Require a valid manifest signature from `public_key` when loading
+    ///
+    /// In file mode the signature is read from the sidecar next to the file, unless one was
+    /// given with [`with_signature`](Self::with_signature); in bytes mode it must be given.
+    /// Loading fails with a signature error if it is missing, does not verify, or the file
+    /// carries no checksums.
+    #[cfg(feature = "burnpack-signature")]
+    pub fn require_signature(mut self, public_key: [u8; burn_pack::PUBLIC_KEY_SIZE]) -> Self {
+        self.trusted_key = Some(public_key);
+        self
+    }
+
+    /// Supply the detached signature to check when loading
+    #[cfg(feature = "burnpack-signature")]
+    pub fn with_signature(mut self, signature: [u8; burn_pack::SIGNATURE_SIZE]) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    /// Get the manifest signature produced by the last save (only when signing)
+    #[cfg(feature = "burnpack-signature")]
+    pub fn get_signature(&self) -> Option<[u8; burn_pack::SIGNATURE_SIZE]> {
+        self.signature
+    }
+
     /// Allow overwriting existing files when saving
     ///
     /// When set to `false`, attempting to save to an existing file will result in an error.
@@ -240,6 +341,32 @@ impl BurnpackStore {
         self
     }
 
+    /// Save as shards of at most `max_bytes` of tensor data each, plus an index.
+    ///
+    /// The shards are written next to the path as `model-00001-of-0000N.bpk`, and the index
+    /// as `model.bpk.index.json`. Loading needs no option: a store pointed at the same path
+    /// opens the index, and each shard is only read once one of its tensors is applied.
+    ///
+    /// Only applies to file mode. Default: no sharding.
+    #[cfg(feature = "std")]
+    pub fn max_shard_size(mut self, max_bytes: usize) -> Self {
+        self.max_shard_size = Some(max_bytes);
+        self
+    }
+
+    /// Save only the tensors that changed since the base snapshot at `base`.
+    ///
+    /// The save is a [delta](crate::delta) naming the base, which must sit in the same
+    /// directory. A tensor is skipped when the base holds it with the same parameter id and
+    /// content. Loading needs no option: a store pointed at a delta reads through to its base.
+    ///
+    /// Only applies to file mode. Default: full saves.
+    #[cfg(feature = "std")]
+    pub fn delta_from<P: AsRef<std::path::Path>>(mut self, base: P) -> Self {
+        self.delta_base = Some(base.as_ref().to_path_buf());
+        self
+    }
+
     /// Set the adapter for loading tensors (converting from source format to Burn).
     pub fn with_from_adapter(mut self, adapter: impl ModuleAdapter + 'static) -> Self {
         self.from_adapter = Box::new(adapter);
@@ -348,6 +475,8 @@ impl BurnpackStore {
                     return Err(PackError::IoError("No bytes to read from".into()));
                 }
             };
+            #[cfg(feature = "burnpack-signature")]
+            self.check_signature(&reader)?;
             self.reader = Some(reader);
         }
 
@@ -355,6 +484,27 @@ impl BurnpackStore {
             .as_ref()
             .ok_or_else(|| PackError::IoError("Reader not initialized".into()))
     }
+
+    /// Verify the manifest signature of a freshly opened reader, if one is required
+    #[cfg(feature = "burnpack-signature")]
+    fn check_signature(&self, reader: &Reader) -> Result<(), PackError> {
+        let Some(public_key) = self.trusted_key else {
+            return Ok(());
+        };
+
+        let signature = match (&self.signature, &self.mode) {
+            (Some(signature), _) => *signature,
+            #[cfg(feature = "std")]
+            (None, StoreMode::File(path)) => burn_pack::read_signature(self.process_path(path))?,
+            (None, _) => {
+                return Err(PackError::SignatureError(
+                    "a signature is required but none was provided".into(),
+                ));
+            }
+        };
+
+        reader.verify_signature(&signature, &public_key)
+    }
 }
 
 impl ModuleStore for BurnpackStore {
@@ -371,7 +521,25 @@ impl ModuleStore for BurnpackStore {
         // Nothing is materialized here: each snapshot becomes a deferred tensor whose
         // `to_data()` runs when the writer reaches it in the data section.
         let tensors = snapshots.into_iter().map(PackTensor::from).collect();
-        let mut writer = Writer::new(tensors);
+        #[cfg(feature = "std")]
+        let writer = match (&self.delta_base, &self.mode) {
+            (None, _) => Writer::new(tensors),
+            (Some(base), StoreMode::File(path)) => {
+                DeltaBase::open(base)?.diff(tensors, self.process_path(path))?
+            }
+            (Some(_), StoreMode::Bytes(_)) => {
+                return Err(PackError::IoError(
+                    "delta saves name their base by file, so need file mode".into(),
+                ));
+            }
+        };
+        #[cfg(not(feature = "std"))]
+        let writer = Writer::new(tensors);
+        #[cfg(feature = "burnpack-signature")]
+        let checksums = self.checksums || self.signing_key.is_some();
+        #[cfg(not(feature = "burnpack-signature"))]
+        let checksums = self.checksums;
+        let mut writer = writer.with_checksums(checksums);
 
         // Add metadata using builder pattern
         for (key, value) in &self.metadata {
@@ -386,20 +554,56 @@ impl ModuleStore for BurnpackStore {
                 // Process path with auto-extension logic
                 let final_path = self.process_path(path);
 
-                // Check if file exists and overwrite is disabled
-                if final_path.exists() && !self.overwrite {
-                    return Err(PackError::IoError(format!(
-                        "File already exists: {}. Use .overwrite(true) to overwrite.",
-                        final_path.display()
-                    )));
+                // Check if file exists and overwrite is disabled. A sharded save lives at the
+                // index path, and counts as an existing file too.
+                let index_path = burn_pack::index_path(&final_path);
+                for existing in [&final_path, &index_path] {
+                    if existing.exists() && !self.overwrite {
+                        return Err(PackError::IoError(format!(
+                            "File already exists: {}. Use .overwrite(true) to overwrite.",
+                            existing.display()
+                        )));
+                    }
                 }
+
                 // Atomic: snapshots materialize mid-write, so a device readback that
                 // fails partway must not truncate whatever was already at this path.
-                writer.write_to_file_atomic(&final_path)?;
+                // The previous save's other form is removed afterwards: a reader prefers the
+                // single file over the index, so a stale one would shadow the new save.
+                let stale = match self.max_shard_size {
+                    Some(max_bytes) => {
+                        writer.write_sharded(&final_path, max_bytes)?;
+                        &final_path
+                    }
+                    None => {
+                        writer.write_to_file_atomic(&final_path)?;
+                        &index_path
+                    }
+                };
+                if stale.exists() {
+                    std::fs::remove_file(stale).map_err(|e| {
+                        PackError::IoError(format!(
+                            "cannot remove the previous save '{}': {e}",
+                            stale.display()
+                        ))
+                    })?;
+                }
+
+                #[cfg(feature = "burnpack-signature")]
+                if let Some(secret_key) = &self.signing_key {
+                    self.signature = Some(burn_pack::sign_file(&final_path, secret_key)?);
+                }
             }
             StoreMode::Bytes(_) => {
                 // Generate and store the bytes
                 let bytes_data = writer.into_bytes()?;
+
+                #[cfg(feature = "burnpack-signature")]
+                if let Some(secret_key) = &self.signing_key {
+                    let reader = Reader::from_bytes(bytes_data.clone())?;
+                    self.signature = Some(burn_pack::sign_manifest(reader.manifest(), secret_key));
+                }
+
                 // Update mode with bytes - this pattern is irrefutable in no-std mode
                 #[cfg_attr(not(feature = "std"), allow(irrefutable_let_patterns))]
                 let StoreMode::Bytes(bytes_ref) = &mut self.mode else {
@@ -484,11 +688,17 @@ impl BurnpackStore {
             .reader
             .take()
             .expect("reader initialized by ensure_reader");
-        let snapshots: Vec<TensorSnapshot> = reader
-            .into_tensors()?
-            .into_iter()
-            .map(TensorSnapshot::from)
-            .collect();
+        #[cfg(feature = "std")]
+        let tensors = match &self.mode {
+            StoreMode::File(path) if delta::is_delta_reader(&reader) => {
+                delta::resolve(&self.process_path(path), reader, 0)?
+            }
+            _ => reader.into_tensors()?,
+        };
+        #[cfg(not(feature = "std"))]
+        let tensors = reader.into_tensors()?;
+        let snapshots: Vec<TensorSnapshot> =
+            tensors.into_iter().map(TensorSnapshot::from).collect();
 
         // Apply remapping if configured (but NOT filtering - that's done at apply time)
         #[cfg(feature = "std")]
@@ -0,0 +1,321 @@
+//! Delta burnpack containers: only the tensors that changed since a base snapshot.
+//!
+//! Fine-tuning leaves most of a model untouched (a [LoRA](burn_core::module::Lora) run freezes
+//! every base weight), so saving the whole module at every checkpoint mostly rewrites bytes
+//! that are already on disk. A delta is an ordinary burnpack container holding only the
+//! tensors whose [`ParamId`](burn_core::module::ParamId) or content differ from the base, plus
+//! metadata naming the base and pinning its manifest. Reading one back with [`read_delta`]
+//! yields the full set of tensors, and [`squash_delta`] rewrites it as a standalone container.
+//!
+//! A base may itself be a delta; the chain is followed on read. The base is referred to by
+//! file name, so a delta must be written next to it.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use std::path::{Path, PathBuf};
+
+use burn_pack::{DType, Digest, Error as PackError, Reader, Shape, Tensor as PackTensor, Writer};
+use hashbrown::{HashMap, HashSet};
+
+/// Metadata key naming the base container, by file name.
+const BASE_KEY: &str = "burn.delta.base";
+/// Metadata key holding the SHA-256 of the base's manifest, so a replaced base is caught.
+const BASE_MANIFEST_KEY: &str = "burn.delta.base_manifest";
+/// Metadata key listing, one per line, the base tensors the delta drops.
+const REMOVED_KEY: &str = "burn.delta.removed";
+
+/// How many bases a chain of deltas may go through, which also stops a cycle.
+const MAX_CHAIN_LENGTH: usize = 64;
+
+/// What a base records about one of its tensors: enough to tell it is unchanged.
+struct BaseTensor {
+    param_id: Option<u64>,
+    dtype: DType,
+    shape: Shape,
+    digest: Digest,
+}
+
+/// A base snapshot that deltas are computed against.
+///
+/// Opening one reads the digest of every tensor. For a container written
+/// [with checksums](Writer::with_checksums) they come from its metadata; otherwise, or when
+/// the base is itself a delta, the tensors are read and hashed once.
+pub struct DeltaBase {
+    path: PathBuf,
+    manifest: Digest,
+    tensors: HashMap<String, BaseTensor>,
+}
+
+impl DeltaBase {
+    /// Open the container at `path` as a base.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PackError> {
+        let path = path.as_ref();
+        let reader = Reader::from_file(path)?;
+        let manifest = Digest::of(reader.manifest());
+        let checksums: HashMap<String, Digest> = match is_delta_reader(&reader) {
+            true => HashMap::new(),
+            false => reader
+                .tensor_names()
+                .into_iter()
+                .filter_map(|name| Some((name.to_string(), reader.tensor_checksum(name)?)))
+                .collect(),
+        };
+
+        let mut tensors = HashMap::new();
+        for tensor in resolve(path, reader, 0)? {
+            let digest = match checksums.get(&tensor.name) {
+                Some(digest) => *digest,
+                None => Digest::of(&tensor.to_bytes()?),
+            };
+            tensors.insert(
+                tensor.name,
+                BaseTensor {
+                    param_id: tensor.param_id,
+                    dtype: tensor.dtype,
+                    shape: tensor.shape,
+                    digest,
+                },
+            );
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            manifest,
+            tensors,
+        })
+    }
+
+    /// The path the base was opened from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the base was saved from the same model as `tensors`: the same tensor names,
+    /// each with the same parameter id, dtype and shape. Contents may differ.
+    pub fn is_base_of(&self, tensors: &[PackTensor]) -> bool {
+        tensors.len() == self.tensors.len()
+            && tensors.iter().all(|tensor| {
+                self.tensors.get(&tensor.name).is_some_and(|base| {
+                    base.param_id == tensor.param_id
+                        && base.dtype == tensor.dtype
+                        && base.shape == tensor.shape
+                })
+            })
+    }
+
+    /// A writer for the delta of `tensors` against this base, to be written at `path`.
+    ///
+    /// A tensor is left out when the base holds one with the same name, parameter id, dtype,
+    /// shape and content; base tensors missing from `tensors` are recorded as removed. Every
+    /// tensor is materialized once to be hashed, and the changed ones are kept in memory
+    /// until written.
+    ///
+    /// `path` must be in the same directory as the base, which the delta names by file name
+    /// only.
+    pub fn diff<P: AsRef<Path>>(
+        &self,
+        tensors: Vec<PackTensor>,
+        path: P,
+    ) -> Result<Writer, PackError> {
+        let path = path.as_ref();
+        let base_name = self.file_name()?;
+        if directory(path)? != directory(&self.path)? {
+            return Err(PackError::ValidationError(format!(
+                "the delta '{}' must be written next to its base '{}'",
+                path.display(),
+                self.path.display()
+            )));
+        }
+        if path.file_name().and_then(|name| name.to_str()) == Some(base_name.as_str()) {
+            return Err(PackError::ValidationError(format!(
+                "the delta would overwrite its base '{}'",
+                self.path.display()
+            )));
+        }
+
+        let mut present = HashSet::new();
+        let mut changed = Vec::new();
+        for tensor in tensors {
+            let (name, dtype, shape, param_id, bytes) = tensor.into_parts()?;
+            let unchanged = self.tensors.get(&name).is_some_and(|base| {
+                base.param_id == param_id
+                    && base.dtype == dtype
+                    && base.shape == shape
+                    && base.digest == Digest::of(&bytes)
+            });
+            if !unchanged {
+                changed.push(PackTensor::new(name.clone(), dtype, shape, param_id, bytes));
+            }
+            present.insert(name);
+        }
+
+        let mut removed: Vec<&str> = self
+            .tensors
+            .keys()
+            .filter(|name| !present.contains(*name))
+            .map(String::as_str)
+            .collect();
+        removed.sort_unstable();
+
+        let mut writer = Writer::new(changed)
+            .with_metadata(BASE_KEY, &base_name)
+            .with_metadata(BASE_MANIFEST_KEY, &self.manifest.to_string());
+        if !removed.is_empty() {
+            writer = writer.with_metadata(REMOVED_KEY, &removed.join("\n"));
+        }
+        Ok(writer)
+    }
+
+    fn file_name(&self) -> Result<String, PackError> {
+        self.path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                PackError::ValidationError(format!(
+                    "the base '{}' has no usable file name",
+                    self.path.display()
+                ))
+            })
+    }
+}
+
+/// Whether the container at `path` is a delta.
+pub fn is_delta<P: AsRef<Path>>(path: P) -> Result<bool, PackError> {
+    Ok(is_delta_reader(&Reader::from_file(path)?))
+}
+
+/// The full set of tensors of the container at `path`, following its chain of bases.
+///
+/// A container that is not a delta reads as itself. Each base's manifest is checked against
+/// the digest its delta recorded, so a base replaced since is refused rather than merged.
+pub fn read_delta<P: AsRef<Path>>(path: P) -> Result<Vec<PackTensor>, PackError> {
+    let path = path.as_ref();
+    resolve(path, Reader::from_file(path)?, 0)
+}
+
+/// Rewrite the delta at `path` as a standalone container at `out`, with checksums.
+///
+/// `out` may be `path` itself: the container is replaced atomically once fully written. The
+/// delta's own metadata and scalars are kept, without the keys that made it a delta.
+pub fn squash_delta<P: AsRef<Path>, Q: AsRef<Path>>(path: P, out: Q) -> Result<(), PackError> {
+    let path = path.as_ref();
+    let reader = Reader::from_file(path)?;
+    let metadata: Vec<(String, String)> = reader
+        .metadata()
+        .iter()
+        .filter(|(key, _)| ![BASE_KEY, BASE_MANIFEST_KEY, REMOVED_KEY].contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let scalars: Vec<_> = reader
+        .scalars()
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    let mut writer = Writer::new(resolve(path, reader, 0)?).with_checksums(true);
+    for (key, value) in &metadata {
+        writer = writer.with_metadata(key, value);
+    }
+    for (key, value) in scalars {
+        writer = writer.with_scalar(&key, value);
+    }
+    writer.write_to_file_atomic(out)
+}
+
+/// Whether an open container is a delta.
+pub(crate) fn is_delta_reader(reader: &Reader) -> bool {
+    reader.metadata().contains_key(BASE_KEY)
+}
+
+/// Merge the container opened by `reader` (from `path`) over its chain of bases.
+///
+/// Base tensors keep their order, replaced by the delta's version where it has one; tensors
+/// new in the delta follow.
+pub(crate) fn resolve(
+    path: &Path,
+    reader: Reader,
+    depth: usize,
+) -> Result<Vec<PackTensor>, PackError> {
+    let Some(base_name) = reader.metadata().get(BASE_KEY).cloned() else {
+        return reader.into_tensors();
+    };
+    if depth == MAX_CHAIN_LENGTH {
+        return Err(PackError::ValidationError(format!(
+            "'{}' is more than {MAX_CHAIN_LENGTH} deltas away from a full container",
+            path.display()
+        )));
+    }
+
+    let base_path = base_path(path, &base_name)?;
+    let base = Reader::from_file(&base_path)?;
+    let expected = reader.metadata().get(BASE_MANIFEST_KEY).cloned();
+    if expected != Some(Digest::of(base.manifest()).to_string()) {
+        return Err(PackError::ValidationError(format!(
+            "'{}' is not the base '{}' was written against",
+            base_path.display(),
+            path.display()
+        )));
+    }
+    let removed: HashSet<String> = reader
+        .metadata()
+        .get(REMOVED_KEY)
+        .map(|names| names.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+
+    let mut overrides: HashMap<String, PackTensor> = reader
+        .into_tensors()?
+        .into_iter()
+        .map(|tensor| (tensor.name.clone(), tensor))
+        .collect();
+    let base_tensors = resolve(&base_path, base, depth + 1)?;
+    let base_names: HashSet<String> = base_tensors.iter().map(|t| t.name.clone()).collect();
+
+    let mut tensors = Vec::with_capacity(base_tensors.len() + overrides.len());
+    let mut added = Vec::new();
+    for tensor in base_tensors {
+        if removed.contains(&tensor.name) {
+            continue;
+        }
+        tensors.push(overrides.remove(&tensor.name).unwrap_or(tensor));
+    }
+    for (name, tensor) in overrides {
+        if !base_names.contains(&name) {
+            added.push(tensor);
+        }
+    }
+    // The map lost the delta's order; its containers list tensors sorted by name.
+    added.sort_by(|a, b| a.name.cmp(&b.name));
+    tensors.extend(added);
+
+    Ok(tensors)
+}
+
+/// Where the base named `name` of the delta at `path` lives.
+///
+/// Only a plain file name is accepted, so a delta cannot point outside its own directory.
+fn base_path(path: &Path, name: &str) -> Result<PathBuf, PackError> {
+    let file = Path::new(name);
+    if file.file_name() != Some(file.as_os_str()) {
+        return Err(PackError::ValidationError(format!(
+            "'{}' names its base '{name}', which is not a plain file name",
+            path.display()
+        )));
+    }
+    Ok(match path.parent() {
+        Some(parent) => parent.join(file),
+        None => file.to_path_buf(),
+    })
+}
+
+/// The canonical directory of `path`, for telling whether two files sit side by side.
+fn directory(path: &Path) -> Result<PathBuf, PackError> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    parent
+        .canonicalize()
+        .map_err(|e| PackError::IoError(format!("cannot resolve '{}': {e}", parent.display())))
+}
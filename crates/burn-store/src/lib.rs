@@ -123,6 +123,9 @@ mod burnpack;
 #[cfg(feature = "burnpack")]
 pub use burnpack::BurnpackStore;
 
+#[cfg(all(feature = "burnpack", feature = "std"))]
+pub mod delta;
+
 /// The burnpack format crate, re-exported.
 ///
 /// A [`TensorSnapshot`] converts into a deferred [`burn_pack::Tensor`], so a crate holding
@@ -130,3 +130,94 @@ fn saving_through_an_adapter_round_trips() {
         assert!((got - want).abs() < 1e-2, "{got} vs {want}");
     }
 }
+
+/// With checksums, a bit flipped on disk fails the load instead of handing the model corrupted
+/// weights.
+#[test]
+fn corrupted_file_fails_to_load() {
+    let device = Device::default();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.bpk");
+
+    TestModel::new(&device)
+        .save_into(&mut BurnpackStore::from_file(&path).checksums(true))
+        .unwrap();
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0x01;
+    std::fs::write(&path, bytes).unwrap();
+
+    let mut loaded = TestModel::new(&device);
+    assert!(
+        loaded
+            .load_from(&mut BurnpackStore::from_file(&path))
+            .is_err()
+    );
+}
+
+#[cfg(feature = "burnpack-signature")]
+#[test]
+fn required_signature_is_checked_on_load() {
+    let device = Device::default();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.bpk");
+    let secret = [9u8; 32];
+    let public = burn_pack::public_key(&secret);
+
+    let model = TestModel::new(&device);
+    model
+        .save_into(&mut BurnpackStore::from_file(&path).sign_with(secret))
+        .unwrap();
+    assert!(dir.path().join("model.bpk.sig").exists());
+
+    let mut loaded = TestModel::new(&device);
+    let mut store = BurnpackStore::from_file(&path).require_signature(public);
+    assert!(loaded.load_from(&mut store).unwrap().is_success());
+    assert_eq!(first_weight(&loaded), first_weight(&model));
+
+    // The wrong key, or no signature at all, refuses to load.
+    let mut store =
+        BurnpackStore::from_file(&path).require_signature(burn_pack::public_key(&[1; 32]));
+    assert!(loaded.load_from(&mut store).is_err());
+
+    std::fs::remove_file(dir.path().join("model.bpk.sig")).unwrap();
+    let mut store = BurnpackStore::from_file(&path).require_signature(public);
+    assert!(loaded.load_from(&mut store).is_err());
+}
+
+#[test]
+fn sharded_save_round_trips() {
+    let device = Device::default();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.bpk");
+
+    let model = TestModel::new(&device);
+    model
+        .save_into(&mut BurnpackStore::from_file(&path).max_shard_size(256))
+        .unwrap();
+    assert!(dir.path().join("model.bpk.index.json").exists());
+    let shards = std::fs::read_dir(dir.path())
+        .unwrap()
+        .filter(|entry| {
+            let name = entry.as_ref().unwrap().file_name();
+            name.to_string_lossy().starts_with("model-0000")
+        })
+        .count();
+    assert!(shards > 1);
+    assert!(!path.exists());
+
+    let mut loaded = TestModel::new(&device);
+    let result = loaded
+        .load_from(&mut BurnpackStore::from_file(&path))
+        .unwrap();
+    assert!(result.is_success());
+    assert_eq!(first_weight(&loaded), first_weight(&model));
+
+    // Saving unsharded over it removes the stale index.
+    model
+        .save_into(&mut BurnpackStore::from_file(&path).overwrite(true))
+        .unwrap();
+    assert!(path.exists());
+    assert!(!dir.path().join("model.bpk.index.json").exists());
+}
@@ -0,0 +1,130 @@
+//! Delta saves through `BurnpackStore`: only changed tensors are written, and loading reads
+//! through to the base.
+
+#![cfg(all(feature = "burnpack", feature = "std"))]
+
+// The `Module` derive expands to `::burn::...` paths.
+use burn_core as burn;
+
+use burn_core::module::Module;
+use burn_core::tensor::Device;
+use burn_nn::{Linear, LinearConfig};
+use burn_store::burn_pack::Reader;
+use burn_store::delta::{DeltaBase, is_delta, read_delta, squash_delta};
+use burn_store::{BurnpackStore, ModuleSnapshot};
+
+#[derive(Module, Debug)]
+struct TestModel {
+    frozen: Linear,
+    head: Linear,
+}
+
+impl TestModel {
+    fn new(device: &Device) -> Self {
+        Self {
+            frozen: LinearConfig::new(8, 16).init(device),
+            head: LinearConfig::new(16, 4).init(device),
+        }
+    }
+}
+
+fn weights(linear: &Linear) -> Vec<f32> {
+    linear.weight.val().to_data().try_to_vec().unwrap()
+}
+
+/// A base, then the same model with a fresh `head`: the delta holds the head only.
+fn base_and_delta(dir: &std::path::Path) -> TestModel {
+    let device = Device::default();
+    let mut model = TestModel::new(&device);
+    model
+        .save_into(&mut BurnpackStore::from_file(dir.join("base.bpk")))
+        .unwrap();
+
+    model.head = LinearConfig::new(16, 4).init(&device);
+    model
+        .save_into(
+            &mut BurnpackStore::from_file(dir.join("delta.bpk")).delta_from(dir.join("base.bpk")),
+        )
+        .unwrap();
+    model
+}
+
+#[test]
+fn delta_holds_only_changed_tensors() {
+    let dir = tempfile::tempdir().unwrap();
+    base_and_delta(dir.path());
+
+    let delta = dir.path().join("delta.bpk");
+    assert!(is_delta(&delta).unwrap());
+    assert!(!is_delta(dir.path().join("base.bpk")).unwrap());
+    assert_eq!(
+        Reader::from_file(&delta).unwrap().tensor_names(),
+        vec!["head.bias", "head.weight"]
+    );
+    assert_eq!(read_delta(&delta).unwrap().len(), 4);
+}
+
+#[test]
+fn store_loads_a_delta_over_its_base() {
+    let dir = tempfile::tempdir().unwrap();
+    let model = base_and_delta(dir.path());
+
+    let mut loaded = TestModel::new(&Device::default());
+    let result = loaded
+        .load_from(&mut BurnpackStore::from_file(dir.path().join("delta.bpk")))
+        .unwrap();
+    assert!(result.is_success());
+    assert_eq!(weights(&loaded.frozen), weights(&model.frozen));
+    assert_eq!(weights(&loaded.head), weights(&model.head));
+    assert_eq!(loaded.head.weight.id, model.head.weight.id);
+}
+
+#[test]
+fn squashed_delta_stands_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    let model = base_and_delta(dir.path());
+    let delta = dir.path().join("delta.bpk");
+
+    squash_delta(&delta, &delta).unwrap();
+    std::fs::remove_file(dir.path().join("base.bpk")).unwrap();
+    assert!(!is_delta(&delta).unwrap());
+
+    let mut loaded = TestModel::new(&Device::default());
+    loaded
+        .load_from(&mut BurnpackStore::from_file(&delta))
+        .unwrap();
+    assert_eq!(weights(&loaded.frozen), weights(&model.frozen));
+    assert_eq!(weights(&loaded.head), weights(&model.head));
+}
+
+/// A delta pins its base's manifest, so a base saved over since is refused, not merged.
+#[test]
+fn replaced_base_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    base_and_delta(dir.path());
+
+    TestModel::new(&Device::default())
+        .save_into(&mut BurnpackStore::from_file(dir.path().join("base.bpk")).overwrite(true))
+        .unwrap();
+
+    let mut loaded = TestModel::new(&Device::default());
+    assert!(
+        loaded
+            .load_from(&mut BurnpackStore::from_file(dir.path().join("delta.bpk")))
+            .is_err()
+    );
+}
+
+#[test]
+fn delta_must_sit_next_to_its_base() {
+    let dir = tempfile::tempdir().unwrap();
+    base_and_delta(dir.path());
+    std::fs::create_dir(dir.path().join("nested")).unwrap();
+
+    let base = DeltaBase::open(dir.path().join("base.bpk")).unwrap();
+    assert!(
+        base.diff(Vec::new(), dir.path().join("nested").join("delta.bpk"))
+            .is_err()
+    );
+    assert!(base.diff(Vec::new(), dir.path().join("base.bpk")).is_err());
+}
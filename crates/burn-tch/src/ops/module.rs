@@ -488,10 +488,25 @@ impl ModuleOps<Self> for LibTorch {
         value: TchTensor,
         mask: Option<TchTensor>,
         attn_bias: Option<TchTensor>,
+        seq_offsets: Option<TchTensor>,
         options: AttentionModuleOptions,
     ) -> TchTensor {
-        if attn_bias.is_some() {
-            return attention_fallback::<Self>(query, key, value, mask, attn_bias, options);
+        // libtorch's sdpa has no softcap, sliding window, ALiBi or packed sequences.
+        if attn_bias.is_some()
+            || options.softcap.is_some()
+            || options.sliding_window.is_some()
+            || options.alibi_max_bias.is_some()
+            || seq_offsets.is_some()
+        {
+            return attention_fallback::<Self>(
+                query,
+                key,
+                value,
+                mask,
+                attn_bias,
+                seq_offsets,
+                options,
+            );
         }
 
         TchTensor::new(tch::Tensor::scaled_dot_product_attention(
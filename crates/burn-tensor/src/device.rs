@@ -391,6 +391,38 @@ impl Device {
         Self::new(device)
     }
 
+    /// Like `remote_websocket`, but carries an authorization credential (e.g. a token) the
+    /// server's PeerAuthorizer will check. Prefer a `wss` address so the credential is encrypted.
+    #[cfg(feature = "remote-websocket")]
+    pub fn remote_websocket_authorized(
+        address: &str,
+        index: impl Into<DeviceIndex>,
+        credential: Vec<u8>,
+    ) -> Self {
+        let index = index.into().resolve();
+        let device =
+            burn_dispatch::devices::RemoteDevice::websocket_authorized(address, index, credential);
+        device.connect();
+        Self::new(device)
+    }
+
+    /// Like `remote_websocket_authorized`, but connects to a `wss` address with `tls`: its
+    /// authorities verify the server, and its client certificate, if any, authenticates this
+    /// client to servers requiring mutual TLS.
+    #[cfg(feature = "remote-websocket-tls")]
+    pub fn remote_websocket_tls(
+        address: &str,
+        index: impl Into<DeviceIndex>,
+        credential: Vec<u8>,
+        tls: burn_dispatch::backends::remote::TlsClientConfig,
+    ) -> Self {
+        let index = index.into().resolve();
+        let device =
+            burn_dispatch::devices::RemoteDevice::websocket_tls(address, index, credential, tls);
+        device.connect();
+        Self::new(device)
+    }
+
     /// Iroh peer-to-peer remote device.
     ///
     /// `endpoint` is the application-owned Iroh endpoint to dial from; `peer` is the compute
@@ -55,6 +55,9 @@ pub use burn_std::{
 mod device;
 pub use device::*;
 
+#[cfg(feature = "std")]
+pub mod profile;
+
 #[cfg(feature = "remote-server")]
 pub mod server;
 
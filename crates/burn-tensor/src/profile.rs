@@ -0,0 +1,80 @@
+//! Profiling of tensor operations, fusion blocks and module calls.
+//!
+//! ```rust,ignore
+//! let (output, report) = Profiler::new(&device).run(|| model.forward(input));
+//!
+//! println!("{report}");
+//! report.save_chrome_trace("forward.json")?;
+//! ```
+//!
+//! Module calls are recorded with their path (e.g. `encoder.layers.0.linear`) when the model is
+//! run with `ForwardHooks::run` from `burn::module`.
+
+pub use burn_std::profile::{
+    ProfileEvent, ProfileEventKind, ProfileReport, ProfileScope, ProfileSummary, scope,
+};
+
+use burn_std::profile::{finish_session, start_session};
+
+use crate::Device;
+
+/// Records the tensor operations executed on a device.
+///
+/// Only one profiler can run at a time, since the events of every thread are recorded.
+#[derive(Clone, Debug)]
+pub struct Profiler {
+    device: Device,
+    sync_operations: bool,
+}
+
+impl Profiler {
+    /// Create a profiler for the given device.
+    pub fn new(device: &Device) -> Self {
+        Self {
+            device: device.clone(),
+            sync_operations: true,
+        }
+    }
+
+    /// Whether the device is synchronized after every operation, which is enabled by default.
+    ///
+    /// Operations are executed asynchronously by most backends, so without synchronization the
+    /// recorded durations only cover the time to enqueue them. Synchronizing gives the execution
+    /// time of each operation, but prevents operations from being fused together.
+    pub fn with_sync_operations(mut self, sync_operations: bool) -> Self {
+        self.sync_operations = sync_operations;
+        self
+    }
+
+    /// Run `func` and return its output along with the report of the recorded events.
+    ///
+    /// The device is synchronized before and after `func`, so that the report only covers the
+    /// work it submitted.
+    ///
+    /// # Panics
+    ///
+    /// If another profiler is running.
+    pub fn run<R>(&self, func: impl FnOnce() -> R) -> (R, ProfileReport) {
+        self.sync();
+        start_session(self.sync_operations);
+        let session = Session;
+        let output = func();
+        self.sync();
+        core::mem::forget(session);
+        (output, finish_session())
+    }
+
+    fn sync(&self) {
+        // Execution errors are reported when reading the tensors, not by the profiler.
+        let _ = self.device.sync();
+    }
+}
+
+/// Finishes the session if the profiled function panics.
+struct Session;
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        finish_session();
+    }
+}
@@ -11,18 +11,27 @@
 //! - Composed (protocol): for applications that own their own Iroh router. Burn hands back only
 //!   its protocol handler to register alongside the application's own protocols.
 //!
+//! Turnkey servers accept every session unless started with a ServerAuth (start_with_auth /
+//! start_async_with_auth), whose PeerAuthorizer checks each session over either transport; the
+//! WebSocket transport can additionally be served over TLS with the `remote-websocket-tls`
+//! feature. Composed handlers can cap what each session and peer may use with ServerQuotas.
+//!
 //! User-defined backends that implement BackendIr but are not part of DispatchDevice use
 //! burn_remote::server::RemoteServerBuilder directly; that is also how custom operations
 //! (backend extensions) are hosted, over either transport.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::Device;
 pub use burn_dispatch::backends::remote::server::{
-    AllowAll, AuthorizationRequest, PeerAuthorizer, RemoteProtocol,
+    AllowAll, AuthorizationRequest, PeerAuthorizer, PeerIdentity, QuotaLimits, RemoteProtocol,
+    ServerAuth, ServerQuotas, TokenAuthorizer,
 };
 pub use burn_dispatch::backends::remote::telemetry;
 pub use burn_dispatch::backends::remote::{Endpoint, RemoteSecret};
+#[cfg(feature = "remote-websocket-tls")]
+pub use burn_dispatch::backends::remote::{TlsError, TlsServerConfig};
 pub use burn_dispatch::devices::BURN_REMOTE_ALPN;
 
 use telemetry::TelemetryProbe;
@@ -39,12 +48,15 @@ pub fn protocol(device: Device, endpoint: &Endpoint) -> RemoteProtocolBuilder<'_
     RemoteProtocolBuilder::new(device, endpoint)
 }
 
-/// Configures optional telemetry and authorization for a RemoteProtocol handler.
+/// Configures optional telemetry, authorization, quotas and session resumption for a RemoteProtocol
+/// handler.
 pub struct RemoteProtocolBuilder<'a> {
     device: Device,
     endpoint: &'a Endpoint,
     probe: Option<TelemetryProbe>,
     authorizer: Option<Arc<dyn PeerAuthorizer>>,
+    quotas: ServerQuotas,
+    resume_grace: Option<Duration>,
 }
 
 impl<'a> RemoteProtocolBuilder<'a> {
@@ -55,6 +67,8 @@ impl<'a> RemoteProtocolBuilder<'a> {
             endpoint,
             probe: None,
             authorizer: None,
+            quotas: ServerQuotas::default(),
+            resume_grace: None,
         }
     }
 
@@ -72,6 +86,20 @@ impl<'a> RemoteProtocolBuilder<'a> {
         self
     }
 
+    /// Limit the resources each session and each peer may use. Sessions going over a limit are
+    /// refused or closed, and their client gets the exceeded quota back as an error.
+    pub fn with_quotas(mut self, quotas: ServerQuotas) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// Keep a session for `grace` after its connection drops, so the client can reconnect and
+    /// resume it with its tensors intact.
+    pub fn with_resume_grace(mut self, grace: Duration) -> Self {
+        self.resume_grace = Some(grace);
+        self
+    }
+
     /// Build the backend-erased protocol handler.
     pub fn build(self) -> RemoteProtocol {
         burn_dispatch::remote_server::remote_protocol(
@@ -79,6 +107,8 @@ impl<'a> RemoteProtocolBuilder<'a> {
             self.endpoint,
             self.probe.unwrap_or_else(TelemetryProbe::disabled),
             self.authorizer.unwrap_or_else(|| Arc::new(AllowAll)),
+            self.quotas,
+            self.resume_grace,
         )
     }
 }
@@ -112,3 +142,20 @@ pub fn start(device: Device, channel: Channel) {
 pub async fn start_async(device: Device, channel: Channel) {
     burn_dispatch::remote_server::start_async(device.into_dispatch(), channel).await
 }
+
+/// Like [`start`], but only admits the sessions allowed by `auth`.
+///
+/// ```rust,ignore
+/// let auth = ServerAuth::default().with_authorizer(TokenAuthorizer::new(token));
+/// server::start_with_auth(Device::cuda(0), Channel::WebSocket { port: 3000 }, auth);
+/// ```
+#[cfg(not(target_family = "wasm"))]
+pub fn start_with_auth(device: Device, channel: Channel, auth: ServerAuth) {
+    burn_dispatch::remote_server::start_with_auth(device.into_dispatch(), channel, auth)
+}
+
+/// Async counterpart of [`start_with_auth`].
+#[cfg(not(target_family = "wasm"))]
+pub async fn start_async_with_auth(device: Device, channel: Channel, auth: ServerAuth) {
+    burn_dispatch::remote_server::start_async_with_auth(device.into_dispatch(), channel, auth).await
+}
@@ -73,6 +73,30 @@ impl<const D: usize> Tensor<D> {
     pub fn grad_replace(&self, grads: &mut Gradients, grad: Tensor<D>) {
         grad_replace_impl(&self.primitive, grads, grad.primitive)
     }
+
+    /// Attach a forward-mode tangent to the tensor, which must be on an autodiff device.
+    ///
+    /// Every operation applied to the returned tensor propagates the tangent, so the
+    /// [tangent](Tensor::tangent) of a result is its directional derivative along the tangents
+    /// of its inputs. Tangents are part of the autodiff graph: calling [backward](Tensor::backward)
+    /// on a tangent computes second-order derivatives.
+    ///
+    /// # Panics
+    ///
+    /// If the shape of the tangent doesn't match the shape of the tensor.
+    pub fn with_tangent(self, tangent: Tensor<D>) -> Self {
+        assert_eq!(
+            self.shape(),
+            tangent.shape(),
+            "The tangent must have the same shape as the tensor"
+        );
+        Self::new(with_tangent_impl(self.primitive, Some(tangent.primitive)))
+    }
+
+    /// Get the forward-mode tangent of the tensor, `None` when it doesn't depend on any tangent.
+    pub fn tangent(&self) -> Option<Tensor<D>> {
+        tangent_impl(&self.primitive).map(Tensor::new)
+    }
 }
 
 #[cfg(feature = "autodiff")]
@@ -92,6 +116,19 @@ fn grad_remove_impl(p: &BridgeTensor, grads: &mut Gradients) -> Option<BridgeTen
     Dispatch::grad_remove(p.try_as_float()?, grads.as_inner_mut()).map(BridgeTensor::float)
 }
 
+#[cfg(feature = "autodiff")]
+fn with_tangent_impl(p: BridgeTensor, tangent: Option<BridgeTensor>) -> BridgeTensor {
+    BridgeTensor::float(Dispatch::with_tangent(
+        p.into_float(),
+        tangent.map(BridgeTensor::into_float),
+    ))
+}
+
+#[cfg(feature = "autodiff")]
+fn tangent_impl(p: &BridgeTensor) -> Option<BridgeTensor> {
+    Dispatch::tangent(p.try_as_float()?).map(BridgeTensor::float)
+}
+
 #[cfg(feature = "autodiff")]
 fn grad_replace_impl(p: &BridgeTensor, grads: &mut Gradients, grad: BridgeTensor) {
     Dispatch::grad_replace(p.as_float(), grads.as_inner_mut(), grad.into_float())
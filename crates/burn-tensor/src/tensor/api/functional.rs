@@ -0,0 +1,145 @@
+use alloc::{vec, vec::Vec};
+
+use burn_std::TensorData;
+
+use crate::Tensor;
+
+// Reverse-mode products are built from backward passes, forward-mode products from the tangents
+// propagated by the autodiff backend. Since tangents are tracked like any other tensor, a backward
+// pass over a tangent gives exact second-order products.
+
+impl<const D: usize> Tensor<D> {
+    /// Computes the vector-Jacobian product of `func` at `primal`.
+    ///
+    /// Returns the output of `func` and `cotangentᵀ J`, the gradient of
+    /// `sum(func(primal) * cotangent)` with respect to `primal`, with a single backward pass.
+    /// The primal can be on any device: `func` receives it on the autodiff wrapper of its device,
+    /// where the tensors it captures should live too, and both results are returned off the
+    /// autodiff graph.
+    ///
+    /// # Panics
+    ///
+    /// If the output of `func` doesn't depend on its input.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (output, grad) = Tensor::vjp(|x| x.clone() * x, primal, cotangent);
+    /// // grad = 2 * primal * cotangent
+    /// ```
+    pub fn vjp<const D2: usize, F>(
+        func: F,
+        primal: Self,
+        cotangent: Tensor<D2>,
+    ) -> (Tensor<D2>, Self)
+    where
+        F: FnOnce(Self) -> Tensor<D2>,
+    {
+        let leaf = leaf(primal);
+        let output = func(leaf.clone());
+        let grad = pullback(&leaf, output.clone(), cotangent);
+
+        (output.no_grad(), grad)
+    }
+
+    /// Computes the Jacobian of `func` at `primal`.
+    ///
+    /// Returns a matrix of shape `[num_outputs, num_inputs]` over the flattened output and input,
+    /// built row by row with one backward pass per output element.
+    ///
+    /// # Panics
+    ///
+    /// If the output of `func` doesn't depend on its input.
+    pub fn jacobian<const D2: usize, F>(func: F, primal: Self) -> Tensor<2>
+    where
+        F: Fn(Self) -> Tensor<D2>,
+    {
+        let primal = primal.no_grad();
+        let (device, dtype) = (primal.device(), primal.dtype());
+        let num_inputs = primal.shape().num_elements();
+        let num_outputs = func(lift(primal.clone())).shape().num_elements();
+
+        let rows = (0..num_outputs)
+            .map(|index| {
+                let leaf = leaf(primal.clone());
+                let output = func(leaf.clone()).reshape([num_outputs]);
+                let mut basis = vec![0.0; num_outputs];
+                basis[index] = 1.0;
+                let basis =
+                    Tensor::from_data(TensorData::new(basis, [num_outputs]), (&device, dtype));
+
+                pullback(&leaf, output, basis).reshape([num_inputs])
+            })
+            .collect::<Vec<_>>();
+
+        Tensor::stack(rows, 0)
+    }
+
+    /// Computes the Jacobian-vector product of `func` at `primal`.
+    ///
+    /// Returns the output of `func` and `J tangent`, its directional derivative along `tangent`,
+    /// with the shape of the output. The product is computed in forward mode: the tangent is
+    /// propagated alongside the primal through a single evaluation of `func`, so prefer it over
+    /// [vjp](Self::vjp) when the output is larger than the input.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (output, jvp) = Tensor::jvp(|x| x.clone() * x, primal, tangent);
+    /// // jvp = 2 * primal * tangent
+    /// ```
+    pub fn jvp<const D2: usize, F>(func: F, primal: Self, tangent: Self) -> (Tensor<D2>, Tensor<D2>)
+    where
+        F: FnOnce(Self) -> Tensor<D2>,
+    {
+        let output = func(lift(primal).with_tangent(lift(tangent)));
+        let jvp = match output.tangent() {
+            Some(tangent) => tangent.no_grad(),
+            None => output.clone().no_grad().zeros_like(),
+        };
+
+        (output.no_grad(), jvp)
+    }
+
+    /// Computes the Hessian-vector product of the scalar function `func` at `primal`.
+    ///
+    /// Returns the output of `func` and `H vector`, with the shape of the primal. The product is
+    /// exact: the directional derivative of `func` along `vector` is propagated in forward mode,
+    /// then differentiated with a single backward pass (reverse-over-forward).
+    pub fn hvp<F>(func: F, primal: Self, vector: Self) -> (Tensor<1>, Self)
+    where
+        F: FnOnce(Self) -> Tensor<1>,
+    {
+        let leaf = leaf(primal).with_tangent(lift(vector));
+        let output = func(leaf.clone());
+        let hvp = match output.tangent() {
+            Some(tangent) => pullback(&leaf, tangent.clone(), tangent.ones_like()),
+            None => leaf.clone().no_grad().zeros_like(),
+        };
+
+        (output.no_grad(), hvp)
+    }
+}
+
+/// Move `tensor` to the autodiff wrapper of its device, without tracking it.
+fn lift<const D: usize>(tensor: Tensor<D>) -> Tensor<D> {
+    Tensor::from_inner(tensor.no_grad())
+}
+
+/// Start a graph from `tensor`, on the autodiff wrapper of its device.
+fn leaf<const D: usize>(tensor: Tensor<D>) -> Tensor<D> {
+    lift(tensor).require_grad()
+}
+
+/// The gradient of `sum(output * cotangent)` with respect to `leaf`, off the autodiff graph.
+fn pullback<const D: usize, const D2: usize>(
+    leaf: &Tensor<D>,
+    output: Tensor<D2>,
+    cotangent: Tensor<D2>,
+) -> Tensor<D> {
+    let cotangent = lift(cotangent);
+    let grads = (output * cotangent).sum().backward();
+
+    leaf.grad(&grads)
+        .unwrap_or_else(|| leaf.clone().no_grad().zeros_like())
+}
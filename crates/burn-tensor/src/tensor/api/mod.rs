@@ -7,6 +7,8 @@ mod cartesian_grid;
 mod cast;
 mod float;
 mod fmod;
+#[cfg(feature = "autodiff")]
+mod functional;
 mod graph;
 mod int;
 mod numeric;
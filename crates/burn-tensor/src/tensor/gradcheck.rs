@@ -0,0 +1,702 @@
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use burn_std::{DType, Shape, TensorData};
+#[allow(unused_imports)]
+use num_traits::float::Float;
+
+use crate::{Bool, Device, Gradients, Int, Tensor};
+
+/// An input of a function whose gradients are checked.
+#[derive(Debug, Clone)]
+pub enum GradInput {
+    /// A float input, perturbed and differentiated in f64.
+    Float(TensorData),
+    /// An int input, e.g. indices, passed unchanged.
+    Int(TensorData),
+    /// A bool input, e.g. a mask, passed unchanged.
+    Bool(TensorData),
+}
+
+impl GradInput {
+    fn shape(&self) -> &Shape {
+        match self {
+            GradInput::Float(data) | GradInput::Int(data) | GradInput::Bool(data) => &data.shape,
+        }
+    }
+}
+
+impl<const D: usize> From<Tensor<D>> for GradInput {
+    fn from(tensor: Tensor<D>) -> Self {
+        GradInput::Float(tensor.into_data())
+    }
+}
+
+impl<const D: usize> From<Tensor<D, Int>> for GradInput {
+    fn from(tensor: Tensor<D, Int>) -> Self {
+        GradInput::Int(tensor.into_data())
+    }
+}
+
+impl<const D: usize> From<Tensor<D, Bool>> for GradInput {
+    fn from(tensor: Tensor<D, Bool>) -> Self {
+        GradInput::Bool(tensor.into_data())
+    }
+}
+
+/// The inputs given to a function whose gradients are checked, in the order of the
+/// [grad inputs](GradInput) they were created from.
+pub struct GradInputs {
+    tensors: Vec<InputTensor>,
+}
+
+enum InputTensor {
+    Float(Tensor<1>, Shape),
+    Int(Tensor<1, Int>, Shape),
+    Bool(Tensor<1, Bool>, Shape),
+}
+
+impl GradInputs {
+    /// Create the inputs on `device`, tracking the float inputs when it is an autodiff device.
+    ///
+    /// Every input is stored flat so that its gradient is the flattened gradient.
+    fn new(inputs: &[GradInput], values: &[Vec<f64>], device: &Device) -> Self {
+        let tensors = inputs
+            .iter()
+            .zip(values)
+            .map(|(input, values)| {
+                let shape = input.shape().clone();
+                let mut data = match input {
+                    GradInput::Float(_) => TensorData::new(values.clone(), [values.len()]),
+                    GradInput::Int(data) | GradInput::Bool(data) => data.clone(),
+                };
+                data.shape = Shape::new([shape.num_elements()]);
+
+                match input {
+                    GradInput::Float(_) => {
+                        let tensor = Tensor::from_data(data, (device, DType::F64));
+                        let tensor = if device.is_autodiff() {
+                            tensor.require_grad()
+                        } else {
+                            tensor
+                        };
+                        InputTensor::Float(tensor, shape)
+                    }
+                    GradInput::Int(_) => InputTensor::Int(Tensor::from_data(data, device), shape),
+                    GradInput::Bool(_) => InputTensor::Bool(Tensor::from_data(data, device), shape),
+                }
+            })
+            .collect();
+
+        Self { tensors }
+    }
+
+    /// Attach the flattened `tangents` to the float inputs.
+    fn with_tangents(mut self, tangents: &[Vec<f64>]) -> Self {
+        for (tensor, tangent) in self.tensors.iter_mut().zip(tangents) {
+            if let InputTensor::Float(tensor, _) = tensor {
+                let data = TensorData::new(tangent.clone(), [tangent.len()]);
+                let tangent = Tensor::from_data(data, (&tensor.device(), DType::F64));
+                *tensor = tensor.clone().with_tangent(tangent);
+            }
+        }
+        self
+    }
+
+    /// The float input at `index`.
+    ///
+    /// # Panics
+    ///
+    /// If the input at `index` is not a float input, or its rank is not `D`.
+    pub fn float<const D: usize>(&self, index: usize) -> Tensor<D> {
+        match &self.tensors[index] {
+            InputTensor::Float(tensor, shape) => tensor.clone().reshape(shape.clone()),
+            _ => panic!("Input {index} is not a float input"),
+        }
+    }
+
+    /// The int input at `index`.
+    ///
+    /// # Panics
+    ///
+    /// If the input at `index` is not an int input, or its rank is not `D`.
+    pub fn int<const D: usize>(&self, index: usize) -> Tensor<D, Int> {
+        match &self.tensors[index] {
+            InputTensor::Int(tensor, shape) => tensor.clone().reshape(shape.clone()),
+            _ => panic!("Input {index} is not an int input"),
+        }
+    }
+
+    /// The bool input at `index`.
+    ///
+    /// # Panics
+    ///
+    /// If the input at `index` is not a bool input, or its rank is not `D`.
+    pub fn bool<const D: usize>(&self, index: usize) -> Tensor<D, Bool> {
+        match &self.tensors[index] {
+            InputTensor::Bool(tensor, shape) => tensor.clone().reshape(shape.clone()),
+            _ => panic!("Input {index} is not a bool input"),
+        }
+    }
+
+    /// The flattened gradient of every input, empty for int and bool inputs.
+    fn gradients(&self, grads: &Gradients) -> Vec<Vec<f64>> {
+        self.tensors
+            .iter()
+            .map(|tensor| match tensor {
+                InputTensor::Float(tensor, shape) => match tensor.grad(grads) {
+                    Some(grad) => grad.into_data().iter::<f64>().collect(),
+                    None => vec![0.0; shape.num_elements()],
+                },
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+}
+
+/// The float outputs of a function whose gradients are checked.
+///
+/// Int and bool outputs, e.g. the indices of an `argmax`, are not differentiable and are left
+/// out.
+#[derive(Default)]
+pub struct GradOutputs {
+    tensors: Vec<Tensor<1>>,
+}
+
+impl GradOutputs {
+    /// Create an empty list of outputs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an output.
+    pub fn with<const D: usize>(mut self, output: Tensor<D>) -> Self {
+        let num_elements = output.shape().num_elements();
+        self.tensors.push(output.reshape([num_elements]));
+        self
+    }
+}
+
+impl<const D: usize> From<Tensor<D>> for GradOutputs {
+    fn from(output: Tensor<D>) -> Self {
+        Self::new().with(output)
+    }
+}
+
+/// An element whose gradient differs from its finite-difference estimate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradMismatch {
+    /// The index of the input.
+    pub input: usize,
+    /// The index of the element in the input.
+    pub index: Vec<usize>,
+    /// The gradient computed by the backward pass.
+    pub analytic: f64,
+    /// The gradient estimated by finite differences.
+    pub numerical: f64,
+}
+
+impl GradMismatch {
+    /// The absolute difference between the analytic and numerical gradients.
+    pub fn error(&self) -> f64 {
+        (self.analytic - self.numerical).abs()
+    }
+}
+
+/// The outcome of a failed gradient check.
+#[derive(Debug, Clone)]
+pub struct GradCheckReport {
+    /// How many elements were compared.
+    pub num_checked: usize,
+    /// How many elements differ beyond the tolerance.
+    pub num_mismatched: usize,
+    /// The elements differing the most, worst first.
+    pub worst: Vec<GradMismatch>,
+    /// The relative tolerance of the check.
+    pub rtol: f64,
+    /// The absolute tolerance of the check.
+    pub atol: f64,
+}
+
+impl fmt::Display for GradCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} of {} gradient elements differ from their finite-difference estimate \
+             (rtol = {:e}, atol = {:e}). Worst elements:",
+            self.num_mismatched, self.num_checked, self.rtol, self.atol
+        )?;
+        for mismatch in self.worst.iter() {
+            writeln!(
+                f,
+                "  input {} at {:?}: analytic = {:e}, numerical = {:e}, error = {:e}",
+                mismatch.input,
+                mismatch.index,
+                mismatch.analytic,
+                mismatch.numerical,
+                mismatch.error()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The error returned by a gradient check.
+#[derive(Debug, Clone)]
+pub enum GradCheckError {
+    /// The device does not support f64, which finite differences need to be accurate.
+    UnsupportedDevice,
+    /// The function has no float input to differentiate.
+    NoFloatInput,
+    /// The function has no output to differentiate.
+    NoOutput,
+    /// The analytic gradients do not match the finite differences.
+    Mismatch(GradCheckReport),
+}
+
+impl fmt::Display for GradCheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GradCheckError::UnsupportedDevice => {
+                write!(
+                    f,
+                    "The device does not support f64, required by gradient checks"
+                )
+            }
+            GradCheckError::NoFloatInput => write!(f, "The function has no float input"),
+            GradCheckError::NoOutput => write!(f, "The function has no output"),
+            GradCheckError::Mismatch(report) => report.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for GradCheckError {}
+
+/// Check the gradients of `func` against finite differences with the default
+/// [configuration](GradCheck).
+///
+/// See [GradCheck::check].
+pub fn gradcheck<F, O>(device: &Device, inputs: &[GradInput], func: F) -> Result<(), GradCheckError>
+where
+    F: Fn(&GradInputs) -> O,
+    O: Into<GradOutputs>,
+{
+    GradCheck::default().check(device, inputs, func)
+}
+
+/// Check the second-order derivatives of `func` against finite differences of its gradients
+/// with the default [configuration](GradCheck).
+///
+/// See [GradCheck::check_second_order].
+pub fn gradgradcheck<F, O>(
+    device: &Device,
+    inputs: &[GradInput],
+    func: F,
+) -> Result<(), GradCheckError>
+where
+    F: Fn(&GradInputs) -> O,
+    O: Into<GradOutputs>,
+{
+    GradCheck::default().check_second_order(device, inputs, func)
+}
+
+/// Validates backward passes against finite differences.
+///
+/// Every output `y_k` of the checked function is reduced against a random cotangent `v_k` into
+/// the scalar `L = Σ_k <y_k, v_k>`, so a single backward pass gives the vector-Jacobian product
+/// `∂L/∂x` for every float input. Each of its elements is then compared with the central
+/// difference `(L(x + eps) - L(x - eps)) / 2 eps`, computing in f64 to keep the estimate
+/// accurate.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use burn_tensor::gradcheck::{GradCheck, GradInput, GradOutputs};
+///
+/// let inputs = [
+///     GradInput::from(Tensor::<2>::random([3, 4], Distribution::Default, &device)),
+///     GradInput::from(Tensor::<2, Int>::from_data([[0, 3], [1, 1], [2, 0]], &device)),
+/// ];
+///
+/// GradCheck::default()
+///     .with_tolerance(1e-4, 1e-6)
+///     .check(&device, &inputs, |inputs| {
+///         let (values, indices) = (inputs.float::<2>(0), inputs.int::<2>(1));
+///         GradOutputs::new()
+///             .with(values.clone().gather(1, indices))
+///             .with(values.exp().sum_dim(1))
+///     })
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct GradCheck {
+    eps: f64,
+    rtol: f64,
+    atol: f64,
+    seed: u64,
+    max_reported: usize,
+}
+
+impl Default for GradCheck {
+    fn default() -> Self {
+        Self {
+            eps: 1e-6,
+            rtol: 1e-3,
+            atol: 1e-5,
+            seed: 0,
+            max_reported: 8,
+        }
+    }
+}
+
+impl GradCheck {
+    /// Set the perturbation of the finite differences.
+    pub fn with_eps(mut self, eps: f64) -> Self {
+        self.eps = eps;
+        self
+    }
+
+    /// Set the tolerance: an element fails when
+    /// `|analytic - numerical| > atol + rtol * |numerical|`.
+    pub fn with_tolerance(mut self, rtol: f64, atol: f64) -> Self {
+        self.rtol = rtol;
+        self.atol = atol;
+        self
+    }
+
+    /// Set the seed of the random cotangents and directions.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set how many of the worst elements are reported.
+    pub fn with_max_reported(mut self, max_reported: usize) -> Self {
+        self.max_reported = max_reported;
+        self
+    }
+
+    /// Check the gradients of `func` with respect to its float `inputs`.
+    ///
+    /// The function is called with the inputs created on `device`, or its autodiff wrapper,
+    /// and returns its float outputs. It should compute in the dtype of its float inputs, f64.
+    ///
+    /// # Errors
+    ///
+    /// - [UnsupportedDevice](GradCheckError::UnsupportedDevice) when the device does not
+    ///   support f64.
+    /// - [Mismatch](GradCheckError::Mismatch) with the worst elements when the gradients do
+    ///   not match.
+    pub fn check<F, O>(
+        &self,
+        device: &Device,
+        inputs: &[GradInput],
+        func: F,
+    ) -> Result<(), GradCheckError>
+    where
+        F: Fn(&GradInputs) -> O,
+        O: Into<GradOutputs>,
+    {
+        let checker = Checker::new(self, device, inputs, func)?;
+        let mut values = checker.values.clone();
+        let analytic = checker.gradients(&values);
+        let numerical = self.differences(&mut values, |values| checker.loss(values));
+
+        self.compare(inputs, &analytic, &numerical)
+    }
+
+    /// Check the second-order derivatives of `func` with respect to its float `inputs`.
+    ///
+    /// For a random direction `w`, the Hessian-vector product `H w` of `L` is computed with
+    /// second-order autodiff: the directional derivative `<∂L/∂x, w>` is propagated as a
+    /// forward-mode tangent, then differentiated with a backward pass. It is compared with the
+    /// central difference of the analytic gradients along the direction,
+    /// `(∂L/∂x(x + eps w) - ∂L/∂x(x - eps w)) / 2 eps`, which validates the tangents and the
+    /// backward passes of the operations they go through.
+    ///
+    /// # Errors
+    ///
+    /// See [check](Self::check).
+    pub fn check_second_order<F, O>(
+        &self,
+        device: &Device,
+        inputs: &[GradInput],
+        func: F,
+    ) -> Result<(), GradCheckError>
+    where
+        F: Fn(&GradInputs) -> O,
+        O: Into<GradOutputs>,
+    {
+        let checker = Checker::new(self, device, inputs, func)?;
+        let direction = checker
+            .values
+            .iter()
+            .enumerate()
+            .map(|(input, values)| {
+                random_vector(self.seed, checker.cotangents.len() + input, values.len())
+            })
+            .collect::<Vec<_>>();
+
+        let analytic = checker.hessian_vector(&checker.values, &direction);
+        let plus = checker.gradients(&shift(&checker.values, &direction, self.eps));
+        let minus = checker.gradients(&shift(&checker.values, &direction, -self.eps));
+        let numerical = plus
+            .iter()
+            .zip(minus.iter())
+            .map(|(plus, minus)| {
+                plus.iter()
+                    .zip(minus)
+                    .map(|(plus, minus)| (plus - minus) / (2.0 * self.eps))
+                    .collect()
+            })
+            .collect::<Vec<Vec<f64>>>();
+
+        self.compare(inputs, &analytic, &numerical)
+    }
+
+    /// Compare the `analytic` derivatives with their `numerical` estimates.
+    fn compare(
+        &self,
+        inputs: &[GradInput],
+        analytic: &[Vec<f64>],
+        numerical: &[Vec<f64>],
+    ) -> Result<(), GradCheckError> {
+        let mut num_checked = 0;
+        let mut mismatches = Vec::new();
+
+        for (input, (analytic, numerical)) in analytic.iter().zip(numerical.iter()).enumerate() {
+            for (index, (analytic, numerical)) in analytic.iter().zip(numerical.iter()).enumerate()
+            {
+                num_checked += 1;
+                let error = (analytic - numerical).abs();
+                // Written so that NaNs fail the check.
+                if !(error <= self.atol + self.rtol * numerical.abs()) {
+                    mismatches.push(GradMismatch {
+                        input,
+                        index: unravel(index, inputs[input].shape()),
+                        analytic: *analytic,
+                        numerical: *numerical,
+                    });
+                }
+            }
+        }
+
+        if mismatches.is_empty() {
+            return Ok(());
+        }
+
+        let num_mismatched = mismatches.len();
+        mismatches.sort_by(|a, b| b.error().total_cmp(&a.error()));
+        mismatches.truncate(self.max_reported);
+
+        Err(GradCheckError::Mismatch(GradCheckReport {
+            num_checked,
+            num_mismatched,
+            worst: mismatches,
+            rtol: self.rtol,
+            atol: self.atol,
+        }))
+    }
+
+    /// The central differences of `func` for every element of the float inputs.
+    fn differences(
+        &self,
+        values: &mut [Vec<f64>],
+        func: impl Fn(&[Vec<f64>]) -> f64,
+    ) -> Vec<Vec<f64>> {
+        let eps = self.eps;
+
+        (0..values.len())
+            .map(|input| {
+                (0..values[input].len())
+                    .map(|index| {
+                        let value = values[input][index];
+                        values[input][index] = value + eps;
+                        let plus = func(values);
+                        values[input][index] = value - eps;
+                        let minus = func(values);
+                        values[input][index] = value;
+
+                        (plus - minus) / (2.0 * eps)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// A function under check, with the values of its inputs and the cotangents of its outputs.
+struct Checker<'a, F> {
+    func: F,
+    inputs: &'a [GradInput],
+    /// The flattened float inputs, empty for int and bool inputs.
+    values: Vec<Vec<f64>>,
+    cotangents: Vec<Vec<f64>>,
+    /// The device the backward passes run on.
+    autodiff: Device,
+    /// The device the function is evaluated on without tracking.
+    device: Device,
+}
+
+impl<'a, F, O> Checker<'a, F>
+where
+    F: Fn(&GradInputs) -> O,
+    O: Into<GradOutputs>,
+{
+    fn new(
+        config: &GradCheck,
+        device: &Device,
+        inputs: &'a [GradInput],
+        func: F,
+    ) -> Result<Self, GradCheckError> {
+        if !device.supports_dtype(DType::F64) {
+            return Err(GradCheckError::UnsupportedDevice);
+        }
+
+        if !inputs
+            .iter()
+            .any(|input| matches!(input, GradInput::Float(_)))
+        {
+            return Err(GradCheckError::NoFloatInput);
+        }
+
+        let values = inputs
+            .iter()
+            .map(|input| match input {
+                GradInput::Float(data) => data.iter::<f64>().collect(),
+                GradInput::Int(_) | GradInput::Bool(_) => Vec::new(),
+            })
+            .collect::<Vec<Vec<f64>>>();
+
+        let autodiff = if device.is_autodiff() {
+            device.clone()
+        } else {
+            device.clone().autodiff()
+        };
+        let device = device.clone().inner();
+
+        let outputs: GradOutputs = func(&GradInputs::new(inputs, &values, &device)).into();
+        if outputs.tensors.is_empty() {
+            return Err(GradCheckError::NoOutput);
+        }
+        let cotangents = outputs
+            .tensors
+            .iter()
+            .enumerate()
+            .map(|(output, tensor)| {
+                random_vector(config.seed, output, tensor.shape().num_elements())
+            })
+            .collect();
+
+        Ok(Self {
+            func,
+            inputs,
+            values,
+            cotangents,
+            autodiff,
+            device,
+        })
+    }
+
+    /// The reduced output `L = Σ_k <y_k, v_k>`, evaluated without tracking.
+    fn loss(&self, values: &[Vec<f64>]) -> f64 {
+        let outputs: GradOutputs =
+            (self.func)(&GradInputs::new(self.inputs, values, &self.device)).into();
+
+        outputs
+            .tensors
+            .into_iter()
+            .zip(self.cotangents.iter())
+            .map(|(output, cotangent)| {
+                let output = output.into_data();
+                output
+                    .iter::<f64>()
+                    .zip(cotangent)
+                    .map(|(y, v)| y * v)
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+
+    /// The gradients `∂L/∂x` of every input computed by the backward pass.
+    fn gradients(&self, values: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let inputs = GradInputs::new(self.inputs, values, &self.autodiff);
+
+        inputs.gradients(&self.tracked_loss(&inputs).backward())
+    }
+
+    /// The product `H w` of the Hessian of `L` with `direction`, computed by the backward pass of
+    /// the forward-mode tangent of `L` along `direction`.
+    fn hessian_vector(&self, values: &[Vec<f64>], direction: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let inputs = GradInputs::new(self.inputs, values, &self.autodiff).with_tangents(direction);
+
+        match self.tracked_loss(&inputs).tangent() {
+            Some(tangent) => inputs.gradients(&tangent.backward()),
+            // The gradients don't depend on the inputs.
+            None => values
+                .iter()
+                .map(|values| vec![0.0; values.len()])
+                .collect(),
+        }
+    }
+
+    /// The reduced output `L = Σ_k <y_k, v_k>`, evaluated on the autodiff device.
+    fn tracked_loss(&self, inputs: &GradInputs) -> Tensor<1> {
+        let outputs: GradOutputs = (self.func)(inputs).into();
+
+        outputs
+            .tensors
+            .into_iter()
+            .zip(self.cotangents.iter())
+            .map(|(output, cotangent)| {
+                let cotangent = TensorData::new(cotangent.clone(), [cotangent.len()]);
+                let cotangent = Tensor::from_data(cotangent, (&self.autodiff, output.dtype()));
+                (output * cotangent).sum()
+            })
+            .reduce(|a, b| a + b)
+            .expect("Should have at least one output")
+    }
+}
+
+/// `values + scale * direction`.
+fn shift(values: &[Vec<f64>], direction: &[Vec<f64>], scale: f64) -> Vec<Vec<f64>> {
+    values
+        .iter()
+        .zip(direction)
+        .map(|(values, direction)| {
+            values
+                .iter()
+                .zip(direction)
+                .map(|(value, direction)| value + scale * direction)
+                .collect()
+        })
+        .collect()
+}
+
+/// The multi-dimensional index of the element at `index` in a contiguous tensor of `shape`.
+fn unravel(mut index: usize, shape: &Shape) -> Vec<usize> {
+    let mut indices = vec![0; shape.num_dims()];
+    for (dim, size) in shape.as_slice().iter().enumerate().rev() {
+        indices[dim] = index % size;
+        index /= size;
+    }
+    indices
+}
+
+/// A deterministic vector of values in `[-1, 1)`, drawn with SplitMix64 from the seed and
+/// stream.
+fn random_vector(seed: u64, stream: usize, len: usize) -> Vec<f64> {
+    let mut state = seed ^ (stream as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+
+    (0..len)
+        .map(|_| {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^= z >> 31;
+
+            (z >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+        })
+        .collect()
+}
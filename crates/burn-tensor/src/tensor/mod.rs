@@ -25,6 +25,10 @@ pub mod container {
 /// The grid module.
 pub mod grid;
 
+/// Checks of gradients against finite differences.
+#[cfg(feature = "autodiff")]
+pub mod gradcheck;
+
 /// The linalg module.
 pub mod linalg;
 
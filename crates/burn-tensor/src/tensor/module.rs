@@ -76,6 +76,53 @@ pub fn ctc_loss(
     )))
 }
 
+/// Computes a [selective state-space scan](burn_backend::ops::ModuleOps::selective_scan).
+///
+/// # Arguments
+///
+/// * `x` - Input of shape `[batch, seq_len, d_inner]`
+/// * `delta` - Positive step sizes of shape `[batch, seq_len, d_inner]`
+/// * `a` - State matrix of shape `[d_inner, d_state]`
+/// * `b` - Input projection of shape `[batch, seq_len, d_state]`
+/// * `c` - Output projection of shape `[batch, seq_len, d_state]`
+///
+/// # Returns
+///
+/// Output of shape `[batch, seq_len, d_inner]`
+pub fn selective_scan(
+    x: Tensor<3>,
+    delta: Tensor<3>,
+    a: Tensor<2>,
+    b: Tensor<3>,
+    c: Tensor<3>,
+) -> Tensor<3> {
+    let [batch_size, seq_len, d_inner] = x.dims();
+    let [a_channels, d_state] = a.dims();
+    assert_eq!(
+        delta.dims(),
+        [batch_size, seq_len, d_inner],
+        "invalid selective scan delta shape"
+    );
+    assert_eq!(a_channels, d_inner, "invalid selective scan A shape");
+    assert_eq!(
+        b.dims(),
+        [batch_size, seq_len, d_state],
+        "invalid selective scan B shape"
+    );
+    assert_eq!(
+        c.dims(),
+        [batch_size, seq_len, d_state],
+        "invalid selective scan C shape"
+    );
+    Tensor::new(BridgeTensor::float(Dispatch::selective_scan(
+        x.primitive.into_float(),
+        delta.primitive.into_float(),
+        a.primitive.into_float(),
+        b.primitive.into_float(),
+        c.primitive.into_float(),
+    )))
+}
+
 /// Applies the [embedding module](burn_backend::ops::ModuleOps::embedding).
 pub fn embedding(weights: Tensor<2>, indices: Tensor<2, Int>) -> Tensor<3> {
     Tensor::new(BridgeTensor::float(Dispatch::embedding(
@@ -588,7 +635,8 @@ fn linear_impl(
 
 /// Computes scaled dot-product attention: softmax(QKᵗ * scale) · V,
 /// where scale defaults to 1/sqrt(head_dim) (configurable via `options.scale`).
-/// Optionally applies masking, additive bias, causal masking, and softcap.
+/// Optionally applies masking, additive bias, causal masking, softcap, sliding-window masking
+/// and ALiBi. See [packed_attention] for variable-length sequences packed into one row.
 ///
 /// # Arguments
 /// - `query`: Query tensor of shape `[batch_size, num_heads, seq_len_q, head_dim]`
@@ -598,7 +646,8 @@ fn linear_impl(
 ///   where `true` indicates positions to mask (i.e. set to -inf before softmax).
 /// - `attn_bias`: Optional float tensor of shape `[batch_size, num_heads, seq_len_q, seq_len_k]`
 ///   added to the attention scores before softmax (e.g. ALiBi, relative position biases).
-/// - `options`: Additional attention options (custom scale, softcap, causal masking).
+/// - `options`: Additional attention options (custom scale, softcap, causal masking, sliding
+///   window, ALiBi), see [AttentionModuleOptions].
 ///
 /// # Returns
 /// A tensor of shape `[batch_size, num_heads, seq_len_q, val_dim]`
@@ -614,6 +663,58 @@ pub fn attention(
     mask: Option<Tensor<4, Bool>>,
     attn_bias: Option<Tensor<4>>,
     options: AttentionModuleOptions,
+) -> Tensor<4> {
+    attention_impl(query, key, value, mask, attn_bias, None, options)
+}
+
+/// Computes [attention] over variable-length sequences packed along the sequence dimension.
+///
+/// Each position only attends to the positions of its own sequence, as if every sequence had
+/// its own batch row, and the causal mask, sliding window and ALiBi distances restart at each
+/// sequence start.
+///
+/// # Arguments
+/// - `seq_offsets`: Cumulative sequence offsets of shape `[num_sequences + 1]`, from `0` to
+///   `seq_len`, shared by every batch row and head. The query and key must have the same
+///   sequence length.
+///
+/// The other arguments and the output are the same as for [attention].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Two documents of 5 and 7 tokens packed into one row of 12.
+/// let offsets = Tensor::<1, Int>::from_ints([0, 5, 12], &device);
+/// let output = packed_attention(query, key, value, offsets, None, None, options);
+/// ```
+pub fn packed_attention(
+    query: Tensor<4>,
+    key: Tensor<4>,
+    value: Tensor<4>,
+    seq_offsets: Tensor<1, Int>,
+    mask: Option<Tensor<4, Bool>>,
+    attn_bias: Option<Tensor<4>>,
+    options: AttentionModuleOptions,
+) -> Tensor<4> {
+    attention_impl(
+        query,
+        key,
+        value,
+        mask,
+        attn_bias,
+        Some(seq_offsets),
+        options,
+    )
+}
+
+fn attention_impl(
+    query: Tensor<4>,
+    key: Tensor<4>,
+    value: Tensor<4>,
+    mask: Option<Tensor<4, Bool>>,
+    attn_bias: Option<Tensor<4>>,
+    seq_offsets: Option<Tensor<1, Int>>,
+    options: AttentionModuleOptions,
 ) -> Tensor<4> {
     Tensor::new(BridgeTensor::float(Dispatch::attention(
         query.primitive.into_float(),
@@ -621,6 +722,7 @@ pub fn attention(
         value.primitive.into_float(),
         mask.map(|mask| mask.primitive.into()),
         attn_bias.map(|bias| bias.primitive.into_float()),
+        seq_offsets.map(|offsets| offsets.primitive.into()),
         options,
     )))
 }
@@ -641,11 +743,31 @@ pub fn attention_fallback(
             value.primitive.into_float(),
             mask.map(|mask| mask.primitive.into()),
             attn_bias.map(|bias| bias.primitive.into_float()),
+            None,
             options,
         ),
     ))
 }
 
+/// Exports the default selective scan to test backend's selective scan against.
+pub fn selective_scan_default(
+    x: Tensor<3>,
+    delta: Tensor<3>,
+    a: Tensor<2>,
+    b: Tensor<3>,
+    c: Tensor<3>,
+) -> Tensor<3> {
+    Tensor::new(BridgeTensor::float(
+        burn_backend::ops::selective_scan::selective_scan_default::<Dispatch>(
+            x.primitive.into_float(),
+            delta.primitive.into_float(),
+            a.primitive.into_float(),
+            b.primitive.into_float(),
+            c.primitive.into_float(),
+        ),
+    ))
+}
+
 /// Calculate the [2D convolution](burn_backend::ops::ModuleOps::conv2d) backward pass, returning the gradient for `weight`.
 pub fn conv2d_weight_backward(
     x: Tensor<4>,
@@ -1,13 +1,29 @@
 use std::path::{Path, PathBuf};
+#[cfg(feature = "delta-checkpoint")]
+use std::sync::Mutex;
+
+#[cfg(feature = "delta-checkpoint")]
+use burn_core::store::RecordError;
+#[cfg(feature = "delta-checkpoint")]
+use burn_store::burn_pack::{Error as PackError, Reader, Writer};
+#[cfg(feature = "delta-checkpoint")]
+use burn_store::delta::{self, DeltaBase};
 
 use super::{Checkpoint, Checkpointer, CheckpointerError};
 
 /// The file checkpointer.
 ///
 /// Saves each record as a [burnpack](burn_core::store) file in the given directory.
+///
+/// With the `delta-checkpoint` feature, in [delta mode](Self::with_delta), each file only
+/// holds the parameters that changed since a base snapshot. Restoring reads delta checkpoints
+/// through to their base in either mode.
 pub struct FileCheckpointer {
     directory: PathBuf,
     name: String,
+    /// The base deltas are computed against, once the first save has written it.
+    #[cfg(feature = "delta-checkpoint")]
+    delta: Option<Mutex<Option<DeltaBase>>>,
 }
 
 impl FileCheckpointer {
@@ -24,12 +40,103 @@ impl FileCheckpointer {
         Self {
             directory: directory.to_path_buf(),
             name: name.to_string(),
+            #[cfg(feature = "delta-checkpoint")]
+            delta: None,
         }
     }
 
+    /// Save each checkpoint as a [delta](burn_store::delta) against a base snapshot.
+    ///
+    /// The first save writes the full record to `{name}-base.bpk`. A base a previous run left
+    /// there is reused only if it was saved from the same model (the same parameters, with the
+    /// same [ids](burn_core::module::ParamId), dtypes and shapes), as when resuming; otherwise
+    /// it is replaced, and the deltas written against it no longer restore. Every checkpoint
+    /// then only holds the parameters whose
+    /// [`ParamId`](burn_core::module::ParamId) or content differ from the base: when
+    /// fine-tuning with [`Lora`](burn_core::module::Lora), the adapters rather than the
+    /// whole frozen model.
+    ///
+    /// The base is never deleted by the checkpointing strategy. Use [`squash`](Self::squash)
+    /// to turn a checkpoint into a standalone file.
+    #[cfg(feature = "delta-checkpoint")]
+    pub fn with_delta(mut self) -> Self {
+        self.delta = Some(Mutex::new(None));
+        self
+    }
+
+    /// Rewrite the delta checkpoint saved at `epoch` as a standalone file, in place.
+    ///
+    /// Does nothing for a checkpoint that is already standalone.
+    #[cfg(feature = "delta-checkpoint")]
+    pub fn squash(&self, epoch: usize) -> Result<(), CheckpointerError> {
+        let file_path = self.path_for_epoch(epoch);
+        if delta::is_delta(&file_path).map_err(record_error)? {
+            log::info!("Squashing checkpoint {}", file_path.display());
+            delta::squash_delta(&file_path, &file_path).map_err(record_error)?;
+        }
+
+        Ok(())
+    }
+
     fn path_for_epoch(&self, epoch: usize) -> PathBuf {
         self.directory.join(format!("{}-{}.bpk", self.name, epoch))
     }
+
+    #[cfg(feature = "delta-checkpoint")]
+    fn base_path(&self) -> PathBuf {
+        self.directory.join(format!("{}-base.bpk", self.name))
+    }
+
+    #[cfg(feature = "delta-checkpoint")]
+    fn save_delta<R: Checkpoint>(
+        &self,
+        base: &Mutex<Option<DeltaBase>>,
+        file_path: &Path,
+        record: R,
+    ) -> Result<(), CheckpointerError> {
+        let bytes = record
+            .checkpoint_into_bytes()
+            .map_err(CheckpointerError::Record)?;
+        let tensors = Reader::from_bytes(bytes)
+            .and_then(Reader::into_tensors)
+            .map_err(record_error)?;
+
+        let mut base = base.lock().unwrap();
+        if base.is_none() {
+            let base_path = self.base_path();
+            let existing = match base_path.exists() {
+                true => Some(DeltaBase::open(&base_path).map_err(record_error)?),
+                false => None,
+            };
+            *base = match existing {
+                Some(existing) if existing.is_base_of(&tensors) => Some(existing),
+                existing => {
+                    if existing.is_some() {
+                        log::warn!(
+                            "Replacing checkpoint base {}, saved from another model",
+                            base_path.display()
+                        );
+                    }
+                    log::trace!("Saving checkpoint base to {}", base_path.display());
+                    Writer::new(tensors.clone())
+                        .with_checksums(true)
+                        .write_to_file_atomic(&base_path)
+                        .map_err(record_error)?;
+                    Some(DeltaBase::open(&base_path).map_err(record_error)?)
+                }
+            };
+        }
+
+        let base = base.as_ref().expect("base initialized above");
+        base.diff(tensors, file_path)
+            .and_then(|writer| writer.with_checksums(true).write_to_file_atomic(file_path))
+            .map_err(record_error)
+    }
+}
+
+#[cfg(feature = "delta-checkpoint")]
+fn record_error(err: PackError) -> CheckpointerError {
+    CheckpointerError::Record(RecordError::from(err))
 }
 
 impl<R> Checkpointer<R> for FileCheckpointer
@@ -40,6 +147,11 @@ where
         let file_path = self.path_for_epoch(epoch);
         log::trace!("Saving checkpoint {} to {}", epoch, file_path.display());
 
+        #[cfg(feature = "delta-checkpoint")]
+        if let Some(base) = &self.delta {
+            return self.save_delta(base, &file_path, record);
+        }
+
         record.save(file_path)
     }
 
@@ -51,6 +163,15 @@ where
             file_path.display()
         );
 
+        // Stateless records save nothing, so there may be no file to inspect.
+        #[cfg(feature = "delta-checkpoint")]
+        if file_path.exists() && delta::is_delta(&file_path).map_err(record_error)? {
+            let bytes = Writer::new(delta::read_delta(&file_path).map_err(record_error)?)
+                .into_bytes()
+                .map_err(record_error)?;
+            return R::checkpoint_from_bytes(bytes).map_err(CheckpointerError::Record);
+        }
+
         R::load(file_path)
     }
 
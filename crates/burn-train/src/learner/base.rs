@@ -6,6 +6,7 @@ use crate::{
     CloneEarlyStoppingStrategy, LearnerModel, TrainOutput, TrainStep, TrainingModelInput,
     TrainingModelOutput,
 };
+use burn_core::module::GradualPruning;
 use burn_core::store::ModuleRecord;
 use burn_core::tensor::Device;
 use burn_optim::lr_scheduler::LrSchedulerRecord;
@@ -21,6 +22,7 @@ pub struct Learner<M: LearnerModel> {
     optim: ModuleOptimizer,
     lr_scheduler: ModuleLrScheduler,
     lr_module: ModuleLearningRate,
+    pruning: Option<GradualPruning>,
 }
 
 impl<M: LearnerModel> Clone for Learner<M> {
@@ -30,6 +32,7 @@ impl<M: LearnerModel> Clone for Learner<M> {
             optim: self.optim.clone(),
             lr_scheduler: self.lr_scheduler.clone(),
             lr_module: self.lr_module.clone(),
+            pruning: self.pruning.clone(),
         }
     }
 }
@@ -46,8 +49,16 @@ impl<M: LearnerModel> Learner<M> {
             optim,
             lr_scheduler: lr_scheduler.into(),
             lr_module: 0.0.into(),
+            pruning: None,
         }
     }
+
+    /// Prune the model gradually during training, updating the masks after the optimizer steps
+    /// scheduled by the [gradual pruning](GradualPruning).
+    pub fn with_pruning(mut self, pruning: GradualPruning) -> Self {
+        self.pruning = Some(pruning);
+        self
+    }
 }
 
 impl<M: LearnerModel> Learner<M> {
@@ -95,6 +106,7 @@ impl<M: LearnerModel> Learner<M> {
         self.model = self
             .model()
             .optimize(&mut self.optim, self.lr_module.clone(), grads);
+        self.pruning_step();
     }
 
     /// Optimize the current module with the provided gradients and learning rate.
@@ -108,6 +120,13 @@ impl<M: LearnerModel> Learner<M> {
         self.model = self
             .model()
             .optimize_multi(&mut self.optim, self.lr_module.clone(), grads);
+        self.pruning_step();
+    }
+
+    fn pruning_step(&mut self) {
+        if let Some(pruning) = &mut self.pruning {
+            self.model = pruning.step(self.model());
+        }
     }
 
     /// Load the module state from a [record](ModuleRecord).
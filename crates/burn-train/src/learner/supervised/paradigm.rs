@@ -318,6 +318,26 @@ impl<M: LearnerModel> SupervisedTraining<M> {
         self
     }
 
+    /// Like [`with_default_checkpointers`](Self::with_default_checkpointers), but the
+    /// [model](LearnerModel) is saved in [delta mode](FileCheckpointer::with_delta): each
+    /// checkpoint only holds the parameters that changed since the first one, which is most of
+    /// the savings when fine-tuning a mostly frozen model.
+    #[cfg(feature = "delta-checkpoint")]
+    pub fn with_delta_checkpointers(mut self) -> Self {
+        let checkpoint_dir = self.directory.join("checkpoint");
+        let checkpointer_model = FileCheckpointer::new(&checkpoint_dir, "model").with_delta();
+        let checkpointer_optimizer = FileCheckpointer::new(&checkpoint_dir, "optim");
+        let checkpointer_scheduler = FileCheckpointer::new(&checkpoint_dir, "scheduler");
+
+        self.checkpointers = Some((
+            AsyncCheckpointer::new(checkpointer_model),
+            AsyncCheckpointer::new(checkpointer_optimizer),
+            AsyncCheckpointer::new(checkpointer_scheduler),
+        ));
+
+        self
+    }
+
     /// Register your own checkpointers that will save the [optimizer](burn_optim::ModuleOptimizer), the
     /// [model](LearnerModel) and the [learning rate scheduler](burn_optim::lr_scheduler::module_lr_scheduler::ModuleLrScheduler) to separate burnpack files.
     pub fn with_custom_checkpointers<CM, CO, CL>(
@@ -20,6 +20,9 @@ pub mod logger;
 /// The metric module.
 pub mod metric;
 
+/// The hyperparameter sweep module.
+pub mod sweep;
+
 pub use metric::processor::*;
 
 mod learner;
@@ -0,0 +1,304 @@
+use burn_core::config::Config;
+use burn_core::tensor::Device;
+use rand::{SeedableRng, rngs::StdRng};
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use super::monitor::{Objective, SweepState};
+use super::space::apply;
+use super::strategy::Rungs;
+use super::{
+    SearchSpace, SearchStrategy, SweepError, SweepSummary, TrialMonitor, TrialParams, TrialStatus,
+    TrialSummary,
+};
+use crate::metric::store::{Direction, Split};
+use crate::metric::{LossMetric, Metric};
+use crate::{Interrupter, LearnerModel, SupervisedTraining};
+
+/// A hyperparameter sweep: trains one model per point of a [search space](SearchSpace) over
+/// the fields of a [`Config`], and records the results in a directory.
+///
+/// Each trial gets its own `trial-NNN` directory holding its `config.json` and the searched
+/// values in `params.json`, to be used as the trial's training directory. Once every trial
+/// ran, a table of the results is written to `summary.md`.
+///
+/// Trials are run on the given devices, one trial per device at a time, and can be pruned
+/// early by the [search strategy](SearchStrategy) through their [`Interrupter`].
+pub struct Sweep<C> {
+    directory: PathBuf,
+    base: C,
+    space: SearchSpace,
+    strategy: SearchStrategy,
+    objective: Objective,
+    devices: Vec<Device>,
+    seed: u64,
+}
+
+impl<C: Config + Clone + Send + Sync> Sweep<C> {
+    /// Create a grid sweep over `space`, minimizing the validation loss on the default device.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The sweep directory.
+    /// * `base` - The config of every trial, before the searched fields are replaced.
+    /// * `space` - The searched fields.
+    pub fn new(directory: impl AsRef<Path>, base: C, space: SearchSpace) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            base,
+            space,
+            strategy: SearchStrategy::Grid,
+            objective: Objective {
+                metric: LossMetric::new().name(),
+                direction: Direction::Lowest,
+                split: Split::Valid,
+            },
+            devices: vec![Device::default()],
+            seed: 0,
+        }
+    }
+
+    /// Replace the default grid search with the given [strategy](SearchStrategy).
+    pub fn with_strategy(mut self, strategy: SearchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// The metric the sweep optimizes, averaged over each epoch.
+    ///
+    /// The metric must be registered on the trials' training for the given split.
+    pub fn objective<Me: Metric>(
+        mut self,
+        metric: &Me,
+        direction: Direction,
+        split: Split,
+    ) -> Self {
+        self.objective = Objective {
+            metric: metric.name(),
+            direction,
+            split,
+        };
+        self
+    }
+
+    /// Run trials concurrently, one on each of the given devices.
+    pub fn with_devices(mut self, devices: Vec<Device>) -> Self {
+        assert!(!devices.is_empty(), "A sweep needs at least one device");
+        self.devices = devices;
+        self
+    }
+
+    /// The seed used to sample random trials.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Run every trial of the sweep.
+    ///
+    /// `train` is called once per [trial](Trial), from a thread dedicated to the trial's
+    /// device, and should [attach](Trial::attach) the trial to its training before launching
+    /// it. A trial that panics is recorded as failed without stopping the sweep.
+    pub fn run<F>(self, train: F) -> Result<SweepSummary, SweepError>
+    where
+        F: Fn(Trial<C>) + Sync,
+    {
+        self.space.check()?;
+
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let (params, rungs) = match &self.strategy {
+            SearchStrategy::Grid => (self.space.grid()?, None),
+            SearchStrategy::Random { trials } => (
+                (0..*trials).map(|_| self.space.sample(&mut rng)).collect(),
+                None,
+            ),
+            SearchStrategy::Asha {
+                trials,
+                min_epochs,
+                reduction_factor,
+            } => (
+                (0..*trials)
+                    .map(|_| self.space.sample(&mut rng))
+                    .collect::<Vec<_>>(),
+                Some(Rungs::new(*min_epochs, *reduction_factor)),
+            ),
+        };
+        // Fail before training anything if a trial config is invalid.
+        let configs = params
+            .iter()
+            .map(|params| apply(&self.base, params))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        std::fs::create_dir_all(&self.directory)?;
+        let state = Arc::new(Mutex::new(SweepState::new(
+            params.len(),
+            rungs,
+            self.objective.direction,
+        )));
+        let next = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            for device in &self.devices {
+                let (state, next, train) = (&state, &next, &train);
+                let (params, configs) = (&params, &configs);
+
+                scope.spawn(move || {
+                    loop {
+                        let id = next.fetch_add(1, Ordering::Relaxed);
+                        if id >= configs.len() {
+                            break;
+                        }
+
+                        let status =
+                            self.run_trial(id, &configs[id], &params[id], device, state, train);
+                        log::info!("Sweep trial {id} {status}");
+                        state.lock().unwrap().trials[id].status = Some(status);
+                    }
+                });
+            }
+        });
+
+        let state = state.lock().unwrap();
+        let trials = params
+            .into_iter()
+            .zip(state.trials.iter())
+            .enumerate()
+            .map(|(id, (params, record))| {
+                let best = record.values.iter().copied().min_by(|(_, a), (_, b)| {
+                    match self.objective.direction {
+                        Direction::Lowest => a.total_cmp(b),
+                        Direction::Highest => b.total_cmp(a),
+                    }
+                });
+                TrialSummary {
+                    id,
+                    params,
+                    status: record.status.clone().unwrap_or(TrialStatus::Completed),
+                    best,
+                    epochs: record.values.last().map_or(0, |(epoch, _)| *epoch),
+                }
+            })
+            .collect();
+
+        let summary = SweepSummary {
+            metric: self.objective.metric.to_string(),
+            split: self.objective.split.clone(),
+            direction: self.objective.direction,
+            fields: self.space.fields().map(String::from).collect(),
+            trials,
+        };
+        std::fs::write(self.directory.join("summary.md"), summary.to_string())?;
+
+        Ok(summary)
+    }
+
+    fn run_trial<F>(
+        &self,
+        id: usize,
+        config: &C,
+        params: &TrialParams,
+        device: &Device,
+        state: &Arc<Mutex<SweepState>>,
+        train: &F,
+    ) -> TrialStatus
+    where
+        F: Fn(Trial<C>) + Sync,
+    {
+        let directory = self.directory.join(format!("trial-{id:03}"));
+        if let Err(err) = Self::prepare(&directory, config, params) {
+            return TrialStatus::Failed {
+                reason: err.to_string(),
+            };
+        }
+
+        let interrupter = Interrupter::new();
+        let monitor = TrialMonitor::new(
+            id,
+            self.objective.clone(),
+            state.clone(),
+            interrupter.clone(),
+        );
+        let trial = Trial {
+            id,
+            config: config.clone(),
+            params: params.clone(),
+            directory,
+            device: device.clone(),
+            interrupter,
+            monitor,
+        };
+
+        if let Err(payload) = std::panic::catch_unwind(AssertUnwindSafe(|| train(trial))) {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .map(|reason| reason.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            return TrialStatus::Failed { reason };
+        }
+
+        let state = state.lock().unwrap();
+        let record = &state.trials[id];
+        if record.values.is_empty() {
+            log::warn!(
+                "Sweep trial {id} never reported its objective, was it attached to its training?"
+            );
+        }
+        match record.pruned_at {
+            Some(epoch) => TrialStatus::Pruned { epoch },
+            None => TrialStatus::Completed,
+        }
+    }
+
+    fn prepare(directory: &Path, config: &C, params: &TrialParams) -> Result<(), SweepError> {
+        std::fs::create_dir_all(directory)?;
+        config.save(directory.join("config.json"))?;
+        let params = serde_json::to_string_pretty(params)
+            .map_err(|err| SweepError::Config(err.to_string()))?;
+        std::fs::write(directory.join("params.json"), params)?;
+        Ok(())
+    }
+}
+
+/// A single training run of a [sweep](Sweep).
+pub struct Trial<C> {
+    /// The trial number.
+    pub id: usize,
+    /// The config to train with.
+    pub config: C,
+    /// The values given to the searched fields.
+    pub params: TrialParams,
+    /// The trial directory, to be used as the training directory.
+    pub directory: PathBuf,
+    /// The device to train on.
+    pub device: Device,
+    interrupter: Interrupter,
+    monitor: TrialMonitor,
+}
+
+impl<C> Trial<C> {
+    /// The interrupter the sweep stops the trial with.
+    pub fn interrupter(&self) -> Interrupter {
+        self.interrupter.clone()
+    }
+
+    /// The early stopping strategy reporting the trial's objective to the sweep.
+    pub fn monitor(&self) -> TrialMonitor {
+        self.monitor.clone()
+    }
+
+    /// Register the trial's [interrupter](Self::interrupter) and [monitor](Self::monitor) on
+    /// its training.
+    ///
+    /// This replaces any early stopping strategy registered before.
+    pub fn attach<M: LearnerModel>(
+        &self,
+        training: SupervisedTraining<M>,
+    ) -> SupervisedTraining<M> {
+        training
+            .with_interrupter(self.interrupter())
+            .early_stopping(self.monitor())
+    }
+}
@@ -0,0 +1,28 @@
+mod base;
+mod monitor;
+mod space;
+mod strategy;
+mod summary;
+
+pub use base::*;
+pub use monitor::TrialMonitor;
+pub use space::{Dimension, SearchSpace, TrialParams};
+pub use strategy::SearchStrategy;
+pub use summary::*;
+
+/// Errors that can occur when running a [sweep](Sweep).
+#[derive(thiserror::Error, Debug)]
+pub enum SweepError {
+    /// A searched field does not exist in the base config.
+    #[error("Unknown config field: {0}")]
+    UnknownField(String),
+    /// The search space can't be used with the search strategy.
+    #[error("Invalid search space: {0}")]
+    InvalidSpace(String),
+    /// A trial config could not be built from the searched values.
+    #[error("Invalid trial config: {0}")]
+    Config(String),
+    /// An IO error happened while writing the sweep directory.
+    #[error("IO error: {0}")]
+    IOError(#[from] std::io::Error),
+}
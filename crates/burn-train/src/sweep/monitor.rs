@@ -0,0 +1,117 @@
+use std::sync::{Arc, Mutex};
+
+use super::TrialStatus;
+use super::strategy::Rungs;
+use crate::Interrupter;
+use crate::learner::EarlyStoppingStrategy;
+use crate::metric::MetricName;
+use crate::metric::store::{Aggregate, Direction, EventStoreClient, Split};
+
+/// The metric a sweep optimizes.
+#[derive(Clone)]
+pub(crate) struct Objective {
+    pub(crate) metric: MetricName,
+    pub(crate) direction: Direction,
+    pub(crate) split: Split,
+}
+
+/// What a sweep knows about one of its trials.
+#[derive(Default)]
+pub(crate) struct TrialRecord {
+    /// The objective after each epoch, as `(epoch, value)`.
+    pub(crate) values: Vec<(usize, f64)>,
+    pub(crate) pruned_at: Option<usize>,
+    pub(crate) status: Option<TrialStatus>,
+}
+
+/// The state shared by the trials of a sweep.
+pub(crate) struct SweepState {
+    pub(crate) direction: Direction,
+    pub(crate) rungs: Option<Rungs>,
+    pub(crate) trials: Vec<TrialRecord>,
+}
+
+impl SweepState {
+    pub(crate) fn new(num_trials: usize, rungs: Option<Rungs>, direction: Direction) -> Self {
+        Self {
+            direction,
+            rungs,
+            trials: (0..num_trials).map(|_| TrialRecord::default()).collect(),
+        }
+    }
+
+    /// Record a trial's objective at `epoch`, returning whether the trial may continue.
+    fn report(&mut self, trial: usize, epoch: usize, value: f64) -> bool {
+        let record = &mut self.trials[trial];
+        record.values.push((epoch, value));
+
+        let promoted = match &mut self.rungs {
+            Some(rungs) => rungs.promote(epoch, value, self.direction),
+            None => true,
+        };
+        if !promoted {
+            record.pruned_at = Some(epoch);
+        }
+        promoted
+    }
+}
+
+/// Reports a trial's objective to its [sweep](super::Sweep) after every epoch, and prunes the
+/// trial when the [search strategy](super::SearchStrategy) says so.
+///
+/// It is the trial's [early stopping strategy](EarlyStoppingStrategy): register it on the
+/// trial's training, which [`Trial::attach`](super::Trial::attach) does. A pruned trial is
+/// stopped through its [`Interrupter`], with the reason as the message.
+#[derive(Clone)]
+pub struct TrialMonitor {
+    trial: usize,
+    objective: Objective,
+    state: Arc<Mutex<SweepState>>,
+    interrupter: Interrupter,
+}
+
+impl TrialMonitor {
+    pub(crate) fn new(
+        trial: usize,
+        objective: Objective,
+        state: Arc<Mutex<SweepState>>,
+        interrupter: Interrupter,
+    ) -> Self {
+        Self {
+            trial,
+            objective,
+            state,
+            interrupter,
+        }
+    }
+}
+
+impl EarlyStoppingStrategy for TrialMonitor {
+    fn should_stop(&mut self, epoch: usize, store: &EventStoreClient) -> bool {
+        let Some(value) = store.find_metric(
+            &self.objective.metric,
+            epoch,
+            Aggregate::Mean,
+            &self.objective.split,
+        ) else {
+            log::warn!(
+                "Can't find the sweep objective {} for trial {}.",
+                self.objective.metric,
+                self.trial
+            );
+            return false;
+        };
+
+        if self.state.lock().unwrap().report(self.trial, epoch, value) {
+            return false;
+        }
+
+        let reason = format!(
+            "Trial {} pruned at epoch {epoch}, {}: {value}",
+            self.trial, self.objective.metric
+        );
+        log::info!("{reason}");
+        self.interrupter.stop(Some(&reason));
+        true
+    }
+}
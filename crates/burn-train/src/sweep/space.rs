@@ -0,0 +1,262 @@
+use burn_core::config::Config;
+use rand::{RngExt, rngs::StdRng};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::SweepError;
+
+/// The values given to the searched fields of a [`Config`] in one trial, keyed by field path.
+pub type TrialParams = BTreeMap<String, Value>;
+
+/// The values a single [`Config`] field is searched over.
+#[derive(Clone, Debug)]
+pub enum Dimension {
+    /// One of the given values.
+    Choice(Vec<Value>),
+    /// An integer in `low..=high`.
+    IntRange {
+        /// The smallest value.
+        low: i64,
+        /// The largest value.
+        high: i64,
+    },
+    /// A float drawn uniformly from `low..high`. Only for random searches.
+    Uniform {
+        /// The lower bound.
+        low: f64,
+        /// The upper bound.
+        high: f64,
+    },
+    /// A float whose logarithm is drawn uniformly, for learning rates and other scales.
+    /// Only for random searches.
+    LogUniform {
+        /// The lower bound, strictly positive.
+        low: f64,
+        /// The upper bound.
+        high: f64,
+    },
+}
+
+impl Dimension {
+    /// Every value of the dimension, or `None` for a continuous one.
+    fn values(&self) -> Option<Vec<Value>> {
+        match self {
+            Dimension::Choice(values) => Some(values.clone()),
+            Dimension::IntRange { low, high } => Some((*low..=*high).map(Value::from).collect()),
+            Dimension::Uniform { .. } | Dimension::LogUniform { .. } => None,
+        }
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> Value {
+        match self {
+            Dimension::Choice(values) => values[rng.random_range(0..values.len())].clone(),
+            Dimension::IntRange { low, high } => Value::from(rng.random_range(*low..=*high)),
+            Dimension::Uniform { low, high } => Value::from(rng.random_range(*low..*high)),
+            Dimension::LogUniform { low, high } => {
+                Value::from(rng.random_range(low.ln()..high.ln()).exp())
+            }
+        }
+    }
+
+    fn check(&self, field: &str) -> Result<(), SweepError> {
+        let valid = match self {
+            Dimension::Choice(values) => !values.is_empty(),
+            Dimension::IntRange { low, high } => low <= high,
+            Dimension::Uniform { low, high } => low < high,
+            Dimension::LogUniform { low, high } => 0.0 < *low && low < high,
+        };
+        match valid {
+            true => Ok(()),
+            false => Err(SweepError::InvalidSpace(format!(
+                "field `{field}` has an empty dimension: {self:?}"
+            ))),
+        }
+    }
+}
+
+/// A search space over the fields of a [`Config`].
+///
+/// Fields are named by their path in the serialized config, nested fields joined with dots
+/// (`"optimizer.learning_rate"`), so any field of a config deriving [`Config`] can be
+/// searched without changes to the config itself.
+#[derive(Clone, Debug, Default)]
+pub struct SearchSpace {
+    dimensions: Vec<(String, Dimension)>,
+}
+
+impl SearchSpace {
+    /// Create an empty search space.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Search `field` over the given [dimension](Dimension).
+    pub fn with(mut self, field: &str, dimension: Dimension) -> Self {
+        self.dimensions.retain(|(name, _)| name != field);
+        self.dimensions.push((field.to_string(), dimension));
+        self
+    }
+
+    /// Search `field` over the given values.
+    ///
+    /// # Panics
+    ///
+    /// If a value does not serialize.
+    pub fn choice<V: Serialize>(self, field: &str, values: impl IntoIterator<Item = V>) -> Self {
+        let values = values
+            .into_iter()
+            .map(|value| serde_json::to_value(value).expect("Search values should serialize"))
+            .collect();
+        self.with(field, Dimension::Choice(values))
+    }
+
+    /// Search `field` over the integers in `low..=high`.
+    pub fn int_range(self, field: &str, low: i64, high: i64) -> Self {
+        self.with(field, Dimension::IntRange { low, high })
+    }
+
+    /// Search `field` over floats drawn uniformly from `low..high`.
+    pub fn uniform(self, field: &str, low: f64, high: f64) -> Self {
+        self.with(field, Dimension::Uniform { low, high })
+    }
+
+    /// Search `field` over floats between `low` and `high`, uniformly on a log scale.
+    pub fn log_uniform(self, field: &str, low: f64, high: f64) -> Self {
+        self.with(field, Dimension::LogUniform { low, high })
+    }
+
+    /// The searched field paths, in the order they were added.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.dimensions.iter().map(|(field, _)| field.as_str())
+    }
+
+    pub(crate) fn check(&self) -> Result<(), SweepError> {
+        self.dimensions
+            .iter()
+            .try_for_each(|(field, dimension)| dimension.check(field))
+    }
+
+    /// Every combination of values, the last field varying fastest.
+    pub(crate) fn grid(&self) -> Result<Vec<TrialParams>, SweepError> {
+        let mut grid = vec![TrialParams::new()];
+        for (field, dimension) in &self.dimensions {
+            let values = dimension.values().ok_or_else(|| {
+                SweepError::InvalidSpace(format!(
+                    "field `{field}` is continuous and cannot be searched on a grid"
+                ))
+            })?;
+            grid = grid
+                .into_iter()
+                .flat_map(|params| {
+                    values.iter().map(move |value| {
+                        let mut params = params.clone();
+                        params.insert(field.clone(), value.clone());
+                        params
+                    })
+                })
+                .collect();
+        }
+        Ok(grid)
+    }
+
+    pub(crate) fn sample(&self, rng: &mut StdRng) -> TrialParams {
+        self.dimensions
+            .iter()
+            .map(|(field, dimension)| (field.clone(), dimension.sample(rng)))
+            .collect()
+    }
+}
+
+/// The `base` config with the fields in `params` replaced.
+pub(crate) fn apply<C: Config>(base: &C, params: &TrialParams) -> Result<C, SweepError> {
+    let mut config =
+        serde_json::to_value(base).map_err(|err| SweepError::Config(err.to_string()))?;
+
+    for (field, value) in params {
+        let mut slot = &mut config;
+        for key in field.split('.') {
+            slot = slot
+                .as_object_mut()
+                .and_then(|object| object.get_mut(key))
+                .ok_or_else(|| SweepError::UnknownField(field.clone()))?;
+        }
+        *slot = value.clone();
+    }
+
+    serde_json::from_value(config).map_err(|err| SweepError::Config(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn_core as burn;
+    use rand::SeedableRng;
+
+    #[derive(Config, Debug)]
+    struct InnerConfig {
+        learning_rate: f64,
+    }
+
+    #[derive(Config, Debug)]
+    struct TestConfig {
+        batch_size: usize,
+        optimizer: InnerConfig,
+    }
+
+    fn base() -> TestConfig {
+        TestConfig::new(8, InnerConfig::new(1e-3))
+    }
+
+    #[test]
+    fn grid_is_the_cartesian_product() {
+        let space = SearchSpace::new()
+            .choice("batch_size", [8, 16, 32])
+            .int_range("depth", 1, 2);
+
+        let grid = space.grid().unwrap();
+        assert_eq!(grid.len(), 6);
+        assert_eq!(grid[1]["batch_size"], 8);
+        assert_eq!(grid[1]["depth"], 2);
+    }
+
+    #[test]
+    fn continuous_dimensions_have_no_grid() {
+        let space = SearchSpace::new().uniform("dropout", 0.0, 0.5);
+        assert!(matches!(space.grid(), Err(SweepError::InvalidSpace(_))));
+    }
+
+    #[test]
+    fn samples_stay_in_range() {
+        let space = SearchSpace::new().log_uniform("optimizer.learning_rate", 1e-5, 1e-1);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for _ in 0..100 {
+            let lr = space.sample(&mut rng)["optimizer.learning_rate"]
+                .as_f64()
+                .unwrap();
+            assert!((1e-5..1e-1).contains(&lr));
+        }
+    }
+
+    #[test]
+    fn apply_replaces_nested_fields() {
+        let params = TrialParams::from([
+            ("batch_size".to_string(), Value::from(32)),
+            ("optimizer.learning_rate".to_string(), Value::from(0.1)),
+        ]);
+
+        let config = apply(&base(), &params).unwrap();
+        assert_eq!(config.batch_size, 32);
+        assert_eq!(config.optimizer.learning_rate, 0.1);
+    }
+
+    #[test]
+    fn apply_rejects_unknown_fields() {
+        let params = TrialParams::from([("optimizer.momentum".to_string(), Value::from(0.9))]);
+        assert!(matches!(
+            apply(&base(), &params),
+            Err(SweepError::UnknownField(field)) if field == "optimizer.momentum"
+        ));
+    }
+}
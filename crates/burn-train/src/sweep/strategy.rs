@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use crate::metric::store::Direction;
+
+/// How a [sweep](super::Sweep) picks the trials it runs.
+#[derive(Clone, Debug)]
+pub enum SearchStrategy {
+    /// Every combination of the values of the [search space](super::SearchSpace), which must not
+    /// have continuous dimensions.
+    Grid,
+    /// The given number of trials, each sampled independently from the search space.
+    Random {
+        /// The number of trials.
+        trials: usize,
+    },
+    /// Random trials pruned by asynchronous successive halving (ASHA).
+    ///
+    /// Trials are compared at rungs: after `min_epochs`, then after `reduction_factor` times as
+    /// many epochs, and so on. A trial reaching a rung continues only if its objective is in
+    /// the best `1 / reduction_factor` of every value recorded at that rung so far, so the
+    /// budget goes to promising trials without waiting for the others to finish.
+    Asha {
+        /// The number of trials.
+        trials: usize,
+        /// The epoch of the first rung.
+        min_epochs: usize,
+        /// The factor between rungs, and the inverse of the fraction promoted at each.
+        reduction_factor: usize,
+    },
+}
+
+/// The objective values recorded at each rung of an [ASHA](SearchStrategy::Asha) search.
+pub(crate) struct Rungs {
+    min_epochs: usize,
+    reduction_factor: usize,
+    values: BTreeMap<usize, Vec<f64>>,
+}
+
+impl Rungs {
+    pub(crate) fn new(min_epochs: usize, reduction_factor: usize) -> Self {
+        Self {
+            min_epochs: min_epochs.max(1),
+            reduction_factor: reduction_factor.max(2),
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Record a trial's objective at `epoch`, returning whether the trial may continue.
+    ///
+    /// Epochs between rungs always continue. At a rung, the trial continues if its value is at
+    /// least as good as the one ranked `n / reduction_factor` among the `n` values recorded
+    /// there, counting its own; the first trial to reach a rung always does.
+    pub(crate) fn promote(&mut self, epoch: usize, value: f64, direction: Direction) -> bool {
+        if !self.is_rung(epoch) {
+            return true;
+        }
+
+        let values = self.values.entry(epoch).or_default();
+        values.push(value);
+
+        let mut ranked = values.clone();
+        ranked.sort_by(|a, b| match direction {
+            Direction::Lowest => a.total_cmp(b),
+            Direction::Highest => b.total_cmp(a),
+        });
+        let threshold = ranked[(ranked.len() / self.reduction_factor).max(1) - 1];
+
+        match direction {
+            Direction::Lowest => value <= threshold,
+            Direction::Highest => value >= threshold,
+        }
+    }
+
+    fn is_rung(&self, epoch: usize) -> bool {
+        let mut rung = self.min_epochs;
+        while rung < epoch {
+            rung *= self.reduction_factor;
+        }
+        rung == epoch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rungs_grow_geometrically() {
+        let rungs = Rungs::new(2, 3);
+        let epochs: Vec<usize> = (1..=20).filter(|epoch| rungs.is_rung(*epoch)).collect();
+        assert_eq!(epochs, vec![2, 6, 18]);
+    }
+
+    #[test]
+    fn only_the_best_fraction_is_promoted() {
+        let mut rungs = Rungs::new(1, 2);
+
+        // The first trial at a rung has nothing to compare against.
+        assert!(rungs.promote(1, 0.5, Direction::Lowest));
+        // Worse than the best of two: pruned.
+        assert!(!rungs.promote(1, 0.9, Direction::Lowest));
+        // Best so far.
+        assert!(rungs.promote(1, 0.1, Direction::Lowest));
+        // Between rungs, nothing is compared.
+        assert!(rungs.promote(3, 10.0, Direction::Lowest));
+    }
+
+    #[test]
+    fn direction_decides_what_is_better() {
+        let mut rungs = Rungs::new(1, 2);
+        assert!(rungs.promote(1, 0.5, Direction::Highest));
+        assert!(rungs.promote(1, 0.9, Direction::Highest));
+        assert!(!rungs.promote(1, 0.1, Direction::Highest));
+    }
+}
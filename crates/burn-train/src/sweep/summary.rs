@@ -0,0 +1,126 @@
+use std::fmt::Display;
+
+use super::TrialParams;
+use crate::metric::store::{Direction, Split};
+
+/// How a trial of a sweep ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrialStatus {
+    /// The trial trained to the end.
+    Completed,
+    /// The search strategy stopped the trial early.
+    Pruned {
+        /// The epoch the trial was stopped after.
+        epoch: usize,
+    },
+    /// The trial panicked.
+    Failed {
+        /// The panic message.
+        reason: String,
+    },
+}
+
+impl Display for TrialStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrialStatus::Completed => write!(f, "completed"),
+            TrialStatus::Pruned { epoch } => write!(f, "pruned at epoch {epoch}"),
+            TrialStatus::Failed { .. } => write!(f, "failed"),
+        }
+    }
+}
+
+/// The outcome of one trial of a sweep.
+#[derive(Clone, Debug)]
+pub struct TrialSummary {
+    /// The trial number, which also names its directory.
+    pub id: usize,
+    /// The values given to the searched fields.
+    pub params: TrialParams,
+    /// How the trial ended.
+    pub status: TrialStatus,
+    /// The best objective value the trial reached, as `(epoch, value)`.
+    pub best: Option<(usize, f64)>,
+    /// The last epoch the trial reported its objective for.
+    pub epochs: usize,
+}
+
+/// The outcome of a sweep: one row per trial, written to `summary.md` in the sweep directory.
+pub struct SweepSummary {
+    /// The objective metric name.
+    pub metric: String,
+    /// The split the objective is read from.
+    pub split: Split,
+    /// Whether lower or higher objective values are better.
+    pub direction: Direction,
+    /// The searched field paths.
+    pub fields: Vec<String>,
+    /// Every trial, by id.
+    pub trials: Vec<TrialSummary>,
+}
+
+impl SweepSummary {
+    /// The trial with the best objective.
+    ///
+    /// Completed trials are preferred: a pruned trial's value was reached with less training,
+    /// so it is only considered when no trial completed.
+    pub fn best(&self) -> Option<&TrialSummary> {
+        let best_of = |completed: bool| {
+            self.trials
+                .iter()
+                .filter(|trial| (trial.status == TrialStatus::Completed) == completed)
+                .filter_map(|trial| trial.best.map(|(_, value)| (trial, value)))
+                .min_by(|(_, a), (_, b)| match self.direction {
+                    Direction::Lowest => a.total_cmp(b),
+                    Direction::Highest => b.total_cmp(a),
+                })
+                .map(|(trial, _)| trial)
+        };
+        best_of(true).or_else(|| best_of(false))
+    }
+}
+
+fn fmt_val(value: f64) -> String {
+    if value.abs() < 1e-2 && value != 0.0 {
+        // Use scientific notation for small values which would otherwise be truncated
+        format!("{value:.3e}")
+    } else {
+        format!("{value:.4}")
+    }
+}
+
+/// A markdown table, the best trial marked with `*`.
+impl Display for SweepSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let best = self.best().map(|trial| trial.id);
+
+        write!(f, "| Trial | Status |")?;
+        for field in &self.fields {
+            write!(f, " {field} |")?;
+        }
+        writeln!(f, " {} ({}) | Epoch |", self.metric, self.split)?;
+
+        write!(f, "|---|---|")?;
+        for _ in &self.fields {
+            write!(f, "---|")?;
+        }
+        writeln!(f, "---|---|")?;
+
+        for trial in &self.trials {
+            let marker = if Some(trial.id) == best { " *" } else { "" };
+            write!(f, "| {}{marker} | {} |", trial.id, trial.status)?;
+            for field in &self.fields {
+                match trial.params.get(field) {
+                    Some(value) => write!(f, " {value} |")?,
+                    None => write!(f, " - |")?,
+                }
+            }
+            match trial.best {
+                Some((epoch, value)) => writeln!(f, " {} | {epoch} |", fmt_val(value))?,
+                None => writeln!(f, " - | - |")?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -258,3 +258,87 @@ fn file_metric_logger_resumes_logging_at_checkpoint() {
         }
     }
 }
+
+/// In delta mode, a checkpoint only holds what changed since the base, and still restores
+/// (and squashes into) the full model.
+#[cfg(feature = "delta-checkpoint")]
+#[test]
+fn delta_checkpoints_hold_only_changed_parameters() {
+    use burn_core::module::{Module, Param};
+    use burn_core::store::ModuleRecord;
+    use burn_core::tensor::{Distribution, Tensor};
+    use burn_store::burn_pack::Reader;
+    use burn_train::checkpoint::{Checkpointer, FileCheckpointer};
+
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let device = Device::flex();
+    let checkpointer = FileCheckpointer::new(dir.path(), "model").with_delta();
+
+    let mut model = TwoLayerModel::new(&device);
+    Checkpointer::<ModuleRecord>::save(&checkpointer, 1, model.clone().into_record()).unwrap();
+    model.active = Param::from_tensor(Tensor::random([1, 2], Distribution::Default, &device));
+    Checkpointer::<ModuleRecord>::save(&checkpointer, 2, model.clone().into_record()).unwrap();
+
+    assert!(dir.path().join("model-base.bpk").exists());
+    let delta = Reader::from_file(dir.path().join("model-2.bpk")).unwrap();
+    assert_eq!(delta.tensor_names(), vec!["active"]);
+
+    let weights = |model: &TwoLayerModel| {
+        (
+            model.frozen.weight.val().try_into_vec_as::<f32>().unwrap(),
+            model.active.val().try_into_vec_as::<f32>().unwrap(),
+        )
+    };
+    let restored: ModuleRecord = checkpointer.restore(2).unwrap();
+    let loaded = TwoLayerModel::new(&device).load_record(restored);
+    assert_eq!(weights(&loaded), weights(&model));
+
+    checkpointer.squash(2).unwrap();
+    fs::remove_file(dir.path().join("model-base.bpk")).unwrap();
+    let restored: ModuleRecord = checkpointer.restore(2).unwrap();
+    let loaded = TwoLayerModel::new(&device).load_record(restored);
+    assert_eq!(weights(&loaded), weights(&model));
+}
+
+/// A base left by a previous run is reused when resuming the same model, and replaced when it
+/// was saved from another one.
+#[cfg(feature = "delta-checkpoint")]
+#[test]
+fn delta_checkpoints_reuse_only_a_base_of_the_same_model() {
+    use burn_core::module::Module;
+    use burn_core::store::ModuleRecord;
+    use burn_store::burn_pack::Reader;
+    use burn_train::checkpoint::{Checkpointer, FileCheckpointer};
+
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let device = Device::flex();
+    let base_path = dir.path().join("model-base.bpk");
+    let save = |model: &TwoLayerModel, epoch| {
+        let checkpointer = FileCheckpointer::new(dir.path(), "model").with_delta();
+        Checkpointer::<ModuleRecord>::save(&checkpointer, epoch, model.clone().into_record())
+            .unwrap();
+    };
+
+    let model = TwoLayerModel::new(&device);
+    save(&model, 1);
+    let base = fs::read(&base_path).unwrap();
+
+    // Resuming: same parameters, so the base is kept and the delta is empty.
+    save(&model, 2);
+    assert_eq!(fs::read(&base_path).unwrap(), base);
+    let delta = Reader::from_file(dir.path().join("model-2.bpk")).unwrap();
+    assert!(delta.tensor_names().is_empty());
+
+    // Another model, whose parameter ids differ: the base is rewritten from it.
+    let other = TwoLayerModel::new(&device);
+    save(&other, 1);
+    assert_ne!(fs::read(&base_path).unwrap(), base);
+    let restored: ModuleRecord = FileCheckpointer::new(dir.path(), "model")
+        .restore(1)
+        .unwrap();
+    let loaded = TwoLayerModel::new(&device).load_record(restored);
+    assert_eq!(
+        loaded.active.val().try_into_vec_as::<f32>().unwrap(),
+        other.active.val().try_into_vec_as::<f32>().unwrap()
+    );
+}
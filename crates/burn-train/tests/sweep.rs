@@ -0,0 +1,118 @@
+//! Integration tests for hyperparameter sweeps over a minimal supervised training loop.
+
+mod common;
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::Mutex;
+
+use common::*;
+
+use burn_core as burn;
+
+use burn_core::config::Config;
+use burn_core::module::Param;
+use burn_core::tensor::{Device, Tensor};
+use burn_optim::{ModuleOptimizer, SgdConfig, lr_scheduler::constant::ConstantLr};
+use burn_train::{
+    Learner, SupervisedTraining,
+    logger::InMemoryMetricLogger,
+    metric::LossMetric,
+    sweep::{SearchSpace, SearchStrategy, Sweep, TrialStatus},
+};
+
+#[derive(Config, Debug)]
+struct ToyConfig {
+    #[config(default = 1e-3)]
+    learning_rate: f64,
+}
+
+/// An ASHA sweep where each trial starts further from the target than the previous one, so with
+/// a single device the first trial stays the best and every later one is pruned at the first
+/// rung.
+#[test]
+fn asha_sweep_prunes_trials_through_their_interrupter() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let dir_path = dir.path().to_path_buf();
+    let device = Device::flex().autodiff();
+    let num_trials = 4;
+
+    let space = SearchSpace::new().log_uniform("learning_rate", 1e-4, 1e-3);
+    let interrupted = Mutex::new(BTreeMap::new());
+    let summary = Sweep::new(&dir_path, ToyConfig::new(), space)
+        .with_strategy(SearchStrategy::Asha {
+            trials: num_trials,
+            min_epochs: 1,
+            reduction_factor: 2,
+        })
+        .with_devices(vec![device])
+        .run(|trial| {
+            // The learning rate is too small to reorder the trials' losses.
+            let init = 1.0 + trial.id as f32;
+            let model = ToyModel {
+                weight: Param::from_tensor(Tensor::full([1, 2], init, &trial.device)),
+            };
+            let optim: ModuleOptimizer = SgdConfig::new().init();
+            let learner = Learner::new(model, optim, ConstantLr::new(trial.config.learning_rate));
+
+            let (dl_train, dl_valid) = make_dataloaders();
+            let training = SupervisedTraining::new(&trial.directory, dl_train, dl_valid)
+                .num_epochs(3)
+                .with_metric_logger(InMemoryMetricLogger::new())
+                .metric_train_numeric(LossMetric::new())
+                .metric_valid_numeric(LossMetric::new())
+                .with_application_logger(None);
+            trial.attach(training).launch(learner);
+
+            let interrupter = trial.interrupter();
+            let message = interrupter.should_stop().then(|| interrupter.get_message());
+            interrupted
+                .lock()
+                .unwrap()
+                .insert(trial.id, message.flatten());
+        })
+        .expect("the sweep should run");
+
+    // Each trial has its own directory with its config and searched values.
+    for id in 0..num_trials {
+        let trial_dir = dir_path.join(format!("trial-{id:03}"));
+        assert!(trial_dir.join("config.json").exists());
+        let params = fs::read_to_string(trial_dir.join("params.json")).unwrap();
+        assert!(params.contains("learning_rate"), "{params}");
+        let config = ToyConfig::load(trial_dir.join("config.json")).unwrap();
+        assert_eq!(
+            serde_json::Value::from(config.learning_rate),
+            summary.trials[id].params["learning_rate"]
+        );
+    }
+
+    let statuses: Vec<_> = summary.trials.iter().map(|trial| &trial.status).collect();
+    assert_eq!(
+        statuses,
+        [
+            &TrialStatus::Completed,
+            &TrialStatus::Pruned { epoch: 1 },
+            &TrialStatus::Pruned { epoch: 1 },
+            &TrialStatus::Pruned { epoch: 1 },
+        ]
+    );
+    assert_eq!(summary.best().map(|trial| trial.id), Some(0));
+    assert_eq!(summary.trials[0].epochs, 3);
+
+    // Pruned trials were stopped through their interrupter, with the reason as the message.
+    let interrupted = interrupted.into_inner().unwrap();
+    assert_eq!(interrupted[&0], None);
+    for id in 1..num_trials {
+        let message = interrupted[&id]
+            .as_deref()
+            .expect("the trial was interrupted");
+        assert!(
+            message.starts_with(&format!("Trial {id} pruned at epoch 1")),
+            "{message}"
+        );
+    }
+
+    let table = fs::read_to_string(dir_path.join("summary.md")).unwrap();
+    assert_eq!(table, summary.to_string());
+    assert!(table.contains("| 0 * | completed |"), "{table}");
+    assert!(table.contains("| 1 | pruned at epoch 1 |"), "{table}");
+}
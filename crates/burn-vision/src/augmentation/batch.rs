@@ -0,0 +1,214 @@
+use alloc::{vec, vec::Vec};
+use burn_core::{
+    data::dataset::vision::{Annotation, BoundingBox, SegmentationMask},
+    tensor::{Tensor, TensorData},
+};
+
+/// A batch of images with the annotations that must follow them through an
+/// [`Augmentation`](super::Augmentation).
+///
+/// Bounding boxes use the `[x_min, y_min, width, height]` pixel coordinates of
+/// [`BoundingBox`], where pixel `i` spans `i..i + 1`. Segmentation masks are kept
+/// as float tensors holding class ids, so they can be resampled on the device
+/// alongside the images (always with nearest-neighbor interpolation).
+#[derive(Debug, Clone)]
+pub struct AugmentBatch {
+    /// Images shaped `[batch, channels, height, width]`.
+    pub images: Tensor<4>,
+    /// Per-image bounding boxes, if the batch carries any.
+    pub boxes: Option<Vec<Vec<BoundingBox>>>,
+    /// Segmentation masks shaped `[batch, masks, height, width]`, if the batch carries any.
+    pub masks: Option<Tensor<4>>,
+    /// For every image of the batch, the original images it was blended from
+    /// and their weights. Mixing augmentations update it so that image-level
+    /// labels can be mixed accordingly.
+    pub sources: Vec<Vec<(usize, f32)>>,
+}
+
+impl AugmentBatch {
+    /// Creates a batch of images without annotations.
+    pub fn new(images: Tensor<4>) -> Self {
+        let [batch_size, _, _, _] = images.dims();
+
+        Self {
+            images,
+            boxes: None,
+            masks: None,
+            sources: (0..batch_size).map(|i| vec![(i, 1.0)]).collect(),
+        }
+    }
+
+    /// Attaches per-image bounding boxes to the batch.
+    ///
+    /// # Panics
+    /// Panics if there isn't exactly one list of boxes per image.
+    pub fn with_boxes(mut self, boxes: Vec<Vec<BoundingBox>>) -> Self {
+        assert_eq!(
+            boxes.len(),
+            self.batch_size(),
+            "Expected one list of bounding boxes per image"
+        );
+        self.boxes = Some(boxes);
+        self
+    }
+
+    /// Attaches segmentation masks shaped `[batch, masks, height, width]` to the batch.
+    ///
+    /// # Panics
+    /// Panics if the masks don't have the batch size and spatial size of the images.
+    pub fn with_masks(mut self, masks: Tensor<4>) -> Self {
+        let [batch_size, _, height, width] = masks.dims();
+        let [_, _, img_height, img_width] = self.images.dims();
+        assert_eq!(
+            [batch_size, height, width],
+            [self.batch_size(), img_height, img_width],
+            "Segmentation masks must match the images batch and spatial sizes"
+        );
+        self.masks = Some(masks);
+        self
+    }
+
+    /// Creates a batch from images and their dataset annotations.
+    ///
+    /// Bounding boxes and segmentation masks are tracked through the augmentations;
+    /// image-level labels are not altered and can be recovered with
+    /// [`annotations`](Self::annotations).
+    ///
+    /// # Panics
+    /// Panics if the annotations don't all have the same kind, or if the
+    /// segmentation masks don't match the image size.
+    pub fn from_annotations(images: Tensor<4>, annotations: &[Annotation]) -> Self {
+        let [batch_size, _, height, width] = images.dims();
+        assert_eq!(
+            annotations.len(),
+            batch_size,
+            "Expected one annotation per image"
+        );
+        let device = images.device();
+        let batch = Self::new(images);
+
+        match annotations.first() {
+            Some(Annotation::BoundingBoxes(_)) => {
+                let boxes = annotations
+                    .iter()
+                    .map(|annotation| match annotation {
+                        Annotation::BoundingBoxes(boxes) => boxes.clone(),
+                        other => panic!("Expected bounding boxes annotation, got {other:?}"),
+                    })
+                    .collect();
+                batch.with_boxes(boxes)
+            }
+            Some(Annotation::SegmentationMask(first)) => {
+                let num_masks = first.mask.len() / (height * width);
+                let mut values = Vec::with_capacity(batch_size * num_masks * height * width);
+                for annotation in annotations {
+                    match annotation {
+                        Annotation::SegmentationMask(mask) => {
+                            assert_eq!(
+                                mask.mask.len(),
+                                num_masks * height * width,
+                                "Segmentation mask doesn't match the image size"
+                            );
+                            values.extend(mask.mask.iter().map(|&class| class as f32));
+                        }
+                        other => panic!("Expected segmentation mask annotation, got {other:?}"),
+                    }
+                }
+                let masks = Tensor::<4>::from_data(
+                    TensorData::new(values, [batch_size, num_masks, height, width]),
+                    &device,
+                );
+                batch.with_masks(masks)
+            }
+            _ => batch,
+        }
+    }
+
+    /// Returns the annotations of the augmented images.
+    ///
+    /// Boxes and masks are taken from the batch. Image-level labels are copied from
+    /// `originals`, using the source with the largest weight for blended images.
+    pub fn annotations(&self, originals: &[Annotation]) -> Vec<Annotation> {
+        if let Some(boxes) = &self.boxes {
+            return boxes
+                .iter()
+                .cloned()
+                .map(Annotation::BoundingBoxes)
+                .collect();
+        }
+
+        if let Some(masks) = &self.masks {
+            let [batch_size, num_masks, height, width] = masks.dims();
+            let per_image = num_masks * height * width;
+            let values: Vec<usize> = masks
+                .clone()
+                .into_data()
+                .iter::<f32>()
+                .map(|class| class.round().max(0.0) as usize)
+                .collect();
+
+            return (0..batch_size)
+                .map(|i| {
+                    Annotation::SegmentationMask(SegmentationMask {
+                        mask: values[i * per_image..(i + 1) * per_image].to_vec(),
+                    })
+                })
+                .collect();
+        }
+
+        self.sources
+            .iter()
+            .map(|sources| {
+                let (index, _) = sources
+                    .iter()
+                    .copied()
+                    .fold((0, f32::MIN), |best, current| {
+                        if current.1 > best.1 { current } else { best }
+                    });
+                originals[index].clone()
+            })
+            .collect()
+    }
+
+    /// The number of images in the batch.
+    pub fn batch_size(&self) -> usize {
+        self.sources.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn_core::tensor::Device;
+
+    #[test]
+    fn segmentation_masks_roundtrip() {
+        let images = Tensor::<4>::zeros([2, 3, 2, 2], &Device::default());
+        let annotations = vec![
+            Annotation::SegmentationMask(SegmentationMask {
+                mask: vec![0, 1, 2, 3],
+            }),
+            Annotation::SegmentationMask(SegmentationMask {
+                mask: vec![3, 2, 1, 0],
+            }),
+        ];
+
+        let batch = AugmentBatch::from_annotations(images, &annotations);
+
+        assert_eq!(batch.masks.as_ref().unwrap().dims(), [2, 1, 2, 2]);
+        assert_eq!(batch.annotations(&annotations), annotations);
+    }
+
+    #[test]
+    fn labels_follow_dominant_source() {
+        let images = Tensor::<4>::zeros([2, 3, 2, 2], &Device::default());
+        let annotations = vec![Annotation::Label(4), Annotation::Label(7)];
+        let mut batch = AugmentBatch::from_annotations(images, &annotations);
+        batch.sources[0] = vec![(0, 0.3), (1, 0.7)];
+
+        assert_eq!(
+            batch.annotations(&annotations),
+            vec![Annotation::Label(7), Annotation::Label(7)]
+        );
+    }
+}
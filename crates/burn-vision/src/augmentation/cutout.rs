@@ -0,0 +1,134 @@
+use alloc::vec::Vec;
+use burn_core as burn;
+use burn_core::{
+    config::Config,
+    tensor::{Device, Int, Tensor, TensorData},
+};
+use burn_std::rand::{RngExt, StdRng};
+
+use super::{AugmentBatch, Augmentation, sample};
+
+/// Builds a `[batch, 1, height, width]` float mask that is `1` inside each image's
+/// rectangle `[x_min, y_min, x_max, y_max]` (in pixel coordinates) and `0` elsewhere.
+///
+/// A pixel belongs to a rectangle when its center does.
+pub(crate) fn rectangle_mask(
+    rectangles: &[[f32; 4]],
+    [height, width]: [usize; 2],
+    device: &Device,
+) -> Tensor<4> {
+    let batch_size = rectangles.len();
+    let bounds: Vec<f32> = rectangles.iter().flatten().copied().collect();
+    let bounds = Tensor::<2>::from_data(TensorData::new(bounds, [batch_size, 4]), device);
+    let bound = |at: usize| {
+        bounds
+            .clone()
+            .narrow(1, at, 1)
+            .reshape([batch_size, 1, 1, 1])
+    };
+
+    let xs = Tensor::<1, Int>::arange(0..width as i64, device)
+        .float()
+        .add_scalar(0.5)
+        .reshape([1, 1, 1, width]);
+    let ys = Tensor::<1, Int>::arange(0..height as i64, device)
+        .float()
+        .add_scalar(0.5)
+        .reshape([1, 1, height, 1]);
+
+    let inside_x = xs.clone().greater_equal(bound(0)).float() * xs.lower(bound(2)).float();
+    let inside_y = ys.clone().greater_equal(bound(1)).float() * ys.lower(bound(3)).float();
+
+    inside_x * inside_y
+}
+
+/// Configuration for a [`Cutout`] augmentation.
+#[derive(Config, Debug)]
+pub struct CutoutConfig {
+    /// Number of rectangles erased from each image.
+    #[config(default = 1)]
+    pub holes: usize,
+    /// Range of the side of the erased rectangles, relative to the image side.
+    #[config(default = "[0.1, 0.3]")]
+    pub size: [f32; 2],
+    /// Value written in the erased rectangles.
+    #[config(default = 0.0)]
+    pub fill: f32,
+    /// Probability of erasing rectangles from an image.
+    #[config(default = 0.5)]
+    pub probability: f32,
+}
+
+impl CutoutConfig {
+    /// Returns the [`Cutout`] from the config.
+    pub fn init(&self) -> Cutout {
+        Cutout {
+            holes: self.holes,
+            size: self.size,
+            fill: self.fill,
+            probability: self.probability,
+        }
+    }
+}
+
+/// Erases random rectangles from images. Built from a [`CutoutConfig`].
+///
+/// Bounding boxes and segmentation masks are left unchanged.
+#[derive(Debug, Clone)]
+pub struct Cutout {
+    holes: usize,
+    size: [f32; 2],
+    fill: f32,
+    probability: f32,
+}
+
+impl Augmentation for Cutout {
+    fn augment(&self, mut batch: AugmentBatch, rng: &mut StdRng) -> AugmentBatch {
+        let dims @ [batch_size, _, height, width] = batch.images.dims();
+        let device = batch.images.device();
+        let (height_f, width_f) = (height as f32, width as f32);
+
+        let applied: Vec<bool> = (0..batch_size)
+            .map(|_| sample(rng, self.probability))
+            .collect();
+        let mut erased = Tensor::<4>::zeros([batch_size, 1, height, width], &device);
+
+        for _ in 0..self.holes {
+            let rectangles: Vec<[f32; 4]> = applied
+                .iter()
+                .map(|&applied| match applied {
+                    true => {
+                        let w = rng.random_range(self.size[0]..=self.size[1]) * width_f;
+                        let h = rng.random_range(self.size[0]..=self.size[1]) * height_f;
+                        let x = rng.random_range(0.0..=(width_f - w).max(0.0));
+                        let y = rng.random_range(0.0..=(height_f - h).max(0.0));
+                        [x, y, x + w, y + h]
+                    }
+                    false => [0.0; 4],
+                })
+                .collect();
+            erased = erased + rectangle_mask(&rectangles, [height, width], &device);
+        }
+
+        let erased = erased.greater_elem(0.0).expand(dims);
+        batch.images = batch.images.mask_fill(erased, self.fill);
+
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn_core::tensor::Tolerance;
+
+    #[test]
+    fn rectangle_mask_covers_pixel_centers() {
+        let mask = rectangle_mask(&[[0.0, 1.0, 2.0, 3.0]], [3, 3], &Device::default());
+
+        let expected = Tensor::<4>::from([[[[0., 0., 0.], [1., 1., 0.], [1., 1., 0.]]]]);
+        expected
+            .to_data()
+            .assert_approx_eq(&mask.to_data(), Tolerance::<f32>::balanced());
+    }
+}
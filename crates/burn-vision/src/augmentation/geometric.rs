@@ -0,0 +1,443 @@
+use alloc::vec::Vec;
+use burn_core as burn;
+use burn_core::{
+    config::Config,
+    data::dataset::vision::BoundingBox,
+    tensor::{
+        FloatDType, Tensor, TensorData,
+        grid::affine_grid_2d,
+        ops::{GridSampleOptions, GridSamplePaddingMode, InterpolateMode},
+    },
+};
+use burn_std::rand::{RngExt, StdRng};
+
+use super::{AugmentBatch, Augmentation, sample};
+use crate::Transform2D;
+
+/// Maps pixel coordinates (pixel `i` spanning `i..i + 1`) of an image of the given
+/// size to the `-1..=1` grid coordinates expected by `grid_sample_2d` with aligned corners.
+fn normalization(height: usize, width: usize) -> Transform2D {
+    assert!(
+        height > 1 && width > 1,
+        "Augmented images must be at least 2x2, got {height}x{width}"
+    );
+    let (sx, sy) = (2.0 / (width - 1) as f32, 2.0 / (height - 1) as f32);
+
+    Transform2D::composed([
+        Transform2D::translation(-1.0 - sx / 2.0, -1.0 - sy / 2.0),
+        Transform2D::scale(sx, sy, 0.0, 0.0),
+    ])
+}
+
+/// Resamples every image of the batch through its own transform.
+///
+/// Each transform maps output pixel coordinates to the input pixel coordinates they read
+/// from, and the output has the spatial size `[height, width]`.
+pub(crate) fn resample(
+    images: Tensor<4>,
+    transforms: &[Transform2D],
+    [height, width]: [usize; 2],
+    mode: InterpolateMode,
+    padding: GridSamplePaddingMode,
+) -> Tensor<4> {
+    let [batch_size, channels, in_height, in_width] = images.dims();
+    let to_input = normalization(in_height, in_width);
+    let from_output = normalization(height, width).inverse();
+
+    let theta: Vec<f32> = transforms
+        .iter()
+        .flat_map(|transform| {
+            Transform2D::composed([to_input.clone(), transform.clone(), from_output.clone()])
+                .matrix()
+                .into_iter()
+                .flatten()
+        })
+        .collect();
+    let theta =
+        Tensor::<3>::from_data(TensorData::new(theta, [batch_size, 2, 3]), &images.device())
+            .cast(FloatDType::from(images.dtype()));
+    let grid = affine_grid_2d(theta, [batch_size, channels, height, width]);
+
+    let options = GridSampleOptions::new(mode)
+        .with_padding_mode(padding)
+        .with_align_corners(true);
+    images.grid_sample_2d(grid, options)
+}
+
+/// Moves bounding boxes through a transform mapping output pixels to input pixels.
+///
+/// Boxes become the axis-aligned hull of their transformed corners, clipped to `clip`
+/// (`[x_min, y_min, x_max, y_max]`). Boxes keeping less than `min_visibility` of their
+/// area after clipping are dropped.
+pub(crate) fn transform_boxes(
+    boxes: &[BoundingBox],
+    transform: &Transform2D,
+    clip: [f32; 4],
+    min_visibility: f32,
+) -> Vec<BoundingBox> {
+    let inverse = transform.inverse();
+
+    boxes
+        .iter()
+        .filter_map(|bbox| {
+            let [x, y, w, h] = bbox.coords;
+            let corners = [(x, y), (x + w, y), (x, y + h), (x + w, y + h)]
+                .map(|(px, py)| inverse.apply(px, py));
+
+            let x_min = corners.iter().map(|c| c.0).fold(f32::INFINITY, f32::min);
+            let y_min = corners.iter().map(|c| c.1).fold(f32::INFINITY, f32::min);
+            let x_max = corners
+                .iter()
+                .map(|c| c.0)
+                .fold(f32::NEG_INFINITY, f32::max);
+            let y_max = corners
+                .iter()
+                .map(|c| c.1)
+                .fold(f32::NEG_INFINITY, f32::max);
+            let area = (x_max - x_min) * (y_max - y_min);
+
+            let [clip_x_min, clip_y_min, clip_x_max, clip_y_max] = clip;
+            let (x_min, y_min) = (x_min.max(clip_x_min), y_min.max(clip_y_min));
+            let (x_max, y_max) = (x_max.min(clip_x_max), y_max.min(clip_y_max));
+            let (w, h) = (x_max - x_min, y_max - y_min);
+
+            let visible = w > 0.0 && h > 0.0 && w * h >= min_visibility * area;
+            visible.then(|| BoundingBox {
+                coords: [x_min, y_min, w, h],
+                label: bbox.label,
+            })
+        })
+        .collect()
+}
+
+/// Applies a per-image geometric transform to the images and their annotations.
+///
+/// Images are resampled bilinearly, masks with nearest-neighbor interpolation and zero
+/// (background) padding.
+pub(crate) fn warp(
+    batch: AugmentBatch,
+    transforms: &[Transform2D],
+    size @ [height, width]: [usize; 2],
+    padding: GridSamplePaddingMode,
+    min_visibility: f32,
+) -> AugmentBatch {
+    let images = resample(
+        batch.images,
+        transforms,
+        size,
+        InterpolateMode::Bilinear,
+        padding,
+    );
+    let masks = batch.masks.map(|masks| {
+        resample(
+            masks,
+            transforms,
+            size,
+            InterpolateMode::Nearest,
+            GridSamplePaddingMode::Zeros,
+        )
+    });
+    let clip = [0.0, 0.0, width as f32, height as f32];
+    let boxes = batch.boxes.map(|boxes| {
+        boxes
+            .iter()
+            .zip(transforms)
+            .map(|(boxes, transform)| transform_boxes(boxes, transform, clip, min_visibility))
+            .collect()
+    });
+
+    AugmentBatch {
+        images,
+        boxes,
+        masks,
+        sources: batch.sources,
+    }
+}
+
+/// Configuration for a [`RandomResizedCrop`] augmentation.
+#[derive(Config, Debug)]
+pub struct RandomResizedCropConfig {
+    /// The `[height, width]` of the output images.
+    pub size: [usize; 2],
+    /// Range of the crop area, relative to the image area.
+    #[config(default = "[0.08, 1.0]")]
+    pub scale: [f32; 2],
+    /// Range of the crop aspect ratio (width over height).
+    #[config(default = "[0.75, 1.333_333_4]")]
+    pub ratio: [f32; 2],
+    /// Minimum fraction of a box's area that must remain inside the crop to keep the box.
+    #[config(default = 0.0)]
+    pub min_visibility: f32,
+    /// Probability of cropping an image before resizing it, otherwise the whole image is resized.
+    #[config(default = 1.0)]
+    pub probability: f32,
+}
+
+impl RandomResizedCropConfig {
+    /// Returns the [`RandomResizedCrop`] from the config.
+    pub fn init(&self) -> RandomResizedCrop {
+        RandomResizedCrop {
+            size: self.size,
+            scale: self.scale,
+            ratio: self.ratio,
+            min_visibility: self.min_visibility,
+            probability: self.probability,
+        }
+    }
+}
+
+/// Crops a random region of each image and resizes it to a fixed size.
+/// Built from a [`RandomResizedCropConfig`].
+#[derive(Debug, Clone)]
+pub struct RandomResizedCrop {
+    size: [usize; 2],
+    scale: [f32; 2],
+    ratio: [f32; 2],
+    min_visibility: f32,
+    probability: f32,
+}
+
+impl RandomResizedCrop {
+    /// Draws a crop `[x, y, width, height]` the same way as torchvision, falling back to the
+    /// whole image when no valid crop is found.
+    fn crop(&self, height: usize, width: usize, rng: &mut StdRng) -> [f32; 4] {
+        let (height, width) = (height as f32, width as f32);
+        let area = height * width;
+        let log_ratio = [self.ratio[0].ln(), self.ratio[1].ln()];
+
+        for _ in 0..10 {
+            let target_area = area * rng.random_range(self.scale[0]..=self.scale[1]);
+            let aspect_ratio = rng.random_range(log_ratio[0]..=log_ratio[1]).exp();
+            let w = (target_area * aspect_ratio).sqrt().round();
+            let h = (target_area / aspect_ratio).sqrt().round();
+
+            if w > 0.0 && h > 0.0 && w <= width && h <= height {
+                let x = rng.random_range(0..=(width - w) as usize) as f32;
+                let y = rng.random_range(0..=(height - h) as usize) as f32;
+                return [x, y, w, h];
+            }
+        }
+
+        [0.0, 0.0, width, height]
+    }
+}
+
+impl Augmentation for RandomResizedCrop {
+    fn augment(&self, batch: AugmentBatch, rng: &mut StdRng) -> AugmentBatch {
+        let [batch_size, _, height, width] = batch.images.dims();
+        let [out_height, out_width] = self.size;
+
+        let transforms: Vec<_> = (0..batch_size)
+            .map(|_| {
+                let [x, y, w, h] = match sample(rng, self.probability) {
+                    true => self.crop(height, width, rng),
+                    false => [0.0, 0.0, width as f32, height as f32],
+                };
+                Transform2D::composed([
+                    Transform2D::translation(x, y),
+                    Transform2D::scale(w / out_width as f32, h / out_height as f32, 0.0, 0.0),
+                ])
+            })
+            .collect();
+
+        warp(
+            batch,
+            &transforms,
+            self.size,
+            GridSamplePaddingMode::Border,
+            self.min_visibility,
+        )
+    }
+}
+
+/// Configuration for a [`RandomFlip`] augmentation.
+#[derive(Config, Debug)]
+pub struct RandomFlipConfig {
+    /// Probability of flipping an image horizontally.
+    #[config(default = 0.5)]
+    pub horizontal: f32,
+    /// Probability of flipping an image vertically.
+    #[config(default = 0.0)]
+    pub vertical: f32,
+}
+
+impl RandomFlipConfig {
+    /// Returns the [`RandomFlip`] from the config.
+    pub fn init(&self) -> RandomFlip {
+        RandomFlip {
+            horizontal: self.horizontal,
+            vertical: self.vertical,
+        }
+    }
+}
+
+/// Randomly mirrors images horizontally and/or vertically. Built from a [`RandomFlipConfig`].
+#[derive(Debug, Clone)]
+pub struct RandomFlip {
+    horizontal: f32,
+    vertical: f32,
+}
+
+impl Augmentation for RandomFlip {
+    fn augment(&self, batch: AugmentBatch, rng: &mut StdRng) -> AugmentBatch {
+        let [batch_size, _, height, width] = batch.images.dims();
+
+        let transforms: Vec<_> = (0..batch_size)
+            .map(|_| {
+                let (sx, tx) = match sample(rng, self.horizontal) {
+                    true => (-1.0, width as f32),
+                    false => (1.0, 0.0),
+                };
+                let (sy, ty) = match sample(rng, self.vertical) {
+                    true => (-1.0, height as f32),
+                    false => (1.0, 0.0),
+                };
+                Transform2D::composed([
+                    Transform2D::translation(tx, ty),
+                    Transform2D::scale(sx, sy, 0.0, 0.0),
+                ])
+            })
+            .collect();
+
+        warp(
+            batch,
+            &transforms,
+            [height, width],
+            GridSamplePaddingMode::Border,
+            0.0,
+        )
+    }
+}
+
+/// Configuration for a [`RandomRotation`] augmentation.
+#[derive(Config, Debug)]
+pub struct RandomRotationConfig {
+    /// Maximum rotation, in degrees. Angles are drawn from `-degrees..=degrees`.
+    pub degrees: f32,
+    /// Padding mode for the areas rotated in from outside the image.
+    #[config(default = "GridSamplePaddingMode::Zeros")]
+    pub padding: GridSamplePaddingMode,
+    /// Minimum fraction of a box's area that must remain inside the image to keep the box.
+    #[config(default = 0.0)]
+    pub min_visibility: f32,
+    /// Probability of rotating an image.
+    #[config(default = 1.0)]
+    pub probability: f32,
+}
+
+impl RandomRotationConfig {
+    /// Returns the [`RandomRotation`] from the config.
+    pub fn init(&self) -> RandomRotation {
+        RandomRotation {
+            degrees: self.degrees,
+            padding: self.padding,
+            min_visibility: self.min_visibility,
+            probability: self.probability,
+        }
+    }
+}
+
+/// Rotates images around their center by a random angle. Built from a [`RandomRotationConfig`].
+///
+/// Bounding boxes are replaced by the axis-aligned hull of their rotated corners.
+#[derive(Debug, Clone)]
+pub struct RandomRotation {
+    degrees: f32,
+    padding: GridSamplePaddingMode,
+    min_visibility: f32,
+    probability: f32,
+}
+
+impl Augmentation for RandomRotation {
+    fn augment(&self, batch: AugmentBatch, rng: &mut StdRng) -> AugmentBatch {
+        let [batch_size, _, height, width] = batch.images.dims();
+        let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+
+        let transforms: Vec<_> = (0..batch_size)
+            .map(|_| match sample(rng, self.probability) {
+                true => {
+                    let degrees = rng.random_range(-self.degrees..=self.degrees);
+                    Transform2D::rotation(degrees.to_radians(), cx, cy)
+                }
+                false => Transform2D::identity(),
+            })
+            .collect();
+
+        warp(
+            batch,
+            &transforms,
+            [height, width],
+            self.padding,
+            self.min_visibility,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use burn_core::tensor::{Device, Tolerance};
+    use burn_std::rand::SeedableRng;
+
+    fn bbox(coords: [f32; 4]) -> BoundingBox {
+        BoundingBox { coords, label: 1 }
+    }
+
+    #[test]
+    fn horizontal_flip_mirrors_images_and_boxes() {
+        let images = Tensor::<4>::from([[[[1., 2., 3.], [4., 5., 6.]]]]);
+        let batch = AugmentBatch::new(images).with_boxes(vec![vec![bbox([0., 0., 1., 2.])]]);
+        let flip = RandomFlipConfig::new().with_horizontal(1.0).init();
+
+        let batch = flip.augment(batch, &mut StdRng::seed_from_u64(0));
+
+        let expected = Tensor::<4>::from([[[[3., 2., 1.], [6., 5., 4.]]]]);
+        expected
+            .to_data()
+            .assert_approx_eq(&batch.images.to_data(), Tolerance::<f32>::balanced());
+        assert_eq!(batch.boxes.unwrap(), vec![vec![bbox([2., 0., 1., 2.])]]);
+    }
+
+    #[test]
+    fn full_crop_is_identity() {
+        let images = Tensor::<4>::from([[[[1., 2., 3.], [4., 5., 6.], [7., 8., 9.]]]]);
+        let batch =
+            AugmentBatch::new(images.clone()).with_boxes(vec![vec![bbox([1., 1., 1., 1.])]]);
+        let crop = RandomResizedCropConfig::new([3, 3])
+            .with_scale([1.0, 1.0])
+            .with_ratio([1.0, 1.0])
+            .init();
+
+        let batch = crop.augment(batch, &mut StdRng::seed_from_u64(0));
+
+        images
+            .to_data()
+            .assert_approx_eq(&batch.images.to_data(), Tolerance::<f32>::balanced());
+        assert_eq!(batch.boxes.unwrap(), vec![vec![bbox([1., 1., 1., 1.])]]);
+    }
+
+    #[test]
+    fn resize_scales_boxes() {
+        let images = Tensor::<4>::zeros([1, 1, 4, 4], &Device::default());
+        let batch = AugmentBatch::new(images).with_boxes(vec![vec![bbox([0., 2., 2., 2.])]]);
+        let resize = RandomResizedCropConfig::new([8, 2])
+            .with_probability(0.0)
+            .init();
+
+        let batch = resize.augment(batch, &mut StdRng::seed_from_u64(0));
+
+        assert_eq!(batch.images.dims(), [1, 1, 8, 2]);
+        assert_eq!(batch.boxes.unwrap(), vec![vec![bbox([0., 4., 1., 4.])]]);
+    }
+
+    #[test]
+    fn rotation_drops_boxes_moved_out_of_the_image() {
+        let boxes = vec![bbox([0., 0., 1., 1.])];
+        let transform = Transform2D::translation(5.0, 0.0);
+
+        let moved = transform_boxes(&boxes, &transform, [0., 0., 4., 4.], 0.5);
+
+        assert!(moved.is_empty());
+    }
+}
@@ -0,0 +1,348 @@
+use alloc::{vec, vec::Vec};
+use burn_core as burn;
+use burn_core::{
+    config::Config,
+    tensor::{
+        Int, Tensor, TensorData,
+        ops::{GridSamplePaddingMode, InterpolateMode},
+    },
+};
+use burn_std::rand::{RngExt, StdRng};
+use rand_distr::{Beta, Distribution};
+
+use super::{
+    AugmentBatch, Augmentation,
+    cutout::rectangle_mask,
+    geometric::{resample, transform_boxes},
+    sample,
+};
+use crate::Transform2D;
+
+/// Gathers the images at `indices` along the batch dimension.
+fn gather(tensor: Tensor<4>, indices: &[usize]) -> Tensor<4> {
+    let device = tensor.device();
+    let indices: Vec<i64> = indices.iter().map(|&i| i as i64).collect();
+    let len = indices.len();
+
+    tensor.select(
+        0,
+        Tensor::<1, Int>::from_data(TensorData::new(indices, [len]), &device),
+    )
+}
+
+/// Merges the sources of two blended images, scaling each by its weight.
+fn blend_sources(sources: &[Vec<(usize, f32)>], parts: &[(usize, f32)]) -> Vec<(usize, f32)> {
+    let mut blended: Vec<(usize, f32)> = Vec::new();
+    for &(image, weight) in parts.iter().filter(|(_, weight)| *weight > 0.0) {
+        for &(source, source_weight) in &sources[image] {
+            match blended.iter_mut().find(|(index, _)| *index == source) {
+                Some((_, total)) => *total += weight * source_weight,
+                None => blended.push((source, weight * source_weight)),
+            }
+        }
+    }
+    blended
+}
+
+/// Configuration for a [`Mixup`] augmentation.
+#[derive(Config, Debug)]
+pub struct MixupConfig {
+    /// Concentration of the symmetric `Beta(alpha, alpha)` distribution of the blend weight.
+    #[config(default = 1.0)]
+    pub alpha: f32,
+    /// Probability of blending an image with another image of the batch.
+    #[config(default = 0.5)]
+    pub probability: f32,
+}
+
+impl MixupConfig {
+    /// Returns the [`Mixup`] from the config.
+    ///
+    /// # Panics
+    /// Panics if `alpha` isn't strictly positive.
+    pub fn init(&self) -> Mixup {
+        Mixup {
+            beta: Beta::new(self.alpha, self.alpha).expect("alpha must be strictly positive"),
+            probability: self.probability,
+        }
+    }
+}
+
+/// Blends images with other images of the batch. Built from a [`MixupConfig`].
+///
+/// The bounding boxes of both images are kept, segmentation masks are taken from the
+/// image with the largest weight, and [`AugmentBatch::sources`] records the blend
+/// weights for image-level labels.
+#[derive(Debug, Clone)]
+pub struct Mixup {
+    beta: Beta<f32>,
+    probability: f32,
+}
+
+impl Augmentation for Mixup {
+    fn augment(&self, batch: AugmentBatch, rng: &mut StdRng) -> AugmentBatch {
+        let batch_size = batch.batch_size();
+        let device = batch.images.device();
+
+        let (partners, weights): (Vec<usize>, Vec<f32>) = (0..batch_size)
+            .map(|i| match batch_size > 1 && sample(rng, self.probability) {
+                true => (rng.random_range(0..batch_size), self.beta.sample(rng)),
+                false => (i, 1.0),
+            })
+            .unzip();
+
+        let lambda = Tensor::<4>::from_data(
+            TensorData::new(weights.clone(), [batch_size, 1, 1, 1]),
+            &device,
+        );
+        let partner_images = gather(batch.images.clone(), &partners);
+        let images =
+            batch.images * lambda.clone() + partner_images * lambda.clone().neg().add_scalar(1.0);
+
+        let masks = batch.masks.map(|masks| {
+            let dims = masks.dims();
+            let from_partner = lambda.clone().lower_elem(0.5).expand(dims);
+            let partner_masks = gather(masks.clone(), &partners);
+            masks.mask_where(from_partner, partner_masks)
+        });
+
+        let boxes = batch.boxes.map(|boxes| {
+            (0..batch_size)
+                .map(|i| {
+                    let mut merged = boxes[i].clone();
+                    if partners[i] != i && weights[i] < 1.0 {
+                        merged.extend(boxes[partners[i]].iter().cloned());
+                    }
+                    merged
+                })
+                .collect()
+        });
+
+        let sources = (0..batch_size)
+            .map(|i| {
+                blend_sources(
+                    &batch.sources,
+                    &[(i, weights[i]), (partners[i], 1.0 - weights[i])],
+                )
+            })
+            .collect();
+
+        AugmentBatch {
+            images,
+            boxes,
+            masks,
+            sources,
+        }
+    }
+}
+
+/// Configuration for a [`Mosaic`] augmentation.
+#[derive(Config, Debug)]
+pub struct MosaicConfig {
+    /// Range of the mosaic center, relative to the image size.
+    #[config(default = "[0.25, 0.75]")]
+    pub center: [f32; 2],
+    /// Minimum fraction of a box's area that must remain inside its tile to keep the box.
+    #[config(default = 0.0)]
+    pub min_visibility: f32,
+    /// Probability of replacing an image by a mosaic.
+    #[config(default = 0.5)]
+    pub probability: f32,
+}
+
+impl MosaicConfig {
+    /// Returns the [`Mosaic`] from the config.
+    pub fn init(&self) -> Mosaic {
+        Mosaic {
+            center: self.center,
+            min_visibility: self.min_visibility,
+            probability: self.probability,
+        }
+    }
+}
+
+/// Tiles four images of the batch in a 2x2 grid around a random center.
+/// Built from a [`MosaicConfig`].
+///
+/// The first tile is the image itself and the other three are drawn from the batch. Each
+/// image is resized to its tile, and its boxes and masks follow. [`AugmentBatch::sources`]
+/// weights every image by the area of its tile.
+#[derive(Debug, Clone)]
+pub struct Mosaic {
+    center: [f32; 2],
+    min_visibility: f32,
+    probability: f32,
+}
+
+impl Augmentation for Mosaic {
+    fn augment(&self, batch: AugmentBatch, rng: &mut StdRng) -> AugmentBatch {
+        let [batch_size, _, height, width] = batch.images.dims();
+        let (height_f, width_f) = (height as f32, width as f32);
+        let device = batch.images.device();
+
+        // Per image and per tile, the image drawn and the `[x_min, y_min, x_max, y_max]` tile.
+        // Images left alone have a single tile covering the whole image.
+        let mut tiles = vec![Vec::with_capacity(batch_size); 4];
+        for i in 0..batch_size {
+            match sample(rng, self.probability) {
+                true => {
+                    let cx = rng.random_range(self.center[0]..=self.center[1]) * width_f;
+                    let cy = rng.random_range(self.center[0]..=self.center[1]) * height_f;
+                    let rectangles = [
+                        [0.0, 0.0, cx, cy],
+                        [cx, 0.0, width_f, cy],
+                        [0.0, cy, cx, height_f],
+                        [cx, cy, width_f, height_f],
+                    ];
+                    for (k, rectangle) in rectangles.into_iter().enumerate() {
+                        let image = if k == 0 {
+                            i
+                        } else {
+                            rng.random_range(0..batch_size)
+                        };
+                        tiles[k].push((image, rectangle));
+                    }
+                }
+                false => {
+                    tiles[0].push((i, [0.0, 0.0, width_f, height_f]));
+                    for tile in tiles.iter_mut().skip(1) {
+                        tile.push((i, [0.0; 4]));
+                    }
+                }
+            }
+        }
+
+        let mut images = batch.images.zeros_like();
+        let mut masks = batch.masks.as_ref().map(|masks| masks.zeros_like());
+        let mut boxes = batch.boxes.as_ref().map(|_| vec![Vec::new(); batch_size]);
+        let mut parts = vec![Vec::new(); batch_size];
+
+        for tile in &tiles {
+            let indices: Vec<usize> = tile.iter().map(|(image, _)| *image).collect();
+            let rectangles: Vec<[f32; 4]> = tile.iter().map(|(_, rectangle)| *rectangle).collect();
+            // Stretches the whole image onto its tile; empty tiles keep the identity.
+            let transforms: Vec<Transform2D> = rectangles
+                .iter()
+                .map(|&[x_min, y_min, x_max, y_max]| {
+                    let (w, h) = (x_max - x_min, y_max - y_min);
+                    match w > 0.0 && h > 0.0 {
+                        true => Transform2D::composed([
+                            Transform2D::scale(width_f / w, height_f / h, 0.0, 0.0),
+                            Transform2D::translation(-x_min, -y_min),
+                        ]),
+                        false => Transform2D::identity(),
+                    }
+                })
+                .collect();
+            let region = rectangle_mask(&rectangles, [height, width], &device);
+
+            let tile_images = resample(
+                gather(batch.images.clone(), &indices),
+                &transforms,
+                [height, width],
+                InterpolateMode::Bilinear,
+                GridSamplePaddingMode::Border,
+            );
+            images = images + tile_images * region.clone();
+
+            if let (Some(masks), Some(source)) = (masks.as_mut(), batch.masks.as_ref()) {
+                let tile_masks = resample(
+                    gather(source.clone(), &indices),
+                    &transforms,
+                    [height, width],
+                    InterpolateMode::Nearest,
+                    GridSamplePaddingMode::Zeros,
+                );
+                *masks = masks.clone() + tile_masks * region;
+            }
+
+            if let (Some(boxes), Some(source)) = (boxes.as_mut(), batch.boxes.as_ref()) {
+                for (i, (image, rectangle)) in tile.iter().enumerate() {
+                    boxes[i].extend(transform_boxes(
+                        &source[*image],
+                        &transforms[i],
+                        *rectangle,
+                        self.min_visibility,
+                    ));
+                }
+            }
+
+            for (i, (image, [x_min, y_min, x_max, y_max])) in tile.iter().enumerate() {
+                let area = (x_max - x_min) * (y_max - y_min) / (width_f * height_f);
+                parts[i].push((*image, area));
+            }
+        }
+
+        let sources = parts
+            .iter()
+            .map(|parts| blend_sources(&batch.sources, parts))
+            .collect();
+
+        AugmentBatch {
+            images,
+            boxes,
+            masks,
+            sources,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn_core::{data::dataset::vision::BoundingBox, tensor::Tolerance};
+    use burn_std::rand::SeedableRng;
+
+    fn batch() -> AugmentBatch {
+        let images = Tensor::<4>::from([[[[1., 1.], [1., 1.]]], [[[3., 3.], [3., 3.]]]]);
+        let bbox = |label| BoundingBox {
+            coords: [0., 0., 2., 2.],
+            label,
+        };
+        AugmentBatch::new(images).with_boxes(vec![vec![bbox(0)], vec![bbox(1)]])
+    }
+
+    #[test]
+    fn mixup_zero_probability_is_passthrough() {
+        let batch = batch();
+        let mixup = MixupConfig::new().with_probability(0.0).init();
+
+        let mixed = mixup.augment(batch.clone(), &mut StdRng::seed_from_u64(0));
+
+        batch
+            .images
+            .to_data()
+            .assert_approx_eq(&mixed.images.to_data(), Tolerance::<f32>::balanced());
+        assert_eq!(mixed.boxes, batch.boxes);
+        assert_eq!(mixed.sources, batch.sources);
+    }
+
+    #[test]
+    fn mixup_weights_sum_to_one() {
+        let mixup = MixupConfig::new().with_probability(1.0).init();
+
+        let mixed = mixup.augment(batch(), &mut StdRng::seed_from_u64(0));
+
+        for sources in mixed.sources {
+            let total: f32 = sources.iter().map(|(_, weight)| weight).sum();
+            assert!((total - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn mosaic_tiles_cover_the_image() {
+        let mosaic = MosaicConfig::new().with_probability(1.0).init();
+
+        let tiled = mosaic.augment(batch(), &mut StdRng::seed_from_u64(0));
+
+        assert_eq!(tiled.images.dims(), [2, 1, 2, 2]);
+        for sources in &tiled.sources {
+            let total: f32 = sources.iter().map(|(_, weight)| weight).sum();
+            assert!((total - 1.0).abs() < 1e-5);
+        }
+        // Each tile brings one clipped box.
+        for boxes in tiled.boxes.unwrap() {
+            assert_eq!(boxes.len(), 4);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+mod batch;
+mod cutout;
+mod geometric;
+mod mix;
+mod photometric;
+
+pub use batch::*;
+pub use cutout::{Cutout, CutoutConfig};
+pub use geometric::{
+    RandomFlip, RandomFlipConfig, RandomResizedCrop, RandomResizedCropConfig, RandomRotation,
+    RandomRotationConfig,
+};
+pub use mix::*;
+pub use photometric::*;
+
+use alloc::{boxed::Box, vec::Vec};
+use burn_std::{
+    rand::{self, RngExt, SeedableRng, StdRng},
+    sync::Mutex,
+};
+
+/// A random transform applied jointly to a batch of images and their annotations.
+///
+/// Random parameters are drawn per image from the given generator, while the tensor work is
+/// done once for the whole batch on its device.
+pub trait Augmentation: Send + Sync {
+    /// Applies the augmentation to the batch.
+    fn augment(&self, batch: AugmentBatch, rng: &mut StdRng) -> AugmentBatch;
+}
+
+/// Draws whether an augmentation applied with the given probability should be applied.
+pub(crate) fn sample(rng: &mut StdRng, probability: f32) -> bool {
+    rng.random::<f32>() < probability
+}
+
+/// A sequence of augmentations applied one after the other, with its own seedable generator.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let pipeline = AugmentationPipeline::new()
+///     .with(RandomResizedCropConfig::new([224, 224]).init())
+///     .with(RandomFlipConfig::new().init())
+///     .with(ColorJitterConfig::new().with_brightness(0.2).init())
+///     .with_seed(42);
+///
+/// let batch = pipeline.forward(AugmentBatch::from_annotations(images, &annotations));
+/// ```
+pub struct AugmentationPipeline {
+    augmentations: Vec<Box<dyn Augmentation>>,
+    rng: Mutex<StdRng>,
+}
+
+impl Default for AugmentationPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for AugmentationPipeline {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AugmentationPipeline")
+            .field("augmentations", &self.augmentations.len())
+            .finish()
+    }
+}
+
+impl AugmentationPipeline {
+    /// Creates an empty pipeline, drawing from the global seeded generator.
+    pub fn new() -> Self {
+        Self {
+            augmentations: Vec::new(),
+            rng: Mutex::new(StdRng::from_rng(&mut rand::get_seeded_rng())),
+        }
+    }
+
+    /// Appends an augmentation to the pipeline.
+    pub fn with<A: Augmentation + 'static>(mut self, augmentation: A) -> Self {
+        self.augmentations.push(Box::new(augmentation));
+        self
+    }
+
+    /// Draws from `seed` rather than from the global generator.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Applies every augmentation of the pipeline, in order.
+    pub fn forward(&self, batch: AugmentBatch) -> AugmentBatch {
+        let mut rng = self.rng.lock();
+
+        self.augmentations
+            .iter()
+            .fold(batch, |batch, augmentation| {
+                augmentation.augment(batch, &mut rng)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use burn_core::{
+        data::dataset::vision::BoundingBox,
+        tensor::{Tensor, TensorData},
+    };
+
+    fn pipeline(seed: u64) -> AugmentationPipeline {
+        AugmentationPipeline::new()
+            .with(RandomFlipConfig::new().init())
+            .with(RandomRotationConfig::new(30.0).init())
+            .with(MixupConfig::new().init())
+            .with_seed(seed)
+    }
+
+    fn batch() -> AugmentBatch {
+        let values: Vec<f32> = (0..2 * 3 * 4 * 4).map(|v| v as f32 / 96.0).collect();
+        let images =
+            Tensor::<4>::from_data(TensorData::new(values, [2, 3, 4, 4]), &Default::default());
+        let bbox = BoundingBox {
+            coords: [1., 1., 2., 2.],
+            label: 0,
+        };
+        AugmentBatch::new(images).with_boxes(vec![vec![bbox.clone()], vec![bbox]])
+    }
+
+    #[test]
+    fn same_seed_same_augmentations() {
+        let first = pipeline(7).forward(batch());
+        let second = pipeline(7).forward(batch());
+
+        assert_eq!(first.images.into_data(), second.images.into_data());
+        assert_eq!(first.boxes, second.boxes);
+        assert_eq!(first.sources, second.sources);
+    }
+}
@@ -0,0 +1,172 @@
+use alloc::{vec, vec::Vec};
+use burn_core as burn;
+use burn_core::{
+    config::Config,
+    tensor::{Tensor, TensorData},
+};
+use burn_std::rand::{RngExt, StdRng};
+
+use super::{AugmentBatch, Augmentation, sample};
+use crate::ColorConversion;
+
+/// Configuration for a [`ColorJitter`] augmentation.
+///
+/// Each factor is drawn uniformly from `1 - x..=1 + x`, where `x` is the configured
+/// strength, and the hue is shifted by up to `hue * 360` degrees.
+#[derive(Config, Debug)]
+pub struct ColorJitterConfig {
+    /// Strength of the brightness (HSV value) jitter.
+    #[config(default = 0.0)]
+    pub brightness: f32,
+    /// Strength of the contrast jitter.
+    #[config(default = 0.0)]
+    pub contrast: f32,
+    /// Strength of the saturation jitter.
+    #[config(default = 0.0)]
+    pub saturation: f32,
+    /// Maximum hue shift, as a fraction of the color wheel, in `0.0..=0.5`.
+    #[config(default = 0.0)]
+    pub hue: f32,
+    /// Probability of jittering an image.
+    #[config(default = 1.0)]
+    pub probability: f32,
+}
+
+impl ColorJitterConfig {
+    /// Returns the [`ColorJitter`] from the config.
+    ///
+    /// # Panics
+    /// Panics if `hue` is outside of `0.0..=0.5`.
+    pub fn init(&self) -> ColorJitter {
+        assert!(
+            (0.0..=0.5).contains(&self.hue),
+            "hue must be in 0.0..=0.5, got {}",
+            self.hue
+        );
+
+        ColorJitter {
+            brightness: self.brightness,
+            contrast: self.contrast,
+            saturation: self.saturation,
+            hue: self.hue,
+            probability: self.probability,
+        }
+    }
+}
+
+/// Randomly changes the brightness, contrast, saturation and hue of RGB images in the
+/// `0.0..=1.0` range, through the [`ColorConversion`] HSV ops. Built from a
+/// [`ColorJitterConfig`].
+///
+/// Annotations are left unchanged.
+#[derive(Debug, Clone)]
+pub struct ColorJitter {
+    brightness: f32,
+    contrast: f32,
+    saturation: f32,
+    hue: f32,
+    probability: f32,
+}
+
+impl ColorJitter {
+    fn factor(strength: f32, rng: &mut StdRng) -> f32 {
+        match strength > 0.0 {
+            true => rng.random_range((1.0 - strength).max(0.0)..=1.0 + strength),
+            false => 1.0,
+        }
+    }
+}
+
+impl Augmentation for ColorJitter {
+    fn augment(&self, mut batch: AugmentBatch, rng: &mut StdRng) -> AugmentBatch {
+        let [batch_size, channels, _, _] = batch.images.dims();
+        assert_eq!(
+            channels, 3,
+            "ColorJitter expects RGB images, got {channels} channels"
+        );
+
+        // Per image, an HSV shift `[hue, 0, 0]`, an HSV scale `[1, saturation, value]`
+        // and a contrast factor.
+        let mut shift = Vec::with_capacity(batch_size * 3);
+        let mut scale = Vec::with_capacity(batch_size * 3);
+        let mut contrast = Vec::with_capacity(batch_size);
+        for _ in 0..batch_size {
+            match sample(rng, self.probability) {
+                true => {
+                    shift.extend([rng.random_range(-self.hue..=self.hue) * 360.0, 0.0, 0.0]);
+                    scale.extend([
+                        1.0,
+                        Self::factor(self.saturation, rng),
+                        Self::factor(self.brightness, rng),
+                    ]);
+                    contrast.push(Self::factor(self.contrast, rng));
+                }
+                false => {
+                    shift.extend([0.0, 0.0, 0.0]);
+                    scale.extend([1.0, 1.0, 1.0]);
+                    contrast.push(1.0);
+                }
+            }
+        }
+
+        let device = batch.images.device();
+        let shift = Tensor::<4>::from_data(TensorData::new(shift, [batch_size, 3, 1, 1]), &device);
+        let scale = Tensor::<4>::from_data(TensorData::new(scale, [batch_size, 3, 1, 1]), &device);
+        let contrast =
+            Tensor::<4>::from_data(TensorData::new(contrast, [batch_size, 1, 1, 1]), &device);
+
+        let hsv = (batch.images.rgb2hsv() + shift) * scale;
+        let hsv = Tensor::cat(
+            vec![
+                hsv.clone().narrow(1, 0, 1).remainder_scalar(360.0),
+                hsv.narrow(1, 1, 2).clamp(0.0, 1.0),
+            ],
+            1,
+        );
+        let rgb = hsv.hsv2rgb();
+
+        // Contrast blends each image with its mean gray level.
+        let mean = rgb.clone().rgb2gray().mean_dim(2).mean_dim(3);
+        batch.images = ((rgb - mean.clone()) * contrast + mean).clamp(0.0, 1.0);
+
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn_core::tensor::Tolerance;
+    use burn_std::rand::SeedableRng;
+
+    #[test]
+    fn zero_strength_is_identity() {
+        let images = Tensor::<4>::from([[[[0.8, 0.1]], [[0.3, 0.7]], [[0.1, 0.4]]]]);
+        let jitter = ColorJitterConfig::new().init();
+
+        let batch = jitter.augment(
+            AugmentBatch::new(images.clone()),
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        images
+            .to_data()
+            .assert_approx_eq(&batch.images.to_data(), Tolerance::<f32>::balanced());
+    }
+
+    #[test]
+    fn jittered_images_stay_in_range() {
+        let images = Tensor::<4>::from([[[[0.8, 0.1]], [[0.3, 0.7]], [[0.1, 0.4]]]]);
+        let jitter = ColorJitterConfig::new()
+            .with_brightness(0.9)
+            .with_contrast(0.9)
+            .with_saturation(0.9)
+            .with_hue(0.5)
+            .init();
+
+        let batch = jitter.augment(AugmentBatch::new(images), &mut StdRng::seed_from_u64(0));
+
+        let values = batch.images.into_data().to_vec::<f32>().unwrap();
+        assert!(values.iter().all(|v| (0.0..=1.0).contains(v)));
+    }
+}
@@ -10,6 +10,9 @@
 //! - `filter2d` (depthwise 2D correlation)
 //! - color conversion (`rgb2gray` / `gray2rgb` / `rgb2hsv` / `hsv2rgb`)
 //!
+//! With the `dataset` feature, the `augmentation` module provides data augmentations that
+//! transform bounding boxes and segmentation masks along with the images.
+//!
 
 #![warn(missing_docs)]
 
@@ -45,6 +48,11 @@ pub use filter::*;
 mod transform;
 pub use transform::*;
 
+#[cfg(feature = "dataset")]
+#[cfg_attr(docsrs, doc(cfg(feature = "dataset")))]
+/// Data augmentation pipelines for images and their annotations.
+pub mod augmentation;
+
 /// Module for vision/image utilities
 pub mod utils;
 
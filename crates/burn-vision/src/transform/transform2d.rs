@@ -7,6 +7,7 @@ use burn_core::tensor::{
 /// 2D point transformation
 ///
 /// Useful for resampling: rotating, scaling, translating, etc image tensors
+#[derive(Debug, Clone)]
 pub struct Transform2D {
     // 2x3 transformation matrix, to be used with column vectors:
     // T(x) = Ax
@@ -83,6 +84,44 @@ impl Transform2D {
         }
     }
 
+    /// Makes the inverse transform, mapping transformed points back to where they came from.
+    ///
+    /// # Panics
+    /// Panics if the transform is singular.
+    pub fn inverse(&self) -> Self {
+        let [[a, b, tx], [c, d, ty]] = self.transform;
+        let det = a * d - b * c;
+        assert!(
+            det.abs() > f32::EPSILON,
+            "Cannot invert a singular 2D transform"
+        );
+
+        let (ia, ib, ic, id) = (d / det, -b / det, -c / det, a / det);
+        let transform = [
+            [ia, ib, -(ia * tx + ib * ty)],
+            [ic, id, -(ic * tx + id * ty)],
+        ];
+
+        Self {
+            transform,
+            padding_mode: self.padding_mode,
+        }
+    }
+
+    /// Applies the transform to a single point
+    ///
+    /// # Returns
+    /// The transformed `(x, y)` point.
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let [[a, b, tx], [c, d, ty]] = self.transform;
+        (a * x + b * y + tx, c * x + d * y + ty)
+    }
+
+    /// The 2x3 matrix of the transform.
+    pub(crate) fn matrix(&self) -> [[f32; 3]; 2] {
+        self.transform
+    }
+
     /// Makes an identity transform (x = Ax)
     pub fn identity() -> Self {
         Self {
@@ -237,6 +276,19 @@ mod tests {
             .assert_approx_eq(&image.to_data(), Tolerance::<f32>::balanced());
     }
 
+    #[test]
+    fn transform_inverse_roundtrip() {
+        let t = Transform2D::composed([
+            Transform2D::translation(3.0, -2.0),
+            Transform2D::rotation(0.4, 1.0, 2.0),
+            Transform2D::scale(2.0, 0.5, 0.0, 0.0),
+        ]);
+        let (x, y) = t.apply(1.5, -0.25);
+        let (x, y) = t.inverse().apply(x, y);
+
+        assert!((x - 1.5).abs() < 1e-5 && (y + 0.25).abs() < 1e-5);
+    }
+
     #[test]
     fn transform_combined() {
         let t1 = Transform2D::translation(0.2, -0.5);
@@ -1,6 +1,9 @@
 #![recursion_limit = "141"]
 
-use burn::{server::Channel, tensor::Device};
+use burn::{
+    server::{Channel, ServerAuth, TokenAuthorizer},
+    tensor::Device,
+};
 
 pub fn start() {
     let port = std::env::var("REMOTE_BACKEND_PORT")
@@ -10,5 +13,11 @@ pub fn start() {
         })
         .unwrap_or(3000);
 
-    burn::server::start(Device::default(), Channel::WebSocket { port });
+    // Only admit clients presenting this token, when set.
+    let auth = match std::env::var("REMOTE_BACKEND_TOKEN") {
+        Ok(token) => ServerAuth::default().with_authorizer(TokenAuthorizer::new(token)),
+        Err(_) => ServerAuth::default(),
+    };
+
+    burn::server::start_with_auth(Device::default(), Channel::WebSocket { port }, auth);
 }